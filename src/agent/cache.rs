@@ -0,0 +1,200 @@
+//! Agent response cache
+//!
+//! A successful agent run for a cacheable phase is stored under
+//! `.wreckit/cache/agent/`, keyed by a hash of the phase, the prompt, and the
+//! HEAD commit. Re-running the phase with the same prompt on the same commit
+//! (say, after the phase failed for a mechanical reason once the agent was
+//! done) replays the stored output and restores the files the agent wrote in
+//! the item directory instead of making another model call. `--no-cache`
+//! turns the cache off for a process.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rsa::sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Result;
+use crate::fs;
+
+use super::AgentResult;
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn the agent cache off (or back on) for this process
+pub fn set_agent_cache_disabled(disabled: bool) {
+    DISABLED.store(disabled, Ordering::SeqCst);
+}
+
+/// Whether `--no-cache` is in effect
+pub fn agent_cache_disabled() -> bool {
+    DISABLED.load(Ordering::SeqCst)
+}
+
+/// A stored agent response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// Phase that ran the agent
+    pub phase: String,
+
+    /// HEAD commit the agent ran on
+    pub head: String,
+
+    /// ISO 8601 timestamp of when the response was stored
+    pub cached_at: String,
+
+    /// Combined agent output
+    pub output: String,
+
+    /// Process exit code, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+
+    /// Files the agent wrote in the item directory, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, String>,
+}
+
+impl CachedResponse {
+    /// The stored response as an agent result
+    pub fn to_result(&self) -> AgentResult {
+        AgentResult {
+            success: true,
+            output: self.output.clone(),
+            timed_out: false,
+            exit_code: self.exit_code,
+            completion_detected: true,
+        }
+    }
+}
+
+/// Cache key for a prompt sent in a phase on a commit
+pub fn agent_cache_key(phase: &str, prompt: &str, head: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [phase, prompt, head] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn entry_path(root: &Path, key: &str) -> PathBuf {
+    fs::get_agent_cache_dir(root).join(format!("{}.json", key))
+}
+
+/// The stored response for a key, if any (an unreadable entry is a miss)
+pub fn read_cached_response(root: &Path, key: &str) -> Option<CachedResponse> {
+    let path = entry_path(root, key);
+    if !path.exists() {
+        return None;
+    }
+    match fs::read_json(&path) {
+        Ok(response) => Some(response),
+        Err(e) => {
+            tracing::debug!("Ignoring agent cache entry {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Store a response under a key.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the entry cannot be written
+pub fn write_cached_response(root: &Path, key: &str, response: &CachedResponse) -> Result<()> {
+    fs::write_json(&entry_path(root, key), response)
+}
+
+/// Text files directly in `dir`, by name (other files are skipped)
+pub fn snapshot_files(dir: &Path) -> BTreeMap<String, String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return BTreeMap::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            let content = std::fs::read_to_string(&path).ok()?;
+            Some((name, content))
+        })
+        .collect()
+}
+
+/// Files in `after` that are new or changed since `before`
+pub fn changed_files(
+    before: &BTreeMap<String, String>,
+    after: BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    after
+        .into_iter()
+        .filter(|(name, content)| before.get(name) != Some(content))
+        .collect()
+}
+
+/// Write a cached response's files back into the item directory.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If a file cannot be written
+pub fn restore_files(dir: &Path, response: &CachedResponse) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    for (name, content) in &response.files {
+        fs::write_file(&dir.join(name), content)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_cache_key() {
+        let key = agent_cache_key("research", "prompt", "abc123");
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, agent_cache_key("research", "prompt", "abc123"));
+        assert_ne!(key, agent_cache_key("plan", "prompt", "abc123"));
+        assert_ne!(key, agent_cache_key("research", "prompt", "def456"));
+        assert_ne!(
+            agent_cache_key("ab", "c", "d"),
+            agent_cache_key("a", "bc", "d")
+        );
+    }
+
+    #[test]
+    fn test_store_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let item_dir = dir.path().join("item");
+        std::fs::create_dir_all(&item_dir).unwrap();
+        std::fs::write(item_dir.join("item.json"), "{}").unwrap();
+        let before = snapshot_files(&item_dir);
+
+        std::fs::write(item_dir.join("research.md"), "# Research").unwrap();
+        let files = changed_files(&before, snapshot_files(&item_dir));
+        assert_eq!(files.keys().collect::<Vec<_>>(), vec!["research.md"]);
+
+        let key = agent_cache_key("research", "prompt", "abc123");
+        assert_eq!(read_cached_response(dir.path(), &key), None);
+        let response = CachedResponse {
+            phase: "research".to_string(),
+            head: "abc123".to_string(),
+            cached_at: "2024-01-01T00:00:00Z".to_string(),
+            output: "done".to_string(),
+            exit_code: Some(0),
+            files,
+        };
+        write_cached_response(dir.path(), &key, &response).unwrap();
+        let stored = read_cached_response(dir.path(), &key).unwrap();
+        assert_eq!(stored, response);
+
+        std::fs::remove_file(item_dir.join("research.md")).unwrap();
+        restore_files(&item_dir, &stored).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(item_dir.join("research.md")).unwrap(),
+            "# Research"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Environment variables for agent runs
+//!
+//! `agent.env` in config and `env` on an item add variables to the agent
+//! process and to the verify commands run for that item, item values
+//! winning. A value is used as written unless it references a secret kept
+//! outside the JSON files: `env:NAME` takes wreckit's own `NAME` variable,
+//! and `keychain:NAME` the secret stored with `wreckit auth set NAME`.
+
+use std::collections::BTreeMap;
+
+use crate::errors::{Result, WreckitError};
+use crate::secrets::{secret_store, KEYCHAIN_REF_PREFIX};
+
+/// Prefix of a value read from wreckit's own environment
+pub const ENV_REF_PREFIX: &str = "env:";
+
+/// Layer `overrides` over `base`
+pub fn merge_env(
+    base: &BTreeMap<String, String>,
+    overrides: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let mut merged = base.clone();
+    merged.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+    merged
+}
+
+/// Resolve one value, following a secret reference.
+///
+/// # Errors
+/// * `ConfigError` - If a referenced variable or secret is not set
+/// * `Wrapped` - If the keychain cannot be read
+pub fn resolve_value(name: &str, value: &str) -> Result<String> {
+    if let Some(var) = value.strip_prefix(ENV_REF_PREFIX) {
+        return std::env::var(var).map_err(|_| {
+            WreckitError::ConfigError(format!(
+                "env {} references ${}, which is not set",
+                name, var
+            ))
+        });
+    }
+    if let Some(secret) = value.strip_prefix(KEYCHAIN_REF_PREFIX) {
+        return secret_store().get(secret)?.ok_or_else(|| {
+            WreckitError::ConfigError(format!(
+                "env {} references keychain secret '{}', which is not stored (run `wreckit auth set {}`)",
+                name, secret, secret
+            ))
+        });
+    }
+    Ok(value.to_string())
+}
+
+/// Resolve every value for passing to a child process.
+///
+/// # Errors
+/// * `ConfigError` - If a referenced variable or secret is not set
+pub fn resolve_env(vars: &BTreeMap<String, String>) -> Result<Vec<(String, String)>> {
+    vars.iter()
+        .map(|(name, value)| Ok((name.clone(), resolve_value(name, value)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::{set_secret_store, MemoryStore, SecretStore};
+    use std::sync::Arc;
+
+    fn map(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_and_resolve() {
+        let merged = merge_env(
+            &map(&[("API_URL", "https://a"), ("REGION", "us")]),
+            &map(&[("API_URL", "https://b"), ("HOME_REF", "env:HOME")]),
+        );
+        let resolved = resolve_env(&merged).unwrap();
+        assert_eq!(
+            resolved[0],
+            ("API_URL".to_string(), "https://b".to_string())
+        );
+        assert_eq!(resolved[1].1, std::env::var("HOME").unwrap());
+        assert_eq!(resolved[2], ("REGION".to_string(), "us".to_string()));
+
+        let err = resolve_env(&map(&[("TOKEN", "env:WRECKIT_TEST_UNSET_VAR")])).unwrap_err();
+        assert!(err.to_string().contains("$WRECKIT_TEST_UNSET_VAR"));
+    }
+
+    #[test]
+    fn test_keychain_reference() {
+        let store = Arc::new(MemoryStore::default());
+        store.set("env-test-jira", "jira-secret").unwrap();
+        set_secret_store(store);
+
+        let resolved = resolve_value("JIRA_TOKEN", "keychain:env-test-jira").unwrap();
+        assert_eq!(resolved, "jira-secret");
+        let err = resolve_value("JIRA_TOKEN", "keychain:env-test-missing").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("wreckit auth set env-test-missing"));
+    }
+}
@@ -0,0 +1,327 @@
+//! Dev container execution
+//!
+//! With `agent.execution = "devcontainer"`, agent and verify commands are run
+//! with `devcontainer exec` against the repository's devcontainer.json, so the
+//! agent builds and tests in the project's canonical environment. With
+//! `"compose"`, they are run with `docker compose run` against the compose
+//! file and service devcontainer.json names. Configured `env` variables are
+//! forwarded into the container; anything else the agent needs (API keys,
+//! tools) must come from the dev container's own configuration.
+//!
+//! `env_activation` enters the project's declared environment first (with
+//! `direnv exec .` or `nix develop --command`), inside the container when
+//! there is one, so commands use the project's toolchain versions.
+
+use std::path::{Path, PathBuf};
+
+use crate::errors::{Result, WreckitError};
+use crate::schemas::{EnvActivation, Execution};
+
+/// Where devcontainer.json is looked for, relative to the repository root
+pub const DEVCONTAINER_PATHS: &[&str] = &[".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// The repository's devcontainer.json, if it has one
+pub fn find_devcontainer(root: &Path) -> Option<PathBuf> {
+    DEVCONTAINER_PATHS
+        .iter()
+        .map(|path| root.join(path))
+        .find(|path| path.is_file())
+}
+
+/// The compose settings of a devcontainer.json
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComposeTarget {
+    /// Compose files, resolved against the devcontainer.json directory
+    pub files: Vec<PathBuf>,
+
+    /// Service the commands run in
+    pub service: String,
+
+    /// Working directory inside the container, if set
+    pub workspace_folder: Option<String>,
+}
+
+/// Drop `//` line comments, which devcontainer.json allows, outside strings
+fn strip_comments(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let mut in_string = false;
+            let mut escaped = false;
+            let bytes = line.as_bytes();
+            for (i, byte) in bytes.iter().enumerate() {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' if in_string => escaped = true,
+                    b'"' => in_string = !in_string,
+                    b'/' if !in_string && bytes.get(i + 1) == Some(&b'/') => return &line[..i],
+                    _ => {}
+                }
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Read the compose file(s) and service from the repository's
+/// devcontainer.json.
+///
+/// # Errors
+/// * `ConfigError` - If there is no devcontainer.json, it cannot be parsed,
+///   or it names no compose file or service
+pub fn read_compose_target(root: &Path) -> Result<ComposeTarget> {
+    let path = find_devcontainer(root).ok_or_else(|| {
+        WreckitError::ConfigError(format!(
+            "agent.execution needs a devcontainer.json ({})",
+            DEVCONTAINER_PATHS.join(" or ")
+        ))
+    })?;
+    let text = std::fs::read_to_string(&path)?;
+    let value: serde_json::Value = serde_json::from_str(&strip_comments(&text))
+        .map_err(|e| WreckitError::ConfigError(format!("{}: {}", path.display(), e)))?;
+    let dir = path.parent().unwrap_or(root);
+    let files: Vec<PathBuf> = match &value["dockerComposeFile"] {
+        serde_json::Value::String(file) => vec![dir.join(file)],
+        serde_json::Value::Array(files) => files
+            .iter()
+            .filter_map(|file| file.as_str())
+            .map(|file| dir.join(file))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let service = value["service"].as_str().map(String::from);
+    match service {
+        Some(service) if !files.is_empty() => Ok(ComposeTarget {
+            files,
+            service,
+            workspace_folder: value["workspaceFolder"].as_str().map(String::from),
+        }),
+        _ => Err(WreckitError::ConfigError(format!(
+            "{} names no dockerComposeFile and service for agent.execution = \"compose\"",
+            path.display()
+        ))),
+    }
+}
+
+/// Wrap a command so it runs in the environment `activation` enters
+fn activate(activation: EnvActivation, program: &str, args: &[String]) -> (String, Vec<String>) {
+    let (launcher, mut wrapped) = match activation {
+        EnvActivation::None => return (program.to_string(), args.to_vec()),
+        EnvActivation::Direnv => ("direnv", vec!["exec".to_string(), ".".to_string()]),
+        EnvActivation::NixDevelop => ("nix", vec!["develop".to_string(), "--command".to_string()]),
+    };
+    wrapped.push(program.to_string());
+    wrapped.extend(args.iter().cloned());
+    (launcher.to_string(), wrapped)
+}
+
+/// Wrap a command so it runs in the environment `activation` enters, where
+/// `execution` says, returning the program and arguments to spawn on the
+/// host. `env` is forwarded into the container by name only: the caller sets
+/// the values in the spawned process's environment, so secrets never appear
+/// on the command line.
+///
+/// # Errors
+/// * `ConfigError` - If the repository has no usable devcontainer.json
+pub fn wrap_command(
+    execution: Execution,
+    activation: EnvActivation,
+    root: &Path,
+    env: &[(String, String)],
+    program: &str,
+    args: &[String],
+) -> Result<(String, Vec<String>)> {
+    let (program, args) = activate(activation, program, args);
+    let mut wrapped = Vec::new();
+    let launcher = match execution {
+        Execution::Host => return Ok((program, args)),
+        Execution::Devcontainer => {
+            if find_devcontainer(root).is_none() {
+                return Err(WreckitError::ConfigError(format!(
+                    "agent.execution = \"devcontainer\" needs a devcontainer.json ({})",
+                    DEVCONTAINER_PATHS.join(" or ")
+                )));
+            }
+            wrapped.extend(["exec".to_string(), "--workspace-folder".to_string()]);
+            wrapped.push(root.display().to_string());
+            // Values come from the spawned process's environment
+            for (name, _) in env {
+                wrapped.push("--remote-env".to_string());
+                wrapped.push(format!("{}=${{localEnv:{}}}", name, name));
+            }
+            "devcontainer"
+        }
+        Execution::Compose => {
+            let target = read_compose_target(root)?;
+            wrapped.push("compose".to_string());
+            for file in &target.files {
+                wrapped.push("-f".to_string());
+                wrapped.push(file.display().to_string());
+            }
+            wrapped.extend(["run".to_string(), "--rm".to_string(), "-T".to_string()]);
+            if let Some(folder) = target.workspace_folder {
+                wrapped.push("-w".to_string());
+                wrapped.push(folder);
+            }
+            // Values come from the spawned process's environment
+            for (name, _) in env {
+                wrapped.push("-e".to_string());
+                wrapped.push(name.clone());
+            }
+            wrapped.push(target.service);
+            "docker"
+        }
+    };
+    wrapped.push(program);
+    wrapped.extend(args);
+    Ok((launcher.to_string(), wrapped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_command() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let env = vec![("STAGE".to_string(), "ci".to_string())];
+        let args = vec!["-c".to_string(), "cargo test".to_string()];
+
+        let host = wrap_command(
+            Execution::Host,
+            EnvActivation::None,
+            root,
+            &env,
+            "sh",
+            &args,
+        )
+        .unwrap();
+        assert_eq!(host, ("sh".to_string(), args.clone()));
+        let (program, wrapped) = wrap_command(
+            Execution::Host,
+            EnvActivation::NixDevelop,
+            root,
+            &env,
+            "sh",
+            &args,
+        )
+        .unwrap();
+        assert_eq!(program, "nix");
+        assert_eq!(
+            wrapped,
+            vec!["develop", "--command", "sh", "-c", "cargo test"]
+        );
+        assert!(wrap_command(
+            Execution::Devcontainer,
+            EnvActivation::None,
+            root,
+            &env,
+            "sh",
+            &args
+        )
+        .is_err());
+        assert!(wrap_command(
+            Execution::Compose,
+            EnvActivation::None,
+            root,
+            &env,
+            "sh",
+            &args
+        )
+        .is_err());
+
+        std::fs::create_dir_all(root.join(".devcontainer")).unwrap();
+        std::fs::write(
+            root.join(".devcontainer/devcontainer.json"),
+            "{\n  // The app service\n  \"dockerComposeFile\": [\"compose.yml\"],\n  \"service\": \"app\",\n  \"workspaceFolder\": \"/workspaces/app\" // mounted\n}\n",
+        )
+        .unwrap();
+        let (program, wrapped) = wrap_command(
+            Execution::Devcontainer,
+            EnvActivation::None,
+            root,
+            &env,
+            "sh",
+            &args,
+        )
+        .unwrap();
+        assert_eq!(program, "devcontainer");
+        let root_arg = root.display().to_string();
+        assert_eq!(
+            wrapped,
+            vec![
+                "exec",
+                "--workspace-folder",
+                root_arg.as_str(),
+                "--remote-env",
+                "STAGE=${localEnv:STAGE}",
+                "sh",
+                "-c",
+                "cargo test"
+            ]
+        );
+
+        let (program, wrapped) = wrap_command(
+            Execution::Compose,
+            EnvActivation::Direnv,
+            root,
+            &env,
+            "sh",
+            &args,
+        )
+        .unwrap();
+        assert_eq!(program, "docker");
+        let compose_file = root.join(".devcontainer/compose.yml").display().to_string();
+        assert_eq!(
+            wrapped,
+            vec![
+                "compose",
+                "-f",
+                compose_file.as_str(),
+                "run",
+                "--rm",
+                "-T",
+                "-w",
+                "/workspaces/app",
+                "-e",
+                "STAGE",
+                "app",
+                "direnv",
+                "exec",
+                ".",
+                "sh",
+                "-c",
+                "cargo test"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrap_command_keeps_values_off_the_command_line() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join(".devcontainer")).unwrap();
+        std::fs::write(
+            root.join(".devcontainer/devcontainer.json"),
+            "{\"dockerComposeFile\": \"compose.yml\", \"service\": \"app\"}\n",
+        )
+        .unwrap();
+        let secret = "ghp_s3cr3t-from-keychain";
+        let env = vec![("GITHUB_TOKEN".to_string(), secret.to_string())];
+        let args = vec!["-c".to_string(), "true".to_string()];
+
+        for execution in [Execution::Devcontainer, Execution::Compose] {
+            let (program, wrapped) =
+                wrap_command(execution, EnvActivation::None, root, &env, "sh", &args).unwrap();
+            assert!(!program.contains(secret));
+            assert!(
+                wrapped.iter().all(|arg| !arg.contains(secret)),
+                "{:?} leaks the value: {:?}",
+                execution,
+                wrapped
+            );
+            assert!(wrapped.iter().any(|arg| arg.contains("GITHUB_TOKEN")));
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Resource caps for agent processes
+//!
+//! `agent.limits` (or a phase's `limits`) keeps a runaway build started by the
+//! agent from freezing the operator's machine. The memory cap and niceness
+//! are set on the agent process before it starts (RLIMIT_AS and
+//! setpriority), so the builds and tests it spawns inherit them. The CPU
+//! quota needs a cgroup, so the command is run in a `systemd-run --user
+//! --scope`; where systemd-run is unavailable, the quota is skipped with a
+//! warning.
+
+use tokio::process::Command;
+
+use crate::schemas::ResourceLimits;
+
+/// Wrap a command in a systemd scope that applies the CPU quota, or None if
+/// no quota is set (or systemd-run is unavailable)
+pub fn cpu_quota_command(
+    limits: &ResourceLimits,
+    program: &str,
+    args: &[String],
+) -> Option<(String, Vec<String>)> {
+    let percent = limits.max_cpu_percent?;
+    if !systemd_run_available() {
+        tracing::warn!(
+            "max_cpu_percent = {} needs systemd-run; running the agent without a CPU quota",
+            percent
+        );
+        return None;
+    }
+    Some(scope_command(percent, program, args))
+}
+
+fn scope_command(percent: u32, program: &str, args: &[String]) -> (String, Vec<String>) {
+    let mut wrapped = vec![
+        "--user".to_string(),
+        "--scope".to_string(),
+        "--quiet".to_string(),
+        "-p".to_string(),
+        format!("CPUQuota={}%", percent),
+        program.to_string(),
+    ];
+    wrapped.extend(args.iter().cloned());
+    ("systemd-run".to_string(), wrapped)
+}
+
+fn systemd_run_available() -> bool {
+    cfg!(target_os = "linux")
+        && std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).any(|dir| dir.join("systemd-run").is_file()))
+            .unwrap_or(false)
+}
+
+/// Set the memory cap and niceness on the command's process before it runs
+#[cfg(unix)]
+pub fn apply_limits(cmd: &mut Command, limits: &ResourceLimits) {
+    let memory = limits
+        .max_memory_mb
+        .map(|mb| mb.saturating_mul(1024 * 1024));
+    let nice = limits.nice;
+    if memory.is_none() && nice.is_none() {
+        return;
+    }
+    // SAFETY: the closure only calls setrlimit(2) and setpriority(2), which
+    // are async-signal-safe, and allocates nothing.
+    unsafe {
+        cmd.pre_exec(move || {
+            if let Some(bytes) = memory {
+                let limit = libc::rlimit {
+                    rlim_cur: bytes as libc::rlim_t,
+                    rlim_max: bytes as libc::rlim_t,
+                };
+                if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            if let Some(nice) = nice {
+                if libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Resource caps are only applied on Unix
+#[cfg(not(unix))]
+pub fn apply_limits(_cmd: &mut Command, limits: &ResourceLimits) {
+    if !limits.is_empty() {
+        tracing::warn!("agent resource limits are not supported on this platform");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_command() {
+        let (program, args) = scope_command(150, "claude", &["--print".to_string()]);
+        assert_eq!(program, "systemd-run");
+        assert_eq!(
+            args,
+            vec![
+                "--user",
+                "--scope",
+                "--quiet",
+                "-p",
+                "CPUQuota=150%",
+                "claude",
+                "--print"
+            ]
+        );
+        assert!(cpu_quota_command(&ResourceLimits::default(), "claude", &[]).is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_limits_are_inherited() {
+        let limits = ResourceLimits {
+            max_memory_mb: Some(512),
+            nice: Some(5),
+            ..Default::default()
+        };
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "ulimit -v; nice"]);
+        apply_limits(&mut cmd, &limits);
+        let output = cmd.output().await.unwrap();
+        let text = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines, vec!["524288", "5"]);
+    }
+}
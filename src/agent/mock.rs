@@ -0,0 +1,222 @@
+//! Mock agent backend
+//!
+//! Used when `agent.mode = "mock"`. Instead of spawning a process, each call
+//! loads a canned response from the fixtures directory, matched by phase
+//! (and story, for the implement phase):
+//!
+//! 1. `<fixtures>/<phase>-<story>.json` (e.g. `implement-US-001.json`)
+//! 2. `<fixtures>/<phase>.json`
+//!
+//! A fixture supplies the agent output and the files the agent would have
+//! written, so end-to-end runs work without network access or a Claude binary.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+
+use super::AgentResult;
+
+/// Default fixtures directory, relative to the repository root
+pub const DEFAULT_FIXTURES_DIR: &str = ".wreckit/fixtures";
+
+/// A canned agent response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MockFixture {
+    /// Agent output (`{{id}}` and `{{story}}` are substituted)
+    #[serde(default)]
+    pub output: String,
+
+    /// Files to write, relative to the item directory
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+
+    /// Files to write, relative to the repository root
+    #[serde(default)]
+    pub repo_files: BTreeMap<String, String>,
+
+    /// Process exit code to report
+    #[serde(default)]
+    pub exit_code: i32,
+
+    /// Whether to append the completion signal to the output
+    #[serde(default = "default_complete")]
+    pub complete: bool,
+}
+
+fn default_complete() -> bool {
+    true
+}
+
+/// A single mock agent invocation
+#[derive(Debug, Clone)]
+pub struct MockRequest<'a> {
+    /// Repository root
+    pub root: &'a Path,
+    /// Item the agent is working on
+    pub item_id: &'a str,
+    /// Phase name (e.g., "research")
+    pub phase: &'a str,
+    /// Story being implemented, if any
+    pub story: Option<&'a str>,
+    /// Fixtures directory override from config (relative to root)
+    pub fixtures_dir: Option<&'a str>,
+    /// Completion signal to append to the output
+    pub completion_signal: &'a str,
+}
+
+/// Find the fixture file for a request, most specific first
+pub fn find_fixture(dir: &Path, phase: &str, story: Option<&str>) -> Option<PathBuf> {
+    let specific = story.map(|s| dir.join(format!("{}-{}.json", phase, s)));
+    specific
+        .into_iter()
+        .chain(std::iter::once(dir.join(format!("{}.json", phase))))
+        .find(|path| path.exists())
+}
+
+/// Answer an agent call from fixtures, writing the fixture's files.
+///
+/// # Errors
+/// * `FileNotFound` - If no fixture matches the phase
+/// * `InvalidJson` - If the fixture cannot be parsed
+/// * `Io` - If a fixture file cannot be written
+pub fn run_mock_agent(request: &MockRequest<'_>) -> Result<AgentResult> {
+    let dir = request
+        .root
+        .join(request.fixtures_dir.unwrap_or(DEFAULT_FIXTURES_DIR));
+    let path = find_fixture(&dir, request.phase, request.story).ok_or_else(|| {
+        WreckitError::FileNotFound(format!(
+            "No mock fixture for phase '{}' in {}",
+            request.phase,
+            dir.display()
+        ))
+    })?;
+    let fixture: MockFixture = fs::read_json(&path)?;
+
+    let item_dir = fs::get_item_dir(request.root, request.item_id);
+    let targets = fixture
+        .files
+        .iter()
+        .map(|(rel, content)| (item_dir.join(rel), content))
+        .chain(
+            fixture
+                .repo_files
+                .iter()
+                .map(|(rel, content)| (request.root.join(rel), content)),
+        );
+    for (target, content) in targets {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write_file(&target, substitute(content, request))?;
+    }
+
+    let mut output = substitute(&fixture.output, request);
+    if fixture.complete && !output.contains(request.completion_signal) {
+        if !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str(request.completion_signal);
+    }
+    let completion_detected = output.contains(request.completion_signal);
+
+    Ok(AgentResult {
+        success: fixture.exit_code == 0 && completion_detected,
+        output,
+        timed_out: false,
+        exit_code: Some(fixture.exit_code),
+        completion_detected,
+    })
+}
+
+fn substitute(text: &str, request: &MockRequest<'_>) -> String {
+    text.replace("{{id}}", request.item_id)
+        .replace("{{story}}", request.story.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn request<'a>(root: &'a Path, phase: &'a str, story: Option<&'a str>) -> MockRequest<'a> {
+        MockRequest {
+            root,
+            item_id: "001-test",
+            phase,
+            story,
+            fixtures_dir: None,
+            completion_signal: "<promise>COMPLETE</promise>",
+        }
+    }
+
+    fn write_fixture(root: &Path, name: &str, fixture: &MockFixture) {
+        fs::write_json(&root.join(DEFAULT_FIXTURES_DIR).join(name), fixture).unwrap();
+    }
+
+    #[test]
+    fn test_mock_writes_files_and_completes() {
+        let temp = TempDir::new().unwrap();
+        let mut fixture = MockFixture {
+            output: "researched {{id}}".to_string(),
+            complete: true,
+            ..Default::default()
+        };
+        fixture.files.insert(
+            "research.md".to_string(),
+            "# Research for {{id}}".to_string(),
+        );
+        write_fixture(temp.path(), "research.json", &fixture);
+
+        let result = run_mock_agent(&request(temp.path(), "research", None)).unwrap();
+        assert!(result.success);
+        assert!(result.output.starts_with("researched 001-test"));
+
+        let written =
+            std::fs::read_to_string(fs::get_research_path(temp.path(), "001-test")).unwrap();
+        assert_eq!(written, "# Research for 001-test");
+    }
+
+    #[test]
+    fn test_mock_prefers_story_specific_fixture() {
+        let temp = TempDir::new().unwrap();
+        let generic = MockFixture {
+            output: "generic".to_string(),
+            complete: true,
+            ..Default::default()
+        };
+        let specific = MockFixture {
+            output: "specific {{story}}".to_string(),
+            complete: true,
+            ..Default::default()
+        };
+        write_fixture(temp.path(), "implement.json", &generic);
+        write_fixture(temp.path(), "implement-US-001.json", &specific);
+
+        let result = run_mock_agent(&request(temp.path(), "implement", Some("US-001"))).unwrap();
+        assert!(result.output.starts_with("specific US-001"));
+        let result = run_mock_agent(&request(temp.path(), "implement", Some("US-002"))).unwrap();
+        assert!(result.output.starts_with("generic"));
+    }
+
+    #[test]
+    fn test_mock_missing_fixture_and_failure() {
+        let temp = TempDir::new().unwrap();
+        let err = run_mock_agent(&request(temp.path(), "plan", None)).unwrap_err();
+        assert!(matches!(err, WreckitError::FileNotFound(_)));
+
+        let failing = MockFixture {
+            output: "boom".to_string(),
+            exit_code: 1,
+            complete: false,
+            ..Default::default()
+        };
+        write_fixture(temp.path(), "plan.json", &failing);
+        let result = run_mock_agent(&request(temp.path(), "plan", None)).unwrap();
+        assert!(!result.success);
+        assert!(!result.completion_detected);
+    }
+}
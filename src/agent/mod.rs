@@ -1,9 +1,32 @@
 //! Agent execution module
 //!
-//! Provides the agent runner for executing Claude CLI or other agents.
+//! Provides the agent runner for executing Claude CLI or other agents,
+//! plus a fixture-backed mock backend for testing, detection of API
+//! rate-limit reports and operator questions in agent output, the
+//! environment given to agents, dev container execution, resource caps
+//! for agent processes, and a cache of agent responses.
 
+mod cache;
+mod env;
+mod execution;
+mod limits;
+mod mock;
 mod parser;
+mod question;
+mod rate_limit;
 mod runner;
 
+pub use cache::{
+    agent_cache_disabled, agent_cache_key, changed_files, read_cached_response, restore_files,
+    set_agent_cache_disabled, snapshot_files, write_cached_response, CachedResponse,
+};
+pub use env::{merge_env, resolve_env, resolve_value, ENV_REF_PREFIX};
+pub use execution::{
+    find_devcontainer, read_compose_target, wrap_command, ComposeTarget, DEVCONTAINER_PATHS,
+};
+pub use limits::{apply_limits, cpu_quota_command};
+pub use mock::{find_fixture, run_mock_agent, MockFixture, MockRequest, DEFAULT_FIXTURES_DIR};
 pub use parser::parse_agent_line;
+pub use question::detect_question;
+pub use rate_limit::{detect_rate_limit, RateLimit};
 pub use runner::{run_agent, AgentResult, RunAgentOptions};
@@ -0,0 +1,43 @@
+//! Questions from the agent
+//!
+//! An agent that cannot go on without a decision from the operator wraps
+//! the question in `<question>...</question>` and stops. [`detect_question`]
+//! finds it in the output so the workflow can pause the item until
+//! `wreckit answer` supplies the answer.
+
+use regex::Regex;
+
+lazy_static::lazy_static! {
+    static ref QUESTION_REGEX: Regex = Regex::new(r"(?s)<question>(.*?)</question>").unwrap();
+}
+
+/// The last question the agent asked in its output, if any
+pub fn detect_question(output: &str) -> Option<String> {
+    QUESTION_REGEX
+        .captures_iter(output)
+        .filter_map(|captures| {
+            let text = captures[1].trim();
+            (!text.is_empty()).then(|| text.to_string())
+        })
+        .last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_question() {
+        let output = "Looked at the auth module.\n<question>\nShould sessions expire after 24h or 7d?\n</question>\n";
+        assert_eq!(
+            detect_question(output).as_deref(),
+            Some("Should sessions expire after 24h or 7d?")
+        );
+        assert_eq!(detect_question("<question> </question> done"), None);
+        assert_eq!(detect_question("no questions here"), None);
+        assert_eq!(
+            detect_question("<question>A?</question> then <question>B?</question>").as_deref(),
+            Some("B?")
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Rate-limit and overload detection in agent output
+//!
+//! The Claude CLI reports API throttling as plain text (HTTP 429s,
+//! `rate_limit_error`, `overloaded_error`, or "usage limit reached"), often
+//! with a hint about when to retry. [`detect_rate_limit`] recognizes these so
+//! callers can back off instead of treating them as ordinary agent failures.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use regex::Regex;
+
+lazy_static::lazy_static! {
+    static ref RATE_LIMIT_REGEX: Regex = Regex::new(
+        r"(?i)(rate[ _-]?limit|overloaded|too many requests|usage limit reached|\b429\b|\b529\b)"
+    ).unwrap();
+
+    static ref RETRY_AFTER_REGEX: Regex = Regex::new(
+        r"(?i)(?:retry[ _-]after|try again in)[:= ]*(?P<n>\d+)\s*(?P<unit>ms|milliseconds?|s|secs?|seconds?|m|mins?|minutes?|h|hours?)?"
+    ).unwrap();
+
+    /// `Claude AI usage limit reached|<unix epoch of reset>`
+    static ref RESET_EPOCH_REGEX: Regex = Regex::new(
+        r"(?i)usage limit reached\|(?P<epoch>\d{9,})"
+    ).unwrap();
+}
+
+/// A rate limit reported by the agent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    /// The output line that reported the limit
+    pub message: String,
+
+    /// Seconds to wait before retrying, if the agent said
+    pub retry_after: Option<u64>,
+}
+
+/// Look for a rate-limit or overload report in agent output
+pub fn detect_rate_limit(output: &str) -> Option<RateLimit> {
+    let line = output
+        .lines()
+        .find(|line| RATE_LIMIT_REGEX.is_match(line))?;
+    Some(RateLimit {
+        message: line.trim().to_string(),
+        retry_after: parse_retry_after(output),
+    })
+}
+
+fn parse_retry_after(output: &str) -> Option<u64> {
+    if let Some(caps) = RESET_EPOCH_REGEX.captures(output) {
+        let reset: u64 = caps["epoch"].parse().ok()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        return Some(reset.saturating_sub(now));
+    }
+
+    let caps = RETRY_AFTER_REGEX.captures(output)?;
+    let n: u64 = caps["n"].parse().ok()?;
+    let unit = caps
+        .name("unit")
+        .map(|u| u.as_str().to_lowercase())
+        .unwrap_or_default();
+    Some(match unit.chars().next() {
+        Some('m') if unit.starts_with("ms") || unit.starts_with("milli") => n.div_ceil(1000),
+        Some('m') => n * 60,
+        Some('h') => n * 60 * 60,
+        _ => n,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_api_errors() {
+        let limit = detect_rate_limit(
+            "working...\nAPI Error: 429 {\"type\":\"rate_limit_error\"}\nRetry after 30 seconds",
+        )
+        .unwrap();
+        assert!(limit.message.starts_with("API Error: 429"));
+        assert_eq!(limit.retry_after, Some(30));
+
+        let overloaded = detect_rate_limit("API Error: 529 overloaded_error").unwrap();
+        assert_eq!(overloaded.retry_after, None);
+
+        assert!(detect_rate_limit("all tests passed\n<promise>COMPLETE</promise>").is_none());
+    }
+
+    #[test]
+    fn test_retry_after_units() {
+        assert_eq!(parse_retry_after("try again in 2 minutes"), Some(120));
+        assert_eq!(parse_retry_after("retry-after: 1500ms"), Some(2));
+        assert_eq!(parse_retry_after("Retry after 1h"), Some(3600));
+        assert_eq!(parse_retry_after("retry_after=45"), Some(45));
+    }
+
+    #[test]
+    fn test_usage_limit_reset_epoch() {
+        let reset = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 600;
+        let limit = detect_rate_limit(&format!("Claude AI usage limit reached|{}", reset)).unwrap();
+        let wait = limit.retry_after.unwrap();
+        assert!((595..=600).contains(&wait));
+    }
+}
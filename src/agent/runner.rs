@@ -4,15 +4,22 @@
 
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::Command;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
 use tokio::time::timeout;
 
+use crate::agent::env::resolve_env;
+use crate::agent::execution::wrap_command;
+use crate::agent::limits::{apply_limits, cpu_quota_command};
 use crate::agent::parser;
 use crate::errors::{Result, WreckitError};
-use crate::schemas::AgentConfig;
+use crate::schemas::{AgentConfig, EnvActivation};
+use crate::tui::control::{cancelled, CancellationToken};
 use crate::tui::events::AgentEvent;
 
 /// Result of an agent execution
@@ -59,24 +66,38 @@ pub struct RunAgentOptions {
 
     /// Channel sender for TUI events (optional)
     pub on_tui_event: Option<tokio::sync::mpsc::Sender<AgentEvent>>,
+
+    /// Cancellation token; when it is cancelled the agent is terminated (optional)
+    pub cancel: Option<CancellationToken>,
+
+    /// Environment activation the agent runs under
+    pub env_activation: EnvActivation,
 }
 
 /// Run an agent with the given options.
 ///
 /// This function:
-/// 1. Spawns the agent process with the configured command and args
+/// 1. Spawns the agent process with the configured command and args, under
+///    the configured resource limits
 /// 2. Writes the prompt to stdin and closes it
 /// 3. Reads stdout/stderr, buffering output
-/// 4. Detects the completion signal in output
+/// 4. Watches output for the completion signal as it streams in, stopping an
+///    agent that has not exited `completion_grace_seconds` after printing it
 /// 5. Applies timeout (SIGTERM, then SIGKILL after 5s)
-/// 6. Returns result with exit code and completion status
+/// 6. Terminates the agent the same way if the cancellation token is cancelled
+/// 7. Returns result with exit code and completion status
 ///
 /// # Arguments
 /// * `options` - Agent execution options
 ///
 /// # Returns
 /// The result of the agent execution
-pub async fn run_agent(options: RunAgentOptions) -> Result<AgentResult> {
+///
+/// # Errors
+/// * `ConfigError` - If an `env` value references an unset variable, or
+///   `execution` needs a devcontainer.json the repository lacks
+/// * `Interrupted` - If the run was cancelled via the token
+pub async fn run_agent(mut options: RunAgentOptions) -> Result<AgentResult> {
     // Handle dry-run mode
     if options.dry_run {
         return Ok(AgentResult {
@@ -88,12 +109,29 @@ pub async fn run_agent(options: RunAgentOptions) -> Result<AgentResult> {
         });
     }
 
-    let mut cmd = Command::new(&options.config.command);
-    cmd.args(&options.config.args)
+    if options.cancel.as_ref().is_some_and(|t| t.is_cancelled()) {
+        return Err(WreckitError::Interrupted);
+    }
+    crate::fs::ensure_writable(|| format!("run the agent `{}`", options.config.command))?;
+    let env = resolve_env(&options.config.env)?;
+    let (program, args) = wrap_command(
+        options.config.execution,
+        options.env_activation,
+        &options.cwd,
+        &env,
+        &options.config.command,
+        &options.config.args,
+    )?;
+    let limits = options.config.limits;
+    let (program, args) = cpu_quota_command(&limits, &program, &args).unwrap_or((program, args));
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .envs(env)
         .current_dir(&options.cwd)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
+    apply_limits(&mut cmd, &limits);
 
     let mut child = cmd
         .spawn()
@@ -108,102 +146,188 @@ pub async fn run_agent(options: RunAgentOptions) -> Result<AgentResult> {
         // stdin is dropped here, closing it
     }
 
-    let mut output = String::new();
-    let mut completion_detected = false;
-
-    // Read stdout
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
+    let signal = options.config.completion_signal.clone();
+    let completed = Arc::new(Notify::new());
+    let stdout_output = Arc::new(Mutex::new(String::new()));
+    let stderr_output = Arc::new(Mutex::new(String::new()));
+
+    // Read stdout and stderr concurrently, parsing stdout for TUI events and
+    // watching both for the completion signal
+    let readers = [
+        spawn_reader(
+            stdout,
+            stdout_output.clone(),
+            &signal,
+            completed.clone(),
+            options.on_tui_event.take(),
+        ),
+        spawn_reader(
+            stderr,
+            stderr_output.clone(),
+            &signal,
+            completed.clone(),
+            None,
+        ),
+    ];
 
     let timeout_duration = Duration::from_secs(options.timeout_seconds as u64);
+    let grace = Duration::from_secs(options.config.completion_grace_seconds);
+    let cancel_signal = cancelled(options.cancel.as_ref());
+
+    // The agent either exits, or prints the completion signal and is given
+    // `grace` to exit on its own before it is stopped
+    let run = timeout(timeout_duration, async {
+        tokio::select! {
+            status = child.wait() => Some(status),
+            _ = async {
+                completed.notified().await;
+                tokio::time::sleep(grace).await;
+            } => None,
+        }
+    });
 
-    // Clone the TUI event sender for the spawned task
-    let tui_event_tx = options.on_tui_event;
-
-    let result = timeout(timeout_duration, async {
-        // Read stdout and stderr concurrently
-        let stdout_handle = tokio::spawn(async move {
-            let mut stdout_output = String::new();
-            if let Some(stdout) = stdout {
-                let mut reader = BufReader::new(stdout).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    let line_clone = line.clone();
-                    stdout_output.push_str(&line);
-                    stdout_output.push('\n');
-
-                    // Parse line for TUI events and send to channel
-                    if let Some(ref tx) = tui_event_tx {
-                        for event in parser::parse_agent_line(&line_clone) {
-                            let _ = tx.try_send(event);
-                        }
-                    }
-                }
-            }
-            stdout_output
-        });
+    let result = tokio::select! {
+        result = run => result,
+        _ = cancel_signal => {
+            terminate_gracefully(&mut child).await;
+            return Err(WreckitError::Interrupted);
+        }
+    };
 
-        let stderr_handle = tokio::spawn(async move {
-            let mut stderr_output = String::new();
-            if let Some(stderr) = stderr {
-                let mut reader = BufReader::new(stderr).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    stderr_output.push_str(&line);
-                    stderr_output.push('\n');
-                }
+    let wait_result = match result {
+        Ok(Some(wait_result)) => {
+            for reader in readers {
+                let _ = reader.await;
             }
-            stderr_output
-        });
-
-        let stdout_output = stdout_handle.await.unwrap_or_default();
-        let stderr_output = stderr_handle.await.unwrap_or_default();
+            wait_result
+        }
+        Ok(None) => {
+            tracing::warn!(
+                "Agent printed the completion signal but did not exit within {}s; stopping it",
+                grace.as_secs()
+            );
+            terminate_gracefully(&mut child).await;
+            drain(readers).await;
+            let output = collect_output(&stdout_output, &stderr_output);
+            report_output(&options, &stdout_output, &stderr_output);
+            return Ok(AgentResult {
+                success: true,
+                output,
+                timed_out: false,
+                exit_code: None,
+                completion_detected: true,
+            });
+        }
+        Err(_) => {
+            // Timeout occurred - terminate the process
+            terminate_gracefully(&mut child).await;
+            drain(readers).await;
 
-        (stdout_output, stderr_output, child.wait().await)
-    })
-    .await;
+            return Ok(AgentResult {
+                success: false,
+                output: collect_output(&stdout_output, &stderr_output),
+                timed_out: true,
+                exit_code: None,
+                completion_detected: false,
+            });
+        }
+    };
 
-    match result {
-        Ok((stdout_output, stderr_output, wait_result)) => {
-            output.push_str(&stdout_output);
-            output.push_str(&stderr_output);
+    let output = collect_output(&stdout_output, &stderr_output);
+    let completion_detected = output.contains(&signal);
+    report_output(&options, &stdout_output, &stderr_output);
 
-            // Check for completion signal
-            completion_detected = output.contains(&options.config.completion_signal);
+    match wait_result {
+        Ok(status) => Ok(AgentResult {
+            success: status.success() && completion_detected,
+            output,
+            timed_out: false,
+            exit_code: status.code(),
+            completion_detected,
+        }),
+        Err(e) => Err(WreckitError::AgentError(format!(
+            "Failed to wait for agent: {}",
+            e
+        ))),
+    }
+}
 
-            // Call callbacks if provided
-            if let Some(ref on_stdout) = options.on_stdout {
-                on_stdout(&stdout_output);
+/// Read a child's output stream line by line into `buffer`, notifying
+/// `completed` when a line contains the completion signal
+fn spawn_reader<R>(
+    stream: Option<R>,
+    buffer: Arc<Mutex<String>>,
+    signal: &str,
+    completed: Arc<Notify>,
+    events: Option<tokio::sync::mpsc::Sender<AgentEvent>>,
+) -> JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    let signal = signal.to_string();
+    tokio::spawn(async move {
+        let Some(stream) = stream else {
+            return;
+        };
+        let mut reader = BufReader::new(stream).lines();
+        while let Ok(Some(line)) = reader.next_line().await {
+            if !signal.is_empty() && line.contains(&signal) {
+                completed.notify_one();
             }
-            if let Some(ref on_stderr) = options.on_stderr {
-                on_stderr(&stderr_output);
+            if let Some(ref tx) = events {
+                for event in parser::parse_agent_line(&line) {
+                    let _ = tx.try_send(event);
+                }
             }
+            let mut buffer = buffer.lock().unwrap_or_else(|e| e.into_inner());
+            buffer.push_str(&line);
+            buffer.push('\n');
+        }
+    })
+}
 
-            match wait_result {
-                Ok(status) => Ok(AgentResult {
-                    success: status.success() && completion_detected,
-                    output,
-                    timed_out: false,
-                    exit_code: status.code(),
-                    completion_detected,
-                }),
-                Err(e) => Err(WreckitError::AgentError(format!(
-                    "Failed to wait for agent: {}",
-                    e
-                ))),
-            }
+/// Wait briefly for the readers of a stopped agent; a grandchild that kept
+/// the pipes open must not hold up the run
+async fn drain(readers: [JoinHandle<()>; 2]) {
+    for reader in readers {
+        let abort = reader.abort_handle();
+        if timeout(Duration::from_secs(5), reader).await.is_err() {
+            abort.abort();
         }
-        Err(_) => {
-            // Timeout occurred - kill the process
-            let _ = child.kill().await;
+    }
+}
 
-            Ok(AgentResult {
-                success: false,
-                output,
-                timed_out: true,
-                exit_code: None,
-                completion_detected: false,
-            })
+/// Pass the collected output to the stdout/stderr callbacks, if any
+fn report_output(options: &RunAgentOptions, stdout: &Mutex<String>, stderr: &Mutex<String>) {
+    if let Some(ref on_stdout) = options.on_stdout {
+        on_stdout(&stdout.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+    if let Some(ref on_stderr) = options.on_stderr {
+        on_stderr(&stderr.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+}
+
+fn collect_output(stdout: &Mutex<String>, stderr: &Mutex<String>) -> String {
+    let mut output = stdout.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    output.push_str(&stderr.lock().unwrap_or_else(|e| e.into_inner()));
+    output
+}
+
+/// Send SIGTERM and give the process 5s to exit before killing it.
+async fn terminate_gracefully(child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: kill(2) with a pid we spawned and own; no memory is touched.
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+        if timeout(Duration::from_secs(5), child.wait()).await.is_ok() {
+            return;
         }
     }
+    let _ = child.kill().await;
 }
 
 #[cfg(test)]
@@ -221,6 +345,8 @@ mod tests {
             on_stdout: None,
             on_stderr: None,
             on_tui_event: None,
+            cancel: None,
+            env_activation: EnvActivation::None,
         };
 
         let result = run_agent(options).await.unwrap();
@@ -240,6 +366,11 @@ mod tests {
                 command: "echo".to_string(),
                 args: vec!["hello".to_string()],
                 completion_signal: "hello".to_string(),
+                completion_grace_seconds: 30,
+                fixtures_dir: None,
+                env: Default::default(),
+                execution: Default::default(),
+                limits: Default::default(),
             },
             cwd: PathBuf::from("."),
             prompt: String::new(),
@@ -248,6 +379,8 @@ mod tests {
             on_stdout: None,
             on_stderr: None,
             on_tui_event: None,
+            cancel: None,
+            env_activation: EnvActivation::None,
         };
 
         let result = run_agent(options).await.unwrap();
@@ -259,6 +392,37 @@ mod tests {
         assert!(result.completion_detected);
     }
 
+    #[tokio::test]
+    async fn test_agent_env() {
+        let options = RunAgentOptions {
+            config: AgentConfig {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), "echo \"$TARGET:$HOME_COPY\"".to_string()],
+                completion_signal: "DONE".to_string(),
+                env: [
+                    ("TARGET".to_string(), "staging".to_string()),
+                    ("HOME_COPY".to_string(), "env:HOME".to_string()),
+                ]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            },
+            cwd: PathBuf::from("."),
+            prompt: String::new(),
+            dry_run: false,
+            timeout_seconds: 10,
+            on_stdout: None,
+            on_stderr: None,
+            on_tui_event: None,
+            cancel: None,
+            env_activation: EnvActivation::None,
+        };
+
+        let result = run_agent(options).await.unwrap();
+        let expected = format!("staging:{}", std::env::var("HOME").unwrap());
+        assert!(result.output.contains(&expected));
+    }
+
     #[tokio::test]
     async fn test_tui_event_callback() {
         let (tx, mut rx) = tokio::sync::mpsc::channel::<AgentEvent>(100);
@@ -271,6 +435,11 @@ mod tests {
                     "<tool_use>{\"toolUseId\":\"test123\",\"name\":\"test_tool\",\"input\":{}}</tool_use>".to_string()
                 ],
                 completion_signal: "tool_use".to_string(),
+                completion_grace_seconds: 30,
+                fixtures_dir: None,
+                env: Default::default(),
+                execution: Default::default(),
+                limits: Default::default(),
             },
             cwd: PathBuf::from("."),
             prompt: String::new(),
@@ -279,6 +448,8 @@ mod tests {
             on_stdout: None,
             on_stderr: None,
             on_tui_event: Some(tx),
+            cancel: None,
+            env_activation: EnvActivation::None,
         };
 
         // Spawn a task to collect events
@@ -301,4 +472,76 @@ mod tests {
         let captured_events = event_collector.abort();
         assert!(result.success, "Agent should have completed successfully");
     }
+
+    #[tokio::test]
+    async fn test_completion_signal_stops_hung_agent() {
+        let options = RunAgentOptions {
+            config: AgentConfig {
+                command: "sh".to_string(),
+                args: vec![
+                    "-c".to_string(),
+                    "echo working; echo DONE; exec sleep 30".to_string(),
+                ],
+                completion_signal: "DONE".to_string(),
+                completion_grace_seconds: 0,
+                ..Default::default()
+            },
+            cwd: PathBuf::from("."),
+            prompt: String::new(),
+            dry_run: false,
+            timeout_seconds: 60,
+            on_stdout: None,
+            on_stderr: None,
+            on_tui_event: None,
+            cancel: None,
+            env_activation: EnvActivation::None,
+        };
+
+        let result = tokio::time::timeout(Duration::from_secs(10), run_agent(options))
+            .await
+            .expect("agent should be stopped after printing the signal")
+            .unwrap();
+        assert!(result.success);
+        assert!(result.completion_detected);
+        assert!(!result.timed_out);
+        assert!(result.output.contains("working"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_terminates_agent() {
+        let (sender, handle) = crate::tui::control::control_channel();
+
+        let options = RunAgentOptions {
+            config: AgentConfig {
+                mode: crate::schemas::AgentMode::Process,
+                command: "sleep".to_string(),
+                args: vec!["30".to_string()],
+                completion_signal: "never".to_string(),
+                completion_grace_seconds: 30,
+                fixtures_dir: None,
+                env: Default::default(),
+                execution: Default::default(),
+                limits: Default::default(),
+            },
+            cwd: PathBuf::from("."),
+            prompt: String::new(),
+            dry_run: false,
+            timeout_seconds: 60,
+            on_stdout: None,
+            on_stderr: None,
+            on_tui_event: None,
+            cancel: Some(handle.cancel_token()),
+            env_activation: EnvActivation::None,
+        };
+
+        let run = tokio::spawn(run_agent(options));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        sender.send(crate::tui::control::ControlCommand::CancelCurrent);
+
+        let result = tokio::time::timeout(Duration::from_secs(5), run)
+            .await
+            .expect("agent should stop promptly after cancel")
+            .unwrap();
+        assert!(matches!(result, Err(WreckitError::Interrupted)));
+    }
 }
@@ -0,0 +1,340 @@
+//! Library API for embedding wreckit
+//!
+//! [`Wreckit`] wraps a repository's [`WorkflowContext`] and exposes the
+//! engine's operations as typed calls that return items instead of printing:
+//! create, read, and list items, run phases, and move items between states.
+//! Nothing here depends on clap or writes to stdout, so bots and servers can
+//! drive wreckit directly. The CLI opens repositories through the same
+//! [`load_context`].
+//!
+//! ```no_run
+//! # async fn demo() -> wreckit::Result<()> {
+//! use wreckit::api::{NewItem, Wreckit};
+//! use wreckit::workflow::PhaseKind;
+//!
+//! let wreckit = Wreckit::open(std::path::Path::new("."))?;
+//! let item = wreckit.create_item(NewItem::new("Add dark mode"))?;
+//! let item = wreckit.run_phase(&item.id, PhaseKind::Research).await?;
+//! println!("{} is {}", item.id, item.state);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+
+use tokio::sync::broadcast;
+
+use crate::config::load_config;
+use crate::domain::{backlog_stats, BacklogStats};
+use crate::errors::Result;
+use crate::fs::{self, ItemQuery};
+use crate::plugins;
+use crate::schemas::Item;
+use crate::workflow::{advance_item, Orchestrator, PhaseKind, WorkflowContext, WorkflowEvent};
+
+/// Resolve the repository containing `start` and build a workflow context.
+///
+/// The SQLite item index is built if enabled and missing (except in dry-run
+/// mode), and the validation plugins in `.wreckit/plugins/` are loaded.
+/// Interrupted metadata writes are left to [`fs::acquire_lock`], which rolls
+/// them forward once no other process can be mid-commit.
+///
+/// # Errors
+/// * `RepoNotFound` - If no repository root is found
+/// * `InvalidJson` / `SchemaValidation` - If config.json is malformed
+/// * `ConfigError` - If a validation plugin cannot be loaded
+/// * `Io` - If the item index cannot be built
+pub fn load_context(start: &Path, dry_run: bool) -> Result<WorkflowContext> {
+    let root = fs::find_repo_root(start)?;
+    // Spectators do not build caches
+    let writes = !dry_run && !fs::is_read_only();
+    let config = load_config(&root)?;
+    if config.sqlite_index && writes && !fs::get_index_db_path(&root).exists() {
+        let count = fs::rebuild_index_db(&root)?;
+        tracing::info!("Built item index cache ({} items)", count);
+    }
+    let validators = plugins::load_validators(&root)?;
+    Ok(WorkflowContext::new(root, config)
+        .with_dry_run(dry_run)
+        .with_validators(validators))
+}
+
+/// A new item to add to the backlog
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NewItem {
+    /// Item title
+    pub title: String,
+
+    /// What the item is about
+    pub overview: String,
+
+    /// Backlog section (e.g. "frontend")
+    pub section: Option<String>,
+
+    /// Problem the item solves
+    pub problem_statement: Option<String>,
+
+    /// Why it matters
+    pub motivation: Option<String>,
+
+    /// How to tell it is done
+    pub success_criteria: Option<Vec<String>>,
+}
+
+impl NewItem {
+    /// Create an item with only a title
+    pub fn new(title: impl Into<String>) -> Self {
+        NewItem {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the overview
+    pub fn with_overview(mut self, overview: impl Into<String>) -> Self {
+        self.overview = overview.into();
+        self
+    }
+
+    /// Set the backlog section
+    pub fn with_section(mut self, section: impl Into<String>) -> Self {
+        self.section = Some(section.into());
+        self
+    }
+
+    /// Set the problem statement
+    pub fn with_problem_statement(mut self, problem: impl Into<String>) -> Self {
+        self.problem_statement = Some(problem.into());
+        self
+    }
+
+    /// Set the motivation
+    pub fn with_motivation(mut self, motivation: impl Into<String>) -> Self {
+        self.motivation = Some(motivation.into());
+        self
+    }
+
+    /// Set the success criteria
+    pub fn with_success_criteria(mut self, criteria: Vec<String>) -> Self {
+        self.success_criteria = Some(criteria);
+        self
+    }
+}
+
+/// A wreckit repository, ready to be driven programmatically
+#[derive(Clone)]
+pub struct Wreckit {
+    ctx: WorkflowContext,
+}
+
+impl Wreckit {
+    /// Open the repository containing `path`.
+    ///
+    /// # Errors
+    /// See [`load_context`].
+    pub fn open(path: &Path) -> Result<Self> {
+        Ok(Self::from_context(load_context(path, false)?))
+    }
+
+    /// Wrap an existing workflow context
+    pub fn from_context(ctx: WorkflowContext) -> Self {
+        Wreckit { ctx }
+    }
+
+    /// Return a handle that describes actions without performing them
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.ctx = self.ctx.with_dry_run(dry_run);
+        self
+    }
+
+    /// Return a handle that re-runs phases even when their artifacts exist
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.ctx = self.ctx.with_force(force);
+        self
+    }
+
+    /// The underlying workflow context
+    pub fn context(&self) -> &WorkflowContext {
+        &self.ctx
+    }
+
+    /// Repository root
+    pub fn root(&self) -> &Path {
+        &self.ctx.root
+    }
+
+    /// Receive every lifecycle event published from now on by this handle
+    /// and its clones
+    pub fn subscribe(&self) -> broadcast::Receiver<WorkflowEvent> {
+        self.ctx.events.subscribe()
+    }
+
+    /// Add an item to the backlog in the idea state, with an ID from the
+    /// configured scheme. In dry-run mode the item is returned but not saved.
+    ///
+    /// # Errors
+    /// * `Io` - If the item cannot be written
+    pub fn create_item(&self, new: NewItem) -> Result<Item> {
+        let id = self.ctx.new_item_id(&new.title)?;
+        let mut item = Item::new(id, new.title, new.overview);
+        item.section = new.section;
+        item.problem_statement = new.problem_statement;
+        item.motivation = new.motivation;
+        item.success_criteria = new.success_criteria;
+        if self.ctx.dry_run {
+            tracing::info!("[DRY RUN] Would create {}", item.id);
+            return Ok(item);
+        }
+        self.ctx.save_item(&item)?;
+        Ok(item)
+    }
+
+    /// Read an item.
+    ///
+    /// # Errors
+    /// * `FileNotFound` - If the item does not exist
+    pub fn item(&self, id: &str) -> Result<Item> {
+        fs::read_item(&self.ctx.root, id)
+    }
+
+    /// List the items matching a query, sorted by ID.
+    ///
+    /// # Errors
+    /// * `InvalidJson` - If an item file is malformed
+    pub fn list(&self, query: &ItemQuery) -> Result<Vec<Item>> {
+        fs::query_items(&self.ctx.root, query)
+    }
+
+    /// Backlog statistics (counts per state, time in state, throughput)
+    ///
+    /// # Errors
+    /// * `InvalidJson` - If an item file is malformed
+    pub fn stats(&self) -> Result<BacklogStats> {
+        let items = fs::query_items(&self.ctx.root, &ItemQuery::new())?;
+        Ok(backlog_stats(&items, chrono::Utc::now()))
+    }
+
+    /// Run one phase for an item and return the updated item.
+    ///
+    /// # Errors
+    /// Any error the phase fails with; it is also recorded on the item.
+    pub async fn run_phase(&self, id: &str, kind: PhaseKind) -> Result<Item> {
+        self.orchestrator().run_phase(id, kind).await
+    }
+
+    /// Run an item through every applicable phase.
+    ///
+    /// # Errors
+    /// Any error a phase fails with; it is also recorded on the item.
+    pub async fn run_item(&self, id: &str) -> Result<Item> {
+        self.orchestrator().run_item(id).await
+    }
+
+    /// Run the next item with work to do, if any.
+    ///
+    /// # Errors
+    /// Any error a phase fails with.
+    pub async fn run_next(&self) -> Result<Option<Item>> {
+        self.orchestrator().run_next().await
+    }
+
+    /// Clear an item's error and re-run a phase (the one it is stuck in if
+    /// `kind` is `None`), rewinding the item first if `rollback` is set.
+    ///
+    /// # Errors
+    /// * `StateTransition` - If the phase cannot run from the item's state
+    /// * Any error the phase fails with
+    pub async fn retry(&self, id: &str, kind: Option<PhaseKind>, rollback: bool) -> Result<Item> {
+        self.orchestrator().retry_phase(id, kind, rollback).await
+    }
+
+    /// Move an item into the next config-defined state, optionally checking
+    /// that it is `target`.
+    ///
+    /// # Errors
+    /// * `StateTransition` - If the item cannot advance (or not to `target`)
+    pub async fn advance(&self, id: &str, target: Option<&str>) -> Result<Item> {
+        advance_item(&self.ctx, id, target).await
+    }
+
+    fn orchestrator(&self) -> Orchestrator {
+        Orchestrator::new(self.ctx.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{MockFixture, DEFAULT_FIXTURES_DIR};
+    use crate::schemas::{AgentMode, Config, WorkflowState};
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_embedded_workflow() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        let mut config = Config::default();
+        config.agent.mode = AgentMode::Mock;
+        config.context_pack.enabled = false;
+        std::fs::create_dir_all(fs::get_wreckit_dir(root)).unwrap();
+        fs::write_json(&fs::get_config_path(root), &config).unwrap();
+
+        let fixtures = root.join(DEFAULT_FIXTURES_DIR);
+        std::fs::create_dir_all(&fixtures).unwrap();
+        let fixture = MockFixture {
+            files: BTreeMap::from([("research.md".to_string(), "# Research\n".to_string())]),
+            complete: true,
+            ..Default::default()
+        };
+        fs::write_json(&fixtures.join("research.json"), &fixture).unwrap();
+
+        let wreckit = Wreckit::open(root).unwrap();
+        let mut events = wreckit.subscribe();
+        let preview = wreckit
+            .clone()
+            .with_dry_run(true)
+            .create_item(NewItem::new("Preview"))
+            .unwrap();
+        assert!(!fs::get_item_json_path(root, &preview.id).exists());
+
+        let item = wreckit
+            .create_item(
+                NewItem::new("Add dark mode")
+                    .with_overview("Themes")
+                    .with_section("ui"),
+            )
+            .unwrap();
+        assert_eq!(
+            wreckit.item(&item.id).unwrap().section.as_deref(),
+            Some("ui")
+        );
+
+        let item = wreckit
+            .run_phase(&item.id, PhaseKind::Research)
+            .await
+            .unwrap();
+        assert_eq!(item.state, WorkflowState::Researched);
+
+        let mut names = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            names.push(event.name());
+        }
+        assert_eq!(
+            names,
+            vec![
+                "item_created",
+                "phase_started",
+                "state_changed",
+                "phase_finished"
+            ]
+        );
+
+        let researched = wreckit
+            .list(&ItemQuery::new().with_state(WorkflowState::Researched))
+            .unwrap();
+        assert_eq!(researched.len(), 1);
+        assert_eq!(wreckit.stats().unwrap().total, 1);
+    }
+}
@@ -0,0 +1,23 @@
+//! Abandon command - Stop work on an item and clean up its PR and branches
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::abandon_item;
+use std::path::Path;
+
+/// Abandon an item, closing its PR and deleting its branches
+pub async fn run(cwd: Option<&Path>, id: &str, reason: Option<&str>, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let item = abandon_item(&ctx, id, reason).await?;
+    tracing::info!(
+        "{} is {}; artifacts kept for reference",
+        item.id,
+        item.state
+    );
+    Ok(())
+}
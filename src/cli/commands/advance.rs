@@ -0,0 +1,19 @@
+//! Advance command - Move an item into the next config-defined state
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::advance_item;
+use std::path::Path;
+
+/// Advance an item to the next state, running that state's validation hooks
+pub async fn run(cwd: Option<&Path>, id: &str, to: Option<&str>, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let item = advance_item(&ctx, id, to).await?;
+    tracing::info!("{} is {}", item.id, item.state_name());
+    Ok(())
+}
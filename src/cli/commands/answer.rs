@@ -0,0 +1,21 @@
+//! Answer command - Reply to a question the agent asked
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::answer_question;
+use std::path::Path;
+
+/// Answer an item's open question so its next run can resume
+pub async fn run(cwd: Option<&Path>, id: &str, text: &str, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    answer_question(&ctx, id, text)?;
+    if !dry_run {
+        tracing::info!("Answered {}; run `wreckit run {}` to resume", id, id);
+    }
+    Ok(())
+}
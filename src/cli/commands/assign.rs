@@ -0,0 +1,37 @@
+//! Claim and assign commands - Item ownership for shared backlogs
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::{assign_item, claim_item};
+use std::path::Path;
+
+fn options(dry_run: bool) -> SessionOptions {
+    SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    }
+}
+
+/// Assign an item to the current identity
+pub async fn claim(cwd: Option<&Path>, id: &str, dry_run: bool) -> Result<()> {
+    let ctx = open_context(cwd, options(dry_run))?;
+    let item = claim_item(&ctx, id).await?;
+    tracing::info!(
+        "{} is assigned to {}",
+        item.id,
+        item.assignee.unwrap_or_default()
+    );
+    Ok(())
+}
+
+/// Set or clear an item's assignee
+pub async fn run(cwd: Option<&Path>, id: &str, user: Option<&str>, dry_run: bool) -> Result<()> {
+    let ctx = open_context(cwd, options(dry_run))?;
+    let item = assign_item(&ctx, id, user)?;
+    match item.assignee {
+        Some(ref assignee) => tracing::info!("{} is assigned to {}", item.id, assignee),
+        None => tracing::info!("{} is unassigned", item.id),
+    }
+    Ok(())
+}
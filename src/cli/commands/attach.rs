@@ -0,0 +1,106 @@
+//! Attach command - Follow a run started with `wreckit run --detach`
+
+use crate::cli::session::{open_context, tui_options, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::NotifyMode;
+use crate::tui::{
+    apply_update, EventReader, Notifier, PlainRenderer, RenderMode, TuiRunner, TuiState, TuiUpdate,
+};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How often an attached renderer checks the event file for new updates
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The event file for an item, or the most recently written one
+fn find_event_file(root: &Path, id: Option<&str>) -> Result<PathBuf> {
+    if let Some(id) = id {
+        return Ok(fs::get_detached_events_path(root, id));
+    }
+    std::fs::read_dir(fs::get_detached_dir(root))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.to_string_lossy().ends_with(".events.jsonl"))
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max()
+        .map(|(_, path)| path)
+        .ok_or_else(|| {
+            WreckitError::FileNotFound(
+                "No detached runs; start one with wreckit run <id> --detach".to_string(),
+            )
+        })
+}
+
+/// Show a detached run from its start and follow it until it finishes
+pub async fn run(cwd: Option<&Path>, id: Option<&str>, no_tui: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run: false,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let mut reader = EventReader::open(&find_event_file(&ctx.root, id)?)?;
+    let items = fs::list_items(&ctx.root)?;
+
+    match RenderMode::detect(no_tui) {
+        RenderMode::Plain => {
+            let mut renderer = PlainRenderer::new(items, tui_options(&ctx)?);
+            loop {
+                for update in reader.read_new()? {
+                    if let Some(line) = renderer.render_update(update) {
+                        println!("{}", line);
+                    }
+                }
+                if reader.finished() {
+                    break;
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+            if let Some(summary) = renderer.summary() {
+                println!("{}", summary);
+            }
+            Ok(())
+        }
+        RenderMode::Interactive => {
+            let mut runner = TuiRunner::new(items.clone(), tui_options(&ctx)?).await;
+            let updates = runner.create_update_sender();
+
+            // Fold each batch into a local state and hand the TUI the result, so
+            // replaying a long run does not overflow the update channel
+            let follow = tokio::spawn(async move {
+                let notifier = Notifier::new(NotifyMode::None);
+                let mut state = TuiState::new(items);
+                loop {
+                    let batch = reader.read_new()?;
+                    if !batch.is_empty() {
+                        for update in batch {
+                            apply_update(&mut state, update, &notifier);
+                        }
+                        let _ = updates.send(TuiUpdate::Restore(Box::new(state.clone())));
+                    }
+                    if reader.finished() {
+                        let _ = updates.send(TuiUpdate::RunFinished);
+                        return Ok::<(), WreckitError>(());
+                    }
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            });
+
+            let result = runner.run().await;
+            if follow.is_finished() {
+                follow
+                    .await
+                    .map_err(|e| WreckitError::wrap(e, "event reader failed"))??;
+            } else {
+                follow.abort();
+            }
+            result
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Auth command - Store and remove tokens in the OS keychain, and check
+//! every configured credential
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::fs::ensure_writable;
+use crate::secrets::{secret_store, validate_secret_name};
+use crate::workflow::check_credentials;
+
+/// Turns terminal echo off until dropped, so a pasted token is not shown
+#[cfg(unix)]
+struct EchoOff {
+    saved: libc::termios,
+}
+
+#[cfg(unix)]
+impl EchoOff {
+    fn new() -> Option<Self> {
+        // SAFETY: tcgetattr fills the zeroed termios for stdin, and
+        // tcsetattr only changes the echo flag of the copy it is given.
+        unsafe {
+            let mut saved: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut saved) != 0 {
+                return None;
+            }
+            let mut quiet = saved;
+            quiet.c_lflag &= !libc::ECHO;
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &quiet) != 0 {
+                return None;
+            }
+            Some(EchoOff { saved })
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        // SAFETY: restores the settings read in `new`
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.saved);
+        }
+    }
+}
+
+/// Read a token from stdin, prompting without echo on a terminal
+fn read_token(name: &str) -> Result<String> {
+    let stdin = std::io::stdin();
+    let interactive = stdin.is_terminal();
+    if interactive {
+        eprint!("Token for {}: ", name);
+        std::io::stderr().flush()?;
+    }
+    #[cfg(unix)]
+    let echo = if interactive { EchoOff::new() } else { None };
+    let mut line = String::new();
+    stdin.lock().read_line(&mut line)?;
+    #[cfg(unix)]
+    drop(echo);
+    if interactive {
+        eprintln!();
+    }
+
+    let token = line.trim().to_string();
+    if token.is_empty() {
+        return Err(WreckitError::ConfigError(format!(
+            "no token given for {}",
+            name
+        )));
+    }
+    Ok(token)
+}
+
+/// Store a token read from stdin in the keychain under `name`
+pub fn set(name: &str) -> Result<()> {
+    validate_secret_name(name)?;
+    ensure_writable(|| format!("store {} in the keychain", name))?;
+    let token = read_token(name)?;
+    secret_store().set(name, &token)?;
+    tracing::info!("Stored {} in the keychain", name);
+    Ok(())
+}
+
+/// Remove the token stored under `name`
+pub fn delete(name: &str) -> Result<()> {
+    validate_secret_name(name)?;
+    ensure_writable(|| format!("remove {} from the keychain", name))?;
+    if secret_store().delete(name)? {
+        tracing::info!("Removed {} from the keychain", name);
+    } else {
+        tracing::info!("No {} token is stored in the keychain", name);
+    }
+    Ok(())
+}
+
+/// Check every configured credential and report pass/fail per provider
+pub async fn status(cwd: Option<&Path>) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run: false,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let checks = check_credentials(&ctx).await;
+    for check in &checks {
+        println!(
+            "{} {:<20} {}",
+            if check.passed { "ok  " } else { "FAIL" },
+            check.provider,
+            check.detail
+        );
+        if let Some(ref hint) = check.hint {
+            println!("     {:<20} hint: {}", "", hint);
+        }
+    }
+    let failed = checks.iter().filter(|c| !c.passed).count();
+    if failed > 0 {
+        return Err(WreckitError::ConfigError(format!(
+            "{} of {} credential check(s) failed",
+            failed,
+            checks.len()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Bench command - Compare agent setups on the same item
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::workflow::{format_bench_report, run_bench};
+use std::path::Path;
+
+/// Run research and plan for an item once per bench variant and report the results
+pub async fn run(
+    cwd: Option<&Path>,
+    id: &str,
+    variants: &[String],
+    json: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let results = run_bench(&ctx, id, variants).await?;
+    if dry_run {
+        return Ok(());
+    }
+
+    if json {
+        let text = serde_json::to_string_pretty(&results)
+            .map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+        println!("{}", text);
+        return Ok(());
+    }
+    println!("{}", format_bench_report(&results));
+    Ok(())
+}
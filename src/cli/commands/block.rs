@@ -0,0 +1,41 @@
+//! Block and unblock commands - Park an item until a condition is met
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::{block_item, unblock_item};
+use std::path::Path;
+
+fn options(dry_run: bool) -> SessionOptions {
+    SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    }
+}
+
+/// Block an item with a reason and optional unblock condition
+pub async fn run(
+    cwd: Option<&Path>,
+    id: &str,
+    reason: &str,
+    until: Option<&str>,
+    on_item: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let ctx = open_context(cwd, options(dry_run))?;
+    let item = block_item(&ctx, id, reason, until, on_item)?;
+    match (until, on_item) {
+        (Some(date), _) => tracing::info!("Blocked {} until {}", item.id, date),
+        (_, Some(other)) => tracing::info!("Blocked {} until {} is done", item.id, other),
+        _ => tracing::info!("Blocked {}", item.id),
+    }
+    Ok(())
+}
+
+/// Lift an item's blocker
+pub async fn unblock(cwd: Option<&Path>, id: &str, dry_run: bool) -> Result<()> {
+    let ctx = open_context(cwd, options(dry_run))?;
+    let item = unblock_item(&ctx, id)?;
+    tracing::info!("{} is {}", item.id, item.state_name());
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Bulk command - Apply one action to every item matching a filter
+
+use crate::cli::session::{open_context, run_with_renderer, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::schemas::Item;
+use crate::workflow::{abandon_item, select_items, tag_item, BulkAction, BulkFilter, Orchestrator};
+use chrono::Utc;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+
+/// Ask the operator to confirm on stderr; anything but y/yes declines
+fn confirm(prompt: &str) -> Result<bool> {
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        return Err(WreckitError::wrap(
+            "no terminal to confirm on; pass --yes",
+            "Refusing to run a bulk action",
+        ));
+    }
+    eprint!("{} [y/N] ", prompt);
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    stdin.lock().read_line(&mut line)?;
+    Ok(matches!(line.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn print_summary(items: &[Item], action: &BulkAction) {
+    eprintln!("{} item(s) will be changed ({}):", items.len(), action);
+    for item in items {
+        eprintln!("  {:<24} {:<12} {}", item.id, item.state_name(), item.title);
+    }
+}
+
+/// Apply an action to every item matching the state and age filters, after
+/// confirmation (unless `yes`)
+pub async fn run(
+    cwd: Option<&Path>,
+    state: Option<&str>,
+    older_than: Option<&str>,
+    action: &str,
+    yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let action: BulkAction = action
+        .parse()
+        .map_err(|e: String| WreckitError::wrap(e, "Invalid --action"))?;
+    let filter = BulkFilter::new(state, older_than)?;
+    let options = SessionOptions {
+        force: true,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let items = select_items(&ctx, &filter, Utc::now())?;
+    if items.is_empty() {
+        tracing::info!("No items match");
+        return Ok(());
+    }
+    print_summary(&items, &action);
+    if dry_run {
+        return Ok(());
+    }
+    if !yes && !confirm("Proceed?")? {
+        tracing::info!("Nothing changed");
+        return Ok(());
+    }
+
+    let ids: Vec<String> = items.into_iter().map(|item| item.id).collect();
+    let mut failures = Vec::new();
+    match action {
+        BulkAction::Abandon => {
+            for id in &ids {
+                if let Err(e) = abandon_item(&ctx, id, Some("bulk abandon")).await {
+                    failures.push(format!("{}: {}", id, e));
+                }
+            }
+        }
+        BulkAction::Tag(ref tag) => {
+            for id in &ids {
+                if let Err(e) = tag_item(&ctx, id, tag) {
+                    failures.push(format!("{}: {}", id, e));
+                }
+            }
+        }
+        BulkAction::Retry => {
+            let retry_ids = ids.clone();
+            failures = run_with_renderer(ctx, true, move |ctx| async move {
+                let mut failures = Vec::new();
+                for id in &retry_ids {
+                    let orchestrator = Orchestrator::new(ctx.clone());
+                    if let Err(e) = orchestrator.retry_phase(id, None, false).await {
+                        failures.push(format!("{}: {}", id, e));
+                    }
+                }
+                Ok(failures)
+            })
+            .await?;
+        }
+    }
+
+    tracing::info!(
+        "{}: {} succeeded, {} failed",
+        action,
+        ids.len() - failures.len(),
+        failures.len()
+    );
+    if failures.is_empty() {
+        return Ok(());
+    }
+    for failure in &failures {
+        tracing::error!("{}", failure);
+    }
+    Err(WreckitError::wrap(
+        format!("{} of {} item(s) failed", failures.len(), ids.len()),
+        format!("Bulk {}", action),
+    ))
+}
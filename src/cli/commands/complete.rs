@@ -1,9 +1,17 @@
 //! Complete command - Mark an item as complete after PR is merged
 
+use crate::cli::session::{run_single_phase, SessionOptions};
 use crate::errors::Result;
+use crate::workflow::PhaseKind;
 use std::path::Path;
 
 /// Mark an item as complete (after PR is merged)
-pub async fn run(_cwd: Option<&Path>, _id: &str, _dry_run: bool) -> Result<()> {
-    todo!("Implement complete command")
+pub async fn run(cwd: Option<&Path>, id: &str, dry_run: bool) -> Result<()> {
+    // A single gh query; the interactive TUI would only flash on screen
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    run_single_phase(cwd, id, PhaseKind::Complete, options).await
 }
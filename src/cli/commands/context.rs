@@ -0,0 +1,31 @@
+//! Context command - Build or print the cached repository context pack
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::context_pack::{get_context_pack_path, refresh_context_pack};
+use std::path::Path;
+
+/// Rebuild the context pack if it is stale (or `refresh` is set) and print it
+pub async fn run(cwd: Option<&Path>, refresh: bool, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let path = get_context_pack_path(&ctx.root);
+    let rebuilt = refresh_context_pack(&ctx, refresh).await?;
+    if dry_run {
+        if rebuilt {
+            tracing::info!("Would rebuild {}", path.display());
+        } else {
+            tracing::info!("{} is up to date", path.display());
+        }
+        return Ok(());
+    }
+    match std::fs::read_to_string(&path) {
+        Ok(pack) => println!("{}", pack.trim_end()),
+        Err(_) => tracing::info!("Not a git repository; no context pack to build"),
+    }
+    Ok(())
+}
@@ -1,9 +1,50 @@
-//! Doctor command - Validate items and optionally fix issues
+//! Doctor command - Check disk space, validate items, and optionally fix issues
 
+use crate::cli::session::{open_context, SessionOptions};
 use crate::errors::Result;
+use crate::workflow::{check_disk, lint_items, WorkflowContext};
 use std::path::Path;
 
-/// Validate items and optionally fix issues
-pub async fn run(_cwd: Option<&Path>, _fix: bool) -> Result<()> {
-    todo!("Implement doctor command")
+/// Check disk space and, with `--deep`, lint artifact contents
+pub async fn run(cwd: Option<&Path>, _fix: bool, deep: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run: false,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let mut problems = report_disk(&ctx).await?;
+    if deep {
+        problems += run_deep(&ctx).await?;
+    }
+    if problems == 0 {
+        tracing::info!("No problems found");
+    } else {
+        println!("{} problem(s) found", problems);
+    }
+    Ok(())
+}
+
+/// Print each disk warning with its fix, returning how many there were
+async fn report_disk(ctx: &WorkflowContext) -> Result<usize> {
+    let warnings = check_disk(ctx).await?;
+    for warning in &warnings {
+        println!("disk [{}] {}", warning.check, warning.message);
+        println!("  fix: {}", warning.fix);
+    }
+    Ok(warnings.len())
+}
+
+/// Lint artifact contents and print each finding with its fix, returning how
+/// many there were
+async fn run_deep(ctx: &WorkflowContext) -> Result<usize> {
+    let findings = lint_items(ctx).await?;
+    for finding in &findings {
+        println!(
+            "{} [{}] {}",
+            finding.item_id, finding.check, finding.message
+        );
+        println!("  fix: {}", finding.fix);
+    }
+    Ok(findings.len())
 }
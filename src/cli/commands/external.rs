@@ -0,0 +1,182 @@
+//! External command - Run a `wreckit-<name>` plugin from PATH
+//!
+//! Unknown subcommands are looked up on PATH cargo/git style: `wreckit foo
+//! --bar` runs `wreckit-foo --bar`. The plugin inherits the terminal and gets
+//! the resolved repository in its environment:
+//!
+//! * `WRECKIT_BIN` - Path of the running wreckit binary
+//! * `WRECKIT_ROOT` - Repository root (unset outside a repository)
+//! * `WRECKIT_CONFIG` - Resolved configuration as JSON (unset outside a repository)
+//! * `WRECKIT_OUTPUT` - "json" or "text", from the global `--output` flag
+//! * `WRECKIT_PROFILE` - Active config profile (unset when none is selected)
+//! * `WRECKIT_DRY_RUN`, `WRECKIT_NO_TUI`, `WRECKIT_VERBOSE`, `WRECKIT_QUIET`,
+//!   `WRECKIT_READ_ONLY` - Set to "1" when the matching global flag was given
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use clap::error::ErrorKind;
+use clap::CommandFactory;
+use tokio::process::Command;
+
+use crate::api::load_context;
+use crate::cli::Cli;
+use crate::config::{active_profile, PROFILE_ENV};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+
+/// Global flags forwarded to a plugin
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PluginFlags {
+    pub verbose: bool,
+    pub quiet: bool,
+    pub dry_run: bool,
+    pub no_tui: bool,
+    pub read_only: bool,
+    /// `--output json` was given
+    pub json: bool,
+}
+
+impl PluginFlags {
+    /// Environment variables set to "1" for the flags that were given
+    fn env_vars(&self) -> Vec<&'static str> {
+        [
+            ("WRECKIT_DRY_RUN", self.dry_run),
+            ("WRECKIT_NO_TUI", self.no_tui),
+            ("WRECKIT_VERBOSE", self.verbose),
+            ("WRECKIT_QUIET", self.quiet),
+            ("WRECKIT_READ_ONLY", self.read_only),
+        ]
+        .into_iter()
+        .filter_map(|(var, set)| set.then_some(var))
+        .collect()
+    }
+}
+
+/// Find the `wreckit-<name>` executable on PATH
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    find_plugin_in(&std::env::var_os("PATH")?, name)
+}
+
+/// Find the `wreckit-<name>` executable in a PATH-style list of directories
+fn find_plugin_in(path: &OsStr, name: &str) -> Option<PathBuf> {
+    std::env::split_paths(path)
+        .map(|dir| dir.join(format!("wreckit-{}", name)))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Report an unknown subcommand the way clap reports its own and exit
+fn unknown_command(name: &str) -> ! {
+    Cli::command()
+        .error(
+            ErrorKind::InvalidSubcommand,
+            format!(
+                "unrecognized subcommand '{}' (no wreckit-{} on PATH)",
+                name, name
+            ),
+        )
+        .exit()
+}
+
+/// Run the plugin named by `args[0]` with the remaining arguments and return
+/// its exit code. Exits with a usage error if no `wreckit-<name>` executable
+/// is on PATH.
+///
+/// # Errors
+/// * `InvalidJson` / `SchemaValidation` - If the repository's config.json is malformed
+/// * `Io` - If the plugin cannot be spawned
+pub async fn run(cwd: Option<&Path>, args: &[String], flags: PluginFlags) -> Result<i32> {
+    let (name, rest) = match args.split_first() {
+        Some(split) => split,
+        None => unknown_command(""),
+    };
+    let plugin = match find_plugin(name) {
+        Some(plugin) => plugin,
+        None => unknown_command(name),
+    };
+
+    let dir = fs::resolve_cwd(cwd);
+    let mut cmd = Command::new(&plugin);
+    cmd.args(rest)
+        .current_dir(&dir)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    if let Ok(bin) = std::env::current_exe() {
+        cmd.env("WRECKIT_BIN", bin);
+    }
+    cmd.env("WRECKIT_OUTPUT", if flags.json { "json" } else { "text" });
+    if let Some(profile) = active_profile() {
+        cmd.env(PROFILE_ENV, profile);
+    }
+
+    // Plugins may also run outside a repository (e.g. to set one up)
+    match load_context(&dir, flags.dry_run) {
+        Ok(ctx) => {
+            let config = serde_json::to_string(&ctx.config)
+                .map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+            cmd.env("WRECKIT_ROOT", &ctx.root)
+                .env("WRECKIT_CONFIG", config);
+        }
+        Err(WreckitError::RepoNotFound(_)) => {}
+        Err(e) => return Err(e),
+    }
+    for var in flags.env_vars() {
+        cmd.env(var, "1");
+    }
+
+    tracing::debug!("Running plugin {}", plugin.display());
+    let status = cmd.status().await?;
+    Ok(status.code().unwrap_or(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_plugin_in_skips_non_executables() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        let plain = first.path().join("wreckit-foo");
+        std::fs::write(&plain, "#!/bin/sh\n").unwrap();
+        let plugin = second.path().join("wreckit-foo");
+        std::fs::write(&plugin, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let path = std::env::join_paths([first.path(), second.path()]).unwrap();
+        assert_eq!(find_plugin_in(&path, "foo"), Some(plugin));
+        assert_eq!(find_plugin_in(&path, "bar"), None);
+    }
+
+    #[test]
+    fn test_flag_env_vars() {
+        assert!(PluginFlags::default().env_vars().is_empty());
+        let flags = PluginFlags {
+            dry_run: true,
+            read_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            flags.env_vars(),
+            vec!["WRECKIT_DRY_RUN", "WRECKIT_READ_ONLY"]
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Flush command - Send forge operations queued while offline
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::workflow::outbox::{flush_outbox, list_outbox};
+use std::path::Path;
+
+/// Deliver the outbox; fails if anything is left queued
+pub async fn run(cwd: Option<&Path>, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let queued = list_outbox(&ctx.root)?;
+    if queued.is_empty() {
+        tracing::info!("Outbox is empty");
+        return Ok(());
+    }
+    let report = flush_outbox(&ctx).await?;
+    if dry_run {
+        return Ok(());
+    }
+    tracing::info!(
+        "Sent {}, rejected {}, still waiting for the network {}",
+        report.sent,
+        report.failed,
+        report.pending
+    );
+    if report.failed + report.pending > 0 {
+        let left: Vec<String> = list_outbox(&ctx.root)?
+            .into_iter()
+            .map(|(path, entry)| {
+                format!(
+                    "{} ({}): {}",
+                    entry.op.describe(),
+                    path.file_name().unwrap_or_default().to_string_lossy(),
+                    entry.last_error.unwrap_or_default()
+                )
+            })
+            .collect();
+        return Err(WreckitError::wrap(
+            left.join("\n"),
+            format!("{} operation(s) remain in the outbox", left.len()),
+        ));
+    }
+    Ok(())
+}
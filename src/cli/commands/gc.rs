@@ -0,0 +1,56 @@
+//! Gc command - Remove stale logs, runs, temp files, and merged item branches
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::gc::{format_bytes, run_gc};
+use std::path::Path;
+
+/// Collect stale artifacts and report what was reclaimed
+pub async fn run(cwd: Option<&Path>, branches: bool, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let report = run_gc(&ctx, branches).await?;
+    if report.is_empty() {
+        tracing::info!("Nothing to collect");
+        return Ok(());
+    }
+
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    for path in &report.logs_removed {
+        tracing::info!("{} old transcript {}", verb, path.display());
+    }
+    for path in &report.runs_removed {
+        tracing::info!("{} old run {}", verb, path.display());
+    }
+    for path in &report.logs_truncated {
+        let verb = if dry_run {
+            "Would truncate"
+        } else {
+            "Truncated"
+        };
+        tracing::info!("{} {}", verb, path.display());
+    }
+    for path in &report.temp_files_removed {
+        tracing::info!("{} orphaned temp file {}", verb, path.display());
+    }
+    for branch in &report.branches_deleted {
+        tracing::info!("{} merged branch {}", verb, branch);
+    }
+    for branch in &report.remote_branches_deleted {
+        tracing::info!("{} branch {} on origin", verb, branch);
+    }
+    tracing::info!(
+        "{} {}",
+        if dry_run {
+            "Would reclaim"
+        } else {
+            "Reclaimed"
+        },
+        format_bytes(report.bytes_reclaimed)
+    );
+    Ok(())
+}
@@ -0,0 +1,19 @@
+//! Issue command - Create an item from a GitHub issue
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::import_issue;
+use std::path::Path;
+
+/// Create an idea from the given issue
+pub async fn run(cwd: Option<&Path>, number: u32, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let item = import_issue(&ctx, number).await?;
+    tracing::info!("Created {} from issue #{}", item.id, number);
+    Ok(())
+}
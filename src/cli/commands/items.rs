@@ -0,0 +1,23 @@
+//! Items command - Inspect the backlog as a whole
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::domain::{render_graph, GraphFormat};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use std::path::Path;
+
+/// Print the item dependency graph as DOT or Mermaid
+pub async fn graph(cwd: Option<&Path>, format: &str) -> Result<()> {
+    let format: GraphFormat = format
+        .parse()
+        .map_err(|e: String| WreckitError::wrap(e, "Invalid graph format"))?;
+    let options = SessionOptions {
+        force: false,
+        dry_run: false,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let items = fs::list_items(&ctx.root)?;
+    print!("{}", render_graph(&items, format));
+    Ok(())
+}
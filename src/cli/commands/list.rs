@@ -1,9 +1,104 @@
 //! List command - List items with optional filtering
 
-use crate::errors::Result;
+use crate::cli::session::{open_context, SessionOptions};
+use crate::domain::{
+    render_table, sort_items, table_rows, Column, SortKey, TableFormat, DEFAULT_COLUMNS,
+};
+use crate::errors::{Result, WreckitError};
+use crate::fs::{self, ItemQuery};
+use crate::schemas::Item;
+use crate::tui;
+use chrono::Utc;
 use std::path::Path;
 
+/// Columns, sort order, and format of an item table, from the command line
+pub(crate) struct TableOptions {
+    pub columns: Vec<Column>,
+    pub sort: Option<SortKey>,
+    pub format: TableFormat,
+}
+
+impl TableOptions {
+    /// Parse `--columns`, `--sort`, and `--format`
+    ///
+    /// # Errors
+    /// Returns an error if a column, sort key, or format is unknown
+    pub fn parse(columns: Option<&str>, sort: Option<&str>, format: &str) -> Result<Self> {
+        let invalid = |e: String| WreckitError::wrap(e, "Invalid table option");
+        Ok(TableOptions {
+            columns: match columns {
+                Some(list) => Column::parse_list(list).map_err(invalid)?,
+                None => DEFAULT_COLUMNS.to_vec(),
+            },
+            sort: sort.map(str::parse).transpose().map_err(invalid)?,
+            format: format.parse().map_err(invalid)?,
+        })
+    }
+
+    /// Sort the items and let the operator pick one from the table,
+    /// returning its ID
+    ///
+    /// # Errors
+    /// * `Interrupted` - If the operator cancels
+    /// * `Io` - If there is no terminal to pick in
+    pub fn pick(&self, items: &mut [Item]) -> Result<String> {
+        if let Some(key) = self.sort {
+            sort_items(items, key);
+        }
+        // One row per item, so the picked index is the item's index
+        let (header, rows) = table_rows(items, &self.columns, Utc::now());
+        let index = tui::pick(&rows, &header)?;
+        Ok(items[index].id.clone())
+    }
+
+    /// Sort the items and print them as a table
+    pub fn print(&self, items: &mut [Item]) {
+        if let Some(key) = self.sort {
+            sort_items(items, key);
+        }
+        print!(
+            "{}",
+            render_table(items, &self.columns, self.format, Utc::now())
+        );
+    }
+}
+
 /// List items with optional filtering
-pub async fn run(_cwd: Option<&Path>, _json: bool, _state: Option<&str>) -> Result<()> {
-    todo!("Implement list command")
+pub async fn run(
+    cwd: Option<&Path>,
+    json: bool,
+    state: Option<&str>,
+    columns: Option<&str>,
+    sort: Option<&str>,
+    format: &str,
+    pick: bool,
+) -> Result<()> {
+    let table = TableOptions::parse(columns, sort, format)?;
+    let options = SessionOptions {
+        force: false,
+        dry_run: false,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let mut items = fs::query_items(&ctx.root, &ItemQuery::new())?;
+    if let Some(state) = state {
+        items.retain(|item| item.state_name() == state);
+    }
+
+    if json {
+        let text = serde_json::to_string_pretty(&items)
+            .map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+        println!("{}", text);
+        return Ok(());
+    }
+    if items.is_empty() {
+        tracing::info!("No items");
+        return Ok(());
+    }
+    if pick {
+        println!("{}", table.pick(&mut items)?);
+        return Ok(());
+    }
+    table.print(&mut items);
+    Ok(())
 }
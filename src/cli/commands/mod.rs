@@ -1,15 +1,45 @@
 //! CLI command implementations
 
+pub mod abandon;
+pub mod advance;
+pub mod answer;
+pub mod assign;
+pub mod attach;
+pub mod auth;
+pub mod bench;
+pub mod block;
+pub mod bulk;
 pub mod complete;
+pub mod context;
 pub mod doctor;
+pub mod external;
+pub mod flush;
+pub mod gc;
 pub mod ideas;
 pub mod implement;
 pub mod init;
+pub mod issue;
+pub mod items;
 pub mod list;
 pub mod next;
+pub mod note;
+pub mod open;
+pub mod pick;
 pub mod plan;
 pub mod pr;
+pub mod preset;
+pub mod prompt;
+pub mod recur;
+pub mod reopen;
+pub mod replay;
 pub mod research;
+pub mod restore;
+pub mod retry;
 pub mod run;
+pub mod runs;
 pub mod show;
+pub mod stats;
 pub mod status;
+pub mod sync_meta;
+pub mod tui;
+pub mod watch;
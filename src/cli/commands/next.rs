@@ -1,9 +1,46 @@
 //! Next command - Find and run the next incomplete item
 
+use crate::cli::session::{open_context, run_with_renderer, SessionOptions};
 use crate::errors::Result;
+use crate::fs;
+use crate::workflow::{
+    find_next_item_for, refresh_blocks, resolve_identity, simulate_item, Orchestrator,
+};
 use std::path::Path;
 
-/// Find and run the next incomplete item
-pub async fn run(_cwd: Option<&Path>, _dry_run: bool) -> Result<()> {
-    todo!("Implement next command")
+/// Find and run the next incomplete item, optionally only among your own
+pub async fn run(cwd: Option<&Path>, mine: bool, dry_run: bool, no_tui: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui,
+    };
+    let ctx = open_context(cwd, options)?;
+    let assignee = if mine {
+        Some(resolve_identity(&ctx).await?)
+    } else {
+        None
+    };
+    if dry_run {
+        let items = fs::query_items(&ctx.root, &fs::ItemQuery::new())?;
+        let items = refresh_blocks(&ctx, items)?;
+        match find_next_item_for(&items, assignee.as_deref()) {
+            Some(item) => print!("{}", simulate_item(&ctx, item)?),
+            None => tracing::info!("No items need work"),
+        }
+        return Ok(());
+    }
+
+    let item = run_with_renderer(ctx, no_tui, |ctx| async move {
+        Orchestrator::new(ctx)
+            .run_next_for(assignee.as_deref())
+            .await
+    })
+    .await?;
+
+    match item {
+        Some(item) => tracing::info!("{} is {}", item.id, item.state),
+        None => tracing::info!("No items need work"),
+    }
+    Ok(())
 }
@@ -0,0 +1,21 @@
+//! Note command - Steer an item with guidance included in its prompts
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::add_note;
+use std::path::Path;
+
+/// Append a note to an item's notes.md
+pub async fn run(cwd: Option<&Path>, id: &str, text: &str, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    add_note(&ctx, id, text)?;
+    if !dry_run {
+        tracing::info!("Noted for {}; later prompts will include it", id);
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! Open command - Jump from an item to its PR, directory, branch, or plan
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git;
+use std::path::Path;
+use std::process::Command;
+
+/// What `wreckit open` opens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenTarget {
+    /// The item's PR, in the browser
+    Pr,
+    /// The item directory (printed)
+    Dir,
+    /// The item branch (checked out)
+    Branch,
+    /// plan.md, in the editor
+    Plan,
+}
+
+impl OpenTarget {
+    /// The target for the command-line flags; the directory if none is set
+    pub fn from_flags(pr: bool, branch: bool, plan: bool) -> Self {
+        if pr {
+            OpenTarget::Pr
+        } else if branch {
+            OpenTarget::Branch
+        } else if plan {
+            OpenTarget::Plan
+        } else {
+            OpenTarget::Dir
+        }
+    }
+}
+
+/// Open an item's PR, directory, branch, or plan
+pub async fn run(cwd: Option<&Path>, id: &str, target: OpenTarget, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let item = fs::read_item(&ctx.root, id)?;
+
+    match target {
+        OpenTarget::Dir => {
+            println!("{}", fs::get_item_dir(&ctx.root, id).display());
+        }
+        OpenTarget::Pr => {
+            let number = item
+                .pr_number
+                .ok_or_else(|| WreckitError::StateTransition(format!("{} has no PR yet", id)))?;
+            if dry_run {
+                tracing::info!("[DRY RUN] Would open PR #{} in the browser", number);
+                return Ok(());
+            }
+            git::run_gh_command(
+                &["pr", "view", &number.to_string(), "--web"],
+                &ctx.git_options(),
+            )
+            .await?;
+        }
+        OpenTarget::Branch => {
+            let branch = ctx.branch_name(&item);
+            let options = ctx.git_options();
+            if !git::branch_exists(&branch, &options).await {
+                return Err(WreckitError::GitError(format!(
+                    "{} has no branch yet ({} does not exist)",
+                    id, branch
+                )));
+            }
+            if dry_run {
+                tracing::info!("[DRY RUN] Would check out {}", branch);
+                return Ok(());
+            }
+            git::run_git_command(&["checkout", &branch], &options).await?;
+            tracing::info!("Checked out {}", branch);
+        }
+        OpenTarget::Plan => {
+            let path = fs::get_plan_path(&ctx.root, id);
+            if !path.exists() {
+                return Err(WreckitError::FileNotFound(format!(
+                    "{} has no plan yet ({})",
+                    id,
+                    path.display()
+                )));
+            }
+            let editor = std::env::var("VISUAL")
+                .or_else(|_| std::env::var("EDITOR"))
+                .unwrap_or_else(|_| "vi".to_string());
+            if dry_run {
+                tracing::info!("[DRY RUN] Would run {} {}", editor, path.display());
+                return Ok(());
+            }
+            // The editor may carry its own flags (e.g. "code --wait")
+            let status = Command::new("sh")
+                .args(["-c", &format!("{} \"$1\"", editor), "sh"])
+                .arg(&path)
+                .status()?;
+            if !status.success() {
+                return Err(WreckitError::ConfigError(format!(
+                    "{} exited with {}",
+                    editor, status
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Item;
+
+    #[test]
+    fn test_open_target_from_flags() {
+        assert_eq!(OpenTarget::from_flags(false, false, false), OpenTarget::Dir);
+        assert_eq!(OpenTarget::from_flags(true, true, true), OpenTarget::Pr);
+        assert_eq!(
+            OpenTarget::from_flags(false, true, true),
+            OpenTarget::Branch
+        );
+        assert_eq!(OpenTarget::from_flags(false, false, true), OpenTarget::Plan);
+    }
+
+    #[tokio::test]
+    async fn test_open_reports_missing_artifacts() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(fs::get_wreckit_dir(root)).unwrap();
+        let item = Item::new("001-a".to_string(), "A".to_string(), String::new());
+        fs::write_item(root, "001-a", &item).unwrap();
+
+        let open = |target| run(Some(root), "001-a", target, true);
+        assert!(open(OpenTarget::Dir).await.is_ok());
+        let err = open(OpenTarget::Pr).await.unwrap_err();
+        assert!(matches!(err, WreckitError::StateTransition(_)));
+        let err = open(OpenTarget::Plan).await.unwrap_err();
+        assert!(matches!(err, WreckitError::FileNotFound(_)));
+
+        let mut item = item;
+        item.pr_number = Some(7);
+        fs::write_item(root, "001-a", &item).unwrap();
+        std::fs::write(fs::get_plan_path(root, "001-a"), "# Plan\n").unwrap();
+        assert!(open(OpenTarget::Pr).await.is_ok());
+        assert!(open(OpenTarget::Plan).await.is_ok());
+    }
+}
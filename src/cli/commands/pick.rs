@@ -0,0 +1,10 @@
+//! Pick command - Choose an item interactively and print its ID
+
+use crate::errors::Result;
+use std::path::Path;
+
+/// Choose an item with the fuzzy finder and print its ID, for use as
+/// `wreckit show $(wreckit pick)`
+pub async fn run(cwd: Option<&Path>, state: Option<&str>) -> Result<()> {
+    super::list::run(cwd, false, state, None, None, "table", true).await
+}
@@ -1,9 +1,22 @@
 //! Plan command - Run the planning phase for an item
 
+use crate::cli::session::{run_single_phase, SessionOptions};
 use crate::errors::Result;
+use crate::workflow::PhaseKind;
 use std::path::Path;
 
 /// Run the planning phase for an item
-pub async fn run(_cwd: Option<&Path>, _id: &str, _force: bool, _dry_run: bool) -> Result<()> {
-    todo!("Implement plan command")
+pub async fn run(
+    cwd: Option<&Path>,
+    id: &str,
+    force: bool,
+    dry_run: bool,
+    no_tui: bool,
+) -> Result<()> {
+    let options = SessionOptions {
+        force,
+        dry_run,
+        no_tui,
+    };
+    run_single_phase(cwd, id, PhaseKind::Plan, options).await
 }
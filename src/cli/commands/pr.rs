@@ -1,9 +1,22 @@
 //! PR command - Create or update the pull request for an item
 
+use crate::cli::session::{run_single_phase, SessionOptions};
 use crate::errors::Result;
+use crate::workflow::PhaseKind;
 use std::path::Path;
 
 /// Create or update the pull request for an item
-pub async fn run(_cwd: Option<&Path>, _id: &str, _force: bool, _dry_run: bool) -> Result<()> {
-    todo!("Implement pr command")
+pub async fn run(
+    cwd: Option<&Path>,
+    id: &str,
+    force: bool,
+    dry_run: bool,
+    no_tui: bool,
+) -> Result<()> {
+    let options = SessionOptions {
+        force,
+        dry_run,
+        no_tui,
+    };
+    run_single_phase(cwd, id, PhaseKind::Pr, options).await
 }
@@ -0,0 +1,34 @@
+//! Preset command - Create an item from a built-in preset
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::schemas::Preset;
+use crate::workflow::create_preset_item;
+use std::path::Path;
+
+/// Create a planned item from the named preset
+pub async fn run(cwd: Option<&Path>, name: &str, dry_run: bool) -> Result<()> {
+    let preset: Preset = name
+        .parse()
+        .map_err(|e: String| WreckitError::wrap(e, "Invalid preset"))?;
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let item = match create_preset_item(&ctx, preset).await? {
+        Some(item) => item,
+        None => {
+            tracing::info!("{} found nothing to do", preset);
+            return Ok(());
+        }
+    };
+    tracing::info!(
+        "Created {} ({}); run `wreckit run {}` to implement it and open a PR",
+        item.id,
+        item.title,
+        item.id
+    );
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! Prompt command - Render an item's next prompt, or check or update prompt
+//! template golden files
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::fs::{self, get_prompt_snapshots_dir};
+use crate::prompts::snapshot::{
+    compare_snapshots, update_snapshots, SnapshotChange, SnapshotStatus,
+};
+use crate::schemas::Preset;
+use crate::workflow::context_pack::read_context_pack;
+use crate::workflow::implement_loop::story_brief;
+use crate::workflow::PhaseKind;
+use std::path::Path;
+
+/// Print the prompt an item's next phase would send, or with `stats` its
+/// estimated token counts by section against the phase's budget
+pub async fn render(
+    cwd: Option<&Path>,
+    id: &str,
+    template: Option<&str>,
+    stats: bool,
+) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run: true,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let item = fs::read_item(&ctx.root, id)?;
+    let template = match template {
+        Some(template) => template.to_string(),
+        None => match PhaseKind::for_state(item.state) {
+            Some(PhaseKind::Implement) => item
+                .preset
+                .as_deref()
+                .and_then(|name| name.parse::<Preset>().ok())
+                .map_or("implement", Preset::implement_template)
+                .to_string(),
+            Some(kind @ (PhaseKind::Research | PhaseKind::Plan | PhaseKind::Pr)) => {
+                kind.name().to_string()
+            }
+            _ => {
+                return Err(WreckitError::StateTransition(format!(
+                    "{} is {} and has no next prompt; pass --template",
+                    id, item.state
+                )))
+            }
+        },
+    };
+
+    let mut variables = ctx.prompt_variables(&item);
+    if matches!(template.as_str(), "research" | "plan") {
+        variables.repo_context = read_context_pack(&ctx);
+    }
+    if variables.story.is_none() {
+        if let Ok(prd) = fs::read_prd(&ctx.root, id) {
+            variables.story = prd.next_pending_story().map(story_brief);
+        }
+    }
+    let model = ctx.token_model(&template);
+    let mut sections: Vec<(String, usize)> = variables
+        .to_map()
+        .into_iter()
+        .map(|(name, value)| (name, model.count(&value)))
+        .filter(|(_, tokens)| *tokens > 0)
+        .collect();
+    sections.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let (prompt, trims) = ctx.budgeted_prompt(&template, variables)?;
+    if !stats {
+        println!("{}", prompt);
+        return Ok(());
+    }
+
+    let budget = ctx.prompt_budget(&template);
+    println!(
+        "{} prompt for {} ({} tokenizer)",
+        template,
+        id,
+        model.name()
+    );
+    for (name, tokens) in &sections {
+        println!("  {:<24} ~{} tokens", name, tokens);
+    }
+    for trim in &trims {
+        println!(
+            "  trimmed {} (~{} to ~{} tokens)",
+            trim.section, trim.tokens_before, trim.tokens_after
+        );
+    }
+    let total = model.count(&prompt);
+    match budget {
+        0 => println!("Total: ~{} tokens (no budget)", total),
+        budget => println!("Total: ~{} of {} tokens", total, budget),
+    }
+    Ok(())
+}
+
+fn describe(change: &SnapshotChange) -> String {
+    match change.status {
+        SnapshotStatus::Unchanged => format!("unchanged {}", change.name),
+        SnapshotStatus::Changed => match change.first_difference {
+            Some(line) => format!("changed   {} (from line {})", change.name, line),
+            None => format!("changed   {}", change.name),
+        },
+        SnapshotStatus::Added => format!("added     {}", change.name),
+        SnapshotStatus::Removed => format!("removed   {}", change.name),
+    }
+}
+
+/// Compare rendered templates with their golden files, or rewrite them with `update`
+pub async fn snapshot(cwd: Option<&Path>, update: bool, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let dir = get_prompt_snapshots_dir(&ctx.root);
+
+    if update && !dry_run {
+        let changes = update_snapshots(&ctx.root)?;
+        for change in &changes {
+            tracing::info!("{}", describe(change));
+        }
+        tracing::info!("{} snapshot(s) updated in {}", changes.len(), dir.display());
+        return Ok(());
+    }
+
+    let changes: Vec<SnapshotChange> = compare_snapshots(&ctx.root)?
+        .into_iter()
+        .filter(|change| change.status != SnapshotStatus::Unchanged)
+        .collect();
+    if changes.is_empty() {
+        tracing::info!("Prompt snapshots in {} are up to date", dir.display());
+        return Ok(());
+    }
+    let report: Vec<String> = changes.iter().map(describe).collect();
+    if update {
+        tracing::info!("[DRY RUN] Would update:\n{}", report.join("\n"));
+        return Ok(());
+    }
+    Err(WreckitError::wrap(
+        report.join("\n"),
+        "Prompt snapshots are out of date (run `wreckit prompt snapshot --update` and review the diff)",
+    ))
+}
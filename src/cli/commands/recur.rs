@@ -0,0 +1,33 @@
+//! Recur command - Create the recurring items that are due
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::create_due_items;
+use std::path::Path;
+
+/// Create an item for every recurring template whose time has come
+pub async fn run(cwd: Option<&Path>, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    if ctx.config.recurring.is_empty() {
+        tracing::warn!("No recurring items are configured in .wreckit/config.json");
+        return Ok(());
+    }
+    let created = create_due_items(&ctx, chrono::Utc::now()).await?;
+    if created.is_empty() {
+        tracing::info!("No recurring items are due");
+    }
+    for item in created {
+        tracing::info!(
+            "Created {} ({}) from {}",
+            item.id,
+            item.title,
+            item.recurrence_of.as_deref().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,19 @@
+//! Reopen command - Create a follow-up item for a done item
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::reopen_item;
+use std::path::Path;
+
+/// Create a follow-up item carrying over the original's context and research
+pub async fn run(cwd: Option<&Path>, id: &str, title: Option<&str>, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let item = reopen_item(&ctx, id, title)?;
+    tracing::info!("Created {} as a follow-up to {}", item.id, id);
+    Ok(())
+}
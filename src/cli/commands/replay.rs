@@ -0,0 +1,49 @@
+//! Replay command - Re-run a phase from recorded agent transcripts
+
+use crate::cli::session::{open_context, run_with_renderer, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::workflow::{load_transcripts, Orchestrator, PhaseKind, ReplaySource};
+use std::path::Path;
+
+/// Re-run a phase for an item, feeding recorded agent outputs instead of calling the agent
+pub async fn run(
+    cwd: Option<&Path>,
+    id: &str,
+    phase: &str,
+    dry_run: bool,
+    no_tui: bool,
+) -> Result<()> {
+    let kind: PhaseKind = phase
+        .parse()
+        .map_err(|e: String| WreckitError::wrap(e, "Invalid --phase"))?;
+
+    let options = SessionOptions {
+        force: true,
+        dry_run,
+        no_tui,
+    };
+    let ctx = open_context(cwd, options)?;
+    let transcripts = load_transcripts(&ctx.root, id, Some(kind.name()))?;
+    if transcripts.is_empty() {
+        return Err(WreckitError::FileNotFound(format!(
+            "No recorded {} transcripts for {}",
+            kind, id
+        )));
+    }
+    tracing::info!(
+        "Replaying {} recorded {} run(s) for {}",
+        transcripts.len(),
+        kind,
+        id
+    );
+
+    let ctx = ctx.with_replay(ReplaySource::new(transcripts));
+    let id = id.to_string();
+    let item = run_with_renderer(ctx, no_tui, move |ctx| async move {
+        Orchestrator::new(ctx).replay_phase(&id, kind).await
+    })
+    .await?;
+
+    tracing::info!("Replay finished; {} is {}", item.id, item.state);
+    Ok(())
+}
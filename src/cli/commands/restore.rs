@@ -0,0 +1,41 @@
+//! Restore command - Roll an item's metadata back to a snapshot
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::fs;
+use std::path::Path;
+
+/// Restore an item from a snapshot, or list its snapshots if none is given
+pub async fn run(cwd: Option<&Path>, id: &str, from: Option<&str>, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    fs::read_item(&ctx.root, id)?;
+
+    let timestamp = match from {
+        Some(timestamp) => timestamp,
+        None => {
+            let snapshots = fs::list_backups(&ctx.root, id)?;
+            if snapshots.is_empty() {
+                tracing::info!("No backups for {}", id);
+            }
+            for snapshot in snapshots {
+                println!("{}", snapshot);
+            }
+            return Ok(());
+        }
+    };
+
+    if dry_run {
+        tracing::info!("[DRY RUN] Would restore {} from {}", id, timestamp);
+        return Ok(());
+    }
+    // Snapshot first so the restore itself can be undone
+    ctx.backup_item(id)?;
+    fs::restore_backup(&ctx.root, id, timestamp)?;
+    tracing::info!("Restored {} from {}", id, timestamp);
+    Ok(())
+}
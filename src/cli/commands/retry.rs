@@ -0,0 +1,63 @@
+//! Retry command - Re-run the phase an item failed in
+
+use crate::cli::session::{open_context, run_with_renderer, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::workflow::{simulate_phase, Orchestrator, PhaseKind};
+use std::path::Path;
+
+/// Clear an item's last error and re-run its failed phase (or `phase`),
+/// optionally rewinding it first and appending operator feedback to the prompt
+pub async fn run(
+    cwd: Option<&Path>,
+    id: &str,
+    phase: Option<&str>,
+    rollback: bool,
+    feedback: Option<&str>,
+    dry_run: bool,
+    no_tui: bool,
+) -> Result<()> {
+    let kind = phase
+        .map(|p| p.parse::<PhaseKind>())
+        .transpose()
+        .map_err(|e| WreckitError::wrap(e, "Invalid --phase"))?;
+
+    let options = SessionOptions {
+        force: true,
+        dry_run,
+        no_tui,
+    };
+    let ctx = open_context(cwd, options)?.with_feedback(feedback.map(String::from));
+    let item = fs::read_item(&ctx.root, id)?;
+    if let Some(ref error) = item.last_error {
+        tracing::info!("{} last failed with: {}", id, error);
+    }
+
+    if dry_run {
+        let orchestrator = Orchestrator::new(ctx);
+        let kind = match kind.or_else(|| orchestrator.next_phase(&item)) {
+            Some(kind) => kind,
+            None => {
+                tracing::info!("{} is {}; there is no phase to retry", id, item.state);
+                return Ok(());
+            }
+        };
+        let item = if rollback {
+            item.with_state(kind.entry_state())
+        } else {
+            item
+        };
+        print!("{}", simulate_phase(orchestrator.context(), &item, kind)?);
+        return Ok(());
+    }
+
+    let id = id.to_string();
+    let item = run_with_renderer(ctx, no_tui, move |ctx| async move {
+        Orchestrator::new(ctx)
+            .retry_phase(&id, kind, rollback)
+            .await
+    })
+    .await?;
+    tracing::info!("Retry finished; {} is {}", item.id, item.state);
+    Ok(())
+}
@@ -1,9 +1,176 @@
 //! Run command - Run an item through all phases until completion
 
-use crate::errors::Result;
+use crate::agent::agent_cache_disabled;
+use crate::cli::session::{open_context, run_with_renderer, SessionOptions};
+use crate::config;
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::workflow::{find_next_item, running_supervisor, simulate_item, supervise, Orchestrator};
 use std::path::Path;
+use std::process::{Command, Stdio};
 
 /// Run an item through all phases until completion
-pub async fn run(_cwd: Option<&Path>, _id: &str, _force: bool, _dry_run: bool) -> Result<()> {
-    todo!("Implement run command")
+pub async fn run(
+    cwd: Option<&Path>,
+    id: &str,
+    force: bool,
+    dry_run: bool,
+    no_tui: bool,
+) -> Result<()> {
+    let options = SessionOptions {
+        force,
+        dry_run,
+        no_tui,
+    };
+    let ctx = open_context(cwd, options)?;
+    if dry_run {
+        let item = fs::read_item(&ctx.root, id)?;
+        print!("{}", simulate_item(&ctx, &item)?);
+        return Ok(());
+    }
+
+    let id = id.to_string();
+    let item = run_with_renderer(ctx, no_tui, move |ctx| async move {
+        Orchestrator::new(ctx).run_item(&id).await
+    })
+    .await?;
+
+    match item.pr_url {
+        Some(ref url) => tracing::info!("{} is {} ({})", item.id, item.state, url),
+        None => tracing::info!("{} is {}", item.id, item.state),
+    }
+    Ok(())
+}
+
+/// Start a supervised run of an item in a background process.
+///
+/// The background run uses the plain renderer, writes its output to
+/// `.wreckit/cache/detached/<id>.log`, streams its updates to
+/// `<id>.events.jsonl` beside it for `wreckit attach`, and is restarted
+/// after a crash up to `supervisor.max_restarts` times. Refuses to start
+/// while another background run of the item, or any run holding the
+/// repository lock, is still going.
+pub async fn detach(cwd: Option<&Path>, id: &str, force: bool, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let item = fs::read_item(&ctx.root, id)?;
+    if dry_run {
+        tracing::info!("Would run {} in the background", item.id);
+        return Ok(());
+    }
+
+    fs::ensure_writable(|| format!("start a background run of {}", item.id))?;
+    if let Some(pid) = running_supervisor(&ctx.root, &item.id) {
+        return Err(WreckitError::Locked(format!(
+            "a background run of {} is still going (pid {}); follow it with `wreckit attach {}`",
+            item.id, pid, item.id
+        )));
+    }
+    if let Some(holder) = fs::lock_holder(&ctx.root) {
+        return Err(WreckitError::Locked(format!(
+            "pid {} is running `{}`; wait for it to finish before starting {}",
+            holder.pid, holder.command, item.id
+        )));
+    }
+
+    let dir = fs::get_detached_dir(&ctx.root);
+    fs::create_dir_all(&dir)?;
+    let events = fs::get_detached_events_path(&ctx.root, &item.id);
+    // A previous run's events must not be mistaken for this one's
+    if events.exists() {
+        fs::remove_file(&events)?;
+    }
+    let log = fs::create_file(&dir.join(format!("{}.log", item.id)))?;
+
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .arg("--cwd")
+        .arg(&ctx.root)
+        .args(["supervise", item.id.as_str()]);
+    if force {
+        command.arg("--force");
+    }
+    if let Some(profile) = config::active_profile() {
+        command.arg("--profile").arg(profile);
+    }
+    if agent_cache_disabled() {
+        command.arg("--no-cache");
+    }
+    command
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    // Its own process group keeps Ctrl-C in the starting terminal from reaching it
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let child = command.spawn()?;
+    fs::write_file(
+        &fs::get_detached_pid_path(&ctx.root, &item.id),
+        child.id().to_string(),
+    )?;
+
+    tracing::info!(
+        "Running {} in the background (pid {}); follow it with `wreckit attach {}`",
+        item.id,
+        child.id(),
+        item.id
+    );
+    Ok(())
+}
+
+/// Run an item under a supervisor that restarts it after crashes (the
+/// background half of `wreckit run --detach`)
+pub async fn supervise_run(cwd: Option<&Path>, id: &str, force: bool) -> Result<()> {
+    let options = SessionOptions {
+        force,
+        dry_run: false,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let events = fs::get_detached_events_path(&ctx.root, id);
+    supervise(&ctx, id, force, &events).await
+}
+
+/// Run every item that has work to do
+pub async fn run_all(cwd: Option<&Path>, force: bool, dry_run: bool, no_tui: bool) -> Result<()> {
+    let options = SessionOptions {
+        force,
+        dry_run,
+        no_tui,
+    };
+    let ctx = open_context(cwd, options)?;
+    if dry_run {
+        let items = fs::list_items(&ctx.root)?;
+        let pending: Vec<_> = items
+            .iter()
+            .filter(|item| find_next_item(std::slice::from_ref(*item)).is_some())
+            .collect();
+        if pending.is_empty() {
+            tracing::info!("No items need work");
+        }
+        for item in pending {
+            print!("{}", simulate_item(&ctx, item)?);
+        }
+        return Ok(());
+    }
+
+    let items = run_with_renderer(ctx, no_tui, |ctx| async move {
+        Orchestrator::new(ctx).run_all().await
+    })
+    .await?;
+
+    if items.is_empty() {
+        tracing::info!("No items need work");
+    }
+    for item in items {
+        tracing::info!("{} is {}", item.id, item.state);
+    }
+    Ok(())
 }
@@ -0,0 +1,116 @@
+//! Runs command - Browse the event streams recorded for each run
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::domain::parse_timestamp;
+use crate::errors::Result;
+use crate::tui::AgentEvent;
+use crate::workflow::{find_run, list_runs, read_run, RunEntry, RunRecord, WorkflowEvent};
+use chrono::Local;
+use std::path::Path;
+
+fn options() -> SessionOptions {
+    SessionOptions {
+        force: false,
+        dry_run: false,
+        no_tui: true,
+    }
+}
+
+/// Local wall-clock time of a record
+fn clock(record: &RunRecord) -> String {
+    parse_timestamp(&record.at)
+        .map(|at| at.with_timezone(&Local).format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| record.at.clone())
+}
+
+/// One-line description of a record's event
+fn describe(entry: &RunEntry) -> String {
+    match entry {
+        RunEntry::Workflow(event) => match event {
+            WorkflowEvent::ItemCreated { state, .. } => format!("created in {}", state),
+            WorkflowEvent::StateChanged { from, to, .. } => format!("{} -> {}", from, to),
+            WorkflowEvent::PhaseStarted { phase, .. } => format!("{} started", phase),
+            WorkflowEvent::PhaseFinished {
+                phase,
+                success,
+                seconds,
+                ..
+            } => format!(
+                "{} {} after {}s",
+                phase,
+                if *success { "finished" } else { "failed" },
+                seconds
+            ),
+            WorkflowEvent::PrOpened { number, url, .. } => format!("opened PR #{} {}", number, url),
+            WorkflowEvent::Error { message, code, .. } => format!("error [{}]: {}", code, message),
+        },
+        RunEntry::Agent { event, .. } => match event {
+            AgentEvent::AssistantText { text } => format!("agent: {}", text.trim()),
+            AgentEvent::ToolStarted { tool_name, .. } => format!("tool {}", tool_name),
+            AgentEvent::ToolResult { tool_use_id, .. } => format!("tool {} done", tool_use_id),
+            AgentEvent::ToolError { tool_use_id, error } => {
+                format!("tool {} failed: {}", tool_use_id, error)
+            }
+            AgentEvent::Error { message } => format!("agent error: {}", message),
+            AgentEvent::RunResult => "agent finished".to_string(),
+        },
+    }
+}
+
+/// List recorded runs with their event counts and items
+pub async fn list(cwd: Option<&Path>) -> Result<()> {
+    let ctx = open_context(cwd, options())?;
+    let runs = list_runs(&ctx.root)?;
+    if runs.is_empty() {
+        tracing::info!("No runs recorded");
+        return Ok(());
+    }
+    for path in runs {
+        let records = read_run(&path)?;
+        let mut items: Vec<&str> = records.iter().map(RunRecord::item_id).collect();
+        items.sort_unstable();
+        items.dedup();
+        let name = path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        println!(
+            "{:<22}  {:>6} events  {}",
+            name,
+            records.len(),
+            items.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Print the events of a run (the latest if `run` is None), optionally only
+/// those about one item
+pub async fn show(
+    cwd: Option<&Path>,
+    run: Option<&str>,
+    item: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let ctx = open_context(cwd, options())?;
+    let path = find_run(&ctx.root, run)?;
+    let records = read_run(&path)?;
+    for record in records
+        .iter()
+        .filter(|record| item.is_none_or(|id| record.item_id() == id))
+    {
+        if json {
+            if let Ok(line) = serde_json::to_string(record) {
+                println!("{}", line);
+            }
+            continue;
+        }
+        println!(
+            "{}  {:<24} {}",
+            clock(record),
+            record.item_id(),
+            describe(&record.entry)
+        );
+    }
+    Ok(())
+}
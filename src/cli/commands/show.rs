@@ -1,9 +1,82 @@
 //! Show command - Show details of a specific item
 
-use crate::errors::Result;
+use crate::cli::session::{open_context, SessionOptions};
+use crate::domain::{describe_timestamp, parse_timestamp, relative_time, time_in_state, DateStyle};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::Item;
+use chrono::{DateTime, Utc};
 use std::path::Path;
 
+fn print_item(item: &Item, now: DateTime<Utc>, style: DateStyle) {
+    println!("{}  {}", item.id, item.title);
+    let state = time_in_state(item, now).unwrap_or_else(|| item.state_name());
+    println!("  state:    {}", state);
+    println!(
+        "  created:  {}",
+        describe_timestamp(&item.created_at, now, style)
+    );
+    println!(
+        "  updated:  {}",
+        describe_timestamp(&item.updated_at, now, style)
+    );
+    if let Some(ref branch) = item.branch {
+        println!("  branch:   {}", branch);
+    }
+    if let Some(ref url) = item.pr_url {
+        println!("  pr:       {}", url);
+    }
+    if !item.tags.is_empty() {
+        println!("  tags:     {}", item.tags.join(", "));
+    }
+    if let Some(ref blocker) = item.blocked {
+        let since = parse_timestamp(&blocker.blocked_at)
+            .map(|at| format!(" ({})", relative_time(at, now)))
+            .unwrap_or_default();
+        println!("  blocked:  {}{}", blocker.reason, since);
+    }
+    if let Some(ref question) = item.question {
+        let asked = parse_timestamp(&question.asked_at)
+            .map(|at| format!(", asked {}", relative_time(at, now)))
+            .unwrap_or_default();
+        println!(
+            "  question: [{}{}] {}",
+            question.phase, asked, question.text
+        );
+    }
+    if let Some(ref error) = item.last_error {
+        println!("  error:    {}", error);
+    }
+    if !item.state_history.is_empty() {
+        println!("  history:");
+        for change in &item.state_history {
+            println!(
+                "    {:<14} {}",
+                change.state,
+                describe_timestamp(&change.at, now, style)
+            );
+        }
+    }
+    if !item.overview.trim().is_empty() {
+        println!("\n{}", item.overview.trim());
+    }
+}
+
 /// Show details of a specific item
-pub async fn run(_cwd: Option<&Path>, _id: &str, _json: bool) -> Result<()> {
-    todo!("Implement show command")
+pub async fn run(cwd: Option<&Path>, id: &str, json: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run: false,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let item = fs::read_item(&ctx.root, id)?;
+    if json {
+        let text = serde_json::to_string_pretty(&item)
+            .map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+        println!("{}", text);
+        return Ok(());
+    }
+    print_item(&item, Utc::now(), DateStyle::detect());
+    Ok(())
 }
@@ -0,0 +1,30 @@
+//! Stats command - Show local phase and agent usage statistics
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::workflow::stats::{format_report, read_stats};
+use std::path::Path;
+
+/// Render `.wreckit/stats.json`
+pub async fn run(cwd: Option<&Path>, json: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run: false,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let stats = read_stats(&ctx.root)?;
+
+    if json {
+        let text = serde_json::to_string_pretty(&stats)
+            .map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+        println!("{}", text);
+        return Ok(());
+    }
+    if stats.phases.is_empty() {
+        tracing::info!("No statistics recorded yet");
+        return Ok(());
+    }
+    println!("{}", format_report(&stats));
+    Ok(())
+}
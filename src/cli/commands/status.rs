@@ -1,9 +1,120 @@
 //! Status command - Show status of all items
 
-use crate::errors::Result;
+use super::list::TableOptions;
+use crate::cli::session::{open_context, SessionOptions};
+use crate::domain::{
+    backlog_stats, format_duration, parse_timestamp, relative_time, BacklogStats, TableFormat,
+};
+use crate::errors::{Result, WreckitError};
+use crate::fs::{self, ItemQuery};
+use crate::schemas::Item;
+use chrono::{DateTime, Utc};
 use std::path::Path;
 
+fn print_questions(items: &[Item], now: DateTime<Utc>) {
+    let questions: Vec<_> = items
+        .iter()
+        .filter_map(|item| item.question.as_ref().map(|q| (&item.id, q)))
+        .collect();
+    if questions.is_empty() {
+        return;
+    }
+    println!("\nAwaiting input (answer with `wreckit answer <id> \"...\"`):");
+    for (id, question) in questions {
+        let asked = parse_timestamp(&question.asked_at)
+            .map(|at| format!(", {}", relative_time(at, now)))
+            .unwrap_or_default();
+        println!(
+            "  {:<24} [{}{}] {}",
+            id, question.phase, asked, question.text
+        );
+    }
+}
+
+fn print_stats(stats: &BacklogStats) {
+    let counts: Vec<String> = stats
+        .by_state
+        .iter()
+        .map(|c| format!("{} {}", c.state, c.count))
+        .collect();
+    println!("\n{} items: {}", stats.total, counts.join(", "));
+
+    if !stats.phase_durations.is_empty() {
+        println!("\nAverage time in state:");
+        for phase in &stats.phase_durations {
+            println!(
+                "  {:<14} {:>8}  ({} samples)",
+                phase.state,
+                format_duration(phase.average_seconds),
+                phase.samples
+            );
+        }
+    }
+
+    if let Some(ref oldest) = stats.oldest_in_flight {
+        println!(
+            "\nOldest in flight: {} ({}, {} old)",
+            oldest.id,
+            oldest.state,
+            format_duration(oldest.age_seconds)
+        );
+    }
+
+    if !stats.awaiting_review.is_empty() {
+        println!("\nPRs awaiting review:");
+        for pr in &stats.awaiting_review {
+            println!(
+                "  {:<24} waiting {:>8}  {}",
+                pr.id,
+                format_duration(pr.age_seconds),
+                pr.pr_url.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
+    let weeks: Vec<String> = stats
+        .weekly_completions
+        .iter()
+        .map(|w| format!("{}: {}", w.week_start, w.completed))
+        .collect();
+    println!("\nCompleted per week: {}", weeks.join(", "));
+}
+
 /// Show status of all items
-pub async fn run(_cwd: Option<&Path>, _json: bool) -> Result<()> {
-    todo!("Implement status command")
+pub async fn run(
+    cwd: Option<&Path>,
+    json: bool,
+    columns: Option<&str>,
+    sort: Option<&str>,
+    format: &str,
+) -> Result<()> {
+    let table = TableOptions::parse(columns, sort, format)?;
+    let options = SessionOptions {
+        force: false,
+        dry_run: false,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let mut items = fs::query_items(&ctx.root, &ItemQuery::new())?;
+    let now = Utc::now();
+    let stats = backlog_stats(&items, now);
+
+    if json {
+        let report = serde_json::json!({ "items": items, "stats": stats });
+        let text = serde_json::to_string_pretty(&report)
+            .map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+        println!("{}", text);
+        return Ok(());
+    }
+    if items.is_empty() {
+        tracing::info!("No items");
+        return Ok(());
+    }
+    table.print(&mut items);
+    if table.format == TableFormat::Tsv {
+        return Ok(());
+    }
+    print_questions(&items, now);
+    print_stats(&stats);
+    Ok(())
 }
@@ -0,0 +1,19 @@
+//! Sync-meta command - Merge .wreckit metadata with the shared metadata branch
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::Result;
+use crate::workflow::sync_metadata;
+use std::path::Path;
+
+/// Commit local metadata, merge origin's metadata branch, and push the result
+pub async fn run(cwd: Option<&Path>, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    sync_metadata(&ctx).await?;
+    tracing::info!("Metadata synced with {}", ctx.config.meta.branch);
+    Ok(())
+}
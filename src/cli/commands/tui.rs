@@ -0,0 +1,79 @@
+//! TUI command - Reopen the view of the last workflow session
+
+use crate::cli::session::{open_context, tui_options, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::tui::{load_session, RenderMode, SessionSnapshot, TuiRunner, TuiUpdate};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// How often an attached TUI checks the session file for changes
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn ended_note(snapshot: &SessionSnapshot) -> String {
+    format!(
+        "[wreckit] Session ended; showing its state as of {}",
+        snapshot.saved_at.format("%Y-%m-%d %H:%M:%S UTC")
+    )
+}
+
+/// Show the saved session, following it while the workflow keeps running
+pub async fn run(cwd: Option<&Path>) -> Result<()> {
+    let options = SessionOptions {
+        force: false,
+        dry_run: false,
+        no_tui: true,
+    };
+    let ctx = open_context(cwd, options)?;
+    let path = fs::get_tui_session_path(&ctx.root);
+    let snapshot = load_session(&path)?.ok_or_else(|| {
+        WreckitError::FileNotFound(format!(
+            "No saved TUI session at {}; start one with wreckit run",
+            path.display()
+        ))
+    })?;
+
+    if RenderMode::detect(false) == RenderMode::Plain {
+        for line in &snapshot.state.logs {
+            println!("{}", line);
+        }
+        if snapshot.finished {
+            println!("{}", ended_note(&snapshot));
+        }
+        return Ok(());
+    }
+
+    let mut runner = TuiRunner::new(Vec::new(), tui_options(&ctx)?).await;
+    let updates = runner.create_update_sender();
+    let _ = updates.send(TuiUpdate::Restore(Box::new(snapshot.state.clone())));
+
+    let follow = tokio::spawn(async move {
+        let mut snapshot = snapshot;
+        let mut seen = modified(&path);
+        while !snapshot.finished {
+            tokio::time::sleep(FOLLOW_INTERVAL).await;
+            let current = modified(&path);
+            if current == seen {
+                continue;
+            }
+            seen = current;
+            match load_session(&path) {
+                Ok(Some(latest)) => {
+                    let _ = updates.send(TuiUpdate::Restore(Box::new(latest.state.clone())));
+                    snapshot = latest;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::debug!("Cannot reload TUI session: {}", e),
+            }
+        }
+        let _ = updates.send(TuiUpdate::AppendLogs(vec![ended_note(&snapshot)]));
+    });
+
+    let result = runner.run().await;
+    follow.abort();
+    result
+}
@@ -0,0 +1,104 @@
+//! Watch command - Act on slash commands posted on wreckit's PRs, keep their
+//! branches current with the base branch, complete items whose PRs
+//! auto-merged, and create recurring items as they fall due
+
+use crate::cli::session::{open_context, SessionOptions};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::tui::{cancel_on_interrupt, control_channel};
+use crate::workflow::{
+    complete_merged_items, create_due_items, poll_pr_comments, refresh_stale_prs, WorkflowContext,
+};
+use std::path::Path;
+use std::time::Duration;
+
+/// Poll in_pr items' PRs until interrupted
+pub async fn run(cwd: Option<&Path>, once: bool, dry_run: bool) -> Result<()> {
+    let options = SessionOptions {
+        force: true,
+        dry_run,
+        no_tui: true,
+    };
+    // SIGINT cancels the in-flight gh poll and the wait between polls
+    let (control, handle) = control_channel();
+    let ctx = open_context(cwd, options)?.with_control(handle);
+    let _interrupts = cancel_on_interrupt(control);
+    let watching = Watching {
+        comments: ctx.config.pr_bot.enabled,
+        merges: ctx.config.pr.auto_merge.method().is_some(),
+        refresh: ctx.config.pr.refresh_on_base_update,
+        recurring: !ctx.config.recurring.is_empty(),
+    };
+    if !watching.any() {
+        tracing::warn!(
+            "Nothing to watch; set pr_bot.enabled, pr.auto_merge, pr.refresh_on_base_update, or recurring in .wreckit/config.json"
+        );
+        return Ok(());
+    }
+    loop {
+        // Polls check out, rebase and re-run the agent in the shared tree, so
+        // each one holds the repository lock; it is released while waiting
+        let lock = if ctx.dry_run {
+            Ok(None)
+        } else {
+            fs::acquire_lock(&ctx.root).map(Some)
+        };
+        match lock {
+            Ok(_lock) => watching.poll(&ctx).await?,
+            Err(WreckitError::Locked(holder)) => {
+                tracing::warn!("Skipping this poll; the repository is locked: {}", holder);
+            }
+            Err(e) => return Err(e),
+        }
+        if once {
+            return Ok(());
+        }
+        ctx.wait(Duration::from_secs(ctx.config.pr_bot.poll_seconds))
+            .await?;
+    }
+}
+
+/// What `wreckit watch` acts on, from the config
+struct Watching {
+    comments: bool,
+    merges: bool,
+    refresh: bool,
+    recurring: bool,
+}
+
+impl Watching {
+    fn any(&self) -> bool {
+        self.comments || self.merges || self.refresh || self.recurring
+    }
+
+    /// One pass over PR comments, stale PRs, merges and recurring items
+    async fn poll(&self, ctx: &WorkflowContext) -> Result<()> {
+        if self.comments {
+            let handled = poll_pr_comments(ctx).await?;
+            if handled > 0 {
+                tracing::info!("Handled {} PR command(s)", handled);
+            }
+        }
+        if self.refresh {
+            for (id, outcome) in refresh_stale_prs(ctx).await? {
+                tracing::info!(
+                    "Refreshed {} onto {}: {:?}",
+                    id,
+                    ctx.config.base_branch,
+                    outcome
+                );
+            }
+        }
+        if self.merges {
+            for item in complete_merged_items(ctx).await? {
+                tracing::info!("{} merged and is done", item.id);
+            }
+        }
+        if self.recurring {
+            for item in create_due_items(ctx, chrono::Utc::now()).await? {
+                tracing::info!("Created recurring item {} ({})", item.id, item.title);
+            }
+        }
+        Ok(())
+    }
+}
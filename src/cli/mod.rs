@@ -3,6 +3,7 @@
 //! Provides the command-line interface using clap.
 
 pub mod commands;
+pub mod session;
 
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
@@ -36,6 +37,26 @@ pub struct Cli {
     /// Override the working directory
     #[arg(long, global = true)]
     pub cwd: Option<PathBuf>,
+
+    /// Config profile to merge over config.json (overrides WRECKIT_PROFILE)
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Refuse every write, git or gh change, and agent run (safe alongside a running loop)
+    #[arg(long, global = true)]
+    pub read_only: bool,
+
+    /// Output format requested of plugins: text or json (passed as WRECKIT_OUTPUT)
+    #[arg(long, global = true, default_value = "text", value_parser = ["text", "json"])]
+    pub output: String,
+
+    /// Run the agent even when a cached response for the same prompt and commit exists
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Remove the repository lock (.wreckit/lock) before running, even if its holder is alive
+    #[arg(long, global = true)]
+    pub force_unlock: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -52,6 +73,25 @@ pub enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Comma-separated columns: id, state, title, pr, age, updated, branch
+        #[arg(long)]
+        columns: Option<String>,
+
+        /// Sort by a column (prefix with - to reverse, e.g. -age)
+        #[arg(long, allow_hyphen_values = true)]
+        sort: Option<String>,
+
+        /// Output format: table or tsv (tab-separated rows without a header)
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+
+    /// Show local phase and agent usage statistics from .wreckit/stats.json
+    Stats {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// List items with optional filtering
@@ -60,7 +100,31 @@ pub enum Commands {
         #[arg(long)]
         json: bool,
 
-        /// Filter by workflow state (idea, researched, planned, implementing, in_pr, done)
+        /// Filter by workflow state (idea, researched, planned, implementing, in_pr, done,
+        /// abandoned, or a custom state)
+        #[arg(long)]
+        state: Option<String>,
+
+        /// Comma-separated columns: id, state, title, pr, age, updated, branch
+        #[arg(long)]
+        columns: Option<String>,
+
+        /// Sort by a column (prefix with - to reverse, e.g. -age)
+        #[arg(long, allow_hyphen_values = true)]
+        sort: Option<String>,
+
+        /// Output format: table or tsv (tab-separated rows without a header)
+        #[arg(long, default_value = "table")]
+        format: String,
+
+        /// Choose an item interactively and print its ID
+        #[arg(long, conflicts_with_all = ["json", "format"])]
+        pick: bool,
+    },
+
+    /// Choose an item interactively with a fuzzy finder and print its ID
+    Pick {
+        /// Only offer items in this workflow state
         #[arg(long)]
         state: Option<String>,
     },
@@ -121,24 +185,317 @@ pub enum Commands {
         id: String,
     },
 
+    /// Stop work on an item: close its PR, delete its branches, mark it abandoned
+    Abandon {
+        /// Item ID
+        id: String,
+
+        /// Reason, posted as the PR comment
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
+    /// Apply one action to every item matching a state and age filter
+    Bulk {
+        /// Only items in this workflow state (built-in or custom)
+        #[arg(long)]
+        state: Option<String>,
+
+        /// Only items not updated for at least this long (e.g. 12h, 14d, 2w)
+        #[arg(long)]
+        older_than: Option<String>,
+
+        /// Action: abandon, retry, or tag:<name>
+        #[arg(long)]
+        action: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Start follow-up work on a done item as a new item
+    Reopen {
+        /// ID of the done item
+        id: String,
+
+        /// Title for the follow-up (defaults to "Follow-up: <title>")
+        #[arg(long)]
+        title: Option<String>,
+    },
+
+    /// Block an item so `next` skips it until it is unblocked
+    Block {
+        /// Item ID
+        id: String,
+
+        /// Why the item is blocked
+        #[arg(long)]
+        reason: String,
+
+        /// Unblock automatically on this date (YYYY-MM-DD)
+        #[arg(long, conflicts_with = "on_item")]
+        until: Option<String>,
+
+        /// Unblock automatically once this item is done
+        #[arg(long)]
+        on_item: Option<String>,
+    },
+
+    /// Lift an item's blocker
+    Unblock {
+        /// Item ID
+        id: String,
+    },
+
+    /// Jump from an item to its PR, directory, branch, or plan (prints the
+    /// item directory by default)
+    Open {
+        /// Item ID
+        id: String,
+
+        /// Open the item's PR in the browser (via gh)
+        #[arg(long, conflicts_with_all = ["dir", "branch", "plan"])]
+        pr: bool,
+
+        /// Print the item directory, e.g. `cd $(wreckit open <id> --dir)`
+        #[arg(long, conflicts_with_all = ["branch", "plan"])]
+        dir: bool,
+
+        /// Check out the item branch
+        #[arg(long, conflicts_with = "plan")]
+        branch: bool,
+
+        /// Open plan.md in $VISUAL or $EDITOR
+        #[arg(long)]
+        plan: bool,
+    },
+
+    /// Add guidance for the agent to an item's notes.md, included in every
+    /// later prompt for the item
+    Note {
+        /// Item ID
+        id: String,
+
+        /// The note
+        text: String,
+    },
+
+    /// Answer the question an item's agent is waiting on; the answer is
+    /// added to its notes and the item can run again
+    Answer {
+        /// Item ID
+        id: String,
+
+        /// The answer
+        text: String,
+    },
+
+    /// Move an item into the next config-defined state (e.g. qa)
+    Advance {
+        /// Item ID
+        id: String,
+
+        /// Expected next state; fails if the item would move elsewhere
+        #[arg(long)]
+        to: Option<String>,
+    },
+
     /// Run an item through all phases until completion
     Run {
         /// Item ID
-        id: String,
+        #[arg(required_unless_present = "all")]
+        id: Option<String>,
+
+        /// Run every item with work to do, backing off when rate limited
+        #[arg(long, conflicts_with = "id")]
+        all: bool,
 
         /// Force re-run of all phases
         #[arg(long)]
         force: bool,
+
+        /// Run in a background process; follow it with `wreckit attach`
+        #[arg(long, conflicts_with = "all")]
+        detach: bool,
+    },
+
+    /// Run an item, restarting it after crashes (started by `run --detach`)
+    #[command(hide = true)]
+    Supervise {
+        /// Item ID
+        id: String,
+
+        /// Force re-run of all phases on the first attempt
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Follow a detached run (the most recent one if no ID is given)
+    Attach {
+        /// Item ID
+        id: Option<String>,
     },
 
     /// Find and run the next incomplete item
-    Next,
+    Next {
+        /// Only pick items assigned to you (config identity or git user.email)
+        #[arg(long)]
+        mine: bool,
+    },
 
-    /// Validate items and optionally fix issues
+    /// Assign an item to yourself
+    Claim {
+        /// Item ID
+        id: String,
+    },
+
+    /// Assign an item to someone (clears the assignee if no user is given)
+    Assign {
+        /// Item ID
+        id: String,
+
+        /// Assignee (e.g. an email address)
+        user: Option<String>,
+    },
+
+    /// Re-run a phase using recorded agent transcripts instead of the agent
+    Replay {
+        /// Item ID
+        id: String,
+
+        /// Phase to replay (research, plan, implement, pr)
+        #[arg(long)]
+        phase: String,
+    },
+
+    /// Clear an item's last error and re-run the phase it failed in
+    Retry {
+        /// Item ID
+        id: String,
+
+        /// Phase to re-run (defaults to the one the item is stuck in)
+        #[arg(long)]
+        phase: Option<String>,
+
+        /// Rewind the item to the phase's entry state first
+        #[arg(long)]
+        rollback: bool,
+
+        /// Guidance appended to the agent prompt
+        #[arg(long)]
+        with_feedback: Option<String>,
+    },
+
+    /// Compare agent setups by running an item's research and plan once per bench variant
+    Bench {
+        /// Item ID
+        id: String,
+
+        /// Variants to run (defaults to every configured variant)
+        #[arg(long = "variant")]
+        variants: Vec<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Restore an item's metadata from a backup (lists backups without --from)
+    Restore {
+        /// Item ID
+        id: String,
+
+        /// Backup timestamp to restore
+        #[arg(long)]
+        from: Option<String>,
+    },
+
+    /// Merge .wreckit metadata with the shared metadata branch on origin
+    SyncMeta,
+
+    /// Prune old transcripts and temp files, trim progress logs, and delete merged item branches
+    Gc {
+        /// Also delete merged item branches on origin and done items' branches everywhere
+        #[arg(long)]
+        branches: bool,
+    },
+
+    /// Create a ready-to-implement item from a built-in preset (dependency_update, flaky_tests)
+    Preset {
+        /// Preset name
+        name: String,
+    },
+
+    /// Create an idea from a GitHub issue; its PR closes the issue when merged
+    Issue {
+        /// Issue number
+        number: u32,
+    },
+
+    /// Create the recurring items from config.json that are due (run from cron)
+    Recur,
+
+    /// Poll open PRs: act on `/wreckit revise` and `/wreckit fix` comments from maintainers,
+    /// rebase branches the base branch has moved past, complete items whose PRs auto-merged,
+    /// and create recurring items as they fall due
+    Watch {
+        /// Poll once and exit
+        #[arg(long)]
+        once: bool,
+    },
+
+    /// Send PRs and comments queued in .wreckit/outbox while offline
+    Flush,
+
+    /// Build the cached repository overview used in research and plan prompts
+    Context {
+        /// Rebuild even if HEAD has not moved far enough to make it stale
+        #[arg(long)]
+        refresh: bool,
+    },
+
+    /// Reopen the TUI on the last workflow session, following it while the run continues
+    Tui {
+        /// Restore the saved session view from .wreckit/cache/tui-session.json
+        #[arg(long, required = true)]
+        attach: bool,
+    },
+
+    /// Inspect the backlog as a whole
+    Items {
+        #[command(subcommand)]
+        command: ItemsCommands,
+    },
+
+    /// Work with prompt templates
+    Prompt {
+        #[command(subcommand)]
+        command: PromptCommands,
+    },
+
+    /// Browse the event streams recorded for each run under .wreckit/runs
+    Runs {
+        #[command(subcommand)]
+        command: RunsCommands,
+    },
+
+    /// Manage tokens stored in the OS keychain
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommands,
+    },
+
+    /// Check disk space, validate items, and optionally fix issues
     Doctor {
         /// Automatically fix recoverable issues
         #[arg(long)]
         fix: bool,
+
+        /// Also lint artifact contents (research sections, testing strategy,
+        /// acceptance criteria, stale branches)
+        #[arg(long)]
+        deep: bool,
     },
 
     /// Ingest ideas from a file or stdin
@@ -147,4 +504,81 @@ pub enum Commands {
         #[arg(short, long)]
         file: Option<PathBuf>,
     },
+
+    /// Run a `wreckit-<name>` plugin from PATH
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ItemsCommands {
+    /// Print the item dependency graph (blocked-on and follow-up edges, colored by state)
+    Graph {
+        /// Output format: dot or mermaid
+        #[arg(long, default_value = "dot")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RunsCommands {
+    /// List recorded runs, oldest first
+    List,
+
+    /// Print a run's events (the latest run if none is given)
+    Show {
+        /// Run file name or prefix (e.g. 20260305T14)
+        run: Option<String>,
+
+        /// Only events about this item
+        #[arg(long)]
+        item: Option<String>,
+
+        /// Print the raw JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuthCommands {
+    /// Store a token in the keychain, read from stdin (e.g. `github`)
+    Set {
+        /// Secret name; `github` is used by the GitHub API client, others by
+        /// `keychain:<name>` env values
+        name: String,
+    },
+
+    /// Remove a token from the keychain
+    Delete {
+        /// Secret name
+        name: String,
+    },
+
+    /// Check every configured credential: forge, agent CLI, and env secrets
+    Status,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PromptCommands {
+    /// Print the prompt an item's next phase would send
+    Render {
+        /// Item ID
+        id: String,
+
+        /// Template to render instead of the next phase's (e.g. "plan")
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Show estimated token counts by section instead of the prompt
+        #[arg(long)]
+        stats: bool,
+    },
+
+    /// Render every template against canned variables and compare with the golden files
+    Snapshot {
+        /// Rewrite the golden files instead of checking them
+        #[arg(long)]
+        update: bool,
+    },
 }
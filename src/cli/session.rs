@@ -0,0 +1,277 @@
+//! Shared setup for commands that run workflow phases
+//!
+//! Resolves the repository, loads configuration, and runs the workflow
+//! under either the interactive TUI or the plain renderer.
+
+use std::future::Future;
+use std::path::Path;
+
+use crate::api::load_context;
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::tui::{
+    cancel_on_interrupt, control_channel, event_log_from_env, record_events, record_session,
+    supervised_from_env, ControlCommand, Keymap, PlainRenderer, RenderMode, Theme, TuiOptions,
+    TuiRunner, TuiUpdate,
+};
+use crate::workflow::outbox::{flush_outbox, list_outbox};
+use crate::workflow::{
+    new_run_path, record_run, simulate_phase, Orchestrator, PhaseKind, WorkflowContext,
+};
+
+/// Options shared by the phase-running commands
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SessionOptions {
+    /// Re-run phases even when their artifacts already exist
+    pub force: bool,
+    /// Describe actions without performing them
+    pub dry_run: bool,
+    /// Use the plain renderer even on a terminal
+    pub no_tui: bool,
+}
+
+/// Resolve the repository root and build a workflow context.
+///
+/// Metadata writes left incomplete by a crash are rolled forward first.
+///
+/// # Errors
+/// * `RepoNotFound` - If no repository root is found
+/// * `InvalidJson` / `SchemaValidation` - If config.json is malformed
+/// * `Io` - If an interrupted write cannot be completed
+pub fn open_context(cwd: Option<&Path>, options: SessionOptions) -> Result<WorkflowContext> {
+    Ok(load_context(&fs::resolve_cwd(cwd), options.dry_run)?.with_force(options.force))
+}
+
+/// Config settings listed in the TUI's help overlay
+fn help_highlights(ctx: &WorkflowContext) -> Vec<(String, String)> {
+    let agent = &ctx.config.agent;
+    let command = std::iter::once(agent.command.as_str())
+        .chain(agent.args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+    vec![
+        ("Agent".to_string(), command),
+        ("Base branch".to_string(), ctx.config.base_branch.clone()),
+        (
+            "Config".to_string(),
+            fs::get_config_path(&ctx.root).display().to_string(),
+        ),
+    ]
+}
+
+/// TUI options from the `tui` config, with no workflow to control.
+///
+/// # Errors
+/// * `ConfigError` - If `tui.keymap` names an unknown action or key
+pub fn tui_options(ctx: &WorkflowContext) -> Result<TuiOptions> {
+    let keymap = Keymap::from_config(&ctx.config.tui.keymap)
+        .map_err(|e| WreckitError::ConfigError(format!("invalid tui.keymap: {}", e)))?;
+    Ok(TuiOptions {
+        notify: ctx.config.tui.notify,
+        theme: Theme::from_config(&ctx.config.tui),
+        reduced_motion: ctx.config.tui.reduced_motion,
+        keymap,
+        highlights: help_highlights(ctx),
+        ..Default::default()
+    })
+}
+
+/// Record that the interactive TUI has been shown in this repository,
+/// returning whether this is the first time
+fn mark_tui_seen(root: &Path) -> bool {
+    let path = fs::get_tui_seen_path(root);
+    if path.exists() {
+        return false;
+    }
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write_file(&path, ""));
+    if let Err(e) = written {
+        tracing::debug!("Cannot record first TUI run: {}", e);
+    }
+    true
+}
+
+/// Send forge operations queued by an earlier offline run; failures are
+/// only logged and the operations stay queued
+async fn flush_queued(ctx: &WorkflowContext) {
+    if ctx.dry_run || !fs::get_outbox_dir(&ctx.root).exists() {
+        return;
+    }
+    match flush_outbox(ctx).await {
+        Ok(report) if report.sent > 0 => {
+            tracing::info!("Sent {} queued forge operation(s)", report.sent);
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Cannot flush the outbox: {}", e),
+    }
+    if let Ok(left) = list_outbox(&ctx.root) {
+        if !left.is_empty() {
+            tracing::warn!(
+                "{} forge operation(s) still queued in the outbox (see `wreckit flush`)",
+                left.len()
+            );
+        }
+    }
+}
+
+/// Run workflow work under a renderer.
+///
+/// The closure receives the context wired to the renderer's update stream
+/// and the operator controls. In interactive mode, quitting the TUI pauses
+/// the run and cancels the in-flight agent before waiting for the work to
+/// end; SIGINT does the same in either mode. The repository lock is held
+/// throughout, and forge operations left in the outbox by an offline run are
+/// sent first. Either way the session is recorded for
+/// `wreckit tui --attach`, the run's events are written under .wreckit/runs
+/// for `wreckit runs`, and a detached run also writes its event file for
+/// `wreckit attach`.
+pub async fn run_with_renderer<F, Fut, T>(ctx: WorkflowContext, no_tui: bool, work: F) -> Result<T>
+where
+    F: FnOnce(WorkflowContext) -> Fut,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    let _lock = if ctx.dry_run {
+        None
+    } else {
+        Some(fs::acquire_lock(&ctx.root)?)
+    };
+    flush_queued(&ctx).await;
+    let items = fs::list_items(&ctx.root)?;
+    let (control, handle) = control_channel();
+    let mut options = TuiOptions {
+        control: Some(control.clone()),
+        ..tui_options(&ctx)?
+    };
+    let session_path = fs::get_tui_session_path(&ctx.root);
+    let recorded_items = items.clone();
+    let interrupts = cancel_on_interrupt(control.clone());
+
+    match RenderMode::detect(no_tui) {
+        RenderMode::Plain => {
+            let renderer = PlainRenderer::new(items, options);
+            let updates = renderer.create_update_sender();
+            let recorder = record_session(session_path, recorded_items, updates.subscribe());
+            let events = event_log_from_env()
+                .map(|path| record_events(path, updates.subscribe(), supervised_from_env()));
+            let run_log = (!ctx.dry_run).then(|| {
+                record_run(
+                    new_run_path(&ctx.root),
+                    ctx.events.subscribe(),
+                    updates.subscribe(),
+                )
+            });
+            let render_task = tokio::spawn(renderer.run());
+
+            // The context (and its sender) is dropped when the work ends, closing the stream
+            let result = work(ctx.with_updates(updates).with_control(handle)).await;
+            interrupts.abort();
+            let _ = render_task.await;
+            let _ = recorder.await;
+            if let Some(events) = events {
+                let _ = events.await;
+            }
+            if let Some(run_log) = run_log {
+                let _ = run_log.await;
+            }
+            result
+        }
+        RenderMode::Interactive => {
+            options.first_run = mark_tui_seen(&ctx.root);
+            let mut runner = TuiRunner::new(items, options).await;
+            let updates = runner.create_update_sender();
+            let finished = updates.clone();
+            let recorder = record_session(session_path, recorded_items, updates.subscribe());
+            let events = event_log_from_env()
+                .map(|path| record_events(path, updates.subscribe(), supervised_from_env()));
+            let run_log = (!ctx.dry_run).then(|| {
+                record_run(
+                    new_run_path(&ctx.root),
+                    ctx.events.subscribe(),
+                    updates.subscribe(),
+                )
+            });
+
+            let fut = work(ctx.with_updates(updates).with_control(handle));
+            let task = tokio::spawn(async move {
+                let result = fut.await;
+                let _ = finished.send(TuiUpdate::RunFinished);
+                result
+            });
+
+            runner.run().await?;
+            if !task.is_finished() {
+                control.send(ControlCommand::Pause);
+                control.send(ControlCommand::CancelCurrent);
+            }
+            let result = task
+                .await
+                .map_err(|e| WreckitError::wrap(e, "workflow task failed"))?;
+            interrupts.abort();
+            let _ = recorder.await;
+            if let Some(events) = events {
+                let _ = events.await;
+            }
+            if let Some(run_log) = run_log {
+                let _ = run_log.await;
+            }
+            result
+        }
+    }
+}
+
+/// Run a single phase for an item (the research/plan/implement/pr/complete commands).
+///
+/// In dry-run mode the phase is simulated and its plan printed instead.
+pub async fn run_single_phase(
+    cwd: Option<&Path>,
+    id: &str,
+    kind: PhaseKind,
+    options: SessionOptions,
+) -> Result<()> {
+    let ctx = open_context(cwd, options)?;
+    if options.dry_run {
+        let item = fs::read_item(&ctx.root, id)?;
+        print!("{}", simulate_phase(&ctx, &item, kind)?);
+        return Ok(());
+    }
+    let id = id.to_string();
+    let item = run_with_renderer(ctx, options.no_tui, move |ctx| async move {
+        Orchestrator::new(ctx).run_phase(&id, kind).await
+    })
+    .await?;
+    tracing::info!("{} phase finished; {} is {}", kind, item.id, item.state);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_highlights() {
+        let mut config = crate::schemas::Config::default();
+        config.agent.command = "claude".to_string();
+        config.agent.args = vec!["--print".to_string()];
+        let ctx = WorkflowContext::new(std::path::PathBuf::from("/repo"), config);
+        let highlights = help_highlights(&ctx);
+        assert_eq!(
+            highlights[0],
+            ("Agent".to_string(), "claude --print".to_string())
+        );
+        assert_eq!(highlights[1].0, "Base branch");
+        assert!(highlights[2].1.ends_with("config.json"));
+    }
+
+    #[test]
+    fn test_mark_tui_seen_writes_nothing_in_read_only_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(fs::with_read_only(|| mark_tui_seen(dir.path())));
+        assert!(!fs::get_tui_seen_path(dir.path()).exists());
+
+        assert!(mark_tui_seen(dir.path()));
+        assert!(!mark_tui_seen(dir.path()));
+    }
+}
@@ -1,15 +1,41 @@
 //! Configuration loading with defaults
+//!
+//! A config may define named profiles under `profiles`, each a partial
+//! config (`{"ci": {"agent": {"command": "..."}}}`). The profile selected
+//! with `--profile` or `WRECKIT_PROFILE` is merged over the rest of the file
+//! before defaults are filled in: objects merge key by key, anything else
+//! in the profile replaces the base value.
 
 use std::path::Path;
+use std::sync::Mutex;
 
-use crate::errors::Result;
+use serde_json::Value;
+
+use crate::errors::{Result, WreckitError};
 use crate::fs;
 use crate::schemas::Config;
 
+/// Environment variable naming the config profile to apply
+pub const PROFILE_ENV: &str = "WRECKIT_PROFILE";
+
+static PROFILE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Select a profile for this process, overriding `WRECKIT_PROFILE`
+pub fn set_profile(profile: Option<String>) {
+    *PROFILE.lock().unwrap_or_else(|e| e.into_inner()) = profile;
+}
+
+/// The selected profile: `--profile`, else a non-empty `WRECKIT_PROFILE`
+pub fn active_profile() -> Option<String> {
+    let selected = PROFILE.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    selected.or_else(|| std::env::var(PROFILE_ENV).ok().filter(|p| !p.is_empty()))
+}
+
 /// Load configuration from the repository, falling back to defaults.
 ///
 /// If config.json exists, it will be read and merged with defaults.
-/// If it doesn't exist, default configuration is returned.
+/// If it doesn't exist, default configuration is returned. The active
+/// profile, if any, is applied.
 ///
 /// # Arguments
 /// * `root` - Path to the repository root
@@ -17,7 +43,76 @@ use crate::schemas::Config;
 /// # Returns
 /// The resolved configuration
 pub fn load_config(root: &Path) -> Result<Config> {
-    fs::read_config(root)
+    load_config_with_profile(root, active_profile().as_deref())
+}
+
+/// Load configuration with the given profile merged in.
+///
+/// # Errors
+/// * `ConfigError` - If the profile is not defined
+/// * `InvalidJson` - If config.json or the merged result is malformed
+pub fn load_config_with_profile(root: &Path, profile: Option<&str>) -> Result<Config> {
+    let profile = match profile {
+        Some(profile) => profile,
+        None => return fs::read_config(root),
+    };
+    let path = fs::get_config_path(root);
+    let mut raw: Value = if path.exists() {
+        fs::read_json(&path)?
+    } else {
+        Value::Object(Default::default())
+    };
+    let overrides = match raw.get("profiles").and_then(|p| p.get(profile)) {
+        Some(overrides) => overrides.clone(),
+        None => {
+            let defined: Vec<&str> = raw
+                .get("profiles")
+                .and_then(Value::as_object)
+                .map(|p| p.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            return Err(WreckitError::ConfigError(format!(
+                "unknown profile '{}' (defined: {})",
+                profile,
+                if defined.is_empty() {
+                    "none".to_string()
+                } else {
+                    defined.join(", ")
+                }
+            )));
+        }
+    };
+    merge_values(&mut raw, overrides);
+    serde_json::from_value(raw).map_err(|e| {
+        WreckitError::InvalidJson(format!(
+            "Invalid JSON in file {} with profile {}: {}",
+            path.display(),
+            profile,
+            e
+        ))
+    })
+}
+
+/// Merge `overlay` into `base`: objects key by key, other values replaced.
+/// A nested `profiles` key in the overlay is ignored.
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                if key == "profiles" {
+                    continue;
+                }
+                match base.get_mut(&key) {
+                    Some(existing) if existing.is_object() && value.is_object() => {
+                        merge_values(existing, value)
+                    }
+                    _ => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
 }
 
 #[cfg(test)]
@@ -58,4 +153,38 @@ mod tests {
         // Default for unspecified field
         assert_eq!(config.timeout_seconds, 3600);
     }
+
+    #[test]
+    fn test_load_config_with_profile() {
+        let temp = TempDir::new().unwrap();
+        let wreckit_dir = temp.path().join(".wreckit");
+        std_fs::create_dir(&wreckit_dir).unwrap();
+
+        let config_content = r#"{
+            "max_iterations": 50,
+            "agent": {"command": "claude", "args": ["--print"], "completion_signal": "DONE"},
+            "profiles": {
+                "ci": {"agent": {"command": "ci-agent"}, "timeout_seconds": 600}
+            }
+        }"#;
+        std_fs::write(wreckit_dir.join("config.json"), config_content).unwrap();
+
+        let base = load_config_with_profile(temp.path(), None).unwrap();
+        assert_eq!(base.agent.command, "claude");
+        assert_eq!(base.timeout_seconds, 3600);
+
+        let ci = load_config_with_profile(temp.path(), Some("ci")).unwrap();
+        assert_eq!(ci.agent.command, "ci-agent");
+        // Siblings of overridden keys are kept
+        assert_eq!(ci.agent.args, vec!["--print".to_string()]);
+        assert_eq!(ci.timeout_seconds, 600);
+        assert_eq!(ci.max_iterations, 50);
+        assert!(ci.profiles.contains_key("ci"));
+
+        let err = load_config_with_profile(temp.path(), Some("laptop")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Configuration error: unknown profile 'laptop' (defined: ci)"
+        );
+    }
 }
@@ -2,4 +2,4 @@
 
 mod loader;
 
-pub use loader::load_config;
+pub use loader::{active_profile, load_config, load_config_with_profile, set_profile, PROFILE_ENV};
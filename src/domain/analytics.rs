@@ -0,0 +1,345 @@
+//! Backlog analytics for `wreckit status`
+//!
+//! Everything here is derived from items alone: their current state, their
+//! creation time, and the state history recorded on each transition. Items
+//! written before state history existed still count toward the per-state
+//! totals but contribute no phase durations.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::schemas::{Item, WorkflowState};
+
+use super::states::{get_state_index, is_terminal_state};
+
+/// Weeks of completion history reported, including the current week
+pub const COMPLETION_WEEKS: usize = 4;
+
+/// How many items are in a state
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StateCount {
+    pub state: String,
+    pub count: usize,
+}
+
+/// Average time items spent in a state before moving on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseDuration {
+    pub state: String,
+    /// Number of completed stays the average is based on
+    pub samples: usize,
+    pub average_seconds: i64,
+}
+
+/// An item somewhere between research and done
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AgingItem {
+    pub id: String,
+    pub title: String,
+    pub state: String,
+    /// Seconds since the item was created (for in-flight items) or since
+    /// its PR was opened (for PRs awaiting review)
+    pub age_seconds: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pr_url: Option<String>,
+}
+
+/// Items completed during one week
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeeklyCount {
+    /// Monday the week starts on (YYYY-MM-DD)
+    pub week_start: String,
+    pub completed: usize,
+}
+
+/// Backlog analytics shown by `wreckit status`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BacklogStats {
+    pub total: usize,
+    pub by_state: Vec<StateCount>,
+    pub phase_durations: Vec<PhaseDuration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_in_flight: Option<AgingItem>,
+    pub awaiting_review: Vec<AgingItem>,
+    pub weekly_completions: Vec<WeeklyCount>,
+}
+
+fn parse_time(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Built-in states in workflow order, custom states (which follow in_pr)
+/// by name after in_pr, and abandoned last
+fn state_order(state: &str) -> (usize, String) {
+    let index = match state.parse::<WorkflowState>() {
+        Ok(WorkflowState::Abandoned) => usize::MAX,
+        Ok(state) => get_state_index(state) * 2,
+        Err(_) => get_state_index(WorkflowState::InPr) * 2 + 1,
+    };
+    (index, state.to_string())
+}
+
+/// Format a duration compactly, to its two largest units (e.g. "2d 4h")
+pub fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (days, hours) = (seconds / 86_400, seconds % 86_400 / 3600);
+    let (minutes, secs) = (seconds % 3600 / 60, seconds % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, secs)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Every state the item has been in, with when it was entered
+fn timeline(item: &Item) -> Vec<(&str, Option<DateTime<Utc>>)> {
+    let mut entries = vec![("idea", parse_time(&item.created_at))];
+    entries.extend(
+        item.state_history
+            .iter()
+            .map(|change| (change.state.as_str(), parse_time(&change.at))),
+    );
+    entries
+}
+
+fn completed_at(item: &Item) -> Option<DateTime<Utc>> {
+    if item.state != WorkflowState::Done {
+        return None;
+    }
+    let done = WorkflowState::Done.to_string();
+    item.state_history
+        .iter()
+        .rev()
+        .find(|change| change.state == done)
+        .and_then(|change| parse_time(&change.at))
+        .or_else(|| parse_time(&item.updated_at))
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Compute backlog analytics as of `now`.
+pub fn backlog_stats(items: &[Item], now: DateTime<Utc>) -> BacklogStats {
+    let mut by_state: Vec<StateCount> = Vec::new();
+    for item in items {
+        let state = item.state_name();
+        match by_state.iter_mut().find(|c| c.state == state) {
+            Some(count) => count.count += 1,
+            None => by_state.push(StateCount { state, count: 1 }),
+        }
+    }
+    by_state.sort_by_key(|c| state_order(&c.state));
+
+    let mut totals: Vec<(String, i64, usize)> = Vec::new();
+    for item in items {
+        let entries = timeline(item);
+        for pair in entries.windows(2) {
+            let (state, start) = pair[0];
+            let (start, end) = match (start, pair[1].1) {
+                (Some(start), Some(end)) if end >= start => (start, end),
+                _ => continue,
+            };
+            let seconds = (end - start).num_seconds();
+            match totals.iter_mut().find(|(s, _, _)| s == state) {
+                Some(total) => {
+                    total.1 += seconds;
+                    total.2 += 1;
+                }
+                None => totals.push((state.to_string(), seconds, 1)),
+            }
+        }
+    }
+    totals.sort_by_key(|(state, _, _)| state_order(state));
+    let phase_durations = totals
+        .into_iter()
+        .map(|(state, seconds, samples)| PhaseDuration {
+            state,
+            samples,
+            average_seconds: seconds / samples as i64,
+        })
+        .collect();
+
+    let oldest_in_flight = items
+        .iter()
+        .filter(|item| item.state != WorkflowState::Idea && !is_terminal_state(item.state))
+        .filter_map(|item| parse_time(&item.created_at).map(|created| (item, created)))
+        .min_by_key(|(_, created)| *created)
+        .map(|(item, created)| AgingItem {
+            id: item.id.clone(),
+            title: item.title.clone(),
+            state: item.state_name(),
+            age_seconds: (now - created).num_seconds(),
+            pr_url: item.pr_url.clone(),
+        });
+
+    let mut awaiting_review: Vec<AgingItem> = items
+        .iter()
+        .filter(|item| {
+            item.state == WorkflowState::InPr
+                && item.custom_state.is_none()
+                && item.pr_url.is_some()
+        })
+        .map(|item| AgingItem {
+            id: item.id.clone(),
+            title: item.title.clone(),
+            state: item.state_name(),
+            age_seconds: parse_time(item.entered_state_at())
+                .map(|entered| (now - entered).num_seconds())
+                .unwrap_or(0),
+            pr_url: item.pr_url.clone(),
+        })
+        .collect();
+    awaiting_review.sort_by_key(|pr| std::cmp::Reverse(pr.age_seconds));
+
+    let this_week = week_start(now.date_naive());
+    let mut weekly_completions: Vec<WeeklyCount> = (0..COMPLETION_WEEKS)
+        .rev()
+        .map(|weeks_ago| WeeklyCount {
+            week_start: (this_week - Duration::weeks(weeks_ago as i64))
+                .format("%Y-%m-%d")
+                .to_string(),
+            completed: 0,
+        })
+        .collect();
+    for done in items.iter().filter_map(completed_at) {
+        let week = week_start(done.date_naive()).format("%Y-%m-%d").to_string();
+        if let Some(count) = weekly_completions.iter_mut().find(|w| w.week_start == week) {
+            count.completed += 1;
+        }
+    }
+
+    BacklogStats {
+        total: items.len(),
+        by_state,
+        phase_durations,
+        oldest_in_flight,
+        awaiting_review,
+        weekly_completions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::StateChange;
+
+    fn at(timestamp: &str) -> String {
+        timestamp.to_string()
+    }
+
+    fn item(id: &str, created: &str, history: &[(&str, &str)]) -> Item {
+        let mut item = Item::new(id.to_string(), id.to_string(), String::new());
+        item.created_at = at(created);
+        item.updated_at = at(created);
+        item.state_history = history
+            .iter()
+            .map(|(state, time)| StateChange {
+                state: state.to_string(),
+                at: at(time),
+            })
+            .collect();
+        if let Some((state, time)) = history.last() {
+            item.updated_at = at(time);
+            match state.parse::<WorkflowState>() {
+                Ok(state) => item.state = state,
+                Err(_) => item.custom_state = Some(state.to_string()),
+            }
+        }
+        item
+    }
+
+    #[test]
+    fn test_backlog_stats() {
+        let now = parse_time("2024-03-14T12:00:00Z").unwrap(); // a Thursday
+        let items = vec![
+            item(
+                "001-done",
+                "2024-03-01T00:00:00Z",
+                &[
+                    ("researched", "2024-03-01T02:00:00Z"),
+                    ("planned", "2024-03-01T03:00:00Z"),
+                    ("implementing", "2024-03-01T04:00:00Z"),
+                    ("in_pr", "2024-03-01T08:00:00Z"),
+                    ("done", "2024-03-12T09:00:00Z"),
+                ],
+            ),
+            item(
+                "002-review",
+                "2024-03-05T00:00:00Z",
+                &[
+                    ("researched", "2024-03-05T04:00:00Z"),
+                    ("planned", "2024-03-05T05:00:00Z"),
+                    ("implementing", "2024-03-05T06:00:00Z"),
+                    ("in_pr", "2024-03-13T12:00:00Z"),
+                ],
+            ),
+            item("003-idea", "2024-03-10T00:00:00Z", &[]),
+        ];
+        let mut items = items;
+        items[1].pr_url = Some("https://github.com/o/r/pull/2".to_string());
+
+        let stats = backlog_stats(&items, now);
+        assert_eq!(stats.total, 3);
+        let states: Vec<(&str, usize)> = stats
+            .by_state
+            .iter()
+            .map(|c| (c.state.as_str(), c.count))
+            .collect();
+        assert_eq!(states, vec![("idea", 1), ("in_pr", 1), ("done", 1)]);
+
+        // idea: 2h and 4h; implementing: 4h and 8 days 6h
+        assert_eq!(stats.phase_durations[0].state, "idea");
+        assert_eq!(stats.phase_durations[0].samples, 2);
+        assert_eq!(stats.phase_durations[0].average_seconds, 3 * 3600);
+        let implementing = stats
+            .phase_durations
+            .iter()
+            .find(|d| d.state == "implementing")
+            .unwrap();
+        assert_eq!(implementing.average_seconds, (4 + 8 * 24 + 6) * 3600 / 2);
+
+        let oldest = stats.oldest_in_flight.unwrap();
+        assert_eq!(oldest.id, "002-review");
+        assert_eq!(stats.awaiting_review.len(), 1);
+        assert_eq!(stats.awaiting_review[0].age_seconds, 24 * 3600);
+
+        let weeks: Vec<(&str, usize)> = stats
+            .weekly_completions
+            .iter()
+            .map(|w| (w.week_start.as_str(), w.completed))
+            .collect();
+        assert_eq!(
+            weeks,
+            vec![
+                ("2024-02-19", 0),
+                ("2024-02-26", 0),
+                ("2024-03-04", 0),
+                ("2024-03-11", 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(3 * 60 + 5), "3m 5s");
+        assert_eq!(format_duration(5 * 3600 + 120), "5h 2m");
+        assert_eq!(format_duration(2 * 86_400 + 4 * 3600 + 59), "2d 4h");
+    }
+
+    #[test]
+    fn test_items_without_history() {
+        let mut legacy = Item::new("001-old".to_string(), "Old".to_string(), String::new());
+        legacy.state = WorkflowState::Done;
+        let stats = backlog_stats(&[legacy], Utc::now());
+        assert!(stats.phase_durations.is_empty());
+        assert_eq!(stats.weekly_completions.last().unwrap().completed, 1);
+    }
+}
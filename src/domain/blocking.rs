@@ -0,0 +1,80 @@
+//! Blocked-item rules
+//!
+//! Blocking is orthogonal to the workflow state: a blocked item keeps its
+//! state but is skipped by `next` until its blocker is lifted, either by hand
+//! or automatically once its date passes or the item it waits on is done.
+
+use chrono::NaiveDate;
+
+use crate::schemas::{Blocker, WorkflowState};
+
+/// Date format accepted for `--until`
+pub const BLOCK_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Parse a block-until date (YYYY-MM-DD)
+pub fn parse_block_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date.trim(), BLOCK_DATE_FORMAT)
+        .map_err(|_| format!("expected a date like 2024-07-01, got '{}'", date))
+}
+
+/// Whether a blocker's automatic unblock condition has been met.
+///
+/// # Arguments
+/// * `blocker` - The item's blocker
+/// * `today` - The current date
+/// * `dependency` - State of the `on_item` dependency, if it exists
+pub fn is_block_lifted(
+    blocker: &Blocker,
+    today: NaiveDate,
+    dependency: Option<WorkflowState>,
+) -> bool {
+    let date_passed = blocker
+        .until
+        .as_deref()
+        .and_then(|d| parse_block_date(d).ok())
+        .is_some_and(|until| today >= until);
+    let dependency_done = blocker.on_item.is_some() && dependency == Some(WorkflowState::Done);
+    date_passed || dependency_done
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        parse_block_date(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_block_date() {
+        assert_eq!(date("2024-07-01").to_string(), "2024-07-01");
+        assert!(parse_block_date("next tuesday").is_err());
+    }
+
+    #[test]
+    fn test_block_lifted_by_date() {
+        let blocker =
+            Blocker::new("waiting".to_string()).with_until(Some("2024-07-01".to_string()));
+        assert!(!is_block_lifted(&blocker, date("2024-06-30"), None));
+        assert!(is_block_lifted(&blocker, date("2024-07-01"), None));
+    }
+
+    #[test]
+    fn test_block_lifted_by_dependency() {
+        let blocker = Blocker::new("waiting".to_string()).with_on_item(Some("002".to_string()));
+        let today = date("2024-07-01");
+        assert!(!is_block_lifted(&blocker, today, Some(WorkflowState::InPr)));
+        assert!(!is_block_lifted(&blocker, today, None));
+        assert!(is_block_lifted(&blocker, today, Some(WorkflowState::Done)));
+    }
+
+    #[test]
+    fn test_manual_block_never_lifts() {
+        let blocker = Blocker::new("waiting".to_string());
+        assert!(!is_block_lifted(
+            &blocker,
+            date("2999-01-01"),
+            Some(WorkflowState::Done)
+        ));
+    }
+}
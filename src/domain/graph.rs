@@ -0,0 +1,240 @@
+//! Item dependency graph rendering
+//!
+//! Every item becomes a node colored by its workflow state. An item blocked
+//! on another (`wreckit block --on-item`) gets a dashed red edge from the item
+//! it waits on, and a follow-up gets a plain edge from the item it follows up
+//! on. Blocked items are outlined in red. The graph renders as Graphviz DOT
+//! or as a Mermaid flowchart for pasting into markdown.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::schemas::{Item, WorkflowState};
+
+/// Output format for the item graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    #[default]
+    Dot,
+
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphFormat::Dot => write!(f, "dot"),
+            GraphFormat::Mermaid => write!(f, "mermaid"),
+        }
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(GraphFormat::Dot),
+            "mermaid" => Ok(GraphFormat::Mermaid),
+            other => Err(format!(
+                "unknown graph format '{}' (expected dot or mermaid)",
+                other
+            )),
+        }
+    }
+}
+
+/// How one item relates to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum EdgeKind {
+    /// The target is blocked until the source is done
+    Blocks,
+
+    /// The target follows up on the source
+    FollowUp,
+}
+
+/// Fill color for a workflow state
+fn state_color(state: WorkflowState) -> &'static str {
+    match state {
+        WorkflowState::Idea => "#e5e7eb",
+        WorkflowState::Researched => "#bfdbfe",
+        WorkflowState::Planned => "#93c5fd",
+        WorkflowState::Implementing => "#fde68a",
+        WorkflowState::InPr => "#c4b5fd",
+        WorkflowState::Done => "#86efac",
+        WorkflowState::Abandoned => "#f3f4f6",
+    }
+}
+
+const BLOCKED_COLOR: &str = "#dc2626";
+
+/// Edges between items in the list, sorted by source then target
+fn edges(items: &[Item]) -> Vec<(&str, &str, EdgeKind)> {
+    let ids: BTreeSet<&str> = items.iter().map(|item| item.id.as_str()).collect();
+    let mut edges = Vec::new();
+    for item in items {
+        let blocked_on = item.blocked.as_ref().and_then(|b| b.on_item.as_deref());
+        if let Some(source) = blocked_on.filter(|id| ids.contains(id)) {
+            edges.push((source, item.id.as_str(), EdgeKind::Blocks));
+        }
+        if let Some(source) = item.follow_up_of.as_deref().filter(|id| ids.contains(id)) {
+            edges.push((source, item.id.as_str(), EdgeKind::FollowUp));
+        }
+    }
+    edges.sort();
+    edges
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn render_dot(items: &[Item]) -> String {
+    let mut out = String::from("digraph wreckit {\n    rankdir=LR;\n");
+    out.push_str("    node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n");
+    for item in items {
+        let label = format!(
+            "{}\\n{}\\n[{}]",
+            dot_escape(&item.id),
+            dot_escape(&item.title),
+            dot_escape(&item.state_name())
+        );
+        let mut attrs = format!(
+            "label=\"{}\", fillcolor=\"{}\"",
+            label,
+            state_color(item.state)
+        );
+        if item.is_blocked() {
+            attrs.push_str(&format!(", color=\"{}\", penwidth=2", BLOCKED_COLOR));
+        }
+        out.push_str(&format!("    \"{}\" [{}];\n", dot_escape(&item.id), attrs));
+    }
+    for (source, target, kind) in edges(items) {
+        let attrs = match kind {
+            EdgeKind::Blocks => format!(
+                " [label=\"blocks\", style=dashed, color=\"{}\"]",
+                BLOCKED_COLOR
+            ),
+            EdgeKind::FollowUp => " [label=\"follow-up\"]".to_string(),
+        };
+        out.push_str(&format!(
+            "    \"{}\" -> \"{}\"{};\n",
+            dot_escape(source),
+            dot_escape(target),
+            attrs
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// A Mermaid node ID for an item ID (Mermaid IDs cannot contain most punctuation)
+fn mermaid_id(id: &str) -> String {
+    let safe: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("item_{}", safe)
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+fn render_mermaid(items: &[Item]) -> String {
+    let mut out = String::from("flowchart LR\n");
+    for item in items {
+        out.push_str(&format!(
+            "    {}[\"{}<br/>{}<br/>[{}]\"]\n",
+            mermaid_id(&item.id),
+            mermaid_escape(&item.id),
+            mermaid_escape(&item.title),
+            mermaid_escape(&item.state_name())
+        ));
+    }
+    for (source, target, kind) in edges(items) {
+        let arrow = match kind {
+            EdgeKind::Blocks => "-. blocks .->",
+            EdgeKind::FollowUp => "-- follow-up -->",
+        };
+        out.push_str(&format!(
+            "    {} {} {}\n",
+            mermaid_id(source),
+            arrow,
+            mermaid_id(target)
+        ));
+    }
+    for item in items {
+        let mut style = format!("fill:{}", state_color(item.state));
+        if item.is_blocked() {
+            style.push_str(&format!(",stroke:{},stroke-width:2px", BLOCKED_COLOR));
+        }
+        out.push_str(&format!("    style {} {}\n", mermaid_id(&item.id), style));
+    }
+    out
+}
+
+/// Render the dependency graph of the given items
+pub fn render_graph(items: &[Item], format: GraphFormat) -> String {
+    match format {
+        GraphFormat::Dot => render_dot(items),
+        GraphFormat::Mermaid => render_mermaid(items),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Blocker;
+
+    fn items() -> Vec<Item> {
+        let a = Item::new("001-a".to_string(), "Add \"x\"".to_string(), String::new())
+            .with_state(WorkflowState::Implementing);
+        let b = Item::new("002-b".to_string(), "B".to_string(), String::new()).with_blocked(Some(
+            Blocker::new("needs a".to_string()).with_on_item(Some("001-a".to_string())),
+        ));
+        let mut c = Item::new("003-c".to_string(), "C".to_string(), String::new());
+        c.follow_up_of = Some("001-a".to_string());
+        // Dependencies outside the list are left out
+        let d = Item::new("004-d".to_string(), "D".to_string(), String::new()).with_blocked(Some(
+            Blocker::new("gone".to_string()).with_on_item(Some("000-x".to_string())),
+        ));
+        vec![a, b, c, d]
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let dot = render_graph(&items(), GraphFormat::Dot);
+        assert!(dot.starts_with("digraph wreckit {\n"));
+        assert!(dot.contains(
+            "    \"001-a\" [label=\"001-a\\nAdd \\\"x\\\"\\n[implementing]\", fillcolor=\"#fde68a\"];\n"
+        ));
+        assert!(dot.contains("\"002-b\" [label=\"002-b\\nB\\n[idea]\", fillcolor=\"#e5e7eb\", color=\"#dc2626\", penwidth=2];"));
+        assert!(dot.contains(
+            "    \"001-a\" -> \"002-b\" [label=\"blocks\", style=dashed, color=\"#dc2626\"];\n"
+        ));
+        assert!(dot.contains("    \"001-a\" -> \"003-c\" [label=\"follow-up\"];\n"));
+        assert!(!dot.contains("000-x"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_render_mermaid() {
+        let mermaid = render_graph(&items(), GraphFormat::Mermaid);
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid
+            .contains("    item_001_a[\"001-a<br/>Add #quot;x#quot;<br/>[implementing]\"]\n"));
+        assert!(mermaid.contains("    item_001_a -. blocks .-> item_002_b\n"));
+        assert!(mermaid.contains("    item_001_a -- follow-up --> item_003_c\n"));
+        assert!(
+            mermaid.contains("    style item_002_b fill:#e5e7eb,stroke:#dc2626,stroke-width:2px\n")
+        );
+        assert!(!mermaid.contains("000_x"));
+        assert_eq!("mermaid".parse(), Ok(GraphFormat::Mermaid));
+        assert!("svg".parse::<GraphFormat>().is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! Item ID generation
+//!
+//! The scheme is chosen by `config.id_scheme`:
+//!
+//! * `numbered` (default) - sequence number plus title slug, e.g. `007-add-dark-mode`
+//! * `sequential` - prefix plus sequence number, e.g. `WRK-007`
+//! * `date` - today's date plus a per-day sequence number, e.g. `20240701-002`
+//! * `slug` - the title slug alone, e.g. `add-dark-mode`
+//! * `ulid` - a ULID, e.g. `01J1X4Z9Q4C0V8S9M6T7N2B3K5`
+//!
+//! Every scheme checks candidates against the IDs already in use and moves
+//! on to the next candidate on a collision.
+
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+
+use crate::schemas::IdScheme;
+
+/// Longest slug kept in a generated ID
+const MAX_SLUG_LEN: usize = 40;
+
+/// Turn a title into a lowercase, hyphen-separated slug
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let mut slug: String = slug.chars().take(MAX_SLUG_LEN).collect();
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
+/// Highest sequence number among IDs that start with `prefix`
+fn max_sequence(existing: &HashSet<String>, prefix: &str) -> u32 {
+    existing
+        .iter()
+        .filter_map(|id| {
+            let rest = id.strip_prefix(prefix)?;
+            rest.split('-').next()?.parse::<u32>().ok()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// The next `numbered` ID for a title, given the IDs already in use
+pub fn next_item_id<'a>(existing: impl IntoIterator<Item = &'a str>, title: &str) -> String {
+    let existing: HashSet<String> = existing.into_iter().map(String::from).collect();
+    generate_item_id(
+        IdScheme::Numbered,
+        "",
+        title,
+        chrono::Utc::now().date_naive(),
+        &existing,
+    )
+}
+
+/// Generate an ID that does not collide with `existing`.
+///
+/// # Arguments
+/// * `scheme` - The configured ID scheme
+/// * `prefix` - Prefix for the `sequential` scheme (e.g. "WRK")
+/// * `title` - Item title, used by the slug-based schemes
+/// * `today` - Current date, used by the `date` scheme
+/// * `existing` - IDs already used by items or the index
+pub fn generate_item_id(
+    scheme: IdScheme,
+    prefix: &str,
+    title: &str,
+    today: NaiveDate,
+    existing: &HashSet<String>,
+) -> String {
+    let slug = slugify(title);
+    let candidate = |n: u32| -> String {
+        match scheme {
+            IdScheme::Numbered if slug.is_empty() => format!("{:03}", n),
+            IdScheme::Numbered => format!("{:03}-{}", n, slug),
+            IdScheme::Sequential => format!("{}-{:03}", prefix, n),
+            IdScheme::Date => format!("{}-{:03}", today.format("%Y%m%d"), n),
+            IdScheme::Slug if slug.is_empty() => format!("item-{}", n),
+            IdScheme::Slug if n == 1 => slug.clone(),
+            IdScheme::Slug => format!("{}-{}", slug, n),
+            IdScheme::Ulid => ulid::Ulid::new().to_string(),
+        }
+    };
+
+    let mut n = match scheme {
+        IdScheme::Numbered => max_sequence(existing, "") + 1,
+        IdScheme::Sequential => max_sequence(existing, &format!("{}-", prefix)) + 1,
+        IdScheme::Date => max_sequence(existing, &format!("{}-", today.format("%Y%m%d"))) + 1,
+        IdScheme::Slug | IdScheme::Ulid => 1,
+    };
+    loop {
+        let id = candidate(n);
+        if !existing.contains(&id) {
+            return id;
+        }
+        n += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(list: &[&str]) -> HashSet<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Add dark mode!"), "add-dark-mode");
+        assert_eq!(slugify("  Follow-up: API v2  "), "follow-up-api-v2");
+        assert_eq!(slugify("???"), "");
+    }
+
+    #[test]
+    fn test_next_item_id() {
+        assert_eq!(next_item_id([], "First"), "001-first");
+        assert_eq!(
+            next_item_id(["001-a", "009-b", "notes"], "Dark mode"),
+            "010-dark-mode"
+        );
+        assert_eq!(next_item_id(["001-a"], "!!"), "002");
+    }
+
+    #[test]
+    fn test_sequential_and_date_schemes() {
+        let existing = ids(&[
+            "WRK-001",
+            "WRK-012",
+            "OPS-099",
+            "20240701-001",
+            "20240630-007",
+        ]);
+        assert_eq!(
+            generate_item_id(IdScheme::Sequential, "WRK", "x", today(), &existing),
+            "WRK-013"
+        );
+        assert_eq!(
+            generate_item_id(IdScheme::Date, "WRK", "x", today(), &existing),
+            "20240701-002"
+        );
+    }
+
+    #[test]
+    fn test_slug_scheme_avoids_collisions() {
+        let existing = ids(&["dark-mode", "dark-mode-2"]);
+        assert_eq!(
+            generate_item_id(IdScheme::Slug, "", "Login", today(), &existing),
+            "login"
+        );
+        assert_eq!(
+            generate_item_id(IdScheme::Slug, "", "Dark mode", today(), &existing),
+            "dark-mode-3"
+        );
+    }
+
+    #[test]
+    fn test_ulid_scheme() {
+        let id = generate_item_id(IdScheme::Ulid, "", "x", today(), &HashSet::new());
+        assert_eq!(id.len(), 26);
+        assert!(id.parse::<ulid::Ulid>().is_ok());
+    }
+}
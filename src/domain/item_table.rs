@@ -0,0 +1,446 @@
+//! Item tables for `wreckit list` and `wreckit status`
+//!
+//! Columns are chosen with `--columns id,state,title,pr,age` and rows ordered
+//! with `--sort <column>` (prefix `-` to reverse). The table format pads
+//! columns to their content and truncates long titles and branches; the tsv
+//! format writes one tab-separated row per item with no header or
+//! truncation, for piping into awk or fzf.
+
+use std::cmp::{Ordering, Reverse};
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+use crate::schemas::Item;
+
+use super::states::get_state_index;
+use super::time_display::{format_age, parse_timestamp, relative_time};
+
+/// A column of the item table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    /// Item ID
+    Id,
+
+    /// Workflow state, marked `*` if blocked and `?` if awaiting input
+    State,
+
+    /// Item title
+    Title,
+
+    /// PR number
+    Pr,
+
+    /// Time since the item was created
+    Age,
+
+    /// Time since the item was last updated
+    Updated,
+
+    /// Working branch
+    Branch,
+}
+
+/// Columns shown when `--columns` is not given
+pub const DEFAULT_COLUMNS: &[Column] = &[
+    Column::Id,
+    Column::State,
+    Column::Title,
+    Column::Pr,
+    Column::Updated,
+];
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Column::Id => "id",
+            Column::State => "state",
+            Column::Title => "title",
+            Column::Pr => "pr",
+            Column::Age => "age",
+            Column::Updated => "updated",
+            Column::Branch => "branch",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "id" => Ok(Column::Id),
+            "state" => Ok(Column::State),
+            "title" => Ok(Column::Title),
+            "pr" => Ok(Column::Pr),
+            "age" => Ok(Column::Age),
+            "updated" => Ok(Column::Updated),
+            "branch" => Ok(Column::Branch),
+            other => Err(format!(
+                "unknown column '{}' (expected id, state, title, pr, age, updated, or branch)",
+                other
+            )),
+        }
+    }
+}
+
+impl Column {
+    /// Parse a comma-separated column list such as "id,state,title"
+    pub fn parse_list(list: &str) -> Result<Vec<Column>, String> {
+        let columns = list
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(Column::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        if columns.is_empty() {
+            return Err("no columns given".to_string());
+        }
+        Ok(columns)
+    }
+
+    fn header(self) -> String {
+        self.to_string().to_uppercase()
+    }
+
+    /// Widest the column is drawn in the table format
+    fn max_width(self) -> Option<usize> {
+        match self {
+            Column::Title => Some(48),
+            Column::Branch => Some(32),
+            _ => None,
+        }
+    }
+
+    fn value(self, item: &Item, now: DateTime<Utc>, format: TableFormat) -> String {
+        let since = |timestamp: &str| parse_timestamp(timestamp).map(|at| (now - at).num_seconds());
+        match self {
+            Column::Id => item.id.clone(),
+            Column::State => {
+                let mut state = item.state_name();
+                if format == TableFormat::Table {
+                    if item.is_blocked() {
+                        state.push('*');
+                    }
+                    if item.is_awaiting_input() {
+                        state.push('?');
+                    }
+                }
+                state
+            }
+            Column::Title => item.title.clone(),
+            Column::Pr => item
+                .pr_number
+                .map(|n| format!("#{}", n))
+                .unwrap_or_default(),
+            Column::Age => since(&item.created_at).map(format_age).unwrap_or_default(),
+            Column::Updated => match format {
+                TableFormat::Table => parse_timestamp(&item.updated_at)
+                    .map(|at| relative_time(at, now))
+                    .unwrap_or_default(),
+                TableFormat::Tsv => since(&item.updated_at).map(format_age).unwrap_or_default(),
+            },
+            Column::Branch => item.branch.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Natural order of two items by this column: workflow order for
+    /// state, oldest first for age, most recent first for updated, and
+    /// items without a PR or branch last
+    fn compare(self, a: &Item, b: &Item) -> Ordering {
+        fn missing_last<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+            match (a, b) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }
+        match self {
+            Column::Id => a.id.cmp(&b.id),
+            Column::State => get_state_index(a.state)
+                .cmp(&get_state_index(b.state))
+                .then_with(|| a.state_name().cmp(&b.state_name())),
+            Column::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            Column::Pr => missing_last(a.pr_number, b.pr_number),
+            Column::Age => missing_last(
+                parse_timestamp(&a.created_at),
+                parse_timestamp(&b.created_at),
+            ),
+            Column::Updated => missing_last(
+                parse_timestamp(&a.updated_at).map(Reverse),
+                parse_timestamp(&b.updated_at).map(Reverse),
+            ),
+            Column::Branch => missing_last(a.branch.as_deref(), b.branch.as_deref()),
+        }
+    }
+}
+
+/// Sort order for the item table: a column, optionally reversed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    /// Column to sort by
+    pub column: Column,
+
+    /// Whether to reverse the column's natural order
+    pub reverse: bool,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (reverse, name) = match s.trim().strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, s),
+        };
+        Ok(SortKey {
+            column: name.parse()?,
+            reverse,
+        })
+    }
+}
+
+/// Sort items by a key, keeping ID order among equal items
+pub fn sort_items(items: &mut [Item], key: SortKey) {
+    items.sort_by(|a, b| {
+        let order = key.column.compare(a, b);
+        let order = if key.reverse { order.reverse() } else { order };
+        order.then_with(|| a.id.cmp(&b.id))
+    });
+}
+
+/// Output format for the item table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableFormat {
+    /// Aligned columns with a header
+    #[default]
+    Table,
+
+    /// Tab-separated rows, no header or truncation
+    Tsv,
+}
+
+impl fmt::Display for TableFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableFormat::Table => write!(f, "table"),
+            TableFormat::Tsv => write!(f, "tsv"),
+        }
+    }
+}
+
+impl FromStr for TableFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(TableFormat::Table),
+            "tsv" => Ok(TableFormat::Tsv),
+            other => Err(format!(
+                "unknown table format '{}' (expected table or tsv)",
+                other
+            )),
+        }
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let cut: String = text.chars().take(width.saturating_sub(1)).collect();
+        format!("{}…", cut)
+    }
+}
+
+fn cells(
+    items: &[Item],
+    columns: &[Column],
+    format: TableFormat,
+    now: DateTime<Utc>,
+) -> Vec<Vec<String>> {
+    // Either format keeps each item on one line, so flatten separators in cells
+    items
+        .iter()
+        .map(|item| {
+            columns
+                .iter()
+                .map(|column| {
+                    column
+                        .value(item, now, format)
+                        .replace(['\t', '\n', '\r'], " ")
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Render the aligned table as its header line and one line per item, in
+/// item order
+pub fn table_rows(items: &[Item], columns: &[Column], now: DateTime<Utc>) -> (String, Vec<String>) {
+    let rows = cells(items, columns, TableFormat::Table, now);
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let widest = rows
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain([column.header().len()])
+                .max()
+                .unwrap_or(0);
+            column.max_width().map_or(widest, |max| widest.min(max))
+        })
+        .collect();
+    let line = |row: &[String]| {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| {
+                let cell = truncate(cell, width);
+                let pad = width.saturating_sub(cell.chars().count());
+                format!("{}{}", cell, " ".repeat(pad))
+            })
+            .collect();
+        cells.join("  ").trim_end().to_string()
+    };
+    let headers: Vec<String> = columns.iter().map(|c| c.header()).collect();
+    (line(&headers), rows.iter().map(|row| line(row)).collect())
+}
+
+/// Render items as a table, one line per item (plus a header line in the
+/// table format)
+pub fn render_table(
+    items: &[Item],
+    columns: &[Column],
+    format: TableFormat,
+    now: DateTime<Utc>,
+) -> String {
+    let mut out = String::new();
+    if format == TableFormat::Tsv {
+        for row in cells(items, columns, format, now) {
+            out.push_str(&row.join("\t"));
+            out.push('\n');
+        }
+        return out;
+    }
+
+    let (header, rows) = table_rows(items, columns, now);
+    for line in std::iter::once(header).chain(rows) {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::WorkflowState;
+    use chrono::Duration;
+
+    fn item(id: &str, title: &str, state: WorkflowState, created: DateTime<Utc>) -> Item {
+        let mut item = Item::new(id.to_string(), title.to_string(), String::new());
+        item.state = state;
+        item.created_at = created.to_rfc3339();
+        item.updated_at = created.to_rfc3339();
+        item
+    }
+
+    #[test]
+    fn test_parse_columns_and_sort() {
+        assert_eq!(
+            Column::parse_list("id, state,age").unwrap(),
+            vec![Column::Id, Column::State, Column::Age]
+        );
+        assert!(Column::parse_list("id,owner").is_err());
+        assert!(Column::parse_list("").is_err());
+        assert_eq!(
+            "-age".parse::<SortKey>().unwrap(),
+            SortKey {
+                column: Column::Age,
+                reverse: true
+            }
+        );
+        assert_eq!("tsv".parse::<TableFormat>().unwrap(), TableFormat::Tsv);
+    }
+
+    #[test]
+    fn test_sort_items() {
+        let now = Utc::now();
+        let mut items = vec![
+            item(
+                "001-a",
+                "A",
+                WorkflowState::Implementing,
+                now - Duration::days(1),
+            ),
+            item("002-b", "B", WorkflowState::Idea, now - Duration::days(3)),
+            item("003-c", "C", WorkflowState::Done, now - Duration::days(2)),
+        ];
+        items[2].pr_number = Some(7);
+        let ids = |items: &[Item]| -> Vec<String> { items.iter().map(|i| i.id.clone()).collect() };
+
+        sort_items(&mut items, "age".parse().unwrap());
+        assert_eq!(ids(&items), vec!["002-b", "003-c", "001-a"]);
+        sort_items(&mut items, "updated".parse().unwrap());
+        assert_eq!(ids(&items), vec!["001-a", "003-c", "002-b"]);
+        sort_items(&mut items, "state".parse().unwrap());
+        assert_eq!(ids(&items), vec!["002-b", "001-a", "003-c"]);
+        sort_items(&mut items, "pr".parse().unwrap());
+        assert_eq!(ids(&items), vec!["003-c", "001-a", "002-b"]);
+        sort_items(&mut items, "-id".parse().unwrap());
+        assert_eq!(ids(&items), vec!["003-c", "002-b", "001-a"]);
+    }
+
+    #[test]
+    fn test_render_table() {
+        let now = Utc::now();
+        let mut long = item(
+            "002-long",
+            &"x".repeat(60),
+            WorkflowState::Idea,
+            now - Duration::hours(5),
+        );
+        long.pr_number = Some(12);
+        let items = vec![
+            item(
+                "001-a",
+                "Short\ttitle",
+                WorkflowState::Planned,
+                now - Duration::days(2),
+            ),
+            long,
+        ];
+        let columns = [Column::Id, Column::Title, Column::Pr, Column::Age];
+
+        let table = render_table(&items, &columns, TableFormat::Table, now);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("ID        TITLE"));
+        assert!(lines[0].ends_with("PR   AGE"));
+        assert!(lines[2].contains(&format!("{}…  #12  5h", "x".repeat(47))));
+
+        let mut multiline = items.clone();
+        multiline[0].title = "Two\nlines\r".to_string();
+        let table = render_table(&multiline, &columns, TableFormat::Table, now);
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.lines().nth(1).unwrap().contains("Two lines"));
+
+        let (header, rows) = table_rows(&multiline, &columns, now);
+        assert_eq!(header, lines[0]);
+        assert_eq!(rows.len(), multiline.len());
+        assert!(rows[1].starts_with("002-long"));
+
+        let tsv = render_table(&items, &columns, TableFormat::Tsv, now);
+        assert_eq!(
+            tsv,
+            format!(
+                "001-a\tShort title\t\t2d\n002-long\t{}\t#12\t5h\n",
+                "x".repeat(60)
+            )
+        );
+    }
+}
@@ -1,6 +1,13 @@
 //! Domain logic for workflow states and transitions
 
+mod analytics;
+mod blocking;
+mod graph;
+mod ids;
+mod item_table;
+mod recurrence;
 mod states;
+mod time_display;
 mod transitions;
 mod validation;
 
@@ -8,12 +15,28 @@ mod validation;
 #[cfg(test)]
 mod property_tests;
 
+pub use analytics::{
+    backlog_stats, format_duration, AgingItem, BacklogStats, PhaseDuration, StateCount,
+    WeeklyCount, COMPLETION_WEEKS,
+};
+pub use blocking::{is_block_lifted, parse_block_date, BLOCK_DATE_FORMAT};
+pub use graph::{render_graph, GraphFormat};
+pub use ids::{generate_item_id, next_item_id, slugify};
+pub use item_table::{
+    render_table, sort_items, table_rows, Column, SortKey, TableFormat, DEFAULT_COLUMNS,
+};
+pub use recurrence::Schedule;
 pub use states::{
-    get_allowed_next_states, get_next_state, get_state_index, is_terminal_state, WORKFLOW_STATES,
+    get_allowed_next_states, get_next_state, get_state_index, is_terminal_state, StateDef,
+    StateTable, WORKFLOW_STATES,
+};
+pub use time_display::{
+    describe_timestamp, format_age, format_local, parse_age, parse_timestamp, relative_time,
+    time_in_state, DateStyle,
 };
 pub use transitions::{apply_state_transition, TransitionResult};
 pub use validation::{
     all_stories_done, can_enter_done, can_enter_implementing, can_enter_in_pr, can_enter_planned,
-    can_enter_researched, has_pending_stories, validate_transition, ValidationContext,
-    ValidationResult,
+    can_enter_researched, check_validators, has_pending_stories, validate_item_transition,
+    validate_transition, TransitionValidator, ValidationContext, ValidationResult,
 };
@@ -0,0 +1,203 @@
+//! Cron-like schedules for recurring items
+//!
+//! A schedule is a standard five-field cron expression (minute, hour, day of
+//! month, month, day of week) evaluated in UTC, or one of the aliases
+//! `@hourly`, `@daily`, `@weekly`, `@monthly`, and `@yearly`. Fields accept
+//! `*`, numbers, ranges (`1-5`), lists (`1,15`), and steps (`*/15`, `0-30/10`).
+//! As in cron, when both day fields are restricted a day matching either one
+//! is due.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+
+/// How many days ahead to look for the next due time; far enough for any
+/// valid schedule (`0 0 29 2 *` waits up to eight years)
+const SEARCH_DAYS: i64 = 366 * 9;
+
+/// A parsed recurrence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days_of_month: Vec<u32>,
+    months: Vec<u32>,
+    days_of_week: Vec<u32>,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+/// Expand one field into its sorted values
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<Vec<u32>, String> {
+    let invalid = || format!("invalid {} field '{}'", name, field);
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                start.parse().map_err(|_| invalid())?,
+                end.parse().map_err(|_| invalid())?,
+            )
+        } else {
+            let value = range.parse().map_err(|_| invalid())?;
+            // `5/10` means every 10 starting at 5
+            if part.contains('/') {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+        if start < min || end > max || start > end {
+            return Err(format!(
+                "{} field '{}' is outside {}-{}",
+                name, field, min, max
+            ));
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+impl Schedule {
+    /// Parse a cron expression or alias
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "expected 5 fields (minute hour day month weekday) or an alias, got '{}'",
+                expr
+            ));
+        }
+        // Sunday may be written as 0 or 7
+        let mut days_of_week: Vec<u32> = parse_field(fields[4], 0, 7, "weekday")?
+            .into_iter()
+            .map(|day| day % 7)
+            .collect();
+        days_of_week.sort_unstable();
+        days_of_week.dedup();
+        Ok(Schedule {
+            minutes: parse_field(fields[0], 0, 59, "minute")?,
+            hours: parse_field(fields[1], 0, 23, "hour")?,
+            days_of_month: parse_field(fields[2], 1, 31, "day")?,
+            months: parse_field(fields[3], 1, 12, "month")?,
+            days_of_week,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if !self.months.contains(&date.month()) {
+            return false;
+        }
+        let dom = self.days_of_month.contains(&date.day());
+        let dow = self
+            .days_of_week
+            .contains(&date.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+
+    /// The first due time strictly after `after`, or `None` if the schedule
+    /// can never fire (e.g. `0 0 31 2 *`)
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date_naive();
+        for _ in 0..SEARCH_DAYS {
+            if self.matches_day(date) {
+                for &hour in &self.hours {
+                    for &minute in &self.minutes {
+                        let time = Utc.from_utc_datetime(&date.and_hms_opt(hour, minute, 0)?);
+                        if time >= start {
+                            return Some(time);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expr: &str, after: &str) -> String {
+        Schedule::parse(expr)
+            .unwrap()
+            .next_after(at(after))
+            .unwrap()
+            .to_rfc3339()
+    }
+
+    #[test]
+    fn test_next_after() {
+        // 2024-07-03 is a Wednesday
+        assert_eq!(
+            next("0 9 * * 1", "2024-07-03T12:00:00Z"),
+            "2024-07-08T09:00:00+00:00"
+        );
+        assert_eq!(
+            next("@weekly", "2024-07-03T12:00:00Z"),
+            "2024-07-07T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2024-07-03T12:15:00Z"),
+            "2024-07-03T12:30:00+00:00"
+        );
+        assert_eq!(
+            next("30 8-10/2 * * *", "2024-07-03T10:31:10Z"),
+            "2024-07-04T08:30:00+00:00"
+        );
+        // Either day field matches when both are restricted
+        assert_eq!(
+            next("0 0 15 * 7", "2024-07-08T00:00:00Z"),
+            "2024-07-14T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 29 2 *", "2024-03-01T00:00:00Z"),
+            "2028-02-29T00:00:00+00:00"
+        );
+        assert_eq!(
+            Schedule::parse("0 0 31 2 *")
+                .unwrap()
+                .next_after(at("2024-01-01T00:00:00Z")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Schedule::parse("weekly").is_err());
+        assert!(Schedule::parse("0 24 * * *").is_err());
+        assert!(Schedule::parse("*/0 * * * *").is_err());
+        assert!(Schedule::parse("0 0 5-1 * *").is_err());
+        assert!(Schedule::parse("0 0 1,15 */3 1-5").is_ok());
+    }
+}
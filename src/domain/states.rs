@@ -2,8 +2,10 @@
 //!
 //! The state machine follows a linear progression:
 //! idea → researched → planned → implementing → in_pr → done
+//!
+//! Config-defined states extend that sequence through a [`StateTable`].
 
-use crate::schemas::WorkflowState;
+use crate::schemas::{CustomStateConfig, WorkflowState};
 
 /// The canonical ordering of workflow states.
 ///
@@ -63,9 +65,137 @@ pub fn get_allowed_next_states(current: WorkflowState) -> Vec<WorkflowState> {
     }
 }
 
-/// Check if a state is the terminal state (done).
+/// Check if a state is terminal (done or abandoned).
 pub fn is_terminal_state(state: WorkflowState) -> bool {
-    state == WorkflowState::Done
+    matches!(state, WorkflowState::Done | WorkflowState::Abandoned)
+}
+
+/// A state in the workflow ordering, built-in or config-defined
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateDef {
+    /// State name as stored on items (e.g., "in_pr", "qa")
+    pub name: String,
+
+    /// The built-in state, or None for a custom state
+    pub builtin: Option<WorkflowState>,
+
+    /// File that must exist to enter the state, relative to the item directory
+    pub artifact: Option<String>,
+
+    /// Shell command that must succeed to enter the state
+    pub command: Option<String>,
+}
+
+impl StateDef {
+    fn builtin(state: WorkflowState) -> Self {
+        StateDef {
+            name: state.to_string(),
+            builtin: Some(state),
+            artifact: None,
+            command: None,
+        }
+    }
+
+    /// Whether this is a config-defined state
+    pub fn is_custom(&self) -> bool {
+        self.builtin.is_none()
+    }
+}
+
+/// The full state ordering: WORKFLOW_STATES plus any config-defined states.
+///
+/// Custom states may only follow `in_pr`, `done`, or another custom state, so
+/// the agent phases keep their linear idea → in_pr progression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateTable {
+    states: Vec<StateDef>,
+}
+
+impl Default for StateTable {
+    fn default() -> Self {
+        StateTable {
+            states: WORKFLOW_STATES
+                .iter()
+                .map(|&s| StateDef::builtin(s))
+                .collect(),
+        }
+    }
+}
+
+impl StateTable {
+    /// Build the table from config, inserting each custom state after the
+    /// state it names (in declaration order).
+    ///
+    /// # Errors
+    /// Returns a description of the problem if a state is unnamed, duplicated,
+    /// or placed before in_pr or after an unknown state.
+    pub fn from_config(custom: &[CustomStateConfig]) -> Result<Self, String> {
+        let mut table = StateTable::default();
+        let first_allowed = get_state_index(WorkflowState::InPr);
+
+        for state in custom {
+            if state.name.trim().is_empty() {
+                return Err("custom state name cannot be empty".to_string());
+            }
+            if table.get(&state.name).is_some() {
+                return Err(format!("state '{}' is defined more than once", state.name));
+            }
+            let anchor = table.index_of(&state.after).ok_or_else(|| {
+                format!(
+                    "state '{}' follows unknown state '{}'",
+                    state.name, state.after
+                )
+            })?;
+            if anchor < first_allowed {
+                return Err(format!(
+                    "state '{}' must follow in_pr or later, not '{}'",
+                    state.name, state.after
+                ));
+            }
+
+            let mut position = anchor + 1;
+            while table.states.get(position).is_some_and(StateDef::is_custom) {
+                position += 1;
+            }
+            table.states.insert(
+                position,
+                StateDef {
+                    name: state.name.clone(),
+                    builtin: None,
+                    artifact: state.artifact.clone(),
+                    command: state.command.clone(),
+                },
+            );
+        }
+        Ok(table)
+    }
+
+    /// All states in order
+    pub fn states(&self) -> &[StateDef] {
+        &self.states
+    }
+
+    /// Look up a state by name
+    pub fn get(&self, name: &str) -> Option<&StateDef> {
+        self.states.iter().find(|s| s.name == name)
+    }
+
+    /// Position of a state in the ordering
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.states.iter().position(|s| s.name == name)
+    }
+
+    /// The state after `name`, or None at the end of the table
+    pub fn next(&self, name: &str) -> Option<&StateDef> {
+        self.index_of(name).and_then(|i| self.states.get(i + 1))
+    }
+
+    /// The built-in state recorded on an item while it is in `name`:
+    /// the nearest built-in state at or before it
+    pub fn builtin_for(&self, name: &str) -> Option<WorkflowState> {
+        let index = self.index_of(name)?;
+        self.states[..=index].iter().rev().find_map(|s| s.builtin)
+    }
 }
 
 #[cfg(test)]
@@ -117,5 +247,56 @@ mod tests {
         assert!(!is_terminal_state(WorkflowState::Implementing));
         assert!(!is_terminal_state(WorkflowState::InPr));
         assert!(is_terminal_state(WorkflowState::Done));
+        assert!(is_terminal_state(WorkflowState::Abandoned));
+        assert_eq!(get_next_state(WorkflowState::Abandoned), None);
+    }
+
+    fn custom(name: &str, after: &str) -> CustomStateConfig {
+        CustomStateConfig {
+            name: name.to_string(),
+            after: after.to_string(),
+            artifact: None,
+            command: None,
+        }
+    }
+
+    #[test]
+    fn test_state_table_default_matches_workflow_states() {
+        let table = StateTable::default();
+        let names: Vec<&str> = table.states().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["idea", "researched", "planned", "implementing", "in_pr", "done"]);
+        assert_eq!(table.next("in_pr").unwrap().builtin, Some(WorkflowState::Done));
+        assert!(table.next("done").is_none());
+    }
+
+    #[test]
+    fn test_state_table_inserts_custom_states_in_order() {
+        let table = StateTable::from_config(&[
+            custom("qa", "in_pr"),
+            custom("deployed", "done"),
+            custom("staging", "in_pr"),
+            custom("verified", "deployed"),
+        ])
+        .unwrap();
+        let names: Vec<&str> = table.states().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "idea", "researched", "planned", "implementing", "in_pr", "qa", "staging", "done",
+                "deployed", "verified"
+            ]
+        );
+        assert_eq!(table.builtin_for("staging"), Some(WorkflowState::InPr));
+        assert_eq!(table.builtin_for("verified"), Some(WorkflowState::Done));
+        assert!(table.get("qa").unwrap().is_custom());
+    }
+
+    #[test]
+    fn test_state_table_rejects_bad_config() {
+        assert!(StateTable::from_config(&[custom("qa", "planned")]).is_err());
+        assert!(StateTable::from_config(&[custom("qa", "nowhere")]).is_err());
+        assert!(StateTable::from_config(&[custom("done", "in_pr")]).is_err());
+        assert!(StateTable::from_config(&[custom("qa", "in_pr"), custom("qa", "done")]).is_err());
+        assert!(StateTable::from_config(&[custom(" ", "in_pr")]).is_err());
     }
 }
@@ -0,0 +1,195 @@
+//! Human-friendly times for CLI tables and the TUI
+//!
+//! Item timestamps are stored as RFC 3339 strings in UTC. For display they
+//! are shown relative to now ("3h ago", "2d in implementing") or, where an
+//! absolute time is wanted, in local time with the date ordered the way the
+//! operator's locale (`LC_ALL`, `LC_TIME`, `LANG`) expects.
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::schemas::Item;
+
+/// Parse a stored RFC 3339 timestamp
+pub fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Format a duration to its largest unit (e.g. "3h", "2d")
+pub fn format_age(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    if seconds >= 86_400 {
+        format!("{}d", seconds / 86_400)
+    } else if seconds >= 3600 {
+        format!("{}h", seconds / 3600)
+    } else if seconds >= 60 {
+        format!("{}m", seconds / 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Parse an age like "30m", "12h", "14d", or "2w" into seconds, the
+/// inverse of [`format_age`]
+pub fn parse_age(age: &str) -> Result<i64, String> {
+    let age = age.trim();
+    let invalid = || format!("expected an age like 12h, 14d, or 2w, got '{}'", age);
+    let split = age.len().saturating_sub(1);
+    let (count, unit) = (age.get(..split).ok_or_else(invalid)?, &age[split..]);
+    let count: i64 = count.parse().map_err(|_| invalid())?;
+    let unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => return Err(invalid()),
+    };
+    Ok(count.saturating_mul(unit))
+}
+
+/// A time relative to now: "just now", "3h ago", or "in 2d"
+pub fn relative_time(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - at).num_seconds();
+    if seconds.abs() < 60 {
+        "just now".to_string()
+    } else if seconds > 0 {
+        format!("{} ago", format_age(seconds))
+    } else {
+        format!("in {}", format_age(-seconds))
+    }
+}
+
+/// How long an item has been in its current state (e.g. "2d in
+/// implementing"), measured from its last state change or its creation
+pub fn time_in_state(item: &Item, now: DateTime<Utc>) -> Option<String> {
+    let entered = match item.state_history.last() {
+        Some(change) => parse_timestamp(&change.at),
+        None => parse_timestamp(&item.created_at),
+    }?;
+    Some(format!(
+        "{} in {}",
+        format_age((now - entered).num_seconds()),
+        item.state_name()
+    ))
+}
+
+/// Order of the date parts in absolute times
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateStyle {
+    /// "Mar 5, 2026 14:03" (US English)
+    MonthFirst,
+    /// "5 Mar 2026 14:03" (most European locales)
+    DayFirst,
+    /// "2026-03-05 14:03" (ISO 8601; East Asian locales, C, and unknown)
+    Iso,
+}
+
+impl DateStyle {
+    /// Style for a POSIX locale name such as "en_US.UTF-8" or "de_DE"
+    pub fn for_locale(locale: &str) -> Self {
+        let name = locale.split(['.', '@']).next().unwrap_or_default();
+        let (language, region) = name.split_once('_').unwrap_or((name, ""));
+        match (language, region) {
+            ("en", "US" | "") => DateStyle::MonthFirst,
+            ("ja" | "zh" | "ko" | "sv" | "lt" | "hu", _) | ("C" | "POSIX" | "", _) => {
+                DateStyle::Iso
+            }
+            _ => DateStyle::DayFirst,
+        }
+    }
+
+    /// Style for the operator's locale
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map_or(DateStyle::Iso, |locale| DateStyle::for_locale(&locale))
+    }
+
+    fn pattern(self) -> &'static str {
+        match self {
+            DateStyle::MonthFirst => "%b %-d, %Y %H:%M",
+            DateStyle::DayFirst => "%-d %b %Y %H:%M",
+            DateStyle::Iso => "%Y-%m-%d %H:%M",
+        }
+    }
+}
+
+/// An absolute time in the operator's timezone and date style
+pub fn format_local(at: DateTime<Utc>, style: DateStyle) -> String {
+    at.with_timezone(&Local).format(style.pattern()).to_string()
+}
+
+/// A stored timestamp as "<local time> (<relative>)", or the raw string if
+/// it cannot be parsed
+pub fn describe_timestamp(timestamp: &str, now: DateTime<Utc>, style: DateStyle) -> String {
+    match parse_timestamp(timestamp) {
+        Some(at) => format!("{} ({})", format_local(at, style), relative_time(at, now)),
+        None => timestamp.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{StateChange, WorkflowState};
+    use chrono::Duration;
+
+    #[test]
+    fn test_relative_time() {
+        let now = Utc::now();
+        assert_eq!(relative_time(now - Duration::seconds(20), now), "just now");
+        assert_eq!(relative_time(now - Duration::minutes(5), now), "5m ago");
+        assert_eq!(relative_time(now - Duration::hours(3), now), "3h ago");
+        assert_eq!(relative_time(now - Duration::hours(50), now), "2d ago");
+        assert_eq!(relative_time(now + Duration::hours(2), now), "in 2h");
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("14d").unwrap(), 14 * 86_400);
+        assert_eq!(parse_age("2w").unwrap(), 14 * 86_400);
+        assert_eq!(parse_age("90m").unwrap(), 5400);
+        assert!(parse_age("14").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("").is_err());
+        assert!(parse_age("3y").is_err());
+    }
+
+    #[test]
+    fn test_time_in_state() {
+        let now = Utc::now();
+        let mut item = Item::new("001-a".to_string(), "A".to_string(), String::new());
+        item.created_at = (now - Duration::days(5)).to_rfc3339();
+        assert_eq!(time_in_state(&item, now).unwrap(), "5d in idea");
+
+        item.state = WorkflowState::Implementing;
+        item.state_history.push(StateChange {
+            state: "implementing".to_string(),
+            at: (now - Duration::hours(49)).to_rfc3339(),
+        });
+        assert_eq!(time_in_state(&item, now).unwrap(), "2d in implementing");
+    }
+
+    #[test]
+    fn test_date_style() {
+        assert_eq!(DateStyle::for_locale("en_US.UTF-8"), DateStyle::MonthFirst);
+        assert_eq!(DateStyle::for_locale("en_GB.UTF-8"), DateStyle::DayFirst);
+        assert_eq!(DateStyle::for_locale("de_DE@euro"), DateStyle::DayFirst);
+        assert_eq!(DateStyle::for_locale("ja_JP.UTF-8"), DateStyle::Iso);
+        assert_eq!(DateStyle::for_locale("C.UTF-8"), DateStyle::Iso);
+
+        let at = parse_timestamp("2026-03-05T14:03:00Z").unwrap();
+        let local = at.with_timezone(&Local);
+        let day = local.format("%-d").to_string();
+        assert!(format_local(at, DateStyle::DayFirst).starts_with(&format!("{} ", day)));
+        assert!(format_local(at, DateStyle::Iso).starts_with(&local.format("%Y-%m-%d").to_string()));
+        assert_eq!(
+            describe_timestamp("not a time", Utc::now(), DateStyle::Iso),
+            "not a time"
+        );
+    }
+}
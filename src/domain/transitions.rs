@@ -5,7 +5,7 @@
 use crate::schemas::Item;
 
 use super::states::get_next_state;
-use super::validation::{validate_transition, ValidationContext};
+use super::validation::{validate_item_transition, ValidationContext};
 
 /// Result of a state transition attempt
 #[derive(Debug)]
@@ -54,7 +54,7 @@ impl TransitionResult {
 ///
 /// This function:
 /// - Never mutates the input item
-/// - Validates the transition before applying
+/// - Validates the transition (including extra validators) before applying
 /// - Returns a new Item with updated state and updated_at
 /// - Returns an error if transition is invalid
 ///
@@ -74,7 +74,7 @@ pub fn apply_state_transition(item: &Item, ctx: &ValidationContext) -> Transitio
         }
     };
 
-    let validation = validate_transition(item.state, next_state, ctx);
+    let validation = validate_item_transition(item, next_state, ctx);
     if !validation.valid {
         return TransitionResult::Error {
             error: validation.reason.unwrap_or_else(|| "Transition validation failed".to_string()),
@@ -1,9 +1,28 @@
 //! Validation rules for state transitions
+//!
+//! The built-in `can_enter_*` rules check artifacts. Extra checks, such as
+//! WASM plugins from `.wreckit/plugins/`, implement [`TransitionValidator`]
+//! and run after them.
 
-use crate::schemas::{Prd, WorkflowState};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::schemas::{Item, Prd, WorkflowState};
 
 use super::get_allowed_next_states;
 
+/// An extra check run when an item enters a state
+pub trait TransitionValidator: fmt::Debug + Send + Sync {
+    /// Name shown in failure reasons
+    fn name(&self) -> &str;
+
+    /// Check whether `item` may enter `state` (a built-in or custom state name)
+    fn can_enter(&self, item: &Item, state: &str, prd: Option<&Prd>) -> ValidationResult;
+
+    /// Problems found in a PRD; empty if it is acceptable
+    fn lint_prd(&self, prd: &Prd) -> Vec<String>;
+}
+
 /// Context required for validating state transitions
 #[derive(Debug, Clone)]
 pub struct ValidationContext {
@@ -21,6 +40,9 @@ pub struct ValidationContext {
 
     /// Whether the PR is merged
     pub pr_merged: bool,
+
+    /// Extra checks run after the built-in rules
+    pub validators: Vec<Arc<dyn TransitionValidator>>,
 }
 
 impl Default for ValidationContext {
@@ -31,6 +53,7 @@ impl Default for ValidationContext {
             prd: None,
             has_pr: false,
             pr_merged: false,
+            validators: Vec::new(),
         }
     }
 }
@@ -151,9 +174,54 @@ pub fn validate_transition(
         WorkflowState::InPr => can_enter_in_pr(ctx.prd.as_ref(), ctx.has_pr),
         WorkflowState::Done => can_enter_done(ctx.pr_merged),
         WorkflowState::Idea => ValidationResult::failure("cannot transition to idea state"),
+        WorkflowState::Abandoned => {
+            ValidationResult::failure("items are abandoned with `wreckit abandon`")
+        }
     }
 }
 
+/// Run the extra validators for `item` entering `state`, stopping at the
+/// first failure. The PRD is also linted when entering "planned".
+pub fn check_validators(item: &Item, state: &str, ctx: &ValidationContext) -> ValidationResult {
+    for validator in &ctx.validators {
+        let result = validator.can_enter(item, state, ctx.prd.as_ref());
+        if !result.valid {
+            return ValidationResult::failure(format!(
+                "{}: {}",
+                validator.name(),
+                result.reason.unwrap_or_else(|| "rejected".to_string())
+            ));
+        }
+        if state == "planned" {
+            if let Some(ref prd) = ctx.prd {
+                let problems = validator.lint_prd(prd);
+                if !problems.is_empty() {
+                    return ValidationResult::failure(format!(
+                        "{}: prd.json: {}",
+                        validator.name(),
+                        problems.join("; ")
+                    ));
+                }
+            }
+        }
+    }
+    ValidationResult::success()
+}
+
+/// Validate moving `item` to `target`: the built-in rules, then the extra
+/// validators
+pub fn validate_item_transition(
+    item: &Item,
+    target: WorkflowState,
+    ctx: &ValidationContext,
+) -> ValidationResult {
+    let result = validate_transition(item.state, target, ctx);
+    if !result.valid {
+        return result;
+    }
+    check_validators(item, &target.to_string(), ctx)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,6 +338,7 @@ mod tests {
             prd: Some(prd),
             has_pr: false,
             pr_merged: false,
+            validators: Vec::new(),
         };
 
         // Valid transition: idea -> researched
@@ -321,4 +390,52 @@ mod tests {
         let result = validate_transition(WorkflowState::Done, WorkflowState::Idea, &ctx);
         assert!(!result.valid);
     }
+
+    #[derive(Debug)]
+    struct NoTodos;
+
+    impl TransitionValidator for NoTodos {
+        fn name(&self) -> &str {
+            "no-todos"
+        }
+
+        fn can_enter(&self, item: &Item, state: &str, _prd: Option<&Prd>) -> ValidationResult {
+            if state == "in_pr" && item.overview.contains("TODO") {
+                return ValidationResult::failure("overview has a TODO");
+            }
+            ValidationResult::success()
+        }
+
+        fn lint_prd(&self, prd: &Prd) -> Vec<String> {
+            prd.user_stories
+                .iter()
+                .filter(|story| story.acceptance_criteria.is_empty())
+                .map(|story| format!("{} has no acceptance criteria", story.id))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_validate_item_transition_runs_validators() {
+        let item = Item::new("001".to_string(), "Test".to_string(), "TODO".to_string())
+            .with_state(WorkflowState::Researched);
+        let ctx = ValidationContext {
+            has_plan_md: true,
+            prd: Some(make_prd_with_stories(&[StoryStatus::Pending])),
+            validators: vec![Arc::new(NoTodos)],
+            ..Default::default()
+        };
+        let result = validate_item_transition(&item, WorkflowState::Planned, &ctx);
+        assert_eq!(
+            result.reason.as_deref(),
+            Some("no-todos: prd.json: US-001 has no acceptance criteria")
+        );
+
+        let result = check_validators(&item, "in_pr", &ctx);
+        assert_eq!(
+            result.reason.as_deref(),
+            Some("no-todos: overview has a TODO")
+        );
+        assert!(check_validators(&item, "qa", &ctx).valid);
+    }
 }
@@ -46,6 +46,26 @@ pub enum WreckitError {
     #[error("Operation interrupted")]
     Interrupted,
 
+    /// The agent stopped to ask the operator a question
+    #[error("Awaiting input: {0}")]
+    AwaitingInput(String),
+
+    /// The agent's API quota or rate limit was hit
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        /// Seconds to wait before retrying, if known
+        retry_after: Option<u64>,
+    },
+
+    /// Another process holds the repository lock
+    #[error("Repository locked: {0}")]
+    Locked(String),
+
+    /// A write was attempted in read-only mode
+    #[error("Read-only mode: refusing to {0}")]
+    ReadOnly(String),
+
     /// Workflow state transition error
     #[error("State transition error: {0}")]
     StateTransition(String),
@@ -72,6 +92,10 @@ impl WreckitError {
             WreckitError::GitError(_) => "GIT_ERROR",
             WreckitError::Timeout(_) => "TIMEOUT",
             WreckitError::Interrupted => "INTERRUPTED",
+            WreckitError::AwaitingInput(_) => "AWAITING_INPUT",
+            WreckitError::RateLimited { .. } => "RATE_LIMITED",
+            WreckitError::Locked(_) => "LOCKED",
+            WreckitError::ReadOnly(_) => "READ_ONLY",
             WreckitError::StateTransition(_) => "STATE_TRANSITION",
             WreckitError::Io(_) => "IO_ERROR",
             WreckitError::Wrapped { .. } => "WRAPPED_ERROR",
@@ -87,10 +111,165 @@ impl WreckitError {
     }
 }
 
+/// Broad cause of a failure, used to suggest what to do next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureClass {
+    /// Git or GitHub credentials are missing or rejected
+    Auth,
+    /// A remote could not be reached
+    Network,
+    /// The branch conflicts with or was rejected by the remote
+    Conflict,
+    /// Artifacts, metadata, or preconditions did not check out
+    Validation,
+    /// The agent stopped without finishing its task
+    AgentGaveUp,
+    /// The agent ran out of time
+    Timeout,
+    /// The agent was rate limited
+    RateLimited,
+    /// The run was cancelled by the operator
+    Interrupted,
+    /// The agent asked the operator a question
+    AwaitingInput,
+    /// The configuration or agent setup is invalid
+    Config,
+    /// Another wreckit process holds the repository lock
+    Locked,
+    /// Anything else
+    Unknown,
+}
+
+/// Message fragments that identify a class, checked in order
+const MESSAGE_CLASSES: &[(FailureClass, &[&str])] = &[
+    (
+        FailureClass::Auth,
+        &[
+            "authentication failed",
+            "gh auth login",
+            "bad credentials",
+            "permission denied (publickey)",
+            "could not read username",
+            "http 401",
+            "http 403",
+        ],
+    ),
+    (
+        FailureClass::Network,
+        &[
+            "could not resolve host",
+            "connection refused",
+            "connection reset",
+            "network is unreachable",
+            "failed to connect",
+            "connection timed out",
+        ],
+    ),
+    (
+        FailureClass::Conflict,
+        &[
+            "conflict",
+            "non-fast-forward",
+            "[rejected]",
+            "would be overwritten",
+            "have diverged",
+        ],
+    ),
+];
+
+impl FailureClass {
+    /// Stable machine-readable name (e.g. "agent_gave_up")
+    pub fn code(self) -> &'static str {
+        match self {
+            FailureClass::Auth => "auth",
+            FailureClass::Network => "network",
+            FailureClass::Conflict => "conflict",
+            FailureClass::Validation => "validation",
+            FailureClass::AgentGaveUp => "agent_gave_up",
+            FailureClass::Timeout => "timeout",
+            FailureClass::RateLimited => "rate_limited",
+            FailureClass::Interrupted => "interrupted",
+            FailureClass::AwaitingInput => "awaiting_input",
+            FailureClass::Config => "config",
+            FailureClass::Locked => "locked",
+            FailureClass::Unknown => "unknown",
+        }
+    }
+
+    /// What the operator should do next; `id` fills in item-specific commands
+    pub fn hint(self, id: Option<&str>) -> Option<String> {
+        let id = id.unwrap_or("<id>");
+        let hint = match self {
+            FailureClass::Auth => {
+                "authenticate with `gh auth login` (and check git credentials), then run `wreckit retry {id}`"
+            }
+            FailureClass::Network => "check your connection to the remote, then run `wreckit retry {id}`",
+            FailureClass::Conflict => {
+                "rebase the item branch onto the base branch and resolve conflicts, then run `wreckit retry {id}`"
+            }
+            FailureClass::Validation => {
+                "inspect `wreckit show {id}` and the item's artifacts, then run `wreckit retry {id} --with-feedback \"...\"`"
+            }
+            FailureClass::AgentGaveUp => {
+                "read the latest transcript in the item's logs and run `wreckit retry {id} --with-feedback \"...\"`"
+            }
+            FailureClass::Timeout => {
+                "raise `timeout_seconds` in .wreckit/config.json or split the item, then run `wreckit retry {id}`"
+            }
+            FailureClass::RateLimited => "wait for the rate limit to reset, then run `wreckit retry {id}`",
+            FailureClass::AwaitingInput => "answer with `wreckit answer {id} \"...\"`, then run `wreckit run {id}`",
+            FailureClass::Config => "fix .wreckit/config.json (see `wreckit doctor`) and try again",
+            FailureClass::Locked => {
+                "wait for the other wreckit process to finish, or rerun with `--force-unlock` if it is stuck"
+            }
+            FailureClass::Interrupted | FailureClass::Unknown => return None,
+        };
+        Some(hint.replace("{id}", id))
+    }
+}
+
+impl WreckitError {
+    /// Classify the error by cause, looking at message text where the
+    /// variant alone is ambiguous (git and agent failures)
+    pub fn classify(&self) -> FailureClass {
+        match self {
+            WreckitError::RateLimited { .. } => return FailureClass::RateLimited,
+            WreckitError::Interrupted => return FailureClass::Interrupted,
+            WreckitError::AwaitingInput(_) => return FailureClass::AwaitingInput,
+            WreckitError::Timeout(_) => return FailureClass::Timeout,
+            WreckitError::ConfigError(_) => return FailureClass::Config,
+            WreckitError::Locked(_) => return FailureClass::Locked,
+            WreckitError::ReadOnly(_) => return FailureClass::Unknown,
+            _ => {}
+        }
+        let message = self.to_string().to_lowercase();
+        if let Some((class, _)) = MESSAGE_CLASSES
+            .iter()
+            .find(|(_, needles)| needles.iter().any(|n| message.contains(n)))
+        {
+            return *class;
+        }
+        match self {
+            WreckitError::AgentError(_) if message.contains("failed to spawn agent") => {
+                FailureClass::Config
+            }
+            WreckitError::AgentError(_) => FailureClass::AgentGaveUp,
+            WreckitError::StateTransition(_)
+            | WreckitError::SchemaValidation(_)
+            | WreckitError::InvalidJson(_)
+            | WreckitError::FileNotFound(_) => FailureClass::Validation,
+            _ => FailureClass::Unknown,
+        }
+    }
+}
+
 /// Convert an error to an appropriate exit code
 pub fn to_exit_code(error: &WreckitError) -> i32 {
     match error {
         WreckitError::Interrupted => 130, // Standard Unix exit code for SIGINT
+        WreckitError::RateLimited { .. }
+        | WreckitError::Locked(_)
+        | WreckitError::AwaitingInput(_) => 75, // EX_TEMPFAIL: try again later
         _ => 1,
     }
 }
@@ -110,6 +289,62 @@ mod tests {
         assert_eq!(WreckitError::GitError("test".into()).code(), "GIT_ERROR");
         assert_eq!(WreckitError::Timeout("test".into()).code(), "TIMEOUT");
         assert_eq!(WreckitError::Interrupted.code(), "INTERRUPTED");
+        let limited = WreckitError::RateLimited {
+            message: "429".into(),
+            retry_after: Some(30),
+        };
+        assert_eq!(limited.code(), "RATE_LIMITED");
+        assert_eq!(to_exit_code(&limited), 75);
+    }
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            (
+                WreckitError::GitError("gh: HTTP 401: Bad credentials".into()),
+                FailureClass::Auth,
+            ),
+            (
+                WreckitError::GitError("fatal: unable to access: Could not resolve host: github.com".into()),
+                FailureClass::Network,
+            ),
+            (
+                WreckitError::GitError("! [rejected] main -> main (non-fast-forward)".into()),
+                FailureClass::Conflict,
+            ),
+            (
+                WreckitError::AgentError("agent finished without emitting the completion signal".into()),
+                FailureClass::AgentGaveUp,
+            ),
+            (
+                WreckitError::AgentError("Failed to spawn agent: No such file".into()),
+                FailureClass::Config,
+            ),
+            (
+                WreckitError::StateTransition("research phase did not produce the required artifacts".into()),
+                FailureClass::Validation,
+            ),
+            (WreckitError::Interrupted, FailureClass::Interrupted),
+            (
+                WreckitError::AwaitingInput("Which database?".into()),
+                FailureClass::AwaitingInput,
+            ),
+            (
+                WreckitError::Locked("pid 42 has held .wreckit/lock (wreckit run 001-conflict)".into()),
+                FailureClass::Locked,
+            ),
+            (WreckitError::GitError("exit status 128".into()), FailureClass::Unknown),
+        ];
+        for (error, class) in cases {
+            assert_eq!(error.classify(), class, "{}", error);
+        }
+
+        let hint = FailureClass::Auth.hint(Some("001-login")).unwrap();
+        assert!(hint.contains("gh auth login"));
+        assert!(hint.ends_with("`wreckit retry 001-login`"));
+        assert!(FailureClass::Conflict.hint(None).unwrap().contains("wreckit retry <id>"));
+        assert_eq!(FailureClass::Interrupted.hint(None), None);
+        assert_eq!(FailureClass::AgentGaveUp.code(), "agent_gave_up");
     }
 
     #[test]
@@ -117,6 +352,7 @@ mod tests {
         assert_eq!(to_exit_code(&WreckitError::Interrupted), 130);
         assert_eq!(to_exit_code(&WreckitError::RepoNotFound("test".into())), 1);
         assert_eq!(to_exit_code(&WreckitError::GitError("test".into())), 1);
+        assert_eq!(to_exit_code(&WreckitError::Locked("test".into())), 75);
     }
 
     #[test]
@@ -0,0 +1,188 @@
+//! Snapshots of item metadata
+//!
+//! Before a phase mutates an item, its directory is copied to
+//! `.wreckit/backups/<id>/<timestamp>/` so a bad agent run that corrupts
+//! prd.json or research.md can be undone. Recorded transcripts are left out;
+//! they are append-only and can be large.
+
+use std::fs;
+use std::path::Path;
+
+use crate::errors::{Result, WreckitError};
+
+use super::paths::{get_item_backups_dir, get_item_dir};
+use super::read_only::ensure_writable;
+
+/// Subdirectories of an item that are not snapshotted or restored
+const EXCLUDED_DIRS: &[&str] = &["transcripts"];
+
+/// Timestamp format used for snapshot directory names
+const SNAPSHOT_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if entry.file_type()?.is_dir() {
+            if !EXCLUDED_DIRS.contains(&name.to_string_lossy().as_ref()) {
+                copy_dir(&entry.path(), &to.join(&name))?;
+            }
+        } else {
+            fs::copy(entry.path(), to.join(&name))?;
+        }
+    }
+    Ok(())
+}
+
+/// List an item's snapshots, oldest first.
+///
+/// # Arguments
+/// * `root` - Path to the repository root
+/// * `id` - Item ID
+///
+/// # Returns
+/// Snapshot timestamps (directory names)
+pub fn list_backups(root: &Path, id: &str) -> Result<Vec<String>> {
+    let dir = get_item_backups_dir(root, id);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            snapshots.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    snapshots.sort();
+    Ok(snapshots)
+}
+
+/// Snapshot an item's directory, keeping at most `keep` snapshots.
+///
+/// # Arguments
+/// * `root` - Path to the repository root
+/// * `id` - Item ID
+/// * `keep` - Number of snapshots to retain (oldest are removed first)
+///
+/// # Returns
+/// The new snapshot's timestamp, or None if the item has no directory yet
+///
+/// # Errors
+/// * `Io` - If the snapshot cannot be written or old ones removed
+pub fn snapshot_item(root: &Path, id: &str, keep: usize) -> Result<Option<String>> {
+    ensure_writable(|| format!("back up {}", id))?;
+    let item_dir = get_item_dir(root, id);
+    if !item_dir.exists() {
+        return Ok(None);
+    }
+
+    let timestamp = chrono::Utc::now().format(SNAPSHOT_FORMAT).to_string();
+    copy_dir(&item_dir, &get_item_backups_dir(root, id).join(&timestamp))?;
+
+    let snapshots = list_backups(root, id)?;
+    let excess = snapshots.len().saturating_sub(keep.max(1));
+    for old in &snapshots[..excess] {
+        fs::remove_dir_all(get_item_backups_dir(root, id).join(old))?;
+    }
+    Ok(Some(timestamp))
+}
+
+/// Restore an item's directory from a snapshot.
+///
+/// Files not in the snapshot are removed; transcripts are kept as they are.
+///
+/// # Errors
+/// * `FileNotFound` - If the snapshot does not exist
+/// * `Io` - If the item directory cannot be rewritten
+pub fn restore_backup(root: &Path, id: &str, timestamp: &str) -> Result<()> {
+    ensure_writable(|| format!("restore {}", id))?;
+    let snapshot = get_item_backups_dir(root, id).join(timestamp);
+    if timestamp.is_empty() || timestamp.contains(['/', '\\']) || !snapshot.is_dir() {
+        return Err(WreckitError::FileNotFound(format!(
+            "No backup {} for {}",
+            timestamp, id
+        )));
+    }
+
+    let item_dir = get_item_dir(root, id);
+    if item_dir.exists() {
+        for entry in fs::read_dir(&item_dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if entry.file_type()?.is_dir() {
+                if !EXCLUDED_DIRS.contains(&name.to_string_lossy().as_ref()) {
+                    fs::remove_dir_all(entry.path())?;
+                }
+            } else {
+                fs::remove_file(entry.path())?;
+            }
+        }
+    }
+    copy_dir(&snapshot, &item_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::{get_prd_path, get_research_path, get_transcripts_dir};
+    use tempfile::TempDir;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(&get_research_path(root, "001"), "good research");
+        write(
+            &get_transcripts_dir(root, "001").join("001-research.json"),
+            "{}",
+        );
+
+        let snapshot = snapshot_item(root, "001", 5).unwrap().unwrap();
+        assert!(!get_item_backups_dir(root, "001")
+            .join(&snapshot)
+            .join("transcripts")
+            .exists());
+
+        write(&get_research_path(root, "001"), "corrupted");
+        write(&get_prd_path(root, "001"), "{ broken");
+        restore_backup(root, "001", &snapshot).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(get_research_path(root, "001")).unwrap(),
+            "good research"
+        );
+        assert!(!get_prd_path(root, "001").exists());
+        assert!(get_transcripts_dir(root, "001")
+            .join("001-research.json")
+            .exists());
+    }
+
+    #[test]
+    fn test_snapshot_retention() {
+        let temp = TempDir::new().unwrap();
+        write(&get_research_path(temp.path(), "001"), "r");
+        for _ in 0..4 {
+            snapshot_item(temp.path(), "001", 2).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert_eq!(list_backups(temp.path(), "001").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_snapshot_missing_item_and_bad_restore() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(snapshot_item(temp.path(), "001", 2).unwrap(), None);
+        assert!(matches!(
+            restore_backup(temp.path(), "001", "nope"),
+            Err(WreckitError::FileNotFound(_))
+        ));
+        assert!(restore_backup(temp.path(), "001", "../..").is_err());
+    }
+}
@@ -0,0 +1,442 @@
+//! SQLite item index cache
+//!
+//! Large backlogs make scanning every item.json slow, and concurrent scans
+//! race with writers. When `sqlite_index` is enabled, wreckit keeps a copy of
+//! every item in `.wreckit/cache/index.db`. Item writes update it as they
+//! happen, and [`query_items`] answers filtered queries from it. Without the
+//! database, queries fall back to scanning the items directory, so deleting
+//! the cache is always safe.
+
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::{params, params_from_iter, Connection};
+
+use crate::domain::is_terminal_state;
+use crate::errors::{Result, WreckitError};
+use crate::schemas::{Item, WorkflowState};
+
+use super::json::list_items;
+use super::paths::get_index_db_path;
+use super::read_only::ensure_writable;
+
+/// How long to wait on a locked database before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS items (
+        id TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        overview TEXT NOT NULL,
+        state TEXT NOT NULL,
+        assignee TEXT,
+        blocked INTEGER NOT NULL,
+        updated_at TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS items_state ON items(state);
+    CREATE INDEX IF NOT EXISTS items_assignee ON items(assignee);
+";
+
+/// Filters for an item query. Results are always sorted by ID.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemQuery {
+    /// Only items in one of these states (any state if empty)
+    pub states: Vec<WorkflowState>,
+
+    /// Skip items that are done, abandoned, or waiting on a PR
+    pub active_only: bool,
+
+    /// Only items assigned to this identity
+    pub assignee: Option<String>,
+
+    /// Case-insensitive text matched against ID, title, and overview
+    pub search: Option<String>,
+
+    /// Maximum number of results
+    pub limit: Option<usize>,
+}
+
+impl ItemQuery {
+    /// Create a query matching every item
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restrict to a workflow state (may be called repeatedly)
+    pub fn with_state(mut self, state: WorkflowState) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    /// Skip items with no work left to run
+    pub fn with_active_only(mut self) -> Self {
+        self.active_only = true;
+        self
+    }
+
+    /// Restrict to items assigned to `assignee`
+    pub fn with_assignee(mut self, assignee: Option<&str>) -> Self {
+        self.assignee = assignee.map(String::from);
+        self
+    }
+
+    /// Restrict to items matching a search term
+    pub fn with_search(mut self, search: &str) -> Self {
+        self.search = Some(search.to_string());
+        self
+    }
+
+    /// Limit the number of results
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Whether an item satisfies the query's filters (ignores `limit`)
+    pub fn matches(&self, item: &Item) -> bool {
+        let search = self.search.as_ref().map(|s| s.to_lowercase());
+        (self.states.is_empty() || self.states.contains(&item.state))
+            && (!self.active_only || is_active(item.state))
+            && self
+                .assignee
+                .as_ref()
+                .is_none_or(|me| item.assignee.as_ref() == Some(me))
+            && search.is_none_or(|term| {
+                [&item.id, &item.title, &item.overview]
+                    .iter()
+                    .any(|field| field.to_lowercase().contains(&term))
+            })
+    }
+}
+
+fn is_active(state: WorkflowState) -> bool {
+    !is_terminal_state(state) && state != WorkflowState::InPr
+}
+
+fn db_error(e: rusqlite::Error) -> WreckitError {
+    WreckitError::wrap(e, "Item index cache")
+}
+
+/// Handle to the SQLite index cache
+pub struct IndexDb {
+    conn: Connection,
+}
+
+impl IndexDb {
+    /// Open the cache, creating the database and its schema if needed.
+    ///
+    /// # Errors
+    /// * `Io` - If the cache directory cannot be created
+    /// * `Wrapped` - If SQLite cannot open or initialize the database
+    pub fn open(root: &Path) -> Result<Self> {
+        let path = get_index_db_path(root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(&path).map_err(db_error)?;
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(db_error)?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(db_error)?;
+        conn.execute_batch(SCHEMA).map_err(db_error)?;
+        Ok(IndexDb { conn })
+    }
+
+    /// Open the cache only if it has already been created
+    pub fn open_existing(root: &Path) -> Result<Option<Self>> {
+        if !get_index_db_path(root).exists() {
+            return Ok(None);
+        }
+        Self::open(root).map(Some)
+    }
+
+    /// Insert or replace an item
+    pub fn upsert(&self, item: &Item) -> Result<()> {
+        ensure_writable(|| "update the item index".to_string())?;
+        upsert_with(&self.conn, item)
+    }
+
+    /// Remove an item, if present
+    pub fn remove(&self, id: &str) -> Result<()> {
+        ensure_writable(|| "update the item index".to_string())?;
+        self.conn
+            .execute("DELETE FROM items WHERE id = ?1", params![id])
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Replace the cache contents with `items` in one transaction
+    pub fn rebuild(&mut self, items: &[Item]) -> Result<()> {
+        ensure_writable(|| "rebuild the item index".to_string())?;
+        let tx = self.conn.transaction().map_err(db_error)?;
+        tx.execute("DELETE FROM items", []).map_err(db_error)?;
+        for item in items {
+            upsert_with(&tx, item)?;
+        }
+        tx.commit().map_err(db_error)
+    }
+
+    /// Number of cached items
+    pub fn len(&self) -> Result<usize> {
+        self.conn
+            .query_row("SELECT COUNT(*) FROM items", [], |row| row.get::<_, i64>(0))
+            .map(|n| n as usize)
+            .map_err(db_error)
+    }
+
+    /// Whether the cache holds no items
+    pub fn is_empty(&self) -> Result<bool> {
+        self.len().map(|n| n == 0)
+    }
+
+    /// Run a query against the cache.
+    ///
+    /// # Errors
+    /// * `Wrapped` - If SQLite fails
+    /// * `InvalidJson` - If a cached item cannot be parsed
+    pub fn query(&self, query: &ItemQuery) -> Result<Vec<Item>> {
+        let mut clauses = Vec::new();
+        let mut values: Vec<String> = Vec::new();
+
+        if !query.states.is_empty() {
+            clauses.push(format!(
+                "state IN ({})",
+                vec!["?"; query.states.len()].join(", ")
+            ));
+            values.extend(query.states.iter().map(|s| s.to_string()));
+        }
+        if query.active_only {
+            let inactive = [
+                WorkflowState::InPr,
+                WorkflowState::Done,
+                WorkflowState::Abandoned,
+            ];
+            clauses.push(format!(
+                "state NOT IN ({})",
+                vec!["?"; inactive.len()].join(", ")
+            ));
+            values.extend(inactive.iter().map(|s| s.to_string()));
+        }
+        if let Some(ref assignee) = query.assignee {
+            clauses.push("assignee = ?".to_string());
+            values.push(assignee.clone());
+        }
+        if let Some(ref search) = query.search {
+            clauses.push(
+                "(instr(lower(id), ?) > 0 OR instr(lower(title), ?) > 0 \
+                 OR instr(lower(overview), ?) > 0)"
+                    .to_string(),
+            );
+            let term = search.to_lowercase();
+            values.extend([term.clone(), term.clone(), term]);
+        }
+
+        let mut sql = "SELECT data FROM items".to_string();
+        if !clauses.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clauses.join(" AND "));
+        }
+        sql.push_str(" ORDER BY id");
+        if let Some(limit) = query.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        let mut stmt = self.conn.prepare(&sql).map_err(db_error)?;
+        let rows = stmt
+            .query_map(params_from_iter(values.iter()), |row| {
+                row.get::<_, String>(0)
+            })
+            .map_err(db_error)?;
+
+        let mut items = Vec::new();
+        for data in rows {
+            let data = data.map_err(db_error)?;
+            items.push(serde_json::from_str(&data).map_err(|e| {
+                WreckitError::InvalidJson(format!("cached item in index.db: {}", e))
+            })?);
+        }
+        Ok(items)
+    }
+}
+
+fn upsert_with(conn: &Connection, item: &Item) -> Result<()> {
+    let data = serde_json::to_string(item)
+        .map_err(|e| WreckitError::InvalidJson(format!("Failed to serialize item: {}", e)))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO items
+            (id, title, overview, state, assignee, blocked, updated_at, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            item.id,
+            item.title,
+            item.overview,
+            item.state.to_string(),
+            item.assignee,
+            item.is_blocked(),
+            item.updated_at,
+            data,
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Create (or recreate) the cache from the items on disk.
+///
+/// # Returns
+/// The number of items indexed
+///
+/// # Errors
+/// * `InvalidJson` - If an item.json cannot be parsed
+/// * `Wrapped` - If SQLite fails
+pub fn rebuild_index_db(root: &Path) -> Result<usize> {
+    let items = list_items(root)?;
+    IndexDb::open(root)?.rebuild(&items)?;
+    Ok(items.len())
+}
+
+/// Mirror an item write into the cache, if the cache exists.
+///
+/// The item file is the source of truth, so a cache that cannot be updated
+/// is deleted rather than left stale; queries then fall back to scanning.
+pub fn update_index_db(root: &Path, item: &Item) {
+    let result = IndexDb::open_existing(root).and_then(|db| match db {
+        Some(db) => db.upsert(item),
+        None => Ok(()),
+    });
+    if let Err(e) = result {
+        tracing::warn!("Dropping item index cache after failed update: {}", e);
+        let _ = std::fs::remove_file(get_index_db_path(root));
+    }
+}
+
+/// Find items matching a query.
+///
+/// Uses the SQLite cache when it exists, otherwise scans the items directory.
+///
+/// # Errors
+/// * `InvalidJson` - If an item cannot be parsed
+pub fn query_items(root: &Path, query: &ItemQuery) -> Result<Vec<Item>> {
+    match IndexDb::open_existing(root) {
+        Ok(Some(db)) => match db.query(query) {
+            Ok(items) => return Ok(items),
+            Err(e) => tracing::warn!("Item index cache query failed, scanning instead: {}", e),
+        },
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Item index cache unavailable, scanning instead: {}", e),
+    }
+
+    let mut items: Vec<Item> = list_items(root)?
+        .into_iter()
+        .filter(|item| query.matches(item))
+        .collect();
+    if let Some(limit) = query.limit {
+        items.truncate(limit);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::{write_item, Transaction};
+    use tempfile::TempDir;
+
+    fn seed(root: &Path) -> Vec<Item> {
+        let items = vec![
+            Item::new("001-a".to_string(), "Dark mode".to_string(), String::new()),
+            Item::new(
+                "002-b".to_string(),
+                "Login".to_string(),
+                "Add OAuth".to_string(),
+            )
+            .with_state(WorkflowState::Researched)
+            .with_assignee(Some("dev@example.com".to_string())),
+            Item::new("003-c".to_string(), "Shipped".to_string(), String::new())
+                .with_state(WorkflowState::Done),
+        ];
+        for item in &items {
+            write_item(root, &item.id, item).unwrap();
+        }
+        items
+    }
+
+    fn ids(items: &[Item]) -> Vec<&str> {
+        items.iter().map(|i| i.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_query_falls_back_without_cache() {
+        let temp = TempDir::new().unwrap();
+        seed(temp.path());
+        assert!(!get_index_db_path(temp.path()).exists());
+
+        let active = query_items(temp.path(), &ItemQuery::new().with_active_only()).unwrap();
+        assert_eq!(ids(&active), vec!["001-a", "002-b"]);
+        let found = query_items(temp.path(), &ItemQuery::new().with_search("oauth")).unwrap();
+        assert_eq!(ids(&found), vec!["002-b"]);
+    }
+
+    #[test]
+    fn test_cache_matches_scan() {
+        let temp = TempDir::new().unwrap();
+        seed(temp.path());
+        assert_eq!(rebuild_index_db(temp.path()).unwrap(), 3);
+
+        let queries = [
+            ItemQuery::new(),
+            ItemQuery::new().with_active_only(),
+            ItemQuery::new().with_state(WorkflowState::Done),
+            ItemQuery::new().with_assignee(Some("dev@example.com")),
+            ItemQuery::new().with_search("DARK"),
+            ItemQuery::new().with_limit(1),
+        ];
+        let db = IndexDb::open(temp.path()).unwrap();
+        let scanned = list_items(temp.path()).unwrap();
+        for query in &queries {
+            let mut expected: Vec<&Item> = scanned.iter().filter(|i| query.matches(i)).collect();
+            expected.truncate(query.limit.unwrap_or(usize::MAX));
+            let cached = db.query(query).unwrap();
+            assert_eq!(
+                ids(&cached),
+                expected.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(),
+                "{:?}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn test_item_writes_update_cache() {
+        let temp = TempDir::new().unwrap();
+        let items = seed(temp.path());
+        rebuild_index_db(temp.path()).unwrap();
+
+        let moved = items[0].clone().with_state(WorkflowState::Done);
+        write_item(temp.path(), &moved.id, &moved).unwrap();
+        let new = Item::new("004-d".to_string(), "New".to_string(), String::new());
+        write_item(temp.path(), &new.id, &new).unwrap();
+
+        let db = IndexDb::open(temp.path()).unwrap();
+        assert_eq!(db.len().unwrap(), 4);
+        let done = db
+            .query(&ItemQuery::new().with_state(WorkflowState::Done))
+            .unwrap();
+        assert_eq!(ids(&done), vec!["001-a", "003-c"]);
+
+        db.remove("004-d").unwrap();
+        assert_eq!(db.len().unwrap(), 3);
+
+        // Journaled writes are mirrored too
+        let planned = items[1].clone().with_state(WorkflowState::Planned);
+        Transaction::new(temp.path())
+            .write_item(&planned)
+            .unwrap()
+            .commit()
+            .unwrap();
+        let found = db
+            .query(&ItemQuery::new().with_state(WorkflowState::Planned))
+            .unwrap();
+        assert_eq!(ids(&found), vec!["002-b"]);
+    }
+}
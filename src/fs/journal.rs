@@ -0,0 +1,442 @@
+//! Write-ahead journal for multi-file metadata updates
+//!
+//! Related writes (e.g. item.json + prd.json + index.json) are staged in a
+//! [`Transaction`]. On commit the full set of new contents, plus each file's
+//! previous contents, is written to `.wreckit/journal/<txid>.json` before any
+//! target file is touched, and the journal entry is removed once every write
+//! has landed. A journal entry that survives a crash marks an incomplete
+//! transaction; [`recover_journal`] rolls it forward or back. The journal
+//! entry itself is written to a temp file and renamed into place, and an
+//! entry that cannot be parsed is set aside with a warning rather than
+//! blocking recovery of the others.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{Result, WreckitError};
+use crate::schemas::{Index, Item, Prd};
+
+use super::index_db::{update_index_db, IndexDb};
+use super::json::read_json;
+use super::paths::{get_index_path, get_item_json_path, get_journal_dir, get_prd_path};
+use super::read_only::ensure_writable;
+
+/// One staged file write
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Target path, relative to the repository root
+    pub path: String,
+
+    /// New file contents
+    pub content: String,
+
+    /// Contents before the transaction (None if the file did not exist)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+}
+
+/// A journaled transaction, as stored on disk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalRecord {
+    /// Transaction ID (also the journal file name)
+    pub id: String,
+
+    /// ISO 8601 timestamp when the transaction was committed
+    pub started_at: String,
+
+    /// Writes in the transaction, applied in order
+    pub entries: Vec<JournalEntry>,
+}
+
+/// How to resolve an incomplete transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryMode {
+    /// Apply every write in the transaction
+    RollForward,
+    /// Restore every file to its contents before the transaction
+    RollBack,
+}
+
+/// A batch of file writes that land together or not at all
+#[derive(Debug)]
+pub struct Transaction {
+    root: PathBuf,
+    entries: Vec<JournalEntry>,
+}
+
+impl Transaction {
+    /// Start an empty transaction for the given repository
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Stage a JSON write to a path under the repository root
+    ///
+    /// # Errors
+    /// * `InvalidJson` - If the value cannot be serialized
+    /// * `Wrapped` - If the path is outside the repository root
+    pub fn write_json<T: Serialize>(mut self, path: &Path, data: &T) -> Result<Self> {
+        let relative = path.strip_prefix(&self.root).map_err(|_| {
+            WreckitError::wrap(
+                format!("{} is outside {}", path.display(), self.root.display()),
+                "Cannot journal write",
+            )
+        })?;
+        let mut content = serde_json::to_string_pretty(data)
+            .map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+        content.push('\n');
+        // The previous contents are captured at commit, not now, so a file
+        // changed in between is never rolled back to a stale snapshot
+        self.entries.push(JournalEntry {
+            path: relative.to_string_lossy().to_string(),
+            content,
+            previous: None,
+        });
+        Ok(self)
+    }
+
+    /// Stage an item.json write
+    pub fn write_item(self, item: &Item) -> Result<Self> {
+        let path = get_item_json_path(&self.root, &item.id);
+        self.write_json(&path, item)
+    }
+
+    /// Stage a prd.json write
+    pub fn write_prd(self, id: &str, prd: &Prd) -> Result<Self> {
+        let path = get_prd_path(&self.root, id);
+        self.write_json(&path, prd)
+    }
+
+    /// Stage an index.json write
+    pub fn write_index(self, index: &Index) -> Result<Self> {
+        let path = get_index_path(&self.root);
+        self.write_json(&path, index)
+    }
+
+    /// Number of staged writes
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing has been staged
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The journal record for the staged writes, with each target's current
+    /// contents as its previous contents
+    fn into_record(mut self) -> JournalRecord {
+        for entry in &mut self.entries {
+            entry.previous = fs::read_to_string(self.root.join(&entry.path)).ok();
+        }
+        let now = chrono::Utc::now();
+        JournalRecord {
+            id: format!("{}-{}", now.format("%Y%m%dT%H%M%S%.6f"), std::process::id()),
+            started_at: now.to_rfc3339(),
+            entries: self.entries,
+        }
+    }
+
+    /// Journal the staged writes, apply them, then clear the journal entry.
+    ///
+    /// # Errors
+    /// * `Io` - If the journal or a target file cannot be written; the
+    ///   journal entry is left behind for recovery
+    pub fn commit(self) -> Result<()> {
+        if self.entries.is_empty() {
+            return Ok(());
+        }
+        ensure_writable(|| "commit a metadata transaction".to_string())?;
+        let root = self.root.clone();
+        let record = self.into_record();
+        let journal_path = get_journal_dir(&root).join(format!("{}.json", record.id));
+        let mut content = serde_json::to_string_pretty(&record)
+            .map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+        content.push('\n');
+        write_atomic(&journal_path, &content)?;
+
+        apply(&root, &record, RecoveryMode::RollForward)?;
+        fs::remove_file(&journal_path)?;
+        Ok(())
+    }
+}
+
+/// Write a file atomically (synced temp file, then rename)
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("journal.tmp");
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+fn apply(root: &Path, record: &JournalRecord, mode: RecoveryMode) -> Result<()> {
+    for entry in &record.entries {
+        let path = root.join(&entry.path);
+        let written = match (mode, &entry.previous) {
+            (RecoveryMode::RollForward, _) => Some(&entry.content),
+            (RecoveryMode::RollBack, previous) => previous.as_ref(),
+        };
+        match written {
+            Some(content) => write_atomic(&path, content)?,
+            None => {
+                if path.exists() {
+                    fs::remove_file(&path)?;
+                }
+            }
+        }
+        if path.file_name().and_then(|n| n.to_str()) == Some("item.json") {
+            sync_cached_item(root, &path, written);
+        }
+    }
+    Ok(())
+}
+
+/// Keep the index cache in step with an item.json the journal wrote or removed
+fn sync_cached_item(root: &Path, path: &Path, content: Option<&String>) {
+    match content.and_then(|c| serde_json::from_str::<Item>(c).ok()) {
+        Some(item) => update_index_db(root, &item),
+        None => {
+            let id = path
+                .parent()
+                .and_then(|dir| dir.file_name())
+                .map(|name| name.to_string_lossy().to_string());
+            if let (Some(id), Ok(Some(db))) = (id, IndexDb::open_existing(root)) {
+                let _ = db.remove(&id);
+            }
+        }
+    }
+}
+
+/// Journal entries on disk, parsed or not
+fn read_journal(root: &Path) -> Result<Vec<(PathBuf, Result<JournalRecord>)>> {
+    let dir = get_journal_dir(root);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            let record = read_json::<JournalRecord>(&path);
+            entries.push((path, record));
+        }
+    }
+    Ok(entries)
+}
+
+/// Transactions whose journal entries were never cleared, oldest first.
+///
+/// Entries that cannot be parsed are skipped with a warning.
+///
+/// # Errors
+/// * `Io` - If the journal directory cannot be read
+pub fn pending_transactions(root: &Path) -> Result<Vec<JournalRecord>> {
+    let mut records = Vec::new();
+    for (path, record) in read_journal(root)? {
+        match record {
+            Ok(record) => records.push(record),
+            Err(e) => tracing::warn!(
+                "Skipping unreadable journal entry {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+    records.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(records)
+}
+
+/// Move unparsable journal entries aside as `<txid>.json.corrupt`
+fn quarantine_unreadable(root: &Path) -> Result<()> {
+    for (path, record) in read_journal(root)? {
+        if let Err(e) = record {
+            let corrupt = path.with_extension("json.corrupt");
+            tracing::warn!(
+                "Moving unreadable journal entry {} to {}: {}",
+                path.display(),
+                corrupt.display(),
+                e
+            );
+            fs::rename(&path, &corrupt)?;
+        }
+    }
+    Ok(())
+}
+
+/// Resolve every incomplete transaction and clear its journal entry.
+///
+/// Rolling back restores files in reverse transaction order so the oldest
+/// snapshot wins. Entries that cannot be parsed (a crash while the journal
+/// itself was written) are moved aside and left alone.
+///
+/// # Returns
+/// The transactions that were resolved
+///
+/// # Errors
+/// * `Io` - If a file cannot be restored
+pub fn recover_journal(root: &Path, mode: RecoveryMode) -> Result<Vec<JournalRecord>> {
+    quarantine_unreadable(root)?;
+    let mut records = pending_transactions(root)?;
+    if mode == RecoveryMode::RollBack {
+        records.reverse();
+    }
+    for record in &records {
+        apply(root, record, mode)?;
+        fs::remove_file(get_journal_dir(root).join(format!("{}.json", record.id)))?;
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::write_json;
+    use crate::schemas::WorkflowState;
+    use tempfile::TempDir;
+
+    fn item(state: WorkflowState) -> Item {
+        Item::new("001-test".to_string(), "Test".to_string(), String::new()).with_state(state)
+    }
+
+    /// Journal a transaction without applying it, as if we crashed mid-commit
+    fn crash_during(tx: Transaction) -> JournalRecord {
+        let root = tx.root.clone();
+        let record = JournalRecord {
+            id: "20240101T000000.000000-1".to_string(),
+            ..tx.into_record()
+        };
+        write_json(
+            &get_journal_dir(&root).join(format!("{}.json", record.id)),
+            &record,
+        )
+        .unwrap();
+        record
+    }
+
+    #[test]
+    fn test_commit_writes_all_and_clears_journal() {
+        let temp = TempDir::new().unwrap();
+        let prd = Prd::new("001-test".to_string(), "wreckit/001-test".to_string());
+        let tx = Transaction::new(temp.path())
+            .write_item(&item(WorkflowState::Planned))
+            .unwrap()
+            .write_prd("001-test", &prd)
+            .unwrap()
+            .write_index(&Index::new())
+            .unwrap();
+        assert_eq!(tx.len(), 3);
+        tx.commit().unwrap();
+
+        let read: Item = read_json(&get_item_json_path(temp.path(), "001-test")).unwrap();
+        assert_eq!(read.state, WorkflowState::Planned);
+        assert!(get_prd_path(temp.path(), "001-test").exists());
+        assert!(get_index_path(temp.path()).exists());
+        assert!(pending_transactions(temp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_recover_roll_forward() {
+        let temp = TempDir::new().unwrap();
+        write_json(
+            &get_item_json_path(temp.path(), "001-test"),
+            &item(WorkflowState::Idea),
+        )
+        .unwrap();
+        let tx = Transaction::new(temp.path())
+            .write_item(&item(WorkflowState::Researched))
+            .unwrap();
+        crash_during(tx);
+
+        assert_eq!(pending_transactions(temp.path()).unwrap().len(), 1);
+        let recovered = recover_journal(temp.path(), RecoveryMode::RollForward).unwrap();
+        assert_eq!(recovered.len(), 1);
+
+        let read: Item = read_json(&get_item_json_path(temp.path(), "001-test")).unwrap();
+        assert_eq!(read.state, WorkflowState::Researched);
+        assert!(pending_transactions(temp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_recover_roll_back() {
+        let temp = TempDir::new().unwrap();
+        let item_path = get_item_json_path(temp.path(), "001-test");
+        write_json(&item_path, &item(WorkflowState::Idea)).unwrap();
+        let prd = Prd::new("001-test".to_string(), "wreckit/001-test".to_string());
+        let tx = Transaction::new(temp.path())
+            .write_item(&item(WorkflowState::Planned))
+            .unwrap()
+            .write_prd("001-test", &prd)
+            .unwrap();
+        // The item write landed, the prd write did not
+        let record = crash_during(tx);
+        write_atomic(
+            &temp.path().join(&record.entries[0].path),
+            &record.entries[0].content,
+        )
+        .unwrap();
+
+        recover_journal(temp.path(), RecoveryMode::RollBack).unwrap();
+        let read: Item = read_json(&item_path).unwrap();
+        assert_eq!(read.state, WorkflowState::Idea);
+        assert!(!get_prd_path(temp.path(), "001-test").exists());
+    }
+
+    #[test]
+    fn test_previous_is_captured_at_commit() {
+        let temp = TempDir::new().unwrap();
+        let item_path = get_item_json_path(temp.path(), "001-test");
+        write_json(&item_path, &item(WorkflowState::Idea)).unwrap();
+        let tx = Transaction::new(temp.path())
+            .write_item(&item(WorkflowState::Planned))
+            .unwrap();
+        // Someone else moves the item on between staging and commit
+        write_json(&item_path, &item(WorkflowState::Researched)).unwrap();
+        crash_during(tx);
+
+        recover_journal(temp.path(), RecoveryMode::RollBack).unwrap();
+        let read: Item = read_json(&item_path).unwrap();
+        assert_eq!(read.state, WorkflowState::Researched);
+    }
+
+    #[test]
+    fn test_unreadable_entry_does_not_block_recovery() {
+        let temp = TempDir::new().unwrap();
+        let tx = Transaction::new(temp.path())
+            .write_item(&item(WorkflowState::Researched))
+            .unwrap();
+        crash_during(tx);
+        // A crash while the journal itself was being written
+        let torn = get_journal_dir(temp.path()).join("20240101T000001.000000-2.json");
+        fs::write(&torn, "{\"id\": \"2024").unwrap();
+
+        assert_eq!(pending_transactions(temp.path()).unwrap().len(), 1);
+        let recovered = recover_journal(temp.path(), RecoveryMode::RollForward).unwrap();
+        assert_eq!(recovered.len(), 1);
+        let read: Item = read_json(&get_item_json_path(temp.path(), "001-test")).unwrap();
+        assert_eq!(read.state, WorkflowState::Researched);
+
+        assert!(!torn.exists());
+        assert!(torn.with_extension("json.corrupt").exists());
+        assert!(pending_transactions(temp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_write_outside_root_rejected() {
+        let temp = TempDir::new().unwrap();
+        let other = TempDir::new().unwrap();
+        let result = Transaction::new(temp.path()).write_json(&other.path().join("x.json"), &1);
+        assert!(result.is_err());
+    }
+}
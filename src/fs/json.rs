@@ -2,6 +2,7 @@
 //!
 //! Provides functions to read and write JSON files with serde validation.
 
+use std::collections::HashSet;
 use std::fs;
 use std::io::Write;
 use std::path::Path;
@@ -10,9 +11,13 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use crate::errors::{Result, WreckitError};
-use crate::schemas::{Config, Item, Prd};
+use crate::schemas::{Config, Index, Item, Prd};
 
-use super::paths::{get_config_path, get_item_json_path, get_prd_path};
+use super::index_db::update_index_db;
+use super::paths::{
+    get_config_path, get_index_path, get_item_json_path, get_items_dir, get_prd_path,
+};
+use super::read_only::ensure_writable;
 
 /// Read and deserialize a JSON file.
 ///
@@ -51,6 +56,7 @@ pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
 /// # Errors
 /// * `Io` - If there's an error writing the file
 pub fn write_json<T: Serialize>(path: &Path, data: &T) -> Result<()> {
+    ensure_writable(|| format!("write {}", path.display()))?;
     let content =
         serde_json::to_string_pretty(data).map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
 
@@ -108,7 +114,74 @@ pub fn read_item(root: &Path, id: &str) -> Result<Item> {
 /// * `item` - The item to write
 pub fn write_item(root: &Path, id: &str, item: &Item) -> Result<()> {
     let path = get_item_json_path(root, id);
-    write_json(&path, item)
+    write_json(&path, item)?;
+    update_index_db(root, item);
+    Ok(())
+}
+
+/// Read every item in the items directory, sorted by ID.
+///
+/// Directories without an item.json are skipped.
+///
+/// # Arguments
+/// * `root` - Path to the repository root
+///
+/// # Returns
+/// All parsed items
+///
+/// # Errors
+/// * `InvalidJson` - If any item.json cannot be parsed
+pub fn list_items(root: &Path) -> Result<Vec<Item>> {
+    let items_dir = get_items_dir(root);
+    if !items_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut items = Vec::new();
+    for entry in fs::read_dir(&items_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let id = entry.file_name().to_string_lossy().to_string();
+        if !get_item_json_path(root, &id).exists() {
+            continue;
+        }
+        items.push(read_item(root, &id)?);
+    }
+
+    items.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(items)
+}
+
+/// Collect every item ID already in use.
+///
+/// Includes every directory under `items/` (with or without an item.json)
+/// and every entry in index.json, so new IDs never collide with either.
+///
+/// # Arguments
+/// * `root` - Path to the repository root
+///
+/// # Errors
+/// * `InvalidJson` - If index.json exists but cannot be parsed
+pub fn existing_item_ids(root: &Path) -> Result<HashSet<String>> {
+    let mut ids = HashSet::new();
+    let items_dir = get_items_dir(root);
+    if items_dir.exists() {
+        for entry in fs::read_dir(&items_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                ids.insert(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+
+    let index_path = get_index_path(root);
+    if index_path.exists() {
+        let index: Index = read_json(&index_path)?;
+        ids.extend(index.items.into_iter().map(|entry| entry.id));
+    }
+    Ok(ids)
 }
 
 /// Read a prd.json file from an item directory.
@@ -226,6 +299,41 @@ mod tests {
         assert_eq!(read.state, WorkflowState::Idea);
     }
 
+    #[test]
+    fn test_list_items_sorted() {
+        let temp = TempDir::new().unwrap();
+        assert!(list_items(temp.path()).unwrap().is_empty());
+
+        for id in ["002-b", "001-a"] {
+            let item = Item::new(id.to_string(), id.to_string(), String::new());
+            write_item(temp.path(), id, &item).unwrap();
+        }
+        // A directory without item.json is ignored
+        fs::create_dir_all(temp.path().join(".wreckit/items/stray")).unwrap();
+
+        let items = list_items(temp.path()).unwrap();
+        let ids: Vec<_> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, vec!["001-a", "002-b"]);
+    }
+
+    #[test]
+    fn test_existing_item_ids_includes_dirs_and_index() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".wreckit/items/001-a")).unwrap();
+        let mut index = Index::new();
+        index.items.push(crate::schemas::IndexItem {
+            id: "WRK-004".to_string(),
+            state: WorkflowState::Idea,
+            title: "Archived".to_string(),
+        });
+        write_json(&get_index_path(temp.path()), &index).unwrap();
+
+        let ids = existing_item_ids(temp.path()).unwrap();
+        assert!(ids.contains("001-a"));
+        assert!(ids.contains("WRK-004"));
+        assert_eq!(ids.len(), 2);
+    }
+
     #[test]
     fn test_read_write_prd() {
         let temp = TempDir::new().unwrap();
@@ -0,0 +1,378 @@
+//! Repository lock
+//!
+//! Commands that drive the agent in the working tree take `.wreckit/lock`
+//! first, so two autonomous loops never edit the same checkout at once. The
+//! lock file records the holder's PID, when it was taken, and the command
+//! line. A lock whose process is gone is stale and is taken over with a
+//! warning; `--force-unlock` removes a lock regardless. Journal recovery
+//! runs only once the lock is held, so it never replays a transaction that
+//! another process is still committing.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{Result, WreckitError};
+
+use super::journal::{recover_journal, RecoveryMode};
+use super::paths::get_lock_path;
+use super::read_only::ensure_writable;
+
+/// Contents of the lock file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockInfo {
+    /// Process holding the lock
+    pub pid: u32,
+
+    /// ISO 8601 timestamp of when the lock was taken
+    pub acquired_at: String,
+
+    /// Command line of the holder
+    pub command: String,
+}
+
+impl LockInfo {
+    /// Lock info for this process
+    pub fn current() -> Self {
+        LockInfo {
+            pid: std::process::id(),
+            acquired_at: chrono::Utc::now().to_rfc3339(),
+            command: std::env::args().collect::<Vec<_>>().join(" "),
+        }
+    }
+}
+
+/// Whether a process with the given PID is running
+#[cfg(unix)]
+pub fn process_alive(pid: u32) -> bool {
+    // SAFETY: kill(2) with signal 0 only checks that the process exists;
+    // EPERM means it exists under another user.
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Whether a process with the given PID is running (assumed so off unix)
+#[cfg(not(unix))]
+pub fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+/// A held repository lock, released when dropped
+#[derive(Debug)]
+pub struct RepoLock {
+    path: PathBuf,
+    pid: u32,
+}
+
+impl Drop for RepoLock {
+    fn drop(&mut self) {
+        // Leave the file alone if someone force-unlocked and took it over
+        if read_lock(&self.path).is_some_and(|info| info.pid == self.pid) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn read_lock(path: &Path) -> Option<LockInfo> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Create the lock file with its full contents in one step.
+///
+/// The info is written to a private temp file first and then hard-linked
+/// into place, so a racing acquirer never sees an empty or half-written lock
+/// and mistakes it for an unreadable one.
+fn try_create(path: &Path, info: &LockInfo) -> std::io::Result<()> {
+    let temp = path.with_extension(format!("{}.tmp", info.pid));
+    let result = write_temp(&temp, info).and_then(|()| fs::hard_link(&temp, path));
+    let _ = fs::remove_file(&temp);
+    result
+}
+
+fn write_temp(temp: &Path, info: &LockInfo) -> std::io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(temp)?;
+    let content = serde_json::to_string_pretty(info).map_err(std::io::Error::other)?;
+    file.write_all(content.as_bytes())?;
+    file.write_all(b"\n")?;
+    file.sync_all()
+}
+
+/// Remove a stale lock file, unless another process replaced it first.
+///
+/// The file is renamed to a name private to this process before it is
+/// discarded, so two processes taking over the same stale lock cannot delete
+/// a fresh lock the other has just published. If the claimed file turns out
+/// not to be the stale one, it is put back.
+///
+/// # Errors
+/// * `Locked` - If another process took the lock over in the meantime
+/// * `Io` - If the lock file cannot be renamed or removed
+fn remove_stale(path: &Path, stale: Option<&LockInfo>, pid: u32) -> Result<()> {
+    let claimed = path.with_extension(format!("{}.stale", pid));
+    match fs::rename(path, &claimed) {
+        Ok(()) => {}
+        // Another process already cleared it; just retry creating the lock
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    }
+    let found = read_lock(&claimed);
+    if found.as_ref() == stale {
+        fs::remove_file(&claimed)?;
+        return Ok(());
+    }
+    // A fresh lock was published after the stale one was read: restore it
+    // (never over a lock taken since) and back off
+    let _ = fs::hard_link(&claimed, path);
+    let _ = fs::remove_file(&claimed);
+    Err(WreckitError::Locked(match found {
+        Some(holder) => format!(
+            "pid {} took over {} since {} ({})",
+            holder.pid,
+            path.display(),
+            holder.acquired_at,
+            holder.command
+        ),
+        None => format!("{} was taken by another process", path.display()),
+    }))
+}
+
+/// Take the repository lock for this process.
+///
+/// A stale lock (its process has exited, or the file is unreadable) is
+/// replaced with a warning. Once the lock is held, metadata writes left
+/// incomplete by a crash are rolled forward.
+///
+/// # Errors
+/// * `Locked` - If another running process holds the lock
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the lock file or an interrupted write cannot be written
+pub fn acquire_lock(root: &Path) -> Result<RepoLock> {
+    ensure_writable(|| "take the repository lock".to_string())?;
+    let path = get_lock_path(root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let info = LockInfo::current();
+    // One retry: the second attempt follows removing a stale lock
+    for _ in 0..2 {
+        match try_create(&path, &info) {
+            Ok(()) => {
+                let lock = RepoLock {
+                    path,
+                    pid: info.pid,
+                };
+                for tx in recover_journal(root, RecoveryMode::RollForward)? {
+                    tracing::warn!(
+                        "Completed interrupted metadata write {} ({} file(s))",
+                        tx.id,
+                        tx.entries.len()
+                    );
+                }
+                return Ok(lock);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e.into()),
+        }
+        let stale = read_lock(&path);
+        match &stale {
+            Some(holder) if holder.pid == info.pid || process_alive(holder.pid) => {
+                return Err(WreckitError::Locked(format!(
+                    "pid {} has held {} since {} ({})",
+                    holder.pid,
+                    path.display(),
+                    holder.acquired_at,
+                    holder.command
+                )));
+            }
+            Some(holder) => tracing::warn!(
+                "Removing stale lock from pid {} (taken {}, no longer running)",
+                holder.pid,
+                holder.acquired_at
+            ),
+            None => tracing::warn!("Removing unreadable lock file {}", path.display()),
+        }
+        remove_stale(&path, stale.as_ref(), info.pid)?;
+    }
+    Err(WreckitError::Locked(format!(
+        "{} was taken by another process",
+        path.display()
+    )))
+}
+
+/// The running process holding the repository lock, if any
+pub fn lock_holder(root: &Path) -> Option<LockInfo> {
+    read_lock(&get_lock_path(root)).filter(|holder| process_alive(holder.pid))
+}
+
+/// Remove the repository lock whoever holds it, returning the holder.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the lock file cannot be removed
+pub fn force_unlock(root: &Path) -> Result<Option<LockInfo>> {
+    ensure_writable(|| "remove the repository lock".to_string())?;
+    let path = get_lock_path(root);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let holder = read_lock(&path);
+    fs::remove_file(&path)?;
+    Ok(holder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".wreckit")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_lock_is_exclusive_and_released_on_drop() {
+        let dir = setup();
+        let lock = acquire_lock(dir.path()).unwrap();
+        let held = read_lock(&get_lock_path(dir.path())).unwrap();
+        assert_eq!(held.pid, std::process::id());
+
+        let err = acquire_lock(dir.path()).unwrap_err();
+        assert!(matches!(err, WreckitError::Locked(_)));
+
+        drop(lock);
+        assert!(!get_lock_path(dir.path()).exists());
+        assert!(acquire_lock(dir.path()).is_ok());
+    }
+
+    #[test]
+    fn test_stale_lock_is_taken_over() {
+        let dir = setup();
+        let stale = LockInfo {
+            pid: u32::MAX / 2,
+            acquired_at: "2024-01-01T00:00:00Z".to_string(),
+            command: "wreckit run 001-a".to_string(),
+        };
+        try_create(&get_lock_path(dir.path()), &stale).unwrap();
+
+        let _lock = acquire_lock(dir.path()).unwrap();
+        let held = read_lock(&get_lock_path(dir.path())).unwrap();
+        assert_eq!(held.pid, std::process::id());
+    }
+
+    #[test]
+    fn test_stale_takeover_keeps_a_racing_fresh_lock() {
+        let dir = setup();
+        let path = get_lock_path(dir.path());
+        let stale = LockInfo {
+            pid: u32::MAX / 2,
+            acquired_at: "2024-01-01T00:00:00Z".to_string(),
+            command: "wreckit run 001-a".to_string(),
+        };
+        try_create(&path, &stale).unwrap();
+        let seen = read_lock(&path);
+
+        // Another process takes the stale lock over between our read and
+        // our removal, and publishes its own lock
+        let racer = LockInfo {
+            pid: std::process::id() + 1,
+            acquired_at: "2024-01-02T00:00:00Z".to_string(),
+            command: "wreckit run 002-b".to_string(),
+        };
+        fs::remove_file(&path).unwrap();
+        try_create(&path, &racer).unwrap();
+
+        let err = remove_stale(&path, seen.as_ref(), std::process::id()).unwrap_err();
+        assert!(matches!(err, WreckitError::Locked(_)));
+        assert_eq!(read_lock(&path), Some(racer));
+        let entries = fs::read_dir(dir.path().join(".wreckit")).unwrap().count();
+        assert_eq!(entries, 1);
+
+        // Without a race the stale lock is removed
+        fs::remove_file(&path).unwrap();
+        try_create(&path, &stale).unwrap();
+        remove_stale(&path, Some(&stale), std::process::id()).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_try_create_is_complete_and_exclusive() {
+        let dir = setup();
+        let path = get_lock_path(dir.path());
+        let info = LockInfo::current();
+        try_create(&path, &info).unwrap();
+        assert_eq!(read_lock(&path), Some(info.clone()));
+
+        let err = try_create(&path, &info).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        // Only the lock itself is left behind, never the temp file
+        let entries: Vec<_> = fs::read_dir(dir.path().join(".wreckit"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec![path.file_name().unwrap().to_owned()]);
+    }
+
+    #[test]
+    fn test_acquire_lock_rolls_journal_forward() {
+        use crate::fs::{
+            get_journal_dir, pending_transactions, write_json, JournalEntry, JournalRecord,
+        };
+
+        let dir = setup();
+        let record = JournalRecord {
+            id: "20240101T000000.000000-1".to_string(),
+            started_at: "2024-01-01T00:00:00Z".to_string(),
+            entries: vec![JournalEntry {
+                path: ".wreckit/done.txt".to_string(),
+                content: "landed".to_string(),
+                previous: None,
+            }],
+        };
+        write_json(
+            &get_journal_dir(dir.path()).join(format!("{}.json", record.id)),
+            &record,
+        )
+        .unwrap();
+
+        let _lock = acquire_lock(dir.path()).unwrap();
+        assert!(pending_transactions(dir.path()).unwrap().is_empty());
+        let landed = fs::read_to_string(dir.path().join(".wreckit/done.txt")).unwrap();
+        assert_eq!(landed, "landed");
+    }
+
+    #[test]
+    fn test_lock_holder() {
+        let dir = setup();
+        assert_eq!(lock_holder(dir.path()), None);
+        let lock = acquire_lock(dir.path()).unwrap();
+        assert_eq!(lock_holder(dir.path()).unwrap().pid, std::process::id());
+        drop(lock);
+
+        let stale = LockInfo {
+            pid: u32::MAX / 2,
+            acquired_at: "2024-01-01T00:00:00Z".to_string(),
+            command: "wreckit run 001-a".to_string(),
+        };
+        try_create(&get_lock_path(dir.path()), &stale).unwrap();
+        assert_eq!(lock_holder(dir.path()), None);
+    }
+
+    #[test]
+    fn test_force_unlock() {
+        let dir = setup();
+        assert_eq!(force_unlock(dir.path()).unwrap(), None);
+        let lock = acquire_lock(dir.path()).unwrap();
+        let holder = force_unlock(dir.path()).unwrap().unwrap();
+        assert_eq!(holder.pid, std::process::id());
+        assert!(!get_lock_path(dir.path()).exists());
+        drop(lock);
+    }
+}
@@ -1,15 +1,41 @@
 //! File system utilities for wreckit
 //!
-//! Provides path resolution and JSON file operations.
+//! Provides path resolution, JSON file operations, a write-ahead journal for
+//! updates that span several metadata files, item snapshots, the repository
+//! lock, read-only spectator mode (with guarded write helpers), and an
+//! optional SQLite index cache.
 
+mod backup;
+mod index_db;
+mod journal;
 mod json;
+mod lock;
 mod paths;
+mod read_only;
 
+pub use backup::{list_backups, restore_backup, snapshot_item};
+pub use index_db::{query_items, rebuild_index_db, update_index_db, IndexDb, ItemQuery};
+pub use journal::{
+    pending_transactions, recover_journal, JournalEntry, JournalRecord, RecoveryMode, Transaction,
+};
 pub use json::{
-    read_config, read_item, read_json, read_prd, write_item, write_json, write_prd,
+    existing_item_ids, list_items, read_config, read_item, read_json, read_prd, write_item,
+    write_json, write_prd,
+};
+pub use lock::{acquire_lock, force_unlock, lock_holder, process_alive, LockInfo, RepoLock};
+pub use read_only::{
+    append_file, copy_file, create_dir_all, create_file, ensure_writable, is_read_only,
+    remove_dir_all, remove_file, rename_file, set_read_only, write_file,
 };
+#[cfg(test)]
+pub(crate) use read_only::with_read_only;
 pub use paths::{
-    find_repo_root, get_config_path, get_item_dir, get_items_dir, get_plan_path,
-    get_progress_log_path, get_prompts_dir, get_prd_path, get_research_path, get_wreckit_dir,
-    resolve_cwd,
+    find_repo_root, get_agent_cache_dir, get_attachments_dir, get_backups_dir, get_cache_dir,
+    get_config_path, get_index_db_path, get_index_path, get_item_backups_dir, get_item_dir,
+    get_item_json_path, get_items_dir, get_journal_dir, get_lock_path, get_notes_path,
+    get_outbox_dir, get_plan_path, get_plugins_dir, get_pr_bot_state_path, get_progress_log_path,
+    get_prompt_snapshots_dir, get_prompts_dir, get_prd_path, get_research_path, get_runs_dir,
+    get_stats_path, get_transcripts_dir, get_detached_dir, get_detached_events_path,
+    get_detached_pid_path, get_tui_seen_path, get_tui_session_path, get_verification_path,
+    get_wreckit_dir, resolve_cwd,
 };
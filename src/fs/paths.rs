@@ -86,11 +86,101 @@ pub fn get_index_path(root: &Path) -> PathBuf {
     get_wreckit_dir(root).join("index.json")
 }
 
+/// Get the path to the repository lock held by commands that run the agent.
+pub fn get_lock_path(root: &Path) -> PathBuf {
+    get_wreckit_dir(root).join("lock")
+}
+
+/// Get the path to the local usage statistics file.
+pub fn get_stats_path(root: &Path) -> PathBuf {
+    get_wreckit_dir(root).join("stats.json")
+}
+
+/// Get the path to the local cache directory.
+pub fn get_cache_dir(root: &Path) -> PathBuf {
+    get_wreckit_dir(root).join("cache")
+}
+
+/// Get the directory holding cached agent responses.
+pub fn get_agent_cache_dir(root: &Path) -> PathBuf {
+    get_cache_dir(root).join("agent")
+}
+
+/// Get the path to the marker recording that the TUI's first-run hint was shown.
+pub fn get_tui_seen_path(root: &Path) -> PathBuf {
+    get_cache_dir(root).join("tui_seen")
+}
+
+/// Get the path to the saved TUI session view.
+pub fn get_tui_session_path(root: &Path) -> PathBuf {
+    get_cache_dir(root).join("tui-session.json")
+}
+
+/// Get the directory holding detached runs' event files and output logs.
+pub fn get_detached_dir(root: &Path) -> PathBuf {
+    get_cache_dir(root).join("detached")
+}
+
+/// Get the path to the event file of an item's detached run.
+pub fn get_detached_events_path(root: &Path, id: &str) -> PathBuf {
+    get_detached_dir(root).join(format!("{}.events.jsonl", id))
+}
+
+/// Get the path to the file recording the supervisor PID of an item's detached run.
+pub fn get_detached_pid_path(root: &Path, id: &str) -> PathBuf {
+    get_detached_dir(root).join(format!("{}.pid", id))
+}
+
+/// Get the path to the SQLite item index cache.
+pub fn get_index_db_path(root: &Path) -> PathBuf {
+    get_cache_dir(root).join("index.db")
+}
+
+/// Get the path to the metadata backups directory.
+pub fn get_backups_dir(root: &Path) -> PathBuf {
+    get_wreckit_dir(root).join("backups")
+}
+
+/// Get the path to a specific item's backups directory.
+pub fn get_item_backups_dir(root: &Path, id: &str) -> PathBuf {
+    get_backups_dir(root).join(id)
+}
+
+/// Get the path to the write-ahead journal directory.
+pub fn get_journal_dir(root: &Path) -> PathBuf {
+    get_wreckit_dir(root).join("journal")
+}
+
+/// Get the directory of forge operations queued while offline.
+pub fn get_outbox_dir(root: &Path) -> PathBuf {
+    get_wreckit_dir(root).join("outbox")
+}
+
+/// Get the directory of per-run event streams (`wreckit runs`).
+pub fn get_runs_dir(root: &Path) -> PathBuf {
+    get_wreckit_dir(root).join("runs")
+}
+
 /// Get the path to the prompts directory.
 pub fn get_prompts_dir(root: &Path) -> PathBuf {
     get_wreckit_dir(root).join("prompts")
 }
 
+/// Get the path to the WASM validation plugins directory.
+pub fn get_plugins_dir(root: &Path) -> PathBuf {
+    get_wreckit_dir(root).join("plugins")
+}
+
+/// Get the path to the PR comments `wreckit watch` has already handled.
+pub fn get_pr_bot_state_path(root: &Path) -> PathBuf {
+    get_wreckit_dir(root).join("pr_bot.json")
+}
+
+/// Get the path to the golden files written by `wreckit prompt snapshot`.
+pub fn get_prompt_snapshots_dir(root: &Path) -> PathBuf {
+    get_prompts_dir(root).join("snapshots")
+}
+
 /// Get the path to the items directory.
 pub fn get_items_dir(root: &Path) -> PathBuf {
     get_wreckit_dir(root).join("items")
@@ -126,6 +216,26 @@ pub fn get_progress_log_path(root: &Path, id: &str) -> PathBuf {
     get_item_dir(root, id).join("progress.log")
 }
 
+/// Get the path to an item's verification.md report of acceptance criteria.
+pub fn get_verification_path(root: &Path, id: &str) -> PathBuf {
+    get_item_dir(root, id).join("verification.md")
+}
+
+/// Get the path to an item's notes.md file of operator guidance.
+pub fn get_notes_path(root: &Path, id: &str) -> PathBuf {
+    get_item_dir(root, id).join("notes.md")
+}
+
+/// Get the path to an item's attachments directory of files for the PR.
+pub fn get_attachments_dir(root: &Path, id: &str) -> PathBuf {
+    get_item_dir(root, id).join("attachments")
+}
+
+/// Get the path to an item's recorded agent transcripts directory.
+pub fn get_transcripts_dir(root: &Path, id: &str) -> PathBuf {
+    get_item_dir(root, id).join("transcripts")
+}
+
 /// Get the path to an item's prompt.md file.
 pub fn get_prompt_path(root: &Path, id: &str) -> PathBuf {
     get_item_dir(root, id).join("prompt.md")
@@ -203,6 +313,9 @@ mod tests {
         assert_eq!(get_research_path(&root, id), PathBuf::from("/repo/.wreckit/items/test-001/research.md"));
         assert_eq!(get_plan_path(&root, id), PathBuf::from("/repo/.wreckit/items/test-001/plan.md"));
         assert_eq!(get_progress_log_path(&root, id), PathBuf::from("/repo/.wreckit/items/test-001/progress.log"));
+        assert_eq!(get_notes_path(&root, id), PathBuf::from("/repo/.wreckit/items/test-001/notes.md"));
+        assert_eq!(get_attachments_dir(&root, id), PathBuf::from("/repo/.wreckit/items/test-001/attachments"));
+        assert_eq!(get_verification_path(&root, id), PathBuf::from("/repo/.wreckit/items/test-001/verification.md"));
     }
 
     #[test]
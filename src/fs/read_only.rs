@@ -0,0 +1,184 @@
+//! Read-only spectator mode
+//!
+//! `--read-only` makes the process a spectator. Metadata writes, git and gh
+//! commands that change anything, GitHub API writes, and the agent and hook
+//! processes are all refused with a `ReadOnly` error. Commands that only look
+//! (`status`, `list`, `show`, `tui --attach`, `doctor`) keep working while
+//! another process holds the repository lock.
+
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::errors::{Result, WreckitError};
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+#[cfg(test)]
+thread_local! {
+    static THREAD_READ_ONLY: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Turn read-only mode on or off for this process
+pub fn set_read_only(read_only: bool) {
+    READ_ONLY.store(read_only, Ordering::SeqCst);
+}
+
+/// Whether this process is in read-only mode
+pub fn is_read_only() -> bool {
+    #[cfg(test)]
+    if THREAD_READ_ONLY.with(std::cell::Cell::get) {
+        return true;
+    }
+    READ_ONLY.load(Ordering::SeqCst)
+}
+
+/// Run `f` in read-only mode on this thread only, so other tests keep writing
+#[cfg(test)]
+pub(crate) fn with_read_only<T>(f: impl FnOnce() -> T) -> T {
+    THREAD_READ_ONLY.with(|flag| flag.set(true));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+    THREAD_READ_ONLY.with(|flag| flag.set(false));
+    result.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+/// Refuse an action that would change the repository in read-only mode.
+///
+/// `action` completes "refusing to ..." (e.g. "write item.json").
+///
+/// # Errors
+/// * `ReadOnly` - If read-only mode is on
+pub fn ensure_writable(action: impl FnOnce() -> String) -> Result<()> {
+    if is_read_only() {
+        return Err(WreckitError::ReadOnly(action()));
+    }
+    Ok(())
+}
+
+// Guarded counterparts of the std::fs calls that change files. Code outside
+// this module writes through these so read-only mode holds everywhere.
+
+/// Write `contents` to `path`, replacing the file.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the file cannot be written
+pub fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    ensure_writable(|| format!("write {}", path.display()))?;
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Create (or truncate) `path` for writing.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the file cannot be created
+pub fn create_file(path: &Path) -> Result<File> {
+    ensure_writable(|| format!("write {}", path.display()))?;
+    Ok(File::create(path)?)
+}
+
+/// Open `path` for appending, creating it if missing.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the file cannot be opened
+pub fn append_file(path: &Path) -> Result<File> {
+    ensure_writable(|| format!("write {}", path.display()))?;
+    Ok(OpenOptions::new().create(true).append(true).open(path)?)
+}
+
+/// Create `path` and any missing parents.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If a directory cannot be created
+pub fn create_dir_all(path: &Path) -> Result<()> {
+    ensure_writable(|| format!("create {}", path.display()))?;
+    std::fs::create_dir_all(path)?;
+    Ok(())
+}
+
+/// Copy the file `from` to `to`, replacing `to`.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the file cannot be copied
+pub fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    ensure_writable(|| format!("write {}", to.display()))?;
+    std::fs::copy(from, to)?;
+    Ok(())
+}
+
+/// Move the file `from` to `to`.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the file cannot be moved
+pub fn rename_file(from: &Path, to: &Path) -> Result<()> {
+    ensure_writable(|| format!("move {}", from.display()))?;
+    std::fs::rename(from, to)?;
+    Ok(())
+}
+
+/// Delete the file at `path`.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the file cannot be removed
+pub fn remove_file(path: &Path) -> Result<()> {
+    ensure_writable(|| format!("remove {}", path.display()))?;
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+/// Delete the directory at `path` and everything in it.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the directory cannot be removed
+pub fn remove_dir_all(path: &Path) -> Result<()> {
+    ensure_writable(|| format!("remove {}", path.display()))?;
+    std::fs::remove_dir_all(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_helpers_refuse_in_read_only_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().join("existing.txt");
+        std::fs::write(&existing, "kept").unwrap();
+        let new = dir.path().join("new.txt");
+        let sub = dir.path().join("sub");
+
+        with_read_only(|| {
+            assert!(is_read_only());
+            let refused = [
+                write_file(&new, "x"),
+                create_file(&new).map(drop),
+                append_file(&new).map(drop),
+                create_dir_all(&sub),
+                copy_file(&existing, &new),
+                rename_file(&existing, &new),
+                remove_file(&existing),
+                remove_dir_all(dir.path()),
+            ];
+            for result in refused {
+                assert!(matches!(result, Err(WreckitError::ReadOnly(_))));
+            }
+        });
+        assert!(!is_read_only());
+
+        assert!(!new.exists());
+        assert!(!sub.exists());
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "kept");
+
+        write_file(&new, "written").unwrap();
+        assert_eq!(std::fs::read_to_string(&new).unwrap(), "written");
+    }
+}
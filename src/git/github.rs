@@ -0,0 +1,671 @@
+//! GitHub REST API client
+//!
+//! Used for PR operations when the `gh` CLI is unavailable (containers, CI).
+//! It authenticates with `GITHUB_TOKEN` / `GH_TOKEN`, the `github` secret
+//! stored with `wreckit auth set github`, or as a GitHub App
+//! installation, exchanging a JWT signed with the app's private key for a
+//! short-lived installation token that is cached until it nears expiry.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::sha2::Sha256;
+use rsa::signature::{SignatureEncoding, Signer};
+use rsa::RsaPrivateKey;
+use serde_json::{json, Value};
+
+use crate::errors::{Result, WreckitError};
+use crate::http::HttpSettings;
+use crate::schemas::{GitHubAuth, GitHubConfig};
+use crate::secrets::lookup_secret;
+
+use super::operations::{run_git_command, CommitStatus, GitOptions, PrResult};
+use super::squash::split_message;
+
+/// Name of the keychain secret holding a GitHub token
+pub const GITHUB_SECRET: &str = "github";
+
+/// Base URL of the public GitHub API
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Marker path for the GraphQL endpoint, resolved by [`GitHubClient::url`]
+const GRAPHQL_PATH: &str = "/graphql";
+
+/// How the client authenticates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Credentials {
+    /// `GITHUB_TOKEN`, `GH_TOKEN`, or the keychain's `github` secret, read
+    /// when a request is made
+    EnvToken,
+
+    /// A GitHub App installation
+    App {
+        app_id: u64,
+        installation_id: u64,
+        private_key_path: PathBuf,
+    },
+}
+
+/// An installation token and when it expires
+type CachedToken = Option<(String, DateTime<Utc>)>;
+
+/// A GitHub REST API client
+#[derive(Clone)]
+pub struct GitHubClient {
+    api_url: String,
+    credentials: Credentials,
+    http: HttpSettings,
+    agent: ureq::Agent,
+    installation_token: Arc<Mutex<CachedToken>>,
+}
+
+impl fmt::Debug for GitHubClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GitHubClient")
+            .field("api_url", &self.api_url)
+            .field("credentials", &self.credentials)
+            .field("http", &self.http)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Whether an executable with this name is on PATH
+fn on_path(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+fn env_token() -> Option<String> {
+    ["GITHUB_TOKEN", "GH_TOKEN"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|token| !token.trim().is_empty())
+        .or_else(|| lookup_secret(GITHUB_SECRET))
+}
+
+/// API base URL for a GitHub host; Enterprise Server serves it under `/api/v3`
+pub fn api_url_for_host(host: &str) -> String {
+    let host = host.trim().trim_end_matches('/');
+    let host = host
+        .strip_prefix("https://")
+        .or_else(|| host.strip_prefix("http://"))
+        .unwrap_or(host);
+    match host {
+        "github.com" | "api.github.com" => GITHUB_API_URL.to_string(),
+        _ => format!("https://{}/api/v3", host),
+    }
+}
+
+/// `(owner, repo)` from an https or ssh remote URL
+pub fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    let url = url.trim().trim_end_matches('/');
+    let path = match url.split_once("://") {
+        // https://host/owner/repo(.git), ssh://git@host/owner/repo(.git)
+        Some((_, rest)) => rest.split_once('/')?.1,
+        // git@host:owner/repo(.git)
+        None => url.split_once(':')?.1,
+    };
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, repo) = path.split_once('/')?;
+    if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+        return None;
+    }
+    Some((owner.to_string(), repo.to_string()))
+}
+
+/// A JWT identifying a GitHub App, valid for nine minutes from `now`
+///
+/// # Errors
+/// * `ConfigError` - If the key is not an RSA private key in PKCS#1 or
+///   PKCS#8 PEM
+pub fn app_jwt(app_id: u64, private_key_pem: &str, now: DateTime<Utc>) -> Result<String> {
+    let key = RsaPrivateKey::from_pkcs1_pem(private_key_pem)
+        .or_else(|_| RsaPrivateKey::from_pkcs8_pem(private_key_pem))
+        .map_err(|e| WreckitError::ConfigError(format!("invalid GitHub App private key: {}", e)))?;
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256","typ":"JWT"}"#);
+    // Backdated to allow for clock drift, as GitHub recommends
+    let claims = json!({
+        "iat": (now - Duration::seconds(60)).timestamp(),
+        "exp": (now + Duration::seconds(540)).timestamp(),
+        "iss": app_id.to_string(),
+    });
+    let claims = URL_SAFE_NO_PAD.encode(claims.to_string());
+    let message = format!("{}.{}", header, claims);
+    let signature = SigningKey::<Sha256>::new(key).sign(message.as_bytes());
+    Ok(format!(
+        "{}.{}",
+        message,
+        URL_SAFE_NO_PAD.encode(signature.to_bytes())
+    ))
+}
+
+fn pr_result(value: &Value, created: bool) -> Option<PrResult> {
+    Some(PrResult {
+        url: value["html_url"].as_str()?.to_string(),
+        number: value["number"].as_u64()? as u32,
+        created,
+    })
+}
+
+impl GitHubClient {
+    /// A client for the given credentials
+    pub fn new(credentials: Credentials) -> Self {
+        let http = HttpSettings::default();
+        GitHubClient {
+            api_url: GITHUB_API_URL.to_string(),
+            credentials,
+            agent: http.agent(GITHUB_API_URL),
+            http,
+            installation_token: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// The client to use for this configuration, or `None` to use `gh`.
+    ///
+    /// In auto mode that is `gh` when it is on PATH, then a token from the
+    /// environment, then the GitHub App if one is configured.
+    pub fn from_config(root: &Path, config: &GitHubConfig, http: &HttpSettings) -> Option<Self> {
+        let app = || match (
+            config.app_id,
+            config.installation_id,
+            &config.private_key_path,
+        ) {
+            (Some(app_id), Some(installation_id), Some(path)) => Some(Credentials::App {
+                app_id,
+                installation_id,
+                private_key_path: root.join(path),
+            }),
+            _ => None,
+        };
+        let credentials = match config.auth {
+            GitHubAuth::Gh => return None,
+            GitHubAuth::Token => Credentials::EnvToken,
+            GitHubAuth::App => match app() {
+                Some(credentials) => credentials,
+                None => {
+                    tracing::warn!(
+                        "forge.github.auth is \"app\" but app_id, installation_id, or private_key_path is missing; using gh"
+                    );
+                    return None;
+                }
+            },
+            GitHubAuth::Auto if on_path("gh") => return None,
+            GitHubAuth::Auto if env_token().is_some() => Credentials::EnvToken,
+            GitHubAuth::Auto => app()?,
+        };
+        let client = GitHubClient::new(credentials).with_http(http.clone());
+        Some(match config.host {
+            Some(ref host) => client.with_api_url(api_url_for_host(host)),
+            None => client,
+        })
+    }
+
+    /// How the client authenticates
+    pub fn credentials(&self) -> &Credentials {
+        &self.credentials
+    }
+
+    /// Return a client that talks to another API base URL
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self.agent = self.http.agent(&self.api_url);
+        self
+    }
+
+    /// Return a client that uses the given proxy and CA settings
+    pub fn with_http(mut self, http: HttpSettings) -> Self {
+        self.agent = http.agent(&self.api_url);
+        self.http = http;
+        self
+    }
+
+    /// Full URL for an API path; Enterprise Server serves GraphQL at
+    /// `/api/graphql` rather than under the REST base `/api/v3`
+    fn url(&self, path: &str) -> String {
+        match self.api_url.strip_suffix("/api/v3") {
+            Some(host) if path == GRAPHQL_PATH => format!("{}/api/graphql", host),
+            _ => format!("{}{}", self.api_url, path),
+        }
+    }
+
+    /// A token for the Authorization header
+    async fn token(&self) -> Result<String> {
+        let (app_id, installation_id, private_key_path) = match self.credentials {
+            Credentials::EnvToken => {
+                return env_token().ok_or_else(|| {
+                    WreckitError::ConfigError(
+                        "set GITHUB_TOKEN or GH_TOKEN, or run `wreckit auth set github`, to use the GitHub API".to_string(),
+                    )
+                })
+            }
+            Credentials::App {
+                app_id,
+                installation_id,
+                ref private_key_path,
+            } => (app_id, installation_id, private_key_path),
+        };
+
+        let now = Utc::now();
+        if let Some((ref token, expires_at)) = *self.lock_token() {
+            if expires_at - now > Duration::seconds(60) {
+                return Ok(token.clone());
+            }
+        }
+        let pem = std::fs::read_to_string(private_key_path).map_err(|e| {
+            WreckitError::ConfigError(format!(
+                "cannot read GitHub App key {}: {}",
+                private_key_path.display(),
+                e
+            ))
+        })?;
+        let jwt = app_jwt(app_id, &pem, now)?;
+        let path = format!("/app/installations/{}/access_tokens", installation_id);
+        let response = self.send("POST", &path, &[], Some(json!({})), &jwt).await?;
+        let token = response["token"].as_str().ok_or_else(|| {
+            WreckitError::InvalidJson("installation token response has no token".to_string())
+        })?;
+        let expires_at = response["expires_at"]
+            .as_str()
+            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            .map(|at| at.with_timezone(&Utc))
+            .unwrap_or(now + Duration::minutes(50));
+        *self.lock_token() = Some((token.to_string(), expires_at));
+        Ok(token.to_string())
+    }
+
+    fn lock_token(&self) -> std::sync::MutexGuard<'_, CachedToken> {
+        self.installation_token
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Make a request with the given bearer token and return the JSON body
+    async fn send(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<Value>,
+        token: &str,
+    ) -> Result<Value> {
+        let mut request = self
+            .agent
+            .request(method, &self.url(path))
+            .set("Accept", "application/vnd.github+json")
+            .set("X-GitHub-Api-Version", "2022-11-28")
+            .set("User-Agent", "wreckit")
+            .set("Authorization", &format!("Bearer {}", token));
+        for (name, value) in query {
+            request = request.query(name, value);
+        }
+        let label = format!("GitHub API {} {}", method, path);
+        let failed = label.clone();
+        // ureq blocks, so keep it off the async runtime's worker threads
+        tokio::task::spawn_blocking(move || {
+            let outcome = match body {
+                Some(body) => request.send_json(body),
+                None => request.call(),
+            };
+            let response = match outcome {
+                Ok(response) => response,
+                Err(ureq::Error::Status(code, response)) => {
+                    let message = response
+                        .into_json::<Value>()
+                        .ok()
+                        .and_then(|body| body["message"].as_str().map(String::from))
+                        .unwrap_or_default();
+                    return Err(WreckitError::GitError(format!(
+                        "{} failed: {} {}",
+                        label, code, message
+                    )));
+                }
+                Err(e) => return Err(WreckitError::GitError(format!("{} failed: {}", label, e))),
+            };
+            let text = response
+                .into_string()
+                .map_err(|e| WreckitError::GitError(format!("{} failed: {}", label, e)))?;
+            if text.trim().is_empty() {
+                return Ok(Value::Null);
+            }
+            serde_json::from_str(&text).map_err(|e| WreckitError::InvalidJson(e.to_string()))
+        })
+        .await
+        .map_err(|e| WreckitError::GitError(format!("{} failed: {}", failed, e)))?
+    }
+
+    /// Make an authenticated request; in dry-run mode only log it
+    async fn request(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<Value>,
+        options: &GitOptions,
+    ) -> Result<Value> {
+        if options.dry_run {
+            tracing::info!("[DRY RUN] GitHub API {} {}", method, path);
+            return Ok(Value::Null);
+        }
+        if method != "GET" {
+            crate::fs::ensure_writable(|| format!("call GitHub API {} {}", method, path))?;
+        }
+        let token = self.token().await?;
+        self.send(method, path, query, body, &token).await
+    }
+
+    /// Check that the API is reachable and accepts the credentials
+    ///
+    /// # Errors
+    /// * `ConfigError` - If no token is set or the app key is unreadable
+    /// * `GitError` - If the API cannot be reached or rejects the credentials
+    pub async fn check_auth(&self, options: &GitOptions) -> Result<()> {
+        if options.dry_run {
+            return Ok(());
+        }
+        match self.credentials {
+            Credentials::EnvToken => {
+                self.request("GET", "/user", &[], None, options).await?;
+            }
+            // Exchanging the JWT for an installation token authenticates the app
+            Credentials::App { .. } => {
+                self.token().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `(owner, repo)` of the origin remote
+    async fn repo(&self, options: &GitOptions) -> Result<(String, String)> {
+        let url = run_git_command(&["remote", "get-url", "origin"], options).await?;
+        parse_remote_url(&url).ok_or_else(|| {
+            WreckitError::GitError(format!("cannot tell the GitHub repository from {}", url))
+        })
+    }
+
+    /// The open PR whose head is `branch`, if any
+    ///
+    /// # Errors
+    /// * `GitError` - If the origin remote is not a GitHub URL or the API
+    ///   request fails
+    pub async fn pr_for_branch(
+        &self,
+        branch: &str,
+        options: &GitOptions,
+    ) -> Result<Option<PrResult>> {
+        if options.dry_run {
+            return Ok(None);
+        }
+        let (owner, repo) = self.repo(options).await?;
+        let head = format!("{}:{}", owner, branch);
+        let pulls = self
+            .request(
+                "GET",
+                &format!("/repos/{}/{}/pulls", owner, repo),
+                &[("head", &head), ("state", "open")],
+                None,
+                options,
+            )
+            .await?;
+        Ok(pulls
+            .as_array()
+            .and_then(|pulls| pulls.first())
+            .and_then(|pr| pr_result(pr, false)))
+    }
+
+    /// Open a PR
+    ///
+    /// # Errors
+    /// * `GitError` - If the origin remote is not a GitHub URL or the API
+    ///   request fails
+    pub async fn create_pr(
+        &self,
+        base_branch: &str,
+        head_branch: &str,
+        title: &str,
+        body: &str,
+        options: &GitOptions,
+    ) -> Result<PrResult> {
+        if options.dry_run {
+            tracing::info!(
+                "[DRY RUN] GitHub API: open PR {} -> {}",
+                head_branch,
+                base_branch
+            );
+            return Ok(PrResult {
+                url: String::new(),
+                number: 0,
+                created: true,
+            });
+        }
+        let (owner, repo) = self.repo(options).await?;
+        let pr = self
+            .request(
+                "POST",
+                &format!("/repos/{}/{}/pulls", owner, repo),
+                &[],
+                Some(json!({
+                    "base": base_branch,
+                    "head": head_branch,
+                    "title": title,
+                    "body": body,
+                })),
+                options,
+            )
+            .await?;
+        pr_result(&pr, true).ok_or_else(|| {
+            WreckitError::InvalidJson("PR response has no number or URL".to_string())
+        })
+    }
+
+    /// Turn on auto-merge for a PR with the given method ("squash", "merge",
+    /// or "rebase") and optional commit message; only the GraphQL API
+    /// supports this
+    ///
+    /// # Errors
+    /// * `GitError` - If the API request fails or GitHub refuses (e.g. the
+    ///   repository does not allow auto-merge)
+    pub async fn enable_auto_merge(
+        &self,
+        pr_number: u32,
+        method: &str,
+        message: Option<&str>,
+        options: &GitOptions,
+    ) -> Result<()> {
+        if options.dry_run {
+            tracing::info!(
+                "[DRY RUN] GitHub API: enable {} auto-merge on #{}",
+                method,
+                pr_number
+            );
+            return Ok(());
+        }
+        let (owner, repo) = self.repo(options).await?;
+        let pr = self
+            .request(
+                "GET",
+                &format!("/repos/{}/{}/pulls/{}", owner, repo, pr_number),
+                &[],
+                None,
+                options,
+            )
+            .await?;
+        let node_id = pr["node_id"]
+            .as_str()
+            .ok_or_else(|| WreckitError::InvalidJson("PR response has no node_id".to_string()))?;
+        let query = "mutation($id: ID!, $method: PullRequestMergeMethod!, \
+            $headline: String, $body: String) { \
+            enablePullRequestAutoMerge(input: {pullRequestId: $id, mergeMethod: $method, \
+            commitHeadline: $headline, commitBody: $body}) { clientMutationId } }";
+        let (headline, body) = match message.filter(|_| method != "rebase") {
+            Some(message) => {
+                let (subject, body) = split_message(message);
+                (Some(subject), Some(body))
+            }
+            None => (None, None),
+        };
+        let response = self
+            .request(
+                "POST",
+                GRAPHQL_PATH,
+                &[],
+                Some(json!({
+                    "query": query,
+                    "variables": {
+                        "id": node_id,
+                        "method": method.to_uppercase(),
+                        "headline": headline,
+                        "body": body,
+                    },
+                })),
+                options,
+            )
+            .await?;
+        match response["errors"]
+            .as_array()
+            .and_then(|errors| errors.first())
+        {
+            Some(error) => Err(WreckitError::GitError(format!(
+                "cannot enable auto-merge on #{}: {}",
+                pr_number,
+                error["message"].as_str().unwrap_or("unknown error")
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether a PR has been merged
+    ///
+    /// # Errors
+    /// * `GitError` - If the origin remote is not a GitHub URL or the API
+    ///   request fails
+    pub async fn is_pr_merged(&self, pr_number: u32, options: &GitOptions) -> Result<bool> {
+        if options.dry_run {
+            return Ok(false);
+        }
+        let (owner, repo) = self.repo(options).await?;
+        let pr = self
+            .request(
+                "GET",
+                &format!("/repos/{}/{}/pulls/{}", owner, repo, pr_number),
+                &[],
+                None,
+                options,
+            )
+            .await?;
+        Ok(pr["merged"].as_bool().unwrap_or(false))
+    }
+
+    /// Set a commit status on `sha`
+    ///
+    /// # Errors
+    /// * `GitError` - If the origin remote is not a GitHub URL or the API
+    ///   request fails
+    pub async fn set_commit_status(
+        &self,
+        sha: &str,
+        status: &CommitStatus,
+        options: &GitOptions,
+    ) -> Result<()> {
+        let (owner, repo) = self.repo(options).await?;
+        self.request(
+            "POST",
+            &format!("/repos/{}/{}/statuses/{}", owner, repo, sha),
+            &[],
+            Some(json!({
+                "state": status.state,
+                "context": status.context,
+                "description": status.description,
+            })),
+            options,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::pkcs1::{EncodeRsaPrivateKey, LineEnding};
+    use rsa::pkcs1v15::{Signature, VerifyingKey};
+    use rsa::rand_core::OsRng;
+    use rsa::signature::Verifier;
+
+    #[test]
+    fn test_parse_remote_url() {
+        let expected = Some(("acme".to_string(), "widgets".to_string()));
+        assert_eq!(
+            parse_remote_url("https://github.com/acme/widgets.git"),
+            expected
+        );
+        assert_eq!(
+            parse_remote_url("git@github.com:acme/widgets.git"),
+            expected
+        );
+        assert_eq!(
+            parse_remote_url("ssh://git@github.com/acme/widgets"),
+            expected
+        );
+        assert_eq!(parse_remote_url("https://github.com/acme"), None);
+        assert_eq!(parse_remote_url("/srv/git/widgets"), None);
+    }
+
+    #[test]
+    fn test_enterprise_host() {
+        assert_eq!(api_url_for_host("github.com"), GITHUB_API_URL);
+        assert_eq!(
+            api_url_for_host("https://github.mycorp.com/"),
+            "https://github.mycorp.com/api/v3"
+        );
+
+        let mut config = GitHubConfig {
+            auth: GitHubAuth::Token,
+            ..Default::default()
+        };
+        let http = HttpSettings::default();
+        let client = GitHubClient::from_config(Path::new("."), &config, &http).unwrap();
+        assert_eq!(client.url("/user"), format!("{}/user", GITHUB_API_URL));
+
+        config.host = Some("github.mycorp.com".to_string());
+        let client = GitHubClient::from_config(Path::new("."), &config, &http).unwrap();
+        assert_eq!(client.url("/user"), "https://github.mycorp.com/api/v3/user");
+        assert_eq!(
+            client.url(GRAPHQL_PATH),
+            "https://github.mycorp.com/api/graphql"
+        );
+    }
+
+    #[test]
+    fn test_app_jwt() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+        let pem = key.to_pkcs1_pem(LineEnding::LF).unwrap();
+        let jwt = app_jwt(42, &pem, now).unwrap();
+        let parts: Vec<&str> = jwt.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        let claims: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[1]).unwrap()).unwrap();
+        assert_eq!(claims["iss"], "42");
+        assert_eq!(claims["iat"], now.timestamp() - 60);
+        assert_eq!(claims["exp"], now.timestamp() + 540);
+
+        let verifying = VerifyingKey::<Sha256>::new(key.to_public_key());
+        let signature =
+            Signature::try_from(URL_SAFE_NO_PAD.decode(parts[2]).unwrap().as_slice()).unwrap();
+        let message = format!("{}.{}", parts[0], parts[1]);
+        assert!(verifying.verify(message.as_bytes(), &signature).is_ok());
+
+        assert!(app_jwt(42, "not a key", now).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+//! Commits to a side branch without touching the working tree
+//!
+//! Used to keep `.wreckit/` metadata on a dedicated (orphan) branch. All
+//! work happens in a scratch index under the git directory, so the checked
+//! out branch, the real index, and uncommitted changes are never disturbed.
+
+use std::path::PathBuf;
+
+use crate::errors::Result;
+use crate::fs;
+
+use super::operations::{run_git_command, run_git_command_with_env, GitOptions};
+
+/// Scratch index file name inside the git directory
+const SCRATCH_INDEX: &str = "wreckit-meta.index";
+
+async fn scratch_index(options: &GitOptions) -> Result<PathBuf> {
+    let git_dir = run_git_command(&["rev-parse", "--absolute-git-dir"], options).await?;
+    let index = PathBuf::from(git_dir).join(SCRATCH_INDEX);
+    if index.exists() {
+        fs::remove_file(&index)?;
+    }
+    Ok(index)
+}
+
+/// Resolve a ref to a commit, or None if it does not exist
+pub async fn resolve_ref(reference: &str, options: &GitOptions) -> Option<String> {
+    run_git_command(&["rev-parse", "--verify", "-q", reference], options)
+        .await
+        .ok()
+        .filter(|sha| !sha.is_empty())
+}
+
+/// Commit the working-tree files matching `pathspec` onto `branch`.
+///
+/// The branch is created as an orphan if it does not exist. Nothing is
+/// committed when the matching files are unchanged.
+///
+/// # Returns
+/// The new commit, or None if there was nothing to commit
+///
+/// # Errors
+/// * `GitError` - If a git plumbing command fails
+pub async fn commit_paths_to_branch(
+    branch: &str,
+    pathspec: &[&str],
+    message: &str,
+    options: &GitOptions,
+) -> Result<Option<String>> {
+    if options.dry_run {
+        tracing::info!(
+            "[DRY RUN] Would commit {} to {}",
+            pathspec.join(" "),
+            branch
+        );
+        return Ok(None);
+    }
+
+    let index = scratch_index(options).await?;
+    let index_str = index.to_string_lossy().to_string();
+    let env = [("GIT_INDEX_FILE", index_str.as_str())];
+    let branch_ref = format!("refs/heads/{}", branch);
+    let parent = resolve_ref(&branch_ref, options).await;
+
+    let result = async {
+        match parent {
+            Some(ref parent) => {
+                run_git_command_with_env(&["read-tree", parent], &env, options).await?
+            }
+            None => run_git_command_with_env(&["read-tree", "--empty"], &env, options).await?,
+        };
+        let mut add = vec!["add", "-A", "-f", "--"];
+        add.extend_from_slice(pathspec);
+        run_git_command_with_env(&add, &env, options).await?;
+        let tree = run_git_command_with_env(&["write-tree"], &env, options).await?;
+
+        if let Some(ref parent) = parent {
+            let parent_tree =
+                run_git_command(&["rev-parse", &format!("{}^{{tree}}", parent)], options).await?;
+            if parent_tree == tree {
+                return Ok(None);
+            }
+        }
+
+        let mut commit_args = vec!["commit-tree", tree.as_str(), "-m", message];
+        if let Some(ref parent) = parent {
+            commit_args.extend_from_slice(&["-p", parent]);
+        }
+        let commit = run_git_command(&commit_args, options).await?;
+        run_git_command(&["update-ref", &branch_ref, &commit], options).await?;
+        Ok(Some(commit))
+    }
+    .await;
+
+    let _ = fs::remove_file(&index);
+    result
+}
+
+/// Merge `other` into `branch` without a checkout.
+///
+/// Fast-forwards when possible and otherwise writes a merge commit.
+///
+/// # Errors
+/// * `GitError` - If the histories conflict or a git command fails
+pub async fn merge_into_branch(branch: &str, other: &str, options: &GitOptions) -> Result<()> {
+    let branch_ref = format!("refs/heads/{}", branch);
+    let theirs = match resolve_ref(other, options).await {
+        Some(sha) => sha,
+        None => return Ok(()),
+    };
+    let ours = match resolve_ref(&branch_ref, options).await {
+        Some(sha) => sha,
+        None => {
+            run_git_command(&["update-ref", &branch_ref, &theirs], options).await?;
+            return Ok(());
+        }
+    };
+
+    let is_ancestor = |a: String, b: String| async move {
+        run_git_command(&["merge-base", "--is-ancestor", &a, &b], options)
+            .await
+            .is_ok()
+    };
+    if is_ancestor(theirs.clone(), ours.clone()).await {
+        return Ok(());
+    }
+    if is_ancestor(ours.clone(), theirs.clone()).await {
+        run_git_command(&["update-ref", &branch_ref, &theirs], options).await?;
+        return Ok(());
+    }
+
+    let output = run_git_command(
+        &[
+            "merge-tree",
+            "--write-tree",
+            "--allow-unrelated-histories",
+            &ours,
+            &theirs,
+        ],
+        options,
+    )
+    .await?;
+    let tree = output.lines().next().unwrap_or_default().to_string();
+    let message = format!("Merge {} into {}", other, branch);
+    let commit = run_git_command(
+        &[
+            "commit-tree",
+            &tree,
+            "-p",
+            &ours,
+            "-p",
+            &theirs,
+            "-m",
+            &message,
+        ],
+        options,
+    )
+    .await?;
+    run_git_command(&["update-ref", &branch_ref, &commit], options).await?;
+    Ok(())
+}
+
+/// Write every file on `branch` into the working tree, overwriting local copies.
+///
+/// # Errors
+/// * `GitError` - If the branch does not exist or a git command fails
+pub async fn checkout_branch_files(branch: &str, options: &GitOptions) -> Result<()> {
+    if options.dry_run {
+        tracing::info!("[DRY RUN] Would check out files from {}", branch);
+        return Ok(());
+    }
+    let index = scratch_index(options).await?;
+    let index_str = index.to_string_lossy().to_string();
+    let env = [("GIT_INDEX_FILE", index_str.as_str())];
+    let result = async {
+        run_git_command_with_env(
+            &["read-tree", &format!("refs/heads/{}", branch)],
+            &env,
+            options,
+        )
+        .await?;
+        run_git_command_with_env(&["checkout-index", "-a", "-f"], &env, options).await?;
+        Ok(())
+    }
+    .await;
+    let _ = fs::remove_file(&index);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn repo() -> (TempDir, GitOptions) {
+        let temp = TempDir::new().unwrap();
+        let options = GitOptions {
+            cwd: temp.path().to_path_buf(),
+            dry_run: false,
+            github: None,
+            gh_host: None,
+            cancel: None,
+        };
+        run_git_command(&["init", "-q", "-b", "main"], &options)
+            .await
+            .unwrap();
+        run_git_command(&["config", "user.email", "test@example.com"], &options)
+            .await
+            .unwrap();
+        run_git_command(&["config", "user.name", "Test"], &options)
+            .await
+            .unwrap();
+        std::fs::write(temp.path().join("code.txt"), "code").unwrap();
+        run_git_command(&["add", "-A"], &options).await.unwrap();
+        run_git_command(&["commit", "-q", "-m", "init"], &options)
+            .await
+            .unwrap();
+        (temp, options)
+    }
+
+    #[tokio::test]
+    async fn test_commit_paths_to_orphan_branch() {
+        let (temp, options) = repo().await;
+        std::fs::create_dir_all(temp.path().join(".wreckit")).unwrap();
+        std::fs::write(temp.path().join(".wreckit/config.json"), "{}").unwrap();
+
+        let first = commit_paths_to_branch("meta", &[".wreckit"], "meta 1", &options)
+            .await
+            .unwrap();
+        assert!(first.is_some());
+        let files = run_git_command(&["ls-tree", "-r", "--name-only", "meta"], &options)
+            .await
+            .unwrap();
+        assert_eq!(files, ".wreckit/config.json");
+
+        // Unchanged files produce no commit; the checked out branch is untouched
+        let second = commit_paths_to_branch("meta", &[".wreckit"], "meta 2", &options)
+            .await
+            .unwrap();
+        assert!(second.is_none());
+        let status = run_git_command(&["status", "--porcelain"], &options)
+            .await
+            .unwrap();
+        assert_eq!(status, "?? .wreckit/");
+    }
+
+    #[tokio::test]
+    async fn test_merge_and_checkout_branch_files() {
+        let (temp, options) = repo().await;
+        let dir = temp.path().join(".wreckit");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(dir.join("a.json"), "a").unwrap();
+        commit_paths_to_branch("meta", &[".wreckit"], "a", &options)
+            .await
+            .unwrap();
+        run_git_command(&["branch", "theirs", "meta"], &options)
+            .await
+            .unwrap();
+
+        // Diverge: one side adds b, the other adds c
+        std::fs::write(dir.join("b.json"), "b").unwrap();
+        commit_paths_to_branch("meta", &[".wreckit"], "b", &options)
+            .await
+            .unwrap();
+        std::fs::remove_file(dir.join("b.json")).unwrap();
+        std::fs::write(dir.join("c.json"), "c").unwrap();
+        commit_paths_to_branch("theirs", &[".wreckit"], "c", &options)
+            .await
+            .unwrap();
+
+        merge_into_branch("meta", "refs/heads/theirs", &options)
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        checkout_branch_files("meta", &options).await.unwrap();
+        for name in ["a.json", "b.json", "c.json"] {
+            assert!(dir.join(name).exists(), "{} missing", name);
+        }
+    }
+}
@@ -1,12 +1,31 @@
 //! Git operations module
 //!
-//! Provides wrappers for git and gh CLI commands.
+//! Provides wrappers for git and gh CLI commands, a GitHub REST client used
+//! in their place where `gh` is unavailable, plus plumbing for committing to
+//! a side branch without a checkout.
 
+mod github;
+mod meta;
 mod operations;
+mod squash;
 
+pub use github::{
+    api_url_for_host, app_jwt, parse_remote_url, Credentials, GitHubClient, GITHUB_API_URL,
+    GITHUB_SECRET,
+};
+pub use meta::{checkout_branch_files, commit_paths_to_branch, merge_into_branch, resolve_ref};
 pub use operations::{
-    branch_exists, check_git_preflight, commit_all, create_or_update_pr, ensure_branch,
-    get_current_branch, get_pr_by_branch, has_uncommitted_changes, is_git_repo, is_pr_merged,
-    push_branch, run_gh_command, run_git_command, BranchResult, GitOptions, GitPreflightResult,
-    PrResult,
+    add_pr_labels, add_worktree, added_lines, branch_exists, changed_files, check_git_preflight,
+    check_github_auth, check_name_matches, close_pr, comment_on_issue, comment_on_pr, commit_all,
+    commit_files, commits_ahead, create_draft_pr, create_or_update_pr, delete_branch,
+    delete_remote_branch, edit_pr, diff_stat, enable_auto_merge, ensure_branch, failed_checks,
+    failed_run_log, filter_checks, get_current_branch, get_pr_by_branch, get_user_email,
+    has_uncommitted_changes, is_ancestor_of_head, is_git_repo, is_pr_merged, issue, mark_pr_ready,
+    merged_branches, merged_remote_branches, new_files, parse_added_lines, parse_failed_checks,
+    parse_issue, parse_pr_checks, parse_pr_comments, parse_workflow_runs, pr_checks, pr_comments,
+    push_branch, remote_branch_exists, remove_worktree, restore_paths, run_gh_command,
+    run_git_command, run_git_command_with_env, set_commit_status, uncommitted_diff, workflow_runs,
+    AddedLine, BranchResult, CheckState, CommitStatus, DiffStat, GitOptions, GitPreflightResult,
+    Issue, PrCheck, PrComment, PrResult, WorkflowRun,
 };
+pub use squash::{build_squash_message, split_message, ITEM_TRAILER};
@@ -6,9 +6,13 @@ use std::path::{Path, PathBuf};
 use std::process::Stdio;
 
 use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
 
 use crate::errors::{Result, WreckitError};
 
+use super::github::GitHubClient;
+use super::squash::split_message;
+
 /// Options for git operations
 #[derive(Debug, Clone)]
 pub struct GitOptions {
@@ -17,6 +21,15 @@ pub struct GitOptions {
 
     /// If true, log commands without executing
     pub dry_run: bool,
+
+    /// GitHub API client for PR operations; `gh` is used when `None`
+    pub github: Option<GitHubClient>,
+
+    /// GitHub Enterprise host passed to `gh` as `GH_HOST`
+    pub gh_host: Option<String>,
+
+    /// Token that kills an in-flight git or gh command when cancelled
+    pub cancel: Option<CancellationToken>,
 }
 
 /// Result of a branch operation
@@ -29,6 +42,45 @@ pub struct BranchResult {
     pub created: bool,
 }
 
+/// Size of a branch's changes relative to its base
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStat {
+    /// Number of files changed
+    pub files: usize,
+
+    /// Lines added plus lines deleted (binary files count as 0)
+    pub lines: usize,
+}
+
+/// A line added on a branch, relative to its base
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddedLine {
+    /// File path relative to the repository root
+    pub path: String,
+
+    /// Line number in the new version of the file
+    pub line: usize,
+
+    /// Line content, without the leading `+`
+    pub text: String,
+}
+
+/// A conversation comment on a PR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrComment {
+    /// GitHub node ID
+    pub id: String,
+
+    /// Login of the author
+    pub author: String,
+
+    /// The author's relationship to the repository (e.g. "MEMBER")
+    pub association: String,
+
+    /// Comment text
+    pub body: String,
+}
+
 /// Result of a PR operation
 #[derive(Debug)]
 pub struct PrResult {
@@ -52,21 +104,161 @@ pub struct GitPreflightResult {
     pub errors: Vec<String>,
 }
 
+/// Run a command to completion, killing it if the options' token is
+/// cancelled first.
+///
+/// # Errors
+/// * `Interrupted` - If the token is cancelled
+/// * `GitError` - If the command cannot be started
+async fn output_or_cancel(
+    command: &mut Command,
+    program: &str,
+    options: &GitOptions,
+) -> Result<std::process::Output> {
+    let cancel = options.cancel.as_ref();
+    if cancel.is_some_and(CancellationToken::is_cancelled) {
+        return Err(WreckitError::Interrupted);
+    }
+    let output = command.kill_on_drop(true).output();
+    let output = match cancel {
+        Some(token) => tokio::select! {
+            output = output => output,
+            _ = token.cancelled() => return Err(WreckitError::Interrupted),
+        },
+        None => output.await,
+    };
+    output.map_err(|e| WreckitError::GitError(format!("Failed to execute {}: {}", program, e)))
+}
+
 /// Execute a git command and return stdout
 pub async fn run_git_command(args: &[&str], options: &GitOptions) -> Result<String> {
+    run_git_command_with_env(args, &[], options).await
+}
+
+/// Git subcommands that never change the repository
+const READ_ONLY_GIT: &[&str] = &[
+    "blame",
+    "cat-file",
+    "describe",
+    "diff",
+    "diff-tree",
+    "for-each-ref",
+    "grep",
+    "log",
+    "ls-files",
+    "ls-remote",
+    "ls-tree",
+    "merge-base",
+    "rev-list",
+    "rev-parse",
+    "show",
+    "status",
+];
+
+/// Whether a git command may change the repository or its config.
+///
+/// Unknown commands are assumed to.
+pub fn git_mutates(args: &[&str]) -> bool {
+    let rest = args.get(1..).unwrap_or_default();
+    match args.first().copied() {
+        Some(sub) if READ_ONLY_GIT.contains(&sub) => false,
+        // `git config <key>` and `git config --get...` read; anything else sets
+        Some("config") => !(rest.len() == 1 || rest.iter().any(|a| a.starts_with("--get"))),
+        Some("remote") => !matches!(rest.first(), Some(&"get-url") | Some(&"-v") | None),
+        Some("branch") => branch_mutates(rest),
+        Some("worktree") => rest.first() != Some(&"list"),
+        _ => true,
+    }
+}
+
+/// `git branch` options that only list or filter branches
+const BRANCH_LIST_FLAGS: &[&str] = &[
+    "--list",
+    "-l",
+    "--show-current",
+    "-a",
+    "--all",
+    "-r",
+    "--remotes",
+    "-v",
+    "-vv",
+    "--merged",
+    "--no-merged",
+    "--contains",
+    "--no-contains",
+];
+
+/// Whether `git branch <args>` may create, delete, or change a branch.
+///
+/// Listings are read-only, including `--merged <base>` and the like with
+/// their commit, `--format=...`, and the patterns a listing filters by.
+fn branch_mutates(args: &[&str]) -> bool {
+    let listing = args.iter().any(|a| {
+        matches!(
+            *a,
+            "--list" | "-l" | "--merged" | "--no-merged" | "--contains" | "--no-contains"
+        )
+    });
+    let mut takes_commit = false;
+    for arg in args {
+        let commit = std::mem::take(&mut takes_commit);
+        if arg.starts_with('-') {
+            if !BRANCH_LIST_FLAGS.contains(arg) && !arg.starts_with("--format=") {
+                return true;
+            }
+            takes_commit = matches!(
+                *arg,
+                "--merged" | "--no-merged" | "--contains" | "--no-contains"
+            );
+        } else if !commit && !listing {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether a gh command may change anything on GitHub or locally.
+///
+/// Only `view`, `list`, `status`, `checks`, and `diff` subcommands and plain
+/// `api` GETs
+/// are treated as reads.
+pub fn gh_mutates(args: &[&str]) -> bool {
+    match args {
+        ["api", rest @ ..] => rest.iter().any(|a| {
+            matches!(
+                *a,
+                "-X" | "--method" | "-f" | "-F" | "--field" | "--raw-field" | "--input"
+            )
+        }),
+        [_, action, ..] => !matches!(*action, "view" | "list" | "status" | "checks" | "diff"),
+        _ => true,
+    }
+}
+
+/// Execute a git command with extra environment variables and return stdout
+///
+/// In read-only mode, commands that would change the repository are refused.
+pub async fn run_git_command_with_env(
+    args: &[&str],
+    env: &[(&str, &str)],
+    options: &GitOptions,
+) -> Result<String> {
     if options.dry_run {
         tracing::info!("[DRY RUN] git {}", args.join(" "));
         return Ok(String::new());
     }
+    if git_mutates(args) {
+        crate::fs::ensure_writable(|| format!("run git {}", args.join(" ")))?;
+    }
 
-    let output = Command::new("git")
+    let mut command = Command::new("git");
+    command
         .args(args)
+        .envs(env.iter().copied())
         .current_dir(&options.cwd)
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .await
-        .map_err(|e| WreckitError::GitError(format!("Failed to execute git: {}", e)))?;
+        .stderr(Stdio::piped());
+    let output = output_or_cancel(&mut command, "git", options).await?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -86,15 +278,20 @@ pub async fn run_gh_command(args: &[&str], options: &GitOptions) -> Result<Strin
         tracing::info!("[DRY RUN] gh {}", args.join(" "));
         return Ok(String::new());
     }
+    if gh_mutates(args) {
+        crate::fs::ensure_writable(|| format!("run gh {}", args.join(" ")))?;
+    }
 
-    let output = Command::new("gh")
+    let mut command = Command::new("gh");
+    if let Some(ref host) = options.gh_host {
+        command.env("GH_HOST", host);
+    }
+    command
         .args(args)
         .current_dir(&options.cwd)
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .await
-        .map_err(|e| WreckitError::GitError(format!("Failed to execute gh: {}", e)))?;
+        .stderr(Stdio::piped());
+    let output = output_or_cancel(&mut command, "gh", options).await?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -136,6 +333,14 @@ pub async fn branch_exists(branch_name: &str, options: &GitOptions) -> bool {
     result.is_ok()
 }
 
+/// Get the configured git user.email, if any
+pub async fn get_user_email(options: &GitOptions) -> Option<String> {
+    run_git_command(&["config", "user.email"], options)
+        .await
+        .ok()
+        .filter(|email| !email.is_empty())
+}
+
 /// Check if there are uncommitted changes
 pub async fn has_uncommitted_changes(options: &GitOptions) -> bool {
     let result = run_git_command(&["status", "--porcelain"], options).await;
@@ -171,6 +376,115 @@ pub async fn ensure_branch(
     }
 }
 
+/// Paths a commit added or modified, relative to the repository root
+pub async fn commit_files(commit: &str, options: &GitOptions) -> Result<Vec<String>> {
+    let output = run_git_command(
+        &[
+            "diff-tree",
+            "--no-commit-id",
+            "--name-only",
+            "--diff-filter=AMR",
+            "-r",
+            commit,
+        ],
+        options,
+    )
+    .await?;
+    Ok(output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Whether `commit` is HEAD or one of its ancestors
+pub async fn is_ancestor_of_head(commit: &str, options: &GitOptions) -> bool {
+    run_git_command(&["merge-base", "--is-ancestor", commit, "HEAD"], options)
+        .await
+        .is_ok()
+}
+
+/// Uncommitted changes outside `.wreckit/` as a diff against HEAD, followed
+/// by the paths of untracked files (which the diff leaves out)
+pub async fn uncommitted_diff(options: &GitOptions) -> Result<String> {
+    let exclude = ":(exclude).wreckit";
+    let mut diff = run_git_command(&["diff", "HEAD", "--", ".", exclude], options).await?;
+    let untracked = run_git_command(
+        &["ls-files", "--others", "--exclude-standard", "--", ".", exclude],
+        options,
+    )
+    .await?;
+    for path in untracked.lines().filter(|line| !line.is_empty()) {
+        diff.push_str(&format!("\nNew file: {}", path));
+    }
+    Ok(diff)
+}
+
+/// Paths with uncommitted changes: staged, modified, deleted, or untracked
+pub async fn changed_files(options: &GitOptions) -> Result<Vec<String>> {
+    let staged = run_git_command(&["diff", "--name-only", "--cached"], options).await?;
+    let unstaged = run_git_command(
+        &[
+            "ls-files",
+            "--modified",
+            "--deleted",
+            "--others",
+            "--exclude-standard",
+        ],
+        options,
+    )
+    .await?;
+    let mut files: Vec<String> = staged
+        .lines()
+        .chain(unstaged.lines())
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Uncommitted paths that do not exist in HEAD: untracked or staged as added
+pub async fn new_files(options: &GitOptions) -> Result<Vec<String>> {
+    let staged = run_git_command(
+        &["diff", "--name-only", "--cached", "--diff-filter=A"],
+        options,
+    )
+    .await?;
+    let untracked =
+        run_git_command(&["ls-files", "--others", "--exclude-standard"], options).await?;
+    let mut files: Vec<String> = staged
+        .lines()
+        .chain(untracked.lines())
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Discard uncommitted changes to paths, restoring them as of HEAD.
+///
+/// Paths that do not exist in HEAD are removed.
+pub async fn restore_paths(paths: &[String], options: &GitOptions) -> Result<()> {
+    for path in paths {
+        let spec = format!("HEAD:{}", path);
+        if run_git_command(&["cat-file", "-e", &spec], options).await.is_ok() {
+            run_git_command(&["checkout", "HEAD", "--", path], options).await?;
+        } else {
+            run_git_command(&["rm", "-q", "--cached", "--ignore-unmatch", "--", path], options)
+                .await?;
+            let full = options.cwd.join(path);
+            if full.is_file() {
+                crate::fs::remove_file(&full)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Commit all changes with a message
 pub async fn commit_all(message: &str, options: &GitOptions) -> Result<()> {
     run_git_command(&["add", "-A"], options).await?;
@@ -184,8 +498,296 @@ pub async fn push_branch(branch_name: &str, options: &GitOptions) -> Result<()>
     Ok(())
 }
 
+/// Open a draft PR with gh
+///
+/// # Errors
+/// * `GitError` - If gh fails
+pub async fn create_draft_pr(
+    base_branch: &str,
+    head_branch: &str,
+    title: &str,
+    body: &str,
+    options: &GitOptions,
+) -> Result<PrResult> {
+    let url = run_gh_command(
+        &[
+            "pr",
+            "create",
+            "--draft",
+            "--base",
+            base_branch,
+            "--head",
+            head_branch,
+            "--title",
+            title,
+            "--body",
+            body,
+        ],
+        options,
+    )
+    .await?;
+    let number = url
+        .rsplit('/')
+        .next()
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(0);
+    Ok(PrResult {
+        url,
+        number,
+        created: true,
+    })
+}
+
+/// Replace a PR's body, and its title if given, with gh
+///
+/// # Errors
+/// * `GitError` - If gh fails
+pub async fn edit_pr(
+    pr_number: u32,
+    title: Option<&str>,
+    body: &str,
+    options: &GitOptions,
+) -> Result<()> {
+    let number = pr_number.to_string();
+    let mut args = vec!["pr", "edit", number.as_str(), "--body", body];
+    if let Some(title) = title {
+        args.extend_from_slice(&["--title", title]);
+    }
+    run_gh_command(&args, options).await?;
+    Ok(())
+}
+
+/// Mark a draft PR ready for review with gh
+///
+/// # Errors
+/// * `GitError` - If gh fails
+pub async fn mark_pr_ready(pr_number: u32, options: &GitOptions) -> Result<()> {
+    run_gh_command(&["pr", "ready", &pr_number.to_string()], options).await?;
+    Ok(())
+}
+
+/// A commit status to post (shown on PRs next to CI checks)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitStatus {
+    /// "pending", "success", "failure", or "error"
+    pub state: &'static str,
+
+    /// Name the status is listed under (e.g. "wreckit")
+    pub context: String,
+
+    /// Short description (GitHub keeps the first 140 characters)
+    pub description: String,
+}
+
+/// Set a commit status on `sha`, via the GitHub API when configured and
+/// gh otherwise
+///
+/// # Errors
+/// * `GitError` - If the request fails
+pub async fn set_commit_status(
+    sha: &str,
+    status: &CommitStatus,
+    options: &GitOptions,
+) -> Result<()> {
+    if options.dry_run {
+        tracing::info!(
+            "[DRY RUN] Would set the {} status on {} to {}: {}",
+            status.context,
+            sha,
+            status.state,
+            status.description
+        );
+        return Ok(());
+    }
+    if let Some(ref github) = options.github {
+        return github.set_commit_status(sha, status, options).await;
+    }
+    let path = format!("repos/{{owner}}/{{repo}}/statuses/{}", sha);
+    let state = format!("state={}", status.state);
+    let context = format!("context={}", status.context);
+    let description = format!("description={}", status.description);
+    run_gh_command(
+        &[
+            "api",
+            &path,
+            "-f",
+            &state,
+            "-f",
+            &context,
+            "-f",
+            &description,
+        ],
+        options,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Delete a local branch, even if unmerged
+pub async fn delete_branch(branch_name: &str, options: &GitOptions) -> Result<()> {
+    run_git_command(&["branch", "-D", branch_name], options).await?;
+    Ok(())
+}
+
+/// Check out HEAD, detached, into a new worktree at `path`
+pub async fn add_worktree(path: &Path, options: &GitOptions) -> Result<()> {
+    let path = path.to_string_lossy();
+    run_git_command(&["worktree", "add", "--detach", &path, "HEAD"], options).await?;
+    Ok(())
+}
+
+/// Delete a worktree, discarding any changes in it
+pub async fn remove_worktree(path: &Path, options: &GitOptions) -> Result<()> {
+    let path = path.to_string_lossy();
+    run_git_command(&["worktree", "remove", "--force", &path], options).await?;
+    Ok(())
+}
+
+/// List local branches starting with `prefix` that are fully merged into `base`
+pub async fn merged_branches(
+    base: &str,
+    prefix: &str,
+    options: &GitOptions,
+) -> Result<Vec<String>> {
+    let pattern = format!("{}*", prefix);
+    let output = run_git_command(
+        &[
+            "branch",
+            "--merged",
+            base,
+            "--format=%(refname:short)",
+            "--list",
+            &pattern,
+        ],
+        options,
+    )
+    .await?;
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|branch| !branch.is_empty() && *branch != base)
+        .map(String::from)
+        .collect())
+}
+
+/// List branches on origin starting with `prefix` that are fully merged into
+/// `origin/<base>`, as of the last fetch
+pub async fn merged_remote_branches(
+    base: &str,
+    prefix: &str,
+    options: &GitOptions,
+) -> Result<Vec<String>> {
+    let remote_base = format!("origin/{}", base);
+    let pattern = format!("origin/{}*", prefix);
+    let output = run_git_command(
+        &[
+            "branch",
+            "--remotes",
+            "--merged",
+            &remote_base,
+            "--format=%(refname:short)",
+            "--list",
+            &pattern,
+        ],
+        options,
+    )
+    .await?;
+    Ok(output
+        .lines()
+        .filter_map(|branch| branch.trim().strip_prefix("origin/"))
+        .filter(|branch| !branch.is_empty() && *branch != base)
+        .map(String::from)
+        .collect())
+}
+
+/// Count the commits on `branch` that are not on `base`
+pub async fn commits_ahead(base: &str, branch: &str, options: &GitOptions) -> Result<u32> {
+    let range = format!("{}..{}", base, branch);
+    let output = run_git_command(&["rev-list", "--count", &range], options).await?;
+    output
+        .trim()
+        .parse()
+        .map_err(|_| WreckitError::GitError(format!("unexpected rev-list output: {}", output)))
+}
+
+/// Measure the changes on HEAD since it diverged from `base`
+pub async fn diff_stat(base: &str, options: &GitOptions) -> Result<DiffStat> {
+    let range = format!("{}...HEAD", base);
+    let output = run_git_command(&["diff", "--numstat", &range], options).await?;
+    let mut stat = DiffStat::default();
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        let mut fields = line.split('\t');
+        let added = fields.next().and_then(|n| n.parse::<usize>().ok());
+        let deleted = fields.next().and_then(|n| n.parse::<usize>().ok());
+        stat.files += 1;
+        stat.lines += added.unwrap_or(0) + deleted.unwrap_or(0);
+    }
+    Ok(stat)
+}
+
+/// Parse added lines out of a zero-context unified diff
+pub fn parse_added_lines(diff: &str) -> Vec<AddedLine> {
+    let mut added = Vec::new();
+    let mut path: Option<String> = None;
+    let mut line = 0;
+    for text in diff.lines() {
+        if let Some(new_path) = text.strip_prefix("+++ ") {
+            // "+++ /dev/null" for deleted files
+            path = new_path.strip_prefix("b/").map(String::from);
+        } else if let Some(hunk) = text.strip_prefix("@@ ") {
+            // "@@ -a,b +c,d @@": added lines start at c
+            line = hunk
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix('+'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .unwrap_or(0);
+        } else if let (Some(content), Some(path)) = (text.strip_prefix('+'), path.as_ref()) {
+            added.push(AddedLine {
+                path: path.clone(),
+                line,
+                text: content.to_string(),
+            });
+            line += 1;
+        }
+    }
+    added
+}
+
+/// Lines added on HEAD since it diverged from `base`
+pub async fn added_lines(base: &str, options: &GitOptions) -> Result<Vec<AddedLine>> {
+    let range = format!("{}...HEAD", base);
+    let diff = run_git_command(&["diff", "--unified=0", "--no-color", &range], options).await?;
+    Ok(parse_added_lines(&diff))
+}
+
+/// Check if a branch exists on origin
+pub async fn remote_branch_exists(branch_name: &str, options: &GitOptions) -> bool {
+    let result = run_git_command(
+        &["ls-remote", "--exit-code", "--heads", "origin", branch_name],
+        options,
+    )
+    .await;
+    result.is_ok()
+}
+
+/// Delete a branch on origin
+pub async fn delete_remote_branch(branch_name: &str, options: &GitOptions) -> Result<()> {
+    run_git_command(&["push", "origin", "--delete", branch_name], options).await?;
+    Ok(())
+}
+
 /// Get PR info by branch name
 pub async fn get_pr_by_branch(branch_name: &str, options: &GitOptions) -> Option<PrResult> {
+    if let Some(ref github) = options.github {
+        return match github.pr_for_branch(branch_name, options).await {
+            Ok(pr) => pr,
+            Err(e) => {
+                tracing::warn!("Cannot look up PR for {}: {}", branch_name, e);
+                None
+            }
+        };
+    }
     let result = run_gh_command(
         &[
             "pr",
@@ -230,6 +832,12 @@ pub async fn create_or_update_pr(
         return Ok(existing);
     }
 
+    if let Some(ref github) = options.github {
+        return github
+            .create_pr(base_branch, head_branch, title, body, options)
+            .await;
+    }
+
     // Create new PR
     let output = run_gh_command(
         &[
@@ -263,8 +871,415 @@ pub async fn create_or_update_pr(
     })
 }
 
+/// Close a PR, leaving a comment explaining why
+pub async fn close_pr(pr_number: u32, comment: &str, options: &GitOptions) -> Result<()> {
+    run_gh_command(
+        &["pr", "close", &pr_number.to_string(), "--comment", comment],
+        options,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Add labels to a PR
+pub async fn add_pr_labels(pr_number: u32, labels: &[String], options: &GitOptions) -> Result<()> {
+    if labels.is_empty() {
+        return Ok(());
+    }
+    run_gh_command(
+        &[
+            "pr",
+            "edit",
+            &pr_number.to_string(),
+            "--add-label",
+            &labels.join(","),
+        ],
+        options,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Turn on auto-merge for a PR with the given method ("squash", "merge", or
+/// "rebase"), so it merges once its required checks pass. The merge commit
+/// gets `message` when given (rebase merges have no commit of their own).
+///
+/// # Errors
+/// * `GitError` - If auto-merge cannot be enabled (e.g. the repository does
+///   not allow it)
+pub async fn enable_auto_merge(
+    pr_number: u32,
+    method: &str,
+    message: Option<&str>,
+    options: &GitOptions,
+) -> Result<()> {
+    if let Some(ref github) = options.github {
+        return github
+            .enable_auto_merge(pr_number, method, message, options)
+            .await;
+    }
+    let number = pr_number.to_string();
+    let flag = format!("--{}", method);
+    let mut args = vec!["pr", "merge", number.as_str(), "--auto", flag.as_str()];
+    if let Some((subject, body)) = message.filter(|_| method != "rebase").map(split_message) {
+        args.extend(["--subject", subject, "--body", body]);
+    }
+    run_gh_command(&args, options).await?;
+    Ok(())
+}
+
+/// Post a comment on a PR
+pub async fn comment_on_pr(pr_number: u32, body: &str, options: &GitOptions) -> Result<()> {
+    run_gh_command(
+        &["pr", "comment", &pr_number.to_string(), "--body", body],
+        options,
+    )
+    .await?;
+    Ok(())
+}
+
+/// A GitHub issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// Issue number
+    pub number: u32,
+
+    /// Issue title
+    pub title: String,
+
+    /// Issue body (markdown)
+    pub body: String,
+
+    /// Issue URL
+    pub url: String,
+}
+
+/// Fetch an issue
+///
+/// # Errors
+/// * `GitError` - If gh fails (e.g. the issue does not exist)
+/// * `InvalidJson` - If gh's output cannot be parsed
+pub async fn issue(number: u32, options: &GitOptions) -> Result<Issue> {
+    let json = run_gh_command(
+        &[
+            "issue",
+            "view",
+            &number.to_string(),
+            "--json",
+            "number,title,body,url",
+        ],
+        options,
+    )
+    .await?;
+    parse_issue(&json)
+}
+
+/// Parse the output of `gh issue view --json number,title,body,url`
+///
+/// # Errors
+/// * `InvalidJson` - If the output is not the expected JSON
+pub fn parse_issue(json: &str) -> Result<Issue> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+    let number = value["number"]
+        .as_u64()
+        .and_then(|n| u32::try_from(n).ok())
+        .ok_or_else(|| WreckitError::InvalidJson("issue has no number".to_string()))?;
+    let field = |name: &str| value[name].as_str().unwrap_or_default().to_string();
+    Ok(Issue {
+        number,
+        title: field("title"),
+        body: field("body"),
+        url: field("url"),
+    })
+}
+
+/// Post a comment on an issue
+pub async fn comment_on_issue(number: u32, body: &str, options: &GitOptions) -> Result<()> {
+    run_gh_command(
+        &["issue", "comment", &number.to_string(), "--body", body],
+        options,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Parse the output of `gh pr view --json comments`
+///
+/// # Errors
+/// * `InvalidJson` - If the output is not the expected JSON
+pub fn parse_pr_comments(json: &str) -> Result<Vec<PrComment>> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+    let comments = value["comments"]
+        .as_array()
+        .ok_or_else(|| WreckitError::InvalidJson("missing comments array".to_string()))?;
+    Ok(comments
+        .iter()
+        .map(|comment| PrComment {
+            id: comment["id"].as_str().unwrap_or_default().to_string(),
+            author: comment["author"]["login"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            association: comment["authorAssociation"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            body: comment["body"].as_str().unwrap_or_default().to_string(),
+        })
+        .collect())
+}
+
+/// List a PR's conversation comments, oldest first
+///
+/// # Errors
+/// * `GitError` - If gh fails
+/// * `InvalidJson` - If gh's output cannot be parsed
+pub async fn pr_comments(pr_number: u32, options: &GitOptions) -> Result<Vec<PrComment>> {
+    let json = run_gh_command(
+        &["pr", "view", &pr_number.to_string(), "--json", "comments"],
+        options,
+    )
+    .await?;
+    parse_pr_comments(&json)
+}
+
+/// Where a PR check stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    /// Queued or still running
+    Pending,
+
+    /// Finished successfully, or was skipped or neutral
+    Passed,
+
+    /// Finished unsuccessfully
+    Failed,
+}
+
+/// A check run or commit status reported on a PR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrCheck {
+    /// Check run name, or commit status context
+    pub name: String,
+
+    /// Where the check stands
+    pub state: CheckState,
+}
+
+/// Every check reported on the PR
+///
+/// # Errors
+/// * `GitError` - If gh fails
+/// * `InvalidJson` - If gh's output cannot be parsed
+pub async fn pr_checks(pr_number: u32, options: &GitOptions) -> Result<Vec<PrCheck>> {
+    let json = run_gh_command(
+        &[
+            "pr",
+            "view",
+            &pr_number.to_string(),
+            "--json",
+            "statusCheckRollup",
+        ],
+        options,
+    )
+    .await?;
+    parse_pr_checks(&json)
+}
+
+/// Parse the output of `gh pr view --json statusCheckRollup` into check runs
+/// and commit statuses
+///
+/// # Errors
+/// * `InvalidJson` - If the output is not the expected JSON
+pub fn parse_pr_checks(json: &str) -> Result<Vec<PrCheck>> {
+    const FAILED: &[&str] = &[
+        "FAILURE",
+        "ERROR",
+        "TIMED_OUT",
+        "CANCELLED",
+        "ACTION_REQUIRED",
+        "STARTUP_FAILURE",
+    ];
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+    let checks = value["statusCheckRollup"]
+        .as_array()
+        .ok_or_else(|| WreckitError::InvalidJson("missing statusCheckRollup array".to_string()))?;
+    Ok(checks
+        .iter()
+        .filter_map(|check| {
+            let name = check["name"].as_str().or(check["context"].as_str())?;
+            // Check runs report a conclusion once completed, commit statuses a state
+            let outcome = check["conclusion"]
+                .as_str()
+                .filter(|conclusion| !conclusion.is_empty())
+                .or(check["state"].as_str());
+            let state = match outcome {
+                Some(outcome) if FAILED.contains(&outcome) => CheckState::Failed,
+                Some("SUCCESS" | "NEUTRAL" | "SKIPPED") => CheckState::Passed,
+                _ => CheckState::Pending,
+            };
+            Some(PrCheck {
+                name: name.to_string(),
+                state,
+            })
+        })
+        .collect())
+}
+
+/// Whether a check name matches a pattern, where `*` matches any run of
+/// characters (including `/`, since names like `test (crates/foo)` embed paths)
+pub fn check_name_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match name.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let parts: Vec<&str> = parts.collect();
+    let (last, middle) = match parts.split_last() {
+        Some(split) => split,
+        None => return rest.is_empty(),
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// The checks whose names match one of `only`, or every check if `only` is
+/// empty
+pub fn filter_checks(checks: Vec<PrCheck>, only: &[String]) -> Vec<PrCheck> {
+    if only.is_empty() {
+        return checks;
+    }
+    checks
+        .into_iter()
+        .filter(|check| {
+            only.iter()
+                .any(|pattern| check_name_matches(pattern, &check.name))
+        })
+        .collect()
+}
+
+/// Names of the PR's failing checks that match one of `only` (every failing
+/// check if `only` is empty)
+///
+/// # Errors
+/// * `GitError` - If gh fails
+/// * `InvalidJson` - If gh's output cannot be parsed
+pub async fn failed_checks(
+    pr_number: u32,
+    only: &[String],
+    options: &GitOptions,
+) -> Result<Vec<String>> {
+    let checks = pr_checks(pr_number, options).await?;
+    Ok(failed_names(filter_checks(checks, only)))
+}
+
+/// Parse the output of `gh pr view --json statusCheckRollup` into the names
+/// of failing check runs and commit statuses
+///
+/// # Errors
+/// * `InvalidJson` - If the output is not the expected JSON
+pub fn parse_failed_checks(json: &str) -> Result<Vec<String>> {
+    Ok(failed_names(parse_pr_checks(json)?))
+}
+
+fn failed_names(checks: Vec<PrCheck>) -> Vec<String> {
+    checks
+        .into_iter()
+        .filter(|check| check.state == CheckState::Failed)
+        .map(|check| check.name)
+        .collect()
+}
+
+/// A GitHub Actions workflow run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkflowRun {
+    /// Run ID
+    pub id: u64,
+
+    /// How the run finished (e.g. "success", "failure"); empty while running
+    pub conclusion: String,
+}
+
+/// The most recent workflow runs on a branch, newest first
+///
+/// # Errors
+/// * `GitError` - If gh fails
+/// * `InvalidJson` - If gh's output cannot be parsed
+pub async fn workflow_runs(
+    branch: &str,
+    limit: u32,
+    options: &GitOptions,
+) -> Result<Vec<WorkflowRun>> {
+    let json = run_gh_command(
+        &[
+            "run",
+            "list",
+            "--branch",
+            branch,
+            "--limit",
+            &limit.to_string(),
+            "--json",
+            "databaseId,conclusion",
+        ],
+        options,
+    )
+    .await?;
+    parse_workflow_runs(&json)
+}
+
+/// Parse the output of `gh run list --json databaseId,conclusion`
+///
+/// # Errors
+/// * `InvalidJson` - If the output is not the expected JSON
+pub fn parse_workflow_runs(json: &str) -> Result<Vec<WorkflowRun>> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+    let runs = value
+        .as_array()
+        .ok_or_else(|| WreckitError::InvalidJson("expected an array of runs".to_string()))?;
+    Ok(runs
+        .iter()
+        .filter_map(|run| {
+            Some(WorkflowRun {
+                id: run["databaseId"].as_u64()?,
+                conclusion: run["conclusion"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Log output of a workflow run's failed steps
+///
+/// # Errors
+/// * `GitError` - If gh fails
+pub async fn failed_run_log(run_id: u64, options: &GitOptions) -> Result<String> {
+    run_gh_command(
+        &["run", "view", &run_id.to_string(), "--log-failed"],
+        options,
+    )
+    .await
+}
+
 /// Check if a PR is merged
 pub async fn is_pr_merged(pr_number: u32, options: &GitOptions) -> bool {
+    if let Some(ref github) = options.github {
+        return match github.is_pr_merged(pr_number, options).await {
+            Ok(merged) => merged,
+            Err(e) => {
+                tracing::warn!("Cannot check whether PR #{} is merged: {}", pr_number, e);
+                false
+            }
+        };
+    }
     let result = run_gh_command(
         &[
             "pr",
@@ -289,6 +1304,24 @@ pub async fn is_pr_merged(pr_number: u32, options: &GitOptions) -> bool {
     }
 }
 
+/// Check that GitHub (the configured host, if any) is reachable and
+/// authenticated, through the API client or `gh auth status`
+///
+/// # Errors
+/// * `GitError` - If the host cannot be reached or the credentials are missing
+///   or rejected
+pub async fn check_github_auth(options: &GitOptions) -> Result<()> {
+    if let Some(ref github) = options.github {
+        return github.check_auth(options).await;
+    }
+    let mut args = vec!["auth", "status"];
+    if let Some(ref host) = options.gh_host {
+        args.extend(["--hostname", host.as_str()]);
+    }
+    run_gh_command(&args, options).await?;
+    Ok(())
+}
+
 /// Run preflight checks before git operations
 pub async fn check_git_preflight(options: &GitOptions) -> GitPreflightResult {
     let mut errors = Vec::new();
@@ -326,6 +1359,161 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_parse_added_lines() {
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -3,0 +4,2 @@ fn a() {\n+    dbg!(x);\n+    // TODO\n@@ -10 +12 @@\n-old\n+new\ndiff --git a/gone.rs b/gone.rs\n--- a/gone.rs\n+++ /dev/null\n@@ -1 +0,0 @@\n-bye\n";
+        let added = parse_added_lines(diff);
+        let lines: Vec<(&str, usize, &str)> = added
+            .iter()
+            .map(|a| (a.path.as_str(), a.line, a.text.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                ("src/lib.rs", 4, "    dbg!(x);"),
+                ("src/lib.rs", 5, "    // TODO"),
+                ("src/lib.rs", 12, "new"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_pr_comments_and_checks() {
+        let json = r#"{"comments": [{"id": "IC_1", "author": {"login": "alice"}, "authorAssociation": "MEMBER", "body": "/wreckit revise"}]}"#;
+        let comments = parse_pr_comments(json).unwrap();
+        assert_eq!(
+            comments,
+            vec![PrComment {
+                id: "IC_1".to_string(),
+                author: "alice".to_string(),
+                association: "MEMBER".to_string(),
+                body: "/wreckit revise".to_string(),
+            }]
+        );
+        assert!(parse_pr_comments("{}").is_err());
+
+        let checks = r#"{"statusCheckRollup": [
+            {"name": "test", "status": "COMPLETED", "conclusion": "FAILURE"},
+            {"name": "lint", "status": "COMPLETED", "conclusion": "SUCCESS"},
+            {"context": "ci/deploy", "state": "ERROR"}
+        ]}"#;
+        assert_eq!(
+            parse_failed_checks(checks).unwrap(),
+            vec!["test", "ci/deploy"]
+        );
+    }
+
+    #[test]
+    fn test_parse_workflow_runs() {
+        let runs = parse_workflow_runs(
+            r#"[{"databaseId": 12, "conclusion": "failure"}, {"databaseId": 11, "conclusion": ""}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            runs,
+            vec![
+                WorkflowRun {
+                    id: 12,
+                    conclusion: "failure".to_string()
+                },
+                WorkflowRun {
+                    id: 11,
+                    conclusion: String::new()
+                }
+            ]
+        );
+        assert!(parse_workflow_runs("{}").is_err());
+    }
+
+    #[test]
+    fn test_parse_issue() {
+        let issue = parse_issue(
+            r#"{"number": 42, "title": "Crash on start", "body": "Steps", "url": "https://github.com/o/r/issues/42"}"#,
+        )
+        .unwrap();
+        assert_eq!(issue.number, 42);
+        assert_eq!(issue.title, "Crash on start");
+        assert_eq!(issue.body, "Steps");
+        assert_eq!(issue.url, "https://github.com/o/r/issues/42");
+        assert!(parse_issue(r#"{"title": "No number"}"#).is_err());
+    }
+
+    #[test]
+    fn test_git_and_gh_mutates() {
+        assert!(!git_mutates(&["status", "--porcelain"]));
+        assert!(!git_mutates(&["rev-parse", "HEAD"]));
+        assert!(!git_mutates(&["config", "user.email"]));
+        assert!(git_mutates(&["config", "user.email", "a@b.c"]));
+        assert!(!git_mutates(&["remote", "get-url", "origin"]));
+        assert!(git_mutates(&["remote", "add", "origin", "url"]));
+        assert!(!git_mutates(&["branch", "--show-current"]));
+        assert!(git_mutates(&["branch", "-D", "wreckit/001-a"]));
+        assert!(!git_mutates(&[
+            "branch",
+            "--merged",
+            "main",
+            "--format=%(refname:short)",
+            "--list",
+            "wreckit/*"
+        ]));
+        assert!(!git_mutates(&[
+            "branch",
+            "--remotes",
+            "--no-merged",
+            "origin/main"
+        ]));
+        assert!(git_mutates(&["branch", "wreckit/001-a"]));
+        assert!(git_mutates(&["branch", "-m", "old", "new"]));
+        assert!(git_mutates(&["commit", "-m", "x"]));
+        assert!(git_mutates(&["fetch", "origin"]));
+
+        assert!(!gh_mutates(&["pr", "view", "12", "--json", "state"]));
+        assert!(!gh_mutates(&["run", "list"]));
+        assert!(gh_mutates(&["pr", "comment", "12", "--body", "x"]));
+        assert!(!gh_mutates(&["api", "repos/o/r/pulls/12/comments"]));
+        assert!(gh_mutates(&["api", "-X", "POST", "repos/o/r/issues"]));
+    }
+
+    #[test]
+    fn test_filter_checks() {
+        assert!(check_name_matches("test (crates/foo)", "test (crates/foo)"));
+        assert!(!check_name_matches("test (crates/foo)", "test (crates/foo2)"));
+        assert!(check_name_matches("test (crates/foo*)", "test (crates/foo2)"));
+        assert!(check_name_matches("* (crates/foo)", "lint (crates/foo)"));
+        assert!(check_name_matches("*foo*bar", "a/foo/b/bar"));
+        assert!(!check_name_matches("*foo*bar", "a/bar/b/foo"));
+        assert!(check_name_matches("*", ""));
+
+        let checks = parse_pr_checks(
+            r#"{"statusCheckRollup": [
+                {"name": "test (crates/foo)", "status": "COMPLETED", "conclusion": "SUCCESS"},
+                {"name": "test (crates/bar)", "status": "COMPLETED", "conclusion": "FAILURE"},
+                {"name": "lint (crates/foo)", "status": "IN_PROGRESS", "conclusion": ""},
+                {"context": "ci/deploy", "state": "PENDING"}
+            ]}"#,
+        )
+        .unwrap();
+        let states: Vec<CheckState> = checks.iter().map(|check| check.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                CheckState::Passed,
+                CheckState::Failed,
+                CheckState::Pending,
+                CheckState::Pending
+            ]
+        );
+
+        let only = vec!["test (crates/foo)".to_string()];
+        let scoped = filter_checks(checks.clone(), &only);
+        assert_eq!(scoped.len(), 1);
+        assert!(failed_names(scoped).is_empty());
+        assert_eq!(
+            failed_names(filter_checks(checks, &[])),
+            vec!["test (crates/bar)"]
+        );
+    }
+
     async fn setup_git_repo() -> TempDir {
         let temp = TempDir::new().unwrap();
 
@@ -387,6 +1575,9 @@ mod tests {
         let options = GitOptions {
             cwd: temp.path().to_path_buf(),
             dry_run: false,
+            github: None,
+            gh_host: None,
+            cancel: None,
         };
 
         let branch = get_current_branch(&options).await.unwrap();
@@ -400,6 +1591,9 @@ mod tests {
         let options = GitOptions {
             cwd: temp.path().to_path_buf(),
             dry_run: false,
+            github: None,
+            gh_host: None,
+            cancel: None,
         };
 
         // No uncommitted changes initially
@@ -416,6 +1610,9 @@ mod tests {
         let options = GitOptions {
             cwd: temp.path().to_path_buf(),
             dry_run: false,
+            github: None,
+            gh_host: None,
+            cancel: None,
         };
 
         // Get current branch name
@@ -428,12 +1625,35 @@ mod tests {
         assert!(!branch_exists("nonexistent-branch", &options).await);
     }
 
+    #[tokio::test]
+    async fn test_delete_branch() {
+        let temp = setup_git_repo().await;
+        let options = GitOptions {
+            cwd: temp.path().to_path_buf(),
+            dry_run: false,
+            github: None,
+            gh_host: None,
+            cancel: None,
+        };
+
+        run_git_command(&["branch", "doomed"], &options).await.unwrap();
+        assert!(branch_exists("doomed", &options).await);
+        delete_branch("doomed", &options).await.unwrap();
+        assert!(!branch_exists("doomed", &options).await);
+
+        // No origin configured
+        assert!(!remote_branch_exists("doomed", &options).await);
+    }
+
     #[tokio::test]
     async fn test_dry_run_git_command() {
         let temp = TempDir::new().unwrap();
         let options = GitOptions {
             cwd: temp.path().to_path_buf(),
             dry_run: true,
+            github: None,
+            gh_host: None,
+            cancel: None,
         };
 
         // Should not fail even if not a git repo
@@ -441,4 +1661,22 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
     }
+
+    #[tokio::test]
+    async fn test_cancelled_git_command() {
+        let temp = setup_git_repo().await;
+        let token = CancellationToken::new();
+        let options = GitOptions {
+            cwd: temp.path().to_path_buf(),
+            dry_run: false,
+            github: None,
+            gh_host: None,
+            cancel: Some(token.clone()),
+        };
+
+        assert!(run_git_command(&["status"], &options).await.is_ok());
+        token.cancel();
+        let result = run_git_command(&["status"], &options).await;
+        assert!(matches!(result, Err(WreckitError::Interrupted)));
+    }
 }
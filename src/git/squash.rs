@@ -0,0 +1,79 @@
+//! Squash commit messages built from an item's PRD
+//!
+//! A squashed item branch would otherwise land as a pile of concatenated
+//! WIP commit messages. Instead the commit gets the item title as its
+//! subject, the overview and story list as its body, and a `Wreckit-Item`
+//! trailer linking it back to the item.
+
+use crate::schemas::{Item, Prd};
+
+/// Trailer naming the item a squash commit came from
+pub const ITEM_TRAILER: &str = "Wreckit-Item";
+
+/// The squash commit message for an item
+pub fn build_squash_message(item: &Item, prd: Option<&Prd>) -> String {
+    let mut message = item.title.trim().to_string();
+    if !item.overview.trim().is_empty() {
+        message.push_str("\n\n");
+        message.push_str(item.overview.trim());
+    }
+    if let Some(prd) = prd.filter(|prd| !prd.user_stories.is_empty()) {
+        message.push_str("\n\nStories:");
+        for story in &prd.user_stories {
+            message.push_str(&format!("\n- {}: {}", story.id, story.title));
+        }
+    }
+    message.push_str(&format!("\n\n{}: {}\n", ITEM_TRAILER, item.id));
+    message
+}
+
+/// Split a commit message into its subject line and body
+pub fn split_message(message: &str) -> (&str, &str) {
+    match message.split_once('\n') {
+        Some((subject, body)) => (subject.trim(), body.trim()),
+        None => (message.trim(), ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Story;
+
+    #[test]
+    fn test_build_squash_message() {
+        let item = Item::new(
+            "001-login".to_string(),
+            "Add login".to_string(),
+            "Users sign in with email.".to_string(),
+        );
+        let mut prd = Prd::new(item.id.clone(), "wreckit/001-login".to_string());
+        prd.user_stories.push(Story::new(
+            "US-001".to_string(),
+            "Form".to_string(),
+            vec![],
+            1,
+        ));
+        prd.user_stories.push(Story::new(
+            "US-002".to_string(),
+            "Session".to_string(),
+            vec![],
+            2,
+        ));
+
+        let message = build_squash_message(&item, Some(&prd));
+        assert_eq!(
+            message,
+            "Add login\n\nUsers sign in with email.\n\nStories:\n- US-001: Form\n- US-002: Session\n\nWreckit-Item: 001-login\n"
+        );
+        let (subject, body) = split_message(&message);
+        assert_eq!(subject, "Add login");
+        assert!(body.starts_with("Users sign in") && body.ends_with("001-login"));
+
+        let bare = Item::new("002".to_string(), "Fix".to_string(), String::new());
+        assert_eq!(
+            build_squash_message(&bare, None),
+            "Fix\n\nWreckit-Item: 002\n"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! HTTP client settings
+//!
+//! Every HTTP-based provider builds its client here, so they all honor the
+//! same network settings: `HTTPS_PROXY` / `HTTP_PROXY` / `ALL_PROXY` (or
+//! `http.proxy` in config) for requests whose host is not excluded by
+//! `NO_PROXY`, and an extra CA bundle from `http.ca_bundle`, trusted
+//! alongside the built-in roots, for corporate TLS interception.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::CertificateDer;
+
+use crate::errors::{Result, WreckitError};
+use crate::schemas::HttpConfig;
+
+/// Request timeout for API clients
+pub const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Network settings resolved from config, shared by the clients built from it
+#[derive(Clone, Default)]
+pub struct HttpSettings {
+    /// Proxy from config, used instead of the environment's
+    proxy: Option<String>,
+
+    /// TLS config trusting the extra CA bundle, if one is configured
+    tls: Option<Arc<rustls::ClientConfig>>,
+}
+
+impl std::fmt::Debug for HttpSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpSettings")
+            .field("proxy", &self.proxy)
+            .field("custom_ca", &self.tls.is_some())
+            .finish()
+    }
+}
+
+/// First non-empty variable among `names`, upper or lower case
+fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .flat_map(|name| [name.to_string(), name.to_lowercase()])
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+}
+
+/// Host of an http(s) URL, without port or credentials
+pub fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match host.strip_prefix('[') {
+        Some(v6) => v6.split(']').next()?,
+        None => host.split(':').next()?,
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// Whether `host` is excluded by a `NO_PROXY` list: `*`, or entries that
+/// match the host or one of its parent domains (`corp.com`, `.corp.com`)
+pub fn no_proxy_matches(host: &str, no_proxy: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    no_proxy.split(',').map(str::trim).any(|entry| {
+        let entry = entry.to_ascii_lowercase();
+        let entry = entry.split(':').next().unwrap_or_default();
+        let domain = entry.trim_start_matches("*.").trim_start_matches('.');
+        entry == "*"
+            || (!domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain))))
+    })
+}
+
+/// The proxy a request to `url` goes through, if any
+pub fn proxy_for(url: &str, configured: Option<&str>) -> Option<String> {
+    let host = url_host(url)?;
+    if env_var(&["NO_PROXY"]).is_some_and(|no_proxy| no_proxy_matches(host, &no_proxy)) {
+        return None;
+    }
+    if let Some(proxy) = configured {
+        return Some(proxy.to_string());
+    }
+    if url.starts_with("http://") {
+        env_var(&["HTTP_PROXY", "ALL_PROXY"])
+    } else {
+        env_var(&["HTTPS_PROXY", "ALL_PROXY"])
+    }
+}
+
+impl HttpSettings {
+    /// Resolve the settings in `config`, reading the CA bundle relative to
+    /// `root`.
+    ///
+    /// # Errors
+    /// * `ConfigError` - If the CA bundle cannot be read or holds no
+    ///   certificates
+    pub fn load(root: &std::path::Path, config: &HttpConfig) -> Result<Self> {
+        let tls = match config.ca_bundle {
+            Some(ref bundle) => Some(Arc::new(tls_with_bundle(&root.join(bundle))?)),
+            None => None,
+        };
+        Ok(HttpSettings {
+            proxy: config.proxy.clone(),
+            tls,
+        })
+    }
+
+    /// Whether an extra CA bundle is trusted
+    pub fn has_custom_ca(&self) -> bool {
+        self.tls.is_some()
+    }
+
+    /// An HTTP agent for requests to `base_url`.
+    ///
+    /// An unusable proxy URL is logged and ignored.
+    pub fn agent(&self, base_url: &str) -> ureq::Agent {
+        let mut builder = ureq::AgentBuilder::new().timeout(HTTP_TIMEOUT);
+        if let Some(proxy) = proxy_for(base_url, self.proxy.as_deref()) {
+            match ureq::Proxy::new(&proxy) {
+                Ok(proxy) => builder = builder.proxy(proxy),
+                Err(e) => tracing::warn!("Ignoring proxy {}: {}", proxy, e),
+            }
+        }
+        if let Some(ref tls) = self.tls {
+            builder = builder.tls_config(tls.clone());
+        }
+        builder.build()
+    }
+}
+
+/// TLS config trusting the built-in roots plus every certificate in `path`
+fn tls_with_bundle(path: &std::path::Path) -> Result<rustls::ClientConfig> {
+    let unreadable = |e: &dyn std::fmt::Display| {
+        WreckitError::ConfigError(format!("cannot read CA bundle {}: {}", path.display(), e))
+    };
+    let mut roots =
+        rustls::RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let certs = CertificateDer::pem_file_iter(path)
+        .map_err(|e| unreadable(&e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| unreadable(&e))?;
+    if certs.is_empty() {
+        return Err(unreadable(&"no PEM certificates found"));
+    }
+    let (_, ignored) = roots.add_parsable_certificates(certs);
+    if ignored > 0 {
+        tracing::warn!(
+            "Skipped {} unusable certificate(s) in {}",
+            ignored,
+            path.display()
+        );
+    }
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| unreadable(&e))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("https://api.github.com/user"),
+            Some("api.github.com")
+        );
+        assert_eq!(url_host("https://ghe.corp:8443/api/v3"), Some("ghe.corp"));
+        assert_eq!(url_host("http://u:p@proxy.local:3128"), Some("proxy.local"));
+        assert_eq!(url_host("https://[::1]:8080/x"), Some("::1"));
+        assert_eq!(url_host("https:///x"), None);
+    }
+
+    #[test]
+    fn test_no_proxy_matches() {
+        let list = "localhost, .corp.example,internal.io:8443";
+        assert!(no_proxy_matches("localhost", list));
+        assert!(no_proxy_matches("ghe.corp.example", list));
+        assert!(no_proxy_matches("corp.example", list));
+        assert!(no_proxy_matches("api.internal.io", list));
+        assert!(!no_proxy_matches("api.github.com", list));
+        assert!(!no_proxy_matches("notcorp.example", list));
+        assert!(no_proxy_matches("anything", "*"));
+        assert!(!no_proxy_matches("anything", ""));
+    }
+
+    #[test]
+    fn test_ca_bundle_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = HttpConfig {
+            ca_bundle: Some("corp-ca.pem".to_string()),
+            proxy: None,
+        };
+        let err = HttpSettings::load(dir.path(), &missing).unwrap_err();
+        assert!(err.to_string().contains("corp-ca.pem"));
+
+        std::fs::write(dir.path().join("corp-ca.pem"), "not a certificate\n").unwrap();
+        let err = HttpSettings::load(dir.path(), &missing).unwrap_err();
+        assert!(err.to_string().contains("no PEM certificates"));
+
+        let settings = HttpSettings::load(dir.path(), &HttpConfig::default()).unwrap();
+        assert!(!settings.has_custom_ca());
+    }
+}
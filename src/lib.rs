@@ -6,17 +6,24 @@
 //! - File system utilities for reading/writing JSON
 //! - Git operations for branch management and PR creation
 //! - Agent execution for running the Claude CLI
+//! - Tokens kept in the OS keychain ([`secrets`])
 //! - Workflow phases (research, plan, implement, pr, complete)
+//! - WASM validation plugins ([`plugins`], with the `wasm-plugins` feature)
+//! - A typed API for embedding the engine in other tools ([`api::Wreckit`])
 
 pub mod agent;
+pub mod api;
 pub mod cli;
 pub mod config;
 pub mod domain;
 pub mod errors;
 pub mod fs;
 pub mod git;
+pub mod http;
+pub mod plugins;
 pub mod prompts;
 pub mod schemas;
+pub mod secrets;
 pub mod tui;
 pub mod workflow;
 
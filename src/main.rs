@@ -2,7 +2,8 @@
 
 use clap::Parser;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
-use wreckit::cli::{Cli, Commands};
+use wreckit::cli::commands::external::PluginFlags;
+use wreckit::cli::{AuthCommands, Cli, Commands, ItemsCommands, PromptCommands, RunsCommands};
 use wreckit::errors::to_exit_code;
 
 #[tokio::main]
@@ -21,55 +22,375 @@ async fn main() {
     match result {
         Ok(()) => std::process::exit(0),
         Err(e) => {
-            eprintln!("Error: {}", e);
+            let class = e.classify();
+            eprintln!("Error [{}]: {}", class.code(), e);
+            if let Some(hint) = class.hint(None) {
+                eprintln!("Hint: {}", hint);
+            }
             std::process::exit(to_exit_code(&e));
         }
     }
 }
 
 async fn run(cli: Cli) -> wreckit::Result<()> {
+    wreckit::fs::set_read_only(cli.read_only);
+    wreckit::config::set_profile(cli.profile.clone());
+    wreckit::agent::set_agent_cache_disabled(cli.no_cache);
+    if cli.force_unlock {
+        let root = wreckit::fs::find_repo_root(&wreckit::fs::resolve_cwd(cli.cwd.as_deref()))?;
+        if let Some(holder) = wreckit::fs::force_unlock(&root)? {
+            tracing::warn!(
+                "Removed the lock held by pid {} since {} ({})",
+                holder.pid,
+                holder.acquired_at,
+                holder.command
+            );
+        }
+    }
     match cli.command {
         Some(Commands::Init { force }) => {
             wreckit::cli::commands::init::run(cli.cwd.as_deref(), force, cli.dry_run).await
         }
-        Some(Commands::Status { json }) => {
-            wreckit::cli::commands::status::run(cli.cwd.as_deref(), json).await
+        Some(Commands::Status {
+            json,
+            columns,
+            sort,
+            format,
+        }) => {
+            wreckit::cli::commands::status::run(
+                cli.cwd.as_deref(),
+                json,
+                columns.as_deref(),
+                sort.as_deref(),
+                &format,
+            )
+            .await
+        }
+        Some(Commands::Stats { json }) => {
+            wreckit::cli::commands::stats::run(cli.cwd.as_deref(), json).await
         }
-        Some(Commands::List { json, state }) => {
-            wreckit::cli::commands::list::run(cli.cwd.as_deref(), json, state.as_deref()).await
+        Some(Commands::List {
+            json,
+            state,
+            columns,
+            sort,
+            format,
+            pick,
+        }) => {
+            wreckit::cli::commands::list::run(
+                cli.cwd.as_deref(),
+                json,
+                state.as_deref(),
+                columns.as_deref(),
+                sort.as_deref(),
+                &format,
+                pick,
+            )
+            .await
+        }
+        Some(Commands::Pick { state }) => {
+            wreckit::cli::commands::pick::run(cli.cwd.as_deref(), state.as_deref()).await
         }
         Some(Commands::Show { id, json }) => {
             wreckit::cli::commands::show::run(cli.cwd.as_deref(), &id, json).await
         }
         Some(Commands::Research { id, force }) => {
-            wreckit::cli::commands::research::run(cli.cwd.as_deref(), &id, force, cli.dry_run)
-                .await
+            wreckit::cli::commands::research::run(
+                cli.cwd.as_deref(),
+                &id,
+                force,
+                cli.dry_run,
+                cli.no_tui,
+            )
+            .await
         }
         Some(Commands::Plan { id, force }) => {
-            wreckit::cli::commands::plan::run(cli.cwd.as_deref(), &id, force, cli.dry_run).await
+            wreckit::cli::commands::plan::run(
+                cli.cwd.as_deref(),
+                &id,
+                force,
+                cli.dry_run,
+                cli.no_tui,
+            )
+            .await
         }
         Some(Commands::Implement { id, force }) => {
-            wreckit::cli::commands::implement::run(cli.cwd.as_deref(), &id, force, cli.dry_run)
-                .await
+            wreckit::cli::commands::implement::run(
+                cli.cwd.as_deref(),
+                &id,
+                force,
+                cli.dry_run,
+                cli.no_tui,
+            )
+            .await
         }
         Some(Commands::Pr { id, force }) => {
-            wreckit::cli::commands::pr::run(cli.cwd.as_deref(), &id, force, cli.dry_run).await
+            wreckit::cli::commands::pr::run(
+                cli.cwd.as_deref(),
+                &id,
+                force,
+                cli.dry_run,
+                cli.no_tui,
+            )
+            .await
         }
         Some(Commands::Complete { id }) => {
             wreckit::cli::commands::complete::run(cli.cwd.as_deref(), &id, cli.dry_run).await
         }
-        Some(Commands::Run { id, force }) => {
-            wreckit::cli::commands::run::run(cli.cwd.as_deref(), &id, force, cli.dry_run).await
+        Some(Commands::Bulk {
+            state,
+            older_than,
+            action,
+            yes,
+        }) => {
+            wreckit::cli::commands::bulk::run(
+                cli.cwd.as_deref(),
+                state.as_deref(),
+                older_than.as_deref(),
+                &action,
+                yes,
+                cli.dry_run,
+            )
+            .await
+        }
+        Some(Commands::Abandon { id, reason }) => {
+            wreckit::cli::commands::abandon::run(
+                cli.cwd.as_deref(),
+                &id,
+                reason.as_deref(),
+                cli.dry_run,
+            )
+            .await
+        }
+        Some(Commands::Reopen { id, title }) => {
+            wreckit::cli::commands::reopen::run(
+                cli.cwd.as_deref(),
+                &id,
+                title.as_deref(),
+                cli.dry_run,
+            )
+            .await
+        }
+        Some(Commands::Retry {
+            id,
+            phase,
+            rollback,
+            with_feedback,
+        }) => {
+            wreckit::cli::commands::retry::run(
+                cli.cwd.as_deref(),
+                &id,
+                phase.as_deref(),
+                rollback,
+                with_feedback.as_deref(),
+                cli.dry_run,
+                cli.no_tui,
+            )
+            .await
+        }
+        Some(Commands::Bench { id, variants, json }) => {
+            wreckit::cli::commands::bench::run(
+                cli.cwd.as_deref(),
+                &id,
+                &variants,
+                json,
+                cli.dry_run,
+            )
+            .await
+        }
+        Some(Commands::Restore { id, from }) => {
+            wreckit::cli::commands::restore::run(
+                cli.cwd.as_deref(),
+                &id,
+                from.as_deref(),
+                cli.dry_run,
+            )
+            .await
+        }
+        Some(Commands::SyncMeta) => {
+            wreckit::cli::commands::sync_meta::run(cli.cwd.as_deref(), cli.dry_run).await
+        }
+        Some(Commands::Gc { branches }) => {
+            wreckit::cli::commands::gc::run(cli.cwd.as_deref(), branches, cli.dry_run).await
+        }
+        Some(Commands::Preset { name }) => {
+            wreckit::cli::commands::preset::run(cli.cwd.as_deref(), &name, cli.dry_run).await
+        }
+        Some(Commands::Issue { number }) => {
+            wreckit::cli::commands::issue::run(cli.cwd.as_deref(), number, cli.dry_run).await
+        }
+        Some(Commands::Recur) => {
+            wreckit::cli::commands::recur::run(cli.cwd.as_deref(), cli.dry_run).await
+        }
+        Some(Commands::Watch { once }) => {
+            wreckit::cli::commands::watch::run(cli.cwd.as_deref(), once, cli.dry_run).await
+        }
+        Some(Commands::Flush) => {
+            wreckit::cli::commands::flush::run(cli.cwd.as_deref(), cli.dry_run).await
+        }
+        Some(Commands::Context { refresh }) => {
+            wreckit::cli::commands::context::run(cli.cwd.as_deref(), refresh, cli.dry_run).await
+        }
+        Some(Commands::Supervise { id, force }) => {
+            wreckit::cli::commands::run::supervise_run(cli.cwd.as_deref(), &id, force).await
         }
-        Some(Commands::Next) => {
-            wreckit::cli::commands::next::run(cli.cwd.as_deref(), cli.dry_run).await
+        Some(Commands::Attach { id }) => {
+            wreckit::cli::commands::attach::run(cli.cwd.as_deref(), id.as_deref(), cli.no_tui)
+                .await
+        }
+        Some(Commands::Tui { .. }) => wreckit::cli::commands::tui::run(cli.cwd.as_deref()).await,
+        Some(Commands::Items { command }) => match command {
+            ItemsCommands::Graph { format } => {
+                wreckit::cli::commands::items::graph(cli.cwd.as_deref(), &format).await
+            }
+        },
+        Some(Commands::Runs { command }) => match command {
+            RunsCommands::List => wreckit::cli::commands::runs::list(cli.cwd.as_deref()).await,
+            RunsCommands::Show { run, item, json } => {
+                wreckit::cli::commands::runs::show(
+                    cli.cwd.as_deref(),
+                    run.as_deref(),
+                    item.as_deref(),
+                    json,
+                )
+                .await
+            }
+        },
+        Some(Commands::Auth { command }) => match command {
+            AuthCommands::Set { name } => wreckit::cli::commands::auth::set(&name),
+            AuthCommands::Delete { name } => wreckit::cli::commands::auth::delete(&name),
+            AuthCommands::Status => wreckit::cli::commands::auth::status(cli.cwd.as_deref()).await,
+        },
+        Some(Commands::Prompt { command }) => match command {
+            PromptCommands::Render {
+                id,
+                template,
+                stats,
+            } => {
+                wreckit::cli::commands::prompt::render(
+                    cli.cwd.as_deref(),
+                    &id,
+                    template.as_deref(),
+                    stats,
+                )
+                .await
+            }
+            PromptCommands::Snapshot { update } => {
+                wreckit::cli::commands::prompt::snapshot(cli.cwd.as_deref(), update, cli.dry_run)
+                    .await
+            }
+        },
+        Some(Commands::Block {
+            id,
+            reason,
+            until,
+            on_item,
+        }) => {
+            wreckit::cli::commands::block::run(
+                cli.cwd.as_deref(),
+                &id,
+                &reason,
+                until.as_deref(),
+                on_item.as_deref(),
+                cli.dry_run,
+            )
+            .await
+        }
+        Some(Commands::Unblock { id }) => {
+            wreckit::cli::commands::block::unblock(cli.cwd.as_deref(), &id, cli.dry_run).await
+        }
+        Some(Commands::Open {
+            id,
+            pr,
+            branch,
+            plan,
+            ..
+        }) => {
+            let target = wreckit::cli::commands::open::OpenTarget::from_flags(pr, branch, plan);
+            wreckit::cli::commands::open::run(cli.cwd.as_deref(), &id, target, cli.dry_run).await
+        }
+        Some(Commands::Note { id, text }) => {
+            wreckit::cli::commands::note::run(cli.cwd.as_deref(), &id, &text, cli.dry_run).await
+        }
+        Some(Commands::Answer { id, text }) => {
+            wreckit::cli::commands::answer::run(cli.cwd.as_deref(), &id, &text, cli.dry_run).await
+        }
+        Some(Commands::Advance { id, to }) => {
+            wreckit::cli::commands::advance::run(cli.cwd.as_deref(), &id, to.as_deref(), cli.dry_run)
+                .await
         }
-        Some(Commands::Doctor { fix }) => {
-            wreckit::cli::commands::doctor::run(cli.cwd.as_deref(), fix).await
+        Some(Commands::Run {
+            id,
+            all,
+            force,
+            detach,
+        }) => match id {
+            Some(id) if detach => {
+                wreckit::cli::commands::run::detach(cli.cwd.as_deref(), &id, force, cli.dry_run)
+                    .await
+            }
+            Some(id) if !all => {
+                wreckit::cli::commands::run::run(
+                    cli.cwd.as_deref(),
+                    &id,
+                    force,
+                    cli.dry_run,
+                    cli.no_tui,
+                )
+                .await
+            }
+            _ => {
+                wreckit::cli::commands::run::run_all(
+                    cli.cwd.as_deref(),
+                    force,
+                    cli.dry_run,
+                    cli.no_tui,
+                )
+                .await
+            }
+        },
+        Some(Commands::Next { mine }) => {
+            wreckit::cli::commands::next::run(cli.cwd.as_deref(), mine, cli.dry_run, cli.no_tui)
+                .await
+        }
+        Some(Commands::Claim { id }) => {
+            wreckit::cli::commands::assign::claim(cli.cwd.as_deref(), &id, cli.dry_run).await
+        }
+        Some(Commands::Assign { id, user }) => {
+            wreckit::cli::commands::assign::run(cli.cwd.as_deref(), &id, user.as_deref(), cli.dry_run)
+                .await
+        }
+        Some(Commands::Replay { id, phase }) => {
+            wreckit::cli::commands::replay::run(
+                cli.cwd.as_deref(),
+                &id,
+                &phase,
+                cli.dry_run,
+                cli.no_tui,
+            )
+            .await
+        }
+        Some(Commands::Doctor { fix, deep }) => {
+            wreckit::cli::commands::doctor::run(cli.cwd.as_deref(), fix, deep).await
         }
         Some(Commands::Ideas { file }) => {
             wreckit::cli::commands::ideas::run(cli.cwd.as_deref(), file.as_deref()).await
         }
+        Some(Commands::External(args)) => {
+            let flags = PluginFlags {
+                verbose: cli.verbose,
+                quiet: cli.quiet,
+                dry_run: cli.dry_run,
+                no_tui: cli.no_tui,
+                read_only: cli.read_only,
+                json: cli.output == "json",
+            };
+            let code = wreckit::cli::commands::external::run(cli.cwd.as_deref(), &args, flags).await?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+            Ok(())
+        }
         None => {
             // Default to showing help - clap handles this
             println!("Use --help for usage information");
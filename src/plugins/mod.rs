@@ -0,0 +1,107 @@
+//! WASM validation plugins
+//!
+//! Each `.wasm` file in `.wreckit/plugins/` is a WebAssembly component that
+//! implements the `validator` world in [`VALIDATOR_WIT`]: `can-enter` runs
+//! whenever an item enters a state and `lint-prd` when it is planned. The
+//! plugins become [`TransitionValidator`]s consulted by the domain validation
+//! layer after the built-in rules.
+//!
+//! Components get no imports, so a plugin cannot touch the file system,
+//! network, or environment, and every call runs on a fixed fuel budget.
+//! Running plugins requires the `wasm-plugins` feature; without it they are
+//! reported and ignored.
+
+#[cfg(feature = "wasm-plugins")]
+mod wasm;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::domain::TransitionValidator;
+use crate::errors::Result;
+use crate::fs::get_plugins_dir;
+
+#[cfg(feature = "wasm-plugins")]
+pub use wasm::WasmValidator;
+
+/// The WIT interface validation plugins implement
+pub const VALIDATOR_WIT: &str = include_str!("validator.wit");
+
+/// The `.wasm` files in `.wreckit/plugins/`, sorted by name
+///
+/// # Errors
+/// * `Io` - If the plugins directory cannot be listed
+pub fn plugin_paths(root: &Path) -> Result<Vec<PathBuf>> {
+    let dir = get_plugins_dir(root);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "wasm") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Load every plugin in `.wreckit/plugins/`
+///
+/// # Errors
+/// * `ConfigError` - If a plugin is not a valid validator component
+/// * `Io` - If the plugins directory cannot be listed
+pub fn load_validators(root: &Path) -> Result<Vec<Arc<dyn TransitionValidator>>> {
+    let paths = plugin_paths(root)?;
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    load(&paths)
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn load(paths: &[PathBuf]) -> Result<Vec<Arc<dyn TransitionValidator>>> {
+    let engine = wasm::engine()?;
+    paths
+        .iter()
+        .map(|path| {
+            let validator = WasmValidator::load(&engine, path)?;
+            Ok(Arc::new(validator) as Arc<dyn TransitionValidator>)
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "wasm-plugins"))]
+fn load(paths: &[PathBuf]) -> Result<Vec<Arc<dyn TransitionValidator>>> {
+    tracing::warn!(
+        "Ignoring {} plugin(s) in .wreckit/plugins: wreckit was built without the wasm-plugins feature",
+        paths.len()
+    );
+    Ok(Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plugin_paths() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        assert!(plugin_paths(root).unwrap().is_empty());
+
+        let dir = get_plugins_dir(root);
+        std::fs::create_dir_all(dir.join("nested.wasm")).unwrap();
+        std::fs::write(dir.join("b.wasm"), "").unwrap();
+        std::fs::write(dir.join("a.wasm"), "").unwrap();
+        std::fs::write(dir.join("README.md"), "").unwrap();
+        let names: Vec<String> = plugin_paths(root)
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["a.wasm", "b.wasm"]);
+    }
+}
@@ -0,0 +1,174 @@
+//! wasmtime host for validation plugins
+
+use std::fmt;
+use std::path::Path;
+
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Engine, Store};
+
+use crate::domain::{TransitionValidator, ValidationResult};
+use crate::errors::{Result, WreckitError};
+use crate::schemas::{Item, Prd};
+
+mod bindings {
+    wasmtime::component::bindgen!({
+        world: "validator",
+        path: "src/plugins/validator.wit",
+    });
+}
+
+use bindings::Validator;
+
+/// Fuel for a single plugin call; a plugin that runs out is reported as failed
+const FUEL_PER_CALL: u64 = 1_000_000_000;
+
+/// An engine with the component model and fuel metering enabled
+///
+/// # Errors
+/// * `ConfigError` - If the engine cannot be created
+pub fn engine() -> Result<Engine> {
+    let mut config = wasmtime::Config::new();
+    config.wasm_component_model(true).consume_fuel(true);
+    Engine::new(&config)
+        .map_err(|e| WreckitError::ConfigError(format!("cannot start WASM engine: {}", e)))
+}
+
+/// A validation plugin compiled from a `.wasm` component
+pub struct WasmValidator {
+    name: String,
+    engine: Engine,
+    component: Component,
+    linker: Linker<()>,
+}
+
+impl fmt::Debug for WasmValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmValidator")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WasmValidator {
+    /// Compile a plugin, named after its file stem
+    ///
+    /// # Errors
+    /// * `ConfigError` - If the file is not a component implementing the
+    ///   validator world
+    pub fn load(engine: &Engine, path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let invalid = |e: wasmtime::Error| {
+            WreckitError::ConfigError(format!("cannot load plugin {}: {}", path.display(), e))
+        };
+        let validator = WasmValidator {
+            name,
+            engine: engine.clone(),
+            component: Component::from_file(engine, path).map_err(invalid)?,
+            linker: Linker::new(engine),
+        };
+        // Instantiate once so a plugin missing an export fails at load time
+        validator.instantiate().map_err(invalid)?;
+        Ok(validator)
+    }
+
+    /// A fresh instance, so no state carries over between calls
+    fn instantiate(&self) -> wasmtime::Result<(Store<()>, Validator)> {
+        let mut store = Store::new(&self.engine, ());
+        store.set_fuel(FUEL_PER_CALL)?;
+        let instance = Validator::instantiate(&mut store, &self.component, &self.linker)?;
+        Ok((store, instance))
+    }
+}
+
+impl TransitionValidator for WasmValidator {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn can_enter(&self, item: &Item, state: &str, prd: Option<&Prd>) -> ValidationResult {
+        let item = serde_json::to_string(item).unwrap_or_default();
+        let prd = prd.and_then(|prd| serde_json::to_string(prd).ok());
+        let outcome = self.instantiate().and_then(|(mut store, instance)| {
+            instance.call_can_enter(&mut store, &item, state, prd.as_deref())
+        });
+        match outcome {
+            Ok(Ok(())) => ValidationResult::success(),
+            Ok(Err(reason)) => ValidationResult::failure(reason),
+            Err(e) => ValidationResult::failure(format!("plugin failed: {}", e)),
+        }
+    }
+
+    fn lint_prd(&self, prd: &Prd) -> Vec<String> {
+        let prd = serde_json::to_string(prd).unwrap_or_default();
+        let outcome = self
+            .instantiate()
+            .and_then(|(mut store, instance)| instance.call_lint_prd(&mut store, &prd));
+        match outcome {
+            Ok(problems) => problems,
+            Err(e) => vec![format!("plugin failed: {}", e)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::WorkflowState;
+    use tempfile::TempDir;
+
+    /// Refuses every transition, giving the target state as the reason, and
+    /// accepts every PRD
+    const ECHO_PLUGIN: &str = r#"
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (global $next (mut i32) (i32.const 1024))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr (global.get $next))
+      (global.set $next (i32.add (global.get $next) (local.get 3)))
+      (local.get $ptr))
+    (func (export "can-enter") (param i32 i32 i32 i32 i32 i32 i32) (result i32)
+      (i32.store8 (i32.const 0) (i32.const 1))
+      (i32.store (i32.const 4) (local.get 2))
+      (i32.store (i32.const 8) (local.get 3))
+      (i32.const 0))
+    (func (export "lint-prd") (param i32 i32) (result i32)
+      (i32.store (i32.const 16) (i32.const 0))
+      (i32.store (i32.const 20) (i32.const 0))
+      (i32.const 16)))
+  (core instance $i (instantiate $m))
+  (func (export "can-enter")
+    (param "item" string) (param "state" string) (param "prd" (option string))
+    (result (result (error string)))
+    (canon lift (core func $i "can-enter") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func (export "lint-prd") (param "prd" string) (result (list string))
+    (canon lift (core func $i "lint-prd") (memory $i "memory") (realloc (func $i "realloc")))))
+"#;
+
+    #[test]
+    fn test_wasm_validator() {
+        let temp = TempDir::new().unwrap();
+        let engine = engine().unwrap();
+
+        // wasmtime's wat feature accepts the text format in place of a binary
+        let path = temp.path().join("echo.wasm");
+        std::fs::write(&path, ECHO_PLUGIN).unwrap();
+        let validator = WasmValidator::load(&engine, &path).unwrap();
+        assert_eq!(validator.name(), "echo");
+
+        let item = Item::new("001".to_string(), "Test".to_string(), String::new())
+            .with_state(WorkflowState::Researched);
+        let result = validator.can_enter(&item, "planned", None);
+        assert_eq!(result.reason.as_deref(), Some("planned"));
+        let prd = Prd::new("001".to_string(), "wreckit/001".to_string());
+        assert!(validator.lint_prd(&prd).is_empty());
+
+        std::fs::write(&path, "(component)").unwrap();
+        let err = WasmValidator::load(&engine, &path).unwrap_err();
+        assert!(err.to_string().contains("cannot load plugin"));
+    }
+}
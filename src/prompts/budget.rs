@@ -0,0 +1,187 @@
+//! Context budgeting for prompt assembly
+//!
+//! Prompts inline research.md, plan.md, prd.json, progress.log, and the
+//! repository context pack, which on a long-running item can exceed the
+//! model's context window. Before
+//! rendering, [`fit_to_budget`] estimates the size of every variable and, if
+//! the total is over budget, trims the sections that matter least for the
+//! prompt being built, in order, until it fits. Trimmed text is replaced by
+//! a marker so the agent knows something was left out. Sizes are estimated
+//! for the model the phase runs (see [`TokenModel`]).
+
+use super::template::PromptVariables;
+use super::tokens::TokenModel;
+
+/// A section trimmed below this many tokens is dropped entirely
+const MIN_SECTION_TOKENS: usize = 200;
+
+/// Tokens set aside for the marker that replaces trimmed text
+const MARKER_TOKENS: usize = 20;
+
+/// A section that was shortened to fit the budget
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trim {
+    /// Section name (e.g. "research")
+    pub section: &'static str,
+
+    /// Estimated tokens before trimming
+    pub tokens_before: usize,
+
+    /// Estimated tokens after trimming (the omission note, if dropped)
+    pub tokens_after: usize,
+}
+
+/// Sections that may be trimmed for a template, least important first.
+///
+/// The story, PRD (outside the PR prompt), and item fields are never trimmed.
+pub fn trim_order(template: &str) -> &'static [&'static str] {
+    match template {
+        "research" => &["progress", "repo_context", "prd", "plan"],
+        "plan" => &["progress", "repo_context", "prd", "research"],
+        "plan_consensus" => &["progress", "repo_context", "research"],
+        "pr" => &["progress", "research", "plan", "prd"],
+        "verify_criteria" => &["changes"],
+        _ => &["progress", "research", "plan"],
+    }
+}
+
+fn section_mut<'a>(vars: &'a mut PromptVariables, name: &str) -> Option<&'a mut Option<String>> {
+    match name {
+        "research" => Some(&mut vars.research),
+        "plan" => Some(&mut vars.plan),
+        "prd" => Some(&mut vars.prd),
+        "progress" => Some(&mut vars.progress),
+        "repo_context" => Some(&mut vars.repo_context),
+        "changes" => Some(&mut vars.changes),
+        _ => None,
+    }
+}
+
+/// Estimated tokens of every variable that ends up in a prompt
+pub fn variables_tokens(vars: &PromptVariables, model: TokenModel) -> usize {
+    vars.to_map().values().map(|value| model.count(value)).sum()
+}
+
+/// Shorten text to about `tokens` tokens, keeping its start or (for logs) its end
+fn shorten(text: &str, tokens: usize, keep_tail: bool, model: TokenModel) -> String {
+    let keep = model.chars_for(tokens);
+    let total = text.chars().count();
+    let cut = total.saturating_sub(keep);
+    let marker = format!(
+        "[... {} characters trimmed to fit the context budget ...]",
+        cut
+    );
+    if keep_tail {
+        let tail: String = text.chars().skip(cut).collect();
+        format!("{}\n{}", marker, tail)
+    } else {
+        let head: String = text.chars().take(keep).collect();
+        format!("{}\n{}", head, marker)
+    }
+}
+
+/// Trim the least important sections until the variables fit in `budget` tokens.
+///
+/// Returns the sections that were trimmed, in the order they were trimmed.
+/// Sections are cut only as far as needed; one that would fall below a
+/// useful size is dropped instead. If the untrimmable parts alone exceed
+/// the budget, every trimmable section is dropped and the prompt stays over.
+pub fn fit_to_budget(
+    vars: &mut PromptVariables,
+    template: &str,
+    budget: usize,
+    model: TokenModel,
+) -> Vec<Trim> {
+    let mut trims = Vec::new();
+    for &name in trim_order(template) {
+        let total = variables_tokens(vars, model);
+        if total <= budget {
+            break;
+        }
+        let section = match section_mut(vars, name) {
+            Some(section) => section,
+            None => continue,
+        };
+        let text = match section.as_deref() {
+            Some(text) if !text.is_empty() => text,
+            _ => continue,
+        };
+        let before = model.count(text);
+        let target = before.saturating_sub(total - budget + MARKER_TOKENS);
+        let (replacement, after) = if target < MIN_SECTION_TOKENS {
+            let note = format!("[{} omitted to fit the context budget]", name);
+            let tokens = model.count(&note);
+            (note, tokens)
+        } else {
+            let shortened = shorten(text, target, name == "progress", model);
+            let tokens = model.count(&shortened);
+            (shortened, tokens)
+        };
+        *section = Some(replacement);
+        trims.push(Trim {
+            section: name,
+            tokens_before: before,
+            tokens_after: after,
+        });
+    }
+    trims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> PromptVariables {
+        PromptVariables {
+            id: "001-test".to_string(),
+            story: Some("US-001".to_string()),
+            research: Some("r".repeat(4000)),
+            plan: Some("p".repeat(4000)),
+            prd: Some("{}".to_string()),
+            progress: Some(format!("{}\nlatest entry", "old\n".repeat(1000))),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_within_budget_is_untouched() {
+        let mut v = vars();
+        assert!(fit_to_budget(&mut v, "implement", 100_000, TokenModel::Generic).is_empty());
+        assert_eq!(v.research.as_deref().map(str::len), Some(4000));
+    }
+
+    #[test]
+    fn test_trims_least_important_first() {
+        let mut v = vars();
+        let before = variables_tokens(&v, TokenModel::Generic);
+        // Over by 1,500 tokens: progress (~1,000) goes, research is cut
+        let trims = fit_to_budget(&mut v, "implement", before - 1500, TokenModel::Generic);
+        assert_eq!(trims.len(), 2);
+        assert_eq!(trims[0].section, "progress");
+        assert_eq!(trims[1].section, "research");
+        assert!(trims[1].tokens_after < trims[1].tokens_before);
+        assert!(variables_tokens(&v, TokenModel::Generic) <= before - 1500);
+
+        assert_eq!(
+            v.progress.as_deref(),
+            Some("[progress omitted to fit the context budget]")
+        );
+        let research = v.research.unwrap();
+        assert!(research.starts_with("rrrr"));
+        assert!(research.ends_with("trimmed to fit the context budget ...]"));
+        assert_eq!(v.plan.unwrap().len(), 4000);
+    }
+
+    #[test]
+    fn test_progress_keeps_its_tail() {
+        let mut v = vars();
+        v.research = None;
+        v.plan = None;
+        let before = variables_tokens(&v, TokenModel::Generic);
+        let trims = fit_to_budget(&mut v, "implement", before - 300, TokenModel::Generic);
+        assert_eq!(trims.len(), 1);
+        let progress = v.progress.unwrap();
+        assert!(progress.starts_with("[... "));
+        assert!(progress.ends_with("latest entry"));
+    }
+}
@@ -1,5 +1,13 @@
-//! Prompt template loading and rendering
+//! Prompt template loading, rendering, and token estimates
 
+mod budget;
+pub mod snapshot;
 mod template;
+mod tokens;
 
-pub use template::{load_prompt_template, render_prompt, PromptVariables};
+pub use budget::{fit_to_budget, trim_order, variables_tokens, Trim};
+pub use tokens::{estimate_tokens, TokenModel};
+pub use template::{
+    list_prompt_variants, load_prompt_template, load_prompt_variant, render_prompt, PromptVariables,
+    BUNDLED_TEMPLATES,
+};
@@ -0,0 +1,301 @@
+//! Golden files for prompt templates
+//!
+//! Every bundled template, every custom template in `.wreckit/prompts/`, and
+//! every prompt variant is rendered against the same canned
+//! [`PromptVariables`] and compared with the golden file of the same name in
+//! `.wreckit/prompts/snapshots/`. `wreckit prompt snapshot --update` rewrites
+//! the golden files, so a prompt refactor shows up as a reviewable diff.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::errors::Result;
+use crate::fs::{self, get_prompt_snapshots_dir, get_prompts_dir};
+
+use super::template::{
+    load_prompt_template, load_prompt_variant, render_prompt, PromptVariables, BUNDLED_TEMPLATES,
+};
+
+/// A rendered template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Template name, with the variant if any (e.g. "implement.a")
+    pub name: String,
+
+    /// The template rendered against [`fixture_variables`]
+    pub rendered: String,
+}
+
+/// How a rendered template compares with its golden file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotStatus {
+    /// Matches the golden file
+    Unchanged,
+    /// Differs from the golden file
+    Changed,
+    /// Has no golden file yet
+    Added,
+    /// Golden file whose template no longer exists
+    Removed,
+}
+
+/// The comparison for one template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotChange {
+    /// Template name, with the variant if any
+    pub name: String,
+
+    /// How it compares
+    pub status: SnapshotStatus,
+
+    /// First line (1-based) that differs, for changed snapshots
+    pub first_difference: Option<usize>,
+}
+
+/// The canned variables every snapshot is rendered with. Every optional
+/// section is filled in so conditional blocks show up in the golden files.
+pub fn fixture_variables() -> PromptVariables {
+    let list = |items: &[&str]| Some(items.iter().map(|item| item.to_string()).collect());
+    PromptVariables {
+        id: "001-example".to_string(),
+        title: "Example item".to_string(),
+        section: "examples".to_string(),
+        overview: "An example item used to render prompt snapshots.".to_string(),
+        item_path: ".wreckit/items/001-example".to_string(),
+        branch_name: "wreckit/001-example".to_string(),
+        base_branch: "main".to_string(),
+        completion_signal: "<promise>COMPLETE</promise>".to_string(),
+        sdk_mode: false,
+        research: Some("Example research.".to_string()),
+        plan: Some("Example plan.".to_string()),
+        prd: Some("{\"user_stories\": []}".to_string()),
+        progress: Some("Example progress.".to_string()),
+        story: Some("US-001: Example story".to_string()),
+        verify_failures: Some("### test\nexample failure".to_string()),
+        diff_stat: Some("10 files, 500 lines".to_string()),
+        repo_context: Some("Example repository context.".to_string()),
+        policy_violations: Some("### src/lib.rs\n- line 1 (no-dbg): `dbg!(x)`".to_string()),
+        candidate_plans: Some("### Plan A\nExample candidate.".to_string()),
+        artifact: Some("### plan.md\nExample plan.".to_string()),
+        changes: Some("diff --git a/src/lib.rs b/src/lib.rs".to_string()),
+        attachments_dir: Some(".wreckit/items/001-example/attachments".to_string()),
+        problem_statement: Some("Example problem.".to_string()),
+        motivation: Some("Example motivation.".to_string()),
+        success_criteria: list(&["Example criterion"]),
+        technical_constraints: list(&["Example constraint"]),
+        scope_in_scope: list(&["Example in scope"]),
+        scope_out_of_scope: list(&["Example out of scope"]),
+    }
+}
+
+/// Names of every template to snapshot: the bundled ones plus each `.md`
+/// file in `.wreckit/prompts/`, sorted
+fn template_names(root: &Path) -> Result<BTreeSet<String>> {
+    let mut names: BTreeSet<String> = BUNDLED_TEMPLATES.iter().map(|n| n.to_string()).collect();
+    let dir = get_prompts_dir(root);
+    if dir.is_dir() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if let Some(name) = file_name.strip_suffix(".md") {
+                if entry.file_type()?.is_file() {
+                    names.insert(name.to_string());
+                }
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Render every template against [`fixture_variables`]
+///
+/// # Errors
+/// * `FileNotFound` - If a custom template cannot be read
+/// * `Io` - If the prompts directory cannot be listed
+pub fn render_snapshots(root: &Path) -> Result<Vec<Snapshot>> {
+    let variables = fixture_variables();
+    template_names(root)?
+        .into_iter()
+        .map(|name| {
+            let template = match name.split_once('.') {
+                Some((base, variant)) => load_prompt_variant(root, base, Some(variant))?,
+                None => load_prompt_template(root, &name)?,
+            };
+            Ok(Snapshot {
+                rendered: render_prompt(&template, &variables),
+                name,
+            })
+        })
+        .collect()
+}
+
+/// First line (1-based) where two texts differ
+fn first_difference(expected: &str, actual: &str) -> usize {
+    let mut expected_lines = expected.split('\n');
+    let mut actual_lines = actual.split('\n');
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => return line,
+            (a, b) if a != b => return line,
+            _ => line += 1,
+        }
+    }
+}
+
+/// Compare every rendered template with its golden file
+///
+/// # Errors
+/// * `FileNotFound` - If a custom template cannot be read
+/// * `Io` - If a golden file cannot be read
+pub fn compare_snapshots(root: &Path) -> Result<Vec<SnapshotChange>> {
+    let dir = get_prompt_snapshots_dir(root);
+    let snapshots = render_snapshots(root)?;
+    let mut changes = Vec::new();
+    for snapshot in &snapshots {
+        let path = dir.join(format!("{}.md", snapshot.name));
+        let (status, difference) = if !path.exists() {
+            (SnapshotStatus::Added, None)
+        } else {
+            let golden = std::fs::read_to_string(&path)?;
+            if golden == snapshot.rendered {
+                (SnapshotStatus::Unchanged, None)
+            } else {
+                let line = first_difference(&golden, &snapshot.rendered);
+                (SnapshotStatus::Changed, Some(line))
+            }
+        };
+        changes.push(SnapshotChange {
+            name: snapshot.name.clone(),
+            status,
+            first_difference: difference,
+        });
+    }
+
+    if dir.is_dir() {
+        let mut removed = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let file_name = entry?.file_name().to_string_lossy().to_string();
+            if let Some(name) = file_name.strip_suffix(".md") {
+                if !snapshots.iter().any(|snapshot| snapshot.name == name) {
+                    removed.push(name.to_string());
+                }
+            }
+        }
+        removed.sort();
+        changes.extend(removed.into_iter().map(|name| SnapshotChange {
+            name,
+            status: SnapshotStatus::Removed,
+            first_difference: None,
+        }));
+    }
+    Ok(changes)
+}
+
+/// Rewrite the golden files to match the current templates, deleting those
+/// of removed templates. Returns what changed.
+///
+/// # Errors
+/// * `FileNotFound` - If a custom template cannot be read
+/// * `Io` - If a golden file cannot be written or deleted
+pub fn update_snapshots(root: &Path) -> Result<Vec<SnapshotChange>> {
+    let dir = get_prompt_snapshots_dir(root);
+    let changes = compare_snapshots(root)?;
+    fs::create_dir_all(&dir)?;
+    for snapshot in render_snapshots(root)? {
+        fs::write_file(
+            &dir.join(format!("{}.md", snapshot.name)),
+            snapshot.rendered,
+        )?;
+    }
+    for change in &changes {
+        if change.status == SnapshotStatus::Removed {
+            fs::remove_file(&dir.join(format!("{}.md", change.name)))?;
+        }
+    }
+    Ok(changes
+        .into_iter()
+        .filter(|change| change.status != SnapshotStatus::Unchanged)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn statuses(changes: &[SnapshotChange]) -> Vec<(&str, SnapshotStatus)> {
+        changes
+            .iter()
+            .filter(|change| change.status != SnapshotStatus::Unchanged)
+            .map(|change| (change.name.as_str(), change.status))
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let prompts = get_prompts_dir(root);
+        std::fs::create_dir_all(&prompts).unwrap();
+        std::fs::write(prompts.join("implement.a.md"), "Story: {{story}}\n").unwrap();
+
+        let snapshots = render_snapshots(root).unwrap();
+        let names: Vec<&str> = snapshots.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "cleanup",
+                "evaluate",
+                "flaky_tests",
+                "implement",
+                "implement.a",
+                "implement_tests",
+                "plan",
+                "plan_consensus",
+                "pr",
+                "research",
+                "split",
+                "verify_criteria"
+            ]
+        );
+        let variant = snapshots.iter().find(|s| s.name == "implement.a").unwrap();
+        assert_eq!(variant.rendered, "Story: US-001: Example story\n");
+
+        let added = update_snapshots(root).unwrap();
+        assert_eq!(added.len(), 12);
+        assert!(added.iter().all(|c| c.status == SnapshotStatus::Added));
+        assert!(statuses(&compare_snapshots(root).unwrap()).is_empty());
+
+        // Edit the variant, override a bundled template, and drop a golden file's template
+        std::fs::write(
+            prompts.join("implement.a.md"),
+            "Story: {{story}}\nBranch: {{branch_name}}\n",
+        )
+        .unwrap();
+        std::fs::write(get_prompt_snapshots_dir(root).join("old.md"), "stale").unwrap();
+        let changes = compare_snapshots(root).unwrap();
+        assert_eq!(
+            statuses(&changes),
+            vec![
+                ("implement.a", SnapshotStatus::Changed),
+                ("old", SnapshotStatus::Removed)
+            ]
+        );
+        let changed = changes.iter().find(|c| c.name == "implement.a").unwrap();
+        assert_eq!(changed.first_difference, Some(2));
+
+        update_snapshots(root).unwrap();
+        assert!(statuses(&compare_snapshots(root).unwrap()).is_empty());
+        assert!(!get_prompt_snapshots_dir(root).join("old.md").exists());
+    }
+
+    #[test]
+    fn test_update_snapshots_refuses_in_read_only_mode() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let err = fs::with_read_only(|| update_snapshots(root)).unwrap_err();
+        assert!(matches!(err, crate::errors::WreckitError::ReadOnly(_)));
+        assert!(!get_prompt_snapshots_dir(root).exists());
+    }
+}
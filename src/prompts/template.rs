@@ -14,7 +14,29 @@ use crate::fs::get_prompts_dir;
 const DEFAULT_RESEARCH_PROMPT: &str = include_str!("../../prompts/research.md");
 const DEFAULT_PLAN_PROMPT: &str = include_str!("../../prompts/plan.md");
 const DEFAULT_IMPLEMENT_PROMPT: &str = include_str!("../../prompts/implement.md");
+const DEFAULT_IMPLEMENT_TESTS_PROMPT: &str = include_str!("../../prompts/implement_tests.md");
 const DEFAULT_PR_PROMPT: &str = include_str!("../../prompts/pr.md");
+const DEFAULT_SPLIT_PROMPT: &str = include_str!("../../prompts/split.md");
+const DEFAULT_CLEANUP_PROMPT: &str = include_str!("../../prompts/cleanup.md");
+const DEFAULT_FLAKY_TESTS_PROMPT: &str = include_str!("../../prompts/flaky_tests.md");
+const DEFAULT_PLAN_CONSENSUS_PROMPT: &str = include_str!("../../prompts/plan_consensus.md");
+const DEFAULT_EVALUATE_PROMPT: &str = include_str!("../../prompts/evaluate.md");
+const DEFAULT_VERIFY_CRITERIA_PROMPT: &str = include_str!("../../prompts/verify_criteria.md");
+
+/// Names of the bundled prompt templates
+pub const BUNDLED_TEMPLATES: &[&str] = &[
+    "research",
+    "plan",
+    "implement",
+    "implement_tests",
+    "pr",
+    "split",
+    "cleanup",
+    "flaky_tests",
+    "plan_consensus",
+    "evaluate",
+    "verify_criteria",
+];
 
 /// Variables available for prompt template rendering
 #[derive(Debug, Clone, Default)]
@@ -58,6 +80,34 @@ pub struct PromptVariables {
     /// Contents of progress.log (if exists)
     pub progress: Option<String>,
 
+    /// The story to implement in this iteration (implement phase only)
+    pub story: Option<String>,
+
+    /// Output of verify checks that failed on the previous attempt
+    pub verify_failures: Option<String>,
+
+    /// Branch size and the configured limits (split prompt only)
+    pub diff_stat: Option<String>,
+
+    /// Cached repository overview (research and plan prompts only)
+    pub repo_context: Option<String>,
+
+    /// Diff policy violations to fix (cleanup prompt only)
+    pub policy_violations: Option<String>,
+
+    /// Candidate plans and PRDs to merge (plan_consensus prompt only)
+    pub candidate_plans: Option<String>,
+
+    /// Artifact to score (evaluate prompt only)
+    pub artifact: Option<String>,
+
+    /// Uncommitted changes for a story (verify_criteria prompt only)
+    pub changes: Option<String>,
+
+    /// Directory for screenshots and reports shown in the PR, when
+    /// `pr.attachments` is on
+    pub attachments_dir: Option<String>,
+
     /// Problem statement (optional context)
     pub problem_statement: Option<String>,
 
@@ -89,7 +139,10 @@ impl PromptVariables {
         map.insert("item_path".to_string(), self.item_path.clone());
         map.insert("branch_name".to_string(), self.branch_name.clone());
         map.insert("base_branch".to_string(), self.base_branch.clone());
-        map.insert("completion_signal".to_string(), self.completion_signal.clone());
+        map.insert(
+            "completion_signal".to_string(),
+            self.completion_signal.clone(),
+        );
         map.insert("sdk_mode".to_string(), self.sdk_mode.to_string());
 
         if let Some(ref research) = self.research {
@@ -104,6 +157,33 @@ impl PromptVariables {
         if let Some(ref progress) = self.progress {
             map.insert("progress".to_string(), progress.clone());
         }
+        if let Some(ref story) = self.story {
+            map.insert("story".to_string(), story.clone());
+        }
+        if let Some(ref failures) = self.verify_failures {
+            map.insert("verify_failures".to_string(), failures.clone());
+        }
+        if let Some(ref stat) = self.diff_stat {
+            map.insert("diff_stat".to_string(), stat.clone());
+        }
+        if let Some(ref context) = self.repo_context {
+            map.insert("repo_context".to_string(), context.clone());
+        }
+        if let Some(ref violations) = self.policy_violations {
+            map.insert("policy_violations".to_string(), violations.clone());
+        }
+        if let Some(ref candidates) = self.candidate_plans {
+            map.insert("candidate_plans".to_string(), candidates.clone());
+        }
+        if let Some(ref artifact) = self.artifact {
+            map.insert("artifact".to_string(), artifact.clone());
+        }
+        if let Some(ref changes) = self.changes {
+            map.insert("changes".to_string(), changes.clone());
+        }
+        if let Some(ref dir) = self.attachments_dir {
+            map.insert("attachments_dir".to_string(), dir.clone());
+        }
         if let Some(ref ps) = self.problem_statement {
             map.insert("problem_statement".to_string(), ps.clone());
         }
@@ -143,7 +223,11 @@ pub fn load_prompt_template(root: &Path, name: &str) -> Result<String> {
     let custom_path = get_prompts_dir(root).join(format!("{}.md", name));
     if custom_path.exists() {
         return std::fs::read_to_string(&custom_path).map_err(|e| {
-            WreckitError::FileNotFound(format!("Cannot read template {}: {}", custom_path.display(), e))
+            WreckitError::FileNotFound(format!(
+                "Cannot read template {}: {}",
+                custom_path.display(),
+                e
+            ))
         });
     }
 
@@ -152,7 +236,14 @@ pub fn load_prompt_template(root: &Path, name: &str) -> Result<String> {
         "research" => Ok(DEFAULT_RESEARCH_PROMPT.to_string()),
         "plan" => Ok(DEFAULT_PLAN_PROMPT.to_string()),
         "implement" => Ok(DEFAULT_IMPLEMENT_PROMPT.to_string()),
+        "implement_tests" => Ok(DEFAULT_IMPLEMENT_TESTS_PROMPT.to_string()),
         "pr" => Ok(DEFAULT_PR_PROMPT.to_string()),
+        "split" => Ok(DEFAULT_SPLIT_PROMPT.to_string()),
+        "cleanup" => Ok(DEFAULT_CLEANUP_PROMPT.to_string()),
+        "flaky_tests" => Ok(DEFAULT_FLAKY_TESTS_PROMPT.to_string()),
+        "plan_consensus" => Ok(DEFAULT_PLAN_CONSENSUS_PROMPT.to_string()),
+        "evaluate" => Ok(DEFAULT_EVALUATE_PROMPT.to_string()),
+        "verify_criteria" => Ok(DEFAULT_VERIFY_CRITERIA_PROMPT.to_string()),
         _ => Err(WreckitError::FileNotFound(format!(
             "Unknown prompt template: {}",
             name
@@ -160,6 +251,48 @@ pub fn load_prompt_template(root: &Path, name: &str) -> Result<String> {
     }
 }
 
+/// Load a named variant of a prompt template (`.wreckit/prompts/<name>.<variant>.md`),
+/// or the template itself if `variant` is `None`.
+///
+/// # Errors
+/// * `FileNotFound` - If the variant or template does not exist
+pub fn load_prompt_variant(root: &Path, name: &str, variant: Option<&str>) -> Result<String> {
+    let variant = match variant {
+        Some(variant) => variant,
+        None => return load_prompt_template(root, name),
+    };
+    let path = get_prompts_dir(root).join(format!("{}.{}.md", name, variant));
+    std::fs::read_to_string(&path).map_err(|e| {
+        WreckitError::FileNotFound(format!(
+            "Cannot read prompt variant {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// List the variants of a prompt template found in `.wreckit/prompts/`, sorted by name
+pub fn list_prompt_variants(root: &Path, name: &str) -> Vec<String> {
+    let prefix = format!("{}.", name);
+    let entries = match std::fs::read_dir(get_prompts_dir(root)) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut variants: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            file_name
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(".md"))
+                .filter(|variant| !variant.is_empty() && !variant.contains('.'))
+                .map(String::from)
+        })
+        .collect();
+    variants.sort();
+    variants
+}
+
 /// Render a prompt template with variable substitution.
 ///
 /// Supports:
@@ -313,6 +446,33 @@ mod tests {
         assert_eq!(template, custom_content);
     }
 
+    #[test]
+    fn test_load_prompt_variants() {
+        let temp = TempDir::new().unwrap();
+        let prompts_dir = temp.path().join(".wreckit").join("prompts");
+        std::fs::create_dir_all(&prompts_dir).unwrap();
+        assert!(list_prompt_variants(temp.path(), "implement").is_empty());
+
+        std::fs::write(prompts_dir.join("implement.b.md"), "B").unwrap();
+        std::fs::write(prompts_dir.join("implement.a.md"), "A").unwrap();
+        std::fs::write(prompts_dir.join("implement.md"), "base").unwrap();
+        std::fs::write(prompts_dir.join("plan.c.md"), "C").unwrap();
+
+        assert_eq!(list_prompt_variants(temp.path(), "implement"), vec!["a", "b"]);
+        assert_eq!(
+            load_prompt_variant(temp.path(), "implement", Some("b")).unwrap(),
+            "B"
+        );
+        assert_eq!(
+            load_prompt_variant(temp.path(), "implement", None).unwrap(),
+            "base"
+        );
+        assert!(matches!(
+            load_prompt_variant(temp.path(), "implement", Some("z")),
+            Err(WreckitError::FileNotFound(_))
+        ));
+    }
+
     #[test]
     fn test_load_unknown_template() {
         let temp = TempDir::new().unwrap();
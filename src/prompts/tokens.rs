@@ -0,0 +1,127 @@
+//! Token estimates
+//!
+//! Prompt sizes are estimated rather than tokenized exactly, with ratios
+//! calibrated per model family: Claude's tokenizer splits English prose and
+//! code into shorter pieces than OpenAI's o200k or Gemini's. Characters
+//! outside ASCII (CJK text, emoji) cost about a token each everywhere. The
+//! context budgeter and the local stats both go through
+//! [`estimate_tokens`], so `wreckit prompt render --stats` shows the numbers
+//! the budget is enforced against.
+
+/// Tokenizer family a model belongs to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TokenModel {
+    /// Anthropic Claude models
+    Claude,
+    /// OpenAI GPT and o-series models
+    OpenAi,
+    /// Google Gemini models
+    Gemini,
+    /// Unknown models (four characters per token)
+    #[default]
+    Generic,
+}
+
+impl TokenModel {
+    /// The family of a model or agent name (e.g. "claude-sonnet-4", "gpt-4o",
+    /// "claude"); unknown or missing names are generic
+    pub fn for_model(model: Option<&str>) -> Self {
+        let Some(model) = model else {
+            return TokenModel::Generic;
+        };
+        let model = model.to_lowercase();
+        let name = model.rsplit('/').next().unwrap_or(&model);
+        if ["claude", "opus", "sonnet", "haiku"]
+            .iter()
+            .any(|family| name.contains(family))
+        {
+            TokenModel::Claude
+        } else if name.starts_with("gpt")
+            || name.starts_with("codex")
+            || ["o1", "o3", "o4"].iter().any(|p| name.starts_with(p))
+        {
+            TokenModel::OpenAi
+        } else if name.contains("gemini") {
+            TokenModel::Gemini
+        } else {
+            TokenModel::Generic
+        }
+    }
+
+    /// Short name for reports
+    pub fn name(self) -> &'static str {
+        match self {
+            TokenModel::Claude => "claude",
+            TokenModel::OpenAi => "openai",
+            TokenModel::Gemini => "gemini",
+            TokenModel::Generic => "generic",
+        }
+    }
+
+    /// ASCII characters per token, in tenths
+    fn ascii_chars_per_token_x10(self) -> usize {
+        match self {
+            TokenModel::Claude => 35,
+            TokenModel::OpenAi => 40,
+            TokenModel::Gemini => 40,
+            TokenModel::Generic => 40,
+        }
+    }
+
+    /// Estimate the token count of a text
+    pub fn count(self, text: &str) -> usize {
+        let (ascii, other) = text.chars().fold((0usize, 0usize), |(ascii, other), c| {
+            if c.is_ascii() {
+                (ascii + 1, other)
+            } else {
+                (ascii, other + 1)
+            }
+        });
+        (ascii * 10).div_ceil(self.ascii_chars_per_token_x10()) + other
+    }
+
+    /// About how many (ASCII) characters make up `tokens` tokens
+    pub fn chars_for(self, tokens: usize) -> usize {
+        tokens * self.ascii_chars_per_token_x10() / 10
+    }
+}
+
+/// Estimate the token count of a text for a model (see [`TokenModel::for_model`])
+pub fn estimate_tokens(text: &str, model: Option<&str>) -> usize {
+    TokenModel::for_model(model).count(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_model() {
+        assert_eq!(TokenModel::for_model(None), TokenModel::Generic);
+        assert_eq!(TokenModel::for_model(Some("claude")), TokenModel::Claude);
+        assert_eq!(
+            TokenModel::for_model(Some("claude-sonnet-4-5")),
+            TokenModel::Claude
+        );
+        assert_eq!(TokenModel::for_model(Some("opus")), TokenModel::Claude);
+        assert_eq!(TokenModel::for_model(Some("gpt-4o")), TokenModel::OpenAi);
+        assert_eq!(
+            TokenModel::for_model(Some("openai/o3-mini")),
+            TokenModel::OpenAi
+        );
+        assert_eq!(
+            TokenModel::for_model(Some("gemini-2.5-pro")),
+            TokenModel::Gemini
+        );
+        assert_eq!(TokenModel::for_model(Some("llama3")), TokenModel::Generic);
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens("", None), 0);
+        assert_eq!(estimate_tokens(&"a".repeat(400), None), 100);
+        assert_eq!(estimate_tokens(&"a".repeat(350), Some("claude")), 100);
+        assert_eq!(estimate_tokens("日本語", None), 3);
+        assert_eq!(TokenModel::Claude.chars_for(100), 350);
+    }
+}
@@ -1,31 +1,1398 @@
 //! Config schema - Configuration for wreckit
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-/// Agent execution mode
+use super::item::PriorityHint;
+
+/// Agent execution mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentMode {
+    /// Execute agent via process spawning
+    #[default]
+    Process,
+    /// Execute agent via SDK (not implemented in Rust port)
+    Sdk,
+    /// Return canned responses from a fixtures directory (for testing)
+    Mock,
+}
+
+/// Where agent and verify commands run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Execution {
+    /// Directly on the host
+    #[default]
+    Host,
+    /// With `devcontainer exec` in the repository's dev container
+    Devcontainer,
+    /// With `docker compose run` against the service named in
+    /// devcontainer.json
+    Compose,
+}
+
+/// How the project's declared environment is entered for agent and verify
+/// commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum EnvActivation {
+    /// Run commands as they are
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    /// With `direnv exec .`, loading the repository's .envrc
+    #[serde(rename = "direnv")]
+    Direnv,
+    /// With `nix develop --command`, in the flake's dev shell
+    #[serde(rename = "nix develop")]
+    NixDevelop,
+}
+
+/// Merge mode for completed work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMode {
+    /// Create a pull request
+    #[default]
+    Pr,
+    /// Direct merge to base branch (YOLO mode)
+    Direct,
+}
+
+/// How wreckit talks to GitHub for PR operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GitHubAuth {
+    /// The `gh` CLI if it is on PATH, otherwise a token from the
+    /// environment, otherwise the configured GitHub App
+    #[default]
+    Auto,
+    /// Always the `gh` CLI
+    Gh,
+    /// The REST API with `GITHUB_TOKEN` (or `GH_TOKEN`, or the keychain's
+    /// `github` secret)
+    Token,
+    /// The REST API as a GitHub App installation
+    App,
+}
+
+/// How the TUI alerts the operator about notable events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyMode {
+    /// No notifications
+    #[default]
+    None,
+    /// Ring the terminal bell
+    Bell,
+    /// Show a desktop notification
+    Desktop,
+}
+
+/// How new item IDs are generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum IdScheme {
+    /// Sequence number plus title slug (e.g. `007-add-dark-mode`)
+    #[default]
+    Numbered,
+    /// Prefix plus sequence number (e.g. `WRK-007`)
+    Sequential,
+    /// Date plus per-day sequence number (e.g. `20240701-002`)
+    Date,
+    /// Title slug alone (e.g. `add-dark-mode`)
+    Slug,
+    /// A ULID
+    Ulid,
+}
+
+/// Where `.wreckit/` metadata is committed after each phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MetaMode {
+    /// Leave metadata uncommitted
+    #[default]
+    Off,
+    /// Commit to a dedicated orphan branch (see `MetaConfig::branch`)
+    Branch,
+    /// Commit to the item's branch when it is checked out
+    Item,
+}
+
+/// A threshold on a number parsed from a check's output (e.g. coverage >= 80)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricGate {
+    /// Regex matching the metric; the first capture group (or the whole
+    /// match) is parsed as a number. The last match in the output wins.
+    pub pattern: String,
+
+    /// Lowest acceptable value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+
+    /// Highest acceptable value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+}
+
+/// A named verification command run after each implemented story
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerifyCheck {
+    /// Short name shown in reports (e.g., "fmt")
+    pub name: String,
+
+    /// Shell command; the check passes if it exits 0
+    pub cmd: String,
+
+    /// Metric threshold; gated checks run once before the item enters
+    /// in_pr instead of after every story
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gate: Option<MetricGate>,
+}
+
+impl VerifyCheck {
+    /// Create a check
+    pub fn new(name: impl Into<String>, cmd: impl Into<String>) -> Self {
+        VerifyCheck {
+            name: name.into(),
+            cmd: cmd.into(),
+            gate: None,
+        }
+    }
+
+    /// Attach a metric threshold
+    pub fn with_gate(mut self, gate: MetricGate) -> Self {
+        self.gate = Some(gate);
+        self
+    }
+}
+
+/// A security scanner run before a PR is opened (e.g. cargo audit, gitleaks)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecurityScan {
+    /// Short name shown in reports (e.g., "audit")
+    pub name: String,
+
+    /// Shell command; a non-zero exit means it found something
+    pub cmd: String,
+}
+
+impl SecurityScan {
+    /// Create a scan
+    pub fn new(name: impl Into<String>, cmd: impl Into<String>) -> Self {
+        SecurityScan {
+            name: name.into(),
+            cmd: cmd.into(),
+        }
+    }
+}
+
+/// What happens when a branch exceeds the PR size limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PrSizeAction {
+    /// Block the PR and explain the overage
+    #[default]
+    Warn,
+    /// Block the PR and ask the agent to move work into a follow-up item
+    Split,
+}
+
+/// Whether, and how, a PR merges itself once its checks pass
+///
+/// Written in config as `"squash"`, `"merge"`, `"rebase"`, or `false`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "serde_json::Value", into = "serde_json::Value")]
+pub enum AutoMerge {
+    /// Leave merging to a maintainer
+    #[default]
+    Off,
+    /// Squash and merge
+    Squash,
+    /// Merge commit
+    Merge,
+    /// Rebase and merge
+    Rebase,
+}
+
+impl AutoMerge {
+    /// The merge method name ("squash", "merge", "rebase"), or `None` when off
+    pub fn method(self) -> Option<&'static str> {
+        match self {
+            AutoMerge::Off => None,
+            AutoMerge::Squash => Some("squash"),
+            AutoMerge::Merge => Some("merge"),
+            AutoMerge::Rebase => Some("rebase"),
+        }
+    }
+}
+
+impl TryFrom<serde_json::Value> for AutoMerge {
+    type Error = String;
+
+    fn try_from(value: serde_json::Value) -> std::result::Result<Self, Self::Error> {
+        match value {
+            serde_json::Value::Bool(false) | serde_json::Value::Null => Ok(AutoMerge::Off),
+            serde_json::Value::String(ref method) if method == "squash" => Ok(AutoMerge::Squash),
+            serde_json::Value::String(ref method) if method == "merge" => Ok(AutoMerge::Merge),
+            serde_json::Value::String(ref method) if method == "rebase" => Ok(AutoMerge::Rebase),
+            other => Err(format!(
+                "auto_merge must be \"squash\", \"merge\", \"rebase\", or false, not {}",
+                other
+            )),
+        }
+    }
+}
+
+impl From<AutoMerge> for serde_json::Value {
+    fn from(auto_merge: AutoMerge) -> Self {
+        match auto_merge.method() {
+            Some(method) => serde_json::Value::String(method.to_string()),
+            None => serde_json::Value::Bool(false),
+        }
+    }
+}
+
+/// Pull request settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrConfig {
+    /// Enable GitHub auto-merge on the PRs wreckit opens, so they merge as
+    /// soon as required checks pass
+    #[serde(default)]
+    pub auto_merge: AutoMerge,
+
+    /// Have `wreckit watch` rebase in_pr branches when the base branch moves,
+    /// re-run verify, force-push, and comment on the PR
+    #[serde(default)]
+    pub refresh_on_base_update: bool,
+
+    /// Ask the implement agent for screenshots and reports in the item's
+    /// attachments/ directory, and embed them in the PR body (pushed to
+    /// the `wreckit/attachments` branch)
+    #[serde(default)]
+    pub attachments: bool,
+
+    /// Post a "wreckit" commit status with story progress (e.g. "5/7
+    /// stories done") on the item branch after each story, pushing the
+    /// branch as it goes
+    #[serde(default)]
+    pub commit_status: bool,
+
+    /// Open a draft PR once the first story is committed and keep a story
+    /// checklist in its body current; the PR phase fills in the final
+    /// description and marks it ready for review
+    #[serde(default)]
+    pub draft_early: bool,
+
+    /// In direct merge mode, squash the item branch into a single commit on
+    /// the base branch instead of merging it with a merge commit
+    #[serde(default)]
+    pub squash_direct_merge: bool,
+}
+
+/// Built-in item types that come with their own research, plan, and stories
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// Bring dependencies up to date, one story per outdated dependency
+    DependencyUpdate,
+
+    /// Stabilize tests that fail intermittently in CI, one story per test
+    FlakyTests,
+}
+
+impl Preset {
+    /// Every preset
+    pub const ALL: [Preset; 2] = [Preset::DependencyUpdate, Preset::FlakyTests];
+
+    /// Name used in config.json and on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::DependencyUpdate => "dependency_update",
+            Preset::FlakyTests => "flaky_tests",
+        }
+    }
+
+    /// Prompt template the implement phase uses for the preset's items
+    pub fn implement_template(self) -> &'static str {
+        match self {
+            Preset::DependencyUpdate => "implement",
+            Preset::FlakyTests => "flaky_tests",
+        }
+    }
+}
+
+impl std::fmt::Display for Preset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Preset::ALL
+            .into_iter()
+            .find(|preset| preset.name() == s)
+            .ok_or_else(|| format!("Unknown preset: {}", s))
+    }
+}
+
+fn default_outdated_command() -> String {
+    "cargo outdated --root-deps-only --format json".to_string()
+}
+
+fn default_update_command() -> String {
+    "cargo update".to_string()
+}
+
+/// Commands behind the `dependency_update` preset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyUpdateConfig {
+    /// Lists outdated dependencies when the item is created; cargo-outdated
+    /// JSON output gets one story per dependency
+    #[serde(default = "default_outdated_command")]
+    pub outdated_command: String,
+
+    /// Applies compatible updates in the first story
+    #[serde(default = "default_update_command")]
+    pub update_command: String,
+}
+
+impl Default for DependencyUpdateConfig {
+    fn default() -> Self {
+        DependencyUpdateConfig {
+            outdated_command: default_outdated_command(),
+            update_command: default_update_command(),
+        }
+    }
+}
+
+fn default_flaky_runs() -> u32 {
+    30
+}
+
+fn default_flaky_failure_pattern() -> String {
+    r"test (\S+) \.\.\. FAILED".to_string()
+}
+
+fn default_flaky_test_command() -> String {
+    "cargo test -- --exact {test}".to_string()
+}
+
+fn default_flaky_repeat() -> u32 {
+    20
+}
+
+/// How the `flaky_tests` preset finds and checks flaky tests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlakyTestsConfig {
+    /// Recent workflow runs on the base branch to scan
+    #[serde(default = "default_flaky_runs")]
+    pub runs: u32,
+
+    /// Regex matching a failed test in a run's log; the first capture group
+    /// is the test name
+    #[serde(default = "default_flaky_failure_pattern")]
+    pub failure_pattern: String,
+
+    /// Runs one test; `{test}` is replaced with its name
+    #[serde(default = "default_flaky_test_command")]
+    pub test_command: String,
+
+    /// Times in a row the test must pass before its story is done
+    #[serde(default = "default_flaky_repeat")]
+    pub repeat: u32,
+}
+
+impl Default for FlakyTestsConfig {
+    fn default() -> Self {
+        FlakyTestsConfig {
+            runs: default_flaky_runs(),
+            failure_pattern: default_flaky_failure_pattern(),
+            test_command: default_flaky_test_command(),
+            repeat: default_flaky_repeat(),
+        }
+    }
+}
+
+/// Settings for the built-in presets
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PresetsConfig {
+    /// The `dependency_update` preset
+    #[serde(default)]
+    pub dependency_update: DependencyUpdateConfig,
+
+    /// The `flaky_tests` preset
+    #[serde(default)]
+    pub flaky_tests: FlakyTestsConfig,
+}
+
+/// An item created afresh on a schedule, turning wreckit into a maintenance
+/// robot (e.g. a weekly dependency bump)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecurringItemConfig {
+    /// Template name, recorded on each item as `recurrence_of`
+    pub name: String,
+
+    /// When a fresh item is due: a five-field cron expression in UTC
+    /// (e.g. `"0 9 * * 1"`) or an alias such as `"@weekly"`
+    pub recurrence: String,
+
+    /// Create the item from a preset instead of the fields below
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<Preset>,
+
+    /// Item title (the preset's if empty); the creation date is appended
+    #[serde(default)]
+    pub title: String,
+
+    /// Item overview
+    #[serde(default)]
+    pub overview: String,
+
+    /// Backlog section for the items
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+
+    /// Success criteria for the items
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub success_criteria: Vec<String>,
+}
+
+/// CI checks an item's PR must pass before it enters a config-defined state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequireChecksConfig {
+    /// Wait for the PR's checks to pass before `wreckit advance` moves an
+    /// in_pr item into a custom state
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Check names that count, with `*` matching any run of characters
+    /// (e.g. `"test (crates/foo)"` or `"lint *"`); every check counts if
+    /// empty. Also limits which failures `/wreckit fix` works on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only: Vec<String>,
+}
+
+/// Limits that keep generated PRs reviewable (no limit if unset)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrSizeConfig {
+    /// Most files a PR may change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<usize>,
+
+    /// Most lines (added plus deleted) a PR may change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+
+    /// What to do when a limit is exceeded
+    #[serde(default)]
+    pub on_exceed: PrSizeAction,
+}
+
+/// What happens when the agent changes a protected path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProtectedPathAction {
+    /// Restore the files and retry the story with the violation in the prompt
+    #[default]
+    Revert,
+    /// Restore the files and stop the implement phase
+    Fail,
+}
+
+/// What happens when the network preflight cannot reach the forge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OfflineAction {
+    /// Stop before the phase starts
+    #[default]
+    Fail,
+    /// Run the phase offline: queue the PR in the outbox and skip posting
+    /// progress
+    Degrade,
+}
+
+/// Connectivity probes run before each phase
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// Probe the endpoints a phase needs (model API, forge host) before it
+    /// starts
+    #[serde(default)]
+    pub preflight: bool,
+
+    /// What to do when the forge is unreachable; an unreachable model API
+    /// always stops a phase that runs the agent
+    #[serde(default)]
+    pub on_unreachable: OfflineAction,
+
+    /// Host of the model API the agent calls
+    #[serde(default = "default_model_api_host")]
+    pub model_api_host: String,
+
+    /// Seconds to wait for each endpoint to accept a connection
+    #[serde(default = "default_probe_timeout_seconds")]
+    pub probe_timeout_seconds: u64,
+}
+
+fn default_model_api_host() -> String {
+    "api.anthropic.com".to_string()
+}
+
+fn default_probe_timeout_seconds() -> u64 {
+    5
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            preflight: false,
+            on_unreachable: OfflineAction::Fail,
+            model_api_host: default_model_api_host(),
+            probe_timeout_seconds: default_probe_timeout_seconds(),
+        }
+    }
+}
+
+/// A content rule checked against lines the branch adds (e.g. no `dbg!(`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffPolicy {
+    /// Short name shown in reports (e.g., "no-dbg")
+    pub name: String,
+
+    /// Regex an added line must not match
+    pub pattern: String,
+
+    /// Globs limiting which files the rule applies to (all files if empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+
+    /// Guidance shown to the agent alongside violations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl DiffPolicy {
+    /// Create a rule that applies to every file
+    pub fn new(name: impl Into<String>, pattern: impl Into<String>) -> Self {
+        DiffPolicy {
+            name: name.into(),
+            pattern: pattern.into(),
+            paths: Vec::new(),
+            message: None,
+        }
+    }
+
+    /// Limit the rule to files matching these globs
+    pub fn with_paths(mut self, paths: Vec<String>) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    /// Attach guidance for the agent
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// A license header required at the top of new files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LicenseHeader {
+    /// Header text, including comment markers (e.g. `// SPDX-License-Identifier: MIT`)
+    pub text: String,
+
+    /// Globs selecting the files that need the header (all new files if empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+}
+
+/// Limits on what the agent may change
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GuardrailsConfig {
+    /// Glob patterns, relative to the repository root, the agent must not
+    /// change (e.g. `.github/workflows/**`, `Cargo.lock`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected_paths: Vec<String>,
+
+    /// What to do when a protected path changes
+    #[serde(default)]
+    pub on_violation: ProtectedPathAction,
+
+    /// Content rules the branch diff must pass before a PR is opened
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policies: Vec<DiffPolicy>,
+
+    /// Header added to files the agent creates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_header: Option<LicenseHeader>,
+
+    /// CODEOWNERS entries the agent works on behalf of (e.g. `@org/platform`);
+    /// edits to files owned only by others are warned about
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+}
+
+/// Backoff applied when the agent reports a rate limit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Wait before the first retry when the agent gives no retry-after hint
+    #[serde(default = "default_initial_backoff_seconds")]
+    pub initial_backoff_seconds: u64,
+
+    /// Upper bound on any single wait
+    #[serde(default = "default_max_backoff_seconds")]
+    pub max_backoff_seconds: u64,
+
+    /// Consecutive rate-limited attempts tolerated before giving up
+    #[serde(default = "default_rate_limit_retries")]
+    pub max_retries: u32,
+}
+
+fn default_initial_backoff_seconds() -> u64 {
+    60
+}
+
+fn default_max_backoff_seconds() -> u64 {
+    900
+}
+
+fn default_rate_limit_retries() -> u32 {
+    5
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            initial_backoff_seconds: default_initial_backoff_seconds(),
+            max_backoff_seconds: default_max_backoff_seconds(),
+            max_retries: default_rate_limit_retries(),
+        }
+    }
+}
+
+/// Crash restarts for runs started with `wreckit run --detach`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SupervisorConfig {
+    /// Restarts after a crash before the supervisor gives up
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+
+    /// Wait before the first restart; doubles with each further restart
+    #[serde(default = "default_restart_backoff_seconds")]
+    pub initial_backoff_seconds: u64,
+
+    /// Upper bound on any single wait
+    #[serde(default = "default_max_restart_backoff_seconds")]
+    pub max_backoff_seconds: u64,
+}
+
+fn default_max_restarts() -> u32 {
+    3
+}
+
+fn default_restart_backoff_seconds() -> u64 {
+    30
+}
+
+fn default_max_restart_backoff_seconds() -> u64 {
+    600
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        SupervisorConfig {
+            max_restarts: default_max_restarts(),
+            initial_backoff_seconds: default_restart_backoff_seconds(),
+            max_backoff_seconds: default_max_restart_backoff_seconds(),
+        }
+    }
+}
+
+/// Garbage collection limits for `wreckit gc`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GcConfig {
+    /// Agent transcripts older than this many days are pruned
+    #[serde(default = "default_log_retention_days")]
+    pub log_retention_days: u32,
+
+    /// progress.log files larger than this (in KiB) are truncated to their tail
+    #[serde(default = "default_max_progress_log_kb")]
+    pub max_progress_log_kb: u64,
+}
+
+fn default_log_retention_days() -> u32 {
+    30
+}
+
+fn default_max_progress_log_kb() -> u64 {
+    1024
+}
+
+impl Default for GcConfig {
+    fn default() -> Self {
+        GcConfig {
+            log_retention_days: default_log_retention_days(),
+            max_progress_log_kb: default_max_progress_log_kb(),
+        }
+    }
+}
+
+/// Disk space thresholds checked by `wreckit doctor` and before the
+/// implement phase
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskConfig {
+    /// Warn when the repository's filesystem has less free space (in MiB)
+    #[serde(default = "default_min_free_mb")]
+    pub min_free_mb: u64,
+
+    /// Warn about untracked files larger than this (in MiB)
+    #[serde(default = "default_max_untracked_mb")]
+    pub max_untracked_mb: u64,
+
+    /// Warn when .wreckit grows larger than this (in MiB)
+    #[serde(default = "default_max_wreckit_mb")]
+    pub max_wreckit_mb: u64,
+}
+
+fn default_min_free_mb() -> u64 {
+    2048
+}
+
+fn default_max_untracked_mb() -> u64 {
+    100
+}
+
+fn default_max_wreckit_mb() -> u64 {
+    1024
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        DiskConfig {
+            min_free_mb: default_min_free_mb(),
+            max_untracked_mb: default_max_untracked_mb(),
+            max_wreckit_mb: default_max_wreckit_mb(),
+        }
+    }
+}
+
+/// What to tidy up once an item is done
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CleanupConfig {
+    /// Delete the item branch locally and on origin after the complete phase
+    /// marks it done
+    #[serde(default)]
+    pub delete_merged_branches: bool,
+}
+
+/// Shell commands run on workflow lifecycle events
+///
+/// Each hook runs through `sh -c` in the repository root. The item ID, state,
+/// title, directory, branch, and phase are passed as `WRECKIT_*` environment
+/// variables, and the event and item as JSON on stdin. A failing `pre_*`
+/// hook stops its phase; failing `post_*` and `on_error` hooks are logged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Before the research phase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_research: Option<String>,
+
+    /// After the research phase succeeds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_research: Option<String>,
+
+    /// Before the plan phase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_plan: Option<String>,
+
+    /// After the plan phase succeeds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_plan: Option<String>,
+
+    /// Before the implement phase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_implement: Option<String>,
+
+    /// After the implement phase succeeds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_implement: Option<String>,
+
+    /// Before the pr phase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_pr: Option<String>,
+
+    /// After the pr phase succeeds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_pr: Option<String>,
+
+    /// Before the complete phase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_complete: Option<String>,
+
+    /// After the complete phase succeeds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_complete: Option<String>,
+
+    /// After any phase fails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_error: Option<String>,
+}
+
+impl HooksConfig {
+    /// The command for a hook name (e.g. "pre_implement"), if configured
+    pub fn command(&self, hook: &str) -> Option<&str> {
+        let command = match hook {
+            "pre_research" => &self.pre_research,
+            "post_research" => &self.post_research,
+            "pre_plan" => &self.pre_plan,
+            "post_plan" => &self.post_plan,
+            "pre_implement" => &self.pre_implement,
+            "post_implement" => &self.post_implement,
+            "pre_pr" => &self.pre_pr,
+            "post_pr" => &self.post_pr,
+            "pre_complete" => &self.pre_complete,
+            "post_complete" => &self.post_complete,
+            "on_error" => &self.on_error,
+            _ => return None,
+        };
+        command.as_deref()
+    }
+}
+
+/// GitHub connection settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitHubConfig {
+    /// Which client performs PR operations
+    #[serde(default)]
+    pub auth: GitHubAuth,
+
+    /// GitHub Enterprise Server hostname (e.g. "github.mycorp.com"); unset
+    /// for github.com
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+
+    /// GitHub App ID, for `auth = "app"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<u64>,
+
+    /// Installation of the app on this repository's owner
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installation_id: Option<u64>,
+
+    /// PEM private key of the app, relative to the repository root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private_key_path: Option<String>,
+}
+
+/// Network settings for the API clients
+///
+/// Proxies otherwise come from `HTTPS_PROXY` / `HTTP_PROXY` / `ALL_PROXY`;
+/// `NO_PROXY` applies either way.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HttpConfig {
+    /// PEM bundle of extra CA certificates to trust, relative to the
+    /// repository root (e.g. a corporate TLS-inspection CA)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<String>,
+
+    /// Proxy URL used instead of the environment's (e.g.
+    /// "http://proxy.corp:3128")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+}
+
+/// Code hosting settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ForgeConfig {
+    /// GitHub connection settings
+    #[serde(default)]
+    pub github: GitHubConfig,
+}
+
+/// Slash commands maintainers post on wreckit's PRs, handled by `wreckit watch`
+///
+/// `/wreckit revise <feedback>` re-runs implement with the comment as
+/// feedback; `/wreckit fix` re-runs it against the PR's failing checks. The
+/// updated branch is pushed to the same PR and the result posted as a reply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrBotConfig {
+    /// Whether `wreckit watch` acts on PR comments
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// GitHub logins allowed to issue commands; when empty, the repository's
+    /// owners, members, and collaborators are
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub maintainers: Vec<String>,
+
+    /// Seconds between polls of open PRs
+    #[serde(default = "default_poll_seconds")]
+    pub poll_seconds: u64,
+}
+
+fn default_poll_seconds() -> u64 {
+    300
+}
+
+impl Default for PrBotConfig {
+    fn default() -> Self {
+        PrBotConfig {
+            enabled: false,
+            maintainers: Vec::new(),
+            poll_seconds: default_poll_seconds(),
+        }
+    }
+}
+
+/// Cached repository overview injected into research and plan prompts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextPackConfig {
+    /// Build .wreckit/cache/context.md and include it in prompts
+    #[serde(default = "default_context_pack_enabled")]
+    pub enabled: bool,
+
+    /// Rebuild the pack once HEAD is this many commits past it
+    #[serde(default = "default_refresh_after_commits")]
+    pub refresh_after_commits: usize,
+}
+
+fn default_context_pack_enabled() -> bool {
+    true
+}
+
+fn default_refresh_after_commits() -> usize {
+    20
+}
+
+impl Default for ContextPackConfig {
+    fn default() -> Self {
+        ContextPackConfig {
+            enabled: default_context_pack_enabled(),
+            refresh_after_commits: default_refresh_after_commits(),
+        }
+    }
+}
+
+/// Consensus planning: two agents plan independently and a third merges
+/// their plans
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlanConfig {
+    /// Plan with two agents and an arbiter pass instead of a single agent
+    #[serde(default)]
+    pub consensus: bool,
+
+    /// Models for the two candidate plans (the plan phase's agent for any
+    /// left unset)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+
+    /// Model for the arbiter pass (the plan phase's agent if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arbiter_model: Option<String>,
+
+    /// Only use consensus for items with at least this priority hint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_priority: Option<PriorityHint>,
+}
+
+impl PlanConfig {
+    /// Whether an item with this priority hint is planned by consensus
+    pub fn uses_consensus(&self, priority: Option<PriorityHint>) -> bool {
+        self.consensus
+            && match self.min_priority {
+                Some(min) => priority.is_some_and(|p| p >= min),
+                None => true,
+            }
+    }
+}
+
+/// What happens when an artifact scores below `evaluation.min_score`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LowScoreAction {
+    /// Re-run the phase with the evaluation as feedback, up to
+    /// `max_revisions` times, then hold the item for approval
+    #[default]
+    Revise,
+    /// Hold the item for approval right away
+    Gate,
+}
+
+/// Self-evaluation of phase artifacts against a rubric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvaluationConfig {
+    /// Score artifacts after their phase runs the agent
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Phases whose artifacts are scored
+    #[serde(default = "default_evaluated_phases")]
+    pub phases: Vec<String>,
+
+    /// Lowest acceptable overall score (1-5)
+    #[serde(default = "default_min_score")]
+    pub min_score: u8,
+
+    /// What a score below `min_score` triggers
+    #[serde(default)]
+    pub on_low_score: LowScoreAction,
+
+    /// Revision passes before a low-scoring artifact is held for approval
+    #[serde(default = "default_max_revisions")]
+    pub max_revisions: u32,
+
+    /// Model for the evaluation pass (the phase's agent if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+fn default_evaluated_phases() -> Vec<String> {
+    vec!["research".to_string(), "plan".to_string()]
+}
+
+fn default_min_score() -> u8 {
+    3
+}
+
+fn default_max_revisions() -> u32 {
+    1
+}
+
+impl Default for EvaluationConfig {
+    fn default() -> Self {
+        EvaluationConfig {
+            enabled: false,
+            phases: default_evaluated_phases(),
+            min_score: default_min_score(),
+            on_low_score: LowScoreAction::default(),
+            max_revisions: default_max_revisions(),
+            model: None,
+        }
+    }
+}
+
+impl EvaluationConfig {
+    /// Whether a phase's artifact is scored
+    pub fn evaluates(&self, phase: &str) -> bool {
+        self.enabled && self.phases.iter().any(|p| p == phase)
+    }
+}
+
+/// Settings for the implement loop
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImplementConfig {
+    /// Split each story into two agent passes: failing tests first,
+    /// committed on their own, then the implementation
+    #[serde(default)]
+    pub test_first: bool,
+}
+
+/// Acceptance-criteria verification of stories the implement loop completes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VerificationConfig {
+    /// Check each story's acceptance criteria against the code before it is
+    /// marked done
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Model for the verification pass (the implement agent's if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// How a phase chooses among its prompt variants (`<phase>.<variant>.md`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptSelection {
+    /// Always use `prompt_variant` (the base template if unset)
+    #[default]
+    Fixed,
+    /// Rotate through every variant, picking the one with the fewest recorded runs
+    RoundRobin,
+}
+
+/// Settings for a single workflow phase
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseConfig {
+    /// Soft budget: warn once the phase has run this long
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warn_after_minutes: Option<u64>,
+
+    /// Also notify the operator when the soft budget is exceeded
+    #[serde(default)]
+    pub notify_over_budget: bool,
+
+    /// Variant used by fixed selection (`.wreckit/prompts/<phase>.<variant>.md`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_variant: Option<String>,
+
+    /// How the phase picks a prompt variant
+    #[serde(default)]
+    pub prompt_selection: PromptSelection,
+
+    /// Reuse a stored agent response for the same prompt on the same commit
+    /// (defaults to on for research and plan, whose agent only writes in the
+    /// item directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<bool>,
+
+    /// Prompt token budget for the phase (overrides `max_prompt_tokens`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_prompt_tokens: Option<usize>,
+
+    /// Model for the phase's agent runs, passed as `--model` in place of any
+    /// model in the agent args (e.g. a cheaper model for research)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Agent args for the phase, replacing `agent.args`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args_override: Option<Vec<String>>,
+
+    /// Resource caps for the phase's agent, replacing `agent.limits`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ResourceLimits>,
+}
+
+/// Phases whose agent responses are cached unless configured otherwise
+pub const CACHED_PHASES: &[&str] = &["research", "plan"];
+
+impl PhaseConfig {
+    /// Whether agent responses for the phase `name` are cached
+    pub fn caches_agent(&self, name: &str) -> bool {
+        self.cache.unwrap_or_else(|| CACHED_PHASES.contains(&name))
+    }
+}
+
+/// A conventional change type and how to recognize it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeType {
+    /// Conventional commit type used as the PR title prefix (e.g. "feat")
+    pub name: String,
+
+    /// Words in the item title, overview, or story titles that suggest this type
+    #[serde(default)]
+    pub keywords: Vec<String>,
+
+    /// Labels applied to PRs of this type
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+impl ChangeType {
+    /// Create a change type
+    pub fn new(name: &str, keywords: &[&str], labels: &[&str]) -> Self {
+        ChangeType {
+            name: name.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+        }
+    }
+}
+
+/// Conventional PR titles and semantic labels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrConventionsConfig {
+    /// Prefix PR titles with the inferred type and apply its labels
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Type used when no keyword matches
+    #[serde(default = "default_change_type")]
+    pub default_type: String,
+
+    /// Known change types, earlier entries winning ties
+    #[serde(default = "default_change_types")]
+    pub types: Vec<ChangeType>,
+}
+
+fn default_change_type() -> String {
+    "feat".to_string()
+}
+
+fn default_change_types() -> Vec<ChangeType> {
+    vec![
+        ChangeType::new(
+            "fix",
+            &["fix", "bug", "crash", "error", "regression", "broken", "incorrect"],
+            &["bug"],
+        ),
+        ChangeType::new(
+            "feat",
+            &["add", "implement", "support", "introduce", "new", "allow", "enable"],
+            &["enhancement"],
+        ),
+        ChangeType::new(
+            "docs",
+            &["docs", "documentation", "readme", "guide"],
+            &["documentation"],
+        ),
+        ChangeType::new(
+            "chore",
+            &["chore", "refactor", "cleanup", "bump", "upgrade", "dependencies", "ci"],
+            &["chore"],
+        ),
+    ]
+}
+
+impl Default for PrConventionsConfig {
+    fn default() -> Self {
+        PrConventionsConfig {
+            enabled: false,
+            default_type: default_change_type(),
+            types: default_change_types(),
+        }
+    }
+}
+
+/// Layout of generated changelog fragments
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
-pub enum AgentMode {
-    /// Execute agent via process spawning
+pub enum ChangelogFormat {
+    /// `<dir>/<id>.md` with a heading and one bullet per story
     #[default]
-    Process,
-    /// Execute agent via SDK (not implemented in Rust port)
-    Sdk,
+    Markdown,
+    /// `<dir>/<id>.<type>.md`, as collected by towncrier
+    Towncrier,
 }
 
-/// Merge mode for completed work
+/// Changelog fragments written when an item reaches in_pr
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangelogConfig {
+    /// Write a fragment for each PR
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Fragment directory, relative to the repository root
+    #[serde(default = "default_changelog_dir")]
+    pub dir: String,
+
+    /// Fragment layout
+    #[serde(default)]
+    pub format: ChangelogFormat,
+}
+
+fn default_changelog_dir() -> String {
+    ".changes".to_string()
+}
+
+impl Default for ChangelogConfig {
+    fn default() -> Self {
+        ChangelogConfig {
+            enabled: false,
+            dir: default_changelog_dir(),
+            format: ChangelogFormat::default(),
+        }
+    }
+}
+
+/// Metadata persistence configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetaConfig {
+    /// Where metadata is committed
+    #[serde(default)]
+    pub mode: MetaMode,
+
+    /// Branch used in `branch` mode and by `wreckit sync-meta`
+    #[serde(default = "default_meta_branch")]
+    pub branch: String,
+
+    /// Push the metadata branch to origin after each commit
+    #[serde(default)]
+    pub push: bool,
+}
+
+fn default_meta_branch() -> String {
+    "wreckit-meta".to_string()
+}
+
+impl Default for MetaConfig {
+    fn default() -> Self {
+        MetaConfig {
+            mode: MetaMode::Off,
+            branch: default_meta_branch(),
+            push: false,
+        }
+    }
+}
+
+/// TUI configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TuiConfig {
+    /// Notification mode for completion, failure, and approval events
+    #[serde(default)]
+    pub notify: NotifyMode,
+
+    /// Draw with ASCII only: no box-drawing characters, and words instead of
+    /// state icons (for screen readers and dumb terminals)
+    #[serde(default)]
+    pub ascii: bool,
+
+    /// Redraw at most once a second (and on key presses) instead of continuously
+    #[serde(default)]
+    pub reduced_motion: bool,
+
+    /// Key bindings
+    #[serde(default)]
+    pub keymap: KeymapConfig,
+}
+
+/// Base set of TUI key bindings
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
-pub enum MergeMode {
-    /// Create a pull request
+pub enum KeymapPreset {
+    /// j/k to scroll, g/G for top and bottom
     #[default]
-    Pr,
-    /// Direct merge to base branch (YOLO mode)
-    Direct,
+    Vi,
+    /// Ctrl-N/Ctrl-P to scroll, Alt-</Alt-> for top and bottom
+    Emacs,
+}
+
+/// TUI key bindings: a preset plus per-action overrides
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeymapConfig {
+    /// Preset the bindings start from
+    #[serde(default)]
+    pub preset: KeymapPreset,
+
+    /// Keys per action, replacing the preset's (e.g. `{"quit": ["ctrl-q"]}`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub bindings: BTreeMap<String, Vec<String>>,
+}
+
+/// A config-defined workflow state (e.g. `qa` or `deployed`)
+///
+/// Custom states follow the built-in agent phases: `after` must name `in_pr`,
+/// `done`, or an earlier custom state. Entering the state runs its validation
+/// hooks; items are moved into it with `wreckit advance`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomStateConfig {
+    /// State name (e.g., "qa")
+    pub name: String,
+
+    /// State this one follows in the ordering
+    #[serde(default = "default_state_after")]
+    pub after: String,
+
+    /// File that must exist to enter the state, relative to the item directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<String>,
+
+    /// Shell command that must succeed to enter the state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+fn default_state_after() -> String {
+    "in_pr".to_string()
 }
 
 /// Agent configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AgentConfig {
     /// Agent execution mode
     #[serde(default)]
@@ -40,6 +1407,88 @@ pub struct AgentConfig {
 
     /// Signal that indicates agent completion
     pub completion_signal: String,
+
+    /// Seconds an agent may keep running after printing the completion
+    /// signal before it is stopped (and counted as complete)
+    #[serde(default = "default_completion_grace_seconds")]
+    pub completion_grace_seconds: u64,
+
+    /// Fixtures directory for mock mode, relative to the repository root
+    /// (defaults to .wreckit/fixtures)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixtures_dir: Option<String>,
+
+    /// Variables set for the agent and verify commands; a value of
+    /// `env:NAME` is read from wreckit's environment
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
+    /// Where the agent and verify commands run: on the host, or inside the
+    /// dev container described by the repository's devcontainer.json
+    #[serde(default)]
+    pub execution: Execution,
+
+    /// Resource caps for the agent process and everything it spawns
+    #[serde(default, skip_serializing_if = "ResourceLimits::is_empty")]
+    pub limits: ResourceLimits,
+}
+
+/// Resource caps for a spawned agent process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct ResourceLimits {
+    /// Address-space limit in megabytes (RLIMIT_AS), inherited by the
+    /// builds and tests the agent starts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_mb: Option<u64>,
+
+    /// CPU quota as a percentage of one core (200 = two cores); applied
+    /// with a `systemd-run --user --scope` cgroup where available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cpu_percent: Option<u32>,
+
+    /// Niceness the agent runs at (0-19; higher yields more readily)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+}
+
+impl ResourceLimits {
+    /// Whether no cap is set
+    pub fn is_empty(&self) -> bool {
+        *self == ResourceLimits::default()
+    }
+}
+
+impl AgentConfig {
+    /// Model passed to the agent with `--model`, if any
+    pub fn model(&self) -> Option<&str> {
+        self.args.iter().enumerate().find_map(|(i, arg)| {
+            match arg.strip_prefix("--model") {
+                Some("") => self.args.get(i + 1).map(String::as_str),
+                Some(rest) => rest.strip_prefix('='),
+                None => None,
+            }
+        })
+    }
+
+    /// Pass `model` with `--model`, replacing any model already in the args
+    pub fn with_model(mut self, model: &str) -> Self {
+        let mut args = Vec::with_capacity(self.args.len() + 2);
+        let mut rest = std::mem::take(&mut self.args).into_iter();
+        while let Some(arg) = rest.next() {
+            if arg == "--model" {
+                rest.next();
+            } else if !arg.starts_with("--model=") {
+                args.push(arg);
+            }
+        }
+        args.extend(["--model".to_string(), model.to_string()]);
+        self.args = args;
+        self
+    }
+}
+
+fn default_completion_grace_seconds() -> u64 {
+    30
 }
 
 impl Default for AgentConfig {
@@ -52,8 +1501,52 @@ impl Default for AgentConfig {
                 "--print".to_string(),
             ],
             completion_signal: "<promise>COMPLETE</promise>".to_string(),
+            completion_grace_seconds: default_completion_grace_seconds(),
+            fixtures_dir: None,
+            env: BTreeMap::new(),
+            execution: Execution::Host,
+            limits: ResourceLimits::default(),
+        }
+    }
+}
+
+/// An agent setup compared by `wreckit bench`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchVariant {
+    /// Short name shown in the report (e.g., "opus")
+    pub name: String,
+
+    /// Agent to run (the configured agent if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<AgentConfig>,
+
+    /// Directory of prompt templates, relative to the repository root, laid
+    /// over .wreckit/prompts for this variant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompts_dir: Option<String>,
+}
+
+impl BenchVariant {
+    /// Create a variant that runs the configured agent and prompts
+    pub fn new(name: impl Into<String>) -> Self {
+        BenchVariant {
+            name: name.into(),
+            agent: None,
+            prompts_dir: None,
         }
     }
+
+    /// Run a different agent
+    pub fn with_agent(mut self, agent: AgentConfig) -> Self {
+        self.agent = Some(agent);
+        self
+    }
+
+    /// Lay a directory of prompt templates over .wreckit/prompts
+    pub fn with_prompts_dir(mut self, dir: impl Into<String>) -> Self {
+        self.prompts_dir = Some(dir.into());
+        self
+    }
 }
 
 /// Main configuration for wreckit
@@ -79,6 +1572,11 @@ pub struct Config {
     #[serde(default)]
     pub agent: AgentConfig,
 
+    /// Environment activation for agent and verify commands, so they use
+    /// the project's declared toolchain
+    #[serde(default)]
+    pub env_activation: EnvActivation,
+
     /// Maximum iterations for implementation phase
     #[serde(default = "default_max_iterations")]
     pub max_iterations: u32,
@@ -86,6 +1584,164 @@ pub struct Config {
     /// Timeout in seconds for agent execution
     #[serde(default = "default_timeout_seconds")]
     pub timeout_seconds: u32,
+
+    /// Estimated token budget for a rendered prompt (0 disables trimming)
+    #[serde(default = "default_max_prompt_tokens")]
+    pub max_prompt_tokens: usize,
+
+    /// Per-phase settings, keyed by phase name (e.g. "implement")
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub phases: BTreeMap<String, PhaseConfig>,
+
+    /// Plan phase settings
+    #[serde(default)]
+    pub plan: PlanConfig,
+
+    /// Self-evaluation of research and plan artifacts
+    #[serde(default)]
+    pub evaluation: EvaluationConfig,
+
+    /// Acceptance-criteria verification of completed stories
+    #[serde(default)]
+    pub verification: VerificationConfig,
+
+    /// Implement loop settings
+    #[serde(default)]
+    pub implement: ImplementConfig,
+
+    /// Shell command that verifies a story (e.g., "cargo test"); skipped if unset.
+    /// Runs as a check named "verify" ahead of the `verify` list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_command: Option<String>,
+
+    /// Checks run after each implemented story (e.g., fmt, lint, test)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verify: Vec<VerifyCheck>,
+
+    /// Security scanners whose findings block PR creation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub security: Vec<SecurityScan>,
+
+    /// Size limits checked before a PR is opened
+    #[serde(default)]
+    pub pr_size: PrSizeConfig,
+
+    /// Pull request settings
+    #[serde(default)]
+    pub pr: PrConfig,
+
+    /// CI checks required before custom states
+    #[serde(default)]
+    pub require_checks: RequireChecksConfig,
+
+    /// Paths the agent may not change during implementation
+    #[serde(default)]
+    pub guardrails: GuardrailsConfig,
+
+    /// Changelog fragments committed with each PR
+    #[serde(default)]
+    pub changelog: ChangelogConfig,
+
+    /// Conventional PR titles and labels
+    #[serde(default)]
+    pub pr_conventions: PrConventionsConfig,
+
+    /// How new item IDs are generated
+    #[serde(default)]
+    pub id_scheme: IdScheme,
+
+    /// Prefix for the `sequential` ID scheme
+    #[serde(default = "default_id_prefix")]
+    pub id_prefix: String,
+
+    /// Identity used for item assignment (defaults to git user.email)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+
+    /// Snapshots kept per item in .wreckit/backups (0 disables snapshots)
+    #[serde(default = "default_backup_retention")]
+    pub backup_retention: u32,
+
+    /// Maintain a SQLite item index in .wreckit/cache/index.db
+    #[serde(default)]
+    pub sqlite_index: bool,
+
+    /// Append key decisions from the agent transcript to research.md and plan.md
+    #[serde(default = "default_summarize_transcripts")]
+    pub summarize_transcripts: bool,
+
+    /// Repository overview for research and plan prompts
+    #[serde(default)]
+    pub context_pack: ContextPackConfig,
+
+    /// Metadata persistence
+    #[serde(default)]
+    pub meta: MetaConfig,
+
+    /// Disk space thresholds for preflight warnings
+    #[serde(default)]
+    pub disk: DiskConfig,
+
+    /// Limits for `wreckit gc`
+    #[serde(default)]
+    pub gc: GcConfig,
+
+    /// Cleanup after items are done
+    #[serde(default)]
+    pub cleanup: CleanupConfig,
+
+    /// Agent setups compared by `wreckit bench`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bench: Vec<BenchVariant>,
+
+    /// Backoff when the agent is rate limited
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+
+    /// Crash restarts for detached runs
+    #[serde(default)]
+    pub supervisor: SupervisorConfig,
+
+    /// Shell commands run on lifecycle events
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Code hosting (GitHub) settings
+    #[serde(default)]
+    pub forge: ForgeConfig,
+
+    /// Proxy and CA settings for the API clients
+    #[serde(default)]
+    pub http: HttpConfig,
+
+    /// Connectivity preflight before each phase
+    #[serde(default)]
+    pub network: NetworkConfig,
+
+    /// PR comment commands handled by `wreckit watch`
+    #[serde(default)]
+    pub pr_bot: PrBotConfig,
+
+    /// TUI configuration
+    #[serde(default)]
+    pub tui: TuiConfig,
+
+    /// Settings for `wreckit preset`
+    #[serde(default)]
+    pub presets: PresetsConfig,
+
+    /// Items created on a schedule by `wreckit watch` or `wreckit recur`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recurring: Vec<RecurringItemConfig>,
+
+    /// Extra workflow states beyond the built-in ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub states: Vec<CustomStateConfig>,
+
+    /// Named overrides merged over this config when selected with
+    /// `--profile` or `WRECKIT_PROFILE` (e.g. `profiles.ci.agent.command`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, serde_json::Value>,
 }
 
 fn default_schema_version() -> u32 {
@@ -108,6 +1764,22 @@ fn default_timeout_seconds() -> u32 {
     3600
 }
 
+fn default_id_prefix() -> String {
+    "WRK".to_string()
+}
+
+fn default_max_prompt_tokens() -> usize {
+    150_000
+}
+
+fn default_backup_retention() -> u32 {
+    10
+}
+
+fn default_summarize_transcripts() -> bool {
+    true
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
@@ -116,8 +1788,89 @@ impl Default for Config {
             branch_prefix: "wreckit/".to_string(),
             merge_mode: MergeMode::Pr,
             agent: AgentConfig::default(),
+            env_activation: EnvActivation::None,
             max_iterations: 100,
             timeout_seconds: 3600,
+            max_prompt_tokens: default_max_prompt_tokens(),
+            phases: BTreeMap::new(),
+            plan: PlanConfig::default(),
+            evaluation: EvaluationConfig::default(),
+            verification: VerificationConfig::default(),
+            implement: ImplementConfig::default(),
+            verify_command: None,
+            verify: Vec::new(),
+            security: Vec::new(),
+            pr_size: PrSizeConfig::default(),
+            pr: PrConfig::default(),
+            require_checks: RequireChecksConfig::default(),
+            guardrails: GuardrailsConfig::default(),
+            changelog: ChangelogConfig::default(),
+            pr_conventions: PrConventionsConfig::default(),
+            id_scheme: IdScheme::Numbered,
+            id_prefix: "WRK".to_string(),
+            identity: None,
+            backup_retention: 10,
+            sqlite_index: false,
+            summarize_transcripts: default_summarize_transcripts(),
+            context_pack: ContextPackConfig::default(),
+            meta: MetaConfig::default(),
+            disk: DiskConfig::default(),
+            gc: GcConfig::default(),
+            cleanup: CleanupConfig::default(),
+            bench: Vec::new(),
+            rate_limit: RateLimitConfig::default(),
+            supervisor: SupervisorConfig::default(),
+            hooks: HooksConfig::default(),
+            forge: ForgeConfig::default(),
+            http: HttpConfig::default(),
+            network: NetworkConfig::default(),
+            pr_bot: PrBotConfig::default(),
+            tui: TuiConfig::default(),
+            presets: PresetsConfig::default(),
+            recurring: Vec::new(),
+            states: Vec::new(),
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Every verify check, with `verify_command` first as "verify"
+    pub fn verify_checks(&self) -> Vec<VerifyCheck> {
+        self.verify_command
+            .iter()
+            .map(|cmd| VerifyCheck::new("verify", cmd.clone()))
+            .chain(self.verify.iter().cloned())
+            .collect()
+    }
+
+    /// The verify checks run after each story (those without a gate)
+    pub fn story_checks(&self) -> Vec<VerifyCheck> {
+        self.verify_checks()
+            .into_iter()
+            .filter(|check| check.gate.is_none())
+            .collect()
+    }
+
+    /// Settings for the named phase (defaults if none are configured)
+    pub fn phase(&self, name: &str) -> PhaseConfig {
+        self.phases.get(name).cloned().unwrap_or_default()
+    }
+
+    /// The agent config for a phase, with its `args_override`, `limits`, and
+    /// `model` applied
+    pub fn phase_agent(&self, name: &str) -> AgentConfig {
+        let phase = self.phase(name);
+        let mut agent = self.agent.clone();
+        if let Some(args) = phase.args_override {
+            agent.args = args;
+        }
+        if let Some(limits) = phase.limits {
+            agent.limits = limits;
+        }
+        match phase.model {
+            Some(ref model) => agent.with_model(model),
+            None => agent,
         }
     }
 }
@@ -135,6 +1888,7 @@ mod tests {
         assert_eq!(config.merge_mode, MergeMode::Pr);
         assert_eq!(config.max_iterations, 100);
         assert_eq!(config.timeout_seconds, 3600);
+        assert_eq!(config.backup_retention, 10);
     }
 
     #[test]
@@ -175,9 +1929,156 @@ mod tests {
         assert_eq!(serde_json::to_string(&MergeMode::Direct).unwrap(), "\"direct\"");
     }
 
+    #[test]
+    fn test_auto_merge_serialization() {
+        let pr: PrConfig = serde_json::from_str(r#"{"auto_merge": "squash"}"#).unwrap();
+        assert_eq!(pr.auto_merge, AutoMerge::Squash);
+        let pr: PrConfig = serde_json::from_str(r#"{"auto_merge": false}"#).unwrap();
+        assert_eq!(pr.auto_merge.method(), None);
+        assert!(serde_json::from_str::<PrConfig>(r#"{"auto_merge": "octopus"}"#).is_err());
+        assert_eq!(serde_json::to_string(&AutoMerge::Off).unwrap(), "false");
+        assert_eq!(serde_json::to_string(&AutoMerge::Rebase).unwrap(), "\"rebase\"");
+    }
+
+    #[test]
+    fn test_tui_notify_config() {
+        assert_eq!(Config::default().tui.notify, NotifyMode::None);
+
+        let json = r#"{"tui": {"notify": "bell"}}"#;
+        let parsed: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.tui.notify, NotifyMode::Bell);
+
+        assert_eq!(serde_json::to_string(&NotifyMode::Desktop).unwrap(), "\"desktop\"");
+    }
+
     #[test]
     fn test_agent_mode_serialization() {
         assert_eq!(serde_json::to_string(&AgentMode::Process).unwrap(), "\"process\"");
         assert_eq!(serde_json::to_string(&AgentMode::Sdk).unwrap(), "\"sdk\"");
+        assert_eq!(serde_json::to_string(&AgentMode::Mock).unwrap(), "\"mock\"");
+    }
+
+    #[test]
+    fn test_env_activation_serialization() {
+        let parsed: Config = serde_json::from_str(r#"{"env_activation": "nix develop"}"#).unwrap();
+        assert_eq!(parsed.env_activation, EnvActivation::NixDevelop);
+        assert_eq!(Config::default().env_activation, EnvActivation::None);
+        assert_eq!(serde_json::to_string(&EnvActivation::Direnv).unwrap(), "\"direnv\"");
+        assert_eq!(serde_json::to_string(&EnvActivation::None).unwrap(), "\"none\"");
+    }
+
+    #[test]
+    fn test_verify_checks() {
+        let json = r#"{
+            "verify_command": "make check",
+            "verify": [
+                {"name": "fmt", "cmd": "cargo fmt --check"},
+                {"name": "test", "cmd": "cargo test"}
+            ]
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let names: Vec<_> = config
+            .verify_checks()
+            .into_iter()
+            .map(|check| check.name)
+            .collect();
+        assert_eq!(names, vec!["verify", "fmt", "test"]);
+
+        let json = r#"{"verify": [
+            {"name": "test", "cmd": "cargo test"},
+            {"name": "coverage", "cmd": "cargo llvm-cov", "gate": {"pattern": "(\\d+)%", "min": 80}}
+        ]}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.verify[1].gate.as_ref().unwrap().min, Some(80.0));
+        assert_eq!(config.story_checks().len(), 1);
+        assert!(Config::default().verify_checks().is_empty());
+    }
+
+    #[test]
+    fn test_id_scheme_config() {
+        assert_eq!(Config::default().id_scheme, IdScheme::Numbered);
+
+        let json = r#"{"id_scheme": "sequential", "id_prefix": "OPS"}"#;
+        let parsed: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.id_scheme, IdScheme::Sequential);
+        assert_eq!(parsed.id_prefix, "OPS");
+        assert_eq!(serde_json::to_string(&IdScheme::Ulid).unwrap(), "\"ulid\"");
+    }
+
+    #[test]
+    fn test_meta_config() {
+        let config = Config::default();
+        assert_eq!(config.meta.mode, MetaMode::Off);
+        assert_eq!(config.meta.branch, "wreckit-meta");
+
+        let json = r#"{"meta": {"mode": "branch", "push": true}}"#;
+        let parsed: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.meta.mode, MetaMode::Branch);
+        assert_eq!(parsed.meta.branch, "wreckit-meta");
+        assert!(parsed.meta.push);
+    }
+
+    #[test]
+    fn test_custom_states_config() {
+        assert!(Config::default().states.is_empty());
+
+        let json = r#"{"states": [{"name": "qa", "artifact": "qa.md"}, {"name": "deployed", "after": "done", "command": "true"}]}"#;
+        let parsed: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.states.len(), 2);
+        assert_eq!(parsed.states[0].after, "in_pr");
+        assert_eq!(parsed.states[0].artifact.as_deref(), Some("qa.md"));
+        assert_eq!(parsed.states[1].after, "done");
+        assert_eq!(parsed.states[1].command.as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn test_phase_agent() {
+        let json = r#"{
+            "agent": {"command": "claude", "args": ["--print", "--model", "opus"], "completion_signal": "DONE", "limits": {"nice": 10}},
+            "phases": {
+                "research": {"model": "haiku"},
+                "pr": {"args_override": ["-p", "--model=sonnet"]},
+                "implement": {"limits": {"max_memory_mb": 8192, "max_cpu_percent": 200}}
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        let research = config.phase_agent("research");
+        assert_eq!(research.args, vec!["--print", "--model", "haiku"]);
+        assert_eq!(research.model(), Some("haiku"));
+        assert_eq!(config.phase_agent("implement").model(), Some("opus"));
+        assert_eq!(config.phase_agent("pr").args, vec!["-p", "--model=sonnet"]);
+        assert_eq!(config.agent.model(), Some("opus"));
+        assert_eq!(research.limits.nice, Some(10));
+        let implement = config.phase_agent("implement").limits;
+        assert_eq!(implement.max_memory_mb, Some(8192));
+        assert_eq!(implement.max_cpu_percent, Some(200));
+        assert_eq!(implement.nice, None);
+    }
+
+    #[test]
+    fn test_evaluation_config() {
+        let config = Config::default();
+        assert!(!config.evaluation.evaluates("research"));
+
+        let json = r#"{"evaluation": {"enabled": true, "phases": ["plan"], "on_low_score": "gate"}}"#;
+        let parsed: Config = serde_json::from_str(json).unwrap();
+        assert!(parsed.evaluation.evaluates("plan"));
+        assert!(!parsed.evaluation.evaluates("research"));
+        assert_eq!(parsed.evaluation.min_score, 3);
+        assert_eq!(parsed.evaluation.on_low_score, LowScoreAction::Gate);
+    }
+
+    #[test]
+    fn test_plan_consensus() {
+        assert!(!Config::default().plan.uses_consensus(Some(PriorityHint::Critical)));
+
+        let json = r#"{"plan": {"consensus": true, "models": ["sonnet", "gpt-5"], "min_priority": "high"}}"#;
+        let parsed: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.plan.models, vec!["sonnet", "gpt-5"]);
+        assert!(parsed.plan.uses_consensus(Some(PriorityHint::High)));
+        assert!(parsed.plan.uses_consensus(Some(PriorityHint::Critical)));
+        assert!(!parsed.plan.uses_consensus(Some(PriorityHint::Medium)));
+        assert!(!parsed.plan.uses_consensus(None));
     }
 }
@@ -1,5 +1,7 @@
 //! Item schema - The main workflow item type
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Workflow state for an item
@@ -18,6 +20,8 @@ pub enum WorkflowState {
     InPr,
     /// Work complete
     Done,
+    /// Work stopped without merging (terminal; outside the linear progression)
+    Abandoned,
 }
 
 impl std::fmt::Display for WorkflowState {
@@ -29,6 +33,7 @@ impl std::fmt::Display for WorkflowState {
             WorkflowState::Implementing => write!(f, "implementing"),
             WorkflowState::InPr => write!(f, "in_pr"),
             WorkflowState::Done => write!(f, "done"),
+            WorkflowState::Abandoned => write!(f, "abandoned"),
         }
     }
 }
@@ -44,13 +49,14 @@ impl std::str::FromStr for WorkflowState {
             "implementing" => Ok(WorkflowState::Implementing),
             "in_pr" => Ok(WorkflowState::InPr),
             "done" => Ok(WorkflowState::Done),
+            "abandoned" => Ok(WorkflowState::Abandoned),
             _ => Err(format!("Unknown workflow state: {}", s)),
         }
     }
 }
 
-/// Priority hint for an item
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Priority hint for an item, ordered from low to critical
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PriorityHint {
     Low,
@@ -59,6 +65,126 @@ pub enum PriorityHint {
     Critical,
 }
 
+/// Why an item is blocked, and the conditions that unblock it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blocker {
+    /// Human-readable reason (e.g., "waiting on API key")
+    pub reason: String,
+
+    /// Unblock once this date (YYYY-MM-DD) is reached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+
+    /// Unblock once this item is done
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_item: Option<String>,
+
+    /// ISO 8601 timestamp when the item was blocked
+    pub blocked_at: String,
+}
+
+impl Blocker {
+    /// Create a blocker with no automatic unblock condition
+    pub fn new(reason: String) -> Self {
+        Blocker {
+            reason,
+            until: None,
+            on_item: None,
+            blocked_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Return a new Blocker that lifts on the given date
+    pub fn with_until(mut self, until: Option<String>) -> Self {
+        self.until = until;
+        self
+    }
+
+    /// Return a new Blocker that lifts once the given item is done
+    pub fn with_on_item(mut self, on_item: Option<String>) -> Self {
+        self.on_item = on_item;
+        self
+    }
+}
+
+/// A recorded move into a workflow state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateChange {
+    /// State entered, custom or built-in (e.g. "implementing")
+    pub state: String,
+
+    /// ISO 8601 timestamp of the change
+    pub at: String,
+}
+
+/// A crash of a detached run's process, recorded by its supervisor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashRecord {
+    /// State the item was in when the process died
+    pub state: String,
+
+    /// How the process ended (e.g. "exit code 101", "signal 9")
+    pub exit: String,
+
+    /// ISO 8601 timestamp of the crash
+    pub at: String,
+}
+
+/// A question the agent asked the operator, pausing the item until answered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Question {
+    /// The question as the agent wrote it
+    pub text: String,
+
+    /// Phase the agent was running when it asked
+    pub phase: String,
+
+    /// ISO 8601 timestamp when the question was asked
+    pub asked_at: String,
+}
+
+impl Question {
+    /// Create a question asked now
+    pub fn new(text: String, phase: String) -> Self {
+        Question {
+            text,
+            phase,
+            asked_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Rubric score the self-evaluation pass gave a phase's artifact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactScore {
+    /// Whether the artifact covers everything the item asks for (1-5)
+    pub completeness: u8,
+
+    /// Whether the approach can be built as described (1-5)
+    pub feasibility: u8,
+
+    /// How well risks and edge cases are identified (1-5)
+    pub risks: u8,
+
+    /// The evaluator's account of the artifact's weaknesses
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub summary: String,
+
+    /// Revision passes made before this score
+    #[serde(default)]
+    pub revisions: u32,
+
+    /// ISO 8601 timestamp of the evaluation
+    pub scored_at: String,
+}
+
+impl ArtifactScore {
+    /// Overall score: the lowest of the three criteria
+    pub fn overall(&self) -> u8 {
+        self.completeness.min(self.feasibility).min(self.risks)
+    }
+}
+
 /// A workflow item representing a feature or task to be implemented
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Item {
@@ -78,6 +204,12 @@ pub struct Item {
     /// Current workflow state
     pub state: WorkflowState,
 
+    /// Config-defined state the item has advanced into, if any.
+    ///
+    /// `state` keeps the built-in state the custom state follows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_state: Option<String>,
+
     /// Overview/description of the item
     pub overview: String,
 
@@ -97,12 +229,68 @@ pub struct Item {
     #[serde(default)]
     pub last_error: Option<String>,
 
+    /// Failure class of the last error (e.g. "auth", "conflict")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error_code: Option<String>,
+
+    /// Who is driving this item (e.g. a git user.email)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+
+    /// ID of the completed item this one follows up on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_up_of: Option<String>,
+
+    /// Name of the recurring item template this item was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence_of: Option<String>,
+
+    /// Name of the preset the item was created from (e.g. "dependency_update")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+
+    /// Number of the GitHub issue the item was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_number: Option<u32>,
+
+    /// URL of the GitHub issue the item was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_url: Option<String>,
+
+    /// Set while the item is blocked; independent of the workflow state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked: Option<Blocker>,
+
+    /// Variables for this item's agent and verify runs, over `agent.env`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
+    /// Free-form labels (e.g. "stale"), in the order they were added
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
     /// ISO 8601 creation timestamp
     pub created_at: String,
 
     /// ISO 8601 last update timestamp
     pub updated_at: String,
 
+    /// States entered after creation, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_history: Vec<StateChange>,
+
+    /// Process crashes during detached runs, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub crashes: Vec<CrashRecord>,
+
+    /// Self-evaluation scores of phase artifacts, by phase
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scores: BTreeMap<String, ArtifactScore>,
+
+    /// Set while the agent is waiting on an answer from the operator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question: Option<Question>,
+
     // Structured context fields for richer research/planning
 
     /// Problem statement for context
@@ -148,13 +336,28 @@ impl Item {
             title,
             section: None,
             state: WorkflowState::Idea,
+            custom_state: None,
             overview,
             branch: None,
             pr_url: None,
             pr_number: None,
             last_error: None,
+            last_error_code: None,
+            assignee: None,
+            follow_up_of: None,
+            recurrence_of: None,
+            preset: None,
+            issue_number: None,
+            issue_url: None,
+            blocked: None,
+            env: BTreeMap::new(),
+            tags: Vec::new(),
             created_at: now.clone(),
             updated_at: now,
+            state_history: Vec::new(),
+            crashes: Vec::new(),
+            scores: BTreeMap::new(),
+            question: None,
             problem_statement: None,
             motivation: None,
             success_criteria: None,
@@ -168,12 +371,48 @@ impl Item {
 
     // ===== IMMUTABLE BUILDER METHODS =====
 
-    /// Return a new Item with the given state, updating the timestamp
+    /// Return a new Item with the given state, updating the timestamp.
+    ///
+    /// Leaves any custom state.
     pub fn with_state(mut self, state: WorkflowState) -> Self {
+        let previous = self.state_name();
         self.state = state;
+        self.custom_state = None;
+        self.touch_returning().recording_state_change(previous)
+    }
+
+    /// Return a new Item in the given custom state, updating the timestamp
+    pub fn with_custom_state(mut self, custom_state: Option<String>) -> Self {
+        let previous = self.state_name();
+        self.custom_state = custom_state;
+        self.touch_returning().recording_state_change(previous)
+    }
+
+    /// Return a new Item with a process crash recorded in its current state
+    pub fn with_crash(mut self, exit: String) -> Self {
+        self.crashes.push(CrashRecord {
+            state: self.state_name(),
+            exit,
+            at: chrono::Utc::now().to_rfc3339(),
+        });
         self.touch_returning()
     }
 
+    /// When the item entered its current state (its creation time if never moved)
+    pub fn entered_state_at(&self) -> &str {
+        self.state_history
+            .last()
+            .map(|change| change.at.as_str())
+            .unwrap_or(&self.created_at)
+    }
+
+    /// Name of the state the item is in, custom or built-in
+    pub fn state_name(&self) -> String {
+        self.custom_state
+            .clone()
+            .unwrap_or_else(|| self.state.to_string())
+    }
+
     /// Return a new Item with the given branch, updating the timestamp
     pub fn with_branch(mut self, branch: Option<String>) -> Self {
         self.branch = branch;
@@ -187,12 +426,67 @@ impl Item {
         self.touch_returning()
     }
 
-    /// Return a new Item with the given error message, updating the timestamp
+    /// Return a new Item with the given error message, updating the timestamp.
+    ///
+    /// Clearing the error also clears its code.
     pub fn with_error(mut self, error: Option<String>) -> Self {
+        if error.is_none() {
+            self.last_error_code = None;
+        }
         self.last_error = error;
         self.touch_returning()
     }
 
+    /// Return a new Item with the given error code, updating the timestamp
+    pub fn with_error_code(mut self, code: Option<String>) -> Self {
+        self.last_error_code = code;
+        self.touch_returning()
+    }
+
+    /// Return a new Item with the given assignee, updating the timestamp
+    pub fn with_assignee(mut self, assignee: Option<String>) -> Self {
+        self.assignee = assignee;
+        self.touch_returning()
+    }
+
+    /// Return a new Item with the given blocker, updating the timestamp
+    pub fn with_blocked(mut self, blocked: Option<Blocker>) -> Self {
+        self.blocked = blocked;
+        self.touch_returning()
+    }
+
+    /// Return a new Item with the tag added (if it is not already there),
+    /// updating the timestamp
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        if self.tags.iter().any(|t| t == tag) {
+            return self;
+        }
+        self.tags.push(tag.to_string());
+        self.touch_returning()
+    }
+
+    /// Return a new Item with a phase's artifact score recorded
+    pub fn with_score(mut self, phase: &str, score: ArtifactScore) -> Self {
+        self.scores.insert(phase.to_string(), score);
+        self.touch_returning()
+    }
+
+    /// Whether the item is currently blocked
+    pub fn is_blocked(&self) -> bool {
+        self.blocked.is_some()
+    }
+
+    /// Return a new Item with the given open question, updating the timestamp
+    pub fn with_question(mut self, question: Option<Question>) -> Self {
+        self.question = question;
+        self.touch_returning()
+    }
+
+    /// Whether the agent asked a question that has not been answered
+    pub fn is_awaiting_input(&self) -> bool {
+        self.question.is_some()
+    }
+
     /// Return a new Item with updated_at set to now
     pub fn with_updated_timestamp(self) -> Self {
         self.touch_returning()
@@ -206,6 +500,18 @@ impl Item {
         self
     }
 
+    /// Append a history entry if the state name differs from `previous`
+    fn recording_state_change(mut self, previous: String) -> Self {
+        let state = self.state_name();
+        if state != previous {
+            self.state_history.push(StateChange {
+                state,
+                at: self.updated_at.clone(),
+            });
+        }
+        self
+    }
+
     // ===== EXISTING METHOD (NOW DEPRECATED) =====
 
     /// Update the updated_at timestamp to now
@@ -229,6 +535,7 @@ mod tests {
         assert_eq!(serde_json::to_string(&WorkflowState::Implementing).unwrap(), "\"implementing\"");
         assert_eq!(serde_json::to_string(&WorkflowState::InPr).unwrap(), "\"in_pr\"");
         assert_eq!(serde_json::to_string(&WorkflowState::Done).unwrap(), "\"done\"");
+        assert_eq!(serde_json::to_string(&WorkflowState::Abandoned).unwrap(), "\"abandoned\"");
     }
 
     #[test]
@@ -258,6 +565,21 @@ mod tests {
         assert_eq!(parsed.state, WorkflowState::Idea);
     }
 
+    #[test]
+    fn test_item_custom_state() {
+        let item = Item::new("test-001".to_string(), "Test".to_string(), String::new())
+            .with_state(WorkflowState::InPr);
+        assert_eq!(item.state_name(), "in_pr");
+        assert!(!serde_json::to_string(&item).unwrap().contains("custom_state"));
+
+        let item = item.with_custom_state(Some("qa".to_string()));
+        assert_eq!(item.state_name(), "qa");
+        let parsed: Item = serde_json::from_str(&serde_json::to_string(&item).unwrap()).unwrap();
+        assert_eq!(parsed.custom_state.as_deref(), Some("qa"));
+
+        assert_eq!(item.with_state(WorkflowState::Done).custom_state, None);
+    }
+
     #[test]
     fn test_item_with_optional_fields() {
         let mut item = Item::new(
@@ -316,6 +638,20 @@ mod tests {
         assert!(updated.updated_at > item.updated_at);
     }
 
+    #[test]
+    fn test_state_history_records_changes() {
+        let item = Item::new("test-001".to_string(), "Test".to_string(), String::new());
+        assert_eq!(item.entered_state_at(), item.created_at);
+
+        let item = item
+            .with_state(WorkflowState::Researched)
+            .with_state(WorkflowState::Researched)
+            .with_custom_state(Some("qa".to_string()));
+        let states: Vec<&str> = item.state_history.iter().map(|c| c.state.as_str()).collect();
+        assert_eq!(states, vec!["researched", "qa"]);
+        assert_eq!(item.entered_state_at(), item.state_history[1].at);
+    }
+
     #[test]
     fn test_item_with_branch() {
         let item = Item::new(
@@ -7,7 +7,19 @@ mod index;
 mod item;
 mod prd;
 
-pub use config::{AgentConfig, AgentMode, Config, MergeMode};
+pub use config::{
+    AgentConfig, AgentMode, AutoMerge, BenchVariant, ChangeType, ChangelogConfig, ChangelogFormat,
+    CleanupConfig, Config, ContextPackConfig, CustomStateConfig, DependencyUpdateConfig, DiffPolicy,
+    DiskConfig, EnvActivation, EvaluationConfig, Execution, FlakyTestsConfig, ForgeConfig, GcConfig,
+    GitHubAuth, GitHubConfig, GuardrailsConfig, HooksConfig, HttpConfig, IdScheme, ImplementConfig,
+    KeymapConfig, KeymapPreset, LicenseHeader, LowScoreAction, MergeMode, MetaConfig, MetaMode,
+    MetricGate, NetworkConfig, NotifyMode, OfflineAction, PhaseConfig, PlanConfig, PrBotConfig,
+    PrConfig, PrConventionsConfig, PrSizeAction, PrSizeConfig, Preset, PresetsConfig,
+    PromptSelection, ProtectedPathAction, RateLimitConfig, RecurringItemConfig, RequireChecksConfig,
+    ResourceLimits, SecurityScan, SupervisorConfig, TuiConfig, VerificationConfig, VerifyCheck,
+};
 pub use index::{Index, IndexItem};
-pub use item::{Item, PriorityHint, WorkflowState};
+pub use item::{
+    ArtifactScore, Blocker, CrashRecord, Item, PriorityHint, Question, StateChange, WorkflowState,
+};
 pub use prd::{Prd, Story, StoryStatus};
@@ -38,6 +38,11 @@ pub struct Story {
 
     /// Additional notes
     pub notes: String,
+
+    /// Command that must also pass before the story is done, run with the
+    /// configured verify checks (e.g. the story's target test, repeated)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify: Option<String>,
 }
 
 impl Story {
@@ -50,6 +55,7 @@ impl Story {
             priority,
             status: StoryStatus::Pending,
             notes: String::new(),
+            verify: None,
         }
     }
 
@@ -67,6 +73,12 @@ impl Story {
         self
     }
 
+    /// Return a new Story with the given extra verify command
+    pub fn with_verify(mut self, verify: Option<String>) -> Self {
+        self.verify = verify;
+        self
+    }
+
     /// Return a new Story marked as done
     pub fn as_done(self) -> Self {
         self.with_status(StoryStatus::Done)
@@ -0,0 +1,171 @@
+//! Secret storage
+//!
+//! Forge tokens and API keys can be kept in the OS keychain (the macOS
+//! Keychain, Windows Credential Manager, or the Linux kernel keyring) rather
+//! than in config files or shell history. `wreckit auth set <name>` stores a
+//! secret under the `wreckit` service; the GitHub API client falls back to
+//! the `github` secret, and an `env` value of `keychain:<name>` passes one to
+//! the agent. Lookups go through a [`SecretStore`] so tests and embedders can
+//! swap the keychain for another backend.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::errors::{Result, WreckitError};
+
+/// Keychain service the secrets are stored under
+pub const KEYCHAIN_SERVICE: &str = "wreckit";
+
+/// Prefix of an `env` value read from the secret store
+pub const KEYCHAIN_REF_PREFIX: &str = "keychain:";
+
+/// A place secrets are stored by name
+pub trait SecretStore: Send + Sync {
+    /// The secret stored under `name`, if any.
+    ///
+    /// # Errors
+    /// * `Wrapped` - If the store cannot be read
+    fn get(&self, name: &str) -> Result<Option<String>>;
+
+    /// Store a secret, replacing any previous value.
+    ///
+    /// # Errors
+    /// * `Wrapped` - If the store cannot be written
+    fn set(&self, name: &str, value: &str) -> Result<()>;
+
+    /// Remove a secret, returning whether one was stored.
+    ///
+    /// # Errors
+    /// * `Wrapped` - If the store cannot be written
+    fn delete(&self, name: &str) -> Result<bool>;
+}
+
+/// The OS keychain
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KeychainStore;
+
+fn keychain_error(action: &str, name: &str, e: keyring::Error) -> WreckitError {
+    WreckitError::wrap(e, format!("Cannot {} '{}' in the keychain", action, name))
+}
+
+impl KeychainStore {
+    fn entry(name: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, name).map_err(|e| keychain_error("open", name, e))
+    }
+}
+
+impl SecretStore for KeychainStore {
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        match Self::entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(keychain_error("read", name, e)),
+        }
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<()> {
+        Self::entry(name)?
+            .set_password(value)
+            .map_err(|e| keychain_error("store", name, e))
+    }
+
+    fn delete(&self, name: &str) -> Result<bool> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(keychain_error("remove", name, e)),
+        }
+    }
+}
+
+/// Secrets held in memory, for tests
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    secrets: Mutex<BTreeMap<String, String>>,
+}
+
+impl MemoryStore {
+    fn secrets(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, String>> {
+        self.secrets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SecretStore for MemoryStore {
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        Ok(self.secrets().get(name).cloned())
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<()> {
+        self.secrets().insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn delete(&self, name: &str) -> Result<bool> {
+        Ok(self.secrets().remove(name).is_some())
+    }
+}
+
+static STORE: RwLock<Option<Arc<dyn SecretStore>>> = RwLock::new(None);
+
+/// Use another store for this process instead of the OS keychain
+pub fn set_secret_store(store: Arc<dyn SecretStore>) {
+    *STORE.write().unwrap_or_else(|e| e.into_inner()) = Some(store);
+}
+
+/// The store secrets are read from and written to
+pub fn secret_store() -> Arc<dyn SecretStore> {
+    match *STORE.read().unwrap_or_else(|e| e.into_inner()) {
+        Some(ref store) => store.clone(),
+        None => Arc::new(KeychainStore),
+    }
+}
+
+/// Check a secret name: non-empty, without whitespace.
+///
+/// # Errors
+/// * `ConfigError` - If the name is unusable
+pub fn validate_secret_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.chars().any(char::is_whitespace) {
+        return Err(WreckitError::ConfigError(format!(
+            "invalid secret name '{}': use a single word such as github",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// The secret stored under `name`, treating a store that cannot be read as
+/// empty (the failure is logged)
+pub fn lookup_secret(name: &str) -> Option<String> {
+    match secret_store().get(name) {
+        Ok(value) => value.filter(|v| !v.trim().is_empty()),
+        Err(e) => {
+            tracing::debug!("{}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemoryStore::default();
+        assert_eq!(store.get("github").unwrap(), None);
+        store.set("github", "ghp_one").unwrap();
+        store.set("github", "ghp_two").unwrap();
+        assert_eq!(store.get("github").unwrap().as_deref(), Some("ghp_two"));
+        assert!(store.delete("github").unwrap());
+        assert!(!store.delete("github").unwrap());
+    }
+
+    #[test]
+    fn test_validate_secret_name() {
+        assert!(validate_secret_name("github").is_ok());
+        assert!(validate_secret_name("linear-api").is_ok());
+        assert!(validate_secret_name("").is_err());
+        assert!(validate_secret_name("my token").is_err());
+    }
+}
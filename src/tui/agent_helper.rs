@@ -67,6 +67,8 @@ mod tests {
             on_stdout: None,
             on_stderr: None,
             on_tui_event: None,
+            cancel: None,
+            env_activation: Default::default(),
         };
 
         let result = run_agent_with_tui(options, "test-item".to_string(), tui_tx.clone()).await.unwrap();
@@ -93,6 +95,11 @@ mod tests {
                     "<assistant_text>Thinking about the problem</assistant_text>".to_string()
                 ],
                 completion_signal: "Thinking".to_string(),
+                completion_grace_seconds: 30,
+                fixtures_dir: None,
+                env: Default::default(),
+                execution: Default::default(),
+                limits: Default::default(),
             },
             cwd: std::path::PathBuf::from("."),
             prompt: String::new(),
@@ -101,6 +108,8 @@ mod tests {
             on_stdout: None,
             on_stderr: None,
             on_tui_event: None,
+            cancel: None,
+            env_activation: Default::default(),
         };
 
         // Spawn a task to collect TUI updates
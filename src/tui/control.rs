@@ -0,0 +1,207 @@
+//! Operator controls for the running workflow
+//!
+//! The TUI sends [`ControlCommand`]s through a [`ControlSender`]; the workflow
+//! loop observes them through a [`ControlHandle`]. Pause takes effect between
+//! iterations, while cancel trips the current item's [`CancellationToken`].
+//! The agent runner, git and gh commands, and poll waits all watch that one
+//! token, so a single cancel (the TUI's cancel key or SIGINT) stops the agent
+//! child process, any in-flight command, and the phase driving them.
+
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+pub use tokio_util::sync::CancellationToken;
+
+/// Commands an operator can issue while a run is in progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Pause after the current iteration finishes
+    Pause,
+    /// Resume a paused run
+    Resume,
+    /// Gracefully terminate the current item's agent
+    CancelCurrent,
+}
+
+/// Sending half of the control channel (held by the TUI)
+#[derive(Debug, Clone)]
+pub struct ControlSender {
+    pause_tx: Arc<watch::Sender<bool>>,
+    cancel: Arc<Mutex<CancellationToken>>,
+}
+
+/// Receiving half of the control channel (held by the workflow loop)
+#[derive(Debug, Clone)]
+pub struct ControlHandle {
+    pause_rx: watch::Receiver<bool>,
+    cancel: Arc<Mutex<CancellationToken>>,
+}
+
+/// Create a connected control sender and handle
+pub fn control_channel() -> (ControlSender, ControlHandle) {
+    let (pause_tx, pause_rx) = watch::channel(false);
+    let cancel = Arc::new(Mutex::new(CancellationToken::new()));
+
+    (
+        ControlSender {
+            pause_tx: Arc::new(pause_tx),
+            cancel: cancel.clone(),
+        },
+        ControlHandle { pause_rx, cancel },
+    )
+}
+
+fn current_token(cancel: &Mutex<CancellationToken>) -> CancellationToken {
+    cancel.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+impl ControlSender {
+    /// Deliver a command to the workflow loop
+    pub fn send(&self, command: ControlCommand) {
+        match command {
+            ControlCommand::Pause => {
+                let _ = self.pause_tx.send(true);
+            }
+            ControlCommand::Resume => {
+                let _ = self.pause_tx.send(false);
+            }
+            ControlCommand::CancelCurrent => current_token(&self.cancel).cancel(),
+        }
+    }
+
+    /// Check whether a pause is currently requested
+    pub fn is_paused(&self) -> bool {
+        *self.pause_tx.borrow()
+    }
+}
+
+impl ControlHandle {
+    /// Check whether the operator has asked to pause
+    pub fn is_paused(&self) -> bool {
+        *self.pause_rx.borrow()
+    }
+
+    /// Check whether the operator has asked to cancel the current item
+    pub fn is_cancel_requested(&self) -> bool {
+        current_token(&self.cancel).is_cancelled()
+    }
+
+    /// Clear a pending cancel request once it has been handled, so the next
+    /// item starts with a fresh token
+    pub fn acknowledge_cancel(&self) {
+        let mut token = self.cancel.lock().unwrap_or_else(|e| e.into_inner());
+        if token.is_cancelled() {
+            *token = CancellationToken::new();
+        }
+    }
+
+    /// Token for the current item, tripped when the operator cancels it
+    pub fn cancel_token(&self) -> CancellationToken {
+        current_token(&self.cancel)
+    }
+
+    /// Wait until the run is no longer paused.
+    ///
+    /// Returns immediately when not paused or when the sender has been dropped.
+    pub async fn wait_while_paused(&mut self) {
+        while *self.pause_rx.borrow_and_update() {
+            if self.pause_rx.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Resolve once `token` is cancelled; never resolves without a token
+pub async fn cancelled(token: Option<&CancellationToken>) {
+    match token {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending::<()>().await,
+    }
+}
+
+/// Treat SIGINT as a cancel: pause so no further item starts and cancel the
+/// current one. A second SIGINT exits at once.
+pub fn cancel_on_interrupt(sender: ControlSender) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        tracing::warn!("Interrupted; cancelling the current item (press Ctrl-C again to exit)");
+        sender.send(ControlCommand::Pause);
+        sender.send(ControlCommand::CancelCurrent);
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(130);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pause_and_resume() {
+        let (sender, handle) = control_channel();
+        assert!(!handle.is_paused());
+
+        sender.send(ControlCommand::Pause);
+        assert!(handle.is_paused());
+        assert!(sender.is_paused());
+
+        sender.send(ControlCommand::Resume);
+        assert!(!handle.is_paused());
+    }
+
+    #[test]
+    fn test_cancel_and_acknowledge() {
+        let (sender, handle) = control_channel();
+        assert!(!handle.is_cancel_requested());
+
+        let token = handle.cancel_token();
+        sender.send(ControlCommand::CancelCurrent);
+        assert!(handle.is_cancel_requested());
+        assert!(token.is_cancelled());
+
+        handle.acknowledge_cancel();
+        assert!(!handle.is_cancel_requested());
+        assert!(!handle.cancel_token().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_wait_while_paused_resumes() {
+        let (sender, mut handle) = control_channel();
+        sender.send(ControlCommand::Pause);
+
+        let waiter = tokio::spawn(async move {
+            handle.wait_while_paused().await;
+        });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        sender.send(ControlCommand::Resume);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should finish after resume")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_resolves_on_cancel() {
+        let (sender, handle) = control_channel();
+        let token = handle.cancel_token();
+
+        let waiter = tokio::spawn(async move {
+            cancelled(Some(&token)).await;
+        });
+
+        sender.send(ControlCommand::CancelCurrent);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("cancelled should resolve")
+            .unwrap();
+    }
+}
@@ -0,0 +1,222 @@
+//! Update stream of a detached run
+//!
+//! `wreckit run <id> --detach` starts a supervisor in a background process,
+//! which runs the item with `WRECKIT_EVENT_LOG` pointing at an event file
+//! and restarts it after a crash. Each attempt appends every TUI update to
+//! that file as a JSON line; the supervisor ends the file with `RunFinished`
+//! once it stops restarting. `wreckit attach` reads the file from the start
+//! and keeps following it, so the TUI or plain renderer it drives shows the
+//! whole run so far and then the live stream.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::tui::runner::TuiUpdate;
+
+/// Environment variable naming the event file a detached run writes
+pub const EVENT_LOG_ENV: &str = "WRECKIT_EVENT_LOG";
+
+/// Environment variable set for attempts started by a supervisor
+pub const SUPERVISED_ENV: &str = "WRECKIT_SUPERVISED";
+
+/// The event file this process should write, if it is a detached run
+pub fn event_log_from_env() -> Option<PathBuf> {
+    std::env::var_os(EVENT_LOG_ENV).map(PathBuf::from)
+}
+
+/// Whether this process is an attempt whose supervisor closes the event file
+pub fn supervised_from_env() -> bool {
+    std::env::var_os(SUPERVISED_ENV).is_some()
+}
+
+fn write_event<W: Write>(out: &mut W, update: &TuiUpdate) -> Result<()> {
+    let line =
+        serde_json::to_string(update).map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+    writeln!(out, "{}", line)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Append one update to an event file.
+///
+/// # Errors
+/// * `Io` - If the file cannot be written
+pub fn append_event(path: &Path, update: &TuiUpdate) -> Result<()> {
+    write_event(&mut fs::append_file(path)?, update)
+}
+
+/// Append a run's updates to the event file until the run finishes or the
+/// stream closes. Unless `supervised`, the file is ended with `RunFinished`
+/// either way.
+pub fn record_events(
+    path: PathBuf,
+    mut updates: broadcast::Receiver<TuiUpdate>,
+    supervised: bool,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let file = match fs::append_file(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!("Cannot write events to {}: {}", path.display(), e);
+                return;
+            }
+        };
+        let mut out = BufWriter::new(file);
+        loop {
+            let update = match updates.recv().await {
+                Ok(update) => update,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Event log skipped {} updates", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) if supervised => return,
+                Err(RecvError::Closed) => TuiUpdate::RunFinished,
+            };
+            let finished = matches!(update, TuiUpdate::RunFinished);
+            if let Err(e) = write_event(&mut out, &update) {
+                tracing::warn!("Cannot write events to {}: {}", path.display(), e);
+                return;
+            }
+            if finished {
+                return;
+            }
+        }
+    })
+}
+
+/// Reads updates from an event file as they are appended
+pub struct EventReader {
+    reader: BufReader<File>,
+    partial: String,
+    finished: bool,
+}
+
+impl EventReader {
+    /// Open an event file from the start.
+    ///
+    /// # Errors
+    /// * `FileNotFound` - If the file does not exist
+    /// * `Io` - If the file cannot be opened
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                WreckitError::FileNotFound(format!("No event file at {}", path.display()))
+            } else {
+                WreckitError::Io(e)
+            }
+        })?;
+        Ok(Self {
+            reader: BufReader::new(file),
+            partial: String::new(),
+            finished: false,
+        })
+    }
+
+    /// Whether the run's final `RunFinished` has been read
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Read every complete update appended since the last call.
+    ///
+    /// A line still being written is kept until its newline arrives.
+    ///
+    /// # Errors
+    /// * `InvalidJson` - If a line is not an update
+    /// * `Io` - If the file cannot be read
+    pub fn read_new(&mut self) -> Result<Vec<TuiUpdate>> {
+        let mut updates = Vec::new();
+        while self.reader.read_line(&mut self.partial)? > 0 {
+            if !self.partial.ends_with('\n') {
+                break;
+            }
+            let line = std::mem::take(&mut self.partial);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let update: TuiUpdate = serde_json::from_str(&line)
+                .map_err(|e| WreckitError::InvalidJson(format!("Invalid event: {}", e)))?;
+            self.finished |= matches!(update, TuiUpdate::RunFinished);
+            updates.push(update);
+        }
+        Ok(updates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::events::AgentEvent;
+
+    #[tokio::test]
+    async fn test_recorded_events_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("001-a.events.jsonl");
+        let (tx, rx) = broadcast::channel(16);
+        let recorder = record_events(path.clone(), rx, false);
+        tx.send(TuiUpdate::SetCurrentItem(Some("001-a".to_string())))
+            .unwrap();
+        tx.send(TuiUpdate::AgentEvent(
+            "001-a".to_string(),
+            AgentEvent::AssistantText {
+                text: "Reading the code".to_string(),
+            },
+        ))
+        .unwrap();
+        drop(tx);
+        recorder.await.unwrap();
+
+        let mut reader = EventReader::open(&path).unwrap();
+        let updates = reader.read_new().unwrap();
+        assert_eq!(updates.len(), 3);
+        assert!(matches!(&updates[0], TuiUpdate::SetCurrentItem(Some(id)) if id == "001-a"));
+        assert!(matches!(updates[2], TuiUpdate::RunFinished));
+        assert!(reader.finished());
+        assert!(reader.read_new().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_supervised_attempts_leave_the_file_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("001-a.events.jsonl");
+        for iteration in 1..=2 {
+            let (tx, rx) = broadcast::channel(16);
+            let recorder = record_events(path.clone(), rx, true);
+            tx.send(TuiUpdate::SetIteration(iteration)).unwrap();
+            drop(tx);
+            recorder.await.unwrap();
+        }
+
+        let mut reader = EventReader::open(&path).unwrap();
+        assert_eq!(reader.read_new().unwrap().len(), 2);
+        assert!(!reader.finished());
+
+        append_event(&path, &TuiUpdate::RunFinished).unwrap();
+        assert_eq!(reader.read_new().unwrap().len(), 1);
+        assert!(reader.finished());
+    }
+
+    #[test]
+    fn test_reader_waits_for_complete_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let mut file = File::create(&path).unwrap();
+        let mut reader = EventReader::open(&path).unwrap();
+
+        write!(file, "{{\"SetIteration\":").unwrap();
+        file.flush().unwrap();
+        assert!(reader.read_new().unwrap().is_empty());
+
+        writeln!(file, "2}}").unwrap();
+        file.flush().unwrap();
+        let updates = reader.read_new().unwrap();
+        assert!(matches!(updates[..], [TuiUpdate::SetIteration(2)]));
+        assert!(!reader.finished());
+    }
+}
@@ -0,0 +1,418 @@
+//! Key bindings for the interactive TUI
+//!
+//! Bindings start from a preset (`vi`, the default, or `emacs`) and
+//! `tui.keymap.bindings` replaces the keys of individual actions, e.g.
+//! `{"quit": ["ctrl-q"]}`. Keys are written as a single character (`q`, `G`,
+//! `/`), a named key (`up`, `down`, `left`, `right`, `pageup`, `pagedown`,
+//! `home`, `end`, `enter`, `esc`, `tab`, `backspace`, `space`), optionally
+//! prefixed with `ctrl-` and/or `alt-`. The keymap is validated when the TUI
+//! starts, so a typo or a key bound to two actions fails fast. Ctrl-C always
+//! quits.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::schemas::{KeymapConfig, KeymapPreset};
+
+/// Something a key can do in the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    ToggleLogs,
+    ToggleTimeline,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    ScrollTop,
+    ScrollBottom,
+    Search,
+    NextMatch,
+    PrevMatch,
+    CycleFilter,
+    Pause,
+    CancelItem,
+    Help,
+}
+
+impl Action {
+    /// Every action, in the order they are listed
+    pub const ALL: [Action; 16] = [
+        Action::Quit,
+        Action::ToggleLogs,
+        Action::ToggleTimeline,
+        Action::ScrollDown,
+        Action::ScrollUp,
+        Action::PageDown,
+        Action::PageUp,
+        Action::ScrollTop,
+        Action::ScrollBottom,
+        Action::Search,
+        Action::NextMatch,
+        Action::PrevMatch,
+        Action::CycleFilter,
+        Action::Pause,
+        Action::CancelItem,
+        Action::Help,
+    ];
+
+    /// Name used in `tui.keymap.bindings`
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::ToggleLogs => "toggle_logs",
+            Action::ToggleTimeline => "toggle_timeline",
+            Action::ScrollDown => "scroll_down",
+            Action::ScrollUp => "scroll_up",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::ScrollTop => "scroll_top",
+            Action::ScrollBottom => "scroll_bottom",
+            Action::Search => "search",
+            Action::NextMatch => "next_match",
+            Action::PrevMatch => "prev_match",
+            Action::CycleFilter => "cycle_filter",
+            Action::Pause => "pause",
+            Action::CancelItem => "cancel_item",
+            Action::Help => "help",
+        }
+    }
+
+    /// What the action does, for the help overlay
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit (the run pauses and the current agent is cancelled)",
+            Action::ToggleLogs => "Show or hide the agent output",
+            Action::ToggleTimeline => "Show or hide the state timeline",
+            Action::ScrollDown => "Scroll the output down a line",
+            Action::ScrollUp => "Scroll the output up a line",
+            Action::PageDown => "Scroll the output down a page",
+            Action::PageUp => "Scroll the output up a page",
+            Action::ScrollTop => "Jump to the oldest output",
+            Action::ScrollBottom => "Jump to the newest output and follow it",
+            Action::Search => "Search the output",
+            Action::NextMatch => "Next search match",
+            Action::PrevMatch => "Previous search match",
+            Action::CycleFilter => "Cycle the output filter",
+            Action::Pause => "Pause or resume after the current iteration",
+            Action::CancelItem => "Cancel the current item",
+            Action::Help => "Show or hide this help",
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == s)
+            .ok_or_else(|| format!("unknown action '{}'", s))
+    }
+}
+
+/// A key with its modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeySpec {
+    pub code: KeyCode,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+const NAMED_KEYS: [(&str, KeyCode); 13] = [
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backspace", KeyCode::Backspace),
+    ("space", KeyCode::Char(' ')),
+];
+
+impl KeySpec {
+    /// Whether a key press is this key. Shift is part of the character
+    /// (`G` vs `g`), so only ctrl and alt are compared.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let code = match event.code {
+            // Terminals differ on whether Ctrl+letter arrives upper case
+            KeyCode::Char(c) if self.ctrl => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        code == self.code
+            && event.modifiers.contains(KeyModifiers::CONTROL) == self.ctrl
+            && event.modifiers.contains(KeyModifiers::ALT) == self.alt
+    }
+}
+
+impl FromStr for KeySpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rest = s;
+        let (mut ctrl, mut alt) = (false, false);
+        loop {
+            if let Some(stripped) = rest.strip_prefix("ctrl-").filter(|r| !r.is_empty()) {
+                ctrl = true;
+                rest = stripped;
+            } else if let Some(stripped) = rest.strip_prefix("alt-").filter(|r| !r.is_empty()) {
+                alt = true;
+                rest = stripped;
+            } else {
+                break;
+            }
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if ctrl => KeyCode::Char(c.to_ascii_lowercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => NAMED_KEYS
+                .iter()
+                .find(|(name, _)| *name == rest)
+                .map(|(_, code)| *code)
+                .ok_or_else(|| format!("unknown key '{}'", s))?,
+        };
+        Ok(KeySpec { code, ctrl, alt })
+    }
+}
+
+impl fmt::Display for KeySpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "ctrl-")?;
+        }
+        if self.alt {
+            write!(f, "alt-")?;
+        }
+        match NAMED_KEYS.iter().find(|(_, code)| *code == self.code) {
+            Some((name, _)) => write!(f, "{}", name),
+            None => match self.code {
+                KeyCode::Char(c) => write!(f, "{}", c),
+                other => write!(f, "{:?}", other),
+            },
+        }
+    }
+}
+
+/// Keys for every action in a preset
+fn preset_bindings(preset: KeymapPreset) -> Vec<(Action, &'static [&'static str])> {
+    let movement: [(Action, &'static [&'static str]); 7] = match preset {
+        KeymapPreset::Vi => [
+            (Action::ScrollDown, &["j", "down"]),
+            (Action::ScrollUp, &["k", "up"]),
+            (Action::PageDown, &["ctrl-d", "pagedown"]),
+            (Action::PageUp, &["ctrl-u", "pageup"]),
+            (Action::ScrollTop, &["g", "home"]),
+            (Action::ScrollBottom, &["G", "end"]),
+            (Action::Search, &["/"]),
+        ],
+        KeymapPreset::Emacs => [
+            (Action::ScrollDown, &["ctrl-n", "down"]),
+            (Action::ScrollUp, &["ctrl-p", "up"]),
+            (Action::PageDown, &["ctrl-v", "pagedown"]),
+            (Action::PageUp, &["alt-v", "pageup"]),
+            (Action::ScrollTop, &["alt-<", "home"]),
+            (Action::ScrollBottom, &["alt->", "end"]),
+            (Action::Search, &["ctrl-s", "/"]),
+        ],
+    };
+    let mut bindings = vec![
+        (Action::Quit, &["q"] as &'static [&'static str]),
+        (Action::ToggleLogs, &["l"]),
+        (Action::ToggleTimeline, &["t"]),
+    ];
+    bindings.extend(movement);
+    bindings.extend([
+        (Action::NextMatch, &["n"] as &'static [&'static str]),
+        (Action::PrevMatch, &["N"]),
+        (Action::CycleFilter, &["f"]),
+        (Action::Pause, &["p"]),
+        (Action::CancelItem, &["x"]),
+        (Action::Help, &["?"]),
+    ]);
+    bindings
+}
+
+/// The active key bindings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeySpec>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::from_config(&KeymapConfig::default()).expect("presets are valid")
+    }
+}
+
+impl Keymap {
+    /// Build and validate the keymap for a config.
+    ///
+    /// Fails on an unknown action or key, or a key bound to two actions.
+    pub fn from_config(config: &KeymapConfig) -> Result<Self, String> {
+        let mut bindings: Vec<(Action, Vec<KeySpec>)> = preset_bindings(config.preset)
+            .into_iter()
+            .map(|(action, keys)| {
+                let keys = keys
+                    .iter()
+                    .map(|key| key.parse())
+                    .collect::<Result<_, _>>()?;
+                Ok((action, keys))
+            })
+            .collect::<Result<_, String>>()?;
+
+        for (name, keys) in &config.bindings {
+            let action: Action = name.parse()?;
+            let keys = keys
+                .iter()
+                .map(|key| key.parse())
+                .collect::<Result<Vec<KeySpec>, _>>()
+                .map_err(|e| format!("{} for {}", e, name))?;
+            if let Some(binding) = bindings.iter_mut().find(|(a, _)| *a == action) {
+                binding.1 = keys;
+            }
+        }
+
+        for (i, (action, keys)) in bindings.iter().enumerate() {
+            for key in keys {
+                let other = bindings[i + 1..]
+                    .iter()
+                    .find(|(_, other_keys)| other_keys.contains(key));
+                if let Some((other, _)) = other {
+                    return Err(format!(
+                        "key '{}' is bound to both {} and {}",
+                        key,
+                        action.name(),
+                        other.name()
+                    ));
+                }
+            }
+        }
+        Ok(Keymap { bindings })
+    }
+
+    /// The action a key press triggers, if any
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|key| key.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    /// The keys bound to an action
+    pub fn keys(&self, action: Action) -> &[KeySpec] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Label for an action's keys, e.g. "j/down"; "-" when unbound
+    pub fn label(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).iter().map(|k| k.to_string()).collect();
+        if keys.is_empty() {
+            "-".to_string()
+        } else {
+            keys.join("/")
+        }
+    }
+
+    /// Label for an action's first key, for compact hints like the footer
+    pub fn short_label(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map(|key| key.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let key: KeySpec = "ctrl-D".parse().unwrap();
+        assert_eq!(key.code, KeyCode::Char('d'));
+        assert!(key.ctrl && !key.alt);
+        assert_eq!(key.to_string(), "ctrl-d");
+        assert_eq!("alt->".parse::<KeySpec>().unwrap().to_string(), "alt->");
+        assert_eq!(
+            "pagedown".parse::<KeySpec>().unwrap().code,
+            KeyCode::PageDown
+        );
+        assert_eq!("-".parse::<KeySpec>().unwrap().code, KeyCode::Char('-'));
+        assert!("ctrl-nope".parse::<KeySpec>().is_err());
+    }
+
+    #[test]
+    fn test_presets() {
+        let vi = Keymap::default();
+        assert_eq!(
+            vi.action_for(&press(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            Some(Action::ScrollBottom)
+        );
+        assert_eq!(
+            vi.action_for(&press(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            Some(Action::PageDown)
+        );
+        assert_eq!(
+            vi.action_for(&press(KeyCode::Char('z'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(vi.label(Action::ScrollDown), "j/down");
+        // Every action is reachable, so the help overlay lists no dead entries
+        assert!(Action::ALL
+            .iter()
+            .all(|&action| !vi.keys(action).is_empty()));
+        assert_eq!(vi.short_label(Action::Help), "?");
+
+        let emacs = Keymap::from_config(&KeymapConfig {
+            preset: KeymapPreset::Emacs,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            emacs.action_for(&press(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+            Some(Action::ScrollDown)
+        );
+        assert_eq!(
+            emacs.action_for(&press(KeyCode::Char('n'), KeyModifiers::NONE)),
+            Some(Action::NextMatch)
+        );
+    }
+
+    #[test]
+    fn test_overrides_are_validated() {
+        let config = |action: &str, key: &str| KeymapConfig {
+            bindings: [(action.to_string(), vec![key.to_string()])].into(),
+            ..Default::default()
+        };
+        let keymap = Keymap::from_config(&config("quit", "ctrl-q")).unwrap();
+        assert_eq!(keymap.short_label(Action::Quit), "ctrl-q");
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::Char('q'), KeyModifiers::NONE)),
+            None
+        );
+
+        assert_eq!(
+            Keymap::from_config(&config("pause", "l")).unwrap_err(),
+            "key 'l' is bound to both toggle_logs and pause"
+        );
+        assert!(Keymap::from_config(&config("explode", "e")).is_err());
+        assert!(Keymap::from_config(&config("quit", "hyper-q")).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! Log pane filtering and search
+//!
+//! Pure helpers for narrowing the raw agent output shown in the logs pane
+//! by category and for locating search matches within the visible lines.
+
+/// Category filter applied to the logs pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFilter {
+    /// Show every line
+    #[default]
+    All,
+    /// Only lines that look like errors
+    Errors,
+    /// Only tool use / tool result lines
+    Tools,
+    /// Only assistant text lines
+    Assistant,
+}
+
+impl LogFilter {
+    /// Return the next filter in the cycle (all → errors → tools → assistant → all)
+    pub fn next(self) -> Self {
+        match self {
+            LogFilter::All => LogFilter::Errors,
+            LogFilter::Errors => LogFilter::Tools,
+            LogFilter::Tools => LogFilter::Assistant,
+            LogFilter::Assistant => LogFilter::All,
+        }
+    }
+
+    /// Short label for the footer and pane title
+    pub fn label(self) -> &'static str {
+        match self {
+            LogFilter::All => "all",
+            LogFilter::Errors => "errors",
+            LogFilter::Tools => "tools",
+            LogFilter::Assistant => "assistant",
+        }
+    }
+
+    /// Check whether a log line passes this filter
+    pub fn matches(self, line: &str) -> bool {
+        match self {
+            LogFilter::All => true,
+            LogFilter::Errors => is_error_line(line),
+            LogFilter::Tools => is_tool_line(line),
+            LogFilter::Assistant => line.contains("<assistant_text>"),
+        }
+    }
+}
+
+fn is_error_line(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    lower.contains("[error]") || lower.contains("error:") || lower.contains("panicked")
+}
+
+fn is_tool_line(line: &str) -> bool {
+    line.contains("<tool_use>") || line.contains("<tool_result>")
+}
+
+/// Return the log lines that pass the given filter, preserving order.
+pub fn filter_logs(logs: &[String], filter: LogFilter) -> Vec<&str> {
+    logs.iter()
+        .map(String::as_str)
+        .filter(|line| filter.matches(line))
+        .collect()
+}
+
+/// Return the indices of lines containing the query (case-insensitive).
+///
+/// An empty query matches nothing.
+pub fn find_matches(lines: &[&str], query: &str) -> Vec<usize> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let needle = query.to_lowercase();
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.to_lowercase().contains(&needle))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Split a line into (text, is_match) segments for highlighting.
+///
+/// Matching is case-insensitive; the original casing of the line is kept.
+pub fn highlight_segments<'a>(line: &'a str, query: &str) -> Vec<(&'a str, bool)> {
+    if query.is_empty() {
+        return vec![(line, false)];
+    }
+
+    let lower_line = line.to_lowercase();
+    let needle = query.to_lowercase();
+    // Lowercasing can change byte lengths for some scripts; fall back to no highlight
+    if lower_line.len() != line.len() {
+        return vec![(line, false)];
+    }
+
+    let mut segments = Vec::new();
+    let mut cursor = 0;
+    while let Some(pos) = lower_line[cursor..].find(&needle) {
+        let start = cursor + pos;
+        let end = start + needle.len();
+        if start > cursor {
+            segments.push((&line[cursor..start], false));
+        }
+        segments.push((&line[start..end], true));
+        cursor = end;
+    }
+    if cursor < line.len() {
+        segments.push((&line[cursor..], false));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_logs() -> Vec<String> {
+        vec![
+            "<assistant_text>Reading the code</assistant_text>".to_string(),
+            r#"<tool_use>{"toolUseId":"1","name":"read_file"}</tool_use>"#.to_string(),
+            r#"<tool_result>{"toolUseId":"1","content":"ok"}</tool_result>"#.to_string(),
+            "[ERROR] agent exited unexpectedly".to_string(),
+            "plain output line".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_filter_cycle() {
+        assert_eq!(LogFilter::All.next(), LogFilter::Errors);
+        assert_eq!(LogFilter::Errors.next(), LogFilter::Tools);
+        assert_eq!(LogFilter::Tools.next(), LogFilter::Assistant);
+        assert_eq!(LogFilter::Assistant.next(), LogFilter::All);
+    }
+
+    #[test]
+    fn test_filter_logs_by_category() {
+        let logs = sample_logs();
+        assert_eq!(filter_logs(&logs, LogFilter::All).len(), 5);
+        assert_eq!(
+            filter_logs(&logs, LogFilter::Errors),
+            vec!["[ERROR] agent exited unexpectedly"]
+        );
+        assert_eq!(filter_logs(&logs, LogFilter::Tools).len(), 2);
+        assert_eq!(filter_logs(&logs, LogFilter::Assistant).len(), 1);
+    }
+
+    #[test]
+    fn test_find_matches_case_insensitive() {
+        let logs = sample_logs();
+        let lines = filter_logs(&logs, LogFilter::All);
+        assert_eq!(find_matches(&lines, "READ"), vec![0, 1]);
+        assert!(find_matches(&lines, "").is_empty());
+        assert!(find_matches(&lines, "missing").is_empty());
+    }
+
+    #[test]
+    fn test_highlight_segments() {
+        let segments = highlight_segments("Error here, another error", "error");
+        assert_eq!(
+            segments,
+            vec![("Error", true), (" here, another ", false), ("error", true)]
+        );
+        assert_eq!(
+            highlight_segments("no match", "xyz"),
+            vec![("no match", false)]
+        );
+        assert_eq!(highlight_segments("line", ""), vec![("line", false)]);
+    }
+}
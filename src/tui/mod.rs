@@ -6,10 +6,38 @@ pub mod state;
 pub mod runner;
 pub mod widgets;
 pub mod events;
+pub mod event_log;
+pub mod keymap;
 pub mod agent_helper;
+pub mod control;
+pub mod log_filter;
+pub mod notify;
+pub mod persist;
+pub mod picker;
+pub mod plain;
+pub mod theme;
+pub mod timeline;
+
+#[cfg(test)]
+mod tests;
 
 // Re-export commonly used types
 pub use state::{AgentActivity, TuiState, ToolExecution, ToolStatus};
-pub use runner::{TuiRunner, TuiOptions};
+pub use runner::{apply_update, TuiOptions, TuiRunner, TuiUpdate};
 pub use events::{AgentEvent, sanitize_assistant_text};
+pub use event_log::{
+    append_event, event_log_from_env, record_events, supervised_from_env, EventReader,
+    EVENT_LOG_ENV, SUPERVISED_ENV,
+};
 pub use agent_helper::run_agent_with_tui;
+pub use control::{
+    cancel_on_interrupt, control_channel, CancellationToken, ControlCommand, ControlHandle,
+    ControlSender,
+};
+pub use keymap::{Action, Keymap};
+pub use log_filter::LogFilter;
+pub use notify::{Notification, Notifier};
+pub use persist::{load_session, record_session, save_session, SessionSnapshot};
+pub use picker::{filter_candidates, fuzzy_score, pick};
+pub use plain::{PlainRenderer, RenderMode};
+pub use theme::Theme;
@@ -0,0 +1,161 @@
+//! Operator notifications for long-running sessions
+//!
+//! Rings the terminal bell or shows a desktop notification when an item
+//! reaches in_pr, fails, or needs human approval. Desktop notifications
+//! require the `desktop-notify` feature; without it they fall back to the bell.
+
+use std::io::{IsTerminal, Write};
+
+use crate::schemas::NotifyMode;
+
+/// An event worth interrupting the operator for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Notification {
+    /// Item reached the in_pr state
+    ReachedInPr { item_id: String },
+    /// Item failed with an error
+    Failed { item_id: String, error: String },
+    /// Item is waiting for human approval or input
+    AwaitingApproval { item_id: String, reason: String },
+    /// A phase ran past its soft time budget
+    OverBudget {
+        item_id: String,
+        phase: String,
+        minutes: u64,
+    },
+}
+
+impl Notification {
+    /// Short summary line for the notification
+    pub fn summary(&self) -> String {
+        match self {
+            Notification::ReachedInPr { item_id } => format!("wreckit: {} is in PR", item_id),
+            Notification::Failed { item_id, .. } => format!("wreckit: {} failed", item_id),
+            Notification::AwaitingApproval { item_id, .. } => {
+                format!("wreckit: {} needs attention", item_id)
+            }
+            Notification::OverBudget { item_id, phase, .. } => {
+                format!("wreckit: {} {} is running long", item_id, phase)
+            }
+        }
+    }
+
+    /// Longer body text for the notification
+    pub fn body(&self) -> String {
+        match self {
+            Notification::ReachedInPr { .. } => "Pull request is ready for review".to_string(),
+            Notification::Failed { error, .. } => error.clone(),
+            Notification::AwaitingApproval { reason, .. } => reason.clone(),
+            Notification::OverBudget { phase, minutes, .. } => {
+                format!("The {} phase has run for over {} minutes", phase, minutes)
+            }
+        }
+    }
+}
+
+/// Delivers notifications according to the configured mode
+#[derive(Debug, Clone, Copy)]
+pub struct Notifier {
+    mode: NotifyMode,
+}
+
+impl Notifier {
+    /// Create a notifier for the given mode
+    pub fn new(mode: NotifyMode) -> Self {
+        Self { mode }
+    }
+
+    /// The configured notification mode
+    pub fn mode(&self) -> NotifyMode {
+        self.mode
+    }
+
+    /// Deliver a notification. Failures are logged, never propagated.
+    pub fn notify(&self, notification: &Notification) {
+        match self.mode {
+            NotifyMode::None => {}
+            NotifyMode::Bell => ring_bell(),
+            NotifyMode::Desktop => {
+                if let Err(e) = show_desktop(notification) {
+                    tracing::warn!("Desktop notification failed, using bell instead: {}", e);
+                    ring_bell();
+                }
+            }
+        }
+    }
+}
+
+/// Ring the bell on whichever of stdout and stderr is a terminal.
+///
+/// The plain renderer runs when stdout is piped or captured, so the BEL byte
+/// must never land in that output; with no terminal at all the bell is
+/// skipped.
+fn ring_bell() {
+    let stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        let _ = ring(stdout.lock());
+        return;
+    }
+    let stderr = std::io::stderr();
+    if stderr.is_terminal() {
+        let _ = ring(stderr.lock());
+    }
+}
+
+fn ring(mut out: impl Write) -> std::io::Result<()> {
+    out.write_all(b"\x07")?;
+    out.flush()
+}
+
+#[cfg(feature = "desktop-notify")]
+fn show_desktop(notification: &Notification) -> std::result::Result<(), String> {
+    notify_rust::Notification::new()
+        .appname("wreckit")
+        .summary(&notification.summary())
+        .body(&notification.body())
+        .show()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "desktop-notify"))]
+fn show_desktop(_notification: &Notification) -> std::result::Result<(), String> {
+    Err("wreckit was built without the desktop-notify feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_text() {
+        let n = Notification::ReachedInPr {
+            item_id: "001-test".to_string(),
+        };
+        assert!(n.summary().contains("001-test"));
+        assert!(n.summary().contains("in PR"));
+
+        let n = Notification::Failed {
+            item_id: "001-test".to_string(),
+            error: "agent timed out".to_string(),
+        };
+        assert!(n.summary().contains("failed"));
+        assert_eq!(n.body(), "agent timed out");
+
+        let n = Notification::AwaitingApproval {
+            item_id: "001-test".to_string(),
+            reason: "plan needs review".to_string(),
+        };
+        assert!(n.summary().contains("needs attention"));
+        assert_eq!(n.body(), "plan needs review");
+    }
+
+    #[test]
+    fn test_notifier_none_is_silent() {
+        let notifier = Notifier::new(NotifyMode::None);
+        assert_eq!(notifier.mode(), NotifyMode::None);
+        notifier.notify(&Notification::ReachedInPr {
+            item_id: "001-test".to_string(),
+        });
+    }
+}
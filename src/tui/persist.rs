@@ -0,0 +1,187 @@
+//! Saved TUI session view
+//!
+//! While a workflow runs, a recorder folds the same update stream the
+//! renderer sees into its own `TuiState` and writes it to
+//! `.wreckit/cache/tui-session.json` every couple of seconds and when the run
+//! ends. `wreckit tui --attach` reads the file back, so a TUI that crashed or
+//! was closed can be reopened with its activity, tool calls, and logs intact,
+//! and keeps re-reading it while the workflow carries on.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+use crate::errors::Result;
+use crate::fs::{read_json, write_json};
+use crate::schemas::{Item, NotifyMode};
+use crate::tui::notify::Notifier;
+use crate::tui::runner::{apply_update, TuiUpdate};
+use crate::tui::state::TuiState;
+
+/// How often the recorder writes the session file while updates arrive
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A saved TUI session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// When the snapshot was written
+    pub saved_at: DateTime<Utc>,
+
+    /// Whether the run had ended when the snapshot was written
+    pub finished: bool,
+
+    /// Progress, activity, and logs
+    pub state: TuiState,
+}
+
+impl SessionSnapshot {
+    /// Snapshot a state now
+    pub fn new(state: &TuiState) -> Self {
+        Self {
+            saved_at: Utc::now(),
+            finished: state.finished,
+            state: state.clone(),
+        }
+    }
+}
+
+/// Write a session snapshot atomically.
+///
+/// # Errors
+/// * `Io` - If the file cannot be written
+pub fn save_session(path: &Path, state: &TuiState) -> Result<()> {
+    write_json(path, &SessionSnapshot::new(state))
+}
+
+/// Read the saved session, if there is one.
+///
+/// # Errors
+/// * `InvalidJson` - If the file is not a session snapshot
+/// * `Io` - If the file cannot be read
+pub fn load_session(path: &Path) -> Result<Option<SessionSnapshot>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    read_json(path).map(Some)
+}
+
+fn save_or_log(path: &Path, state: &TuiState) {
+    if let Err(e) = save_session(path, state) {
+        tracing::debug!("Cannot save TUI session to {}: {}", path.display(), e);
+    }
+}
+
+/// Record a run's updates to the session file until the run finishes or the
+/// stream closes.
+///
+/// The final state is always written, marked finished.
+pub fn record_session(
+    path: PathBuf,
+    items: Vec<Item>,
+    mut updates: broadcast::Receiver<TuiUpdate>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let notifier = Notifier::new(NotifyMode::None);
+        let mut state = TuiState::new(items);
+        let mut ticker = tokio::time::interval(SAVE_INTERVAL);
+        let mut dirty = true;
+        while !state.finished {
+            tokio::select! {
+                update = updates.recv() => match update {
+                    Ok(update) => {
+                        apply_update(&mut state, update, &notifier);
+                        dirty = true;
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!("TUI session recorder skipped {} updates", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = ticker.tick() => {
+                    if dirty {
+                        save_or_log(&path, &state);
+                        dirty = false;
+                    }
+                }
+            }
+        }
+        save_or_log(&path, &state.with_finished(true));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::state::{ToolExecution, ToolStatus};
+
+    fn item(id: &str) -> Item {
+        Item::new(id.to_string(), "Title".to_string(), String::new())
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_drops_view_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tui-session.json");
+        assert!(load_session(&path).unwrap().is_none());
+
+        let mut state = TuiState::new(vec![item("001-a")])
+            .with_log("started".to_string())
+            .with_show_logs(true)
+            .with_item_state("001-a".to_string(), "implementing".to_string());
+        state.append_tool(
+            "001-a",
+            ToolExecution {
+                tool_use_id: "t1".to_string(),
+                tool_name: "Bash".to_string(),
+                input: serde_json::json!({"command": "ls"}),
+                status: ToolStatus::Running,
+                result: None,
+                started_at: Utc::now(),
+                finished_at: None,
+            },
+        );
+        save_session(&path, &state).unwrap();
+
+        let snapshot = load_session(&path).unwrap().unwrap();
+        assert!(!snapshot.finished);
+        assert_eq!(snapshot.state.logs, vec!["started".to_string()]);
+        assert_eq!(snapshot.state.items[0].state, "implementing");
+        assert_eq!(snapshot.state.items[0].history.len(), 2);
+        assert_eq!(
+            snapshot.state.activity_by_item["001-a"].tools[0].tool_name,
+            "Bash"
+        );
+        assert!(!snapshot.state.show_logs);
+
+        // Restoring keeps the attaching TUI's own view settings
+        let restored = TuiState::new(Vec::new())
+            .with_show_timeline(true)
+            .with_restored(snapshot.state);
+        assert!(restored.show_timeline);
+        assert_eq!(restored.logs, vec!["started".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_record_session_saves_final_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join("tui-session.json");
+        let (tx, rx) = broadcast::channel(16);
+        let recorder = record_session(path.clone(), vec![item("001-a")], rx);
+
+        tx.send(TuiUpdate::SetCurrentItem(Some("001-a".to_string())))
+            .unwrap();
+        tx.send(TuiUpdate::AppendLogs(vec!["working".to_string()]))
+            .unwrap();
+        drop(tx);
+        recorder.await.unwrap();
+
+        let snapshot = load_session(&path).unwrap().unwrap();
+        assert!(snapshot.finished);
+        assert_eq!(snapshot.state.current_item.as_deref(), Some("001-a"));
+        assert_eq!(snapshot.state.logs, vec!["working".to_string()]);
+    }
+}
@@ -0,0 +1,202 @@
+//! Interactive fuzzy picker
+//!
+//! A small skim-style finder for `wreckit pick`: type to narrow the
+//! candidates by fuzzy match, move with the arrow keys (or Ctrl-P/Ctrl-N),
+//! Enter to choose, Esc or Ctrl-C to cancel. It draws on stderr so the
+//! command's stdout carries only the choice, as in `wreckit show $(wreckit
+//! pick)`.
+
+use std::io::{self, Stderr};
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState, Paragraph},
+    Terminal,
+};
+
+use crate::errors::{Result, WreckitError};
+
+/// Score a fuzzy match of `query` against `text`, or None if the query's
+/// characters do not all appear in order. Case-insensitive; consecutive
+/// matches and matches at the start of a word score higher, gaps lower.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars().flat_map(char::to_lowercase) {
+        if wanted.is_whitespace() {
+            continue;
+        }
+        let found = position + text[position..].iter().position(|&c| c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        } else if let Some(p) = previous {
+            score -= ((found - p - 1) as i64).min(5);
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+/// Indices of the candidates matching `query`, best match first (ties keep
+/// their original order). An empty query matches everything.
+pub fn filter_candidates(candidates: &[String], query: &str) -> Vec<usize> {
+    let mut matches: Vec<(usize, i64)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(i, text)| fuzzy_score(query, text).map(|score| (i, score)))
+        .collect();
+    matches.sort_by_key(|&(i, score)| (-score, i));
+    matches.into_iter().map(|(i, _)| i).collect()
+}
+
+/// Let the operator choose one of `candidates` interactively, returning its
+/// index. `header` is shown above the list.
+///
+/// # Errors
+/// * `Interrupted` - If the operator cancels with Esc or Ctrl-C
+/// * `Io` - If stderr is not a terminal or cannot be drawn on
+pub fn pick(candidates: &[String], header: &str) -> Result<usize> {
+    if !atty::is(atty::Stream::Stderr) {
+        return Err(WreckitError::Io(io::Error::other(
+            "interactive picking needs a terminal on stderr",
+        )));
+    }
+    enable_raw_mode()?;
+    let mut stderr = io::stderr();
+    execute!(stderr, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stderr))?;
+
+    let result = pick_loop(&mut terminal, candidates, header);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+fn pick_loop(
+    terminal: &mut Terminal<CrosstermBackend<Stderr>>,
+    candidates: &[String],
+    header: &str,
+) -> Result<usize> {
+    let mut query = String::new();
+    let mut matches = filter_candidates(candidates, &query);
+    let mut list_state = ListState::default();
+    list_state.select(Some(0));
+
+    loop {
+        terminal.draw(|frame| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Min(0),
+                ])
+                .split(frame.area());
+            let prompt = Line::from(vec![
+                Span::styled("> ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(query.as_str()),
+                Span::raw(format!("  {}/{}", matches.len(), candidates.len())),
+            ]);
+            frame.render_widget(Paragraph::new(prompt), chunks[0]);
+            frame.render_widget(
+                Paragraph::new(header).style(Style::default().add_modifier(Modifier::DIM)),
+                chunks[1],
+            );
+            let rows: Vec<ListItem> = matches
+                .iter()
+                .map(|&i| ListItem::new(candidates[i].as_str()))
+                .collect();
+            let list =
+                List::new(rows).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+            frame.render_stateful_widget(list, chunks[2], &mut list_state);
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let selected = list_state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => return Err(WreckitError::Interrupted),
+            KeyCode::Char('c') if ctrl => return Err(WreckitError::Interrupted),
+            KeyCode::Enter => {
+                if let Some(&index) = matches.get(selected) {
+                    return Ok(index);
+                }
+            }
+            KeyCode::Up => list_state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Char('p') if ctrl => list_state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Down => {
+                list_state.select(Some((selected + 1).min(matches.len().saturating_sub(1))))
+            }
+            KeyCode::Char('n') if ctrl => {
+                list_state.select(Some((selected + 1).min(matches.len().saturating_sub(1))))
+            }
+            KeyCode::Backspace => {
+                query.pop();
+                matches = filter_candidates(candidates, &query);
+                list_state.select(Some(0));
+            }
+            KeyCode::Char(c) if !ctrl => {
+                query.push(c);
+                matches = filter_candidates(candidates, &query);
+                list_state.select(Some(0));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("dkm", "dark mode toggle").is_some());
+        assert!(fuzzy_score("mkd", "dark mode toggle").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        // Consecutive and word-start matches beat scattered ones
+        assert!(
+            fuzzy_score("mode", "dark mode").unwrap()
+                > fuzzy_score("mode", "my old dog e").unwrap()
+        );
+        assert!(fuzzy_score("DARK", "dark mode").is_some());
+    }
+
+    #[test]
+    fn test_filter_candidates() {
+        let candidates: Vec<String> = [
+            "001-login  idea  Fix login redirect",
+            "002-dark   done  Add dark mode",
+            "003-logs   idea  Rotate old logs",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(filter_candidates(&candidates, ""), vec![0, 1, 2]);
+        assert_eq!(filter_candidates(&candidates, "dark"), vec![1]);
+        assert_eq!(filter_candidates(&candidates, "log")[..2], [0, 2]);
+        assert!(filter_candidates(&candidates, "zzz").is_empty());
+    }
+}
@@ -0,0 +1,304 @@
+//! Headless "plain" renderer
+//!
+//! Consumes the same [`TuiUpdate`] stream as the interactive TUI and prints
+//! compact one-line progress updates to stdout. Used when stdout is not a
+//! terminal (CI, nohup) so runs stay observable everywhere.
+
+use std::io::Write;
+
+use tokio::sync::broadcast;
+
+use crate::errors::Result;
+use crate::schemas::Item;
+use crate::tui::events::AgentEvent;
+use crate::tui::notify::Notifier;
+use crate::tui::runner::{apply_update, TuiOptions, TuiUpdate};
+use crate::tui::state::{ToolStatus, TuiState};
+use crate::tui::theme::Theme;
+
+/// Which renderer to use for a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Full-screen interactive TUI
+    Interactive,
+    /// One-line progress output
+    Plain,
+}
+
+impl RenderMode {
+    /// Pick the interactive TUI only when it is allowed and stdout is a terminal
+    pub fn detect(no_tui: bool) -> Self {
+        if no_tui || !atty::is(atty::Stream::Stdout) {
+            RenderMode::Plain
+        } else {
+            RenderMode::Interactive
+        }
+    }
+}
+
+/// Non-interactive renderer sharing TUI state
+pub struct PlainRenderer {
+    state: TuiState,
+    notifier: Notifier,
+    theme: Theme,
+    state_tx: broadcast::Sender<TuiUpdate>,
+    state_rx: broadcast::Receiver<TuiUpdate>,
+}
+
+impl PlainRenderer {
+    /// Create a new plain renderer
+    pub fn new(items: Vec<Item>, options: TuiOptions) -> Self {
+        let (state_tx, state_rx) = broadcast::channel(100);
+        Self {
+            state: TuiState::new(items),
+            notifier: Notifier::new(options.notify),
+            theme: options.theme,
+            state_tx,
+            state_rx,
+        }
+    }
+
+    /// Create a sender for state updates
+    pub fn create_update_sender(&self) -> broadcast::Sender<TuiUpdate> {
+        self.state_tx.clone()
+    }
+
+    /// Current state snapshot
+    pub fn state(&self) -> &TuiState {
+        &self.state
+    }
+
+    /// Apply an update and return the progress line to print, if any
+    pub fn render_update(&mut self, update: TuiUpdate) -> Option<String> {
+        let line = describe_update(&self.state, &update, &self.theme);
+        apply_update(&mut self.state, update, &self.notifier);
+        line.map(|text| format!("[{}] {}", elapsed(&self.state), text))
+    }
+
+    /// Print updates until every sender has been dropped
+    pub async fn run(mut self) -> Result<()> {
+        self.run_with_writer(&mut std::io::stdout()).await
+    }
+
+    /// Print updates to the given writer until every sender has been dropped
+    pub async fn run_with_writer<W: Write>(&mut self, out: &mut W) -> Result<()> {
+        // Drop our own sender so the channel closes when producers finish
+        let (closed_tx, _) = broadcast::channel(1);
+        drop(std::mem::replace(&mut self.state_tx, closed_tx));
+
+        loop {
+            match self.state_rx.recv().await {
+                Ok(update) => {
+                    if let Some(line) = self.render_update(update) {
+                        writeln!(out, "{}", line)?;
+                        out.flush()?;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    writeln!(out, "... skipped {} updates", skipped)?;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+
+        if let Some(summary) = self.summary() {
+            writeln!(out, "{}", summary)?;
+        }
+        Ok(())
+    }
+
+    /// Closing summary of items completed and elapsed time, if there are items
+    pub fn summary(&self) -> Option<String> {
+        summary_line(&self.state)
+    }
+}
+
+/// Describe an update as a single line, or None if it is not worth printing
+fn describe_update(state: &TuiState, update: &TuiUpdate, theme: &Theme) -> Option<String> {
+    let item = state.current_item.as_deref().unwrap_or("-");
+    match update {
+        TuiUpdate::SetCurrentItem(Some(id)) => Some(format!("{} | started", id)),
+        TuiUpdate::SetCurrentPhase(Some(phase)) => Some(format!("{} | phase {}", item, phase)),
+        TuiUpdate::SetIteration(iteration) => Some(format!(
+            "{} | iteration {}/{} | {}",
+            item,
+            iteration,
+            state.max_iterations,
+            tool_counts(state, item)
+        )),
+        TuiUpdate::SetCurrentStory(Some(story)) => Some(format!("{} | story {}", item, story)),
+        TuiUpdate::SetItemState(id, new_state) => {
+            Some(format!("{} | state {} {}", id, theme.arrow(), new_state))
+        }
+        TuiUpdate::AgentEvent(id, AgentEvent::Error { message }) => {
+            Some(format!("{} | error: {}", id, message))
+        }
+        TuiUpdate::AgentEvent(id, AgentEvent::RunResult) => Some(format!(
+            "{} | agent finished | {}",
+            id,
+            tool_counts(state, id)
+        )),
+        TuiUpdate::ItemFailed(id, error) => Some(format!("{} | FAILED: {}", id, error)),
+        TuiUpdate::AwaitingApproval(id, reason) => {
+            Some(format!("{} | awaiting approval: {}", id, reason))
+        }
+        TuiUpdate::PhaseOverBudget(id, phase, minutes, _) => Some(format!(
+            "{} | WARNING: {} running over {}m budget",
+            id, phase, minutes
+        )),
+        _ => None,
+    }
+}
+
+/// Summarize tool activity for an item ("tools 12 ok, 1 err, 0 running")
+fn tool_counts(state: &TuiState, item_id: &str) -> String {
+    let tools = state
+        .activity_by_item
+        .get(item_id)
+        .map(|a| a.tools.as_slice())
+        .unwrap_or(&[]);
+    let count = |status: ToolStatus| tools.iter().filter(|t| t.status == status).count();
+    format!(
+        "tools {} ok, {} err, {} running",
+        count(ToolStatus::Completed),
+        count(ToolStatus::Error),
+        count(ToolStatus::Running)
+    )
+}
+
+/// Final progress summary printed when the stream closes
+fn summary_line(state: &TuiState) -> Option<String> {
+    if state.total_count == 0 {
+        return None;
+    }
+    Some(format!(
+        "[{}] done: {}/{} complete",
+        elapsed(state),
+        state.completed_count,
+        state.total_count
+    ))
+}
+
+fn elapsed(state: &TuiState) -> String {
+    let secs = (chrono::Utc::now() - state.start_time).num_seconds().max(0);
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::WorkflowState;
+
+    fn renderer() -> PlainRenderer {
+        let items = vec![
+            Item::new("001-a".to_string(), "A".to_string(), String::new()),
+            Item::new("002-b".to_string(), "B".to_string(), String::new())
+                .with_state(WorkflowState::Done),
+        ];
+        PlainRenderer::new(items, TuiOptions::default())
+    }
+
+    #[test]
+    fn test_render_update_lines() {
+        let mut r = renderer();
+
+        let line = r.render_update(TuiUpdate::SetCurrentItem(Some("001-a".to_string())));
+        assert!(line.unwrap().ends_with("001-a | started"));
+
+        let line = r.render_update(TuiUpdate::SetCurrentPhase(Some("research".to_string())));
+        assert!(line.unwrap().ends_with("001-a | phase research"));
+
+        let line = r.render_update(TuiUpdate::SetCurrentStory(Some(
+            "US-001 - Login".to_string(),
+        )));
+        assert!(line.unwrap().contains("story US-001 - Login"));
+        assert_eq!(r.state().current_story.as_ref().unwrap().id, "US-001");
+
+        // Logs are not echoed
+        assert!(r
+            .render_update(TuiUpdate::AppendLogs(vec!["noise".to_string()]))
+            .is_none());
+    }
+
+    #[test]
+    fn test_ascii_state_line() {
+        let items = vec![Item::new(
+            "001-a".to_string(),
+            "A".to_string(),
+            String::new(),
+        )];
+        let options = TuiOptions {
+            theme: Theme {
+                ascii: true,
+                color: false,
+            },
+            ..Default::default()
+        };
+        let mut r = PlainRenderer::new(items, options);
+        let line = r.render_update(TuiUpdate::SetItemState(
+            "001-a".to_string(),
+            "researched".to_string(),
+        ));
+        assert!(line.unwrap().ends_with("001-a | state -> researched"));
+    }
+
+    #[test]
+    fn test_tool_counts_in_iteration_line() {
+        let mut r = renderer();
+        r.render_update(TuiUpdate::SetCurrentItem(Some("001-a".to_string())));
+        r.render_update(TuiUpdate::AgentEvent(
+            "001-a".to_string(),
+            AgentEvent::ToolStarted {
+                tool_use_id: "t1".to_string(),
+                tool_name: "read".to_string(),
+                input: serde_json::Value::Null,
+            },
+        ));
+        r.render_update(TuiUpdate::AgentEvent(
+            "001-a".to_string(),
+            AgentEvent::ToolResult {
+                tool_use_id: "t1".to_string(),
+                result: serde_json::Value::Null,
+            },
+        ));
+
+        let line = r.render_update(TuiUpdate::SetIteration(2)).unwrap();
+        assert!(line.contains("iteration 2/100"));
+        assert!(line.contains("tools 1 ok, 0 err, 0 running"));
+    }
+
+    #[tokio::test]
+    async fn test_run_with_writer_stops_when_senders_drop() {
+        let mut r = renderer();
+        let tx = r.create_update_sender();
+
+        let producer = tokio::spawn(async move {
+            tx.send(TuiUpdate::SetCurrentItem(Some("001-a".to_string())))
+                .unwrap();
+            tx.send(TuiUpdate::ItemFailed(
+                "001-a".to_string(),
+                "boom".to_string(),
+            ))
+            .unwrap();
+        });
+
+        let mut out = Vec::new();
+        producer.await.unwrap();
+        r.run_with_writer(&mut out).await.unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("001-a | started"));
+        assert!(text.contains("001-a | FAILED: boom"));
+        assert!(text.contains("done: 1/2 complete"));
+    }
+
+    #[test]
+    fn test_render_mode_no_tui_forces_plain() {
+        assert_eq!(RenderMode::detect(true), RenderMode::Plain);
+    }
+}
@@ -1,9 +1,15 @@
 //! TUI runner - manages TUI lifecycle and rendering
 
 use crate::errors::Result;
-use crate::schemas::Item;
+use crate::schemas::{Item, NotifyMode};
+use crate::tui::control::{ControlCommand, ControlSender};
 use crate::tui::events::{sanitize_assistant_text, AgentEvent};
-use crate::tui::state::{AgentActivity, ToolExecution, ToolStatus, TuiState};
+use crate::tui::keymap::{Action, Keymap};
+use crate::tui::notify::{Notification, Notifier};
+use crate::tui::log_filter::{filter_logs, find_matches};
+use crate::tui::state::{AgentActivity, CurrentStory, ToolExecution, ToolStatus, TuiState};
+use crate::tui::theme::Theme;
+use serde::{Deserialize, Serialize};
 use ratatui::{
     backend::CrosstermBackend,
     crossterm::{
@@ -15,7 +21,7 @@ use ratatui::{
 };
 use std::io::{self, Stdout};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
 /// Options for TUI initialization
@@ -23,6 +29,20 @@ use tokio::sync::Mutex;
 pub struct TuiOptions {
     pub on_quit: Option<Arc<dyn Fn() + Send + Sync>>,
     pub debug: bool,
+    /// Control channel to the workflow loop (enables pause/cancel keys)
+    pub control: Option<ControlSender>,
+    /// How to alert the operator on completion, failure, and approval events
+    pub notify: NotifyMode,
+    /// Glyphs and colors for the interactive TUI
+    pub theme: Theme,
+    /// Redraw at most once a second, and on key presses, instead of continuously
+    pub reduced_motion: bool,
+    /// Key bindings
+    pub keymap: Keymap,
+    /// Config settings shown in the help overlay, as (label, value)
+    pub highlights: Vec<(String, String)>,
+    /// Show the first-run hint until the first key press
+    pub first_run: bool,
 }
 
 impl Default for TuiOptions {
@@ -30,12 +50,19 @@ impl Default for TuiOptions {
         Self {
             on_quit: None,
             debug: false,
+            control: None,
+            notify: NotifyMode::None,
+            theme: Theme::default(),
+            reduced_motion: false,
+            keymap: Keymap::default(),
+            highlights: Vec::new(),
+            first_run: false,
         }
     }
 }
 
 /// State update events
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TuiUpdate {
     SetCurrentItem(Option<String>),
     SetCurrentPhase(Option<String>),
@@ -46,8 +73,154 @@ pub enum TuiUpdate {
     AppendLogs(Vec<String>),
     ToggleLogs(bool),
     AgentEvent(String, AgentEvent),
+    /// Item failed with an error message
+    ItemFailed(String, String),
+    /// Item is waiting for human approval, with a reason
+    AwaitingApproval(String, String),
+    /// A phase passed its soft budget: item, phase, budget in minutes, and
+    /// whether to notify the operator
+    PhaseOverBudget(String, String, u64, bool),
+    /// The workflow run has ended; the interactive TUI exits
+    RunFinished,
+    /// Replace the progress shown with a saved session's (`wreckit tui --attach`)
+    Restore(Box<TuiState>),
+}
+
+/// Apply a single update to the TUI state.
+///
+/// Shared by the interactive TUI and the plain renderer so both views stay
+/// consistent. Notification side effects are delivered through `notifier`.
+pub fn apply_update(state: &mut TuiState, update: TuiUpdate, notifier: &Notifier) {
+    match update {
+        TuiUpdate::SetCurrentItem(item) => {
+            *state = state.clone().with_current_item(item);
+        }
+        TuiUpdate::SetCurrentPhase(phase) => {
+            *state = state.clone().with_current_phase(phase);
+        }
+        TuiUpdate::SetIteration(iter) => {
+            *state = state.clone().with_iteration(iter);
+        }
+        TuiUpdate::SetCurrentStory(story) => {
+            let story = story.map(|s| parse_current_story(&s));
+            *state = state.clone().with_current_story(story);
+        }
+        TuiUpdate::SetItemState(item_id, item_state) => {
+            let entered_pr = item_state == "in_pr"
+                && state
+                    .items
+                    .iter()
+                    .any(|i| i.id == item_id && i.state != item_state);
+            if entered_pr {
+                notifier.notify(&Notification::ReachedInPr {
+                    item_id: item_id.clone(),
+                });
+            }
+            *state = state.clone().with_item_state(item_id, item_state);
+        }
+        TuiUpdate::SetCompletedCount(count) => {
+            *state = state.clone().with_completed_count(count);
+        }
+        TuiUpdate::AppendLogs(logs) => {
+            *state = state.clone().with_logs(logs);
+        }
+        TuiUpdate::ToggleLogs(show) => {
+            *state = state.clone().with_show_logs(show);
+        }
+        TuiUpdate::AgentEvent(item_id, event) => {
+            handle_agent_event(state, item_id, event);
+        }
+        TuiUpdate::ItemFailed(item_id, error) => {
+            *state = state
+                .clone()
+                .with_log(format!("[ERROR] {} failed: {}", item_id, error));
+            notifier.notify(&Notification::Failed { item_id, error });
+        }
+        TuiUpdate::AwaitingApproval(item_id, reason) => {
+            *state = state
+                .clone()
+                .with_log(format!("{} is awaiting approval: {}", item_id, reason));
+            notifier.notify(&Notification::AwaitingApproval { item_id, reason });
+        }
+        TuiUpdate::PhaseOverBudget(item_id, phase, minutes, notify) => {
+            *state = state.clone().with_log(format!(
+                "[WARN] {} {} phase has run for over {} minutes",
+                item_id, phase, minutes
+            ));
+            if notify {
+                notifier.notify(&Notification::OverBudget {
+                    item_id,
+                    phase,
+                    minutes,
+                });
+            }
+        }
+        TuiUpdate::RunFinished => {
+            *state = state.clone().with_finished(true);
+        }
+        TuiUpdate::Restore(saved) => {
+            *state = state.clone().with_restored(*saved);
+        }
+    }
+}
+
+/// Parse a story label of the form "US-001 - Title" (title optional)
+fn parse_current_story(label: &str) -> CurrentStory {
+    match label.split_once(" - ") {
+        Some((id, title)) => CurrentStory {
+            id: id.trim().to_string(),
+            title: title.trim().to_string(),
+        },
+        None => CurrentStory {
+            id: label.trim().to_string(),
+            title: String::new(),
+        },
+    }
+}
+
+/// Fold an agent event into the per-item activity
+fn handle_agent_event(state: &mut TuiState, item_id: String, event: AgentEvent) {
+    match event {
+        AgentEvent::AssistantText { text } => {
+            if let Some(cleaned) = sanitize_assistant_text(&text) {
+                state.append_thought(&item_id, cleaned);
+            }
+        }
+        AgentEvent::ToolStarted {
+            tool_use_id,
+            tool_name,
+            input,
+        } => {
+            let tool = ToolExecution {
+                tool_use_id,
+                tool_name,
+                input,
+                status: ToolStatus::Running,
+                result: None,
+                started_at: chrono::Utc::now(),
+                finished_at: None,
+            };
+            state.append_tool(&item_id, tool);
+        }
+        AgentEvent::ToolResult { tool_use_id, result } => {
+            state.update_tool_status(&item_id, &tool_use_id, ToolStatus::Completed, Some(result));
+        }
+        AgentEvent::ToolError { tool_use_id, error } => {
+            state.update_tool_status(&item_id, &tool_use_id, ToolStatus::Error, None);
+            state.append_thought(&item_id, format!("[ERROR] {}", error));
+        }
+        AgentEvent::Error { message } => {
+            state.append_thought(&item_id, format!("[ERROR] {}", message));
+        }
+        AgentEvent::RunResult => {
+            // No state update needed
+        }
+    }
 }
 
+/// Minimum time between redraws in reduced-motion mode
+const REDUCED_MOTION_REDRAW: Duration = Duration::from_secs(1);
+
 /// Main TUI runner
 pub struct TuiRunner {
     state: Arc<Mutex<TuiState>>,
@@ -56,6 +229,8 @@ pub struct TuiRunner {
     _state_rx: tokio::sync::broadcast::Receiver<TuiUpdate>,
     scroll_offset: usize,
     auto_scroll: bool,
+    current_match: Option<usize>,
+    show_hint: bool,
 }
 
 impl TuiRunner {
@@ -67,87 +242,23 @@ impl TuiRunner {
         // Spawn task to process state updates
         let state_clone = state.clone();
         let mut rx = state_tx.subscribe();
+        let notifier = Notifier::new(options.notify);
         tokio::spawn(async move {
             while let Ok(update) = rx.recv().await {
                 let mut state = state_clone.lock().await;
-                match update {
-                    TuiUpdate::SetCurrentItem(item) => {
-                        *state = state.clone().with_current_item(item);
-                    }
-                    TuiUpdate::SetCurrentPhase(phase) => {
-                        *state = state.clone().with_current_phase(phase);
-                    }
-                    TuiUpdate::SetIteration(iter) => {
-                        *state = state.clone().with_iteration(iter);
-                    }
-                    TuiUpdate::SetCurrentStory(_story) => {
-                        // TODO: Parse story from string in Phase 4
-                    }
-                    TuiUpdate::SetItemState(item_id, item_state) => {
-                        *state = state.clone().with_item_state(item_id, item_state);
-                    }
-                    TuiUpdate::SetCompletedCount(count) => {
-                        *state = state.clone().with_completed_count(count);
-                    }
-                    TuiUpdate::AppendLogs(logs) => {
-                        *state = state.clone().with_logs(logs);
-                    }
-                    TuiUpdate::ToggleLogs(show) => {
-                        *state = state.clone().with_show_logs(show);
-                    }
-                    TuiUpdate::AgentEvent(item_id, event) => {
-                        Self::handle_agent_event(&mut state, item_id, event);
-                    }
-                }
+                apply_update(&mut state, update, &notifier);
             }
         });
 
         Self {
             state,
+            show_hint: options.first_run,
             options,
             state_tx,
             _state_rx: state_rx,
             scroll_offset: 0,
             auto_scroll: true,
-        }
-    }
-
-    fn handle_agent_event(state: &mut TuiState, item_id: String, event: AgentEvent) {
-        match event {
-            AgentEvent::AssistantText { text } => {
-                if let Some(cleaned) = sanitize_assistant_text(&text) {
-                    state.append_thought(&item_id, cleaned);
-                }
-            }
-            AgentEvent::ToolStarted {
-                tool_use_id,
-                tool_name,
-                input,
-            } => {
-                let tool = ToolExecution {
-                    tool_use_id,
-                    tool_name,
-                    input,
-                    status: ToolStatus::Running,
-                    result: None,
-                    started_at: chrono::Utc::now(),
-                    finished_at: None,
-                };
-                state.append_tool(&item_id, tool);
-            }
-            AgentEvent::ToolResult { tool_use_id, result } => {
-                state.update_tool_status(&item_id, &tool_use_id, ToolStatus::Completed, Some(result));
-            }
-            AgentEvent::ToolError { tool_use_id, error } => {
-                state.update_tool_status(&item_id, &tool_use_id, ToolStatus::Error, None);
-                state.append_thought(&item_id, format!("[ERROR] {}", error));
-            }
-            AgentEvent::Error { message } => {
-                state.append_thought(&item_id, format!("[ERROR] {}", message));
-            }
-            AgentEvent::RunResult => {
-                // No state update needed
-            }
+            current_match: None,
         }
     }
 
@@ -193,80 +304,143 @@ impl TuiRunner {
     async fn run_tui_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
         use ratatui::layout::{Constraint, Direction, Layout};
 
+        let theme = self.options.theme;
+        let mut last_draw: Option<Instant> = None;
+        let mut input_seen = false;
         loop {
             let state = self.get_state().await;
+            if state.finished {
+                return Ok(());
+            }
+
+            // Reduced motion redraws on input and otherwise at a slow, steady pace
+            let due = !self.options.reduced_motion
+                || input_seen
+                || last_draw.is_none_or(|at| at.elapsed() >= REDUCED_MOTION_REDRAW);
+            input_seen = false;
 
             // Draw
-            terminal.draw(|f| {
-                let size = f.area();
-
-                // Header (5 lines), Main (flex), Footer (4 lines)
-                let header_height = 5;
-                let footer_height = 4;
-
-                let chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([
-                        Constraint::Length(header_height),
-                        Constraint::Min(0),
-                        Constraint::Length(footer_height),
-                    ])
-                    .split(size);
-
-                // Render header
-                crate::tui::widgets::render_header(f, chunks[0], &state);
-
-                // Render main area
-                if state.show_logs {
-                    crate::tui::widgets::render_logs_pane(f, chunks[1], &state, self.scroll_offset);
-                } else {
-                    let main_chunks = Layout::default()
-                        .direction(Direction::Horizontal)
-                        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-                        .split(chunks[1]);
-
-                    crate::tui::widgets::render_items_pane(f, main_chunks[0], &state);
-
-                    let right_chunks = Layout::default()
+            if due {
+                last_draw = Some(Instant::now());
+                terminal.draw(|f| {
+                    let size = f.area();
+
+                    // Header (5 lines), Main (flex), Footer (4 lines)
+                    let header_height = 5;
+                    let footer_height = 4;
+
+                    let chunks = Layout::default()
                         .direction(Direction::Vertical)
-                        .constraints([Constraint::Length(4), Constraint::Min(0)])
-                        .split(main_chunks[1]);
+                        .constraints([
+                            Constraint::Length(header_height),
+                            Constraint::Min(0),
+                            Constraint::Length(footer_height),
+                        ])
+                        .split(size);
 
-                    crate::tui::widgets::render_active_item_pane(f, right_chunks[0], &state);
-                    crate::tui::widgets::render_agent_activity_pane(f, right_chunks[1], &state);
-                }
+                    // Render header
+                    crate::tui::widgets::render_header(f, chunks[0], &state, &theme);
 
-                // Render footer
-                crate::tui::widgets::render_footer(f, chunks[2], &state, state.show_logs);
-            })?;
+                    // Render main area
+                    if state.show_logs {
+                        crate::tui::widgets::render_logs_pane(
+                            f,
+                            chunks[1],
+                            &state,
+                            self.scroll_offset,
+                            self.current_match,
+                            &theme,
+                        );
+                    } else if state.show_timeline {
+                        crate::tui::widgets::render_timeline_pane(f, chunks[1], &state, &theme);
+                    } else {
+                        let main_chunks = Layout::default()
+                            .direction(Direction::Horizontal)
+                            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                            .split(chunks[1]);
+
+                        crate::tui::widgets::render_items_pane(f, main_chunks[0], &state, &theme);
+
+                        let right_chunks = Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([Constraint::Length(4), Constraint::Min(0)])
+                            .split(main_chunks[1]);
+
+                        crate::tui::widgets::render_active_item_pane(f, right_chunks[0], &state, &theme);
+                        crate::tui::widgets::render_agent_activity_pane(f, right_chunks[1], &state, &theme);
+                    }
+
+                    // Render footer
+                    crate::tui::widgets::render_footer(
+                        f,
+                        chunks[2],
+                        &state,
+                        state.show_logs,
+                        &theme,
+                        &self.options.keymap,
+                        self.show_hint,
+                    );
+
+                    if state.show_help {
+                        crate::tui::widgets::render_help_overlay(
+                            f,
+                            size,
+                            &theme,
+                            &self.options.keymap,
+                            &self.options.highlights,
+                        );
+                    }
+                })?;
+            }
 
             // Handle events (with timeout)
             if crossterm::event::poll(Duration::from_millis(100))? {
+                input_seen = true;
                 match crossterm::event::read()? {
+                    crossterm::event::Event::Key(key) if state.search_input.is_some() => {
+                        self.handle_search_input(key.code).await;
+                    }
+                    crossterm::event::Event::Key(key)
+                        if key.code == crossterm::event::KeyCode::Char('c')
+                            && key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) =>
+                    {
+                        return Ok(());
+                    }
+                    crossterm::event::Event::Key(_) if state.show_help => {
+                        // Any key closes the help overlay
+                        let mut s = self.state.lock().await;
+                        *s = s.clone().with_show_help(false);
+                    }
                     crossterm::event::Event::Key(key) => {
-                        match key.code {
-                            crossterm::event::KeyCode::Char('q') => {
+                        self.show_hint = false;
+                        match self.options.keymap.action_for(&key) {
+                            Some(Action::Quit) => {
                                 return Ok(());
                             }
-                            crossterm::event::KeyCode::Char('l') => {
+                            Some(Action::ToggleLogs) => {
                                 let mut s = self.state.lock().await;
                                 *s = s.clone().with_show_logs(!s.show_logs);
                             }
-                            crossterm::event::KeyCode::Char('j')
-                            | crossterm::event::KeyCode::Down => {
+                            Some(Action::ToggleTimeline) => {
+                                let mut s = self.state.lock().await;
+                                *s = s
+                                    .clone()
+                                    .with_show_timeline(!s.show_timeline)
+                                    .with_show_logs(false);
+                            }
+                            Some(Action::ScrollDown) => {
                                 if state.show_logs && self.scroll_offset > 0 {
                                     self.scroll_offset -= 1;
                                     self.auto_scroll = false;
                                 }
                             }
-                            crossterm::event::KeyCode::Char('k')
-                            | crossterm::event::KeyCode::Up => {
+                            Some(Action::ScrollUp) => {
                                 if state.show_logs {
                                     self.scroll_offset += 1;
                                     self.auto_scroll = false;
                                 }
                             }
-                            crossterm::event::KeyCode::PageDown => {
+                            Some(Action::PageDown) => {
                                 if state.show_logs {
                                     let logs_height = 15;
                                     self.scroll_offset =
@@ -274,33 +448,76 @@ impl TuiRunner {
                                     self.auto_scroll = false;
                                 }
                             }
-                            crossterm::event::KeyCode::PageUp => {
+                            Some(Action::PageUp) => {
                                 if state.show_logs {
                                     let logs_height = 15;
                                     self.scroll_offset += logs_height;
                                     self.auto_scroll = false;
                                 }
                             }
-                            crossterm::event::KeyCode::Char('g') => {
+                            Some(Action::ScrollTop) => {
                                 if state.show_logs {
                                     self.scroll_offset = state.logs.len();
                                     self.auto_scroll = false;
                                 }
                             }
-                            crossterm::event::KeyCode::Char('G') => {
+                            Some(Action::ScrollBottom) => {
                                 if state.show_logs {
                                     self.scroll_offset = 0;
                                     self.auto_scroll = true;
                                 }
                             }
-                            crossterm::event::KeyCode::Char('c')
-                                if key.modifiers.contains(
-                                    crossterm::event::KeyModifiers::CONTROL,
-                                ) =>
-                            {
-                                return Ok(());
+                            Some(Action::Search) if state.show_logs => {
+                                let mut s = self.state.lock().await;
+                                *s = s.clone().with_search_input(Some(String::new()));
+                            }
+                            Some(Action::NextMatch) if state.show_logs => {
+                                self.jump_to_match(&state, true);
+                            }
+                            Some(Action::PrevMatch) if state.show_logs => {
+                                self.jump_to_match(&state, false);
                             }
-                            _ => {}
+                            Some(Action::CycleFilter) if state.show_logs => {
+                                let mut s = self.state.lock().await;
+                                *s = s.clone().with_log_filter(s.log_filter.next());
+                                self.current_match = None;
+                                self.scroll_offset = 0;
+                                self.auto_scroll = true;
+                            }
+                            Some(Action::Pause) => {
+                                if let Some(ref control) = self.options.control {
+                                    let paused = !state.paused;
+                                    control.send(if paused {
+                                        ControlCommand::Pause
+                                    } else {
+                                        ControlCommand::Resume
+                                    });
+                                    let message = if paused {
+                                        "Pause requested: will stop after the current iteration"
+                                    } else {
+                                        "Resumed"
+                                    };
+                                    let mut s = self.state.lock().await;
+                                    *s = s.clone().with_paused(paused).with_log(message.to_string());
+                                }
+                            }
+                            Some(Action::CancelItem) => {
+                                if let (Some(ref control), Some(ref item_id)) =
+                                    (&self.options.control, &state.current_item)
+                                {
+                                    control.send(ControlCommand::CancelCurrent);
+                                    let mut s = self.state.lock().await;
+                                    *s = s
+                                        .clone()
+                                        .with_log(format!("Cancel requested for {}", item_id));
+                                }
+                            }
+                            Some(Action::Help) => {
+                                let mut s = self.state.lock().await;
+                                *s = s.clone().with_show_help(true);
+                            }
+                            // Log actions while the logs are hidden
+                            Some(_) | None => {}
                         }
                     }
                     crossterm::event::Event::Resize(_, _) => {
@@ -316,4 +533,67 @@ impl TuiRunner {
             }
         }
     }
+
+    /// Handle a key press while the search prompt is open
+    async fn handle_search_input(&mut self, code: crossterm::event::KeyCode) {
+        let mut s = self.state.lock().await;
+        let mut input = s.search_input.clone().unwrap_or_default();
+        match code {
+            crossterm::event::KeyCode::Enter => {
+                *s = s.clone().with_search_input(None).with_log_search(Some(input));
+                let snapshot = s.clone();
+                drop(s);
+                self.current_match = None;
+                self.jump_to_match(&snapshot, true);
+            }
+            crossterm::event::KeyCode::Esc => {
+                *s = s.clone().with_search_input(None);
+            }
+            crossterm::event::KeyCode::Backspace => {
+                input.pop();
+                *s = s.clone().with_search_input(Some(input));
+            }
+            crossterm::event::KeyCode::Char(c) => {
+                input.push(c);
+                *s = s.clone().with_search_input(Some(input));
+            }
+            _ => {}
+        }
+    }
+
+    /// Move the current match forward or backward and scroll it into view
+    fn jump_to_match(&mut self, state: &TuiState, forward: bool) {
+        let query = match state.log_search.as_deref() {
+            Some(q) => q,
+            None => return,
+        };
+        let lines = filter_logs(&state.logs, state.log_filter);
+        let matches = find_matches(&lines, query);
+        if matches.is_empty() {
+            self.current_match = None;
+            return;
+        }
+
+        let next = match (self.current_match, forward) {
+            (None, true) => matches.last().copied(),
+            (None, false) => matches.first().copied(),
+            (Some(cur), true) => matches
+                .iter()
+                .find(|&&i| i > cur)
+                .or(matches.first())
+                .copied(),
+            (Some(cur), false) => matches
+                .iter()
+                .rev()
+                .find(|&&i| i < cur)
+                .or(matches.last())
+                .copied(),
+        };
+
+        if let Some(index) = next {
+            self.current_match = Some(index);
+            self.scroll_offset = lines.len() - 1 - index;
+            self.auto_scroll = false;
+        }
+    }
 }
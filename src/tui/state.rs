@@ -1,12 +1,14 @@
 //! TUI state management
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::schemas::Item;
+use crate::tui::log_filter::LogFilter;
 
 /// Tool execution tracking
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolExecution {
     pub tool_use_id: String,
     pub tool_name: String,
@@ -18,7 +20,8 @@ pub struct ToolExecution {
 }
 
 /// Tool status
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ToolStatus {
     Running,
     Completed,
@@ -26,7 +29,7 @@ pub enum ToolStatus {
 }
 
 /// Agent activity for a specific item
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentActivity {
     pub thoughts: Vec<String>,
     pub tools: Vec<ToolExecution>,
@@ -41,35 +44,68 @@ impl Default for AgentActivity {
     }
 }
 
+/// A state an item entered, and when
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateEntry {
+    pub state: String,
+    pub entered_at: DateTime<Utc>,
+}
+
 /// Item state for TUI display
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ItemState {
     pub id: String,
     pub state: String,
     pub title: String,
     pub current_story_id: Option<String>,
+    /// States entered so far, oldest first, starting with creation
+    pub history: Vec<StateEntry>,
+}
+
+fn parse_time(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
 }
 
 impl From<Item> for ItemState {
     fn from(item: Item) -> Self {
+        let created = parse_time(&item.created_at).map(|entered_at| StateEntry {
+            state: "idea".to_string(),
+            entered_at,
+        });
+        let history = created
+            .into_iter()
+            .chain(item.state_history.iter().filter_map(|change| {
+                Some(StateEntry {
+                    state: change.state.clone(),
+                    entered_at: parse_time(&change.at)?,
+                })
+            }))
+            .collect();
         Self {
             id: item.id,
             state: item.state.to_string(),
             title: item.title,
             current_story_id: None,
+            history,
         }
     }
 }
 
 /// Story tracking
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CurrentStory {
     pub id: String,
     pub title: String,
 }
 
 /// Main TUI state
-#[derive(Debug, Clone)]
+///
+/// Serializes for the saved session view; the view settings (panes,
+/// filters, search, pause) and the finished flag belong to one TUI and are
+/// not saved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TuiState {
     pub current_item: Option<String>,
     pub current_phase: Option<String>,
@@ -81,7 +117,22 @@ pub struct TuiState {
     pub total_count: usize,
     pub start_time: DateTime<Utc>,
     pub logs: Vec<String>,
+    #[serde(skip)]
     pub show_logs: bool,
+    #[serde(skip)]
+    pub show_timeline: bool,
+    #[serde(skip)]
+    pub show_help: bool,
+    #[serde(skip)]
+    pub log_filter: LogFilter,
+    #[serde(skip)]
+    pub log_search: Option<String>,
+    #[serde(skip)]
+    pub search_input: Option<String>,
+    #[serde(skip)]
+    pub paused: bool,
+    #[serde(skip)]
+    pub finished: bool,
     pub activity_by_item: HashMap<String, AgentActivity>,
 }
 
@@ -116,6 +167,13 @@ impl TuiState {
             start_time: Utc::now(),
             logs: Vec::new(),
             show_logs: false,
+            show_timeline: false,
+            show_help: false,
+            log_filter: LogFilter::All,
+            log_search: None,
+            search_input: None,
+            paused: false,
+            finished: false,
             activity_by_item,
         }
     }
@@ -146,9 +204,16 @@ impl TuiState {
         self
     }
 
-    /// Return a new TuiState with an item state updated
+    /// Return a new TuiState with an item state updated, recording the
+    /// change in the item's history
     pub fn with_item_state(mut self, item_id: String, state: String) -> Self {
         if let Some(item) = self.items.iter_mut().find(|i| i.id == item_id) {
+            if item.state != state {
+                item.history.push(StateEntry {
+                    state: state.clone(),
+                    entered_at: Utc::now(),
+                });
+            }
             item.state = state;
         }
         self
@@ -185,6 +250,66 @@ impl TuiState {
         self
     }
 
+    /// Return a new TuiState with the timeline view toggled
+    pub fn with_show_timeline(mut self, show: bool) -> Self {
+        self.show_timeline = show;
+        self
+    }
+
+    /// Return a new TuiState with the help overlay toggled
+    pub fn with_show_help(mut self, show: bool) -> Self {
+        self.show_help = show;
+        self
+    }
+
+    /// Return a new TuiState with the log category filter updated
+    pub fn with_log_filter(mut self, filter: LogFilter) -> Self {
+        self.log_filter = filter;
+        self
+    }
+
+    /// Return a new TuiState with the active log search query updated
+    pub fn with_log_search(mut self, query: Option<String>) -> Self {
+        self.log_search = query.filter(|q| !q.is_empty());
+        self
+    }
+
+    /// Return a new TuiState with the in-progress search input updated
+    ///
+    /// `Some` means the search prompt is open; `None` closes it.
+    pub fn with_search_input(mut self, input: Option<String>) -> Self {
+        self.search_input = input;
+        self
+    }
+
+    /// Return a new TuiState with the paused flag updated
+    pub fn with_paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    /// Return a new TuiState marked as finished (the workflow run has ended)
+    pub fn with_finished(mut self, finished: bool) -> Self {
+        self.finished = finished;
+        self
+    }
+
+    /// Return a new TuiState showing a saved session's progress, keeping
+    /// this TUI's view settings
+    pub fn with_restored(self, saved: TuiState) -> Self {
+        Self {
+            show_logs: self.show_logs,
+            show_timeline: self.show_timeline,
+            show_help: self.show_help,
+            log_filter: self.log_filter,
+            log_search: self.log_search,
+            search_input: self.search_input,
+            paused: self.paused,
+            finished: self.finished,
+            ..saved
+        }
+    }
+
     /// Return a new TuiState with agent activity updated
     pub fn with_agent_activity(mut self, item_id: String, activity: AgentActivity) -> Self {
         self.activity_by_item.insert(item_id, activity);
@@ -243,3 +368,26 @@ impl TuiState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_item_state_records_history() {
+        let item = Item::new("001-a".to_string(), "A".to_string(), String::new());
+        let state = TuiState::new(vec![item]);
+        assert_eq!(state.items[0].history.len(), 1);
+
+        // Creation and the change are both in the history; repeats are not
+        let updated = state
+            .with_item_state("001-a".to_string(), "done".to_string())
+            .with_item_state("001-a".to_string(), "done".to_string());
+        let states: Vec<&str> = updated.items[0]
+            .history
+            .iter()
+            .map(|entry| entry.state.as_str())
+            .collect();
+        assert_eq!(states, vec!["idea", "done"]);
+    }
+}
@@ -0,0 +1,190 @@
+//! Glyphs and colors for the interactive TUI
+//!
+//! The default theme draws box-drawing borders, unicode state icons, and
+//! colors. `tui.ascii` swaps every glyph for plain ASCII and the state icons
+//! for words, which screen readers and dumb terminals handle better. Colors
+//! are dropped when `NO_COLOR` is set to a non-empty value
+//! (<https://no-color.org>), leaving emphasis to bold and reverse video.
+//! The plain renderer shares the ASCII setting for its arrows.
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    symbols::border,
+    widgets::{Block, Borders},
+};
+
+use crate::schemas::TuiConfig;
+
+/// Box borders drawn with ASCII only
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+/// Whether a `NO_COLOR` value asks for colorless output
+pub fn no_color_requested(value: Option<&str>) -> bool {
+    value.is_some_and(|v| !v.is_empty())
+}
+
+/// How the TUI draws text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Use ASCII glyphs and word labels instead of unicode
+    pub ascii: bool,
+
+    /// Use colors
+    pub color: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            ascii: false,
+            color: true,
+        }
+    }
+}
+
+impl Theme {
+    /// The theme for a config, honoring `NO_COLOR` from the environment
+    pub fn from_config(config: &TuiConfig) -> Self {
+        let no_color = std::env::var("NO_COLOR").ok();
+        Theme {
+            ascii: config.ascii,
+            color: !no_color_requested(no_color.as_deref()),
+        }
+    }
+
+    /// A style with the given foreground color, if colors are on
+    pub fn fg(&self, color: Color) -> Style {
+        if self.color {
+            Style::default().fg(color)
+        } else {
+            Style::default()
+        }
+    }
+
+    /// Style for a search match; the current match stands out further
+    pub fn highlight(&self, current: bool) -> Style {
+        match (self.color, current) {
+            (true, true) => Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            (true, false) => Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            (false, true) => Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            (false, false) => Style::default().add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// A bordered block in the frame color
+    pub fn block(&self) -> Block<'static> {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.fg(Color::Cyan));
+        if self.ascii {
+            block.border_set(ASCII_BORDER)
+        } else {
+            block
+        }
+    }
+
+    fn pick(&self, unicode: &'static str, ascii: &'static str) -> &'static str {
+        if self.ascii {
+            ascii
+        } else {
+            unicode
+        }
+    }
+
+    /// Horizontal rule
+    pub fn horizontal(&self) -> &'static str {
+        self.pick("─", "-")
+    }
+
+    /// Vertical rule
+    pub fn vertical(&self) -> &'static str {
+        self.pick("│", "|")
+    }
+
+    /// Top corners, left and right
+    pub fn top_corners(&self) -> (&'static str, &'static str) {
+        (self.pick("┌", "+"), self.pick("┐", "+"))
+    }
+
+    /// Tees joining a separator to the side rules, left and right
+    pub fn tees(&self) -> (&'static str, &'static str) {
+        (self.pick("├", "+"), self.pick("┤", "+"))
+    }
+
+    /// Marks truncated text
+    pub fn ellipsis(&self) -> &'static str {
+        self.pick("…", "...")
+    }
+
+    /// Arrow for a state change
+    pub fn arrow(&self) -> &'static str {
+        self.pick("→", "->")
+    }
+
+    /// Bullet for agent thoughts
+    pub fn bullet(&self) -> &'static str {
+        self.pick("•", "-")
+    }
+
+    /// Filled timeline cell and empty timeline cell
+    pub fn bar_cells(&self) -> (&'static str, &'static str) {
+        (self.pick("█", "#"), self.pick("·", "."))
+    }
+
+    /// Icon (or, in ASCII mode, word) for an item state
+    pub fn state_icon(&self, state: &str) -> &'static str {
+        match state {
+            "done" => self.pick("✓", "done  "),
+            "implementing" | "in_pr" => self.pick("→", "active"),
+            _ => self.pick("○", "todo  "),
+        }
+    }
+
+    /// Icon (or word) for a tool call that is running, finished, or failed
+    pub fn tool_icon(&self, running: bool, failed: bool) -> &'static str {
+        match (running, failed) {
+            (true, _) => self.pick("▶", "run"),
+            (false, false) => self.pick("✓", "ok"),
+            (false, true) => self.pick("✗", "err"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_color_requested() {
+        assert!(!no_color_requested(None));
+        assert!(!no_color_requested(Some("")));
+        assert!(no_color_requested(Some("1")));
+    }
+
+    #[test]
+    fn test_ascii_theme() {
+        let theme = Theme {
+            ascii: true,
+            color: false,
+        };
+        assert_eq!(theme.state_icon("done"), "done  ");
+        assert_eq!(theme.tool_icon(false, true), "err");
+        assert_eq!(theme.top_corners(), ("+", "+"));
+        assert_eq!(theme.fg(Color::Cyan), Style::default());
+        assert!(Theme::default().state_icon("in_pr") == "→");
+    }
+}
@@ -0,0 +1,175 @@
+//! Phase timeline for the TUI's timeline view
+//!
+//! Each item's state history is cut into segments (one per state it sat in)
+//! and clipped to a window, normally the current session. The view draws the
+//! segments as a horizontal bar scaled to the window and lists how long the
+//! item spent in each state.
+
+use chrono::{DateTime, Utc};
+
+use crate::tui::state::StateEntry;
+
+/// Time an item spent in one state
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineSegment {
+    pub state: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl TimelineSegment {
+    /// Length of the segment in seconds
+    pub fn seconds(&self) -> i64 {
+        (self.end - self.start).num_seconds()
+    }
+}
+
+/// Cut a state history into segments clipped to `[window_start, now]`.
+///
+/// The last state runs until `now`; empty segments are dropped.
+pub fn segments(
+    history: &[StateEntry],
+    window_start: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<TimelineSegment> {
+    history
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            let end = history
+                .get(i + 1)
+                .map(|next| next.entered_at)
+                .unwrap_or(now);
+            let start = entry.entered_at.max(window_start);
+            let end = end.min(now);
+            (end > start).then(|| TimelineSegment {
+                state: entry.state.clone(),
+                start,
+                end,
+            })
+        })
+        .collect()
+}
+
+/// Lay segments out on a bar `width` cells wide spanning
+/// `[window_start, now]`, returning runs of (state, cells) with `None` for
+/// time before the item's first segment.
+pub fn bar_runs(
+    segments: &[TimelineSegment],
+    window_start: DateTime<Utc>,
+    now: DateTime<Utc>,
+    width: usize,
+) -> Vec<(Option<String>, usize)> {
+    let total = (now - window_start).num_milliseconds().max(1) as f64;
+    let cell = |at: DateTime<Utc>| {
+        let offset = (at - window_start).num_milliseconds().max(0) as f64;
+        ((offset / total) * width as f64).round().min(width as f64) as usize
+    };
+
+    let mut runs = Vec::new();
+    let mut filled = 0;
+    for segment in segments {
+        let start = cell(segment.start).max(filled);
+        let end = cell(segment.end).max(start);
+        if start > filled {
+            runs.push((None, start - filled));
+        }
+        if end > start {
+            runs.push((Some(segment.state.clone()), end - start));
+        }
+        filled = end;
+    }
+    runs
+}
+
+/// Total seconds per state, in the order the states were first entered
+pub fn state_durations(segments: &[TimelineSegment]) -> Vec<(String, i64)> {
+    let mut totals: Vec<(String, i64)> = Vec::new();
+    for segment in segments {
+        match totals.iter_mut().find(|(state, _)| *state == segment.state) {
+            Some(total) => total.1 += segment.seconds(),
+            None => totals.push((segment.state.clone(), segment.seconds())),
+        }
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn entry(state: &str, time: &str) -> StateEntry {
+        StateEntry {
+            state: state.to_string(),
+            entered_at: at(time),
+        }
+    }
+
+    #[test]
+    fn test_segments_clip_to_window() {
+        let history = vec![
+            entry("idea", "2024-07-01T00:00:00Z"),
+            entry("researched", "2024-07-03T10:00:00Z"),
+            entry("implementing", "2024-07-03T10:30:00Z"),
+        ];
+        let segments = segments(
+            &history,
+            at("2024-07-03T09:00:00Z"),
+            at("2024-07-03T11:00:00Z"),
+        );
+        let spans: Vec<(&str, i64)> = segments
+            .iter()
+            .map(|s| (s.state.as_str(), s.seconds()))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![("idea", 3600), ("researched", 1800), ("implementing", 1800)]
+        );
+
+        let runs = bar_runs(
+            &segments,
+            at("2024-07-03T09:00:00Z"),
+            at("2024-07-03T11:00:00Z"),
+            20,
+        );
+        assert_eq!(
+            runs,
+            vec![
+                (Some("idea".to_string()), 10),
+                (Some("researched".to_string()), 5),
+                (Some("implementing".to_string()), 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bar_runs_pad_late_items() {
+        let history = vec![entry("idea", "2024-07-03T10:00:00Z")];
+        let start = at("2024-07-03T09:00:00Z");
+        let now = at("2024-07-03T11:00:00Z");
+        let runs = bar_runs(&segments(&history, start, now), start, now, 10);
+        assert_eq!(runs, vec![(None, 5), (Some("idea".to_string()), 5)]);
+    }
+
+    #[test]
+    fn test_state_durations() {
+        let history = vec![
+            entry("implementing", "2024-07-03T10:00:00Z"),
+            entry("blocked_review", "2024-07-03T10:10:00Z"),
+            entry("implementing", "2024-07-03T10:20:00Z"),
+        ];
+        let start = at("2024-07-03T10:00:00Z");
+        let durations = state_durations(&segments(&history, start, at("2024-07-03T10:25:00Z")));
+        assert_eq!(
+            durations,
+            vec![
+                ("implementing".to_string(), 900),
+                ("blocked_review".to_string(), 600)
+            ]
+        );
+    }
+}
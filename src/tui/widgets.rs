@@ -4,14 +4,19 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Clear, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
+use crate::domain::{format_age, format_duration};
+use crate::tui::keymap::{Action, Keymap};
+use crate::tui::log_filter::{filter_logs, find_matches, highlight_segments};
 use crate::tui::state::{AgentActivity, ToolStatus, TuiState};
+use crate::tui::theme::Theme;
+use crate::tui::timeline::{bar_runs, segments, state_durations};
 
 /// Render the header section (5 lines)
-pub fn render_header(f: &mut Frame, area: Rect, state: &TuiState) {
+pub fn render_header(f: &mut Frame, area: Rect, state: &TuiState, theme: &Theme) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -25,13 +30,15 @@ pub fn render_header(f: &mut Frame, area: Rect, state: &TuiState) {
 
     // Title line
     let border_width = area.width as usize;
+    let (left, right) = theme.top_corners();
+    let (left_tee, right_tee) = theme.tees();
     let title = Line::from(vec![
-        Span::styled("┌─ Wreckit ", Style::default().fg(Color::Cyan)),
+        Span::styled(format!("{}{} Wreckit ", left, theme.horizontal()), theme.fg(Color::Cyan)),
         Span::styled(
-            "─".repeat(border_width.saturating_sub(12)),
-            Style::default().fg(Color::Cyan),
+            theme.horizontal().repeat(border_width.saturating_sub(12)),
+            theme.fg(Color::Cyan),
         ),
-        Span::styled("┐", Style::default().fg(Color::Cyan)),
+        Span::styled(right, theme.fg(Color::Cyan)),
     ]);
     let title_paragraph = Paragraph::new(Text::from(title)).alignment(Alignment::Left);
     f.render_widget(title_paragraph, chunks[0]);
@@ -43,30 +50,33 @@ pub fn render_header(f: &mut Frame, area: Rect, state: &TuiState) {
         .map(|id| format!("Running: {}", id))
         .unwrap_or_else(|| "Waiting...".to_string());
     let item_line = Line::from(vec![
-        Span::styled("│ ", Style::default().fg(Color::Cyan)),
+        Span::styled(format!("{} ", theme.vertical()), theme.fg(Color::Cyan)),
         Span::styled(
-            pad_to_width(&current_item_text, border_width.saturating_sub(4)),
+            pad_to_width(theme, &current_item_text, border_width.saturating_sub(4)),
             Style::default(),
         ),
-        Span::styled(" │", Style::default().fg(Color::Cyan)),
+        Span::styled(format!(" {}", theme.vertical()), theme.fg(Color::Cyan)),
     ]);
     let item_paragraph = Paragraph::new(Text::from(item_line));
     f.render_widget(item_paragraph, chunks[1]);
 
     // Phase line
-    let phase_text = state.current_phase.as_ref().map(|phase| {
+    let mut phase_text = state.current_phase.as_ref().map(|phase| {
         format!(
             "Phase: {} (iteration {}/{})",
             phase, state.current_iteration, state.max_iterations
         )
     }).unwrap_or_else(|| "Phase: idle".to_string());
+    if state.paused {
+        phase_text.push_str(" [paused]");
+    }
     let phase_line = Line::from(vec![
-        Span::styled("│ ", Style::default().fg(Color::Cyan)),
+        Span::styled(format!("{} ", theme.vertical()), theme.fg(Color::Cyan)),
         Span::styled(
-            pad_to_width(&phase_text, border_width.saturating_sub(4)),
+            pad_to_width(theme, &phase_text, border_width.saturating_sub(4)),
             Style::default(),
         ),
-        Span::styled(" │", Style::default().fg(Color::Cyan)),
+        Span::styled(format!(" {}", theme.vertical()), theme.fg(Color::Cyan)),
     ]);
     let phase_paragraph = Paragraph::new(Text::from(phase_line));
     f.render_widget(phase_paragraph, chunks[2]);
@@ -76,36 +86,36 @@ pub fn render_header(f: &mut Frame, area: Rect, state: &TuiState) {
         format!("Story: {} - {}", story.id, story.title)
     }).unwrap_or_else(|| "Story: none".to_string());
     let story_line = Line::from(vec![
-        Span::styled("│ ", Style::default().fg(Color::Cyan)),
+        Span::styled(format!("{} ", theme.vertical()), theme.fg(Color::Cyan)),
         Span::styled(
-            pad_to_width(&story_text, border_width.saturating_sub(4)),
+            pad_to_width(theme, &story_text, border_width.saturating_sub(4)),
             Style::default(),
         ),
-        Span::styled(" │", Style::default().fg(Color::Cyan)),
+        Span::styled(format!(" {}", theme.vertical()), theme.fg(Color::Cyan)),
     ]);
     let story_paragraph = Paragraph::new(Text::from(story_line));
     f.render_widget(story_paragraph, chunks[3]);
 
     // Separator line
     let separator = Line::from(vec![
-        Span::styled("├", Style::default().fg(Color::Cyan)),
+        Span::styled(left_tee, theme.fg(Color::Cyan)),
         Span::styled(
-            "─".repeat(border_width.saturating_sub(2)),
-            Style::default().fg(Color::Cyan),
+            theme.horizontal().repeat(border_width.saturating_sub(2)),
+            theme.fg(Color::Cyan),
         ),
-        Span::styled("┤", Style::default().fg(Color::Cyan)),
+        Span::styled(right_tee, theme.fg(Color::Cyan)),
     ]);
     let separator_paragraph = Paragraph::new(Text::from(separator));
     f.render_widget(separator_paragraph, chunks[4]);
 }
 
 /// Render the items pane (left side)
-pub fn render_items_pane(f: &mut Frame, area: Rect, state: &TuiState) {
+pub fn render_items_pane(f: &mut Frame, area: Rect, state: &TuiState, theme: &Theme) {
     let items: Vec<ListItem> = state
         .items
         .iter()
         .map(|item| {
-            let icon = get_state_icon(&item.state);
+            let icon = theme.state_icon(&item.state);
             let color = get_state_color(&item.state);
 
             let story_info = item
@@ -119,26 +129,34 @@ pub fn render_items_pane(f: &mut Frame, area: Rect, state: &TuiState) {
                 icon, item.id, item.state, story_info
             );
 
-            ListItem::new(Line::from(vec![Span::styled(text, Style::default().fg(color))]))
+            ListItem::new(Line::from(vec![Span::styled(text, theme.fg(color))]))
         })
         .collect();
 
     let list = List::new(items).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan)),
+        theme.block(),
     );
 
     f.render_widget(list, area);
 }
 
 /// Render the active item pane (top right)
-pub fn render_active_item_pane(f: &mut Frame, area: Rect, state: &TuiState) {
+pub fn render_active_item_pane(f: &mut Frame, area: Rect, state: &TuiState, theme: &Theme) {
     let text = if let Some(ref item_id) = state.current_item {
         if let Some(item) = state.items.iter().find(|i| &i.id == item_id) {
+            let since = item
+                .history
+                .last()
+                .map(|entry| {
+                    format!(
+                        " ({})",
+                        format_age((chrono::Utc::now() - entry.entered_at).num_seconds())
+                    )
+                })
+                .unwrap_or_default();
             format!(
-                "Current Item: {}\nState: {}\n\n{}",
-                item.id, item.state, item.title
+                "Current Item: {}\nState: {}{}\n\n{}",
+                item.id, item.state, since, item.title
             )
         } else {
             "Item not found".to_string()
@@ -149,9 +167,7 @@ pub fn render_active_item_pane(f: &mut Frame, area: Rect, state: &TuiState) {
 
     let paragraph = Paragraph::new(text)
         .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
+            theme.block()
                 .title("Active Item"),
         )
         .wrap(Wrap { trim: false });
@@ -160,23 +176,22 @@ pub fn render_active_item_pane(f: &mut Frame, area: Rect, state: &TuiState) {
 }
 
 /// Render the agent activity pane (bottom right)
-pub fn render_agent_activity_pane(f: &mut Frame, area: Rect, state: &TuiState) {
+pub fn render_agent_activity_pane(f: &mut Frame, area: Rect, state: &TuiState, theme: &Theme) {
     let text = if let Some(ref item_id) = state.current_item {
         if let Some(activity) = state.activity_by_item.get(item_id) {
             let mut lines = Vec::new();
 
             // Add thoughts
             for thought in &activity.thoughts {
-                lines.push(format!("• {}", thought));
+                lines.push(format!("{} {}", theme.bullet(), thought));
             }
 
             // Add tools
             for tool in &activity.tools {
-                let status_symbol = match tool.status {
-                    ToolStatus::Running => "▶",
-                    ToolStatus::Completed => "✓",
-                    ToolStatus::Error => "✗",
-                };
+                let status_symbol = theme.tool_icon(
+                    tool.status == ToolStatus::Running,
+                    tool.status == ToolStatus::Error,
+                );
                 lines.push(format!("{} {}", status_symbol, tool.tool_name));
             }
 
@@ -194,9 +209,7 @@ pub fn render_agent_activity_pane(f: &mut Frame, area: Rect, state: &TuiState) {
 
     let paragraph = Paragraph::new(text)
         .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
+            theme.block()
                 .title("Agent Activity"),
         )
         .wrap(Wrap { trim: false });
@@ -205,38 +218,193 @@ pub fn render_agent_activity_pane(f: &mut Frame, area: Rect, state: &TuiState) {
 }
 
 /// Render the logs pane (full width when toggled)
-pub fn render_logs_pane(f: &mut Frame, area: Rect, state: &TuiState, scroll_offset: usize) {
-    let max_log_lines = area.height as usize;
-
-    let logs: Vec<ListItem> = if state.logs.is_empty() {
-        vec![ListItem::new("(no output yet)")]
-    } else {
-        let start = if scroll_offset + max_log_lines > state.logs.len() {
-            0.max(state.logs.len() - max_log_lines)
+///
+/// Lines are narrowed by the active category filter and search matches are
+/// highlighted. `scroll_offset` counts lines up from the bottom of the
+/// filtered output; `current_match` is an index into the filtered lines.
+pub fn render_logs_pane(
+    f: &mut Frame,
+    area: Rect,
+    state: &TuiState,
+    scroll_offset: usize,
+    current_match: Option<usize>,
+    theme: &Theme,
+) {
+    let max_log_lines = area.height.saturating_sub(2) as usize;
+    let lines = filter_logs(&state.logs, state.log_filter);
+    let query = state.log_search.as_deref().unwrap_or("");
+
+    let logs: Vec<ListItem> = if lines.is_empty() {
+        let placeholder = if state.logs.is_empty() {
+            "(no output yet)"
         } else {
-            scroll_offset
+            "(no lines match the current filter)"
         };
+        vec![ListItem::new(placeholder)]
+    } else {
+        let end = lines.len().saturating_sub(scroll_offset).max(max_log_lines.min(lines.len()));
+        let start = end.saturating_sub(max_log_lines);
 
-        let end = (start + max_log_lines).min(state.logs.len());
-
-        state.logs[start..end]
+        lines[start..end]
             .iter()
-            .map(|log| ListItem::new(log.as_str()))
+            .enumerate()
+            .map(|(offset, line)| {
+                let index = start + offset;
+                let match_style = theme.highlight(current_match == Some(index));
+                let spans: Vec<Span> = highlight_segments(line, query)
+                    .into_iter()
+                    .map(|(text, is_match)| {
+                        if is_match {
+                            Span::styled(text, match_style)
+                        } else {
+                            Span::raw(text)
+                        }
+                    })
+                    .collect();
+                ListItem::new(Line::from(spans))
+            })
             .collect()
     };
 
+    let mut title = format!("Agent Output [{}]", state.log_filter.label());
+    if !query.is_empty() {
+        let match_count = find_matches(&lines, query).len();
+        title.push_str(&format!(" /{} ({} matches)", query, match_count));
+    }
+
     let list = List::new(logs).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan))
-            .title("Agent Output"),
+        theme.block()
+            .title(title),
+    );
+
+    f.render_widget(list, area);
+}
+
+/// Render the timeline pane (full width when toggled)
+///
+/// Each item gets a bar spanning the session so far, colored by the state it
+/// was in at each point, and a line with the time spent in each state.
+pub fn render_timeline_pane(f: &mut Frame, area: Rect, state: &TuiState, theme: &Theme) {
+    let now = chrono::Utc::now();
+    let label_width = 24;
+    let bar_width = (area.width as usize).saturating_sub(label_width + 3);
+    let (filled, empty) = theme.bar_cells();
+
+    let rows: Vec<ListItem> = state
+        .items
+        .iter()
+        .map(|item| {
+            let segments = segments(&item.history, state.start_time, now);
+            let mut bar = vec![Span::raw(format!(
+                "{} ",
+                pad_to_width(theme, &item.id, label_width)
+            ))];
+            for (run_state, cells) in bar_runs(&segments, state.start_time, now, bar_width) {
+                bar.push(match run_state {
+                    Some(ref run_state) => Span::styled(
+                        filled.repeat(cells),
+                        theme.fg(get_timeline_color(run_state)),
+                    ),
+                    None => Span::styled(empty.repeat(cells), theme.fg(Color::DarkGray)),
+                });
+            }
+
+            let mut durations = vec![Span::raw(" ".repeat(label_width + 1))];
+            for (i, (run_state, seconds)) in state_durations(&segments).into_iter().enumerate() {
+                if i > 0 {
+                    durations.push(Span::raw("  "));
+                }
+                durations.push(Span::styled(
+                    run_state.clone(),
+                    theme.fg(get_timeline_color(&run_state)),
+                ));
+                durations.push(Span::raw(format!(" {}", format_duration(seconds))));
+            }
+            ListItem::new(Text::from(vec![Line::from(bar), Line::from(durations)]))
+        })
+        .collect();
+
+    let title = format!(
+        "Timeline since {} ({})",
+        state.start_time.format("%H:%M UTC"),
+        format_runtime(state.start_time)
+    );
+    let list = List::new(rows).block(
+        theme.block()
+            .title(title),
     );
 
     f.render_widget(list, area);
 }
 
+/// What each pane shows, for the help overlay
+const PANE_DESCRIPTIONS: [(&str, &str); 5] = [
+    ("Items", "Every item and its workflow state"),
+    ("Active Item", "The item being worked on and its state"),
+    ("Agent Activity", "The agent's recent thoughts and tool calls"),
+    ("Agent Output", "Raw agent output, with a category filter and search"),
+    ("Timeline", "How long each item spent in each state this session"),
+];
+
+/// Render the help overlay centered over `area`: key bindings, what each
+/// pane shows, and the config settings that shape the run
+pub fn render_help_overlay(
+    f: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    keymap: &Keymap,
+    highlights: &[(String, String)],
+) {
+    let heading = theme.fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let mut lines = vec![Line::from(Span::styled("Keys", heading))];
+    for action in Action::ALL {
+        lines.push(Line::from(format!(
+            "  {:<18} {}",
+            keymap.label(action),
+            action.description()
+        )));
+    }
+    lines.push(Line::from("  ctrl-c             Quit"));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Panes", heading)));
+    for (pane, description) in PANE_DESCRIPTIONS {
+        lines.push(Line::from(format!("  {:<18} {}", pane, description)));
+    }
+    if !highlights.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Config", heading)));
+        for (label, value) in highlights {
+            lines.push(Line::from(format!("  {:<18} {}", label, value)));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from("Press any key to close"));
+
+    let width = area.width.saturating_sub(4).min(84);
+    let height = area.height.saturating_sub(2).min(lines.len() as u16 + 2);
+    let overlay = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(theme.block().title("Help"))
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, overlay);
+    f.render_widget(paragraph, overlay);
+}
+
 /// Render the footer section (4 lines)
-pub fn render_footer(f: &mut Frame, area: Rect, state: &TuiState, show_logs: bool) {
+pub fn render_footer(
+    f: &mut Frame,
+    area: Rect,
+    state: &TuiState,
+    show_logs: bool,
+    theme: &Theme,
+    keymap: &Keymap,
+    show_hint: bool,
+) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -248,15 +416,16 @@ pub fn render_footer(f: &mut Frame, area: Rect, state: &TuiState, show_logs: boo
         .split(area);
 
     let border_width = area.width as usize;
+    let (left_tee, right_tee) = theme.tees();
 
     // Separator line
     let separator = Line::from(vec![
-        Span::styled("├", Style::default().fg(Color::Cyan)),
+        Span::styled(left_tee, theme.fg(Color::Cyan)),
         Span::styled(
-            "─".repeat(border_width.saturating_sub(2)),
-            Style::default().fg(Color::Cyan),
+            theme.horizontal().repeat(border_width.saturating_sub(2)),
+            theme.fg(Color::Cyan),
         ),
-        Span::styled("┤", Style::default().fg(Color::Cyan)),
+        Span::styled(right_tee, theme.fg(Color::Cyan)),
     ]);
     let separator_paragraph = Paragraph::new(Text::from(separator));
     f.render_widget(separator_paragraph, chunks[0]);
@@ -269,38 +438,79 @@ pub fn render_footer(f: &mut Frame, area: Rect, state: &TuiState, show_logs: boo
         format_runtime(state.start_time)
     );
     let progress_line = Line::from(vec![
-        Span::styled("│ ", Style::default().fg(Color::Cyan)),
+        Span::styled(format!("{} ", theme.vertical()), theme.fg(Color::Cyan)),
         Span::styled(
-            pad_to_width(&progress_text, border_width.saturating_sub(4)),
+            pad_to_width(theme, &progress_text, border_width.saturating_sub(4)),
             Style::default(),
         ),
-        Span::styled(" │", Style::default().fg(Color::Cyan)),
+        Span::styled(format!(" {}", theme.vertical()), theme.fg(Color::Cyan)),
     ]);
     let progress_paragraph = Paragraph::new(Text::from(progress_line));
     f.render_widget(progress_paragraph, chunks[1]);
 
-    // Empty line
-    let empty_line = Line::from(vec![
-        Span::styled("│ ", Style::default().fg(Color::Cyan)),
+    // Hint line: empty unless this is the first run
+    let hint_text = if show_hint {
+        format!(
+            "New here? Press [{}] for keys and panes; [{}] shows the agent output",
+            keymap.short_label(Action::Help),
+            keymap.short_label(Action::ToggleLogs)
+        )
+    } else {
+        String::new()
+    };
+    let hint_line = Line::from(vec![
+        Span::styled(format!("{} ", theme.vertical()), theme.fg(Color::Cyan)),
         Span::styled(
-            pad_to_width("", border_width.saturating_sub(4)),
-            Style::default(),
+            pad_to_width(theme, &hint_text, border_width.saturating_sub(4)),
+            theme.fg(Color::Yellow),
         ),
-        Span::styled(" │", Style::default().fg(Color::Cyan)),
+        Span::styled(format!(" {}", theme.vertical()), theme.fg(Color::Cyan)),
     ]);
-    let empty_paragraph = Paragraph::new(Text::from(empty_line));
-    f.render_widget(empty_paragraph, chunks[2]);
+    let hint_paragraph = Paragraph::new(Text::from(hint_line));
+    f.render_widget(hint_paragraph, chunks[2]);
 
     // Keyboard shortcuts line
     let logs_label = if show_logs { "items" } else { "logs" };
-    let keys_text = format!("[q] quit  [l] {}", logs_label);
+    let key = |action| keymap.short_label(action);
+    let keys_text = match state.search_input {
+        Some(ref input) => format!("/{}  [enter] search  [esc] cancel", input),
+        None if show_logs => format!(
+            "[{}] quit  [{}] {}  [{}] search  [{}/{}] next/prev  [{}] filter: {}",
+            key(Action::Quit),
+            key(Action::ToggleLogs),
+            logs_label,
+            key(Action::Search),
+            key(Action::NextMatch),
+            key(Action::PrevMatch),
+            key(Action::CycleFilter),
+            state.log_filter.label()
+        ),
+        None if state.show_timeline => format!(
+            "[{}] quit  [{}] {}  [{}] items",
+            key(Action::Quit),
+            key(Action::ToggleLogs),
+            logs_label,
+            key(Action::ToggleTimeline)
+        ),
+        None => format!(
+            "[{}] quit  [{}] {}  [{}] timeline  [{}] {}  [{}] cancel item  [{}] help",
+            key(Action::Quit),
+            key(Action::ToggleLogs),
+            logs_label,
+            key(Action::ToggleTimeline),
+            key(Action::Pause),
+            if state.paused { "resume" } else { "pause" },
+            key(Action::CancelItem),
+            key(Action::Help)
+        ),
+    };
     let keys_line = Line::from(vec![
-        Span::styled("│ ", Style::default().fg(Color::Cyan)),
+        Span::styled(format!("{} ", theme.vertical()), theme.fg(Color::Cyan)),
         Span::styled(
-            pad_to_width(&keys_text, border_width.saturating_sub(4)),
+            pad_to_width(theme, &keys_text, border_width.saturating_sub(4)),
             Style::default(),
         ),
-        Span::styled(" │", Style::default().fg(Color::Cyan)),
+        Span::styled(format!(" {}", theme.vertical()), theme.fg(Color::Cyan)),
     ]);
     let keys_paragraph = Paragraph::new(Text::from(keys_line));
     f.render_widget(keys_paragraph, chunks[3]);
@@ -308,15 +518,6 @@ pub fn render_footer(f: &mut Frame, area: Rect, state: &TuiState, show_logs: boo
 
 // ===== HELPER FUNCTIONS =====
 
-/// Get state icon
-fn get_state_icon(state: &str) -> &'static str {
-    match state {
-        "done" => "✓",
-        "implementing" | "in_pr" => "→",
-        _ => "○",
-    }
-}
-
 /// Get state color
 fn get_state_color(state: &str) -> Color {
     match state {
@@ -326,10 +527,26 @@ fn get_state_color(state: &str) -> Color {
     }
 }
 
+/// Get the timeline bar color for a state
+fn get_timeline_color(state: &str) -> Color {
+    match state {
+        "idea" => Color::DarkGray,
+        "researched" => Color::Cyan,
+        "planned" => Color::Blue,
+        "implementing" => Color::Yellow,
+        "in_pr" => Color::Magenta,
+        "done" => Color::Green,
+        "abandoned" => Color::Gray,
+        _ => Color::LightMagenta,
+    }
+}
+
 /// Pad string to width (truncate with ellipsis if too long)
-fn pad_to_width(text: &str, width: usize) -> String {
-    if text.len() > width {
-        format!("{}…", &text[..width - 1])
+fn pad_to_width(theme: &Theme, text: &str, width: usize) -> String {
+    if text.chars().count() > width {
+        let ellipsis = theme.ellipsis();
+        let keep = width.saturating_sub(ellipsis.chars().count());
+        format!("{}{}", text.chars().take(keep).collect::<String>(), ellipsis)
     } else {
         format!("{:<width$}", text, width = width)
     }
@@ -347,3 +564,60 @@ fn format_runtime(start_time: chrono::DateTime<chrono::Utc>) -> String {
 
     format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_to_width() {
+        let theme = Theme {
+            ascii: false,
+            color: false,
+        };
+        assert_eq!(pad_to_width(&theme, "abc", 5), "abc  ");
+        assert_eq!(pad_to_width(&theme, "abcdef", 5), "abcd…");
+        // Multibyte titles are cut on characters, not bytes
+        assert_eq!(pad_to_width(&theme, "Überprüfung", 5), "Über…");
+        assert_eq!(pad_to_width(&theme, "日本語", 3), "日本語");
+        assert_eq!(pad_to_width(&theme, "日本語のタイトル", 4), "日本語…");
+    }
+
+    fn render_text(draw: impl FnOnce(&mut Frame, Rect)) -> String {
+        let backend = ratatui::backend::TestBackend::new(100, 40);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|f| draw(f, f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_help_overlay_lists_keys_panes_and_config() {
+        let theme = Theme::default();
+        let keymap = Keymap::default();
+        let highlights = vec![("Base branch".to_string(), "trunk".to_string())];
+        let text =
+            render_text(|f, area| render_help_overlay(f, area, &theme, &keymap, &highlights));
+        assert!(text.contains("Help"));
+        assert!(text.contains(Action::Help.description()));
+        assert!(text.contains("Agent Activity"));
+        assert!(text.contains("trunk"));
+    }
+
+    #[test]
+    fn test_footer_shows_first_run_hint() {
+        let theme = Theme::default();
+        let keymap = Keymap::default();
+        let state = TuiState::new(Vec::new());
+        let footer = |hint| {
+            render_text(|f, area| render_footer(f, area, &state, false, &theme, &keymap, hint))
+        };
+        assert!(footer(true).contains("New here? Press [?]"));
+        assert!(!footer(false).contains("New here?"));
+    }
+}
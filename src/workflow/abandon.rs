@@ -0,0 +1,129 @@
+//! Abandoning items
+//!
+//! Stops work on an item cleanly: closes its PR with a comment, deletes the
+//! item branch locally and on origin, and moves the item to the terminal
+//! `abandoned` state. The item's artifacts stay in `.wreckit/items/<id>` for
+//! reference.
+
+use crate::domain::is_terminal_state;
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git;
+use crate::schemas::{Item, WorkflowState};
+use crate::tui::runner::TuiUpdate;
+
+use super::context::WorkflowContext;
+
+/// Build the comment left on the closed PR
+pub fn abandon_comment(reason: Option<&str>) -> String {
+    match reason {
+        Some(reason) => format!("Abandoned by wreckit: {}", reason),
+        None => "Abandoned by wreckit.".to_string(),
+    }
+}
+
+/// Abandon an item, cleaning up its PR and branches.
+///
+/// # Errors
+/// * `FileNotFound` - If the item does not exist
+/// * `StateTransition` - If the item is already done or abandoned
+/// * `GitError` - If the PR cannot be closed or a branch cannot be deleted
+pub async fn abandon_item(ctx: &WorkflowContext, id: &str, reason: Option<&str>) -> Result<Item> {
+    let item = fs::read_item(&ctx.root, id)?;
+    if is_terminal_state(item.state) {
+        return Err(WreckitError::StateTransition(format!(
+            "{} is already {}",
+            id, item.state
+        )));
+    }
+
+    let options = ctx.git_options();
+    if let Some(number) = item.pr_number {
+        git::close_pr(number, &abandon_comment(reason), &options).await?;
+        tracing::info!("Closed PR #{}", number);
+    }
+
+    if git::is_git_repo(&ctx.root).await {
+        let branch = ctx.branch_name(&item);
+        if git::branch_exists(&branch, &options).await {
+            if git::get_current_branch(&options).await? == branch {
+                git::run_git_command(&["checkout", &ctx.config.base_branch], &options).await?;
+            }
+            git::delete_branch(&branch, &options).await?;
+            tracing::info!("Deleted branch {}", branch);
+        }
+        if git::remote_branch_exists(&branch, &options).await {
+            git::delete_remote_branch(&branch, &options).await?;
+            tracing::info!("Deleted origin/{}", branch);
+        }
+    }
+
+    let item = item
+        .with_state(WorkflowState::Abandoned)
+        .with_blocked(None)
+        .with_error(None);
+    ctx.save_item(&item)?;
+    ctx.emit(TuiUpdate::AppendLogs(vec![format!(
+        "[INFO] {} abandoned",
+        item.id
+    )]));
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use tempfile::TempDir;
+
+    fn git(dir: &std::path::Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_abandon_comment() {
+        assert_eq!(
+            abandon_comment(Some("superseded by 004")),
+            "Abandoned by wreckit: superseded by 004"
+        );
+        assert_eq!(abandon_comment(None), "Abandoned by wreckit.");
+    }
+
+    #[tokio::test]
+    async fn test_abandon_deletes_branch_and_keeps_artifacts() {
+        let temp = TempDir::new().unwrap();
+        git(temp.path(), &["init", "-q", "-b", "main"]);
+        git(temp.path(), &["config", "user.email", "test@example.com"]);
+        git(temp.path(), &["config", "user.name", "Test"]);
+        git(
+            temp.path(),
+            &["commit", "-q", "--allow-empty", "-m", "init"],
+        );
+        git(temp.path(), &["checkout", "-q", "-b", "wreckit/001-test"]);
+
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), Config::default());
+        let item = Item::new("001-test".to_string(), "Test".to_string(), String::new())
+            .with_state(WorkflowState::Implementing);
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+        std::fs::write(fs::get_research_path(temp.path(), &item.id), "# R").unwrap();
+
+        let item = abandon_item(&ctx, "001-test", Some("no longer needed"))
+            .await
+            .unwrap();
+        assert_eq!(item.state, WorkflowState::Abandoned);
+
+        let options = ctx.git_options();
+        assert_eq!(git::get_current_branch(&options).await.unwrap(), "main");
+        assert!(!git::branch_exists("wreckit/001-test", &options).await);
+        assert!(fs::get_research_path(temp.path(), "001-test").exists());
+
+        let err = abandon_item(&ctx, "001-test", None).await.unwrap_err();
+        assert!(matches!(err, WreckitError::StateTransition(_)));
+    }
+}
@@ -0,0 +1,91 @@
+//! Item assignment for teams sharing one backlog
+//!
+//! Each item may carry an `assignee`. `wreckit claim` assigns an item to the
+//! current identity (config `identity`, else git user.email), `wreckit assign`
+//! sets or clears it explicitly, and `next --mine` only picks the caller's
+//! items.
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git;
+use crate::schemas::Item;
+
+use super::context::WorkflowContext;
+
+/// The identity of whoever is running wreckit.
+///
+/// # Errors
+/// * `ConfigError` - If neither `identity` nor git user.email is set
+pub async fn resolve_identity(ctx: &WorkflowContext) -> Result<String> {
+    if let Some(ref identity) = ctx.config.identity {
+        return Ok(identity.clone());
+    }
+    git::get_user_email(&ctx.git_options())
+        .await
+        .ok_or_else(|| {
+            WreckitError::ConfigError(
+                "No identity: set \"identity\" in config.json or git user.email".to_string(),
+            )
+        })
+}
+
+/// Set or clear an item's assignee.
+///
+/// # Errors
+/// * `FileNotFound` - If the item does not exist
+pub fn assign_item(ctx: &WorkflowContext, id: &str, assignee: Option<&str>) -> Result<Item> {
+    let item = fs::read_item(&ctx.root, id)?.with_assignee(assignee.map(String::from));
+    ctx.save_item(&item)?;
+    Ok(item)
+}
+
+/// Assign an item to the current identity.
+///
+/// # Errors
+/// * `ConfigError` - If no identity is configured
+/// * `StateTransition` - If someone else already holds the item
+pub async fn claim_item(ctx: &WorkflowContext, id: &str) -> Result<Item> {
+    let me = resolve_identity(ctx).await?;
+    let item = fs::read_item(&ctx.root, id)?;
+    match item.assignee {
+        Some(ref owner) if owner == &me => Ok(item),
+        Some(ref owner) => Err(WreckitError::StateTransition(format!(
+            "{} is assigned to {} (use `wreckit assign` to reassign)",
+            id, owner
+        ))),
+        None => assign_item(ctx, id, Some(&me)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_claim_and_assign() {
+        let temp = TempDir::new().unwrap();
+        let config = Config {
+            identity: Some("ana@example.com".to_string()),
+            ..Default::default()
+        };
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        let item = Item::new("001".to_string(), "a".to_string(), String::new());
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+
+        assert_eq!(resolve_identity(&ctx).await.unwrap(), "ana@example.com");
+        let item = claim_item(&ctx, "001").await.unwrap();
+        assert_eq!(item.assignee.as_deref(), Some("ana@example.com"));
+
+        assign_item(&ctx, "001", Some("bo@example.com")).unwrap();
+        let err = claim_item(&ctx, "001").await.unwrap_err();
+        assert!(err.to_string().contains("assigned to bo@example.com"));
+
+        assert_eq!(assign_item(&ctx, "001", None).unwrap().assignee, None);
+        assert!(fs::read_item(temp.path(), "001")
+            .unwrap()
+            .assignee
+            .is_none());
+    }
+}
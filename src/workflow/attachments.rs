@@ -0,0 +1,152 @@
+//! PR attachments
+//!
+//! With `pr.attachments`, the implement prompt asks the agent to save
+//! screenshots and generated reports to the item's attachments/ directory.
+//! When the PR is opened, the files are committed to the
+//! `wreckit/attachments` branch (never the item branch), pushed, and linked
+//! from an Attachments section of the PR body, with images shown inline.
+
+use std::path::{Path, PathBuf};
+
+use crate::errors::Result;
+use crate::fs;
+use crate::git;
+use crate::schemas::Item;
+
+use super::context::WorkflowContext;
+
+/// Branch the attachments are pushed to
+pub const ATTACHMENTS_BRANCH: &str = "wreckit/attachments";
+
+/// Extensions GitHub renders inline as images
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg"];
+
+/// Files in an item's attachments directory, sorted by name
+pub fn list_attachments(root: &Path, id: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(fs::get_attachments_dir(root, id))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+fn is_image(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Markdown section linking each attachment (repository path, URL), with
+/// images embedded
+pub fn attachments_section(files: &[(String, String)]) -> String {
+    let links: Vec<String> = files
+        .iter()
+        .map(|(path, url)| {
+            let name = Path::new(path)
+                .file_name()
+                .map_or(path.clone(), |n| n.to_string_lossy().to_string());
+            if is_image(path) {
+                format!("![{}]({})", name, url)
+            } else {
+                format!("[{}]({})", name, url)
+            }
+        })
+        .collect();
+    format!("## Attachments\n\n{}", links.join("\n\n"))
+}
+
+/// Push an item's attachments and return the PR body section linking them.
+///
+/// Returns None when `pr.attachments` is off, the forge is offline, or the
+/// item has none.
+///
+/// # Errors
+/// * `GitError` - If committing or pushing the attachments branch fails, or
+///   the repository URL cannot be read with gh
+pub async fn upload_attachments(ctx: &WorkflowContext, item: &Item) -> Result<Option<String>> {
+    if !ctx.config.pr.attachments || ctx.offline {
+        return Ok(None);
+    }
+    let files = list_attachments(&ctx.root, &item.id);
+    if files.is_empty() {
+        return Ok(None);
+    }
+    let options = ctx.git_options();
+    let dir = fs::get_attachments_dir(&ctx.root, &item.id);
+    let dir = dir.strip_prefix(&ctx.root).unwrap_or(&dir);
+    let pathspec = dir.to_string_lossy().to_string();
+    git::commit_paths_to_branch(
+        ATTACHMENTS_BRANCH,
+        &[pathspec.as_str()],
+        &format!("wreckit({}): attachments", item.id),
+        &options,
+    )
+    .await?;
+    let Some(commit) =
+        git::resolve_ref(&format!("refs/heads/{}", ATTACHMENTS_BRANCH), &options).await
+    else {
+        return Ok(None);
+    };
+    git::run_git_command(&["push", "origin", ATTACHMENTS_BRANCH], &options).await?;
+    let repo_url =
+        git::run_gh_command(&["repo", "view", "--json", "url", "--jq", ".url"], &options).await?;
+
+    let links: Vec<(String, String)> = files
+        .iter()
+        .filter_map(|file| file.strip_prefix(&ctx.root).ok())
+        .map(|path| {
+            let path = path.to_string_lossy().replace('\\', "/");
+            let url = format!(
+                "{}/blob/{}/{}?raw=true",
+                repo_url.trim(),
+                commit,
+                path.replace(' ', "%20")
+            );
+            (path, url)
+        })
+        .collect();
+    tracing::info!("Attached {} file(s) to the PR for {}", links.len(), item.id);
+    Ok(Some(attachments_section(&links)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachments_section() {
+        let temp = tempfile::tempdir().unwrap();
+        assert!(list_attachments(temp.path(), "001-a").is_empty());
+        let dir = fs::get_attachments_dir(temp.path(), "001-a");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("login.png"), "png").unwrap();
+        std::fs::write(dir.join("coverage.html"), "html").unwrap();
+        let names: Vec<_> = list_attachments(temp.path(), "001-a")
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["coverage.html", "login.png"]);
+
+        let section = attachments_section(&[
+            (
+                ".wreckit/items/001-a/attachments/login.PNG".to_string(),
+                "https://github.com/o/r/blob/abc/login.PNG?raw=true".to_string(),
+            ),
+            (
+                ".wreckit/items/001-a/attachments/coverage.html".to_string(),
+                "https://github.com/o/r/blob/abc/coverage.html?raw=true".to_string(),
+            ),
+        ]);
+        assert!(section.starts_with("## Attachments"));
+        assert!(
+            section.contains("![login.PNG](https://github.com/o/r/blob/abc/login.PNG?raw=true)")
+        );
+        assert!(section
+            .contains("\n[coverage.html](https://github.com/o/r/blob/abc/coverage.html?raw=true)"));
+    }
+}
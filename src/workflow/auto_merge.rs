@@ -0,0 +1,39 @@
+//! Completing items whose PRs merged on their own
+//!
+//! With `pr.auto_merge` set, the pr phase turns on GitHub auto-merge and the
+//! PR merges once its checks pass. `wreckit watch` then only has to notice:
+//! every in_pr item whose PR has merged is run through the complete phase.
+
+use crate::errors::Result;
+use crate::fs;
+use crate::git;
+use crate::schemas::{Item, WorkflowState};
+
+use super::context::WorkflowContext;
+use super::orchestrator::Orchestrator;
+use super::phases::PhaseKind;
+
+/// Complete every in_pr item whose PR has merged and return the completed
+/// items. An item that fails to complete is logged and skipped.
+///
+/// # Errors
+/// * `InvalidJson` - If an item file is malformed
+pub async fn complete_merged_items(ctx: &WorkflowContext) -> Result<Vec<Item>> {
+    let options = ctx.git_options();
+    let orchestrator = Orchestrator::new(ctx.clone());
+    let mut completed = Vec::new();
+    for item in fs::list_items(&ctx.root)? {
+        let pr_number = match item.pr_number {
+            Some(number) if item.state == WorkflowState::InPr => number,
+            _ => continue,
+        };
+        if !git::is_pr_merged(pr_number, &options).await {
+            continue;
+        }
+        match orchestrator.run_phase(&item.id, PhaseKind::Complete).await {
+            Ok(item) => completed.push(item),
+            Err(e) => tracing::warn!("Cannot complete {}: {}", item.id, e),
+        }
+    }
+    Ok(completed)
+}
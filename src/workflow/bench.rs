@@ -0,0 +1,493 @@
+//! Agent configuration benchmarks
+//!
+//! `wreckit bench <id>` runs one item through research and plan once per
+//! `bench` variant (agent command and model arguments, prompt templates),
+//! each in a throwaway worktree of HEAD seeded with a fresh copy of the
+//! item. The runs are compared side by side: wall-clock time, estimated
+//! tokens, and simple quality heuristics on the artifacts they produced.
+//! The item and the working tree being benchmarked are left untouched.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::agent::DEFAULT_FIXTURES_DIR;
+use crate::domain::format_duration;
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git;
+use crate::schemas::{BenchVariant, Item, WorkflowState};
+
+use super::context::WorkflowContext;
+use super::phases::{run_phase_kind, PhaseKind};
+use super::stats::read_stats;
+
+/// Phases each variant runs
+const BENCH_PHASES: &[PhaseKind] = &[PhaseKind::Research, PhaseKind::Plan];
+
+/// Rough signals of how substantial a run's research and plan were
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ArtifactQuality {
+    /// Words in research.md
+    pub research_words: usize,
+
+    /// Markdown headings in research.md and plan.md
+    pub sections: usize,
+
+    /// Stories in prd.json
+    pub stories: usize,
+
+    /// Acceptance criteria across all stories
+    pub acceptance_criteria: usize,
+
+    /// Distinct repository files the artifacts mention that actually exist
+    pub file_refs: usize,
+}
+
+/// How one variant did
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchResult {
+    /// Variant name
+    pub variant: String,
+
+    /// Phases that finished (e.g. ["research", "plan"])
+    pub phases_completed: Vec<String>,
+
+    /// Wall-clock seconds across the phases
+    pub seconds: u64,
+
+    /// Estimated prompt tokens sent to the agent
+    pub prompt_tokens: u64,
+
+    /// Estimated tokens of agent output
+    pub output_tokens: u64,
+
+    /// Heuristics on whatever artifacts were written
+    pub quality: ArtifactQuality,
+
+    /// Why the run stopped early, if it did
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Measure the research and plan artifacts of an item
+pub fn assess_artifacts(root: &Path, id: &str) -> ArtifactQuality {
+    let research = std::fs::read_to_string(fs::get_research_path(root, id)).unwrap_or_default();
+    let plan = std::fs::read_to_string(fs::get_plan_path(root, id)).unwrap_or_default();
+    let prd = fs::read_prd(root, id).ok();
+
+    let headings = |text: &str| {
+        text.lines()
+            .filter(|line| line.trim_start().starts_with('#'))
+            .count()
+    };
+    let path_regex = Regex::new(r"[A-Za-z0-9_.-]+(?:/[A-Za-z0-9_.-]+)*\.[A-Za-z0-9]+").unwrap();
+    let file_refs: BTreeSet<&str> = path_regex
+        .find_iter(&research)
+        .chain(path_regex.find_iter(&plan))
+        .map(|m| m.as_str())
+        .filter(|path| root.join(path).is_file())
+        .collect();
+
+    ArtifactQuality {
+        research_words: research.split_whitespace().count(),
+        sections: headings(&research) + headings(&plan),
+        stories: prd.as_ref().map_or(0, |prd| prd.user_stories.len()),
+        acceptance_criteria: prd.as_ref().map_or(0, |prd| {
+            prd.user_stories
+                .iter()
+                .map(|story| story.acceptance_criteria.len())
+                .sum()
+        }),
+        file_refs: file_refs.len(),
+    }
+}
+
+/// The variants to run: the configured ones (filtered by name if `names` is
+/// non-empty), or the current setup alone if none are configured.
+///
+/// # Errors
+/// * `ConfigError` - If a requested variant is not configured
+pub fn select_variants(ctx: &WorkflowContext, names: &[String]) -> Result<Vec<BenchVariant>> {
+    let configured = &ctx.config.bench;
+    if configured.is_empty() && names.is_empty() {
+        return Ok(vec![BenchVariant::new("configured")]);
+    }
+    if names.is_empty() {
+        return Ok(configured.clone());
+    }
+    names
+        .iter()
+        .map(|name| {
+            configured
+                .iter()
+                .find(|variant| &variant.name == name)
+                .cloned()
+                .ok_or_else(|| {
+                    WreckitError::ConfigError(format!("no bench variant named {}", name))
+                })
+        })
+        .collect()
+}
+
+/// Copy a directory tree, overwriting files that already exist
+fn copy_tree(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_tree(&entry.path(), &target)?;
+        } else {
+            fs::copy_file(&entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// The item as a fresh idea: same content, no progress, artifacts, or errors
+fn seed_item(item: &Item) -> Item {
+    let mut seed = item.clone();
+    seed.state = WorkflowState::Idea;
+    seed.custom_state = None;
+    seed.branch = None;
+    seed.pr_url = None;
+    seed.pr_number = None;
+    seed.last_error = None;
+    seed.last_error_code = None;
+    seed.blocked = None;
+    seed.state_history = Vec::new();
+    seed
+}
+
+/// Build the context a variant runs in, inside `dir`
+fn variant_context(ctx: &WorkflowContext, variant: &BenchVariant, dir: &Path) -> WorkflowContext {
+    let mut variant_ctx = ctx.clone();
+    variant_ctx.root = dir.to_path_buf();
+    variant_ctx.force = false;
+    variant_ctx.replay = None;
+    variant_ctx.feedback = None;
+    if let Some(ref agent) = variant.agent {
+        variant_ctx.config.agent = agent.clone();
+    }
+    variant_ctx
+}
+
+/// Give a fresh worktree the variant's prompts and fixtures and the seeded item
+fn prepare_worktree(
+    ctx: &WorkflowContext,
+    variant_ctx: &WorkflowContext,
+    variant: &BenchVariant,
+    item: &Item,
+) -> Result<()> {
+    let dir = &variant_ctx.root;
+    let prompts = fs::get_prompts_dir(&ctx.root);
+    if prompts.is_dir() {
+        copy_tree(&prompts, &fs::get_prompts_dir(dir))?;
+    }
+    if let Some(ref prompts_dir) = variant.prompts_dir {
+        let source = ctx.root.join(prompts_dir);
+        if !source.is_dir() {
+            return Err(WreckitError::ConfigError(format!(
+                "bench variant {}: prompts_dir {} does not exist",
+                variant.name,
+                source.display()
+            )));
+        }
+        copy_tree(&source, &fs::get_prompts_dir(dir))?;
+    }
+    let fixtures = variant_ctx
+        .config
+        .agent
+        .fixtures_dir
+        .as_deref()
+        .unwrap_or(DEFAULT_FIXTURES_DIR);
+    if ctx.root.join(fixtures).is_dir() {
+        copy_tree(&ctx.root.join(fixtures), &dir.join(fixtures))?;
+    }
+
+    let item_dir = fs::get_item_dir(dir, &item.id);
+    if item_dir.exists() {
+        fs::remove_dir_all(&item_dir)?;
+    }
+    fs::write_item(dir, &item.id, &seed_item(item))
+}
+
+/// Run research and plan in `variant_ctx`, stopping at the first failure
+async fn run_phases(
+    variant_ctx: &WorkflowContext,
+    variant: &BenchVariant,
+    item: &Item,
+) -> BenchResult {
+    let started = std::time::Instant::now();
+    let mut completed = Vec::new();
+    let mut error = None;
+    let mut current = seed_item(item);
+    for kind in BENCH_PHASES {
+        match run_phase_kind(*kind, variant_ctx, current.clone()).await {
+            Ok(next) => {
+                completed.push(kind.name().to_string());
+                current = next;
+            }
+            Err(e) => {
+                error = Some(format!("{}: {}", kind, e));
+                break;
+            }
+        }
+    }
+
+    let totals = read_stats(&variant_ctx.root)
+        .map(|stats| stats.totals())
+        .unwrap_or_default();
+    BenchResult {
+        variant: variant.name.clone(),
+        phases_completed: completed,
+        seconds: started.elapsed().as_secs(),
+        prompt_tokens: totals.prompt_tokens,
+        output_tokens: totals.output_tokens,
+        quality: assess_artifacts(&variant_ctx.root, &item.id),
+        error,
+    }
+}
+
+/// Run one variant in its own worktree under `dir`, removing it afterwards
+async fn run_variant(
+    ctx: &WorkflowContext,
+    variant: &BenchVariant,
+    item: &Item,
+    dir: PathBuf,
+) -> Result<BenchResult> {
+    let options = ctx.git_options();
+    git::add_worktree(&dir, &options).await?;
+    let variant_ctx = variant_context(ctx, variant, &dir);
+    let result = match prepare_worktree(ctx, &variant_ctx, variant, item) {
+        Ok(()) => Ok(run_phases(&variant_ctx, variant, item).await),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = git::remove_worktree(&dir, &options).await {
+        tracing::warn!("Failed to remove bench worktree {}: {}", dir.display(), e);
+    }
+    result
+}
+
+/// Benchmark the selected variants on an item, one after another.
+///
+/// In dry-run mode nothing runs and no results are returned. A variant whose
+/// phases fail still gets a result, with `error` set.
+///
+/// # Errors
+/// * `FileNotFound` - If the item does not exist
+/// * `ConfigError` - If a requested variant or its prompts_dir is missing
+/// * `GitError` - If a worktree cannot be created
+pub async fn run_bench(
+    ctx: &WorkflowContext,
+    id: &str,
+    names: &[String],
+) -> Result<Vec<BenchResult>> {
+    let item = fs::read_item(&ctx.root, id)?;
+    let variants = select_variants(ctx, names)?;
+    if ctx.dry_run {
+        for variant in &variants {
+            tracing::info!(
+                "[DRY RUN] Would run research and plan for {} with variant {}",
+                id,
+                variant.name
+            );
+        }
+        return Ok(Vec::new());
+    }
+
+    let scratch = std::env::temp_dir().join(format!("wreckit-bench-{}", ulid::Ulid::new()));
+    let mut results = Vec::new();
+    for variant in &variants {
+        tracing::info!("Benchmarking variant {} on {}", variant.name, id);
+        let result = run_variant(ctx, variant, &item, scratch.join(&variant.name)).await?;
+        results.push(result);
+    }
+    let _ = fs::remove_dir_all(&scratch);
+    Ok(results)
+}
+
+/// Render results as a side-by-side table
+pub fn format_bench_report(results: &[BenchResult]) -> String {
+    let mut lines = vec![format!(
+        "{:<14} {:<15} {:>8} {:>10} {:>10} {:>7} {:>8} {:>7} {:>8} {:>6}",
+        "VARIANT",
+        "COMPLETED",
+        "TIME",
+        "PROMPT TOK",
+        "OUTPUT TOK",
+        "WORDS",
+        "SECTIONS",
+        "STORIES",
+        "CRITERIA",
+        "FILES"
+    )];
+    for result in results {
+        let completed = if result.phases_completed.is_empty() {
+            "-".to_string()
+        } else {
+            result.phases_completed.join(",")
+        };
+        lines.push(format!(
+            "{:<14} {:<15} {:>8} {:>10} {:>10} {:>7} {:>8} {:>7} {:>8} {:>6}",
+            result.variant,
+            completed,
+            format_duration(result.seconds as i64),
+            result.prompt_tokens,
+            result.output_tokens,
+            result.quality.research_words,
+            result.quality.sections,
+            result.quality.stories,
+            result.quality.acceptance_criteria,
+            result.quality.file_refs
+        ));
+    }
+    for result in results {
+        if let Some(ref error) = result.error {
+            lines.push(format!("{} failed at {}", result.variant, error));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::MockFixture;
+    use crate::schemas::{AgentMode, Config};
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    fn fixture(dir: &Path, name: &str, files: &[(&str, &str)]) {
+        let fixture = MockFixture {
+            files: files
+                .iter()
+                .map(|(path, content)| (path.to_string(), content.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+            complete: true,
+            ..Default::default()
+        };
+        fs::write_json(&dir.join(name), &fixture).unwrap();
+    }
+
+    #[test]
+    fn test_copy_tree_refuses_in_read_only_mode() {
+        let temp = TempDir::new().unwrap();
+        let from = temp.path().join("from");
+        std::fs::create_dir_all(&from).unwrap();
+        std::fs::write(from.join("a.json"), "{}").unwrap();
+        let to = temp.path().join("to");
+
+        let err = fs::with_read_only(|| copy_tree(&from, &to)).unwrap_err();
+        assert!(matches!(err, WreckitError::ReadOnly(_)));
+        assert!(!to.exists());
+
+        copy_tree(&from, &to).unwrap();
+        assert!(to.join("a.json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_bench_compares_variants() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-q", "-b", "main"]);
+        git(root, &["config", "user.email", "test@example.com"]);
+        git(root, &["config", "user.name", "Test"]);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join(".gitignore"), ".wreckit/\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn a() {}\n").unwrap();
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "init"]);
+
+        let prd = r#"{"schema_version":1,"id":"001-x","branch_name":"wreckit/001-x","user_stories":[{"id":"US-001","title":"A","acceptance_criteria":["one","two"],"priority":1,"status":"pending","notes":""}]}"#;
+        let main = root.join(DEFAULT_FIXTURES_DIR);
+        std::fs::create_dir_all(&main).unwrap();
+        fixture(
+            &main,
+            "research.json",
+            &[(
+                "research.md",
+                "# Research\nTouch src/lib.rs and src/missing.rs\n",
+            )],
+        );
+        fixture(
+            &main,
+            "plan.json",
+            &[("plan.md", "# Plan\n## Steps\n"), ("prd.json", prd)],
+        );
+        let terse = root.join(".wreckit/terse-fixtures");
+        std::fs::create_dir_all(&terse).unwrap();
+        fixture(&terse, "research.json", &[("research.md", "short")]);
+
+        let mut config = Config::default();
+        config.agent.mode = AgentMode::Mock;
+        config.context_pack.enabled = false;
+        let mut terse_agent = config.agent.clone();
+        terse_agent.fixtures_dir = Some(".wreckit/terse-fixtures".to_string());
+        config.bench = vec![
+            BenchVariant::new("default"),
+            BenchVariant::new("terse").with_agent(terse_agent),
+        ];
+        let ctx = WorkflowContext::new(root.to_path_buf(), config);
+        let item = Item::new("001-x".to_string(), "X".to_string(), String::new())
+            .with_state(WorkflowState::Planned);
+        fs::write_item(root, &item.id, &item).unwrap();
+
+        let results = run_bench(&ctx, &item.id, &[]).await.unwrap();
+        assert_eq!(results.len(), 2);
+        let full = &results[0];
+        assert_eq!(full.phases_completed, vec!["research", "plan"]);
+        assert!(full.error.is_none());
+        assert!(full.prompt_tokens > 0);
+        assert_eq!(
+            full.quality,
+            ArtifactQuality {
+                research_words: 6,
+                sections: 3,
+                stories: 1,
+                acceptance_criteria: 2,
+                file_refs: 1,
+            }
+        );
+        let terse = &results[1];
+        assert_eq!(terse.phases_completed, vec!["research"]);
+        assert!(terse.error.as_deref().unwrap().starts_with("plan:"));
+        assert_eq!(terse.quality.research_words, 1);
+
+        let report = format_bench_report(&results);
+        assert!(report.lines().nth(1).unwrap().starts_with("default"));
+        assert!(report.contains("terse failed at plan:"));
+
+        // The benchmarked item and tree are untouched
+        assert_eq!(
+            fs::read_item(root, &item.id).unwrap().state,
+            WorkflowState::Planned
+        );
+        assert!(!fs::get_research_path(root, &item.id).exists());
+        let worktrees = std::process::Command::new("git")
+            .args(["worktree", "list"])
+            .current_dir(root)
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&worktrees.stdout).lines().count(),
+            1
+        );
+
+        let missing = run_bench(&ctx, &item.id, &["nope".to_string()]).await;
+        assert!(matches!(missing, Err(WreckitError::ConfigError(_))));
+    }
+}
@@ -0,0 +1,198 @@
+//! Blocking and unblocking items
+//!
+//! `wreckit block` records a [`Blocker`] on the item; `next` skips blocked
+//! items and [`refresh_blocks`] lifts blockers whose date has passed or whose
+//! dependency is done before each pick.
+
+use chrono::Utc;
+
+use crate::domain::{is_block_lifted, is_terminal_state, parse_block_date};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::{Blocker, Item, WorkflowState};
+
+use super::context::WorkflowContext;
+
+/// Block an item with a reason and optional unblock condition.
+///
+/// # Errors
+/// * `Wrapped` - If `until` is not a YYYY-MM-DD date
+/// * `FileNotFound` - If the item or the `on_item` dependency does not exist
+/// * `StateTransition` - If the item is finished or depends on itself
+pub fn block_item(
+    ctx: &WorkflowContext,
+    id: &str,
+    reason: &str,
+    until: Option<&str>,
+    on_item: Option<&str>,
+) -> Result<Item> {
+    let item = fs::read_item(&ctx.root, id)?;
+    if is_terminal_state(item.state) {
+        return Err(WreckitError::StateTransition(format!(
+            "{} is {} and cannot be blocked",
+            id, item.state
+        )));
+    }
+
+    let until = until
+        .map(|d| parse_block_date(d).map(|date| date.to_string()))
+        .transpose()
+        .map_err(|e| WreckitError::wrap(e, "Invalid --until"))?;
+    if let Some(dependency) = on_item {
+        if dependency == id {
+            return Err(WreckitError::StateTransition(format!(
+                "{} cannot wait on itself",
+                id
+            )));
+        }
+        fs::read_item(&ctx.root, dependency)?;
+    }
+
+    let blocker = Blocker::new(reason.to_string())
+        .with_until(until)
+        .with_on_item(on_item.map(String::from));
+    let item = item.with_blocked(Some(blocker));
+    ctx.save_item(&item)?;
+    Ok(item)
+}
+
+/// Lift an item's blocker by hand.
+///
+/// # Errors
+/// * `FileNotFound` - If the item does not exist
+pub fn unblock_item(ctx: &WorkflowContext, id: &str) -> Result<Item> {
+    let item = fs::read_item(&ctx.root, id)?;
+    if !item.is_blocked() {
+        tracing::info!("{} is not blocked", id);
+        return Ok(item);
+    }
+    let item = item.with_blocked(None);
+    ctx.save_item(&item)?;
+    Ok(item)
+}
+
+/// Lift blockers whose unblock condition has been met, saving the items.
+///
+/// Returns the items with their blockers updated.
+///
+/// # Errors
+/// * `Io` - If an unblocked item cannot be saved
+pub fn refresh_blocks(ctx: &WorkflowContext, items: Vec<Item>) -> Result<Vec<Item>> {
+    let today = Utc::now().date_naive();
+    let states: Vec<(String, WorkflowState)> =
+        items.iter().map(|i| (i.id.clone(), i.state)).collect();
+    let dependency_state = |id: &str| {
+        states
+            .iter()
+            .find(|(other, _)| other == id)
+            .map(|(_, state)| *state)
+    };
+
+    items
+        .into_iter()
+        .map(|item| {
+            let lifted = item.blocked.as_ref().is_some_and(|blocker| {
+                let dependency = blocker.on_item.as_deref().and_then(dependency_state);
+                is_block_lifted(blocker, today, dependency)
+            });
+            if !lifted {
+                return Ok(item);
+            }
+            tracing::info!("Unblocking {}: condition met", item.id);
+            let item = item.with_blocked(None);
+            ctx.save_item(&item)?;
+            Ok(item)
+        })
+        .collect()
+}
+
+/// Error out if an item is still blocked after refreshing its blocker.
+///
+/// Returns the (possibly unblocked) item.
+///
+/// # Errors
+/// * `StateTransition` - If the item is blocked
+pub fn ensure_unblocked(ctx: &WorkflowContext, item: Item) -> Result<Item> {
+    if !item.is_blocked() {
+        return Ok(item);
+    }
+    let items = fs::list_items(&ctx.root)?
+        .into_iter()
+        .filter(|other| other.id != item.id)
+        .chain(std::iter::once(item.clone()))
+        .collect();
+    let refreshed = refresh_blocks(ctx, items)?
+        .into_iter()
+        .find(|other| other.id == item.id)
+        .unwrap_or(item);
+    match refreshed.blocked {
+        Some(ref blocker) => Err(WreckitError::StateTransition(format!(
+            "{} is blocked: {}",
+            refreshed.id, blocker.reason
+        ))),
+        None => Ok(refreshed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, WorkflowContext) {
+        let temp = TempDir::new().unwrap();
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), Config::default());
+        for (id, state) in [("001", WorkflowState::Idea), ("002", WorkflowState::InPr)] {
+            let item = Item::new(id.to_string(), id.to_string(), String::new()).with_state(state);
+            fs::write_item(temp.path(), id, &item).unwrap();
+        }
+        (temp, ctx)
+    }
+
+    #[test]
+    fn test_block_validates_input() {
+        let (_temp, ctx) = setup();
+        assert!(block_item(&ctx, "001", "r", Some("soon"), None).is_err());
+        assert!(block_item(&ctx, "001", "r", None, Some("001")).is_err());
+        assert!(matches!(
+            block_item(&ctx, "001", "r", None, Some("404")),
+            Err(WreckitError::FileNotFound(_))
+        ));
+
+        let item = block_item(&ctx, "001", "waiting on API key", Some("2024-07-01"), None).unwrap();
+        let blocker = item.blocked.unwrap();
+        assert_eq!(blocker.reason, "waiting on API key");
+        assert_eq!(blocker.until.as_deref(), Some("2024-07-01"));
+        assert!(fs::read_item(&ctx.root, "001").unwrap().is_blocked());
+
+        assert!(!unblock_item(&ctx, "001").unwrap().is_blocked());
+    }
+
+    #[test]
+    fn test_refresh_lifts_met_conditions() {
+        let (temp, ctx) = setup();
+        block_item(&ctx, "001", "later", Some("2000-01-01"), None).unwrap();
+        block_item(&ctx, "002", "manual", None, None).unwrap();
+
+        let items = refresh_blocks(&ctx, fs::list_items(temp.path()).unwrap()).unwrap();
+        assert!(!items[0].is_blocked());
+        assert!(items[1].is_blocked());
+        assert!(!fs::read_item(temp.path(), "001").unwrap().is_blocked());
+    }
+
+    #[test]
+    fn test_ensure_unblocked_waits_for_dependency() {
+        let (temp, ctx) = setup();
+        let item = block_item(&ctx, "001", "needs 002", None, Some("002")).unwrap();
+        let err = ensure_unblocked(&ctx, item).unwrap_err();
+        assert!(err.to_string().contains("blocked: needs 002"));
+
+        let done = fs::read_item(temp.path(), "002")
+            .unwrap()
+            .with_state(WorkflowState::Done);
+        fs::write_item(temp.path(), "002", &done).unwrap();
+        let item = fs::read_item(temp.path(), "001").unwrap();
+        assert!(!ensure_unblocked(&ctx, item).unwrap().is_blocked());
+    }
+}
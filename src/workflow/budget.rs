@@ -0,0 +1,136 @@
+//! Soft time budgets for phases
+//!
+//! `phases.<name>.warn_after_minutes` sets a budget well below the hard
+//! agent timeout. When a phase runs past it, a warning is logged and sent to
+//! the renderer (and, with `notify_over_budget`, to the operator) so a stuck
+//! agent gets noticed early. The phase itself keeps running.
+
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::tui::runner::TuiUpdate;
+
+use super::context::WorkflowContext;
+use super::phases::PhaseKind;
+
+/// Watches a running phase and warns once it exceeds its budget.
+///
+/// The watch ends when the guard is dropped.
+#[derive(Debug)]
+pub struct BudgetWatch {
+    handle: Option<JoinHandle<()>>,
+}
+
+impl BudgetWatch {
+    /// Start watching a phase using its configured budget.
+    ///
+    /// Does nothing in dry-run mode or when the phase has no budget.
+    pub fn start(ctx: &WorkflowContext, kind: PhaseKind, item_id: &str) -> Self {
+        let settings = ctx.config.phase(kind.name());
+        match settings.warn_after_minutes {
+            Some(minutes) if !ctx.dry_run => Self::start_after(
+                ctx,
+                kind,
+                item_id,
+                Duration::from_secs(minutes * 60),
+                settings.notify_over_budget,
+            ),
+            _ => BudgetWatch { handle: None },
+        }
+    }
+
+    /// Start watching a phase with an explicit budget
+    pub fn start_after(
+        ctx: &WorkflowContext,
+        kind: PhaseKind,
+        item_id: &str,
+        budget: Duration,
+        notify: bool,
+    ) -> Self {
+        let updates = ctx.updates.clone();
+        let item_id = item_id.to_string();
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(budget).await;
+            let minutes = budget.as_secs() / 60;
+            tracing::warn!(
+                "{} phase for {} has run for over {} minutes",
+                kind,
+                item_id,
+                minutes
+            );
+            if let Some(tx) = updates {
+                let _ = tx.send(TuiUpdate::PhaseOverBudget(
+                    item_id,
+                    kind.name().to_string(),
+                    minutes,
+                    notify,
+                ));
+            }
+        });
+        BudgetWatch {
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for BudgetWatch {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use tokio::sync::broadcast;
+
+    fn context() -> (WorkflowContext, broadcast::Receiver<TuiUpdate>) {
+        let (tx, rx) = broadcast::channel(8);
+        let ctx = WorkflowContext::new(std::env::temp_dir(), Config::default()).with_updates(tx);
+        (ctx, rx)
+    }
+
+    #[tokio::test]
+    async fn test_warns_after_budget() {
+        let (ctx, mut rx) = context();
+        let _watch = BudgetWatch::start_after(
+            &ctx,
+            PhaseKind::Implement,
+            "001-test",
+            Duration::from_millis(10),
+            true,
+        );
+        match rx.recv().await.unwrap() {
+            TuiUpdate::PhaseOverBudget(id, phase, _, notify) => {
+                assert_eq!(id, "001-test");
+                assert_eq!(phase, "implement");
+                assert!(notify);
+            }
+            other => panic!("unexpected update {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_warning_when_phase_finishes_in_time() {
+        let (ctx, mut rx) = context();
+        let watch = BudgetWatch::start_after(
+            &ctx,
+            PhaseKind::Research,
+            "001-test",
+            Duration::from_millis(50),
+            false,
+        );
+        drop(watch);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(rx.try_recv().is_err());
+
+        // No budget configured: nothing is spawned
+        assert!(BudgetWatch::start(&ctx, PhaseKind::Research, "001-test")
+            .handle
+            .is_none());
+    }
+}
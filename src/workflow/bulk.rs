@@ -0,0 +1,174 @@
+//! Bulk operations over filtered items
+//!
+//! `wreckit bulk` selects items by state and by how long they have gone
+//! without an update, then applies one action to each: abandon, retry, or
+//! `tag:<name>`. Selection and tagging live here; the command confirms the
+//! selection before anything is changed.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::{parse_age, parse_timestamp};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::Item;
+
+use super::context::WorkflowContext;
+
+/// Action applied to each selected item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkAction {
+    /// Abandon the item, closing its PR and deleting its branches
+    Abandon,
+
+    /// Re-run the phase the item failed in
+    Retry,
+
+    /// Add a tag to the item
+    Tag(String),
+}
+
+impl fmt::Display for BulkAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BulkAction::Abandon => write!(f, "abandon"),
+            BulkAction::Retry => write!(f, "retry"),
+            BulkAction::Tag(tag) => write!(f, "tag:{}", tag),
+        }
+    }
+}
+
+impl FromStr for BulkAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("tag", tag)) if !tag.trim().is_empty() => {
+                Ok(BulkAction::Tag(tag.trim().to_string()))
+            }
+            None if s == "abandon" => Ok(BulkAction::Abandon),
+            None if s == "retry" => Ok(BulkAction::Retry),
+            _ => Err(format!(
+                "unknown action '{}' (expected abandon, retry, or tag:<name>)",
+                s
+            )),
+        }
+    }
+}
+
+/// Which items a bulk operation applies to; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BulkFilter {
+    /// State name the item must be in (built-in or custom)
+    pub state: Option<String>,
+
+    /// Minimum seconds since the item was last updated
+    pub older_than: Option<i64>,
+}
+
+impl BulkFilter {
+    /// Build a filter from `--state` and `--older-than`.
+    ///
+    /// # Errors
+    /// * `Wrapped` - If `older_than` is not an age like "14d"
+    pub fn new(state: Option<&str>, older_than: Option<&str>) -> Result<Self> {
+        let older_than = older_than
+            .map(parse_age)
+            .transpose()
+            .map_err(|e| WreckitError::wrap(e, "Invalid --older-than"))?;
+        Ok(BulkFilter {
+            state: state.map(String::from),
+            older_than,
+        })
+    }
+
+    /// Whether an item passes the filter at `now`. Items whose update time
+    /// cannot be read never pass an age filter.
+    pub fn matches(&self, item: &Item, now: DateTime<Utc>) -> bool {
+        if self
+            .state
+            .as_ref()
+            .is_some_and(|state| *state != item.state_name())
+        {
+            return false;
+        }
+        match self.older_than {
+            Some(seconds) => parse_timestamp(&item.updated_at)
+                .is_some_and(|updated| (now - updated).num_seconds() >= seconds),
+            None => true,
+        }
+    }
+}
+
+/// Items matching the filter, in ID order.
+///
+/// # Errors
+/// Returns an error if the items cannot be read
+pub fn select_items(
+    ctx: &WorkflowContext,
+    filter: &BulkFilter,
+    now: DateTime<Utc>,
+) -> Result<Vec<Item>> {
+    let mut items: Vec<Item> = fs::list_items(&ctx.root)?
+        .into_iter()
+        .filter(|item| filter.matches(item, now))
+        .collect();
+    items.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(items)
+}
+
+/// Add a tag to an item.
+///
+/// # Errors
+/// * `FileNotFound` - If the item does not exist
+pub fn tag_item(ctx: &WorkflowContext, id: &str, tag: &str) -> Result<Item> {
+    let item = fs::read_item(&ctx.root, id)?.with_tag(tag);
+    ctx.save_item(&item)?;
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::WorkflowState;
+    use chrono::Duration;
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!(
+            "abandon".parse::<BulkAction>().unwrap(),
+            BulkAction::Abandon
+        );
+        assert_eq!("retry".parse::<BulkAction>().unwrap(), BulkAction::Retry);
+        assert_eq!(
+            "tag:stale".parse::<BulkAction>().unwrap(),
+            BulkAction::Tag("stale".to_string())
+        );
+        assert!("tag:".parse::<BulkAction>().is_err());
+        assert!("delete".parse::<BulkAction>().is_err());
+        assert_eq!(
+            BulkAction::Tag("stale".to_string()).to_string(),
+            "tag:stale"
+        );
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let now = Utc::now();
+        let mut item = Item::new("001-a".to_string(), "A".to_string(), String::new());
+        item.state = WorkflowState::InPr;
+        item.updated_at = (now - Duration::days(20)).to_rfc3339();
+
+        let filter = BulkFilter::new(Some("in_pr"), Some("14d")).unwrap();
+        assert!(filter.matches(&item, now));
+        item.updated_at = (now - Duration::days(3)).to_rfc3339();
+        assert!(!filter.matches(&item, now));
+        item.updated_at = (now - Duration::days(20)).to_rfc3339();
+        item.state = WorkflowState::Idea;
+        assert!(!filter.matches(&item, now));
+        assert!(BulkFilter::default().matches(&item, now));
+        assert!(BulkFilter::new(None, Some("soon")).is_err());
+    }
+}
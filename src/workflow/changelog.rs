@@ -0,0 +1,152 @@
+//! Changelog fragments
+//!
+//! With `changelog.enabled`, the PR phase writes a fragment describing the
+//! item (its title and completed stories) into `changelog.dir` and commits it
+//! on the item branch, so release tooling can collect autonomous work into
+//! release notes. Remediation stories added by quality gates and security
+//! scans are left out; they describe how the change was finished, not what
+//! it delivers.
+
+use std::path::PathBuf;
+
+use crate::errors::Result;
+use crate::fs;
+use crate::schemas::{ChangelogConfig, ChangelogFormat, Item, Prd, StoryStatus};
+
+use super::context::WorkflowContext;
+use super::gates::GATE_STORY_PREFIX;
+use super::security::SECURITY_STORY_PREFIX;
+
+/// Towncrier fragment type used for generated fragments
+const TOWNCRIER_TYPE: &str = "feature";
+
+/// Path of the fragment for an item
+pub fn fragment_path(ctx: &WorkflowContext, config: &ChangelogConfig, id: &str) -> PathBuf {
+    let name = match config.format {
+        ChangelogFormat::Markdown => format!("{}.md", id),
+        ChangelogFormat::Towncrier => format!("{}.{}.md", id, TOWNCRIER_TYPE),
+    };
+    ctx.root.join(&config.dir).join(name)
+}
+
+/// Titles of the stories an item delivered, in priority order
+fn delivered_stories(prd: Option<&Prd>) -> Vec<&str> {
+    let mut stories: Vec<_> = prd
+        .map(|prd| prd.user_stories.iter().collect())
+        .unwrap_or_default();
+    stories.retain(|s| {
+        s.status == StoryStatus::Done
+            && !s.id.starts_with(GATE_STORY_PREFIX)
+            && !s.id.starts_with(SECURITY_STORY_PREFIX)
+    });
+    stories.sort_by_key(|s| s.priority);
+    stories.into_iter().map(|s| s.title.as_str()).collect()
+}
+
+/// Render the fragment text for an item
+pub fn render_fragment(format: ChangelogFormat, item: &Item, prd: Option<&Prd>) -> String {
+    let stories = delivered_stories(prd);
+    match format {
+        ChangelogFormat::Markdown => {
+            let mut text = format!("### {} ({})\n", item.title, item.id);
+            if !stories.is_empty() {
+                text.push('\n');
+                for story in stories {
+                    text.push_str(&format!("- {}\n", story));
+                }
+            }
+            text
+        }
+        ChangelogFormat::Towncrier => {
+            let mut text = format!("{}\n", item.title);
+            for story in stories {
+                text.push_str(&format!("  - {}\n", story));
+            }
+            text
+        }
+    }
+}
+
+/// Write the item's changelog fragment into the working tree.
+///
+/// Returns the fragment path, or `None` when fragments are disabled or in
+/// dry-run mode. The caller commits it with the rest of the branch.
+///
+/// # Errors
+/// * `Io` - If the fragment cannot be written
+pub fn write_changelog_fragment(ctx: &WorkflowContext, item: &Item) -> Result<Option<PathBuf>> {
+    let config = &ctx.config.changelog;
+    if !config.enabled || ctx.dry_run {
+        return Ok(None);
+    }
+    let prd = fs::read_prd(&ctx.root, &item.id).ok();
+    let path = fragment_path(ctx, config, &item.id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write_file(&path, render_fragment(config.format, item, prd.as_ref()))?;
+    tracing::info!("{}: wrote changelog fragment {}", item.id, path.display());
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{Config, Story};
+    use tempfile::TempDir;
+
+    fn prd() -> Prd {
+        Prd::new("001-login".to_string(), "wreckit/001-login".to_string())
+            .with_story(
+                Story::new("US-002".to_string(), "Remember me".to_string(), vec![], 2).as_done(),
+            )
+            .with_story(Story::new("US-001".to_string(), "Log in".to_string(), vec![], 1).as_done())
+            .with_story(
+                Story::new("SEC-audit".to_string(), "Fix audit".to_string(), vec![], 3).as_done(),
+            )
+    }
+
+    #[test]
+    fn test_render_fragment() {
+        let item = Item::new(
+            "001-login".to_string(),
+            "Add login".to_string(),
+            String::new(),
+        );
+        assert_eq!(
+            render_fragment(ChangelogFormat::Markdown, &item, Some(&prd())),
+            "### Add login (001-login)\n\n- Log in\n- Remember me\n"
+        );
+        assert_eq!(
+            render_fragment(ChangelogFormat::Towncrier, &item, Some(&prd())),
+            "Add login\n  - Log in\n  - Remember me\n"
+        );
+        assert_eq!(
+            render_fragment(ChangelogFormat::Markdown, &item, None),
+            "### Add login (001-login)\n"
+        );
+    }
+
+    #[test]
+    fn test_write_changelog_fragment() {
+        let temp = TempDir::new().unwrap();
+        let item = Item::new(
+            "001-login".to_string(),
+            "Add login".to_string(),
+            String::new(),
+        );
+        fs::write_prd(temp.path(), &item.id, &prd()).unwrap();
+
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), Config::default());
+        assert!(write_changelog_fragment(&ctx, &item).unwrap().is_none());
+
+        let mut ctx = ctx;
+        ctx.config.changelog.enabled = true;
+        ctx.config.changelog.format = ChangelogFormat::Towncrier;
+        let path = write_changelog_fragment(&ctx, &item).unwrap().unwrap();
+        assert_eq!(path, temp.path().join(".changes/001-login.feature.md"));
+        assert!(std::fs::read_to_string(path)
+            .unwrap()
+            .starts_with("Add login\n"));
+    }
+}
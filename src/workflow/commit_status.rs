@@ -0,0 +1,75 @@
+//! Story progress as a commit status
+//!
+//! With `pr.commit_status`, the implement loop pushes the item branch after
+//! each story and sets a "wreckit" commit status on its head, so reviewers
+//! watching the PR see how far the autonomous implementation has got.
+
+use crate::fs;
+use crate::git::{self, CommitStatus};
+use crate::schemas::{Item, Prd, StoryStatus};
+
+use super::context::WorkflowContext;
+
+/// Name the status is listed under on the PR
+pub const STATUS_CONTEXT: &str = "wreckit";
+
+/// The status for a PRD's progress: pending until every story is done
+pub fn progress_status(prd: &Prd) -> CommitStatus {
+    let total = prd.user_stories.len();
+    let done = prd
+        .user_stories
+        .iter()
+        .filter(|story| story.status == StoryStatus::Done)
+        .count();
+    CommitStatus {
+        state: if done == total { "success" } else { "pending" },
+        context: STATUS_CONTEXT.to_string(),
+        description: format!("{}/{} stories done", done, total),
+    }
+}
+
+/// Push the item branch and post its story progress. Failures are only
+/// logged; the status is informational. Skipped while offline.
+pub async fn post_progress(ctx: &WorkflowContext, item: &Item) {
+    if !ctx.config.pr.commit_status || ctx.dry_run || ctx.offline {
+        return;
+    }
+    let Ok(prd) = fs::read_prd(&ctx.root, &item.id) else {
+        return;
+    };
+    let options = ctx.git_options();
+    let branch = ctx.branch_name(item);
+    let posted = async {
+        git::push_branch(&branch, &options).await?;
+        let sha = git::resolve_ref("HEAD", &options).await.unwrap_or_default();
+        git::set_commit_status(&sha, &progress_status(&prd), &options).await
+    }
+    .await;
+    if let Err(e) = posted {
+        tracing::warn!("Failed to post the progress status for {}: {}", item.id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Story;
+
+    #[test]
+    fn test_progress_status() {
+        let story = |id: &str| Story::new(id.to_string(), id.to_string(), vec![], 1);
+        let prd = Prd::new("001-a".to_string(), "wreckit/001-a".to_string())
+            .with_story(story("US-001"))
+            .with_story(story("US-002"))
+            .with_story(story("US-003"))
+            .with_story_done("US-002");
+        let status = progress_status(&prd);
+        assert_eq!(status.state, "pending");
+        assert_eq!(status.context, "wreckit");
+        assert_eq!(status.description, "1/3 stories done");
+
+        let status = progress_status(&prd.with_all_stories_done());
+        assert_eq!(status.state, "success");
+        assert_eq!(status.description, "3/3 stories done");
+    }
+}
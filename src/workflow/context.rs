@@ -0,0 +1,860 @@
+//! Shared context for running workflow phases
+//!
+//! Bundles the repository root, resolved configuration, the lifecycle event
+//! bus, and the optional TUI update stream and operator controls that every
+//! phase needs.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+
+use crate::agent::{
+    agent_cache_disabled, agent_cache_key, changed_files, detect_question, detect_rate_limit,
+    merge_env, parse_agent_line, read_cached_response, resolve_env, restore_files, run_agent,
+    run_mock_agent, snapshot_files, write_cached_response, AgentResult, CachedResponse,
+    MockRequest, RunAgentOptions,
+};
+use crate::domain::{generate_item_id, StateTable, TransitionValidator, ValidationContext};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git::{resolve_ref, GitHubClient, GitOptions};
+use crate::http::HttpSettings;
+use crate::prompts::{
+    fit_to_budget, load_prompt_variant, render_prompt, PromptVariables, TokenModel, Trim,
+};
+use crate::schemas::{AgentConfig, AgentMode, Config, Item, Question};
+use crate::tui::control::{cancelled, CancellationToken, ControlHandle};
+use crate::tui::events::AgentEvent;
+use crate::tui::runner::TuiUpdate;
+
+use super::events::{EventBus, WorkflowEvent};
+use super::experiments::select_prompt_variant;
+use super::implement_loop::append_progress;
+use super::notes::read_notes;
+use super::phases::PhaseKind;
+use super::stats::record_agent_run;
+use super::transcript::{record_transcript, ReplaySource, Transcript};
+
+/// One of several agents a phase consults
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentRole<'a> {
+    /// Name the role's responses are cached under (e.g. "a", "arbiter")
+    pub label: &'a str,
+
+    /// Model replacing the phase agent's, if any
+    pub model: Option<&'a str>,
+}
+
+/// Context shared by all phases of a workflow run
+#[derive(Clone)]
+pub struct WorkflowContext {
+    /// Repository root (contains .git and .wreckit)
+    pub root: PathBuf,
+
+    /// Resolved configuration
+    pub config: Config,
+
+    /// If true, describe actions without performing them
+    pub dry_run: bool,
+
+    /// If true, re-run phases even when their artifacts already exist
+    pub force: bool,
+
+    /// Sender for TUI / plain renderer updates (optional)
+    pub updates: Option<broadcast::Sender<TuiUpdate>>,
+
+    /// Operator pause/cancel controls (optional)
+    pub control: Option<ControlHandle>,
+
+    /// Recorded transcripts to replay instead of calling the agent (optional)
+    pub replay: Option<Arc<ReplaySource>>,
+
+    /// Operator guidance appended to every prompt (set by `wreckit retry`)
+    pub feedback: Option<String>,
+
+    /// Lifecycle events, shared by every clone of the context
+    pub events: EventBus,
+
+    /// Extra transition checks (e.g. WASM plugins from .wreckit/plugins/)
+    pub validators: Vec<Arc<dyn TransitionValidator>>,
+
+    /// GitHub API client, when PR operations bypass `gh`
+    pub github: Option<GitHubClient>,
+
+    /// If true, the forge is unreachable: the PR is queued in the outbox and
+    /// progress posting and attachment uploads are skipped
+    pub offline: bool,
+}
+
+impl WorkflowContext {
+    /// Create a new context for the given repository
+    pub fn new(root: PathBuf, config: Config) -> Self {
+        let http = HttpSettings::load(&root, &config.http).unwrap_or_else(|e| {
+            tracing::warn!("{}; using the default HTTP settings", e);
+            HttpSettings::default()
+        });
+        let github = GitHubClient::from_config(&root, &config.forge.github, &http);
+        Self {
+            root,
+            config,
+            dry_run: false,
+            force: false,
+            updates: None,
+            control: None,
+            replay: None,
+            feedback: None,
+            events: EventBus::new(),
+            validators: Vec::new(),
+            github,
+            offline: false,
+        }
+    }
+
+    // ===== BUILDER METHODS =====
+
+    /// Return a new context with dry-run mode set
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Return a new context with offline mode set
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Return a new context with force mode set
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Return a new context publishing updates to the given sender
+    pub fn with_updates(mut self, updates: broadcast::Sender<TuiUpdate>) -> Self {
+        self.updates = Some(updates);
+        self
+    }
+
+    /// Return a new context observing the given operator controls
+    pub fn with_control(mut self, control: ControlHandle) -> Self {
+        self.control = Some(control);
+        self
+    }
+
+    /// Return a new context that replays recorded transcripts
+    pub fn with_replay(mut self, replay: ReplaySource) -> Self {
+        self.replay = Some(Arc::new(replay));
+        self
+    }
+
+    /// Return a new context that appends operator feedback to prompts
+    pub fn with_feedback(mut self, feedback: Option<String>) -> Self {
+        self.feedback = feedback.filter(|text| !text.trim().is_empty());
+        self
+    }
+
+    /// Return a new context publishing lifecycle events to the given bus
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Return a new context running extra checks on every transition
+    pub fn with_validators(mut self, validators: Vec<Arc<dyn TransitionValidator>>) -> Self {
+        self.validators = validators;
+        self
+    }
+
+    // ===== HELPERS =====
+
+    /// Publish a lifecycle event, forwarding it to the renderer if it shows it
+    pub fn publish(&self, event: WorkflowEvent) {
+        if let Some(update) = event.renderer_update() {
+            self.emit(update);
+        }
+        self.events.publish(event);
+    }
+
+    /// Publish an update to the renderer, if one is attached
+    pub fn emit(&self, update: TuiUpdate) {
+        if let Some(ref tx) = self.updates {
+            let _ = tx.send(update);
+        }
+    }
+
+    /// Git options rooted at the repository
+    pub fn git_options(&self) -> GitOptions {
+        GitOptions {
+            cwd: self.root.clone(),
+            dry_run: self.dry_run,
+            github: self.github.clone(),
+            gh_host: self.config.forge.github.host.clone(),
+            cancel: self.cancel_token(),
+        }
+    }
+
+    /// Token for the current item, cancelled when the operator cancels it
+    pub fn cancel_token(&self) -> Option<CancellationToken> {
+        self.control.as_ref().map(ControlHandle::cancel_token)
+    }
+
+    /// Sleep for `duration`, returning early if the operator cancels.
+    ///
+    /// # Errors
+    /// * `Interrupted` - If the current item is cancelled while waiting
+    pub async fn wait(&self, duration: Duration) -> Result<()> {
+        let cancel = self.cancel_token();
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => Ok(()),
+            _ = cancelled(cancel.as_ref()) => Err(WreckitError::Interrupted),
+        }
+    }
+
+    /// Path to an item's directory
+    pub fn item_dir(&self, id: &str) -> PathBuf {
+        fs::get_item_dir(&self.root, id)
+    }
+
+    /// The agent config for an item's phase: the phase's `model` and
+    /// `args_override` applied, and the item's `env` layered over `agent.env`
+    pub fn agent_config(&self, item_id: &str, phase: PhaseKind) -> AgentConfig {
+        let mut agent = self.config.phase_agent(phase.name());
+        if let Ok(item) = fs::read_item(&self.root, item_id) {
+            agent.env = merge_env(&agent.env, &item.env);
+        }
+        agent
+    }
+
+    /// Variables for an item's verify commands, resolved.
+    ///
+    /// # Errors
+    /// * `ConfigError` - If a value references an unset variable
+    pub fn verify_env(&self, item: &Item) -> Result<Vec<(String, String)>> {
+        resolve_env(&merge_env(&self.config.agent.env, &item.env))
+    }
+
+    /// Branch name for an item (existing branch, or prefix + ID)
+    pub fn branch_name(&self, item: &Item) -> String {
+        item.branch
+            .clone()
+            .unwrap_or_else(|| format!("{}{}", self.config.branch_prefix, item.id))
+    }
+
+    /// Build prompt variables from the item and its artifacts on disk
+    pub fn prompt_variables(&self, item: &Item) -> PromptVariables {
+        let prd = read_optional(&fs::get_prd_path(&self.root, &item.id));
+        PromptVariables {
+            id: item.id.clone(),
+            title: item.title.clone(),
+            section: item.section.clone().unwrap_or_default(),
+            overview: item.overview.clone(),
+            item_path: self.item_dir(&item.id).display().to_string(),
+            branch_name: self.branch_name(item),
+            base_branch: self.config.base_branch.clone(),
+            completion_signal: self.config.agent.completion_signal.clone(),
+            sdk_mode: false,
+            research: read_optional(&fs::get_research_path(&self.root, &item.id)),
+            plan: read_optional(&fs::get_plan_path(&self.root, &item.id)),
+            prd,
+            progress: read_optional(&fs::get_progress_log_path(&self.root, &item.id)),
+            story: None,
+            verify_failures: None,
+            diff_stat: None,
+            repo_context: None,
+            policy_violations: None,
+            candidate_plans: None,
+            artifact: None,
+            changes: None,
+            attachments_dir: self.config.pr.attachments.then(|| {
+                fs::get_attachments_dir(&self.root, &item.id)
+                    .display()
+                    .to_string()
+            }),
+            problem_statement: item.problem_statement.clone(),
+            motivation: item.motivation.clone(),
+            success_criteria: item.success_criteria.clone(),
+            technical_constraints: item.technical_constraints.clone(),
+            scope_in_scope: item.scope_in_scope.clone(),
+            scope_out_of_scope: item.scope_out_of_scope.clone(),
+        }
+    }
+
+    /// Tokenizer family for a phase's prompts: the `--model` its agent runs
+    /// with, or failing that the agent command (e.g. `claude`)
+    pub fn token_model(&self, phase: &str) -> TokenModel {
+        let agent = self.config.phase_agent(phase);
+        TokenModel::for_model(agent.model().or(Some(agent.command.as_str())))
+    }
+
+    /// Prompt token budget for a template: its phase's `max_prompt_tokens`,
+    /// or the global one (0 disables budgeting)
+    pub fn prompt_budget(&self, template_name: &str) -> usize {
+        self.config
+            .phase(template_name)
+            .max_prompt_tokens
+            .unwrap_or(self.config.max_prompt_tokens)
+    }
+
+    /// Load a prompt template (or the prompt variant selected for it) and
+    /// render it, trimming the variables to fit the phase's token budget, and
+    /// append the item's notes and any operator feedback. Returns the prompt
+    /// and what was trimmed.
+    ///
+    /// # Errors
+    /// * `FileNotFound` - If the template or variant does not exist
+    pub fn budgeted_prompt(
+        &self,
+        template_name: &str,
+        mut variables: PromptVariables,
+    ) -> Result<(String, Vec<Trim>)> {
+        let variant = select_prompt_variant(self, template_name);
+        let template = load_prompt_variant(&self.root, template_name, variant.as_deref())?;
+        let max = self.prompt_budget(template_name);
+        let model = self.token_model(template_name);
+        let notes = read_notes(&self.root, &variables.id);
+        let trims = if max > 0 {
+            let fixed = model.count(&template) + notes.as_deref().map_or(0, |n| model.count(n));
+            let budget = max.saturating_sub(fixed);
+            fit_to_budget(&mut variables, template_name, budget, model)
+        } else {
+            Vec::new()
+        };
+        let mut prompt = render_prompt(&template, &variables);
+        if let Some(notes) = notes {
+            prompt.push_str(&format!("\n\n## Operator Notes\n{}\n", notes.trim()));
+        }
+        if let Some(ref feedback) = self.feedback {
+            prompt.push_str(&format!("\n\n## Operator Feedback\n{}\n", feedback.trim()));
+        }
+        Ok((prompt, trims))
+    }
+
+    /// Render a prompt within the context budget, recording each trimmed
+    /// section in the log and (outside dry-run mode) the item's progress.log.
+    ///
+    /// # Errors
+    /// * `FileNotFound` - If the template does not exist
+    /// * `Io` - If progress.log cannot be written
+    pub fn render_prompt(
+        &self,
+        template_name: &str,
+        item_id: &str,
+        variables: PromptVariables,
+    ) -> Result<String> {
+        let (prompt, trims) = self.budgeted_prompt(template_name, variables)?;
+        for trim in trims {
+            let line = format!(
+                "context budget: trimmed {} for the {} prompt (~{} to ~{} tokens)",
+                trim.section, template_name, trim.tokens_before, trim.tokens_after
+            );
+            tracing::warn!("{}: {}", item_id, line);
+            if !self.dry_run {
+                append_progress(&self.root, item_id, &line)?;
+            }
+        }
+        Ok(prompt)
+    }
+
+    /// Build a validation context from the artifacts on disk
+    pub fn validation_context(&self, item: &Item) -> ValidationContext {
+        ValidationContext {
+            has_research_md: fs::get_research_path(&self.root, &item.id).exists(),
+            has_plan_md: fs::get_plan_path(&self.root, &item.id).exists(),
+            prd: fs::read_prd(&self.root, &item.id).ok(),
+            has_pr: item.pr_url.is_some(),
+            pr_merged: false,
+            validators: self.validators.clone(),
+        }
+    }
+
+    /// The state ordering, including config-defined states
+    ///
+    /// # Errors
+    /// * `ConfigError` - If a custom state is misconfigured
+    pub fn state_table(&self) -> Result<StateTable> {
+        StateTable::from_config(&self.config.states).map_err(WreckitError::ConfigError)
+    }
+
+    /// Generate an ID for a new item using the configured `id_scheme`.
+    ///
+    /// # Errors
+    /// * `InvalidJson` - If index.json cannot be parsed
+    pub fn new_item_id(&self, title: &str) -> Result<String> {
+        let existing = fs::existing_item_ids(&self.root)?;
+        Ok(generate_item_id(
+            self.config.id_scheme,
+            &self.config.id_prefix,
+            title,
+            chrono::Utc::now().date_naive(),
+            &existing,
+        ))
+    }
+
+    /// Snapshot an item's metadata before it is changed.
+    ///
+    /// No-op in dry-run mode or when `backup_retention` is 0.
+    pub fn backup_item(&self, id: &str) -> Result<()> {
+        if self.dry_run || self.config.backup_retention == 0 {
+            return Ok(());
+        }
+        if let Some(snapshot) =
+            fs::snapshot_item(&self.root, id, self.config.backup_retention as usize)?
+        {
+            tracing::debug!("Backed up {} as {}", id, snapshot);
+        }
+        Ok(())
+    }
+
+    /// Persist an item, publishing its creation or state change. Nothing is
+    /// written in dry-run mode.
+    pub fn save_item(&self, item: &Item) -> Result<()> {
+        match fs::read_item(&self.root, &item.id) {
+            Err(_) => self.publish(WorkflowEvent::ItemCreated {
+                item_id: item.id.clone(),
+                state: item.state_name(),
+            }),
+            Ok(previous) if previous.state_name() != item.state_name() => {
+                self.publish(WorkflowEvent::StateChanged {
+                    item_id: item.id.clone(),
+                    from: previous.state_name(),
+                    to: item.state_name(),
+                })
+            }
+            Ok(_) => {}
+        }
+        if self.dry_run {
+            return Ok(());
+        }
+        fs::write_item(&self.root, &item.id, item)
+    }
+
+    /// Run the configured agent for an item, forwarding events to the renderer
+    /// and honoring the operator's cancel signal.
+    ///
+    /// In mock mode the response comes from fixtures. Live and mock runs are
+    /// recorded as transcripts and counted in the local stats; when
+    /// replaying, the next recording is returned instead of invoking the agent.
+    pub async fn run_agent(
+        &self,
+        item_id: &str,
+        phase: PhaseKind,
+        story: Option<&str>,
+        prompt: String,
+    ) -> Result<AgentResult> {
+        self.dispatch_agent(item_id, phase, story, None, prompt)
+            .await
+    }
+
+    /// Like [`run_agent`](Self::run_agent), for one of several agents a phase
+    /// consults (consensus planning)
+    pub async fn run_agent_as(
+        &self,
+        item_id: &str,
+        phase: PhaseKind,
+        role: AgentRole<'_>,
+        prompt: String,
+    ) -> Result<AgentResult> {
+        self.dispatch_agent(item_id, phase, None, Some(role), prompt)
+            .await
+    }
+
+    async fn dispatch_agent(
+        &self,
+        item_id: &str,
+        phase: PhaseKind,
+        story: Option<&str>,
+        role: Option<AgentRole<'_>>,
+        prompt: String,
+    ) -> Result<AgentResult> {
+        if let Some(ref replay) = self.replay {
+            return self.replay_agent(replay, item_id, phase, &prompt);
+        }
+        let cache = self.agent_cache_key(phase, role, &prompt).await;
+        if let Some((ref key, _)) = cache {
+            if let Some(cached) = read_cached_response(&self.root, key) {
+                restore_files(&self.item_dir(item_id), &cached)?;
+                tracing::info!(
+                    "Reusing the cached {} response for {} (pass --no-cache to run the agent)",
+                    phase,
+                    item_id
+                );
+                let result = cached.to_result();
+                self.emit_finished(item_id, &result);
+                return Ok(result);
+            }
+        }
+        let before = cache
+            .as_ref()
+            .map(|_| snapshot_files(&self.item_dir(item_id)));
+
+        let result = match self.config.agent.mode {
+            AgentMode::Mock if !self.dry_run => {
+                let result = run_mock_agent(&MockRequest {
+                    root: &self.root,
+                    item_id,
+                    phase: phase.name(),
+                    story,
+                    fixtures_dir: self.config.agent.fixtures_dir.as_deref(),
+                    completion_signal: &self.config.agent.completion_signal,
+                })?;
+                self.emit_finished(item_id, &result);
+                result
+            }
+            _ => {
+                let model = role.and_then(|role| role.model);
+                self.run_live_agent(item_id, phase, model, prompt.clone())
+                    .await?
+            }
+        };
+        if !self.dry_run {
+            let transcript = Transcript::from_result(phase.name(), story, &prompt, &result)
+                .with_prompt_variant(select_prompt_variant(self, phase.name()));
+            if let Err(e) = record_transcript(&self.root, item_id, &transcript) {
+                tracing::warn!("Failed to record transcript for {}: {}", item_id, e);
+            }
+            record_agent_run(self, phase, &prompt, &result.output);
+            if let Some(question) = detect_question(&result.output) {
+                return Err(self.pause_for_question(item_id, phase, question));
+            }
+        }
+        if let (Some((key, head)), Some(before)) = (cache, before) {
+            if result.success {
+                let response = CachedResponse {
+                    phase: phase.name().to_string(),
+                    head,
+                    cached_at: chrono::Utc::now().to_rfc3339(),
+                    output: result.output.clone(),
+                    exit_code: result.exit_code,
+                    files: changed_files(&before, snapshot_files(&self.item_dir(item_id))),
+                };
+                if let Err(e) = write_cached_response(&self.root, &key, &response) {
+                    tracing::warn!("Failed to cache the agent response for {}: {}", item_id, e);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Record a question the agent asked on its item and surface it, returning
+    /// the `AwaitingInput` error that stops the run until it is answered
+    fn pause_for_question(
+        &self,
+        item_id: &str,
+        phase: PhaseKind,
+        question: String,
+    ) -> WreckitError {
+        tracing::warn!("{} is awaiting input: {}", item_id, question);
+        let recorded = fs::read_item(&self.root, item_id).and_then(|item| {
+            let item = item.with_question(Some(Question::new(
+                question.clone(),
+                phase.name().to_string(),
+            )));
+            fs::write_item(&self.root, item_id, &item)
+        });
+        if let Err(e) = recorded {
+            tracing::warn!("Failed to record the question for {}: {}", item_id, e);
+        }
+        self.emit(TuiUpdate::AwaitingApproval(
+            item_id.to_string(),
+            format!("question: {}", question),
+        ));
+        WreckitError::AwaitingInput(question)
+    }
+
+    /// Cache key and HEAD commit for an agent call, or None when the call
+    /// should not be cached (mock mode, dry-run, `--no-cache`, a phase with
+    /// caching off, or a repository without commits). A role's runs are
+    /// cached apart from the phase's own.
+    async fn agent_cache_key(
+        &self,
+        phase: PhaseKind,
+        role: Option<AgentRole<'_>>,
+        prompt: &str,
+    ) -> Option<(String, String)> {
+        if self.config.agent.mode == AgentMode::Mock
+            || self.dry_run
+            || agent_cache_disabled()
+            || !self.config.phase(phase.name()).caches_agent(phase.name())
+        {
+            return None;
+        }
+        let head = resolve_ref("HEAD", &self.git_options()).await?;
+        let name = match role {
+            Some(role) => format!("{}.{}", phase.name(), role.label),
+            None => phase.name().to_string(),
+        };
+        Some((agent_cache_key(&name, prompt, &head), head))
+    }
+
+    async fn run_live_agent(
+        &self,
+        item_id: &str,
+        phase: PhaseKind,
+        model: Option<&str>,
+        prompt: String,
+    ) -> Result<AgentResult> {
+        let mut config = self.agent_config(item_id, phase);
+        if let Some(model) = model {
+            config = config.with_model(model);
+        }
+        let (event_tx, forwarder) = match self.updates.clone() {
+            Some(updates) => {
+                let (tx, mut rx) = tokio::sync::mpsc::channel::<AgentEvent>(100);
+                let id = item_id.to_string();
+                let handle = tokio::spawn(async move {
+                    while let Some(event) = rx.recv().await {
+                        let _ = updates.send(TuiUpdate::AgentEvent(id.clone(), event));
+                    }
+                });
+                (Some(tx), Some(handle))
+            }
+            None => (None, None),
+        };
+
+        let options = RunAgentOptions {
+            config,
+            cwd: self.root.clone(),
+            prompt,
+            dry_run: self.dry_run,
+            timeout_seconds: self.config.timeout_seconds,
+            on_stdout: None,
+            on_stderr: None,
+            on_tui_event: event_tx,
+            cancel: self.cancel_token(),
+            env_activation: self.config.env_activation,
+        };
+
+        let result = run_agent(options).await;
+
+        if let Some(handle) = forwarder {
+            // The sender was moved into the runner and dropped, so the forwarder drains and exits
+            let _ = handle.await;
+        }
+        if let Ok(ref r) = result {
+            self.emit_finished(item_id, r);
+        }
+
+        result
+    }
+
+    fn replay_agent(
+        &self,
+        replay: &ReplaySource,
+        item_id: &str,
+        phase: PhaseKind,
+        prompt: &str,
+    ) -> Result<AgentResult> {
+        let transcript = replay.next_transcript().ok_or_else(|| {
+            WreckitError::AgentError(format!(
+                "no recorded transcript left to replay for {}",
+                phase
+            ))
+        })?;
+        if transcript.phase != phase.name() {
+            tracing::warn!(
+                "Replay diverged: recording {} is from the {} phase, expected {}",
+                transcript.sequence,
+                transcript.phase,
+                phase
+            );
+        } else if transcript.prompt != prompt {
+            tracing::warn!(
+                "Replay diverged: prompt for recording {} differs from the recorded prompt",
+                transcript.sequence
+            );
+        }
+
+        let result = transcript.to_result();
+        for line in result.output.lines() {
+            for event in parse_agent_line(line) {
+                self.emit(TuiUpdate::AgentEvent(item_id.to_string(), event));
+            }
+        }
+        self.emit_finished(item_id, &result);
+        Ok(result)
+    }
+
+    fn emit_finished(&self, item_id: &str, result: &AgentResult) {
+        self.emit(TuiUpdate::AppendLogs(
+            result.output.lines().map(String::from).collect(),
+        ));
+        self.emit(TuiUpdate::AgentEvent(
+            item_id.to_string(),
+            AgentEvent::RunResult,
+        ));
+    }
+
+    /// Wait while the operator has paused the run
+    pub async fn wait_if_paused(&self) {
+        if let Some(mut control) = self.control.clone() {
+            if control.is_paused() {
+                tracing::info!("Paused; waiting for resume");
+                control.wait_while_paused().await;
+            }
+        }
+    }
+}
+
+/// Convert a failed agent result into an error.
+///
+/// Failures caused by API rate limits or overload become `RateLimited`.
+pub fn check_agent_result(result: &AgentResult) -> Result<()> {
+    if !result.success {
+        if let Some(limit) = detect_rate_limit(&result.output) {
+            return Err(WreckitError::RateLimited {
+                message: limit.message,
+                retry_after: limit.retry_after,
+            });
+        }
+    }
+    if result.timed_out {
+        return Err(WreckitError::Timeout(
+            "agent did not finish in time".to_string(),
+        ));
+    }
+    if !result.success {
+        let reason = if result.completion_detected {
+            format!("agent exited with code {:?}", result.exit_code)
+        } else {
+            "agent finished without emitting the completion signal".to_string()
+        };
+        return Err(WreckitError::AgentError(reason));
+    }
+    Ok(())
+}
+
+fn read_optional(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::WorkflowState;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, WorkflowContext, Item) {
+        let temp = TempDir::new().unwrap();
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), Config::default());
+        let item = Item::new(
+            "001-test".to_string(),
+            "Test".to_string(),
+            "Overview".to_string(),
+        );
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+        (temp, ctx, item)
+    }
+
+    #[test]
+    fn test_branch_name_defaults_to_prefix_and_id() {
+        let (_temp, ctx, item) = setup();
+        assert_eq!(ctx.branch_name(&item), "wreckit/001-test");
+
+        let item = item.with_branch(Some("custom".to_string()));
+        assert_eq!(ctx.branch_name(&item), "custom");
+    }
+
+    #[test]
+    fn test_git_options_carry_enterprise_host() {
+        let (_temp, mut ctx, _) = setup();
+        assert_eq!(ctx.git_options().gh_host, None);
+
+        ctx.config.forge.github.host = Some("github.mycorp.com".to_string());
+        assert_eq!(
+            ctx.git_options().gh_host.as_deref(),
+            Some("github.mycorp.com")
+        );
+    }
+
+    #[test]
+    fn test_prompt_variables_include_artifacts() {
+        let (temp, ctx, item) = setup();
+        std::fs::write(fs::get_research_path(temp.path(), &item.id), "findings").unwrap();
+
+        let vars = ctx.prompt_variables(&item);
+        assert_eq!(vars.id, "001-test");
+        assert_eq!(vars.research.as_deref(), Some("findings"));
+        assert!(vars.plan.is_none());
+        assert_eq!(vars.base_branch, "main");
+    }
+
+    #[test]
+    fn test_render_prompt_records_trims() {
+        let (temp, mut ctx, item) = setup();
+        std::fs::write(
+            fs::get_research_path(temp.path(), &item.id),
+            "r".repeat(40_000),
+        )
+        .unwrap();
+        ctx.config.max_prompt_tokens = 8_000;
+
+        let prompt = ctx
+            .render_prompt("plan", &item.id, ctx.prompt_variables(&item))
+            .unwrap();
+        assert!(prompt.contains("trimmed to fit the context budget"));
+        assert!(ctx.token_model("plan").count(&prompt) <= 8_000);
+        let progress =
+            std::fs::read_to_string(fs::get_progress_log_path(temp.path(), &item.id)).unwrap();
+        assert!(progress.contains("context budget: trimmed research for the plan prompt"));
+    }
+
+    #[test]
+    fn test_validation_context_reflects_disk() {
+        let (temp, ctx, item) = setup();
+        assert!(!ctx.validation_context(&item).has_research_md);
+
+        std::fs::write(fs::get_research_path(temp.path(), &item.id), "findings").unwrap();
+        assert!(ctx.validation_context(&item).has_research_md);
+    }
+
+    #[test]
+    fn test_save_item_dry_run_does_not_write() {
+        let (temp, ctx, item) = setup();
+        let ctx = ctx.with_dry_run(true);
+
+        ctx.save_item(&item.clone().with_state(WorkflowState::Done))
+            .unwrap();
+        let stored = fs::read_item(temp.path(), &item.id).unwrap();
+        assert_eq!(stored.state, WorkflowState::Idea);
+    }
+
+    #[test]
+    fn test_check_agent_result() {
+        let ok = AgentResult {
+            success: true,
+            output: String::new(),
+            timed_out: false,
+            exit_code: Some(0),
+            completion_detected: true,
+        };
+        assert!(check_agent_result(&ok).is_ok());
+
+        let limited = AgentResult {
+            success: false,
+            output: "API Error: 429 rate_limit_error, retry after 12 seconds".to_string(),
+            timed_out: false,
+            exit_code: Some(1),
+            completion_detected: false,
+        };
+        assert!(matches!(
+            check_agent_result(&limited),
+            Err(WreckitError::RateLimited {
+                retry_after: Some(12),
+                ..
+            })
+        ));
+
+        let timed_out = AgentResult {
+            success: false,
+            timed_out: true,
+            ..ok
+        };
+        assert!(matches!(
+            check_agent_result(&timed_out),
+            Err(WreckitError::Timeout(_))
+        ));
+    }
+}
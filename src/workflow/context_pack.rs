@@ -0,0 +1,374 @@
+//! Repository context pack
+//!
+//! A compact overview of the repository — its layout, key files, build
+//! commands, and the checks its CI runs — cached at
+//! `.wreckit/cache/context.md` and injected into research and plan prompts
+//! so the agent starts with the lay of the land instead of rediscovering it
+//! for every item. The pack records the commit it was built from and is
+//! rebuilt once HEAD has moved `context_pack.refresh_after_commits` commits
+//! past it (or to an unrelated commit). `wreckit context` rebuilds it on
+//! demand.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::errors::Result;
+use crate::fs;
+use crate::git;
+
+use super::context::WorkflowContext;
+
+/// First line of a cached pack: `<!-- wreckit-context head=<sha> -->`
+const HEADER_PREFIX: &str = "<!-- wreckit-context head=";
+
+/// Title line of a rendered pack
+const TITLE: &str = "# Repository Context";
+
+/// Most top-level entries listed in the layout
+const MAX_LAYOUT_ENTRIES: usize = 40;
+
+/// Most CI commands listed
+const MAX_CI_COMMANDS: usize = 20;
+
+/// Files worth pointing the agent at, if present
+const KEY_FILES: &[&str] = &[
+    "README.md",
+    "CONTRIBUTING.md",
+    "AGENTS.md",
+    "CLAUDE.md",
+    "Cargo.toml",
+    "cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "go.mod",
+    "Makefile",
+    "justfile",
+    "Dockerfile",
+    "rustfmt.toml",
+    ".editorconfig",
+];
+
+/// Path of the cached context pack
+pub fn get_context_pack_path(root: &Path) -> PathBuf {
+    fs::get_cache_dir(root).join("context.md")
+}
+
+/// The commit a cached pack was built from
+pub fn cached_head(pack: &str) -> Option<&str> {
+    let line = pack.lines().next()?;
+    let rest = line.strip_prefix(HEADER_PREFIX)?;
+    rest.strip_suffix(" -->").filter(|sha| !sha.is_empty())
+}
+
+/// Summarize tracked files as top-level entries with file counts
+fn layout(files: &[String]) -> Vec<String> {
+    let mut dirs: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut top_files = Vec::new();
+    for file in files {
+        match file.split_once('/') {
+            Some((dir, _)) => *dirs.entry(dir).or_default() += 1,
+            None => top_files.push(file.as_str()),
+        }
+    }
+    let mut lines: Vec<String> = dirs
+        .into_iter()
+        .map(|(dir, count)| format!("- `{}/` ({} files)", dir, count))
+        .collect();
+    lines.extend(top_files.into_iter().map(|f| format!("- `{}`", f)));
+    if lines.len() > MAX_LAYOUT_ENTRIES {
+        let more = lines.len() - MAX_LAYOUT_ENTRIES;
+        lines.truncate(MAX_LAYOUT_ENTRIES);
+        lines.push(format!("- ... and {} more", more));
+    }
+    lines
+}
+
+/// Build and test commands implied by the project's manifests
+fn build_commands(root: &Path) -> Vec<String> {
+    let mut commands = Vec::new();
+    if root.join("Cargo.toml").exists() || root.join("cargo.toml").exists() {
+        commands
+            .extend(["cargo build", "cargo test", "cargo clippy --all-targets"].map(String::from));
+    }
+    if let Ok(text) = std::fs::read_to_string(root.join("package.json")) {
+        let scripts = serde_json::from_str::<serde_json::Value>(&text)
+            .ok()
+            .and_then(|v| v["scripts"].as_object().cloned())
+            .unwrap_or_default();
+        commands.extend(scripts.keys().map(|name| format!("npm run {}", name)));
+    }
+    if root.join("pyproject.toml").exists() {
+        commands.push("pytest".to_string());
+    }
+    if root.join("go.mod").exists() {
+        commands.extend(["go build ./...", "go test ./..."].map(String::from));
+    }
+    if let Ok(text) = std::fs::read_to_string(root.join("Makefile")) {
+        commands.extend(
+            text.lines()
+                .filter_map(|line| line.split_once(':').map(|(target, _)| target))
+                .filter(|target| {
+                    !target.is_empty()
+                        && !target.starts_with(['.', '\t', ' ', '#'])
+                        && !target.contains(['=', '$', ' '])
+                })
+                .map(|target| format!("make {}", target)),
+        );
+    }
+    commands
+}
+
+/// Commands run by CI workflows (`run:` steps and GitLab `script` entries)
+fn ci_commands(root: &Path) -> Vec<String> {
+    let mut configs: Vec<PathBuf> = std::fs::read_dir(root.join(".github/workflows"))
+        .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).collect())
+        .unwrap_or_default();
+    configs.sort();
+    configs.push(root.join(".gitlab-ci.yml"));
+
+    let mut commands: Vec<String> = Vec::new();
+    for path in configs {
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) => continue,
+        };
+        let mut in_script = false;
+        for line in text.lines() {
+            let trimmed = line.trim();
+            let command = if let Some(run) = trimmed
+                .strip_prefix("- run:")
+                .or_else(|| trimmed.strip_prefix("run:"))
+            {
+                in_script = false;
+                run.trim()
+            } else if trimmed.starts_with("script:") {
+                in_script = true;
+                continue;
+            } else if in_script && trimmed.starts_with("- ") {
+                &trimmed[2..]
+            } else {
+                in_script = in_script && trimmed.is_empty();
+                continue;
+            };
+            let command = command.trim_matches(['"', '\'']).trim();
+            if !command.is_empty() && command != "|" && !commands.iter().any(|c| c == command) {
+                commands.push(command.to_string());
+            }
+        }
+    }
+    commands.truncate(MAX_CI_COMMANDS);
+    commands
+}
+
+/// Render the context pack for a repository.
+///
+/// `files` are the tracked paths relative to the root; `head` is recorded in
+/// the header so staleness can be detected later.
+pub fn render_context_pack(root: &Path, files: &[String], head: &str) -> String {
+    let mut text = format!("{}{} -->\n{}\n", HEADER_PREFIX, head, TITLE);
+
+    text.push_str("\n## Layout\n\n");
+    text.push_str(&layout(files).join("\n"));
+    text.push('\n');
+
+    let key_files: Vec<&str> = KEY_FILES
+        .iter()
+        .copied()
+        .filter(|name| root.join(name).exists())
+        .collect();
+    if !key_files.is_empty() {
+        text.push_str("\n## Key Files\n\n");
+        for name in key_files {
+            text.push_str(&format!("- `{}`\n", name));
+        }
+    }
+
+    let commands = build_commands(root);
+    if !commands.is_empty() {
+        text.push_str("\n## Build Commands\n\n");
+        for command in commands {
+            text.push_str(&format!("- `{}`\n", command));
+        }
+    }
+
+    let ci = ci_commands(root);
+    if !ci.is_empty() {
+        text.push_str("\n## CI Checks\n\nChanges are expected to pass what CI runs:\n\n");
+        for command in ci {
+            text.push_str(&format!("- `{}`\n", command));
+        }
+    }
+    text
+}
+
+async fn commits_since(base: &str, options: &git::GitOptions) -> Option<usize> {
+    let range = format!("{}..HEAD", base);
+    git::run_git_command(&["rev-list", "--count", &range], options)
+        .await
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Rebuild the context pack if it is missing, stale, or `force` is set.
+///
+/// Returns whether the pack was (re)built. Outside a git repository there
+/// is nothing to summarize and nothing is written.
+///
+/// # Errors
+/// * `GitError` - If tracked files or HEAD cannot be read
+/// * `Io` - If the cache cannot be written
+pub async fn refresh_context_pack(ctx: &WorkflowContext, force: bool) -> Result<bool> {
+    if !git::is_git_repo(&ctx.root).await {
+        return Ok(false);
+    }
+    let options = ctx.git_options();
+    let head = git::run_git_command(&["rev-parse", "HEAD"], &options)
+        .await?
+        .trim()
+        .to_string();
+    let path = get_context_pack_path(&ctx.root);
+    let cached = std::fs::read_to_string(&path).ok();
+
+    if !force {
+        if let Some(base) = cached.as_deref().and_then(cached_head) {
+            let moved = match commits_since(base, &options).await {
+                Some(count) => count,
+                // Unknown or unrelated commit: rebuild
+                None => usize::MAX,
+            };
+            if base == head || moved < ctx.config.context_pack.refresh_after_commits {
+                return Ok(false);
+            }
+        }
+    }
+    if ctx.dry_run {
+        return Ok(true);
+    }
+
+    let files: Vec<String> = git::run_git_command(&["ls-files"], &options)
+        .await?
+        .lines()
+        .filter(|line| !line.starts_with(".wreckit/"))
+        .map(String::from)
+        .collect();
+    fs::create_dir_all(&fs::get_cache_dir(&ctx.root))?;
+    fs::write_file(&path, render_context_pack(&ctx.root, &files, &head))?;
+    tracing::info!("Built repository context pack at {}", path.display());
+    Ok(true)
+}
+
+/// The cached context pack, without its header and title, if enabled and present
+pub fn read_context_pack(ctx: &WorkflowContext) -> Option<String> {
+    if !ctx.config.context_pack.enabled {
+        return None;
+    }
+    let pack = std::fs::read_to_string(get_context_pack_path(&ctx.root)).ok()?;
+    let body = pack
+        .lines()
+        .skip_while(|line| line.starts_with(HEADER_PREFIX) || line.starts_with(TITLE))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(body.trim().to_string())
+}
+
+/// Refresh the pack before a research or plan prompt, if enabled.
+///
+/// Failures are logged; a missing overview should not stop a phase.
+pub async fn prepare_context_pack(ctx: &WorkflowContext) {
+    if !ctx.config.context_pack.enabled {
+        return;
+    }
+    if let Err(e) = refresh_context_pack(ctx, false).await {
+        tracing::warn!("Failed to refresh repository context pack: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    fn commit(dir: &Path, name: &str) {
+        std::fs::write(dir.join(name), name).unwrap();
+        git(dir, &["add", "-A"]);
+        git(dir, &["commit", "-q", "-m", name]);
+    }
+
+    #[test]
+    fn test_render_context_pack() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(
+            root.join("Makefile"),
+            "all: build\n\ttouch x\nlint:\n.PHONY: all\nX = 1\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join(".github/workflows")).unwrap();
+        std::fs::write(
+            root.join(".github/workflows/ci.yml"),
+            "jobs:\n  test:\n    steps:\n      - uses: actions/checkout@v4\n      - run: cargo fmt --check\n      - name: Test\n        run: cargo test --all\n",
+        )
+        .unwrap();
+        let files: Vec<String> = ["src/main.rs", "src/lib.rs", "tests/it.rs", "Cargo.toml"]
+            .map(String::from)
+            .to_vec();
+
+        let pack = render_context_pack(root, &files, "abc123");
+        assert_eq!(cached_head(&pack), Some("abc123"));
+        assert!(pack.contains("- `src/` (2 files)\n- `tests/` (1 files)\n- `Cargo.toml`"));
+        assert!(pack.contains("## Key Files\n\n- `Cargo.toml`\n- `Makefile`"));
+        assert!(pack.contains("- `cargo clippy --all-targets`\n- `make all`\n- `make lint`\n"));
+        assert!(pack.contains("- `cargo fmt --check`\n- `cargo test --all`\n"));
+    }
+
+    #[test]
+    fn test_ci_script_entries() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join(".gitlab-ci.yml"),
+            "test:\n  script:\n    - npm ci\n    - npm test\n  artifacts:\n    - coverage\n",
+        )
+        .unwrap();
+        assert_eq!(ci_commands(temp.path()), vec!["npm ci", "npm test"]);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_after_enough_commits() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-q", "-b", "main"]);
+        git(root, &["config", "user.email", "test@example.com"]);
+        git(root, &["config", "user.name", "Test"]);
+        commit(root, "README.md");
+
+        let mut config = Config::default();
+        config.context_pack.refresh_after_commits = 2;
+        let ctx = WorkflowContext::new(root.to_path_buf(), config);
+
+        assert!(refresh_context_pack(&ctx, false).await.unwrap());
+        assert!(read_context_pack(&ctx).unwrap().starts_with("## Layout"));
+
+        commit(root, "a.txt");
+        assert!(!refresh_context_pack(&ctx, false).await.unwrap());
+        commit(root, "b.txt");
+        assert!(refresh_context_pack(&ctx, false).await.unwrap());
+        assert!(read_context_pack(&ctx).unwrap().contains("- `b.txt`"));
+
+        // Forced rebuilds ignore staleness
+        assert!(refresh_context_pack(&ctx, true).await.unwrap());
+    }
+}
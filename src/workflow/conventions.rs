@@ -0,0 +1,152 @@
+//! Conventional PR titles and semantic labels
+//!
+//! With `pr_conventions.enabled`, the PR phase infers a change type
+//! (feat, fix, chore, ...) from keywords in the item title, overview, and
+//! story titles, prefixes the PR title with it, and applies the type's
+//! labels. The types, their keywords, and their labels come from config so
+//! each team can match its own conventions.
+
+use regex::Regex;
+
+use crate::schemas::{ChangeType, Item, PrConventionsConfig, Prd};
+
+lazy_static::lazy_static! {
+    static ref CONVENTIONAL_TITLE_REGEX: Regex =
+        Regex::new(r"^[a-z]+(\([^)]*\))?!?: ").unwrap();
+}
+
+/// Keyword hits in the item title count this many times more than elsewhere
+const TITLE_WEIGHT: usize = 3;
+
+/// Suffixes stripped so "fixes", "added", and "crashing" match their keyword
+const WORD_SUFFIXES: &[&str] = &["ing", "es", "ed", "s", "d"];
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn matches_keyword(word: &str, keyword: &str) -> bool {
+    word == keyword
+        || WORD_SUFFIXES
+            .iter()
+            .any(|suffix| word.strip_suffix(suffix) == Some(keyword))
+}
+
+fn score(change_type: &ChangeType, words: &[String]) -> usize {
+    words
+        .iter()
+        .filter(|word| {
+            change_type
+                .keywords
+                .iter()
+                .any(|keyword| matches_keyword(word, &keyword.to_lowercase()))
+        })
+        .count()
+}
+
+/// Infer the change type of an item from its title, overview, and stories.
+///
+/// Returns the configured type with the most keyword hits (title hits
+/// weighted highest, earlier types winning ties), or the default type.
+pub fn infer_change_type(config: &PrConventionsConfig, item: &Item, prd: Option<&Prd>) -> String {
+    let title = words(&item.title);
+    let mut body = words(&item.overview);
+    if let Some(prd) = prd {
+        for story in &prd.user_stories {
+            body.extend(words(&story.title));
+        }
+    }
+
+    let mut best: Option<(&ChangeType, usize)> = None;
+    for change_type in &config.types {
+        let total = score(change_type, &title) * TITLE_WEIGHT + score(change_type, &body);
+        if total > 0 && best.is_none_or(|(_, top)| total > top) {
+            best = Some((change_type, total));
+        }
+    }
+    best.map(|(change_type, _)| change_type.name.clone())
+        .unwrap_or_else(|| config.default_type.clone())
+}
+
+/// Prefix a PR title with its change type, unless it already has one
+pub fn conventional_title(change_type: &str, title: &str) -> String {
+    if CONVENTIONAL_TITLE_REGEX.is_match(title) {
+        title.to_string()
+    } else {
+        format!("{}: {}", change_type, title)
+    }
+}
+
+/// Labels configured for a change type
+pub fn labels_for(config: &PrConventionsConfig, change_type: &str) -> Vec<String> {
+    config
+        .types
+        .iter()
+        .find(|t| t.name == change_type)
+        .map(|t| t.labels.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Story;
+
+    fn item(title: &str, overview: &str) -> Item {
+        Item::new("001-x".to_string(), title.to_string(), overview.to_string())
+    }
+
+    #[test]
+    fn test_infer_change_type() {
+        let config = PrConventionsConfig::default();
+        assert_eq!(
+            infer_change_type(&config, &item("Fix crash on empty input", ""), None),
+            "fix"
+        );
+        assert_eq!(
+            infer_change_type(&config, &item("Add CSV export", "Fixes nothing"), None),
+            "feat"
+        );
+        assert_eq!(
+            infer_change_type(&config, &item("Bump tokio", ""), None),
+            "chore"
+        );
+        // No keywords: fall back to the default type
+        assert_eq!(infer_change_type(&config, &item("Login", ""), None), "feat");
+
+        // Story titles count too
+        let prd = Prd::new("001-x".to_string(), "wreckit/001-x".to_string())
+            .with_story(Story::new(
+                "US-001".to_string(),
+                "Update the README".to_string(),
+                vec![],
+                1,
+            ))
+            .with_story(Story::new(
+                "US-002".to_string(),
+                "Document flags in the guide".to_string(),
+                vec![],
+                2,
+            ));
+        assert_eq!(
+            infer_change_type(&config, &item("Usage", ""), Some(&prd)),
+            "docs"
+        );
+    }
+
+    #[test]
+    fn test_conventional_title_and_labels() {
+        assert_eq!(conventional_title("feat", "Add login"), "feat: Add login");
+        assert_eq!(
+            conventional_title("feat", "fix(auth)!: Reject expired tokens"),
+            "fix(auth)!: Reject expired tokens"
+        );
+
+        let config = PrConventionsConfig::default();
+        assert_eq!(labels_for(&config, "fix"), vec!["bug"]);
+        assert!(labels_for(&config, "perf").is_empty());
+    }
+}
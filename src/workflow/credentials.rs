@@ -0,0 +1,273 @@
+//! Credential checks
+//!
+//! `wreckit auth status` verifies every credential the configuration relies
+//! on in one pass: the CA bundle, the forge (`gh` or the API client), the
+//! agent CLI, and the `env:` / `keychain:` secrets passed to the agent. Each check reports pass
+//! or fail with a remediation hint. Phases reuse the same checks as
+//! preflight, so a missing secret or agent fails before any work starts.
+
+use std::path::{Path, PathBuf};
+
+use crate::agent::{merge_env, resolve_value, ENV_REF_PREFIX};
+use crate::errors::{Result, WreckitError};
+use crate::git::{self, Credentials};
+use crate::http::HttpSettings;
+use crate::schemas::{AgentMode, Item};
+use crate::secrets::KEYCHAIN_REF_PREFIX;
+
+use super::context::WorkflowContext;
+
+/// Outcome of checking one credential
+#[derive(Debug, Clone, PartialEq)]
+pub struct CredentialCheck {
+    /// What was checked (e.g. "github (gh)", "agent", "env JIRA_TOKEN")
+    pub provider: String,
+
+    /// Whether the credential is usable
+    pub passed: bool,
+
+    /// What was found, or why the check failed
+    pub detail: String,
+
+    /// How to fix a failed check
+    pub hint: Option<String>,
+}
+
+impl CredentialCheck {
+    fn pass(provider: impl Into<String>, detail: impl Into<String>) -> Self {
+        CredentialCheck {
+            provider: provider.into(),
+            passed: true,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn fail(
+        provider: impl Into<String>,
+        detail: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        CredentialCheck {
+            provider: provider.into(),
+            passed: false,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    /// The failure as an error, or `Ok` if the check passed.
+    ///
+    /// # Errors
+    /// * `ConfigError` - If the check failed
+    pub fn into_result(self) -> Result<()> {
+        if self.passed {
+            return Ok(());
+        }
+        Err(WreckitError::ConfigError(format!(
+            "{}: {} ({})",
+            self.provider,
+            self.detail,
+            self.hint.unwrap_or_default()
+        )))
+    }
+}
+
+/// Where `command` would be run from: a path relative to `root`, or the
+/// first match on PATH
+pub fn find_command(command: &str, root: &Path) -> Option<PathBuf> {
+    if command.contains('/') {
+        let path = root.join(command);
+        return path.is_file().then_some(path);
+    }
+    std::env::var_os("PATH").and_then(|path| {
+        std::env::split_paths(&path)
+            .map(|dir| dir.join(command))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Check that the forge accepts the configured GitHub credentials
+pub async fn check_github(ctx: &WorkflowContext) -> CredentialCheck {
+    let provider = match ctx.github {
+        Some(ref client) => match client.credentials() {
+            Credentials::EnvToken => "github (token)",
+            Credentials::App { .. } => "github (app)",
+        },
+        None => "github (gh)",
+    };
+    let host = ctx
+        .config
+        .forge
+        .github
+        .host
+        .clone()
+        .unwrap_or_else(|| "github.com".to_string());
+    if ctx.github.is_none() && find_command("gh", &ctx.root).is_none() {
+        return CredentialCheck::fail(
+            provider,
+            "gh is not on PATH",
+            "install the GitHub CLI, or set GITHUB_TOKEN / run `wreckit auth set github`",
+        );
+    }
+    match git::check_github_auth(&ctx.git_options()).await {
+        Ok(()) => CredentialCheck::pass(provider, format!("authenticated to {}", host)),
+        Err(e) => CredentialCheck::fail(
+            provider,
+            format!("{} rejected the credentials or is unreachable: {}", host, e),
+            match ctx.github {
+                None => format!("run `gh auth login --hostname {}`", host),
+                Some(_) => {
+                    "set GITHUB_TOKEN, run `wreckit auth set github`, or check the GitHub App settings"
+                        .to_string()
+                }
+            },
+        ),
+    }
+}
+
+/// Check that the agent CLI can be started
+pub fn check_agent(ctx: &WorkflowContext) -> CredentialCheck {
+    let agent = &ctx.config.agent;
+    if agent.mode == AgentMode::Mock {
+        return CredentialCheck::pass("agent", "mock mode replays fixtures");
+    }
+    match find_command(&agent.command, &ctx.root) {
+        Some(path) => CredentialCheck::pass("agent", format!("{}", path.display())),
+        None => CredentialCheck::fail(
+            "agent",
+            format!("`{}` is not on PATH", agent.command),
+            format!("install {} and log in, or set agent.command", agent.command),
+        ),
+    }
+}
+
+/// Check every `env:` and `keychain:` value in `agent.env` and, when given,
+/// an item's `env`
+pub fn check_secrets(ctx: &WorkflowContext, item: Option<&Item>) -> Vec<CredentialCheck> {
+    let env = match item {
+        Some(item) => merge_env(&ctx.config.agent.env, &item.env),
+        None => ctx.config.agent.env.clone(),
+    };
+    env.iter()
+        .filter(|(_, value)| {
+            value.starts_with(ENV_REF_PREFIX) || value.starts_with(KEYCHAIN_REF_PREFIX)
+        })
+        .map(|(name, value)| {
+            let provider = format!("env {}", name);
+            match resolve_value(name, value) {
+                Ok(_) => CredentialCheck::pass(provider, format!("from {}", value)),
+                Err(e) => CredentialCheck::fail(
+                    provider,
+                    e.to_string(),
+                    match value.strip_prefix(KEYCHAIN_REF_PREFIX) {
+                        Some(secret) => format!("run `wreckit auth set {}`", secret),
+                        None => format!("export {}", &value[ENV_REF_PREFIX.len()..]),
+                    },
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Check that the configured CA bundle loads
+pub fn check_ca_bundle(ctx: &WorkflowContext) -> Option<CredentialCheck> {
+    let bundle = ctx.config.http.ca_bundle.as_ref()?;
+    Some(match HttpSettings::load(&ctx.root, &ctx.config.http) {
+        Ok(_) => CredentialCheck::pass("http ca_bundle", format!("trusting {}", bundle)),
+        Err(e) => CredentialCheck::fail(
+            "http ca_bundle",
+            e.to_string(),
+            "point http.ca_bundle at a PEM file of CA certificates",
+        ),
+    })
+}
+
+/// Run every credential check
+pub async fn check_credentials(ctx: &WorkflowContext) -> Vec<CredentialCheck> {
+    let mut checks: Vec<CredentialCheck> = check_ca_bundle(ctx).into_iter().collect();
+    checks.push(check_github(ctx).await);
+    checks.push(check_agent(ctx));
+    checks.extend(check_secrets(ctx, None));
+    checks
+}
+
+/// Preflight for phases that run the agent: the agent CLI and its secrets
+/// must be available. Skipped in dry-run and replay mode.
+///
+/// # Errors
+/// * `ConfigError` - Describing the first failed check
+pub fn require_agent_credentials(ctx: &WorkflowContext, item: &Item) -> Result<()> {
+    if ctx.dry_run || ctx.replay.is_some() {
+        return Ok(());
+    }
+    check_agent(ctx).into_result()?;
+    check_secrets(ctx, Some(item))
+        .into_iter()
+        .try_for_each(CredentialCheck::into_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+
+    fn context(config: Config) -> (tempfile::TempDir, WorkflowContext) {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = WorkflowContext::new(dir.path().to_path_buf(), config);
+        (dir, ctx)
+    }
+
+    #[test]
+    fn test_check_agent() {
+        let mut config = Config::default();
+        config.agent.command = "sh".to_string();
+        let (_dir, ctx) = context(config.clone());
+        assert!(check_agent(&ctx).passed);
+
+        config.agent.command = "wreckit-test-no-such-agent".to_string();
+        let (_dir, ctx) = context(config.clone());
+        let check = check_agent(&ctx);
+        assert!(!check.passed);
+        assert!(check.hint.unwrap().contains("agent.command"));
+
+        config.agent.mode = AgentMode::Mock;
+        let (_dir, ctx) = context(config);
+        assert!(check_agent(&ctx).passed);
+    }
+
+    #[test]
+    fn test_check_secrets() {
+        let mut config = Config::default();
+        config.agent.command = "sh".to_string();
+        config
+            .agent
+            .env
+            .insert("REGION".to_string(), "us-east-1".to_string());
+        config
+            .agent
+            .env
+            .insert("HOME_DIR".to_string(), "env:HOME".to_string());
+        let (_dir, ctx) = context(config);
+
+        let checks = check_secrets(&ctx, None);
+        assert_eq!(checks.len(), 1);
+        assert!(checks[0].passed);
+
+        let mut item = Item::new("001-a".to_string(), "A".to_string(), String::new());
+        item.env.insert(
+            "JIRA_TOKEN".to_string(),
+            "env:WRECKIT_TEST_UNSET_JIRA".to_string(),
+        );
+        let checks = check_secrets(&ctx, Some(&item));
+        let jira = checks
+            .iter()
+            .find(|c| c.provider == "env JIRA_TOKEN")
+            .unwrap();
+        assert!(!jira.passed);
+        assert_eq!(jira.hint.as_deref(), Some("export WRECKIT_TEST_UNSET_JIRA"));
+
+        let err = require_agent_credentials(&ctx, &item).unwrap_err();
+        assert!(err.to_string().contains("JIRA_TOKEN"));
+    }
+}
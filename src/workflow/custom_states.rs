@@ -0,0 +1,276 @@
+//! Config-defined workflow states
+//!
+//! Custom states (see [`crate::schemas::CustomStateConfig`]) are entered with
+//! `wreckit advance`. Entering one runs its validation hooks: the artifact
+//! must exist in the item directory and the command must exit successfully.
+//! With `require_checks.enabled`, an item with a PR also waits for the PR's
+//! CI checks, limited to the names matching `require_checks.only` so an item
+//! scoped to one package does not wait on unrelated pipelines.
+
+use crate::domain::{check_validators, StateDef};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git::{self, CheckState};
+use crate::schemas::Item;
+
+use super::context::WorkflowContext;
+use super::implement_loop::run_verify_in;
+use super::phases::{run_phase_kind, PhaseKind};
+
+/// Fail unless every required check on the item's PR has passed.
+///
+/// # Errors
+/// * `StateTransition` - If a required check failed, is still running, or
+///   none has reported yet
+/// * `GitError` - If the PR's checks cannot be read
+pub async fn check_required_checks(
+    ctx: &WorkflowContext,
+    pr_number: u32,
+    state: &StateDef,
+) -> Result<()> {
+    if ctx.dry_run {
+        tracing::info!(
+            "[DRY RUN] Would wait for PR #{}'s checks to enter {}",
+            pr_number,
+            state.name
+        );
+        return Ok(());
+    }
+    let only = &ctx.config.require_checks.only;
+    let checks = git::filter_checks(git::pr_checks(pr_number, &ctx.git_options()).await?, only);
+    let names = |wanted: CheckState| -> Vec<String> {
+        checks
+            .iter()
+            .filter(|check| check.state == wanted)
+            .map(|check| check.name.clone())
+            .collect()
+    };
+    let failed = names(CheckState::Failed);
+    if !failed.is_empty() {
+        return Err(WreckitError::StateTransition(format!(
+            "cannot enter {}: checks failing on PR #{}: {}",
+            state.name,
+            pr_number,
+            failed.join(", ")
+        )));
+    }
+    let pending = names(CheckState::Pending);
+    if !pending.is_empty() {
+        return Err(WreckitError::StateTransition(format!(
+            "cannot enter {}: checks still running on PR #{}: {}",
+            state.name,
+            pr_number,
+            pending.join(", ")
+        )));
+    }
+    if checks.is_empty() && !only.is_empty() {
+        return Err(WreckitError::StateTransition(format!(
+            "cannot enter {}: no check matching {} has reported on PR #{}",
+            state.name,
+            only.join(", "),
+            pr_number
+        )));
+    }
+    Ok(())
+}
+
+/// Run the validation hooks for entering a state, then any extra validators.
+///
+/// In dry-run mode the command is reported rather than run.
+///
+/// # Errors
+/// * `StateTransition` - If a required check has not passed, the artifact
+///   is missing, the command fails, or a validator rejects the item
+/// * `GitError` - If the PR's checks cannot be read
+/// * `Timeout` - If the command does not finish in time
+pub async fn check_state_hooks(ctx: &WorkflowContext, item: &Item, state: &StateDef) -> Result<()> {
+    if ctx.config.require_checks.enabled {
+        if let Some(pr_number) = item.pr_number {
+            check_required_checks(ctx, pr_number, state).await?;
+        }
+    }
+
+    if let Some(ref artifact) = state.artifact {
+        if !ctx.item_dir(&item.id).join(artifact).exists() {
+            return Err(WreckitError::StateTransition(format!(
+                "cannot enter {}: {} does not exist",
+                state.name, artifact
+            )));
+        }
+    }
+
+    if let Some(ref command) = state.command {
+        if ctx.dry_run {
+            tracing::info!("[DRY RUN] Would run `{}` to enter {}", command, state.name);
+            return Ok(());
+        }
+        let outcome = run_verify_in(
+            ctx.config.agent.execution,
+            ctx.config.env_activation,
+            command,
+            &ctx.root,
+            ctx.config.timeout_seconds,
+            &ctx.verify_env(item)?,
+        )
+        .await?;
+        if !outcome.passed {
+            return Err(WreckitError::StateTransition(format!(
+                "cannot enter {}: `{}` failed\n{}",
+                state.name,
+                command,
+                outcome.output.trim_end()
+            )));
+        }
+    }
+
+    let result = check_validators(item, &state.name, &ctx.validation_context(item));
+    if !result.valid {
+        return Err(WreckitError::StateTransition(format!(
+            "cannot enter {}: {}",
+            state.name,
+            result.reason.unwrap_or_default()
+        )));
+    }
+    Ok(())
+}
+
+/// Advance an item to the state after its current one.
+///
+/// The next state must be a custom state, or the built-in state that follows
+/// the item's last custom state (run through its phase, e.g. complete).
+/// Built-in transitions out of built-in states belong to the phase commands.
+/// If `target` is given it must name the next state.
+///
+/// # Errors
+/// * `ConfigError` - If the custom states are misconfigured
+/// * `StateTransition` - If the item cannot advance or a hook fails
+pub async fn advance_item(ctx: &WorkflowContext, id: &str, target: Option<&str>) -> Result<Item> {
+    let table = ctx.state_table()?;
+    let item = fs::read_item(&ctx.root, id)?;
+    let current = item.state_name();
+
+    let next = table.next(&current).ok_or_else(|| {
+        WreckitError::StateTransition(format!("{} is in final state {}", id, current))
+    })?;
+    if let Some(target) = target {
+        if target != next.name {
+            return Err(WreckitError::StateTransition(format!(
+                "cannot advance {} from {} to {}; the next state is {}",
+                id, current, target, next.name
+            )));
+        }
+    }
+
+    match next.builtin {
+        None => {
+            check_state_hooks(ctx, &item, next).await?;
+            if ctx.dry_run {
+                tracing::info!(
+                    "[DRY RUN] Would move {} from {} to {}",
+                    id,
+                    current,
+                    next.name
+                );
+                return Ok(item);
+            }
+            let item = item
+                .with_custom_state(Some(next.name.clone()))
+                .with_error(None);
+            ctx.save_item(&item)?;
+            Ok(item)
+        }
+        Some(_) if item.custom_state.is_some() => match PhaseKind::for_state(item.state) {
+            Some(kind) => run_phase_kind(kind, ctx, item).await,
+            None => Err(WreckitError::StateTransition(format!(
+                "no phase leads from {} to {}",
+                current, next.name
+            ))),
+        },
+        Some(state) => Err(WreckitError::StateTransition(format!(
+            "{} → {} is a built-in transition; use `wreckit {}`",
+            current,
+            state,
+            PhaseKind::for_state(item.state).map_or("run", PhaseKind::name)
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{Config, CustomStateConfig, WorkflowState};
+    use tempfile::TempDir;
+
+    fn setup(states: Vec<CustomStateConfig>) -> (TempDir, WorkflowContext) {
+        let temp = TempDir::new().unwrap();
+        let config = Config {
+            states,
+            ..Default::default()
+        };
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        let item = Item::new("001-test".to_string(), "Test".to_string(), String::new())
+            .with_state(WorkflowState::InPr);
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+        (temp, ctx)
+    }
+
+    fn state(name: &str, after: &str) -> CustomStateConfig {
+        CustomStateConfig {
+            name: name.to_string(),
+            after: after.to_string(),
+            artifact: None,
+            command: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_advance_runs_hooks() {
+        let mut qa = state("qa", "in_pr");
+        qa.artifact = Some("qa.md".to_string());
+        let mut staged = state("staged", "qa");
+        staged.command = Some("echo not ready; exit 1".to_string());
+        let (temp, ctx) = setup(vec![qa, staged]);
+
+        let err = advance_item(&ctx, "001-test", None).await.unwrap_err();
+        assert!(err.to_string().contains("qa.md does not exist"));
+
+        std::fs::write(ctx.item_dir("001-test").join("qa.md"), "ok").unwrap();
+        let item = advance_item(&ctx, "001-test", Some("qa")).await.unwrap();
+        assert_eq!(item.state, WorkflowState::InPr);
+        assert_eq!(item.state_name(), "qa");
+        assert_eq!(
+            fs::read_item(temp.path(), "001-test")
+                .unwrap()
+                .custom_state
+                .as_deref(),
+            Some("qa")
+        );
+
+        let err = advance_item(&ctx, "001-test", None).await.unwrap_err();
+        assert!(err.to_string().contains("not ready"));
+    }
+
+    #[tokio::test]
+    async fn test_advance_rejects_wrong_target_and_builtin_steps() {
+        let (_temp, ctx) = setup(vec![state("deployed", "done")]);
+
+        let err = advance_item(&ctx, "001-test", Some("deployed"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("the next state is done"));
+
+        let err = advance_item(&ctx, "001-test", None).await.unwrap_err();
+        assert!(err.to_string().contains("wreckit complete"));
+    }
+
+    #[tokio::test]
+    async fn test_complete_requires_custom_states_first() {
+        let (_temp, ctx) = setup(vec![state("qa", "in_pr")]);
+        let item = fs::read_item(&ctx.root, "001-test").unwrap();
+
+        let err = run_phase_kind(PhaseKind::Complete, &ctx, item)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("must enter qa before done"));
+    }
+}
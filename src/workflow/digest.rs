@@ -0,0 +1,176 @@
+//! Key-decision digests of agent transcripts
+//!
+//! Research and plan transcripts run to thousands of lines, but later phases
+//! and reviewers mostly need the handful of choices the agent made. After
+//! those phases, lines in the agent output that state a decision ("we will
+//! use", "chose X instead of Y", "because ...") are collected by a local
+//! heuristic and appended to the phase artifact under a "Key Decisions"
+//! heading. Re-running a phase replaces the section rather than adding
+//! another one.
+
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::errors::Result;
+use crate::fs;
+
+use super::context::WorkflowContext;
+
+/// Heading of the appended section
+pub const KEY_DECISIONS_HEADING: &str = "## Key Decisions";
+
+/// Most decisions kept in a digest
+const MAX_DECISIONS: usize = 10;
+
+/// Longest decision line kept, in characters
+const MAX_DECISION_CHARS: usize = 240;
+
+/// Lines shorter than this rarely carry a whole decision
+const MIN_DECISION_CHARS: usize = 20;
+
+lazy_static::lazy_static! {
+    static ref DECISION_REGEX: Regex = Regex::new(
+        r"(?i)\b(decided|decision|chose|choosing|opted|will use|going with|instead of|rather than|trade-?offs?|because|prefer)\b"
+    ).unwrap();
+
+    static ref BULLET_REGEX: Regex = Regex::new(r"^(?:[-*+]|\d+[.)])\s+").unwrap();
+}
+
+fn clean(line: &str) -> String {
+    let line = BULLET_REGEX.replace(line.trim(), "").replace("**", "");
+    let line = line.trim();
+    if line.chars().count() > MAX_DECISION_CHARS {
+        let cut: String = line.chars().take(MAX_DECISION_CHARS - 1).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Pick the lines of agent output that state decisions, in order, deduplicated
+pub fn extract_key_decisions(output: &str) -> Vec<String> {
+    let mut decisions: Vec<String> = Vec::new();
+    let mut in_code = false;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || trimmed.starts_with('#') || trimmed.starts_with('{') {
+            continue;
+        }
+        if !DECISION_REGEX.is_match(trimmed) {
+            continue;
+        }
+        let decision = clean(trimmed);
+        if decision.chars().count() >= MIN_DECISION_CHARS && !decisions.contains(&decision) {
+            decisions.push(decision);
+        }
+        if decisions.len() == MAX_DECISIONS {
+            break;
+        }
+    }
+    decisions
+}
+
+/// Replace (or add) the key-decisions section at the end of an artifact
+pub fn with_key_decisions(artifact: &str, decisions: &[String]) -> String {
+    let body = match artifact.find(KEY_DECISIONS_HEADING) {
+        Some(start) => &artifact[..start],
+        None => artifact,
+    };
+    let mut text = body.trim_end().to_string();
+    text.push_str("\n\n");
+    text.push_str(KEY_DECISIONS_HEADING);
+    text.push_str("\n\n_Distilled from the agent transcript._\n\n");
+    for decision in decisions {
+        text.push_str(&format!("- {}\n", decision));
+    }
+    text
+}
+
+/// Append the decisions found in `output` to the artifact at `path`.
+///
+/// Returns how many decisions were written; the artifact is left alone if
+/// it does not exist or no decisions were found.
+///
+/// # Errors
+/// * `Io` - If the artifact cannot be read or written
+pub fn append_key_decisions(path: &Path, output: &str) -> Result<usize> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    let decisions = extract_key_decisions(output);
+    if decisions.is_empty() {
+        return Ok(0);
+    }
+    let artifact = std::fs::read_to_string(path)?;
+    fs::write_file(path, with_key_decisions(&artifact, &decisions))?;
+    Ok(decisions.len())
+}
+
+/// Digest a phase transcript into its artifact, if enabled.
+///
+/// Failures are logged rather than returned: the digest is a convenience and
+/// must not fail a phase whose agent run succeeded.
+pub fn record_key_decisions(ctx: &WorkflowContext, item_id: &str, path: &Path, output: &str) {
+    if !ctx.config.summarize_transcripts || ctx.dry_run {
+        return;
+    }
+    match append_key_decisions(path, output) {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(
+            "{}: added {} key decision(s) to {}",
+            item_id,
+            count,
+            path.display()
+        ),
+        Err(e) => tracing::warn!("{}: failed to digest transcript: {}", item_id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const OUTPUT: &str = "Reading src/main.rs\n\
+        I decided to reuse the existing session store instead of adding Redis.\n\
+        ```rust\n\
+        // chose this because it compiles\n\
+        ```\n\
+        - **We will use** serde for the wire format because it is already a dependency.\n\
+        because\n\
+        I decided to reuse the existing session store instead of adding Redis.\n\
+        Done.";
+
+    #[test]
+    fn test_extract_key_decisions() {
+        assert_eq!(
+            extract_key_decisions(OUTPUT),
+            vec![
+                "I decided to reuse the existing session store instead of adding Redis.",
+                "We will use serde for the wire format because it is already a dependency.",
+            ]
+        );
+        assert!(extract_key_decisions("nothing notable here").is_empty());
+    }
+
+    #[test]
+    fn test_append_replaces_existing_section() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("research.md");
+        assert_eq!(append_key_decisions(&path, OUTPUT).unwrap(), 0);
+
+        std::fs::write(&path, "# Research\n\nFindings.\n").unwrap();
+        assert_eq!(append_key_decisions(&path, OUTPUT).unwrap(), 2);
+        assert_eq!(append_key_decisions(&path, OUTPUT).unwrap(), 2);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# Research\n\nFindings.\n\n## Key Decisions"));
+        assert_eq!(text.matches(KEY_DECISIONS_HEADING).count(), 1);
+        assert!(text.ends_with("already a dependency.\n"));
+    }
+}
@@ -0,0 +1,197 @@
+//! Disk space preflight
+//!
+//! An implement run that fills the disk fails mid-way, often with a
+//! half-written build. `wreckit doctor` and the implement phase's preflight
+//! check the free space on the repository's filesystem, untracked files that
+//! have grown large (build output that escaped .gitignore), and the size of
+//! .wreckit, against the `disk` thresholds. Findings are warnings: the run
+//! still starts.
+
+use std::path::Path;
+
+use crate::errors::Result;
+use crate::fs;
+use crate::git;
+
+use super::context::WorkflowContext;
+use super::gc::format_bytes;
+
+const MIB: u64 = 1024 * 1024;
+
+/// A disk problem and how to address it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiskWarning {
+    /// Short name of the check (e.g. "free-space")
+    pub check: String,
+
+    /// What was found
+    pub message: String,
+
+    /// How to fix it
+    pub fix: String,
+}
+
+impl DiskWarning {
+    fn new(check: &str, message: String, fix: &str) -> Self {
+        DiskWarning {
+            check: check.to_string(),
+            message,
+            fix: fix.to_string(),
+        }
+    }
+}
+
+/// Bytes available to unprivileged users on the filesystem holding `path`
+#[cfg(unix)]
+pub fn free_space(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statvfs(3) writes into the zeroed struct we own; the path is a
+    // valid NUL-terminated string.
+    unsafe {
+        let mut stat: libc::statvfs = std::mem::zeroed();
+        if libc::statvfs(path.as_ptr(), &mut stat) != 0 {
+            return None;
+        }
+        Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+    }
+}
+
+/// Free space is only measured on Unix
+#[cfg(not(unix))]
+pub fn free_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Total size of the files under a directory
+pub fn dir_size(dir: &Path) -> u64 {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Check free space, large untracked files, and the size of .wreckit
+/// against the `disk` thresholds.
+///
+/// # Errors
+/// * `GitError` - If the untracked files cannot be listed
+pub async fn check_disk(ctx: &WorkflowContext) -> Result<Vec<DiskWarning>> {
+    let config = &ctx.config.disk;
+    let mut warnings = Vec::new();
+
+    if let Some(free) = free_space(&ctx.root) {
+        if free < config.min_free_mb.saturating_mul(MIB) {
+            warnings.push(DiskWarning::new(
+                "free-space",
+                format!(
+                    "only {} free on the repository's filesystem (minimum {})",
+                    format_bytes(free),
+                    format_bytes(config.min_free_mb.saturating_mul(MIB))
+                ),
+                "free up space (build caches, `wreckit gc`) or lower disk.min_free_mb",
+            ));
+        }
+    }
+
+    let untracked = git::run_git_command(
+        &[
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+            "--",
+            ".",
+            ":(exclude).wreckit",
+        ],
+        &ctx.git_options(),
+    )
+    .await?;
+    for path in untracked.lines().filter(|line| !line.is_empty()) {
+        let size = std::fs::metadata(ctx.root.join(path))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        if size > config.max_untracked_mb.saturating_mul(MIB) {
+            warnings.push(DiskWarning::new(
+                "untracked-file",
+                format!("untracked {} is {}", path, format_bytes(size)),
+                "delete it or add it to .gitignore, so the agent does not commit it",
+            ));
+        }
+    }
+
+    let wreckit = dir_size(&fs::get_wreckit_dir(&ctx.root));
+    if wreckit > config.max_wreckit_mb.saturating_mul(MIB) {
+        warnings.push(DiskWarning::new(
+            "wreckit-size",
+            format!(".wreckit is {}", format_bytes(wreckit)),
+            "run `wreckit gc` to prune transcripts and logs, or raise disk.max_wreckit_mb",
+        ));
+    }
+    Ok(warnings)
+}
+
+/// Log a warning for each disk problem before a run. Failures to check are
+/// logged too; they never stop the run.
+pub async fn warn_disk_problems(ctx: &WorkflowContext) {
+    match check_disk(ctx).await {
+        Ok(warnings) => {
+            for warning in warnings {
+                tracing::warn!("{} ({})", warning.message, warning.fix);
+            }
+        }
+        Err(e) => tracing::warn!("Could not check disk space: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+
+    #[tokio::test]
+    async fn test_check_disk() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(root)
+            .status()
+            .unwrap();
+        std::fs::write(root.join("small.txt"), "x").unwrap();
+        std::fs::write(root.join("dump.bin"), vec![0u8; 2 * MIB as usize]).unwrap();
+        let wreckit = fs::get_wreckit_dir(root);
+        std::fs::create_dir_all(wreckit.join("logs")).unwrap();
+        std::fs::write(wreckit.join("logs/run.log"), vec![0u8; MIB as usize + 1]).unwrap();
+        assert_eq!(dir_size(&wreckit), MIB + 1);
+
+        let mut config = Config::default();
+        config.disk.min_free_mb = 0;
+        config.disk.max_untracked_mb = 1;
+        config.disk.max_wreckit_mb = 1;
+        let ctx = WorkflowContext::new(root.to_path_buf(), config);
+        let warnings = check_disk(&ctx).await.unwrap();
+        let checks: Vec<&str> = warnings.iter().map(|w| w.check.as_str()).collect();
+        assert!(checks.contains(&"wreckit-size"));
+        let untracked: Vec<&DiskWarning> = warnings
+            .iter()
+            .filter(|w| w.check == "untracked-file")
+            .collect();
+        assert_eq!(untracked.len(), 1);
+        assert!(untracked[0].message.contains("dump.bin is 2.0 MiB"));
+        assert!(!checks.contains(&"free-space"));
+
+        let mut config = Config::default();
+        config.disk.min_free_mb = u64::MAX / MIB;
+        let ctx = WorkflowContext::new(root.to_path_buf(), config);
+        let warnings = check_disk(&ctx).await.unwrap();
+        if cfg!(unix) {
+            assert_eq!(warnings[0].check, "free-space");
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Draft PR kept current during implementation
+//!
+//! With `pr.draft_early`, a draft PR is opened as soon as the implement loop
+//! commits its first story, and its body is rewritten with a story
+//! checklist after every story after that, so stakeholders can follow
+//! progress on the PR without the TUI. The PR phase then replaces the body
+//! with the final description and marks the PR ready for review.
+
+use crate::fs;
+use crate::git;
+use crate::schemas::{Item, Prd, StoryStatus};
+
+use super::context::WorkflowContext;
+
+/// PR body listing every story, done ones checked off
+pub fn progress_body(item: &Item, prd: &Prd) -> String {
+    let mut stories = prd.user_stories.clone();
+    stories.sort_by_key(|story| story.priority);
+    let done = stories
+        .iter()
+        .filter(|story| story.status == StoryStatus::Done)
+        .count();
+    let mut body = format!(
+        "{}\n\n## Progress ({}/{} stories done)\n",
+        item.overview.trim(),
+        done,
+        stories.len()
+    );
+    for story in &stories {
+        if story.status == StoryStatus::Done {
+            body.push_str(&format!("\n- [x] ✅ {} - {}", story.id, story.title));
+        } else {
+            body.push_str(&format!("\n- [ ] {} - {}", story.id, story.title));
+        }
+    }
+    body.push_str("\n\n_Draft opened by wreckit; updated after each story._");
+    body.trim_start().to_string()
+}
+
+/// Push the item branch and open or update its draft PR. Failures are only
+/// logged; the draft is informational. Skipped while offline.
+pub async fn update_draft_pr(ctx: &WorkflowContext, item: &Item) {
+    if !ctx.config.pr.draft_early || ctx.dry_run || ctx.offline {
+        return;
+    }
+    let Ok(prd) = fs::read_prd(&ctx.root, &item.id) else {
+        return;
+    };
+    let options = ctx.git_options();
+    let branch = ctx.branch_name(item);
+    let body = progress_body(item, &prd);
+    let updated = async {
+        git::push_branch(&branch, &options).await?;
+        match git::get_pr_by_branch(&branch, &options).await {
+            Some(pr) => git::edit_pr(pr.number, None, &body, &options).await,
+            None => {
+                let pr = git::create_draft_pr(
+                    &ctx.config.base_branch,
+                    &branch,
+                    &item.title,
+                    &body,
+                    &options,
+                )
+                .await?;
+                tracing::info!("Opened draft PR for {}: {}", item.id, pr.url);
+                Ok(())
+            }
+        }
+    }
+    .await;
+    if let Err(e) = updated {
+        tracing::warn!("Failed to update the draft PR for {}: {}", item.id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Story;
+
+    #[test]
+    fn test_progress_body() {
+        let item = Item::new(
+            "001-a".to_string(),
+            "A".to_string(),
+            "Adds login".to_string(),
+        );
+        let prd = Prd::new(item.id.clone(), "wreckit/001-a".to_string())
+            .with_story(Story::new(
+                "US-002".to_string(),
+                "Logout".to_string(),
+                vec![],
+                2,
+            ))
+            .with_story(Story::new(
+                "US-001".to_string(),
+                "Login".to_string(),
+                vec![],
+                1,
+            ))
+            .with_story_done("US-001");
+        let body = progress_body(&item, &prd);
+        assert!(body.starts_with("Adds login\n\n## Progress (1/2 stories done)"));
+        let done = body.find("- [x] ✅ US-001 - Login").unwrap();
+        let pending = body.find("- [ ] US-002 - Logout").unwrap();
+        assert!(done < pending);
+    }
+}
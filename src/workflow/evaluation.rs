@@ -0,0 +1,252 @@
+//! Self-evaluation of phase artifacts
+//!
+//! With `evaluation.enabled`, once the research or plan phase has run the
+//! agent, a second pass scores the artifact against a rubric (completeness,
+//! feasibility, risks; 1-5 each) and records the score on the item. An
+//! artifact whose lowest criterion falls below `evaluation.min_score` is
+//! revised: the phase runs again with the evaluation as operator feedback,
+//! up to `max_revisions` times. One that still scores low (or any low score,
+//! with `on_low_score = "gate"`) blocks the item until the operator reviews
+//! it and runs `wreckit unblock`.
+
+use crate::errors::Result;
+use crate::fs;
+use crate::schemas::{ArtifactScore, Blocker, Item, LowScoreAction};
+use crate::tui::TuiUpdate;
+
+use super::context::{check_agent_result, AgentRole, WorkflowContext};
+use super::phases::{Phase, PhaseKind};
+
+const EVALUATION_JSON_START: &str = "EVALUATION_JSON_START";
+const EVALUATION_JSON_END: &str = "EVALUATION_JSON_END";
+
+/// Parse the rubric scores from the evaluator's output.
+///
+/// Expects `{"completeness": n, "feasibility": n, "risks": n, "summary": ...}`
+/// between `EVALUATION_JSON_START` and `EVALUATION_JSON_END` markers, with
+/// every score from 1 to 5.
+pub fn parse_evaluation(output: &str) -> Option<ArtifactScore> {
+    let start = output.rfind(EVALUATION_JSON_START)? + EVALUATION_JSON_START.len();
+    let end = start + output[start..].find(EVALUATION_JSON_END)?;
+    let value: serde_json::Value = serde_json::from_str(output[start..end].trim()).ok()?;
+    let score = |name: &str| {
+        value[name]
+            .as_u64()
+            .filter(|n| (1..=5).contains(n))
+            .map(|n| n as u8)
+    };
+    Some(ArtifactScore {
+        completeness: score("completeness")?,
+        feasibility: score("feasibility")?,
+        risks: score("risks")?,
+        summary: value["summary"]
+            .as_str()
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        revisions: 0,
+        scored_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// The phase's artifacts as markdown sections, or None if it has none (or
+/// they are missing)
+fn artifact_text(ctx: &WorkflowContext, item: &Item, phase: PhaseKind) -> Option<String> {
+    let paths = match phase {
+        PhaseKind::Research => vec![fs::get_research_path(&ctx.root, &item.id)],
+        PhaseKind::Plan => vec![
+            fs::get_plan_path(&ctx.root, &item.id),
+            fs::get_prd_path(&ctx.root, &item.id),
+        ],
+        _ => return None,
+    };
+    let sections = paths
+        .iter()
+        .map(|path| {
+            let content = std::fs::read_to_string(path).ok()?;
+            let name = path.file_name()?.to_string_lossy();
+            Some(format!("### {}\n\n{}", name, content.trim()))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(sections.join("\n\n"))
+}
+
+/// Run the evaluation pass for a phase's artifact.
+///
+/// Returns None if the phase has no artifact to score or the evaluator's
+/// output has no readable scores.
+///
+/// # Errors
+/// * `AgentError` - If the evaluation agent fails
+/// * `FileNotFound` - If the evaluate prompt cannot be loaded
+pub async fn score_artifact(
+    ctx: &WorkflowContext,
+    item: &Item,
+    phase: PhaseKind,
+) -> Result<Option<ArtifactScore>> {
+    let Some(artifact) = artifact_text(ctx, item, phase) else {
+        return Ok(None);
+    };
+    let mut variables = ctx.prompt_variables(item);
+    variables.research = None;
+    variables.plan = None;
+    variables.prd = None;
+    variables.progress = None;
+    variables.artifact = Some(artifact);
+    let prompt = ctx.render_prompt("evaluate", &item.id, variables)?;
+    let role = AgentRole {
+        label: "evaluate",
+        model: ctx.config.evaluation.model.as_deref(),
+    };
+    let result = ctx.run_agent_as(&item.id, phase, role, prompt).await?;
+    check_agent_result(&result)?;
+    let score = parse_evaluation(&result.output);
+    if score.is_none() {
+        tracing::warn!(
+            "The {} evaluation for {} had no readable scores; skipping it",
+            phase,
+            item.id
+        );
+    }
+    Ok(score)
+}
+
+fn describe(phase: PhaseKind, score: &ArtifactScore) -> String {
+    format!(
+        "{} scored {}/5 (completeness {}, feasibility {}, risks {})",
+        phase,
+        score.overall(),
+        score.completeness,
+        score.feasibility,
+        score.risks
+    )
+}
+
+/// Score the artifact a phase's agent just wrote, revising or holding the
+/// item for approval if it scores below `evaluation.min_score`. Returns the
+/// item with the score recorded (and a blocker, if held). No-op unless the
+/// phase is configured for evaluation, and in dry-run mode.
+///
+/// # Errors
+/// * `AgentError` - If a revision or evaluation agent fails
+/// * `FileNotFound` - If a prompt cannot be loaded
+pub async fn review_artifact<P: Phase>(
+    phase: &P,
+    ctx: &WorkflowContext,
+    mut item: Item,
+) -> Result<Item> {
+    let kind = phase.kind();
+    let config = &ctx.config.evaluation;
+    if ctx.dry_run || !config.evaluates(kind.name()) {
+        return Ok(item);
+    }
+
+    let mut revisions = 0;
+    loop {
+        let Some(mut score) = score_artifact(ctx, &item, kind).await? else {
+            return Ok(item);
+        };
+        score.revisions = revisions;
+        let overall = score.overall();
+        let summary = score.summary.clone();
+        let description = describe(kind, &score);
+        item = item.with_score(kind.name(), score);
+        if overall >= config.min_score {
+            tracing::info!("{} {}", item.id, description);
+            return Ok(item);
+        }
+
+        if config.on_low_score == LowScoreAction::Revise && revisions < config.max_revisions {
+            revisions += 1;
+            tracing::info!(
+                "{} {}; revising (pass {} of {})",
+                item.id,
+                description,
+                revisions,
+                config.max_revisions
+            );
+            let feedback = format!(
+                "Self-evaluation: the {} {}, below the minimum of {}. Revise it to address:\n{}",
+                kind.name(),
+                description,
+                config.min_score,
+                summary
+            );
+            let feedback = match ctx.feedback {
+                Some(ref operator) => format!("{}\n\n{}", operator.trim(), feedback),
+                None => feedback,
+            };
+            let revise_ctx = ctx.clone().with_feedback(Some(feedback));
+            if let Some(prompt) = phase.build_prompt(&revise_ctx, &item)? {
+                item = phase.run_agent(&revise_ctx, item, prompt).await?;
+            }
+            continue;
+        }
+
+        let reason = format!(
+            "{} (minimum {}): {}; review it and run `wreckit unblock {}`",
+            description, config.min_score, summary, item.id
+        );
+        tracing::warn!("{} is awaiting approval: {}", item.id, reason);
+        ctx.emit(TuiUpdate::AwaitingApproval(item.id.clone(), reason.clone()));
+        return Ok(item.with_blocked(Some(Blocker::new(reason))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{MockFixture, DEFAULT_FIXTURES_DIR};
+    use crate::schemas::{AgentMode, Config, WorkflowState};
+    use crate::workflow::phases::{run_phase, ResearchPhase};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_evaluation() {
+        let output = "Looked at the plan.\nEVALUATION_JSON_START\n{\"completeness\": 4, \"feasibility\": 2, \"risks\": 3, \"summary\": \"No migration step\"}\nEVALUATION_JSON_END\nDONE";
+        let score = parse_evaluation(output).unwrap();
+        assert_eq!(
+            (score.completeness, score.feasibility, score.risks),
+            (4, 2, 3)
+        );
+        assert_eq!(score.overall(), 2);
+        assert_eq!(score.summary, "No migration step");
+
+        assert!(parse_evaluation("no scores").is_none());
+        let out_of_range = "EVALUATION_JSON_START {\"completeness\": 9, \"feasibility\": 2, \"risks\": 3} EVALUATION_JSON_END";
+        assert!(parse_evaluation(out_of_range).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_low_score_holds_item_for_approval() {
+        let temp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.agent.mode = AgentMode::Mock;
+        config.context_pack.enabled = false;
+        config.evaluation.enabled = true;
+        config.evaluation.on_low_score = LowScoreAction::Gate;
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+
+        // The mock answers the research and evaluation calls alike
+        let fixture = MockFixture {
+            output: "EVALUATION_JSON_START {\"completeness\": 2, \"feasibility\": 4, \"risks\": 4, \"summary\": \"Misses the API\"} EVALUATION_JSON_END".to_string(),
+            files: [("research.md".to_string(), "# Research".to_string())].into(),
+            complete: true,
+            ..Default::default()
+        };
+        fs::write_json(
+            &temp.path().join(DEFAULT_FIXTURES_DIR).join("research.json"),
+            &fixture,
+        )
+        .unwrap();
+        let item = Item::new("001-test".to_string(), "Test".to_string(), String::new());
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+
+        let item = run_phase(&ResearchPhase, &ctx, item).await.unwrap();
+        assert_eq!(item.state, WorkflowState::Researched);
+        assert_eq!(item.scores["research"].overall(), 2);
+        let blocker = item.blocked.unwrap();
+        assert!(blocker.reason.contains("Misses the API"));
+        assert!(fs::read_item(temp.path(), &item.id).unwrap().is_blocked());
+    }
+}
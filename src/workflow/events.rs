@@ -0,0 +1,168 @@
+//! Workflow lifecycle events
+//!
+//! Every [`WorkflowContext`](super::WorkflowContext) carries an
+//! [`EventBus`]. The workflow publishes a typed [`WorkflowEvent`] when an item
+//! is created or changes state, when a phase starts or finishes, when a PR
+//! is opened, and when a phase fails. Any number of consumers subscribe to
+//! the same stream — hooks, webhooks, and library users through
+//! [`Wreckit::subscribe`](crate::api::Wreckit::subscribe) — and the events
+//! the renderer cares about are forwarded to it as [`TuiUpdate`]s.
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::tui::runner::TuiUpdate;
+
+/// Events buffered per subscriber before the slowest one starts missing events
+const EVENT_CAPACITY: usize = 256;
+
+/// Something that happened in the workflow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WorkflowEvent {
+    /// An item was added to the backlog
+    ItemCreated { item_id: String, state: String },
+
+    /// An item moved to a new state
+    StateChanged {
+        item_id: String,
+        from: String,
+        to: String,
+    },
+
+    /// A phase began running for an item
+    PhaseStarted { item_id: String, phase: String },
+
+    /// A phase finished for an item, successfully or not
+    PhaseFinished {
+        item_id: String,
+        phase: String,
+        success: bool,
+        seconds: u64,
+    },
+
+    /// A pull request was opened for an item
+    PrOpened {
+        item_id: String,
+        number: u32,
+        url: String,
+    },
+
+    /// A phase failed; `code` is the failure class (e.g. "validation")
+    Error {
+        item_id: String,
+        message: String,
+        code: String,
+    },
+}
+
+impl WorkflowEvent {
+    /// The item the event is about
+    pub fn item_id(&self) -> &str {
+        match self {
+            WorkflowEvent::ItemCreated { item_id, .. }
+            | WorkflowEvent::StateChanged { item_id, .. }
+            | WorkflowEvent::PhaseStarted { item_id, .. }
+            | WorkflowEvent::PhaseFinished { item_id, .. }
+            | WorkflowEvent::PrOpened { item_id, .. }
+            | WorkflowEvent::Error { item_id, .. } => item_id,
+        }
+    }
+
+    /// The event's name (e.g. "phase_started")
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorkflowEvent::ItemCreated { .. } => "item_created",
+            WorkflowEvent::StateChanged { .. } => "state_changed",
+            WorkflowEvent::PhaseStarted { .. } => "phase_started",
+            WorkflowEvent::PhaseFinished { .. } => "phase_finished",
+            WorkflowEvent::PrOpened { .. } => "pr_opened",
+            WorkflowEvent::Error { .. } => "error",
+        }
+    }
+
+    /// The renderer update showing this event, if the renderer shows it
+    pub fn renderer_update(&self) -> Option<TuiUpdate> {
+        match self {
+            WorkflowEvent::ItemCreated { item_id, state }
+            | WorkflowEvent::StateChanged {
+                item_id, to: state, ..
+            } => Some(TuiUpdate::SetItemState(item_id.clone(), state.clone())),
+            WorkflowEvent::PhaseStarted { phase, .. } => {
+                Some(TuiUpdate::SetCurrentPhase(Some(phase.clone())))
+            }
+            WorkflowEvent::PrOpened { item_id, url, .. } => {
+                Some(TuiUpdate::AppendLogs(vec![format!(
+                    "{}: opened {}",
+                    item_id, url
+                )]))
+            }
+            WorkflowEvent::Error {
+                item_id, message, ..
+            } => Some(TuiUpdate::ItemFailed(item_id.clone(), message.clone())),
+            WorkflowEvent::PhaseFinished { .. } => None,
+        }
+    }
+}
+
+/// Broadcasts workflow events to every subscriber. Clones share the stream.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<WorkflowEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    /// Create a bus with no subscribers
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        EventBus { sender }
+    }
+
+    /// Receive every event published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<WorkflowEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Deliver an event to current subscribers (dropped if there are none)
+    pub fn publish(&self, event: WorkflowEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribers_share_events() {
+        let bus = EventBus::new();
+        bus.publish(WorkflowEvent::PhaseStarted {
+            item_id: "001".to_string(),
+            phase: "plan".to_string(),
+        });
+
+        let mut first = bus.subscribe();
+        let mut second = bus.clone().subscribe();
+        let event = WorkflowEvent::PrOpened {
+            item_id: "001".to_string(),
+            number: 7,
+            url: "https://example.com/pr/7".to_string(),
+        };
+        bus.publish(event.clone());
+        assert_eq!(first.try_recv().unwrap(), event);
+        assert_eq!(second.try_recv().unwrap(), event);
+        assert!(first.try_recv().is_err());
+
+        assert_eq!(event.name(), "pr_opened");
+        assert_eq!(event.item_id(), "001");
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "pr_opened");
+        assert_eq!(json["number"], 7);
+    }
+}
@@ -0,0 +1,94 @@
+//! Prompt A/B experiments
+//!
+//! A phase prompt can have named variants next to it in `.wreckit/prompts/`
+//! (`implement.a.md`, `implement.b.md`). `phases.<phase>.prompt_selection`
+//! decides which one a run uses: `fixed` always uses `prompt_variant` (the
+//! base template if unset), while `round_robin` picks the variant with the
+//! fewest recorded runs so every variant gets its turn. The chosen variant is
+//! recorded in the run's transcripts and in `.wreckit/stats.json`, where
+//! `wreckit stats` reports each variant's success rate.
+
+use crate::prompts::list_prompt_variants;
+use crate::schemas::PromptSelection;
+
+use super::context::WorkflowContext;
+use super::stats::read_stats;
+
+/// The prompt variant the next run of a phase should use (`None` for the
+/// base template). Stable until the run is recorded, so every prompt of a
+/// phase run uses the same variant.
+pub fn select_prompt_variant(ctx: &WorkflowContext, phase: &str) -> Option<String> {
+    let settings = ctx.config.phase(phase);
+    match settings.prompt_selection {
+        PromptSelection::Fixed => settings.prompt_variant,
+        PromptSelection::RoundRobin => {
+            let stats = read_stats(&ctx.root).unwrap_or_default();
+            let runs = |variant: &String| {
+                stats
+                    .phases
+                    .get(phase)
+                    .and_then(|phase| phase.variants.get(variant))
+                    .map_or(0, |outcome| outcome.runs)
+            };
+            // Variants are sorted, so ties go to the first by name
+            list_prompt_variants(&ctx.root, phase)
+                .into_iter()
+                .min_by_key(runs)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs;
+    use crate::schemas::{Config, PhaseConfig};
+    use crate::workflow::phases::PhaseKind;
+    use crate::workflow::stats::record_phase_run;
+    use tempfile::TempDir;
+
+    fn context(selection: PromptSelection, variant: Option<&str>) -> (TempDir, WorkflowContext) {
+        let temp = TempDir::new().unwrap();
+        let prompts = fs::get_prompts_dir(temp.path());
+        std::fs::create_dir_all(&prompts).unwrap();
+        std::fs::write(prompts.join("implement.a.md"), "A").unwrap();
+        std::fs::write(prompts.join("implement.b.md"), "B").unwrap();
+
+        let mut config = Config::default();
+        config.phases.insert(
+            "implement".to_string(),
+            PhaseConfig {
+                prompt_variant: variant.map(String::from),
+                prompt_selection: selection,
+                ..Default::default()
+            },
+        );
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        (temp, ctx)
+    }
+
+    #[test]
+    fn test_fixed_selection() {
+        let (_temp, ctx) = context(PromptSelection::Fixed, Some("b"));
+        assert_eq!(
+            select_prompt_variant(&ctx, "implement").as_deref(),
+            Some("b")
+        );
+        assert_eq!(select_prompt_variant(&ctx, "plan"), None);
+
+        let (_temp, ctx) = context(PromptSelection::Fixed, None);
+        assert_eq!(select_prompt_variant(&ctx, "implement"), None);
+    }
+
+    #[test]
+    fn test_round_robin_rotates() {
+        let (_temp, ctx) = context(PromptSelection::RoundRobin, None);
+        let mut picked = Vec::new();
+        for _ in 0..3 {
+            let variant = select_prompt_variant(&ctx, "implement").unwrap();
+            record_phase_run(&ctx, PhaseKind::Implement, 1, false, Some(&variant), None);
+            picked.push(variant);
+        }
+        assert_eq!(picked, vec!["a", "b", "a"]);
+    }
+}
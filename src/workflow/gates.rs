@@ -0,0 +1,255 @@
+//! Metric threshold gates
+//!
+//! A verify check with a `gate` parses a number from its output (e.g. a
+//! coverage percentage) and compares it against a minimum and/or maximum.
+//! Gated checks do not run after every story; they run once when the item is
+//! about to move to in_pr. If a threshold is missed, the shortfall is added
+//! to prd.json as a pending story whose acceptance criteria state the target,
+//! so the next implement run works on closing the gap before a PR is opened.
+
+use regex::Regex;
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::{Item, MetricGate, Prd, Story, StoryStatus, VerifyCheck};
+
+use super::context::WorkflowContext;
+use super::implement_loop::run_verify_in;
+
+/// Prefix of the story IDs created for missed gates
+pub const GATE_STORY_PREFIX: &str = "GATE-";
+
+/// Result of evaluating one gated check
+#[derive(Debug, Clone, PartialEq)]
+pub struct GateOutcome {
+    /// Check name
+    pub name: String,
+
+    /// Metric parsed from the check's output, if one was found
+    pub value: Option<f64>,
+
+    /// Whether the metric met every threshold
+    pub passed: bool,
+
+    /// The acceptance criterion that states the target
+    pub criterion: String,
+}
+
+fn describe_target(gate: &MetricGate) -> String {
+    match (gate.min, gate.max) {
+        (Some(min), Some(max)) => format!("between {} and {}", min, max),
+        (Some(min), None) => format!("at least {}", min),
+        (None, Some(max)) => format!("at most {}", max),
+        (None, None) => "reported".to_string(),
+    }
+}
+
+/// Parse the gate's metric from command output and compare it to the thresholds.
+///
+/// # Errors
+/// * `ConfigError` - If the gate's pattern is not a valid regex
+pub fn evaluate_gate(check_name: &str, gate: &MetricGate, output: &str) -> Result<GateOutcome> {
+    let regex = Regex::new(&gate.pattern).map_err(|e| {
+        WreckitError::ConfigError(format!("invalid gate pattern for {}: {}", check_name, e))
+    })?;
+    let value = regex
+        .captures_iter(output)
+        .last()
+        .and_then(|caps| caps.get(1).or_else(|| caps.get(0)))
+        .and_then(|m| m.as_str().trim().parse::<f64>().ok());
+
+    let passed = value.is_some_and(|v| {
+        gate.min.is_none_or(|min| v >= min) && gate.max.is_none_or(|max| v <= max)
+    });
+    let target = describe_target(gate);
+    let criterion = match value {
+        Some(v) => format!(
+            "`{}` metric must be {} (currently {})",
+            check_name, target, v
+        ),
+        None => format!(
+            "`{}` metric must be {} (no value found in the check output)",
+            check_name, target
+        ),
+    };
+    Ok(GateOutcome {
+        name: check_name.to_string(),
+        value,
+        passed,
+        criterion,
+    })
+}
+
+/// The configured checks that carry a metric gate, with their gates
+pub fn gated_checks(checks: &[VerifyCheck]) -> Vec<(&VerifyCheck, &MetricGate)> {
+    checks
+        .iter()
+        .filter_map(|check| check.gate.as_ref().map(|gate| (check, gate)))
+        .collect()
+}
+
+/// Add (or reopen) a pending story that fixes a pre-PR check failure.
+///
+/// A new story is ordered after every existing one; a story that already
+/// exists keeps its priority and gets the new criteria and notes.
+pub fn with_remediation_story(prd: &Prd, story: Story) -> Prd {
+    let priority = match prd.user_stories.iter().find(|s| s.id == story.id) {
+        Some(existing) => existing.priority,
+        None => {
+            prd.user_stories
+                .iter()
+                .map(|s| s.priority)
+                .max()
+                .unwrap_or(0)
+                + 1
+        }
+    };
+    prd.with_story(Story {
+        priority,
+        status: StoryStatus::Pending,
+        ..story
+    })
+}
+
+/// Run every gated check and require its threshold before an item enters in_pr.
+///
+/// Each missed threshold becomes (or reopens) a `GATE-<name>` story in
+/// prd.json with the target as its acceptance criterion. No-op in dry-run
+/// mode or when no checks are gated.
+///
+/// # Errors
+/// * `StateTransition` - If any threshold is not met
+/// * `ConfigError` - If a gate pattern is invalid
+/// * `Timeout` / `Io` - If a check command cannot be run
+pub async fn enforce_gates(ctx: &WorkflowContext, item: &Item) -> Result<()> {
+    let checks = ctx.config.verify_checks();
+    let gated = gated_checks(&checks);
+    if ctx.dry_run || gated.is_empty() {
+        return Ok(());
+    }
+
+    let env = ctx.verify_env(item)?;
+    let mut missed = Vec::new();
+    for (check, gate) in gated {
+        let run = run_verify_in(
+            ctx.config.agent.execution,
+            ctx.config.env_activation,
+            &check.cmd,
+            &ctx.root,
+            ctx.config.timeout_seconds,
+            &env,
+        )
+        .await?;
+        let outcome = evaluate_gate(&check.name, gate, &run.output)?;
+        if outcome.passed {
+            tracing::info!("{}: gate {} met", item.id, outcome.name);
+        } else {
+            missed.push(outcome);
+        }
+    }
+    if missed.is_empty() {
+        return Ok(());
+    }
+
+    let mut prd = fs::read_prd(&ctx.root, &item.id)?;
+    for outcome in &missed {
+        prd = with_remediation_story(
+            &prd,
+            Story::new(
+                format!("{}{}", GATE_STORY_PREFIX, outcome.name),
+                format!("Meet the {} threshold", outcome.name),
+                vec![outcome.criterion.clone()],
+                0,
+            ),
+        );
+    }
+    fs::write_prd(&ctx.root, &item.id, &prd)?;
+
+    let criteria: Vec<&str> = missed.iter().map(|o| o.criterion.as_str()).collect();
+    Err(WreckitError::StateTransition(format!(
+        "quality gate not met: {}",
+        criteria.join("; ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use tempfile::TempDir;
+
+    fn coverage(min: f64) -> MetricGate {
+        MetricGate {
+            pattern: r"TOTAL.*?(\d+(?:\.\d+)?)%".to_string(),
+            min: Some(min),
+            max: None,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_gate() {
+        let output = "src/lib.rs 90.0%\nTOTAL 1200 lines 72.5%\n";
+        let outcome = evaluate_gate("coverage", &coverage(80.0), output).unwrap();
+        assert_eq!(outcome.value, Some(72.5));
+        assert!(!outcome.passed);
+        assert_eq!(
+            outcome.criterion,
+            "`coverage` metric must be at least 80 (currently 72.5)"
+        );
+
+        assert!(
+            evaluate_gate("coverage", &coverage(70.0), output)
+                .unwrap()
+                .passed
+        );
+
+        let missing = evaluate_gate("coverage", &coverage(70.0), "no data").unwrap();
+        assert!(!missing.passed);
+        assert!(missing.criterion.contains("no value found"));
+
+        let bad = MetricGate {
+            pattern: "(".to_string(),
+            min: None,
+            max: Some(1.0),
+        };
+        assert!(matches!(
+            evaluate_gate("x", &bad, ""),
+            Err(WreckitError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_missed_gate_adds_story() {
+        let temp = TempDir::new().unwrap();
+        let config = Config {
+            verify: vec![
+                VerifyCheck::new("test", "true"),
+                VerifyCheck::new("coverage", "echo 'TOTAL 64.0%'").with_gate(coverage(80.0)),
+            ],
+            ..Default::default()
+        };
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        let item = Item::new("001-test".to_string(), "Test".to_string(), String::new());
+        let prd = Prd::new(item.id.clone(), "wreckit/001-test".to_string())
+            .with_story(Story::new("US-001".to_string(), "Only".to_string(), vec![], 3).as_done());
+        fs::write_prd(temp.path(), &item.id, &prd).unwrap();
+
+        let err = enforce_gates(&ctx, &item).await.unwrap_err();
+        assert!(matches!(err, WreckitError::StateTransition(_)));
+        assert!(err.to_string().contains("currently 64"));
+
+        let prd = fs::read_prd(temp.path(), &item.id).unwrap();
+        let gate = prd.next_pending_story().unwrap();
+        assert_eq!(gate.id, "GATE-coverage");
+        assert_eq!(gate.priority, 4);
+        assert_eq!(
+            gate.acceptance_criteria,
+            vec!["`coverage` metric must be at least 80 (currently 64)"]
+        );
+
+        // Once the threshold is met the item may proceed
+        let mut ctx = ctx;
+        ctx.config.verify[1].cmd = "echo 'TOTAL 85%'".to_string();
+        enforce_gates(&ctx, &item).await.unwrap();
+    }
+}
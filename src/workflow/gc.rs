@@ -0,0 +1,509 @@
+//! Garbage collection for stale artifacts
+//!
+//! `wreckit gc` prunes agent transcripts and recorded runs older than
+//! `gc.log_retention_days`, cuts progress.log files over `gc.max_progress_log_kb` down to their most
+//! recent lines, removes temp files left behind by interrupted writes, and
+//! deletes local item branches that are fully merged into the base branch.
+//! `--branches` also sweeps origin: merged item branches there, and the
+//! branches of done items (whose squash merges git cannot see as merged) in
+//! both places. With `--dry-run` it only reports what it would remove.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::errors::Result;
+use crate::fs;
+use crate::git;
+use crate::schemas::{Item, WorkflowState};
+
+use super::context::WorkflowContext;
+use super::run_log::list_runs;
+
+/// Temp files younger than this may belong to a write still in progress
+const ORPHAN_MIN_AGE: Duration = Duration::from_secs(60);
+
+/// Suffixes of the temp files written by atomic JSON and journal writes
+const TEMP_SUFFIXES: &[&str] = &[".json.tmp", ".journal.tmp"];
+
+/// Marker line written at the top of a truncated progress.log
+const TRUNCATED_MARKER: &str = "[earlier entries removed by wreckit gc]";
+
+/// What a gc run removed (or would remove, in dry-run mode)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GcReport {
+    /// Transcript files pruned
+    pub logs_removed: Vec<PathBuf>,
+
+    /// Run event files pruned
+    pub runs_removed: Vec<PathBuf>,
+
+    /// progress.log files truncated
+    pub logs_truncated: Vec<PathBuf>,
+
+    /// Orphaned temp files removed
+    pub temp_files_removed: Vec<PathBuf>,
+
+    /// Merged local branches deleted
+    pub branches_deleted: Vec<String>,
+
+    /// Branches deleted on origin
+    pub remote_branches_deleted: Vec<String>,
+
+    /// Bytes freed on disk
+    pub bytes_reclaimed: u64,
+}
+
+impl GcReport {
+    /// Whether nothing needed collecting
+    pub fn is_empty(&self) -> bool {
+        self.logs_removed.is_empty()
+            && self.runs_removed.is_empty()
+            && self.logs_truncated.is_empty()
+            && self.temp_files_removed.is_empty()
+            && self.branches_deleted.is_empty()
+            && self.remote_branches_deleted.is_empty()
+    }
+}
+
+/// Format a byte count for humans (e.g. "1.5 MiB")
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+fn age(path: &Path, now: SystemTime) -> Option<Duration> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    now.duration_since(modified).ok()
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn item_ids(root: &Path) -> Result<Vec<String>> {
+    let dir = fs::get_items_dir(root);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut ids = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            ids.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    ids.sort();
+    Ok(ids)
+}
+
+fn collect_temp_files(dir: &Path, found: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_temp_files(&path, found)?;
+        } else if TEMP_SUFFIXES
+            .iter()
+            .any(|suffix| path.to_string_lossy().ends_with(suffix))
+        {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Keep roughly the last `keep` bytes of a log, starting at a line boundary
+fn log_tail(content: &str, keep: usize) -> String {
+    let mut start = content.len().saturating_sub(keep);
+    while !content.is_char_boundary(start) {
+        start += 1;
+    }
+    let tail = &content[start..];
+    let tail = match tail.find('\n') {
+        Some(newline) if start > 0 => &tail[newline + 1..],
+        _ => tail,
+    };
+    format!("{}\n{}", TRUNCATED_MARKER, tail)
+}
+
+async fn has_origin(options: &git::GitOptions) -> bool {
+    git::run_git_command(&["remote", "get-url", "origin"], options)
+        .await
+        .is_ok()
+}
+
+/// Delete a done item's branch locally and on origin, returning the local
+/// and remote branches removed. The branch is force-deleted since a squash
+/// merge leaves it unmerged as far as git can tell; the base branch is
+/// checked out first if the item branch is current. In dry-run mode the
+/// branches are found and returned but not deleted.
+///
+/// # Errors
+/// * `GitError` - If a branch cannot be deleted
+pub async fn prune_item_branch(
+    ctx: &WorkflowContext,
+    item: &Item,
+) -> Result<(Vec<String>, Vec<String>)> {
+    let (mut local, mut remote) = (Vec::new(), Vec::new());
+    let branch = match item.branch {
+        Some(ref branch) if item.state == WorkflowState::Done => branch,
+        _ => return Ok((local, remote)),
+    };
+    let options = ctx.git_options();
+    let read = git::GitOptions {
+        dry_run: false,
+        ..options.clone()
+    };
+    if git::branch_exists(branch, &read).await {
+        if !ctx.dry_run {
+            if git::get_current_branch(&read).await.ok().as_ref() == Some(branch) {
+                git::run_git_command(&["checkout", &ctx.config.base_branch], &options).await?;
+            }
+            git::run_git_command(&["branch", "-D", branch], &options).await?;
+        }
+        local.push(branch.clone());
+    }
+    if has_origin(&read).await && git::remote_branch_exists(branch, &read).await {
+        if !ctx.dry_run {
+            git::delete_remote_branch(branch, &options).await?;
+        }
+        remote.push(branch.clone());
+    }
+    Ok((local, remote))
+}
+
+/// Collect stale artifacts under `.wreckit/` and merged item branches, and
+/// with `branches`, item branches on origin and those of done items.
+///
+/// # Errors
+/// * `Io` - If a file cannot be read or removed
+/// * `GitError` - If merged branches cannot be listed or deleted
+pub async fn run_gc(ctx: &WorkflowContext, branches: bool) -> Result<GcReport> {
+    let now = SystemTime::now();
+    let retention = Duration::from_secs(ctx.config.gc.log_retention_days as u64 * 24 * 60 * 60);
+    let max_log = ctx.config.gc.max_progress_log_kb * 1024;
+    let mut report = GcReport::default();
+
+    for id in item_ids(&ctx.root)? {
+        let transcripts = fs::get_transcripts_dir(&ctx.root, &id);
+        if transcripts.exists() {
+            let mut paths: Vec<PathBuf> = std::fs::read_dir(&transcripts)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .collect();
+            paths.sort();
+            for path in paths {
+                if age(&path, now).is_some_and(|age| age > retention) {
+                    report.bytes_reclaimed += file_size(&path);
+                    if !ctx.dry_run {
+                        fs::remove_file(&path)?;
+                    }
+                    report.logs_removed.push(path);
+                }
+            }
+        }
+
+        let progress = fs::get_progress_log_path(&ctx.root, &id);
+        let size = file_size(&progress);
+        if max_log > 0 && size > max_log {
+            let content = std::fs::read_to_string(&progress)?;
+            // Leave headroom so the log does not cross the limit again right away
+            let truncated = log_tail(&content, (max_log / 2) as usize);
+            report.bytes_reclaimed += size.saturating_sub(truncated.len() as u64);
+            if !ctx.dry_run {
+                fs::write_file(&progress, truncated)?;
+            }
+            report.logs_truncated.push(progress);
+        }
+    }
+
+    for path in list_runs(&ctx.root)? {
+        if age(&path, now).is_some_and(|age| age > retention) {
+            report.bytes_reclaimed += file_size(&path);
+            if !ctx.dry_run {
+                fs::remove_file(&path)?;
+            }
+            report.runs_removed.push(path);
+        }
+    }
+
+    let wreckit_dir = fs::get_wreckit_dir(&ctx.root);
+    if wreckit_dir.exists() {
+        let mut temp_files = Vec::new();
+        collect_temp_files(&wreckit_dir, &mut temp_files)?;
+        temp_files.sort();
+        for path in temp_files {
+            if age(&path, now).is_some_and(|age| age >= ORPHAN_MIN_AGE) {
+                report.bytes_reclaimed += file_size(&path);
+                if !ctx.dry_run {
+                    fs::remove_file(&path)?;
+                }
+                report.temp_files_removed.push(path);
+            }
+        }
+    }
+
+    let options = ctx.git_options();
+    // Listing changes nothing, so a dry run still reports what it would delete
+    let read = git::GitOptions {
+        dry_run: false,
+        ..options.clone()
+    };
+    if git::is_git_repo(&ctx.root).await {
+        let current = git::get_current_branch(&read).await.ok();
+        let merged =
+            git::merged_branches(&ctx.config.base_branch, &ctx.config.branch_prefix, &read).await?;
+        for branch in merged {
+            if current.as_deref() == Some(branch.as_str()) {
+                continue;
+            }
+            if !ctx.dry_run {
+                git::run_git_command(&["branch", "-d", &branch], &options).await?;
+            }
+            report.branches_deleted.push(branch);
+        }
+    }
+
+    if branches && git::is_git_repo(&ctx.root).await {
+        for item in fs::list_items(&ctx.root)? {
+            let (local, remote) = prune_item_branch(ctx, &item).await?;
+            report.branches_deleted.extend(local);
+            report.remote_branches_deleted.extend(remote);
+        }
+        if has_origin(&read).await {
+            // A dry run lists against the last fetch rather than updating refs
+            git::run_git_command(&["fetch", "--prune", "origin"], &options).await?;
+            let merged = git::merged_remote_branches(
+                &ctx.config.base_branch,
+                &ctx.config.branch_prefix,
+                &read,
+            )
+            .await?;
+            for branch in merged {
+                if !ctx.dry_run {
+                    git::delete_remote_branch(&branch, &options).await?;
+                }
+                report.remote_branches_deleted.push(branch);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    fn set_age(path: &Path, days: u64) {
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60))
+            .unwrap();
+    }
+
+    fn setup() -> (TempDir, WorkflowContext) {
+        let temp = TempDir::new().unwrap();
+        git(temp.path(), &["init", "-q", "-b", "main"]);
+        git(temp.path(), &["config", "user.email", "test@example.com"]);
+        git(temp.path(), &["config", "user.name", "Test"]);
+        git(
+            temp.path(),
+            &["commit", "-q", "--allow-empty", "-m", "init"],
+        );
+        let config = Config {
+            gc: crate::schemas::GcConfig {
+                log_retention_days: 30,
+                max_progress_log_kb: 1,
+            },
+            ..Default::default()
+        };
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        (temp, ctx)
+    }
+
+    /// Lay out an old and a fresh transcript, a large progress.log, a temp
+    /// file, and an old and a fresh run
+    fn seed(root: &Path) -> (PathBuf, PathBuf, PathBuf) {
+        let transcripts = fs::get_transcripts_dir(root, "001-a");
+        std::fs::create_dir_all(&transcripts).unwrap();
+        let old = transcripts.join("001-research.json");
+        let fresh = transcripts.join("002-plan.json");
+        std::fs::write(&old, "{}").unwrap();
+        std::fs::write(&fresh, "{}").unwrap();
+        set_age(&old, 90);
+
+        let lines: Vec<String> = (0..200).map(|i| format!("line {}", i)).collect();
+        std::fs::write(
+            fs::get_progress_log_path(root, "001-a"),
+            lines.join("\n") + "\n",
+        )
+        .unwrap();
+
+        let temp_file = fs::get_item_dir(root, "001-a").join("item.json.tmp");
+        std::fs::write(&temp_file, "{").unwrap();
+        set_age(&temp_file, 1);
+
+        let runs = fs::get_runs_dir(root);
+        std::fs::create_dir_all(&runs).unwrap();
+        std::fs::write(runs.join("20250101T000000.000Z.jsonl"), "").unwrap();
+        std::fs::write(runs.join("20260101T000000.000Z.jsonl"), "").unwrap();
+        set_age(&runs.join("20250101T000000.000Z.jsonl"), 90);
+        (old, fresh, temp_file)
+    }
+
+    #[test]
+    fn test_log_tail_starts_at_line_boundary() {
+        let tail = log_tail("first\nsecond\nthird\n", 8);
+        assert_eq!(tail, format!("{}\nthird\n", TRUNCATED_MARKER));
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+    }
+
+    #[tokio::test]
+    async fn test_gc_prunes_stale_artifacts() {
+        let (temp, ctx) = setup();
+        let (old, fresh, temp_file) = seed(temp.path());
+
+        let report = run_gc(&ctx, false).await.unwrap();
+        assert_eq!(report.logs_removed, vec![old.clone()]);
+        assert_eq!(report.temp_files_removed, vec![temp_file.clone()]);
+        assert_eq!(report.logs_truncated.len(), 1);
+        let runs = fs::get_runs_dir(temp.path());
+        assert_eq!(
+            report.runs_removed,
+            vec![runs.join("20250101T000000.000Z.jsonl")]
+        );
+        assert!(report.bytes_reclaimed > 0);
+
+        assert!(!old.exists());
+        assert!(fresh.exists());
+        assert!(!temp_file.exists());
+        assert_eq!(list_runs(temp.path()).unwrap().len(), 1);
+        let progress =
+            std::fs::read_to_string(fs::get_progress_log_path(temp.path(), "001-a")).unwrap();
+        assert!(progress.starts_with(TRUNCATED_MARKER));
+        assert!(progress.ends_with("line 199\n"));
+        assert!(progress.len() <= 1024);
+    }
+
+    #[test]
+    fn test_gc_refuses_in_read_only_mode() {
+        let (temp, ctx) = setup();
+        let (old, _, temp_file) = seed(temp.path());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let err = fs::with_read_only(|| runtime.block_on(run_gc(&ctx, false))).unwrap_err();
+        assert!(matches!(err, crate::errors::WreckitError::ReadOnly(_)));
+        assert!(old.exists());
+        assert!(temp_file.exists());
+        assert_eq!(list_runs(temp.path()).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_gc_deletes_merged_item_branches() {
+        let (temp, ctx) = setup();
+        git(temp.path(), &["branch", "wreckit/001-merged"]);
+        git(temp.path(), &["checkout", "-q", "-b", "wreckit/002-open"]);
+        git(temp.path(), &["commit", "-q", "--allow-empty", "-m", "wip"]);
+        git(temp.path(), &["checkout", "-q", "main"]);
+        git(temp.path(), &["branch", "feature/other"]);
+
+        let report = run_gc(&ctx, false).await.unwrap();
+        assert_eq!(report.branches_deleted, vec!["wreckit/001-merged"]);
+        let options = ctx.git_options();
+        assert!(!git::branch_exists("wreckit/001-merged", &options).await);
+        assert!(git::branch_exists("wreckit/002-open", &options).await);
+        assert!(git::branch_exists("feature/other", &options).await);
+    }
+
+    #[tokio::test]
+    async fn test_gc_dry_run_changes_nothing() {
+        let (temp, ctx) = setup();
+        let ctx = ctx.with_dry_run(true);
+        let (old, _, temp_file) = seed(temp.path());
+        git(temp.path(), &["branch", "wreckit/001-merged"]);
+
+        let report = run_gc(&ctx, false).await.unwrap();
+        assert!(!report.is_empty());
+        assert_eq!(report.branches_deleted, ["wreckit/001-merged"]);
+        assert_eq!(report.runs_removed.len(), 1);
+        assert!(old.exists());
+        assert!(temp_file.exists());
+        assert_eq!(list_runs(temp.path()).unwrap().len(), 2);
+        let options = ctx.with_dry_run(false).git_options();
+        assert!(git::branch_exists("wreckit/001-merged", &options).await);
+    }
+
+    #[tokio::test]
+    async fn test_gc_sweeps_branches() {
+        let (temp, ctx) = setup();
+        let root = temp.path();
+        let origin = TempDir::new().unwrap();
+        git(origin.path(), &["init", "-q", "--bare", "-b", "main"]);
+        git(
+            root,
+            &["remote", "add", "origin", origin.path().to_str().unwrap()],
+        );
+        git(root, &["push", "-q", "origin", "main"]);
+
+        // Squash-merged: its commit never lands on main
+        git(root, &["checkout", "-q", "-b", "wreckit/001-done"]);
+        git(root, &["commit", "-q", "--allow-empty", "-m", "work"]);
+        git(root, &["push", "-q", "origin", "wreckit/001-done"]);
+        git(root, &["checkout", "-q", "main"]);
+        let item = Item::new("001-done".to_string(), "Done".to_string(), String::new())
+            .with_state(WorkflowState::Done)
+            .with_branch(Some("wreckit/001-done".to_string()));
+        fs::write_item(root, &item.id, &item).unwrap();
+
+        git(root, &["push", "-q", "origin", "main:wreckit/002-merged"]);
+        git(root, &["push", "-q", "origin", "main:feature/other"]);
+
+        let report = run_gc(&ctx, false).await.unwrap();
+        assert!(report.remote_branches_deleted.is_empty());
+
+        let dry_run = ctx.clone().with_dry_run(true);
+        let report = run_gc(&dry_run, true).await.unwrap();
+        assert_eq!(report.branches_deleted, vec!["wreckit/001-done"]);
+        assert_eq!(
+            report.remote_branches_deleted,
+            vec!["wreckit/001-done", "wreckit/002-merged"]
+        );
+        let options = ctx.git_options();
+        assert!(git::branch_exists("wreckit/001-done", &options).await);
+        assert!(git::remote_branch_exists("wreckit/001-done", &options).await);
+        assert!(git::remote_branch_exists("wreckit/002-merged", &options).await);
+
+        let report = run_gc(&ctx, true).await.unwrap();
+        assert_eq!(report.branches_deleted, vec!["wreckit/001-done"]);
+        assert_eq!(
+            report.remote_branches_deleted,
+            vec!["wreckit/001-done", "wreckit/002-merged"]
+        );
+        assert!(!git::remote_branch_exists("wreckit/001-done", &options).await);
+        assert!(git::remote_branch_exists("feature/other", &options).await);
+    }
+}
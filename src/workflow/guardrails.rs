@@ -0,0 +1,405 @@
+//! File guardrails for implement iterations
+//!
+//! `guardrails.protected_paths` lists files the agent must not touch (CI
+//! workflows, lockfiles, release config). After each implement iteration the
+//! uncommitted changes are checked against those patterns; any protected
+//! file that changed is restored, and the violation is either reported to
+//! the agent on its next attempt or, with `on_violation = "fail"`, stops
+//! the phase.
+//!
+//! The same pass adds `guardrails.license_header` to files the agent
+//! created without it, and warns when the agent edits files that CODEOWNERS
+//! assigns only to owners other than `guardrails.owners`.
+//!
+//! Patterns are globs anchored at the repository root: `*` and `?` stay
+//! within one path segment, `**` spans any number of segments, and a
+//! trailing `/` protects everything below a directory.
+
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git;
+use crate::schemas::{LicenseHeader, ProtectedPathAction};
+
+use super::context::WorkflowContext;
+
+/// Where GitHub looks for CODEOWNERS, in order
+const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Translate a glob pattern into an anchored regex
+fn glob_regex(pattern: &str) -> Regex {
+    let pattern = match pattern.strip_suffix('/') {
+        Some(dir) => format!("{}/**", dir),
+        None => pattern.to_string(),
+    };
+    let mut re = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    Regex::new(&re).expect("escaped glob is a valid regex")
+}
+
+/// Whether a repository-relative path matches a protected-path pattern
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    glob_regex(pattern).is_match(path)
+}
+
+/// The files among `changed` that match any of `patterns`
+pub fn protected_changes(patterns: &[String], changed: &[String]) -> Vec<String> {
+    let matchers: Vec<Regex> = patterns.iter().map(|p| glob_regex(p)).collect();
+    changed
+        .iter()
+        .filter(|path| matchers.iter().any(|m| m.is_match(path)))
+        .cloned()
+        .collect()
+}
+
+/// Explain a violation to the agent, in the format of a failing check
+pub fn violation_report(paths: &[String]) -> String {
+    let list: Vec<String> = paths.iter().map(|p| format!("- `{}`", p)).collect();
+    format!(
+        "### protected paths\nThese files are protected and must not be changed. Your changes to them were reverted; complete the story without modifying them:\n{}",
+        list.join("\n")
+    )
+}
+
+/// Restore any protected files the agent changed.
+///
+/// Returns the paths that were restored (empty if none were touched).
+///
+/// # Errors
+/// * `GitError` - If changes cannot be listed or restored
+/// * `StateTransition` - If `on_violation` is `fail` and a protected path changed
+pub async fn enforce_protected_paths(ctx: &WorkflowContext, item_id: &str) -> Result<Vec<String>> {
+    let guardrails = &ctx.config.guardrails;
+    if guardrails.protected_paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let options = ctx.git_options();
+    let changed = git::changed_files(&options).await?;
+    let violations = protected_changes(&guardrails.protected_paths, &changed);
+    if violations.is_empty() {
+        return Ok(violations);
+    }
+    git::restore_paths(&violations, &options).await?;
+    tracing::warn!(
+        "{}: agent changed protected paths (reverted): {}",
+        item_id,
+        violations.join(", ")
+    );
+    if guardrails.on_violation == ProtectedPathAction::Fail {
+        return Err(WreckitError::StateTransition(format!(
+            "agent changed protected paths: {}",
+            violations.join(", ")
+        )));
+    }
+    Ok(violations)
+}
+
+/// Whether text already starts with the header (after an optional shebang)
+pub fn has_license_header(text: &str, header: &str) -> bool {
+    let body = match text.strip_prefix("#!") {
+        Some(_) => text.split_once('\n').map_or("", |(_, rest)| rest),
+        None => text,
+    };
+    body.trim_start().starts_with(header.trim())
+}
+
+/// Prepend the header, keeping a shebang line first
+pub fn with_license_header(text: &str, header: &str) -> String {
+    let header = format!("{}\n\n", header.trim_end());
+    match text.split_once('\n') {
+        Some((shebang, rest)) if shebang.starts_with("#!") => {
+            format!("{}\n{}{}", shebang, header, rest)
+        }
+        _ => format!("{}{}", header, text),
+    }
+}
+
+/// Add the license header to new files that match its paths and lack it.
+///
+/// Returns the files that were changed. Files that are not UTF-8 are skipped.
+///
+/// # Errors
+/// * `GitError` - If new files cannot be listed
+/// * `Io` - If a file cannot be rewritten
+pub async fn apply_license_header(
+    root: &Path,
+    header: &LicenseHeader,
+    options: &git::GitOptions,
+) -> Result<Vec<String>> {
+    let mut added = Vec::new();
+    for path in git::new_files(options).await? {
+        if !header.paths.is_empty() && !header.paths.iter().any(|glob| path_matches(glob, &path)) {
+            continue;
+        }
+        let full = root.join(&path);
+        let text = match std::fs::read_to_string(&full) {
+            Ok(text) => text,
+            Err(_) => continue,
+        };
+        if !has_license_header(&text, &header.text) {
+            fs::write_file(&full, with_license_header(&text, &header.text))?;
+            added.push(path);
+        }
+    }
+    Ok(added)
+}
+
+/// A CODEOWNERS rule: a path pattern and the owners it assigns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnersRule {
+    pub pattern: String,
+    pub owners: Vec<String>,
+}
+
+/// Parse CODEOWNERS text, skipping comments and blank lines
+pub fn parse_codeowners(text: &str) -> Vec<OwnersRule> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let pattern = parts.next()?.to_string();
+            Some(OwnersRule {
+                pattern,
+                owners: parts.map(String::from).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Whether a CODEOWNERS (gitignore-style) pattern covers a path
+fn codeowners_matches(pattern: &str, path: &str) -> bool {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    // A leading or inner slash anchors the pattern at the root
+    let glob = match trimmed.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if trimmed.contains('/') => trimmed.to_string(),
+        None => format!("**/{}", trimmed),
+    };
+    (!dir_only && path_matches(&glob, path)) || path_matches(&format!("{}/**", glob), path)
+}
+
+/// Owners of a path: the last matching rule wins, as on GitHub
+pub fn owners_of<'a>(rules: &'a [OwnersRule], path: &str) -> &'a [String] {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| codeowners_matches(&rule.pattern, path))
+        .map_or(&[], |rule| rule.owners.as_slice())
+}
+
+/// The repository's CODEOWNERS rules (empty if it has none)
+pub fn read_codeowners(root: &Path) -> Vec<OwnersRule> {
+    CODEOWNERS_PATHS
+        .iter()
+        .find_map(|path| std::fs::read_to_string(root.join(path)).ok())
+        .map(|text| parse_codeowners(&text))
+        .unwrap_or_default()
+}
+
+/// Changed files owned exclusively by others, with their owners
+pub fn foreign_owned<'a>(
+    rules: &'a [OwnersRule],
+    ours: &[String],
+    changed: &[String],
+) -> Vec<(String, &'a [String])> {
+    changed
+        .iter()
+        .map(|path| (path.clone(), owners_of(rules, path)))
+        .filter(|(_, owners)| !owners.is_empty() && !owners.iter().any(|o| ours.contains(o)))
+        .collect()
+}
+
+/// Add license headers to new files and warn about edits outside our
+/// CODEOWNERS boundaries. Returns lines for progress.log.
+///
+/// # Errors
+/// * `GitError` - If changed or new files cannot be listed
+/// * `Io` - If a header cannot be written
+pub async fn check_file_guardrails(ctx: &WorkflowContext, item_id: &str) -> Result<Vec<String>> {
+    let guardrails = &ctx.config.guardrails;
+    let options = ctx.git_options();
+    let mut notes = Vec::new();
+
+    if let Some(ref header) = guardrails.license_header {
+        let added = apply_license_header(&ctx.root, header, &options).await?;
+        if !added.is_empty() {
+            tracing::info!("{}: added license header to {}", item_id, added.join(", "));
+            notes.push(format!("added license header to {}", added.join(", ")));
+        }
+    }
+
+    if !guardrails.owners.is_empty() {
+        let rules = read_codeowners(&ctx.root);
+        if !rules.is_empty() {
+            let changed = git::changed_files(&options).await?;
+            for (path, owners) in foreign_owned(&rules, &guardrails.owners, &changed) {
+                let line = format!("edited {} owned by {}", path, owners.join(" "));
+                tracing::warn!("{}: {}", item_id, line);
+                notes.push(line);
+            }
+        }
+    }
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("Cargo.lock", "Cargo.lock"));
+        assert!(!path_matches("Cargo.lock", "crates/a/Cargo.lock"));
+        assert!(path_matches("**/Cargo.lock", "crates/a/Cargo.lock"));
+        assert!(path_matches("**/Cargo.lock", "Cargo.lock"));
+        assert!(path_matches(
+            ".github/workflows/**",
+            ".github/workflows/ci.yml"
+        ));
+        assert!(!path_matches(".github/workflows/**", ".github/CODEOWNERS"));
+        assert!(path_matches("migrations/", "migrations/2024/01.sql"));
+        assert!(path_matches("*.toml", "rustfmt.toml"));
+        assert!(!path_matches("*.toml", "config/app.toml"));
+        assert!(path_matches("v?.txt", "v1.txt"));
+    }
+
+    #[test]
+    fn test_license_header() {
+        let header = "// SPDX-License-Identifier: MIT";
+        assert_eq!(
+            with_license_header("fn main() {}\n", header),
+            "// SPDX-License-Identifier: MIT\n\nfn main() {}\n"
+        );
+        let script = with_license_header("#!/bin/sh\necho hi\n", "# Copyright Acme\n");
+        assert_eq!(script, "#!/bin/sh\n# Copyright Acme\n\necho hi\n");
+        assert!(has_license_header(&script, "# Copyright Acme"));
+        assert!(!has_license_header("fn main() {}", header));
+    }
+
+    #[test]
+    fn test_codeowners() {
+        let rules = parse_codeowners(
+            "# Owners\n* @org/platform\n*.js @org/web\n/docs/ @org/docs # writers\nbuild/logs @org/ops\n",
+        );
+        assert_eq!(rules.len(), 4);
+        assert_eq!(owners_of(&rules, "src/main.rs"), ["@org/platform"]);
+        assert_eq!(owners_of(&rules, "web/app/index.js"), ["@org/web"]);
+        assert_eq!(owners_of(&rules, "docs/guide/intro.md"), ["@org/docs"]);
+        assert_eq!(owners_of(&rules, "build/logs/today.txt"), ["@org/ops"]);
+        assert_eq!(owners_of(&rules, "src/docs/x.md"), ["@org/platform"]);
+
+        let changed = vec!["src/main.rs".to_string(), "docs/a.md".to_string()];
+        let ours = vec!["@org/platform".to_string()];
+        let foreign = foreign_owned(&rules, &ours, &changed);
+        assert_eq!(foreign.len(), 1);
+        assert_eq!(foreign[0].0, "docs/a.md");
+    }
+
+    #[tokio::test]
+    async fn test_enforce_restores_protected_files() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-q", "-b", "main"]);
+        git(root, &["config", "user.email", "test@example.com"]);
+        git(root, &["config", "user.name", "Test"]);
+        std::fs::write(root.join("Cargo.lock"), "original").unwrap();
+        std::fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "init"]);
+
+        let mut config = Config::default();
+        config.guardrails.protected_paths =
+            vec!["Cargo.lock".to_string(), ".github/**".to_string()];
+        let mut ctx = WorkflowContext::new(root.to_path_buf(), config);
+
+        std::fs::write(root.join("Cargo.lock"), "changed").unwrap();
+        std::fs::write(root.join("main.rs"), "fn main() { run() }").unwrap();
+        std::fs::create_dir_all(root.join(".github/workflows")).unwrap();
+        std::fs::write(root.join(".github/workflows/ci.yml"), "on: push").unwrap();
+
+        let restored = enforce_protected_paths(&ctx, "001").await.unwrap();
+        assert_eq!(restored, vec![".github/workflows/ci.yml", "Cargo.lock"]);
+        assert_eq!(
+            std::fs::read_to_string(root.join("Cargo.lock")).unwrap(),
+            "original"
+        );
+        assert!(!root.join(".github/workflows/ci.yml").exists());
+        assert_eq!(
+            std::fs::read_to_string(root.join("main.rs")).unwrap(),
+            "fn main() { run() }"
+        );
+
+        ctx.config.guardrails.license_header = Some(LicenseHeader {
+            text: "// Copyright Acme".to_string(),
+            paths: vec!["**/*.rs".to_string()],
+        });
+        ctx.config.guardrails.owners = vec!["@org/platform".to_string()];
+        std::fs::write(
+            root.join("CODEOWNERS"),
+            "* @org/platform\nmain.rs @org/core\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("new.rs"), "fn new() {}\n").unwrap();
+        std::fs::write(root.join("notes.txt"), "notes\n").unwrap();
+        let notes = check_file_guardrails(&ctx, "001").await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("new.rs")).unwrap(),
+            "// Copyright Acme\n\nfn new() {}\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("notes.txt")).unwrap(),
+            "notes\n"
+        );
+        assert_eq!(
+            notes,
+            vec![
+                "added license header to new.rs",
+                "edited main.rs owned by @org/core"
+            ]
+        );
+
+        ctx.config.guardrails.on_violation = ProtectedPathAction::Fail;
+        std::fs::write(root.join("Cargo.lock"), "changed").unwrap();
+        let err = enforce_protected_paths(&ctx, "001").await.unwrap_err();
+        assert!(matches!(err, WreckitError::StateTransition(_)));
+        assert_eq!(
+            std::fs::read_to_string(root.join("Cargo.lock")).unwrap(),
+            "original"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! User-defined shell hooks
+//!
+//! Hooks (see [`crate::schemas::HooksConfig`]) are shell commands bound to
+//! lifecycle events: `pre_<phase>` runs when a phase starts, `post_<phase>`
+//! when it succeeds, and `on_error` when it fails. They let a repository
+//! update tickets, warm caches, or deploy previews without patching wreckit.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+
+use super::context::WorkflowContext;
+use super::events::WorkflowEvent;
+
+/// The hook bound to an event (e.g. "pre_implement"), if any
+pub fn hook_name(event: &WorkflowEvent) -> Option<String> {
+    match event {
+        WorkflowEvent::PhaseStarted { phase, .. } => Some(format!("pre_{}", phase)),
+        WorkflowEvent::PhaseFinished {
+            phase,
+            success: true,
+            ..
+        } => Some(format!("post_{}", phase)),
+        WorkflowEvent::Error { .. } => Some("on_error".to_string()),
+        _ => None,
+    }
+}
+
+/// Run the hook bound to an event, if one is configured.
+///
+/// The command runs through `sh -c` in the repository root with the item's
+/// details in `WRECKIT_*` environment variables and `{"hook", "event",
+/// "item"}` as JSON on stdin. In dry-run mode the command is reported rather
+/// than run.
+///
+/// # Errors
+/// * `StateTransition` - If the hook exits non-zero
+/// * `Timeout` - If the hook does not finish within `timeout_seconds`
+/// * `Io` - If the shell cannot be spawned
+pub async fn run_hook(ctx: &WorkflowContext, event: &WorkflowEvent) -> Result<()> {
+    let hook = match hook_name(event) {
+        Some(hook) => hook,
+        None => return Ok(()),
+    };
+    let command = match ctx.config.hooks.command(&hook) {
+        Some(command) => command.to_string(),
+        None => return Ok(()),
+    };
+    if ctx.dry_run {
+        tracing::info!("[DRY RUN] Would run {} hook `{}`", hook, command);
+        return Ok(());
+    }
+
+    fs::ensure_writable(|| format!("run the {} hook", hook))?;
+    let item = fs::read_item(&ctx.root, event.item_id()).ok();
+    let payload = serde_json::json!({ "hook": hook, "event": event, "item": item });
+
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", &command])
+        .current_dir(&ctx.root)
+        .env("WRECKIT_HOOK", &hook)
+        .env("WRECKIT_EVENT", event.name())
+        .env("WRECKIT_ROOT", &ctx.root)
+        .env("WRECKIT_ITEM_ID", event.item_id())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    match event {
+        WorkflowEvent::PhaseStarted { phase, .. } | WorkflowEvent::PhaseFinished { phase, .. } => {
+            cmd.env("WRECKIT_PHASE", phase);
+        }
+        _ => {}
+    }
+    if let Some(ref item) = item {
+        cmd.env("WRECKIT_ITEM_STATE", item.state_name())
+            .env("WRECKIT_ITEM_TITLE", &item.title)
+            .env("WRECKIT_ITEM_DIR", ctx.item_dir(&item.id))
+            .env("WRECKIT_BRANCH", ctx.branch_name(item));
+    }
+
+    let mut child = cmd.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The hook may exit without reading its input
+        let _ = stdin.write_all(payload.to_string().as_bytes()).await;
+    }
+    let output = tokio::time::timeout(
+        Duration::from_secs(ctx.config.timeout_seconds as u64),
+        child.wait_with_output(),
+    )
+    .await
+    .map_err(|_| WreckitError::Timeout(format!("{} hook timed out: {}", hook, command)))??;
+
+    if !output.status.success() {
+        let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        return Err(WreckitError::StateTransition(format!(
+            "{} hook `{}` failed\n{}",
+            hook,
+            command,
+            text.trim_end()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{Config, Item};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hook_names() {
+        let finished = |success| WorkflowEvent::PhaseFinished {
+            item_id: "001".to_string(),
+            phase: "pr".to_string(),
+            success,
+            seconds: 1,
+        };
+        assert_eq!(hook_name(&finished(true)).as_deref(), Some("post_pr"));
+        assert_eq!(hook_name(&finished(false)), None);
+        let started = WorkflowEvent::PhaseStarted {
+            item_id: "001".to_string(),
+            phase: "implement".to_string(),
+        };
+        assert_eq!(hook_name(&started).as_deref(), Some("pre_implement"));
+    }
+
+    #[tokio::test]
+    async fn test_run_hook_passes_item_context() {
+        let temp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.hooks.pre_research = Some(
+            "printf '%s %s %s\\n' \"$WRECKIT_HOOK\" \"$WRECKIT_ITEM_STATE\" \"$WRECKIT_BRANCH\" > hook.txt; cat >> hook.txt"
+                .to_string(),
+        );
+        config.hooks.on_error = Some("echo ticket system down; exit 3".to_string());
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        let item = Item::new(
+            "001-test".to_string(),
+            "Test".to_string(),
+            "Overview".to_string(),
+        );
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+
+        let started = WorkflowEvent::PhaseStarted {
+            item_id: item.id.clone(),
+            phase: "research".to_string(),
+        };
+        run_hook(&ctx, &started).await.unwrap();
+        let written = std::fs::read_to_string(temp.path().join("hook.txt")).unwrap();
+        let (env, stdin) = written.split_once('\n').unwrap();
+        assert_eq!(env, "pre_research idea wreckit/001-test");
+        let payload: serde_json::Value = serde_json::from_str(stdin).unwrap();
+        assert_eq!(payload["event"]["event"], "phase_started");
+        assert_eq!(payload["item"]["title"], "Test");
+
+        // Unconfigured hooks do nothing; failing ones report their output
+        let finished = WorkflowEvent::PhaseFinished {
+            item_id: item.id.clone(),
+            phase: "research".to_string(),
+            success: true,
+            seconds: 0,
+        };
+        run_hook(&ctx, &finished).await.unwrap();
+        let error = WorkflowEvent::Error {
+            item_id: item.id.clone(),
+            message: "boom".to_string(),
+            code: "agent".to_string(),
+        };
+        let err = run_hook(&ctx, &error).await.unwrap_err();
+        assert!(err.to_string().contains("ticket system down"));
+    }
+}
@@ -0,0 +1,688 @@
+//! Story-by-story implementation loop
+//!
+//! Each iteration picks the next pending story, runs the agent with a prompt
+//! scoped to that story, runs the configured verify checks (plus the story's
+//! own `verify` command, if any), marks the story done in prd.json, and
+//! commits. Failing checks are recorded per check in progress.log, and their
+//! output is included in the prompt for the next attempt at the story.
+//! Changes to protected paths are reverted and reported the same way; new
+//! files get the license header, and edits to files owned by other teams are
+//! logged. With `verification.enabled`, a story whose checks pass must also
+//! have its acceptance criteria verified (see [`super::verification`]). With
+//! `implement.test_first`, the story's failing tests are written and
+//! committed in a pass of their own first (see [`super::test_first`]). With
+//! `pr.commit_status` and `pr.draft_early`, story progress is posted on the
+//! branch head and in a draft PR after each story.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::process::Command;
+
+use crate::agent::wrap_command;
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git;
+use crate::schemas::{EnvActivation, Execution, Item, Prd, Preset, Story, VerifyCheck};
+use crate::tui::runner::TuiUpdate;
+
+use super::commit_status::post_progress;
+use super::context::{check_agent_result, WorkflowContext};
+use super::draft_pr::update_draft_pr;
+use super::guardrails::{check_file_guardrails, enforce_protected_paths, violation_report};
+use super::phases::PhaseKind;
+use super::test_first::{check_tests_precede, write_failing_tests, TestPass};
+use super::verification::verify_story;
+
+/// Lines of verify output kept in progress.log on failure
+const VERIFY_OUTPUT_TAIL: usize = 20;
+
+/// Result of running the verify command
+#[derive(Debug, Clone)]
+pub struct VerifyOutcome {
+    /// Whether the command exited successfully
+    pub passed: bool,
+
+    /// Combined stdout/stderr output
+    pub output: String,
+}
+
+/// Result of one named verify check
+#[derive(Debug, Clone)]
+pub struct CheckOutcome {
+    /// Check name (e.g., "fmt")
+    pub name: String,
+
+    /// Command that was run
+    pub cmd: String,
+
+    /// Whether the command exited successfully
+    pub passed: bool,
+
+    /// Combined stdout/stderr output
+    pub output: String,
+}
+
+/// Summary of an implement loop run
+#[derive(Debug, Clone, Default)]
+pub struct LoopSummary {
+    /// Number of iterations run
+    pub iterations: u32,
+
+    /// Story IDs completed during this run, in order
+    pub completed: Vec<String>,
+
+    /// Whether every story in the PRD is done
+    pub all_done: bool,
+}
+
+/// Describe a story for the implement prompt
+pub fn story_brief(story: &Story) -> String {
+    let mut brief = format!("{} - {}", story.id, story.title);
+    if !story.acceptance_criteria.is_empty() {
+        brief.push_str("\n\nAcceptance criteria:");
+        for criterion in &story.acceptance_criteria {
+            brief.push_str(&format!("\n- {}", criterion));
+        }
+    }
+    if !story.notes.is_empty() {
+        brief.push_str(&format!("\n\nNotes: {}", story.notes));
+    }
+    brief
+}
+
+/// Render the implement prompt scoped to a single story, including any
+/// check failures from the previous attempt at it. Items created from a
+/// preset use the preset's implement template.
+pub fn story_prompt(
+    ctx: &WorkflowContext,
+    item: &Item,
+    story: &Story,
+    verify_failures: Option<&str>,
+) -> Result<String> {
+    let mut variables = ctx.prompt_variables(item);
+    variables.story = Some(story_brief(story));
+    variables.verify_failures = verify_failures.map(String::from);
+    let template = item
+        .preset
+        .as_deref()
+        .and_then(|name| name.parse::<Preset>().ok())
+        .map_or("implement", Preset::implement_template);
+    ctx.render_prompt(template, &item.id, variables)
+}
+
+/// The last lines of a command's output
+fn output_tail(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    lines[lines.len().saturating_sub(VERIFY_OUTPUT_TAIL)..].join("\n")
+}
+
+/// Run the verify command through the shell where `execution` says (on the
+/// host or in the dev container) and in the environment `activation` enters,
+/// with extra variables set.
+///
+/// # Errors
+/// * `Timeout` - If the command does not finish within `timeout_seconds`
+/// * `ConfigError` - If `execution` needs a devcontainer.json the repository
+///   lacks
+/// * `Io` - If the shell cannot be spawned
+pub async fn run_verify_in(
+    execution: Execution,
+    activation: EnvActivation,
+    command: &str,
+    cwd: &Path,
+    timeout_seconds: u32,
+    env: &[(String, String)],
+) -> Result<VerifyOutcome> {
+    let (program, args) = wrap_command(
+        execution,
+        activation,
+        cwd,
+        env,
+        "sh",
+        &["-c".to_string(), command.to_string()],
+    )?;
+    let child = Command::new(program)
+        .args(args)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output();
+
+    let output = tokio::time::timeout(Duration::from_secs(timeout_seconds as u64), child)
+        .await
+        .map_err(|_| WreckitError::Timeout(format!("verify command timed out: {}", command)))??;
+
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(VerifyOutcome {
+        passed: output.status.success(),
+        output: text,
+    })
+}
+
+/// Run every check in order, collecting each outcome.
+///
+/// All checks run even after a failure so every problem is reported at once.
+///
+/// # Errors
+/// * `Timeout` - If a check does not finish within `timeout_seconds`
+/// * `ConfigError` - If `execution` needs a devcontainer.json the repository
+///   lacks
+/// * `Io` - If the shell cannot be spawned
+pub async fn run_verify_checks(
+    execution: Execution,
+    activation: EnvActivation,
+    checks: &[VerifyCheck],
+    cwd: &Path,
+    timeout_seconds: u32,
+    env: &[(String, String)],
+) -> Result<Vec<CheckOutcome>> {
+    let mut outcomes = Vec::new();
+    for check in checks {
+        let outcome =
+            run_verify_in(execution, activation, &check.cmd, cwd, timeout_seconds, env).await?;
+        outcomes.push(CheckOutcome {
+            name: check.name.clone(),
+            cmd: check.cmd.clone(),
+            passed: outcome.passed,
+            output: outcome.output,
+        });
+    }
+    Ok(outcomes)
+}
+
+/// Describe failing checks for the agent prompt; None if every check passed
+pub fn failure_report(outcomes: &[CheckOutcome]) -> Option<String> {
+    let sections: Vec<String> = outcomes
+        .iter()
+        .filter(|outcome| !outcome.passed)
+        .map(|outcome| {
+            format!(
+                "### {} (`{}`)\n```\n{}\n```",
+                outcome.name,
+                outcome.cmd,
+                output_tail(&outcome.output)
+            )
+        })
+        .collect();
+    if sections.is_empty() {
+        None
+    } else {
+        Some(sections.join("\n\n"))
+    }
+}
+
+/// Mark a story done in the item's prd.json and return the updated PRD
+pub fn mark_story_done(root: &Path, item_id: &str, story_id: &str) -> Result<Prd> {
+    let prd = fs::read_prd(root, item_id)?.with_story_done(story_id);
+    fs::write_prd(root, item_id, &prd)?;
+    Ok(prd)
+}
+
+/// Append a line to the item's progress.log
+pub fn append_progress(root: &Path, item_id: &str, line: &str) -> Result<()> {
+    let path = fs::get_progress_log_path(root, item_id);
+    let mut file = fs::append_file(&path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Work through the item's pending stories until all are done or
+/// `max_iterations` is reached.
+///
+/// The caller is responsible for checking out the item branch first.
+///
+/// # Errors
+/// * `FileNotFound` / `InvalidJson` - If prd.json is missing or malformed
+/// * `AgentError` / `Timeout` / `Interrupted` - If an agent run fails
+/// * `GitError` - If committing a story fails
+/// * `StateTransition` - If the agent changed a protected path and
+///   `guardrails.on_violation` is `fail`
+pub async fn run_implement_loop(ctx: &WorkflowContext, item: &Item) -> Result<LoopSummary> {
+    let options = ctx.git_options();
+    let checks = ctx.config.story_checks();
+    let mut summary = LoopSummary::default();
+    // Failure report from the last attempt, keyed by story
+    let mut feedback: Option<(String, String)> = None;
+    // Test-first mode: the tests commit for the story in progress
+    let mut tests_commit: Option<(String, String)> = None;
+
+    for iteration in 1..=ctx.config.max_iterations {
+        ctx.wait_if_paused().await;
+
+        let prd = fs::read_prd(&ctx.root, &item.id)?;
+        let story = match prd.next_pending_story() {
+            Some(story) => story.clone(),
+            None => break,
+        };
+
+        summary.iterations = iteration;
+        ctx.emit(TuiUpdate::SetIteration(iteration));
+        ctx.emit(TuiUpdate::SetCurrentStory(Some(format!(
+            "{} - {}",
+            story.id, story.title
+        ))));
+
+        let mut story_checks = checks.clone();
+        if let Some(ref cmd) = story.verify {
+            story_checks.push(VerifyCheck::new(story.id.clone(), cmd.clone()));
+        }
+
+        let mut failures = feedback
+            .as_ref()
+            .filter(|(id, _)| *id == story.id)
+            .map(|(_, report)| report.clone());
+        if ctx.config.implement.test_first
+            && tests_commit.as_ref().is_none_or(|(id, _)| *id != story.id)
+        {
+            match write_failing_tests(ctx, item, &story, &story_checks, failures.as_deref()).await?
+            {
+                Some(TestPass::Committed {
+                    commit,
+                    failures: report,
+                }) => {
+                    append_progress(
+                        &ctx.root,
+                        &item.id,
+                        &format!(
+                            "[iteration {}] {} tests committed ({})",
+                            iteration, story.id, commit
+                        ),
+                    )?;
+                    if report.is_none() {
+                        append_progress(
+                            &ctx.root,
+                            &item.id,
+                            &format!(
+                                "[iteration {}] {} tests already pass before implementation",
+                                iteration, story.id
+                            ),
+                        )?;
+                    }
+                    tests_commit = Some((story.id.clone(), commit));
+                    failures = report;
+                }
+                Some(TestPass::Rejected(report)) => {
+                    append_progress(
+                        &ctx.root,
+                        &item.id,
+                        &format!("[iteration {}] {} test pass rejected", iteration, story.id),
+                    )?;
+                    feedback = Some((story.id.clone(), report));
+                    continue;
+                }
+                None => {}
+            }
+        }
+        let prompt = story_prompt(ctx, item, &story, failures.as_deref())?;
+        let result = ctx
+            .run_agent(&item.id, PhaseKind::Implement, Some(&story.id), prompt)
+            .await?;
+        check_agent_result(&result)?;
+
+        if ctx.dry_run {
+            tracing::info!("[DRY RUN] Would verify, mark {} done, and commit", story.id);
+            break;
+        }
+
+        let violations = enforce_protected_paths(ctx, &item.id).await?;
+        if !violations.is_empty() {
+            append_progress(
+                &ctx.root,
+                &item.id,
+                &format!(
+                    "[iteration {}] {} changed protected paths (reverted): {}",
+                    iteration,
+                    story.id,
+                    violations.join(", ")
+                ),
+            )?;
+            ctx.emit(TuiUpdate::AppendLogs(vec![format!(
+                "[ERROR] {} changed protected paths: {}",
+                story.id,
+                violations.join(", ")
+            )]));
+            feedback = Some((story.id.clone(), violation_report(&violations)));
+            continue;
+        }
+        for note in check_file_guardrails(ctx, &item.id).await? {
+            append_progress(
+                &ctx.root,
+                &item.id,
+                &format!("[iteration {}] {} {}", iteration, story.id, note),
+            )?;
+        }
+
+        let outcomes = run_verify_checks(
+            ctx.config.agent.execution,
+            ctx.config.env_activation,
+            &story_checks,
+            &ctx.root,
+            ctx.config.timeout_seconds,
+            &ctx.verify_env(item)?,
+        )
+        .await?;
+        if let Some(report) = failure_report(&outcomes) {
+            let failed: Vec<&CheckOutcome> = outcomes.iter().filter(|o| !o.passed).collect();
+            for outcome in &failed {
+                append_progress(
+                    &ctx.root,
+                    &item.id,
+                    &format!(
+                        "[iteration {}] {} failed verification check {} (`{}`):\n{}",
+                        iteration,
+                        story.id,
+                        outcome.name,
+                        outcome.cmd,
+                        output_tail(&outcome.output)
+                    ),
+                )?;
+            }
+            let names: Vec<&str> = failed.iter().map(|o| o.name.as_str()).collect();
+            ctx.emit(TuiUpdate::AppendLogs(vec![format!(
+                "[ERROR] {} failed verification: {}",
+                story.id,
+                names.join(", ")
+            )]));
+            feedback = Some((story.id.clone(), report));
+            continue;
+        }
+        if let Some(report) = verify_story(ctx, item, &story).await? {
+            append_progress(
+                &ctx.root,
+                &item.id,
+                &format!(
+                    "[iteration {}] {} has unverified acceptance criteria (see verification.md)",
+                    iteration, story.id
+                ),
+            )?;
+            ctx.emit(TuiUpdate::AppendLogs(vec![format!(
+                "[ERROR] {} has unverified acceptance criteria",
+                story.id
+            )]));
+            feedback = Some((story.id.clone(), report));
+            continue;
+        }
+        if let Some((_, ref commit)) = tests_commit {
+            if let Some(report) = check_tests_precede(ctx, commit).await? {
+                append_progress(
+                    &ctx.root,
+                    &item.id,
+                    &format!(
+                        "[iteration {}] {} lost its tests commit; writing the tests again",
+                        iteration, story.id
+                    ),
+                )?;
+                tests_commit = None;
+                feedback = Some((story.id.clone(), report));
+                continue;
+            }
+        }
+        feedback = None;
+
+        mark_story_done(&ctx.root, &item.id, &story.id)?;
+        append_progress(
+            &ctx.root,
+            &item.id,
+            &format!("[iteration {}] {} done", iteration, story.id),
+        )?;
+        if git::has_uncommitted_changes(&options).await {
+            git::commit_all(
+                &format!("wreckit({}): {} {}", item.id, story.id, story.title),
+                &options,
+            )
+            .await?;
+        }
+        post_progress(ctx, item).await;
+        update_draft_pr(ctx, item).await;
+        summary.completed.push(story.id.clone());
+    }
+
+    summary.all_done = fs::read_prd(&ctx.root, &item.id)
+        .map(|prd| prd.all_stories_done())
+        .unwrap_or(false);
+    if !summary.all_done && !ctx.dry_run {
+        tracing::warn!(
+            "{}: stopped after {} iterations with stories still pending",
+            item.id,
+            summary.iterations
+        );
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{Config, StoryStatus};
+    use crate::workflow::transcript::load_transcripts;
+    use tempfile::TempDir;
+
+    fn setup(verify_command: Option<&str>) -> (TempDir, WorkflowContext, Item) {
+        let temp = TempDir::new().unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["config", "user.email", "test@example.com"][..],
+            &["config", "user.name", "Test"][..],
+        ] {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp.path())
+                .status()
+                .unwrap();
+        }
+        let mut config = Config::default();
+        config.agent.command = "cat".to_string();
+        config.agent.args = vec![];
+        config.agent.completion_signal = String::new();
+        config.max_iterations = 5;
+        config.verify_command = verify_command.map(String::from);
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+
+        let item = Item::new("001-test".to_string(), "Test".to_string(), String::new());
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+        let prd = Prd::new(item.id.clone(), "wreckit/001-test".to_string())
+            .with_story(Story::new(
+                "US-002".to_string(),
+                "Second".to_string(),
+                vec![],
+                2,
+            ))
+            .with_story(Story::new(
+                "US-001".to_string(),
+                "First".to_string(),
+                vec!["works".to_string()],
+                1,
+            ));
+        fs::write_prd(temp.path(), &item.id, &prd).unwrap();
+        (temp, ctx, item)
+    }
+
+    #[test]
+    fn test_story_brief_lists_criteria() {
+        let story = Story::new(
+            "US-001".to_string(),
+            "Login".to_string(),
+            vec!["accepts email".to_string()],
+            1,
+        );
+        let brief = story_brief(&story);
+        assert!(brief.starts_with("US-001 - Login"));
+        assert!(brief.contains("- accepts email"));
+    }
+
+    #[test]
+    fn test_story_prompt_is_scoped() {
+        let (_temp, ctx, item) = setup(None);
+        let story = Story::new("US-001".to_string(), "First".to_string(), vec![], 1);
+        let prompt = story_prompt(&ctx, &item, &story, None).unwrap();
+        assert!(prompt.contains("## Current Story"));
+        assert!(prompt.contains("US-001 - First"));
+        assert!(!prompt.contains("Repeat for remaining stories"));
+        assert!(!prompt.contains("## Failing Checks"));
+
+        let prompt = story_prompt(&ctx, &item, &story, Some("### fmt")).unwrap();
+        assert!(prompt.contains("## Failing Checks"));
+        assert!(prompt.contains("### fmt"));
+    }
+
+    #[tokio::test]
+    async fn test_loop_completes_stories_in_priority_order() {
+        let (temp, ctx, item) = setup(Some("true"));
+
+        let summary = run_implement_loop(&ctx, &item).await.unwrap();
+        assert_eq!(summary.completed, vec!["US-001", "US-002"]);
+        assert_eq!(summary.iterations, 2);
+        assert!(summary.all_done);
+
+        let progress =
+            std::fs::read_to_string(fs::get_progress_log_path(temp.path(), &item.id)).unwrap();
+        assert!(progress.contains("US-001 done"));
+    }
+
+    #[tokio::test]
+    async fn test_loop_records_verify_failures() {
+        let (temp, ctx, item) = setup(Some("echo broken test; exit 1"));
+
+        let summary = run_implement_loop(&ctx, &item).await.unwrap();
+        assert!(summary.completed.is_empty());
+        assert_eq!(summary.iterations, 5);
+        assert!(!summary.all_done);
+
+        let prd = fs::read_prd(temp.path(), &item.id).unwrap();
+        assert!(prd
+            .user_stories
+            .iter()
+            .all(|s| s.status == StoryStatus::Pending));
+        let progress =
+            std::fs::read_to_string(fs::get_progress_log_path(temp.path(), &item.id)).unwrap();
+        assert!(progress.contains("US-001 failed verification check verify"));
+        assert!(progress.contains("broken test"));
+    }
+
+    #[tokio::test]
+    async fn test_loop_reports_each_failing_check_to_agent() {
+        let (temp, mut ctx, item) = setup(None);
+        ctx.config.max_iterations = 2;
+        ctx.config.verify = vec![
+            VerifyCheck::new("fmt", "true"),
+            VerifyCheck::new("lint", "echo unused variable; exit 1"),
+            VerifyCheck::new("test", "echo 2 failed; exit 1"),
+        ];
+
+        let summary = run_implement_loop(&ctx, &item).await.unwrap();
+        assert!(summary.completed.is_empty());
+
+        let progress =
+            std::fs::read_to_string(fs::get_progress_log_path(temp.path(), &item.id)).unwrap();
+        assert!(progress.contains("failed verification check lint"));
+        assert!(progress.contains("failed verification check test"));
+        assert!(!progress.contains("check fmt"));
+
+        // The second attempt's prompt (echoed back by `cat`) carries the failures
+        let transcripts = load_transcripts(temp.path(), &item.id, None).unwrap();
+        assert_eq!(transcripts.len(), 2);
+        assert!(!transcripts[0].prompt.contains("## Failing Checks"));
+        assert!(transcripts[1]
+            .prompt
+            .contains("### lint (`echo unused variable; exit 1`)"));
+        assert!(transcripts[1].prompt.contains("2 failed"));
+    }
+
+    #[tokio::test]
+    async fn test_loop_reverts_protected_paths() {
+        let (temp, mut ctx, item) = setup(Some("true"));
+        ctx.config.max_iterations = 2;
+        ctx.config.agent.command = "sh".to_string();
+        ctx.config.agent.args = vec!["-c".to_string(), "echo x > Cargo.lock; cat".to_string()];
+        ctx.config.guardrails.protected_paths = vec!["Cargo.lock".to_string()];
+
+        let summary = run_implement_loop(&ctx, &item).await.unwrap();
+        assert!(summary.completed.is_empty());
+        assert!(!temp.path().join("Cargo.lock").exists());
+
+        let progress =
+            std::fs::read_to_string(fs::get_progress_log_path(temp.path(), &item.id)).unwrap();
+        assert!(progress.contains("US-001 changed protected paths (reverted): Cargo.lock"));
+        let transcripts = load_transcripts(temp.path(), &item.id, None).unwrap();
+        assert!(transcripts[1].prompt.contains("### protected paths"));
+        assert!(transcripts[1].prompt.contains("- `Cargo.lock`"));
+    }
+
+    #[tokio::test]
+    async fn test_loop_commits_tests_before_implementation() {
+        let (temp, mut ctx, item) = setup(Some("test -f impl.txt"));
+        ctx.config.max_iterations = 1;
+        ctx.config.implement.test_first = true;
+        ctx.config.agent.command = "sh".to_string();
+        ctx.config.agent.args = vec![
+            "-c".to_string(),
+            "case \"$(cat)\" in *'Write Failing Tests'*) echo t > tests.txt;; *) echo i > impl.txt;; esac".to_string(),
+        ];
+
+        let summary = run_implement_loop(&ctx, &item).await.unwrap();
+        assert_eq!(summary.completed, vec!["US-001"]);
+        let log = std::process::Command::new("git")
+            .args(["log", "--format=%s"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        let log = String::from_utf8_lossy(&log.stdout);
+        let subjects: Vec<&str> = log.lines().collect();
+        assert_eq!(
+            subjects,
+            vec![
+                "wreckit(001-test): US-001 First",
+                "wreckit(001-test): US-001 tests"
+            ]
+        );
+
+        // The implementation pass is given the tests' failures
+        let transcripts = load_transcripts(temp.path(), &item.id, None).unwrap();
+        assert_eq!(transcripts.len(), 2);
+        assert!(transcripts[0].prompt.contains("Write Failing Tests"));
+        assert!(transcripts[1].prompt.contains("## Failing Checks"));
+        let progress =
+            std::fs::read_to_string(fs::get_progress_log_path(temp.path(), &item.id)).unwrap();
+        assert!(progress.contains("US-001 tests committed"));
+    }
+
+    #[tokio::test]
+    async fn test_loop_reopens_stories_with_unverified_criteria() {
+        let (temp, mut ctx, item) = setup(Some("true"));
+        ctx.config.max_iterations = 1;
+        ctx.config.verification.enabled = true;
+        let agent = |verified: bool| {
+            format!(
+                "cat > /dev/null; echo 'VERIFICATION_JSON_START {{\"criteria\": [{{\"criterion\": \"works\", \"verified\": {}, \"evidence\": \"no test\"}}]}} VERIFICATION_JSON_END'",
+                verified
+            )
+        };
+        ctx.config.agent.command = "sh".to_string();
+        ctx.config.agent.args = vec!["-c".to_string(), agent(false)];
+
+        let summary = run_implement_loop(&ctx, &item).await.unwrap();
+        assert!(summary.completed.is_empty());
+        let prd = fs::read_prd(temp.path(), &item.id).unwrap();
+        assert!(prd
+            .user_stories
+            .iter()
+            .all(|s| s.status == StoryStatus::Pending));
+        let progress =
+            std::fs::read_to_string(fs::get_progress_log_path(temp.path(), &item.id)).unwrap();
+        assert!(progress.contains("US-001 has unverified acceptance criteria"));
+        let report =
+            std::fs::read_to_string(fs::get_verification_path(temp.path(), &item.id)).unwrap();
+        assert!(report.contains("- [ ] works — no test"));
+
+        ctx.config.agent.args = vec!["-c".to_string(), agent(true)];
+        let summary = run_implement_loop(&ctx, &item).await.unwrap();
+        assert_eq!(summary.completed, vec!["US-001"]);
+    }
+}
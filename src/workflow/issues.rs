@@ -0,0 +1,154 @@
+//! Items that originate from GitHub issues
+//!
+//! `wreckit issue <number>` creates an idea from an issue and records the
+//! issue on the item. The PR phase then adds `Closes #<number>` to the PR body
+//! so merging the PR closes the issue, and the issue gets a comment when the
+//! item enters in_pr and again when it is done. Comment failures are logged
+//! and never fail the phase.
+
+use regex::Regex;
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git;
+use crate::schemas::{Item, WorkflowState};
+
+use super::context::WorkflowContext;
+use super::outbox;
+
+/// Append a `Closes #<number>` line to a PR body unless the body already
+/// references the issue with a closing keyword
+pub fn with_closing_keyword(body: &str, number: u32) -> String {
+    let pattern = format!(
+        r"(?i)\b(close[sd]?|fix(e[sd])?|resolve[sd]?)\s+#{}\b",
+        number
+    );
+    let linked = Regex::new(&pattern).is_ok_and(|re| re.is_match(body));
+    if linked {
+        return body.to_string();
+    }
+    let body = body.trim_end();
+    if body.is_empty() {
+        format!("Closes #{}", number)
+    } else {
+        format!("{}\n\nCloses #{}", body, number)
+    }
+}
+
+/// The comment to post on an item's issue for its current state, if any
+pub fn issue_comment(item: &Item) -> Option<String> {
+    let pr = item
+        .pr_url
+        .as_deref()
+        .map(|url| format!(" in {}", url))
+        .unwrap_or_default();
+    match item.state {
+        WorkflowState::InPr => Some(format!(
+            "wreckit opened a pull request for this issue{} (item {}).",
+            pr, item.id
+        )),
+        WorkflowState::Done => Some(format!(
+            "wreckit finished item {}{}; this issue is resolved.",
+            item.id, pr
+        )),
+        _ => None,
+    }
+}
+
+/// Comment on the item's issue about the state it just entered. Does
+/// nothing for items without an issue.
+pub async fn notify_issue(ctx: &WorkflowContext, item: &Item) {
+    let (number, comment) = match (item.issue_number, issue_comment(item)) {
+        (Some(number), Some(comment)) => (number, comment),
+        _ => return,
+    };
+    let options = ctx.git_options();
+    if let Err(e) = outbox::comment_on_issue(&ctx.root, &item.id, number, &comment, &options).await
+    {
+        // The issue is only a courtesy link; the item itself has moved on
+        tracing::warn!(
+            "Failed to comment on issue #{} for {}: {}",
+            number,
+            item.id,
+            e
+        );
+    }
+}
+
+/// Create an idea from a GitHub issue and return it. In dry-run mode the
+/// item is returned but not saved.
+///
+/// # Errors
+/// * `StateTransition` - If an item already tracks the issue
+/// * `GitError` - If the issue cannot be fetched
+/// * `InvalidJson` - If an item file or gh's output is malformed
+/// * `Io` - If the item cannot be written
+pub async fn import_issue(ctx: &WorkflowContext, number: u32) -> Result<Item> {
+    let existing = fs::list_items(&ctx.root)?
+        .into_iter()
+        .find(|item| item.issue_number == Some(number));
+    if let Some(existing) = existing {
+        return Err(WreckitError::StateTransition(format!(
+            "issue #{} is already tracked by {}",
+            number, existing.id
+        )));
+    }
+
+    // Reading the issue has no side effects, so dry-run still fetches it
+    let options = git::GitOptions {
+        dry_run: false,
+        ..ctx.git_options()
+    };
+    let issue = git::issue(number, &options).await?;
+    let mut item = Item::new(ctx.new_item_id(&issue.title)?, issue.title, issue.body);
+    item.issue_number = Some(issue.number);
+    item.issue_url = Some(issue.url).filter(|url| !url.is_empty());
+    if ctx.dry_run {
+        tracing::info!("[DRY RUN] Would create {} from issue #{}", item.id, number);
+        return Ok(item);
+    }
+    ctx.save_item(&item)?;
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_closing_keyword() {
+        assert_eq!(
+            with_closing_keyword("Adds a flag.\n", 12),
+            "Adds a flag.\n\nCloses #12"
+        );
+        assert_eq!(with_closing_keyword("", 12), "Closes #12");
+        assert_eq!(with_closing_keyword("Fixes #12", 12), "Fixes #12");
+        assert_eq!(
+            with_closing_keyword("resolved #12 and more", 12),
+            "resolved #12 and more"
+        );
+        // Another issue, or a mention without a keyword, still gets the line
+        assert_eq!(
+            with_closing_keyword("Closes #123", 12),
+            "Closes #123\n\nCloses #12"
+        );
+        assert_eq!(with_closing_keyword("See #12", 12), "See #12\n\nCloses #12");
+    }
+
+    #[test]
+    fn test_issue_comment() {
+        let item = Item::new("001-a".to_string(), "A".to_string(), String::new());
+        assert_eq!(issue_comment(&item), None);
+        let item = item
+            .with_state(WorkflowState::InPr)
+            .with_pr(Some("https://github.com/o/r/pull/7".to_string()), Some(7));
+        assert_eq!(
+            issue_comment(&item).unwrap(),
+            "wreckit opened a pull request for this issue in https://github.com/o/r/pull/7 (item 001-a)."
+        );
+        let item = item.with_state(WorkflowState::Done);
+        assert!(issue_comment(&item)
+            .unwrap()
+            .contains("finished item 001-a"));
+    }
+}
@@ -0,0 +1,250 @@
+//! Deep artifact linting for `wreckit doctor --deep`
+//!
+//! Structural checks only ask whether an item's files parse. These checks
+//! read the artifacts themselves: research.md should have the sections the
+//! research prompt asks for, plan.md a testing strategy, every story
+//! acceptance criteria, and an implementing item's branch at least one
+//! commit. Each finding carries a fix-it suggestion.
+
+use serde::Serialize;
+
+use crate::errors::Result;
+use crate::fs;
+use crate::git;
+use crate::schemas::{Item, WorkflowState};
+
+use super::context::WorkflowContext;
+
+/// Sections the research prompt asks research.md to contain
+pub const RESEARCH_SECTIONS: &[&str] = &[
+    "Summary",
+    "Current State Analysis",
+    "Technical Considerations",
+    "Risks and Mitigations",
+    "Recommended Approach",
+];
+
+/// A problem found in an item's artifacts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintFinding {
+    /// Item the problem is in
+    pub item_id: String,
+
+    /// Short name of the check (e.g. "research-sections")
+    pub check: String,
+
+    /// What is wrong
+    pub message: String,
+
+    /// How to fix it
+    pub fix: String,
+}
+
+impl LintFinding {
+    fn new(item: &Item, check: &str, message: String, fix: String) -> Self {
+        LintFinding {
+            item_id: item.id.clone(),
+            check: check.to_string(),
+            message,
+            fix,
+        }
+    }
+}
+
+/// Text of every markdown heading, without the leading `#`s
+pub fn markdown_headings(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim_start)
+        .filter(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim().to_string())
+        .collect()
+}
+
+/// The required sections missing from research.md (compared case-insensitively)
+pub fn missing_research_sections(research: &str) -> Vec<&'static str> {
+    let headings: Vec<String> = markdown_headings(research)
+        .iter()
+        .map(|heading| heading.to_lowercase())
+        .collect();
+    RESEARCH_SECTIONS
+        .iter()
+        .filter(|section| !headings.contains(&section.to_lowercase()))
+        .copied()
+        .collect()
+}
+
+/// Whether plan.md has a heading mentioning testing
+pub fn has_testing_strategy(plan: &str) -> bool {
+    markdown_headings(plan)
+        .iter()
+        .any(|heading| heading.to_lowercase().contains("testing"))
+}
+
+/// Lint one item's artifacts.
+///
+/// # Errors
+/// * `Io` - If an artifact exists but cannot be read
+pub async fn lint_item(ctx: &WorkflowContext, item: &Item) -> Result<Vec<LintFinding>> {
+    let mut findings = Vec::new();
+
+    let research_path = fs::get_research_path(&ctx.root, &item.id);
+    if research_path.exists() {
+        let missing = missing_research_sections(&std::fs::read_to_string(&research_path)?);
+        if !missing.is_empty() {
+            findings.push(LintFinding::new(
+                item,
+                "research-sections",
+                format!("research.md is missing sections: {}", missing.join(", ")),
+                format!(
+                    "add the missing sections or re-run `wreckit research {} --force`",
+                    item.id
+                ),
+            ));
+        }
+    }
+
+    let plan_path = fs::get_plan_path(&ctx.root, &item.id);
+    if plan_path.exists() && !has_testing_strategy(&std::fs::read_to_string(&plan_path)?) {
+        findings.push(LintFinding::new(
+            item,
+            "plan-testing",
+            "plan.md has no testing strategy heading".to_string(),
+            format!(
+                "add a `## Testing Strategy` section or re-run `wreckit plan {} --force`",
+                item.id
+            ),
+        ));
+    }
+
+    if let Ok(prd) = fs::read_prd(&ctx.root, &item.id) {
+        for story in prd
+            .user_stories
+            .iter()
+            .filter(|story| story.acceptance_criteria.is_empty())
+        {
+            findings.push(LintFinding::new(
+                item,
+                "story-criteria",
+                format!("{} has no acceptance criteria", story.id),
+                format!(
+                    "add acceptance_criteria to {} in prd.json so the agent knows when it is done",
+                    story.id
+                ),
+            ));
+        }
+    }
+
+    if item.state == WorkflowState::Implementing {
+        if let Some(ref branch) = item.branch {
+            let options = ctx.git_options();
+            let stale = if git::branch_exists(branch, &options).await {
+                git::commits_ahead(&ctx.config.base_branch, branch, &options).await? == 0
+            } else {
+                true
+            };
+            if stale {
+                findings.push(LintFinding::new(
+                    item,
+                    "stale-branch",
+                    format!(
+                        "{} is implementing but {} has no commits ahead of {}",
+                        item.id, branch, ctx.config.base_branch
+                    ),
+                    format!(
+                        "run `wreckit implement {}`, or `wreckit retry {} --rollback` to start over",
+                        item.id, item.id
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(findings)
+}
+
+/// Lint every item, in ID order.
+///
+/// # Errors
+/// * `InvalidJson` - If an item file is malformed
+/// * `Io` - If an artifact exists but cannot be read
+pub async fn lint_items(ctx: &WorkflowContext) -> Result<Vec<LintFinding>> {
+    let mut findings = Vec::new();
+    for item in fs::list_items(&ctx.root)? {
+        findings.extend(lint_item(ctx, &item).await?);
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{Config, Prd, Story};
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    #[test]
+    fn test_missing_research_sections() {
+        let research = "# Research: X\n## Summary\n## current state analysis\n### Key Files\n## Recommended Approach\n";
+        assert_eq!(
+            missing_research_sections(research),
+            vec!["Technical Considerations", "Risks and Mitigations"]
+        );
+        assert!(has_testing_strategy("# Plan\n## Testing Strategy\n"));
+        assert!(!has_testing_strategy("# Plan\nWe will add tests.\n"));
+    }
+
+    #[tokio::test]
+    async fn test_lint_item() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-q", "-b", "main"]);
+        git(root, &["config", "user.email", "test@example.com"]);
+        git(root, &["config", "user.name", "Test"]);
+        git(root, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        git(root, &["branch", "wreckit/001-test"]);
+        let ctx = WorkflowContext::new(root.to_path_buf(), Config::default());
+
+        let item = Item::new(
+            "001-test".to_string(),
+            "Test".to_string(),
+            "Overview".to_string(),
+        )
+        .with_state(WorkflowState::Implementing)
+        .with_branch(Some("wreckit/001-test".to_string()));
+        fs::write_item(root, &item.id, &item).unwrap();
+        std::fs::write(fs::get_plan_path(root, &item.id), "# Plan\n").unwrap();
+        let mut prd = Prd::new(item.id.clone(), "wreckit/001-test".to_string());
+        prd.user_stories.push(Story::new(
+            "US-001".to_string(),
+            "Story".to_string(),
+            vec![],
+            1,
+        ));
+        fs::write_prd(root, &item.id, &prd).unwrap();
+
+        let checks = |findings: Vec<LintFinding>| -> Vec<String> {
+            findings.into_iter().map(|finding| finding.check).collect()
+        };
+        assert_eq!(
+            checks(lint_items(&ctx).await.unwrap()),
+            vec!["plan-testing", "story-criteria", "stale-branch"]
+        );
+
+        // A commit on the branch clears the stale-branch finding
+        git(root, &["checkout", "-q", "wreckit/001-test"]);
+        git(root, &["commit", "-q", "--allow-empty", "-m", "work"]);
+        assert_eq!(
+            checks(lint_item(&ctx, &item).await.unwrap()),
+            vec!["plan-testing", "story-criteria"]
+        );
+    }
+}
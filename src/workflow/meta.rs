@@ -0,0 +1,176 @@
+//! Persisting `.wreckit/` metadata to git
+//!
+//! With `meta.mode = "branch"` the metadata is committed after each phase to
+//! a dedicated orphan branch (without touching the checkout), so the backlog
+//! survives machine loss and can be shared; `wreckit sync-meta` merges a
+//! teammate's copy from origin. With `meta.mode = "item"` it is committed to
+//! the item branch instead.
+
+use crate::errors::Result;
+use crate::git;
+use crate::schemas::{Item, MetaMode};
+
+use super::context::WorkflowContext;
+
+/// Metadata paths committed to git; backups and the journal stay local
+pub const META_PATHSPEC: &[&str] = &[
+    ".wreckit",
+    ":(exclude).wreckit/backups",
+    ":(exclude).wreckit/journal",
+    ":(exclude).wreckit/cache",
+];
+
+/// Commit the current metadata according to `meta.mode`.
+///
+/// # Errors
+/// * `GitError` - If committing or pushing fails
+pub async fn persist_metadata(ctx: &WorkflowContext, item: &Item, phase: &str) -> Result<()> {
+    if ctx.dry_run {
+        return Ok(());
+    }
+    let message = format!("wreckit({}): {} metadata", item.id, phase);
+    let options = ctx.git_options();
+
+    match ctx.config.meta.mode {
+        MetaMode::Off => Ok(()),
+        MetaMode::Branch => {
+            let branch = &ctx.config.meta.branch;
+            if git::commit_paths_to_branch(branch, META_PATHSPEC, &message, &options)
+                .await?
+                .is_some()
+                && ctx.config.meta.push
+            {
+                git::push_branch(branch, &options).await?;
+            }
+            Ok(())
+        }
+        MetaMode::Item => {
+            if git::get_current_branch(&options).await? != ctx.branch_name(item) {
+                return Ok(());
+            }
+            let mut add = vec!["add", "-A", "-f", "--"];
+            add.extend_from_slice(META_PATHSPEC);
+            git::run_git_command(&add, &options).await?;
+            let staged = git::run_git_command(
+                &["diff", "--cached", "--name-only", "--", ".wreckit"],
+                &options,
+            )
+            .await?;
+            if !staged.is_empty() {
+                let mut commit = vec!["commit", "-q", "-m", message.as_str(), "--"];
+                commit.extend_from_slice(META_PATHSPEC);
+                git::run_git_command(&commit, &options).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Merge local and remote metadata on the metadata branch.
+///
+/// Commits the local metadata, merges `origin/<branch>` if it exists,
+/// writes the merged files back into `.wreckit/`, and pushes the result.
+///
+/// # Errors
+/// * `GitError` - If the histories conflict or a git command fails
+pub async fn sync_metadata(ctx: &WorkflowContext) -> Result<()> {
+    let branch = &ctx.config.meta.branch;
+    let options = ctx.git_options();
+
+    git::commit_paths_to_branch(branch, META_PATHSPEC, "wreckit: sync metadata", &options).await?;
+
+    if git::remote_branch_exists(branch, &options).await {
+        let remote_ref = format!("refs/remotes/origin/{}", branch);
+        git::run_git_command(
+            &[
+                "fetch",
+                "-q",
+                "origin",
+                &format!("+refs/heads/{}:{}", branch, remote_ref),
+            ],
+            &options,
+        )
+        .await?;
+        git::merge_into_branch(branch, &remote_ref, &options).await?;
+    }
+
+    if git::resolve_ref(&format!("refs/heads/{}", branch), &options)
+        .await
+        .is_some()
+    {
+        git::checkout_branch_files(branch, &options).await?;
+        git::push_branch(branch, &options).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs;
+    use crate::schemas::Config;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn clone_with_item(remote: &Path, dir: &Path, id: &str) -> WorkflowContext {
+        git(
+            remote.parent().unwrap(),
+            &[
+                "clone",
+                "-q",
+                remote.to_str().unwrap(),
+                dir.to_str().unwrap(),
+            ],
+        );
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        let mut config = Config::default();
+        config.meta.mode = MetaMode::Branch;
+        let ctx = WorkflowContext::new(dir.to_path_buf(), config);
+        let item = Item::new(id.to_string(), id.to_string(), String::new());
+        fs::write_item(dir, id, &item).unwrap();
+        ctx
+    }
+
+    #[tokio::test]
+    async fn test_persist_and_sync_between_clones() {
+        let temp = TempDir::new().unwrap();
+        let remote = temp.path().join("remote.git");
+        git(
+            temp.path(),
+            &["init", "-q", "--bare", remote.to_str().unwrap()],
+        );
+
+        let alice = clone_with_item(&remote, &temp.path().join("alice"), "001-a");
+        let item = fs::read_item(&alice.root, "001-a").unwrap();
+        persist_metadata(&alice, &item, "research").await.unwrap();
+        let files = git(
+            &alice.root,
+            &["ls-tree", "-r", "--name-only", "wreckit-meta"],
+        );
+        assert_eq!(files, ".wreckit/items/001-a/item.json");
+        sync_metadata(&alice).await.unwrap();
+
+        let bob = clone_with_item(&remote, &temp.path().join("bob"), "002-b");
+        sync_metadata(&bob).await.unwrap();
+        let ids: Vec<String> = fs::list_items(&bob.root)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(ids, vec!["001-a", "002-b"]);
+
+        sync_metadata(&alice).await.unwrap();
+        assert!(fs::read_item(&alice.root, "002-b").is_ok());
+    }
+}
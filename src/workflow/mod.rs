@@ -1,10 +1,102 @@
 //! Workflow phase runners
 //!
-//! This module will contain the implementation of each workflow phase:
-//! - Research phase
-//! - Planning phase
-//! - Implementation phase
-//! - PR phase
-//! - Completion phase
-//!
-// Placeholder for future implementation
+//! Each phase (research, plan, implement, pr, complete) implements the
+//! [`phases::Phase`] trait. The [`Orchestrator`] picks the phase for an item's
+//! current state and drives it; the `run`, `next`, and per-phase CLI commands
+//! are thin wrappers around it. With `--dry-run` they print a
+//! [`SimulationPlan`] instead of running anything. Config-defined states
+//! after in_pr are entered with [`advance_item`].
+
+pub mod abandon;
+pub mod assignment;
+pub mod attachments;
+pub mod auto_merge;
+pub mod bench;
+pub mod blocking;
+pub mod budget;
+pub mod bulk;
+pub mod changelog;
+pub mod commit_status;
+pub mod context;
+pub mod context_pack;
+pub mod conventions;
+pub mod credentials;
+pub mod custom_states;
+pub mod digest;
+pub mod disk;
+pub mod draft_pr;
+pub mod evaluation;
+pub mod events;
+pub mod experiments;
+pub mod gates;
+pub mod gc;
+pub mod guardrails;
+pub mod hooks;
+pub mod implement_loop;
+pub mod issues;
+pub mod lint;
+pub mod meta;
+pub mod network;
+pub mod notes;
+pub mod orchestrator;
+pub mod outbox;
+pub mod phases;
+pub mod policies;
+pub mod pr_bot;
+pub mod pr_size;
+pub mod presets;
+pub mod recurring;
+pub mod refresh;
+pub mod reopen;
+pub mod run_log;
+pub mod security;
+pub mod simulate;
+pub mod stats;
+pub mod supervisor;
+pub mod test_first;
+pub mod transcript;
+pub mod verification;
+
+pub use abandon::abandon_item;
+pub use assignment::{assign_item, claim_item, resolve_identity};
+pub use auto_merge::complete_merged_items;
+pub use bench::{format_bench_report, run_bench, BenchResult};
+pub use blocking::{block_item, ensure_unblocked, refresh_blocks, unblock_item};
+pub use budget::BudgetWatch;
+pub use bulk::{select_items, tag_item, BulkAction, BulkFilter};
+pub use changelog::write_changelog_fragment;
+pub use context::WorkflowContext;
+pub use conventions::{conventional_title, infer_change_type};
+pub use credentials::{check_credentials, require_agent_credentials, CredentialCheck};
+pub use custom_states::{advance_item, check_state_hooks};
+pub use digest::{append_key_decisions, extract_key_decisions};
+pub use disk::{check_disk, warn_disk_problems, DiskWarning};
+pub use evaluation::{parse_evaluation, review_artifact, score_artifact};
+pub use events::{EventBus, WorkflowEvent};
+pub use experiments::select_prompt_variant;
+pub use gates::{enforce_gates, evaluate_gate, GateOutcome};
+pub use gc::{run_gc, GcReport};
+pub use hooks::{hook_name, run_hook};
+pub use implement_loop::{run_implement_loop, LoopSummary};
+pub use issues::{import_issue, notify_issue, with_closing_keyword};
+pub use lint::{lint_item, lint_items, LintFinding};
+pub use meta::{persist_metadata, sync_metadata};
+pub use network::{network_preflight, required_endpoints, Endpoint};
+pub use notes::{add_note, answer_question, read_notes};
+pub use orchestrator::{find_next_item, find_next_item_for, Orchestrator};
+pub use outbox::{flush_outbox, queue_op, FlushReport, OutboxOp};
+pub use phases::{run_phase, Phase, PhaseKind};
+pub use pr_bot::{parse_bot_command, poll_pr_comments, BotCommand};
+pub use pr_size::enforce_pr_size;
+pub use presets::{
+    create_preset_item, find_flaky_tests, parse_outdated, FlakyTest, OutdatedDependency,
+};
+pub use recurring::create_due_items;
+pub use refresh::{refresh_item, refresh_stale_prs, RefreshOutcome};
+pub use reopen::reopen_item;
+pub use run_log::{find_run, list_runs, new_run_path, read_run, record_run, RunEntry, RunRecord};
+pub use security::{enforce_security_scans, run_security_scans, ScanOutcome};
+pub use simulate::{simulate_item, simulate_phase, PlanStep, SimulationPlan};
+pub use supervisor::{record_crash, restart_delay, running_supervisor, supervise};
+pub use transcript::{load_transcripts, record_transcript, ReplaySource, Transcript};
+pub use verification::{parse_verification, verify_story, CriterionCheck};
@@ -0,0 +1,177 @@
+//! Network connectivity preflight
+//!
+//! With `network.preflight`, each phase first checks that the endpoints it
+//! needs accept a connection: the model API for phases that run the agent,
+//! and the forge host for implement (progress posting), pr, and complete.
+//! An unreachable model API stops the phase with a clear error instead of an
+//! agent failure minutes later. An unreachable forge does too, unless
+//! `network.on_unreachable = "degrade"`: then the phase runs offline, with
+//! its PR queued in the outbox (see [`super::outbox`]) and progress posting
+//! and attachment uploads skipped. The complete phase cannot confirm a merge
+//! offline, so it always stops.
+
+use std::time::Duration;
+
+use tokio::net::TcpStream;
+
+use crate::errors::{Result, WreckitError};
+use crate::schemas::{AgentMode, OfflineAction};
+
+use super::context::WorkflowContext;
+use super::phases::PhaseKind;
+
+/// Port probed when a host does not name one
+const HTTPS_PORT: u16 = 443;
+
+/// An endpoint a phase needs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoint {
+    /// What the endpoint is for ("model API" or "forge")
+    pub name: &'static str,
+
+    /// Hostname probed, with an optional `:port` (443 if unset)
+    pub host: String,
+}
+
+impl Endpoint {
+    /// Host and port to connect to
+    fn address(&self) -> (&str, u16) {
+        match self.host.rsplit_once(':') {
+            Some((host, port)) => match port.parse() {
+                Ok(port) => (host, port),
+                Err(_) => (&self.host, HTTPS_PORT),
+            },
+            None => (&self.host, HTTPS_PORT),
+        }
+    }
+}
+
+/// Forge host PR operations go to
+fn forge_host(ctx: &WorkflowContext) -> String {
+    ctx.config
+        .forge
+        .github
+        .host
+        .clone()
+        .unwrap_or_else(|| "github.com".to_string())
+}
+
+/// Endpoints a phase needs; the model API only when the agent runs as a
+/// process (not mocked or replayed)
+pub fn required_endpoints(ctx: &WorkflowContext, kind: PhaseKind) -> Vec<Endpoint> {
+    let mut endpoints = Vec::new();
+    let agent_is_live = ctx.config.agent.mode == AgentMode::Process && ctx.replay.is_none();
+    if agent_is_live && kind != PhaseKind::Complete {
+        endpoints.push(Endpoint {
+            name: "model API",
+            host: ctx.config.network.model_api_host.clone(),
+        });
+    }
+    if matches!(
+        kind,
+        PhaseKind::Implement | PhaseKind::Pr | PhaseKind::Complete
+    ) {
+        endpoints.push(Endpoint {
+            name: "forge",
+            host: forge_host(ctx),
+        });
+    }
+    endpoints
+}
+
+/// Check that `host` accepts a TCP connection within the timeout, returning
+/// why not if it does not
+pub async fn probe(host: &str, port: u16, timeout: Duration) -> std::result::Result<(), String> {
+    match tokio::time::timeout(timeout, TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("connection timed out after {}s", timeout.as_secs())),
+    }
+}
+
+/// Probe the endpoints a phase needs before it starts. Returns whether the
+/// phase should run offline (the forge is unreachable and
+/// `on_unreachable = "degrade"`). No-op unless `network.preflight` is set,
+/// and in dry-run mode.
+///
+/// # Errors
+/// * `AgentError` - If the model API is unreachable
+/// * `GitError` - If the forge is unreachable and the phase cannot degrade
+pub async fn network_preflight(ctx: &WorkflowContext, kind: PhaseKind) -> Result<bool> {
+    let config = &ctx.config.network;
+    if !config.preflight || ctx.dry_run {
+        return Ok(false);
+    }
+    let timeout = Duration::from_secs(config.probe_timeout_seconds);
+    let mut offline = false;
+    for endpoint in required_endpoints(ctx, kind) {
+        let (host, port) = endpoint.address();
+        let Err(reason) = probe(host, port, timeout).await else {
+            continue;
+        };
+        let message = format!(
+            "network preflight for the {} phase: failed to connect to the {} at {} ({})",
+            kind, endpoint.name, endpoint.host, reason
+        );
+        if endpoint.name == "model API" {
+            return Err(WreckitError::AgentError(message));
+        }
+        if config.on_unreachable == OfflineAction::Fail || kind == PhaseKind::Complete {
+            return Err(WreckitError::GitError(message));
+        }
+        tracing::warn!("{}; running offline", message);
+        offline = true;
+    }
+    Ok(offline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+
+    #[test]
+    fn test_required_endpoints() {
+        let mut config = Config::default();
+        config.forge.github.host = Some("github.mycorp.com".to_string());
+        let ctx = WorkflowContext::new(std::env::temp_dir(), config);
+        let hosts = |kind| -> Vec<String> {
+            required_endpoints(&ctx, kind)
+                .into_iter()
+                .map(|e| e.host)
+                .collect()
+        };
+        assert_eq!(hosts(PhaseKind::Research), vec!["api.anthropic.com"]);
+        assert_eq!(
+            hosts(PhaseKind::Pr),
+            vec!["api.anthropic.com", "github.mycorp.com"]
+        );
+        assert_eq!(hosts(PhaseKind::Complete), vec!["github.mycorp.com"]);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_forge_degrades() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let timeout = Duration::from_secs(2);
+        assert!(probe("127.0.0.1", port, timeout).await.is_ok());
+        // Nothing listens on the port once the listener is dropped
+        drop(listener);
+        assert!(probe("127.0.0.1", port, timeout).await.is_err());
+
+        let mut config = Config::default();
+        config.agent.mode = AgentMode::Mock;
+        config.network.preflight = true;
+        config.forge.github.host = Some(format!("127.0.0.1:{}", port));
+        config.network.probe_timeout_seconds = 2;
+        let ctx = WorkflowContext::new(std::env::temp_dir(), config.clone());
+        let error = network_preflight(&ctx, PhaseKind::Pr).await.unwrap_err();
+        assert!(error.to_string().contains("failed to connect to the forge"));
+        assert!(!network_preflight(&ctx, PhaseKind::Research).await.unwrap());
+
+        config.network.on_unreachable = OfflineAction::Degrade;
+        let ctx = WorkflowContext::new(std::env::temp_dir(), config);
+        assert!(network_preflight(&ctx, PhaseKind::Pr).await.unwrap());
+        assert!(network_preflight(&ctx, PhaseKind::Complete).await.is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Operator notes
+//!
+//! `wreckit note <id> "..."` appends timestamped guidance to the item's
+//! notes.md. Every prompt rendered for the item afterwards ends with the
+//! notes, whatever the template, so a running item can be steered without
+//! editing templates or restarting it.
+//!
+//! `wreckit answer <id> "..."` answers a question the agent asked: the
+//! question and answer are appended to the notes, so the next prompt carries
+//! them, and the item is released to run again.
+
+use std::io::Write;
+use std::path::Path;
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::Item;
+
+use super::context::WorkflowContext;
+
+/// Append a timestamped note to an item's notes.md.
+///
+/// # Errors
+/// * `FileNotFound` - If the item does not exist
+/// * `ConfigError` - If the note is empty
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If notes.md cannot be written
+pub fn add_note(ctx: &WorkflowContext, id: &str, text: &str) -> Result<()> {
+    fs::read_item(&ctx.root, id)?;
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(WreckitError::ConfigError("the note is empty".to_string()));
+    }
+    let path = fs::get_notes_path(&ctx.root, id);
+    if ctx.dry_run {
+        tracing::info!("[DRY RUN] Would add a note to {}", path.display());
+        return Ok(());
+    }
+    fs::ensure_writable(|| format!("add a note to {}", id))?;
+    let mut file = fs::append_file(&path)?;
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC");
+    writeln!(file, "### {}\n\n{}\n", now, text)?;
+    Ok(())
+}
+
+/// Answer the question an item's agent is waiting on.
+///
+/// Records the question and answer as a note and clears the question (and
+/// the error it left), so the next run resumes with the answer in its prompt.
+///
+/// # Errors
+/// * `FileNotFound` - If the item does not exist
+/// * `StateTransition` - If the item has no open question
+/// * `ConfigError` - If the answer is empty
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If notes.md or the item cannot be written
+pub fn answer_question(ctx: &WorkflowContext, id: &str, answer: &str) -> Result<Item> {
+    let item = fs::read_item(&ctx.root, id)?;
+    let Some(ref question) = item.question else {
+        return Err(WreckitError::StateTransition(format!(
+            "{} has no open question",
+            id
+        )));
+    };
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Err(WreckitError::ConfigError("the answer is empty".to_string()));
+    }
+    let note = format!(
+        "**Question ({}):** {}\n\n**Answer:** {}",
+        question.phase, question.text, answer
+    );
+    add_note(ctx, id, &note)?;
+    let item = item
+        .with_question(None)
+        .with_error(None)
+        .with_error_code(None);
+    ctx.save_item(&item)?;
+    Ok(item)
+}
+
+/// An item's notes, if it has any
+pub fn read_notes(root: &Path, id: &str) -> Option<String> {
+    std::fs::read_to_string(fs::get_notes_path(root, id))
+        .ok()
+        .filter(|notes| !notes.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{MockFixture, DEFAULT_FIXTURES_DIR};
+    use crate::schemas::{AgentMode, Config, WorkflowState};
+    use crate::workflow::phases::{run_phase, ResearchPhase};
+
+    #[test]
+    fn test_notes_are_appended_and_prompted() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = WorkflowContext::new(dir.path().to_path_buf(), Config::default());
+        let item = Item::new("001-a".to_string(), "A".to_string(), String::new());
+        fs::write_item(dir.path(), &item.id, &item).unwrap();
+        assert_eq!(read_notes(dir.path(), "001-a"), None);
+
+        add_note(&ctx, "001-a", "Use the existing retry helper").unwrap();
+        add_note(&ctx, "001-a", "Skip the migration").unwrap();
+        assert!(add_note(&ctx, "001-a", "  ").is_err());
+        assert!(add_note(&ctx, "002-missing", "hi").is_err());
+
+        let notes = read_notes(dir.path(), "001-a").unwrap();
+        let first = notes.find("Use the existing retry helper").unwrap();
+        assert!(notes[first..].contains("Skip the migration"));
+
+        let prompt = ctx
+            .render_prompt("research", "001-a", ctx.prompt_variables(&item))
+            .unwrap();
+        assert!(prompt.contains("## Operator Notes"));
+        assert!(prompt.contains("Skip the migration"));
+    }
+
+    #[tokio::test]
+    async fn test_question_pauses_until_answered() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.agent.mode = AgentMode::Mock;
+        config.context_pack.enabled = false;
+        let ctx = WorkflowContext::new(dir.path().to_path_buf(), config);
+        let fixture = MockFixture {
+            output: "<question>Should sessions expire after 24h or 7d?</question>".to_string(),
+            ..Default::default()
+        };
+        fs::write_json(
+            &dir.path().join(DEFAULT_FIXTURES_DIR).join("research.json"),
+            &fixture,
+        )
+        .unwrap();
+        let item = Item::new("001-a".to_string(), "A".to_string(), String::new());
+        fs::write_item(dir.path(), &item.id, &item).unwrap();
+        assert!(answer_question(&ctx, "001-a", "7d").is_err());
+
+        let error = run_phase(&ResearchPhase, &ctx, item).await.unwrap_err();
+        assert!(matches!(error, WreckitError::AwaitingInput(_)));
+        let paused = fs::read_item(dir.path(), "001-a").unwrap();
+        assert_eq!(paused.state, WorkflowState::Idea);
+        let question = paused.question.unwrap();
+        assert_eq!(question.text, "Should sessions expire after 24h or 7d?");
+        assert_eq!(question.phase, "research");
+
+        let answered = answer_question(&ctx, "001-a", "7d").unwrap();
+        assert!(!answered.is_awaiting_input());
+        assert!(!fs::read_item(dir.path(), "001-a")
+            .unwrap()
+            .is_awaiting_input());
+        let notes = read_notes(dir.path(), "001-a").unwrap();
+        assert!(notes.contains("Should sessions expire after 24h or 7d?"));
+        assert!(notes.contains("**Answer:** 7d"));
+    }
+}
@@ -0,0 +1,722 @@
+//! Phase orchestration
+//!
+//! Picks the phase that applies to an item's current state, runs it, and
+//! keeps going until the item is waiting on a merge or done. Failures are
+//! recorded on the item as `last_error`, classified in `last_error_code`,
+//! and logged with a suggestion for what to do next.
+
+use crate::domain::{all_stories_done, is_terminal_state};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::{Item, RateLimitConfig, StoryStatus, WorkflowState};
+use crate::tui::runner::TuiUpdate;
+
+use super::blocking::{ensure_unblocked, refresh_blocks};
+use super::context::WorkflowContext;
+use super::events::WorkflowEvent;
+use super::experiments::select_prompt_variant;
+use super::hooks::run_hook;
+use super::meta::persist_metadata;
+use super::phases::{run_phase_kind, PhaseKind};
+use super::stats::record_phase_run;
+use super::transcript::load_transcripts;
+
+/// Runs items through workflow phases
+pub struct Orchestrator {
+    ctx: WorkflowContext,
+}
+
+impl Orchestrator {
+    /// Create an orchestrator for the given context
+    pub fn new(ctx: WorkflowContext) -> Self {
+        Self { ctx }
+    }
+
+    /// The workflow context
+    pub fn context(&self) -> &WorkflowContext {
+        &self.ctx
+    }
+
+    /// Run a single phase for an item
+    pub async fn run_phase(&self, id: &str, kind: PhaseKind) -> Result<Item> {
+        let item = fs::read_item(&self.ctx.root, id)?;
+        self.ctx
+            .emit(TuiUpdate::SetCurrentItem(Some(item.id.clone())));
+        self.run_and_record(kind, item).await
+    }
+
+    /// Re-run one phase for an item against recorded transcripts.
+    ///
+    /// The context should carry a replay source. The item is rewound to the
+    /// phase's entry state and, for implement, the recorded stories are reset
+    /// to pending so the phase is driven end to end again.
+    pub async fn replay_phase(&self, id: &str, kind: PhaseKind) -> Result<Item> {
+        let item = fs::read_item(&self.ctx.root, id)?;
+        let rewound = item.clone().with_state(kind.entry_state());
+
+        if kind == PhaseKind::Implement && !self.ctx.dry_run {
+            let stories = load_transcripts(&self.ctx.root, id, Some(kind.name()))?
+                .into_iter()
+                .filter_map(|t| t.story);
+            let mut prd = fs::read_prd(&self.ctx.root, id)?;
+            for story in stories {
+                prd = prd.with_story_status(&story, StoryStatus::Pending);
+            }
+            // Rewind the item and its stories together
+            fs::Transaction::new(&self.ctx.root)
+                .write_item(&rewound)?
+                .write_prd(id, &prd)?
+                .commit()?;
+        }
+
+        self.ctx
+            .emit(TuiUpdate::SetCurrentItem(Some(item.id.clone())));
+        let result = run_phase_kind(kind, &self.ctx, rewound).await;
+        let item = self.record_outcome(item, result).await?;
+
+        let left = self.ctx.replay.as_ref().map_or(0, |r| r.remaining());
+        if left > 0 {
+            tracing::warn!("Replay finished with {} recording(s) unused", left);
+        }
+        Ok(item)
+    }
+
+    /// Re-run the phase an item failed in, or `kind` if given.
+    ///
+    /// Clears `last_error` first. With `rollback`, the item is rewound to the
+    /// phase's entry state; otherwise it must already be in one of the
+    /// phase's entry states. The phase re-runs even if its artifacts exist
+    /// when the context is forced.
+    ///
+    /// # Errors
+    /// * `StateTransition` - If no phase applies or the item is in the wrong
+    ///   state for the requested phase
+    pub async fn retry_phase(
+        &self,
+        id: &str,
+        kind: Option<PhaseKind>,
+        rollback: bool,
+    ) -> Result<Item> {
+        let item = fs::read_item(&self.ctx.root, id)?;
+        let kind = match kind.or_else(|| self.next_phase(&item)) {
+            Some(kind) => kind,
+            None => {
+                return Err(WreckitError::StateTransition(format!(
+                    "{} is {}; there is no phase to retry",
+                    id, item.state
+                )))
+            }
+        };
+        let mut item = item.with_error(None);
+        if rollback {
+            item = item.with_state(kind.entry_state());
+        } else if item.state != kind.entry_state() && PhaseKind::for_state(item.state) != Some(kind)
+        {
+            return Err(WreckitError::StateTransition(format!(
+                "{} is {}; pass --rollback to rewind it to {} for the {} phase",
+                id,
+                item.state,
+                kind.entry_state(),
+                kind
+            )));
+        }
+        if !self.ctx.dry_run {
+            self.ctx.save_item(&item.clone().with_updated_timestamp())?;
+        }
+        tracing::info!("Retrying {} phase for {}", kind, id);
+        self.ctx
+            .emit(TuiUpdate::SetCurrentItem(Some(item.id.clone())));
+        self.run_recorded(kind, item, true).await
+    }
+
+    /// Run an item through every applicable phase.
+    ///
+    /// Stops once the item is in_pr (waiting on review) or done. Blocked
+    /// items are refused unless their unblock condition has been met, as are
+    /// items whose agent is waiting on an answer.
+    pub async fn run_item(&self, id: &str) -> Result<Item> {
+        let mut item = ensure_unblocked(&self.ctx, fs::read_item(&self.ctx.root, id)?)?;
+        if let Some(ref question) = item.question {
+            return Err(WreckitError::StateTransition(format!(
+                "{} is awaiting input: {}; answer it with `wreckit answer {} \"...\"`",
+                id, question.text, id
+            )));
+        }
+        self.ctx
+            .emit(TuiUpdate::SetCurrentItem(Some(item.id.clone())));
+
+        while let Some(kind) = self.next_phase(&item) {
+            self.ctx.wait_if_paused().await;
+
+            let before = item.state;
+            item = self.run_and_record(kind, item).await?;
+
+            if item.state == before {
+                // Dry runs (and phases that did not advance) would loop forever
+                break;
+            }
+            if item.is_blocked() {
+                // Held for approval (a low self-evaluation score)
+                break;
+            }
+        }
+
+        self.ctx.emit(TuiUpdate::SetCurrentPhase(None));
+        Ok(item)
+    }
+
+    /// Find and run the next item that still has work to do.
+    ///
+    /// Blockers whose conditions are met are lifted first. Returns None when
+    /// no item needs work.
+    pub async fn run_next(&self) -> Result<Option<Item>> {
+        self.run_next_for(None).await
+    }
+
+    /// Like [`Orchestrator::run_next`], but only considers items assigned to
+    /// `assignee` when one is given.
+    pub async fn run_next_for(&self, assignee: Option<&str>) -> Result<Option<Item>> {
+        // Blockers can depend on finished items, so every item is needed here
+        let items = fs::query_items(&self.ctx.root, &fs::ItemQuery::new())?;
+        let items = refresh_blocks(&self.ctx, items)?;
+        match find_next_item_for(&items, assignee) {
+            Some(item) => self.run_item(&item.id).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Run every item that has work to do, one after another.
+    ///
+    /// Items that fail or are cancelled from the TUI are recorded and skipped
+    /// for the rest of the run; an interrupt that pauses the run stops it. A
+    /// rate limit pauses the whole run instead: the item is retried after a
+    /// backoff (the agent's retry-after hint, or an exponential delay), and
+    /// the run stops once `rate_limit.max_retries` consecutive attempts have
+    /// been throttled rather than failing every remaining item.
+    ///
+    /// # Returns
+    /// The items that were run, in order, with their final states
+    ///
+    /// # Errors
+    /// * `RateLimited` - If the rate limit outlasts the retry budget
+    pub async fn run_all(&self) -> Result<Vec<Item>> {
+        let limits = &self.ctx.config.rate_limit;
+        let mut attempted: Vec<String> = Vec::new();
+        let mut finished = Vec::new();
+        let mut throttled = 0u32;
+
+        loop {
+            let items = fs::query_items(&self.ctx.root, &fs::ItemQuery::new())?;
+            let items = refresh_blocks(&self.ctx, items)?;
+            let candidates: Vec<Item> = items
+                .into_iter()
+                .filter(|item| !attempted.contains(&item.id))
+                .collect();
+            let id = match find_next_item_for(&candidates, None) {
+                Some(item) => item.id.clone(),
+                None => break,
+            };
+
+            match self.run_item(&id).await {
+                Ok(item) => {
+                    throttled = 0;
+                    attempted.push(id);
+                    finished.push(item);
+                }
+                Err(WreckitError::RateLimited {
+                    message,
+                    retry_after,
+                }) => {
+                    throttled += 1;
+                    if throttled > limits.max_retries {
+                        return Err(WreckitError::RateLimited {
+                            message,
+                            retry_after,
+                        });
+                    }
+                    let wait = backoff_seconds(retry_after, throttled, limits);
+                    tracing::warn!(
+                        "Rate limited on {} ({}); pausing all work for {}s (retry {}/{})",
+                        id,
+                        message,
+                        wait,
+                        throttled,
+                        limits.max_retries
+                    );
+                    self.ctx.wait(std::time::Duration::from_secs(wait)).await?;
+                }
+                Err(WreckitError::Interrupted) if self.is_stopped() => {
+                    return Err(WreckitError::Interrupted)
+                }
+                Err(WreckitError::Interrupted) => {
+                    // Only the current item was cancelled; move on to the next
+                    throttled = 0;
+                    tracing::info!("{} was cancelled; continuing with the next item", id);
+                    attempted.push(id);
+                }
+                Err(WreckitError::AwaitingInput(question)) => {
+                    throttled = 0;
+                    tracing::info!("{} is awaiting input: {}", id, question);
+                    attempted.push(id);
+                }
+                Err(e) => {
+                    throttled = 0;
+                    tracing::warn!("{} failed: {}", id, e);
+                    attempted.push(id);
+                }
+            }
+        }
+        Ok(finished)
+    }
+
+    /// Whether the whole run was stopped (SIGINT or an operator pause), as
+    /// opposed to only the current item being cancelled
+    fn is_stopped(&self) -> bool {
+        self.ctx
+            .control
+            .as_ref()
+            .is_some_and(|control| control.is_paused())
+    }
+
+    /// The phase `run_item` should run next for an item, if any
+    pub fn next_phase(&self, item: &Item) -> Option<PhaseKind> {
+        match item.state {
+            WorkflowState::InPr | WorkflowState::Done | WorkflowState::Abandoned => None,
+            WorkflowState::Implementing
+                if all_stories_done(fs::read_prd(&self.ctx.root, &item.id).ok().as_ref()) =>
+            {
+                Some(PhaseKind::Pr)
+            }
+            state => PhaseKind::for_state(state),
+        }
+    }
+
+    /// Run a phase, record its outcome, and persist the metadata it changed
+    async fn run_and_record(&self, kind: PhaseKind, item: Item) -> Result<Item> {
+        let retry = item.last_error.is_some();
+        self.run_recorded(kind, item, retry).await
+    }
+
+    /// Like [`Orchestrator::run_and_record`], counting the run as a retry if `retry`
+    async fn run_recorded(&self, kind: PhaseKind, item: Item, retry: bool) -> Result<Item> {
+        let id = item.id.clone();
+        let variant = select_prompt_variant(&self.ctx, kind.name());
+        let started = std::time::Instant::now();
+        let result = run_phase_kind(kind, &self.ctx, item.clone()).await;
+        let seconds = started.elapsed().as_secs();
+        record_phase_run(
+            &self.ctx,
+            kind,
+            seconds,
+            retry,
+            variant.as_deref(),
+            result.as_ref().err(),
+        );
+        let outcome = self.record_outcome(item, result).await;
+
+        if let Ok(latest) = fs::read_item(&self.ctx.root, &id) {
+            if let Err(e) = persist_metadata(&self.ctx, &latest, kind.name()).await {
+                tracing::warn!("Could not commit metadata for {}: {}", id, e);
+            }
+        }
+        outcome
+    }
+
+    /// Record a phase failure on the item, publish it, and run the on_error hook
+    async fn record_outcome(&self, item: Item, result: Result<Item>) -> Result<Item> {
+        match result {
+            Ok(item) => Ok(item),
+            Err(e) => {
+                if matches!(e, WreckitError::Interrupted) {
+                    if let Some(ref control) = self.ctx.control {
+                        control.acknowledge_cancel();
+                    }
+                }
+                // Re-read so artifacts written during the phase (e.g. branch) are kept
+                let latest = fs::read_item(&self.ctx.root, &item.id).unwrap_or(item);
+                let class = e.classify();
+                let failed = latest
+                    .with_error(Some(e.to_string()))
+                    .with_error_code(Some(class.code().to_string()));
+                self.ctx.save_item(&failed)?;
+                if let Some(hint) = class.hint(Some(&failed.id)) {
+                    tracing::warn!("{} failed ({}); next: {}", failed.id, class.code(), hint);
+                }
+                let event = WorkflowEvent::Error {
+                    item_id: failed.id.clone(),
+                    message: e.to_string(),
+                    code: class.code().to_string(),
+                };
+                if let Err(hook_error) = run_hook(&self.ctx, &event).await {
+                    tracing::warn!("{}", hook_error);
+                }
+                self.ctx.publish(event);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Seconds to wait before the `attempt`-th retry after a rate limit
+pub fn backoff_seconds(retry_after: Option<u64>, attempt: u32, limits: &RateLimitConfig) -> u64 {
+    let wait = retry_after.unwrap_or_else(|| {
+        limits
+            .initial_backoff_seconds
+            .saturating_mul(1u64 << attempt.saturating_sub(1).min(16))
+    });
+    wait.min(limits.max_backoff_seconds)
+}
+
+/// The first item (by ID) that is not blocked, awaiting an answer, waiting on a
+/// merge, or finished
+pub fn find_next_item(items: &[Item]) -> Option<&Item> {
+    find_next_item_for(items, None)
+}
+
+/// Like [`find_next_item`], restricted to items assigned to `assignee` if given
+pub fn find_next_item_for<'a>(items: &'a [Item], assignee: Option<&str>) -> Option<&'a Item> {
+    items.iter().find(|item| {
+        !item.is_blocked()
+            && !item.is_awaiting_input()
+            && !is_terminal_state(item.state)
+            && item.state != WorkflowState::InPr
+            && assignee.is_none_or(|me| item.assignee.as_deref() == Some(me))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AgentResult;
+    use crate::schemas::{Blocker, Config, Question};
+    use crate::workflow::transcript::{record_transcript, ReplaySource, Transcript};
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Orchestrator) {
+        let temp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.agent.command = "cat".to_string();
+        config.agent.args = vec![];
+        config.agent.completion_signal = String::new();
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        (temp, Orchestrator::new(ctx))
+    }
+
+    fn write(temp: &TempDir, id: &str, state: WorkflowState) -> Item {
+        let item = Item::new(id.to_string(), id.to_string(), String::new()).with_state(state);
+        fs::write_item(temp.path(), id, &item).unwrap();
+        item
+    }
+
+    #[test]
+    fn test_find_next_item_skips_waiting_and_done() {
+        let items = vec![
+            Item::new("001".to_string(), "a".to_string(), String::new())
+                .with_state(WorkflowState::Done),
+            Item::new("002".to_string(), "b".to_string(), String::new())
+                .with_state(WorkflowState::InPr),
+            Item::new("003".to_string(), "c".to_string(), String::new())
+                .with_state(WorkflowState::Researched),
+        ];
+        assert_eq!(find_next_item(&items).unwrap().id, "003");
+        assert!(find_next_item(&items[..2]).is_none());
+    }
+
+    #[test]
+    fn test_find_next_item_skips_blocked() {
+        let items = vec![
+            Item::new("001".to_string(), "a".to_string(), String::new())
+                .with_blocked(Some(Blocker::new("waiting".to_string()))),
+            Item::new("002".to_string(), "b".to_string(), String::new()).with_question(Some(
+                Question::new("Which region?".to_string(), "plan".to_string()),
+            )),
+            Item::new("003".to_string(), "c".to_string(), String::new()),
+        ];
+        assert_eq!(find_next_item(&items).unwrap().id, "003");
+    }
+
+    #[test]
+    fn test_find_next_item_for_assignee() {
+        let items = vec![
+            Item::new("001".to_string(), "a".to_string(), String::new())
+                .with_assignee(Some("bo@example.com".to_string())),
+            Item::new("002".to_string(), "b".to_string(), String::new()),
+            Item::new("003".to_string(), "c".to_string(), String::new())
+                .with_assignee(Some("ana@example.com".to_string())),
+        ];
+        assert_eq!(find_next_item_for(&items, None).unwrap().id, "001");
+        assert_eq!(
+            find_next_item_for(&items, Some("ana@example.com"))
+                .unwrap()
+                .id,
+            "003"
+        );
+        assert!(find_next_item_for(&items, Some("cy@example.com")).is_none());
+    }
+
+    #[test]
+    fn test_backoff_seconds() {
+        let limits = RateLimitConfig {
+            initial_backoff_seconds: 10,
+            max_backoff_seconds: 60,
+            max_retries: 5,
+        };
+        assert_eq!(backoff_seconds(None, 1, &limits), 10);
+        assert_eq!(backoff_seconds(None, 3, &limits), 40);
+        assert_eq!(backoff_seconds(None, 9, &limits), 60);
+        assert_eq!(backoff_seconds(Some(5), 4, &limits), 5);
+        assert_eq!(backoff_seconds(Some(600), 1, &limits), 60);
+    }
+
+    #[tokio::test]
+    async fn test_retry_phase_with_feedback() {
+        let (temp, orchestrator) = setup();
+        let item =
+            write(&temp, "001-a", WorkflowState::Researched).with_error(Some("boom".to_string()));
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+        std::fs::write(fs::get_research_path(temp.path(), &item.id), "# Research").unwrap();
+
+        // Research is behind the item; it has to be rewound explicitly
+        let err = orchestrator
+            .retry_phase("001-a", Some(PhaseKind::Research), false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("--rollback"));
+
+        let ctx = orchestrator
+            .context()
+            .clone()
+            .with_force(true)
+            .with_feedback(Some("Focus on the API layer".to_string()));
+        let item = Orchestrator::new(ctx)
+            .retry_phase("001-a", Some(PhaseKind::Research), true)
+            .await
+            .unwrap();
+        assert_eq!(item.state, WorkflowState::Researched);
+        assert!(item.last_error.is_none());
+
+        let transcripts = load_transcripts(temp.path(), "001-a", Some("research")).unwrap();
+        assert!(transcripts[0]
+            .prompt
+            .ends_with("## Operator Feedback\nFocus on the API layer\n"));
+
+        write(&temp, "002-b", WorkflowState::Done);
+        assert!(orchestrator
+            .retry_phase("002-b", None, false)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_all_backs_off_globally_when_rate_limited() {
+        use crate::agent::{MockFixture, DEFAULT_FIXTURES_DIR};
+        use crate::schemas::AgentMode;
+
+        let (temp, orchestrator) = setup();
+        write(&temp, "001-a", WorkflowState::Idea);
+        write(&temp, "002-b", WorkflowState::Idea);
+        let throttled = MockFixture {
+            output: "API Error: 429 rate_limit_error. Retry after 0 seconds".to_string(),
+            exit_code: 1,
+            complete: false,
+            ..Default::default()
+        };
+        fs::write_json(
+            &temp.path().join(DEFAULT_FIXTURES_DIR).join("research.json"),
+            &throttled,
+        )
+        .unwrap();
+
+        let mut ctx = orchestrator.context().clone();
+        ctx.config.agent.mode = AgentMode::Mock;
+        ctx.config.rate_limit.max_retries = 2;
+        let orchestrator = Orchestrator::new(ctx);
+
+        let err = orchestrator.run_all().await.unwrap_err();
+        assert!(matches!(err, WreckitError::RateLimited { .. }));
+        // The first item was retried; the second was never attempted
+        assert_eq!(
+            load_transcripts(temp.path(), "001-a", None).unwrap().len(),
+            3
+        );
+        assert!(load_transcripts(temp.path(), "002-b", None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_all_skips_cancelled_item_and_continues() {
+        use crate::tui::control::{control_channel, ControlCommand};
+
+        let (temp, orchestrator) = setup();
+        write(&temp, "001-a", WorkflowState::Idea);
+        write(&temp, "002-b", WorkflowState::Idea);
+
+        let (sender, handle) = control_channel();
+        let ctx = orchestrator.context().clone().with_control(handle);
+        let orchestrator = Orchestrator::new(ctx);
+        sender.send(ControlCommand::CancelCurrent);
+
+        let finished = orchestrator.run_all().await.unwrap();
+        assert!(finished.is_empty());
+
+        let cancelled = fs::read_item(temp.path(), "001-a").unwrap();
+        assert_eq!(cancelled.last_error_code.as_deref(), Some("interrupted"));
+        // The next item still ran, with a fresh token
+        let next = fs::read_item(temp.path(), "002-b").unwrap();
+        assert!(next.last_error_code.is_some());
+        assert_ne!(next.last_error_code.as_deref(), Some("interrupted"));
+    }
+
+    #[tokio::test]
+    async fn test_run_item_advances_until_artifacts_missing() {
+        let (temp, orchestrator) = setup();
+        write(&temp, "001-test", WorkflowState::Idea);
+        std::fs::write(fs::get_research_path(temp.path(), "001-test"), "# Research").unwrap();
+
+        // Research is already done; planning produces nothing, so the run fails there
+        let err = orchestrator.run_item("001-test").await.unwrap_err();
+        assert!(err.to_string().contains("plan"));
+
+        let stored = fs::read_item(temp.path(), "001-test").unwrap();
+        assert_eq!(stored.state, WorkflowState::Researched);
+        assert!(stored.last_error.is_some());
+        assert_eq!(stored.last_error_code.as_deref(), Some("validation"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_phase_uses_recordings() {
+        let (temp, orchestrator) = setup();
+        write(&temp, "001-test", WorkflowState::Planned);
+        std::fs::write(fs::get_research_path(temp.path(), "001-test"), "# Research").unwrap();
+        let recorded = AgentResult {
+            success: true,
+            output: "researched".to_string(),
+            timed_out: false,
+            exit_code: Some(0),
+            completion_detected: true,
+        };
+        record_transcript(
+            temp.path(),
+            "001-test",
+            &Transcript::from_result("research", None, "prompt", &recorded),
+        )
+        .unwrap();
+
+        // A live agent call would fail; the replay must not make one
+        let mut ctx = orchestrator.context().clone();
+        ctx.config.agent.command = "false".to_string();
+        let source = ReplaySource::new(load_transcripts(temp.path(), "001-test", None).unwrap());
+        let orchestrator = Orchestrator::new(ctx.with_force(true).with_replay(source));
+
+        let item = orchestrator
+            .replay_phase("001-test", PhaseKind::Research)
+            .await
+            .unwrap();
+        assert_eq!(item.state, WorkflowState::Researched);
+        assert_eq!(
+            orchestrator.context().replay.as_ref().unwrap().remaining(),
+            0
+        );
+    }
+
+    fn git(dir: &std::path::Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[tokio::test]
+    async fn test_run_item_end_to_end_with_mock_agent() {
+        use crate::agent::{MockFixture, DEFAULT_FIXTURES_DIR};
+        use crate::schemas::{AgentMode, MergeMode};
+
+        let temp = TempDir::new().unwrap();
+        let remote = temp.path().join("remote.git");
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        git(
+            temp.path(),
+            &["init", "-q", "--bare", remote.to_str().unwrap()],
+        );
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["config", "user.email", "test@example.com"]);
+        git(&repo, &["config", "user.name", "Test"]);
+        std::fs::write(repo.join("README.md"), "demo").unwrap();
+        git(&repo, &["add", "-A"]);
+        git(&repo, &["commit", "-q", "-m", "init"]);
+        git(
+            &repo,
+            &["remote", "add", "origin", remote.to_str().unwrap()],
+        );
+        git(&repo, &["push", "-q", "-u", "origin", "main"]);
+
+        let fixtures = repo.join(DEFAULT_FIXTURES_DIR);
+        let fixture = |files: &[(&str, &str)], repo_files: &[(&str, &str)]| MockFixture {
+            complete: true,
+            files: files
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            repo_files: repo_files
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+        let prd = r#"{"schema_version":1,"id":"{{id}}","branch_name":"wreckit/{{id}}","user_stories":[{"id":"US-001","title":"Add feature","acceptance_criteria":[],"priority":1,"status":"pending","notes":""}]}"#;
+        fs::write_json(
+            &fixtures.join("research.json"),
+            &fixture(&[("research.md", "# R")], &[]),
+        )
+        .unwrap();
+        fs::write_json(
+            &fixtures.join("plan.json"),
+            &fixture(&[("plan.md", "# P"), ("prd.json", prd)], &[]),
+        )
+        .unwrap();
+        fs::write_json(
+            &fixtures.join("implement.json"),
+            &fixture(&[], &[("feature.txt", "{{story}}")]),
+        )
+        .unwrap();
+        fs::write_json(&fixtures.join("pr.json"), &fixture(&[], &[])).unwrap();
+
+        let mut config = Config::default();
+        config.agent.mode = AgentMode::Mock;
+        config.merge_mode = MergeMode::Direct;
+        let item = Item::new("001-test".to_string(), "Test".to_string(), String::new());
+        fs::write_item(&repo, &item.id, &item).unwrap();
+
+        let orchestrator = Orchestrator::new(WorkflowContext::new(repo.clone(), config));
+        let item = orchestrator.run_item("001-test").await.unwrap();
+
+        assert_eq!(item.state, WorkflowState::Done);
+        assert_eq!(
+            std::fs::read_to_string(repo.join("feature.txt")).unwrap(),
+            "US-001"
+        );
+        assert!(fs::read_prd(&repo, "001-test").unwrap().all_stories_done());
+        let log = std::process::Command::new("git")
+            .args(["log", "-1", "--format=%B", "main"])
+            .current_dir(&repo)
+            .output()
+            .unwrap();
+        let message = String::from_utf8_lossy(&log.stdout);
+        assert!(message.starts_with("Test\n\nStories:\n- US-001: Add feature"));
+        assert!(message.contains("Wreckit-Item: 001-test"));
+        // research, plan, implement, pr
+        assert_eq!(load_transcripts(&repo, "001-test", None).unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_run_next_none_when_all_waiting() {
+        let (temp, orchestrator) = setup();
+        write(&temp, "001-test", WorkflowState::InPr);
+        assert!(orchestrator.run_next().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,373 @@
+//! Offline queue for forge operations
+//!
+//! When a PR or comment cannot be sent because the forge is unreachable, the
+//! operation is written to `.wreckit/outbox/` instead of failing the run, so
+//! an implement loop keeps going without a network. A queued PR counts as
+//! opened for the PR phase, and the item moves to in_pr without a URL. The
+//! next workflow command that can reach the forge (or `wreckit flush`)
+//! replays the queue in order and records the PR on the item. Operations
+//! that fail for another reason stay queued with the error, so nothing is
+//! silently dropped.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git::{self, GitOptions};
+
+use super::context::WorkflowContext;
+use super::phases::{open_pr, PrRequest};
+
+/// Error text from git, gh, or the API client that means the forge could
+/// not be reached
+const OFFLINE_PATTERNS: &[&str] = &[
+    "could not resolve host",
+    "could not resolve hostname",
+    "no such host",
+    "name or service not known",
+    "temporary failure in name resolution",
+    "network is unreachable",
+    "connection refused",
+    "connection timed out",
+    "failed to connect",
+    "error connecting to",
+    "dns failed",
+    "dial tcp",
+];
+
+/// Whether an error means the forge was unreachable rather than that it
+/// refused the operation
+pub fn is_offline_error(error: &WreckitError) -> bool {
+    let message = error.to_string().to_lowercase();
+    OFFLINE_PATTERNS
+        .iter()
+        .any(|pattern| message.contains(pattern))
+}
+
+/// A forge operation waiting for the network
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum OutboxOp {
+    /// Push an item branch and open its PR
+    OpenPr(PrRequest),
+
+    /// Comment on a PR
+    PrComment {
+        item_id: String,
+        pr_number: u32,
+        body: String,
+    },
+
+    /// Comment on an issue
+    IssueComment {
+        item_id: String,
+        issue_number: u32,
+        body: String,
+    },
+}
+
+impl OutboxOp {
+    /// Item the operation is for
+    pub fn item_id(&self) -> &str {
+        match self {
+            OutboxOp::OpenPr(request) => &request.item_id,
+            OutboxOp::PrComment { item_id, .. } | OutboxOp::IssueComment { item_id, .. } => item_id,
+        }
+    }
+
+    /// Short description for logs
+    pub fn describe(&self) -> String {
+        match self {
+            OutboxOp::OpenPr(request) => format!("open PR for {}", request.item_id),
+            OutboxOp::PrComment {
+                item_id, pr_number, ..
+            } => format!("comment on PR #{} for {}", pr_number, item_id),
+            OutboxOp::IssueComment {
+                item_id,
+                issue_number,
+                ..
+            } => format!("comment on issue #{} for {}", issue_number, item_id),
+        }
+    }
+}
+
+/// A queued operation as stored in the outbox
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    /// ISO 8601 timestamp of when the operation was queued
+    pub queued_at: String,
+
+    /// Failed delivery attempts after queueing
+    #[serde(default)]
+    pub attempts: u32,
+
+    /// Error from the last failed attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+
+    /// The operation
+    #[serde(flatten)]
+    pub op: OutboxOp,
+}
+
+/// Queue an operation, returning its file.
+///
+/// # Errors
+/// * `ReadOnly` - In read-only mode
+/// * `Io` - If the entry cannot be written
+pub fn queue_op(root: &Path, op: OutboxOp) -> Result<PathBuf> {
+    let now = chrono::Utc::now();
+    let path = fs::get_outbox_dir(root).join(format!(
+        "{}-{}.json",
+        now.format("%Y%m%dT%H%M%S%.6f"),
+        std::process::id()
+    ));
+    let entry = OutboxEntry {
+        queued_at: now.to_rfc3339(),
+        attempts: 0,
+        last_error: None,
+        op,
+    };
+    fs::write_json(&path, &entry)?;
+    Ok(path)
+}
+
+/// Every queued operation, oldest first.
+///
+/// # Errors
+/// * `InvalidJson` - If an entry is malformed
+/// * `Io` - If the outbox cannot be read
+pub fn list_outbox(root: &Path) -> Result<Vec<(PathBuf, OutboxEntry)>> {
+    let dir = fs::get_outbox_dir(root);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let entry = fs::read_json(&path)?;
+            Ok((path, entry))
+        })
+        .collect()
+}
+
+/// Whether a PR for the item is waiting in the outbox
+pub fn has_queued_pr(root: &Path, item_id: &str) -> bool {
+    list_outbox(root).is_ok_and(|entries| {
+        entries
+            .iter()
+            .any(|(_, entry)| matches!(entry.op, OutboxOp::OpenPr(ref r) if r.item_id == item_id))
+    })
+}
+
+/// Comment on a PR, queueing the comment if the forge is unreachable.
+///
+/// # Errors
+/// * `GitError` - If the forge rejects the comment
+/// * `Io` - If the comment cannot be queued
+pub async fn comment_on_pr(
+    root: &Path,
+    item_id: &str,
+    pr_number: u32,
+    body: &str,
+    options: &GitOptions,
+) -> Result<()> {
+    match git::comment_on_pr(pr_number, body, options).await {
+        Err(e) if is_offline_error(&e) => {
+            queue_op(
+                root,
+                OutboxOp::PrComment {
+                    item_id: item_id.to_string(),
+                    pr_number,
+                    body: body.to_string(),
+                },
+            )?;
+            tracing::warn!("Offline; queued a comment on PR #{} ({})", pr_number, e);
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Comment on an issue, queueing the comment if the forge is unreachable.
+///
+/// # Errors
+/// * `GitError` - If the forge rejects the comment
+/// * `Io` - If the comment cannot be queued
+pub async fn comment_on_issue(
+    root: &Path,
+    item_id: &str,
+    issue_number: u32,
+    body: &str,
+    options: &GitOptions,
+) -> Result<()> {
+    match git::comment_on_issue(issue_number, body, options).await {
+        Err(e) if is_offline_error(&e) => {
+            queue_op(
+                root,
+                OutboxOp::IssueComment {
+                    item_id: item_id.to_string(),
+                    issue_number,
+                    body: body.to_string(),
+                },
+            )?;
+            tracing::warn!(
+                "Offline; queued a comment on issue #{} ({})",
+                issue_number,
+                e
+            );
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// What flushing the outbox did
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlushReport {
+    /// Operations delivered and removed
+    pub sent: usize,
+
+    /// Operations the forge rejected; they stay queued with the error
+    pub failed: usize,
+
+    /// Operations left untried because the forge is still unreachable
+    pub pending: usize,
+}
+
+async fn deliver(ctx: &WorkflowContext, op: &OutboxOp) -> Result<()> {
+    let options = ctx.git_options();
+    match op {
+        OutboxOp::OpenPr(request) => {
+            let pr = open_pr(ctx, request).await?;
+            let item =
+                fs::read_item(&ctx.root, &request.item_id)?.with_pr(Some(pr.url), Some(pr.number));
+            ctx.save_item(&item)
+        }
+        OutboxOp::PrComment {
+            pr_number, body, ..
+        } => git::comment_on_pr(*pr_number, body, &options).await,
+        OutboxOp::IssueComment {
+            issue_number, body, ..
+        } => git::comment_on_issue(*issue_number, body, &options).await,
+    }
+}
+
+/// Send queued operations in order. Delivery stops at the first one that
+/// still cannot reach the forge; one the forge rejects keeps its place with
+/// the error and the rest are still tried. No-op in dry-run mode.
+///
+/// # Errors
+/// * `InvalidJson` - If an entry is malformed
+/// * `Io` - If the outbox cannot be read or updated
+pub async fn flush_outbox(ctx: &WorkflowContext) -> Result<FlushReport> {
+    let entries = list_outbox(&ctx.root)?;
+    let mut report = FlushReport::default();
+    if ctx.dry_run {
+        for (_, entry) in &entries {
+            tracing::info!("[DRY RUN] Would {}", entry.op.describe());
+        }
+        report.pending = entries.len();
+        return Ok(report);
+    }
+
+    let mut offline = false;
+    for (path, mut entry) in entries {
+        if offline {
+            report.pending += 1;
+            continue;
+        }
+        match deliver(ctx, &entry.op).await {
+            Ok(()) => {
+                fs::remove_file(&path)?;
+                tracing::info!("Outbox: {}", entry.op.describe());
+                report.sent += 1;
+            }
+            Err(e) => {
+                offline = is_offline_error(&e);
+                entry.attempts += 1;
+                entry.last_error = Some(e.to_string());
+                fs::write_json(&path, &entry)?;
+                if offline {
+                    report.pending += 1;
+                } else {
+                    tracing::warn!("Outbox: cannot {}: {}", entry.op.describe(), e);
+                    report.failed += 1;
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(item_id: &str, pr_number: u32) -> OutboxOp {
+        OutboxOp::PrComment {
+            item_id: item_id.to_string(),
+            pr_number,
+            body: "rebased".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_is_offline_error() {
+        let offline = WreckitError::GitError(
+            "git push -u origin wreckit/001 failed: fatal: unable to access 'https://github.com/o/r/': Could not resolve host: github.com".to_string(),
+        );
+        assert!(is_offline_error(&offline));
+        let refused = WreckitError::GitError(
+            "gh pr create failed: GraphQL: No commits between main and wreckit/001".to_string(),
+        );
+        assert!(!is_offline_error(&refused));
+    }
+
+    #[test]
+    fn test_queue_and_list_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list_outbox(dir.path()).unwrap().is_empty());
+
+        queue_op(dir.path(), comment("001-a", 7)).unwrap();
+        let request = PrRequest {
+            item_id: "002-b".to_string(),
+            base: "main".to_string(),
+            branch: "wreckit/002-b".to_string(),
+            title: "B".to_string(),
+            body: String::new(),
+            labels: Vec::new(),
+            auto_merge: None,
+        };
+        queue_op(dir.path(), OutboxOp::OpenPr(request.clone())).unwrap();
+
+        let entries = list_outbox(dir.path()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].1.op, comment("001-a", 7));
+        assert_eq!(entries[1].1.op, OutboxOp::OpenPr(request));
+        assert!(has_queued_pr(dir.path(), "002-b"));
+        assert!(!has_queued_pr(dir.path(), "001-a"));
+
+        let stored = std::fs::read_to_string(&entries[0].0).unwrap();
+        assert!(stored.contains("\"op\": \"pr_comment\""));
+    }
+
+    #[tokio::test]
+    async fn test_flush_in_dry_run_keeps_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        queue_op(dir.path(), comment("001-a", 7)).unwrap();
+        let ctx =
+            WorkflowContext::new(dir.path().to_path_buf(), Default::default()).with_dry_run(true);
+
+        let report = flush_outbox(&ctx).await.unwrap();
+        assert_eq!(report.pending, 1);
+        assert_eq!(list_outbox(dir.path()).unwrap().len(), 1);
+    }
+}
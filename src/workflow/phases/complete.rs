@@ -0,0 +1,95 @@
+//! Complete phase: mark an item done once its PR has merged
+
+use std::future::Future;
+
+use crate::domain::ValidationContext;
+use crate::errors::{Result, WreckitError};
+use crate::git;
+use crate::schemas::{Item, WorkflowState};
+use crate::workflow::context::WorkflowContext;
+use crate::workflow::gc::prune_item_branch;
+
+use super::{Phase, PhaseKind};
+
+/// Moves an item from in_pr to done
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompletePhase;
+
+impl Phase for CompletePhase {
+    fn kind(&self) -> PhaseKind {
+        PhaseKind::Complete
+    }
+
+    fn entry_states(&self) -> &'static [WorkflowState] {
+        &[WorkflowState::InPr]
+    }
+
+    fn exit_state(&self) -> WorkflowState {
+        WorkflowState::Done
+    }
+
+    fn preflight(
+        &self,
+        ctx: &WorkflowContext,
+        item: &Item,
+    ) -> impl Future<Output = Result<()>> + Send {
+        // Config-defined states between in_pr and done must be passed first
+        let pending = ctx.state_table().map(|table| {
+            table
+                .next(&item.state_name())
+                .filter(|state| state.is_custom())
+                .map(|state| state.name.clone())
+        });
+        let id = item.id.clone();
+        async move {
+            if let Some(state) = pending? {
+                return Err(WreckitError::StateTransition(format!(
+                    "{} must enter {} before done (run `wreckit advance {}`)",
+                    id, state, id
+                )));
+            }
+            Ok(())
+        }
+    }
+
+    fn build_prompt(&self, _ctx: &WorkflowContext, _item: &Item) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn validation_context(
+        &self,
+        ctx: &WorkflowContext,
+        item: &Item,
+    ) -> impl Future<Output = ValidationContext> + Send {
+        let mut vctx = ctx.validation_context(item);
+        let options = ctx.git_options();
+        let pr_number = item.pr_number;
+        async move {
+            vctx.pr_merged = match pr_number {
+                Some(number) => git::is_pr_merged(number, &options).await,
+                None => false,
+            };
+            vctx
+        }
+    }
+}
+
+/// Delete a done item's branch locally and on origin when
+/// `cleanup.delete_merged_branches` is set. Failures are only logged; the
+/// item is done either way.
+pub async fn cleanup_branch(ctx: &WorkflowContext, item: &Item) {
+    if !ctx.config.cleanup.delete_merged_branches {
+        return;
+    }
+    match prune_item_branch(ctx, item).await {
+        Ok((local, remote)) => {
+            for branch in local {
+                tracing::info!("Deleted branch {}", branch);
+            }
+            for branch in remote {
+                tracing::info!("Deleted branch {} on origin", branch);
+            }
+        }
+        Err(e) => tracing::warn!("Cannot delete the branch of {}: {}", item.id, e),
+    }
+}
@@ -0,0 +1,90 @@
+//! Implement phase: work through PRD stories on the item branch
+
+use std::future::Future;
+
+use crate::domain::{all_stories_done, ValidationContext, ValidationResult};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git;
+use crate::schemas::{Item, WorkflowState};
+use crate::workflow::context::WorkflowContext;
+use crate::workflow::disk::warn_disk_problems;
+use crate::workflow::implement_loop::run_implement_loop;
+
+use super::{transition_to, Phase, PhaseKind};
+
+/// Moves an item from planned through implementing until every story is done
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImplementPhase;
+
+impl Phase for ImplementPhase {
+    fn kind(&self) -> PhaseKind {
+        PhaseKind::Implement
+    }
+
+    fn entry_states(&self) -> &'static [WorkflowState] {
+        &[WorkflowState::Planned, WorkflowState::Implementing]
+    }
+
+    fn exit_state(&self) -> WorkflowState {
+        WorkflowState::Implementing
+    }
+
+    fn preflight(
+        &self,
+        ctx: &WorkflowContext,
+        _item: &Item,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let options = ctx.git_options();
+        async move {
+            if !git::is_git_repo(&options.cwd).await {
+                return Err(WreckitError::GitError(
+                    "Not in a git repository".to_string(),
+                ));
+            }
+            if !options.dry_run && git::get_current_branch(&options).await? == "HEAD" {
+                return Err(WreckitError::GitError("HEAD is detached".to_string()));
+            }
+            if !ctx.dry_run {
+                warn_disk_problems(ctx).await;
+            }
+            Ok(())
+        }
+    }
+
+    fn enter(&self, ctx: &WorkflowContext, item: Item) -> Result<Item> {
+        if item.state != WorkflowState::Planned || ctx.dry_run {
+            return Ok(item);
+        }
+        let vctx = ctx.validation_context(&item);
+        transition_to(item, WorkflowState::Implementing, &vctx)
+    }
+
+    fn is_complete(&self, ctx: &WorkflowContext, item: &Item) -> bool {
+        all_stories_done(fs::read_prd(&ctx.root, &item.id).ok().as_ref())
+    }
+
+    async fn run_agent(&self, ctx: &WorkflowContext, item: Item, _prompt: String) -> Result<Item> {
+        // Each iteration renders its own story-scoped prompt
+        let branch = git::ensure_branch(
+            &ctx.config.base_branch,
+            "",
+            &ctx.branch_name(&item),
+            &ctx.git_options(),
+        )
+        .await?;
+        let item = item.with_branch(Some(branch.branch_name));
+        ctx.save_item(&item)?;
+
+        run_implement_loop(ctx, &item).await?;
+        Ok(item)
+    }
+
+    fn validate(&self, _item: &Item, vctx: &ValidationContext) -> ValidationResult {
+        if all_stories_done(vctx.prd.as_ref()) {
+            ValidationResult::success()
+        } else {
+            ValidationResult::failure("not all stories are done")
+        }
+    }
+}
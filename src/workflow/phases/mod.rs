@@ -0,0 +1,435 @@
+//! Workflow phases
+//!
+//! Each phase implements the [`Phase`] trait, which breaks the work into
+//! preflight, prompt building, running the agent, validating artifacts, and
+//! transitioning state. [`run_phase`] drives any phase through those steps.
+
+mod complete;
+mod implement;
+mod plan;
+mod pr;
+mod research;
+
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+
+use crate::domain::{
+    apply_state_transition, get_next_state, validate_transition, TransitionResult,
+    ValidationContext, ValidationResult,
+};
+use crate::errors::{Result, WreckitError};
+use crate::schemas::{Item, WorkflowState};
+
+use super::budget::BudgetWatch;
+use super::context::{check_agent_result, WorkflowContext};
+use super::credentials::require_agent_credentials;
+use super::evaluation::review_artifact;
+use super::events::WorkflowEvent;
+use super::hooks::run_hook;
+use super::issues::notify_issue;
+use super::network::network_preflight;
+
+pub use complete::{cleanup_branch, CompletePhase};
+pub use implement::ImplementPhase;
+pub use plan::PlanPhase;
+pub use pr::{open_pr, parse_pr_description, PrPhase, PrRequest};
+pub use research::ResearchPhase;
+
+/// Identifies a workflow phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhaseKind {
+    /// Research the codebase and write research.md
+    Research,
+    /// Write plan.md and prd.json
+    Plan,
+    /// Implement user stories on the item branch
+    Implement,
+    /// Push the branch and open a pull request
+    Pr,
+    /// Confirm the PR was merged
+    Complete,
+}
+
+impl PhaseKind {
+    /// All phases in workflow order
+    pub const ALL: [PhaseKind; 5] = [
+        PhaseKind::Research,
+        PhaseKind::Plan,
+        PhaseKind::Implement,
+        PhaseKind::Pr,
+        PhaseKind::Complete,
+    ];
+
+    /// Phase name as used by the CLI and prompt templates
+    pub fn name(self) -> &'static str {
+        match self {
+            PhaseKind::Research => "research",
+            PhaseKind::Plan => "plan",
+            PhaseKind::Implement => "implement",
+            PhaseKind::Pr => "pr",
+            PhaseKind::Complete => "complete",
+        }
+    }
+
+    /// The state an item starts this phase from
+    pub fn entry_state(self) -> WorkflowState {
+        match self {
+            PhaseKind::Research => WorkflowState::Idea,
+            PhaseKind::Plan => WorkflowState::Researched,
+            PhaseKind::Implement | PhaseKind::Pr => WorkflowState::Implementing,
+            PhaseKind::Complete => WorkflowState::InPr,
+        }
+    }
+
+    /// The phase that advances an item out of the given state, if any
+    pub fn for_state(state: WorkflowState) -> Option<PhaseKind> {
+        match state {
+            WorkflowState::Idea => Some(PhaseKind::Research),
+            WorkflowState::Researched => Some(PhaseKind::Plan),
+            WorkflowState::Planned | WorkflowState::Implementing => Some(PhaseKind::Implement),
+            WorkflowState::InPr => Some(PhaseKind::Complete),
+            WorkflowState::Done | WorkflowState::Abandoned => None,
+        }
+    }
+}
+
+impl fmt::Display for PhaseKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for PhaseKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        PhaseKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| format!("Unknown phase: {}", s))
+    }
+}
+
+/// A single step of the workflow.
+///
+/// Default methods cover the common "render a prompt, run the agent once,
+/// check the artifacts" shape; phases override only what differs.
+pub trait Phase: Send + Sync {
+    /// Which phase this is
+    fn kind(&self) -> PhaseKind;
+
+    /// States an item may be in when this phase starts
+    fn entry_states(&self) -> &'static [WorkflowState];
+
+    /// State the item is in after this phase succeeds
+    fn exit_state(&self) -> WorkflowState;
+
+    /// Check preconditions before doing any work
+    fn preflight(
+        &self,
+        _ctx: &WorkflowContext,
+        _item: &Item,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Prepare the item before the agent runs (e.g. enter a working state)
+    fn enter(&self, _ctx: &WorkflowContext, item: Item) -> Result<Item> {
+        Ok(item)
+    }
+
+    /// Whether the phase's artifacts already exist, so the agent can be skipped
+    fn is_complete(&self, _ctx: &WorkflowContext, _item: &Item) -> bool {
+        false
+    }
+
+    /// Build the agent prompt, or None if this phase does not run the agent
+    fn build_prompt(&self, ctx: &WorkflowContext, item: &Item) -> Result<Option<String>> {
+        let name = self.kind().name();
+        Ok(Some(ctx.render_prompt(
+            name,
+            &item.id,
+            ctx.prompt_variables(item),
+        )?))
+    }
+
+    /// Run the agent with the prompt and return the updated item
+    fn run_agent(
+        &self,
+        ctx: &WorkflowContext,
+        item: Item,
+        prompt: String,
+    ) -> impl Future<Output = Result<Item>> + Send {
+        async move {
+            let result = ctx.run_agent(&item.id, self.kind(), None, prompt).await?;
+            check_agent_result(&result)?;
+            Ok(item)
+        }
+    }
+
+    /// Gather the facts used to validate artifacts and transitions
+    fn validation_context(
+        &self,
+        ctx: &WorkflowContext,
+        item: &Item,
+    ) -> impl Future<Output = ValidationContext> + Send {
+        let vctx = ctx.validation_context(item);
+        async move { vctx }
+    }
+
+    /// Check that the artifacts required to leave the current state exist
+    fn validate(&self, item: &Item, vctx: &ValidationContext) -> ValidationResult {
+        if item.state == self.exit_state() {
+            return ValidationResult::success();
+        }
+        match get_next_state(item.state) {
+            Some(next) => validate_transition(item.state, next, vctx),
+            None => ValidationResult::failure(format!("{} is a terminal state", item.state)),
+        }
+    }
+
+    /// Move the item to the exit state
+    fn transition(&self, item: Item, vctx: &ValidationContext) -> Result<Item> {
+        transition_to(item, self.exit_state(), vctx)
+    }
+}
+
+/// Apply validated transitions until the item reaches the target state
+pub fn transition_to(
+    mut item: Item,
+    target: WorkflowState,
+    vctx: &ValidationContext,
+) -> Result<Item> {
+    while item.state != target {
+        let from = item.state;
+        item = match apply_state_transition(&item, vctx) {
+            TransitionResult::Success { next_item } => next_item,
+            TransitionResult::Error { error } => {
+                return Err(WreckitError::StateTransition(format!(
+                    "{} → {}: {}",
+                    from, target, error
+                )))
+            }
+        };
+    }
+    Ok(item)
+}
+
+/// Drive an item through a single phase.
+///
+/// Runs preflight, snapshots the item's metadata, runs the agent (skipped
+/// when artifacts exist unless forced), validates artifacts, and applies the
+/// state transition, then saves the item. Publishes `PhaseStarted` and
+/// `PhaseFinished` around the run.
+/// In dry-run mode nothing is written and the transition is only reported.
+pub async fn run_phase<P: Phase>(phase: &P, ctx: &WorkflowContext, item: Item) -> Result<Item> {
+    let kind = phase.kind();
+    if !phase.entry_states().contains(&item.state) {
+        return Err(WreckitError::StateTransition(format!(
+            "cannot run {} phase for {} in state {}",
+            kind, item.id, item.state
+        )));
+    }
+
+    let id = item.id.clone();
+    let event = WorkflowEvent::PhaseStarted {
+        item_id: id.clone(),
+        phase: kind.name().to_string(),
+    };
+    run_hook(ctx, &event).await?;
+    ctx.publish(event);
+    let started = std::time::Instant::now();
+    let result = drive_phase(phase, ctx, item).await;
+    let event = WorkflowEvent::PhaseFinished {
+        item_id: id,
+        phase: kind.name().to_string(),
+        success: result.is_ok(),
+        seconds: started.elapsed().as_secs(),
+    };
+    if let Err(e) = run_hook(ctx, &event).await {
+        tracing::warn!("{}", e);
+    }
+    ctx.publish(event);
+    result
+}
+
+async fn drive_phase<P: Phase>(phase: &P, ctx: &WorkflowContext, item: Item) -> Result<Item> {
+    let kind = phase.kind();
+    tracing::info!("Running {} phase for {}", kind, item.id);
+    let _budget = BudgetWatch::start(ctx, kind, &item.id);
+
+    let offline_ctx;
+    let ctx = if network_preflight(ctx, kind).await? {
+        offline_ctx = ctx.clone().with_offline(true);
+        &offline_ctx
+    } else {
+        ctx
+    };
+    phase.preflight(ctx, &item).await?;
+    ctx.backup_item(&item.id)?;
+
+    let entered_from = item.state;
+    let mut item = phase.enter(ctx, item)?;
+    if item.state != entered_from {
+        ctx.save_item(&item)?;
+    }
+
+    if ctx.force || !phase.is_complete(ctx, &item) {
+        if let Some(prompt) = phase.build_prompt(ctx, &item)? {
+            require_agent_credentials(ctx, &item)?;
+            item = phase.run_agent(ctx, item, prompt).await?;
+            item = review_artifact(phase, ctx, item).await?;
+        }
+    } else {
+        tracing::info!(
+            "{} artifacts already exist for {}; skipping agent",
+            kind,
+            item.id
+        );
+    }
+
+    if ctx.dry_run {
+        tracing::info!(
+            "[DRY RUN] Would transition {} from {} to {}",
+            item.id,
+            item.state,
+            phase.exit_state()
+        );
+        return Ok(item);
+    }
+
+    let vctx = phase.validation_context(ctx, &item).await;
+    let validation = phase.validate(&item, &vctx);
+    if !validation.valid {
+        return Err(WreckitError::StateTransition(format!(
+            "{} phase did not produce the required artifacts: {}",
+            kind,
+            validation.reason.unwrap_or_default()
+        )));
+    }
+
+    let item = phase.transition(item, &vctx)?.with_error(None);
+    ctx.save_item(&item)?;
+    Ok(item)
+}
+
+/// Run the phase identified by `kind`
+pub async fn run_phase_kind(kind: PhaseKind, ctx: &WorkflowContext, item: Item) -> Result<Item> {
+    match kind {
+        PhaseKind::Research => run_phase(&ResearchPhase, ctx, item).await,
+        PhaseKind::Plan => run_phase(&PlanPhase, ctx, item).await,
+        PhaseKind::Implement => run_phase(&ImplementPhase, ctx, item).await,
+        PhaseKind::Pr => {
+            let before = item.state;
+            let item = run_phase(&PrPhase, ctx, item).await?;
+            if item.state != before {
+                notify_issue(ctx, &item).await;
+            }
+            Ok(item)
+        }
+        PhaseKind::Complete => {
+            let before = item.state;
+            let item = run_phase(&CompletePhase, ctx, item).await?;
+            if item.state != before {
+                notify_issue(ctx, &item).await;
+            }
+            cleanup_branch(ctx, &item).await;
+            Ok(item)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs;
+    use crate::schemas::Config;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, WorkflowContext, Item) {
+        let temp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.agent.command = "cat".to_string();
+        config.agent.args = vec![];
+        config.agent.completion_signal = String::new();
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        let item = Item::new(
+            "001-test".to_string(),
+            "Test".to_string(),
+            "Overview".to_string(),
+        );
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+        (temp, ctx, item)
+    }
+
+    #[test]
+    fn test_phase_kind_for_state() {
+        assert_eq!(
+            PhaseKind::for_state(WorkflowState::Idea),
+            Some(PhaseKind::Research)
+        );
+        assert_eq!(
+            PhaseKind::for_state(WorkflowState::Implementing),
+            Some(PhaseKind::Implement)
+        );
+        assert_eq!(
+            PhaseKind::for_state(WorkflowState::InPr),
+            Some(PhaseKind::Complete)
+        );
+        assert_eq!(PhaseKind::for_state(WorkflowState::Done), None);
+    }
+
+    #[test]
+    fn test_phase_kind_from_str() {
+        assert_eq!("plan".parse::<PhaseKind>().unwrap(), PhaseKind::Plan);
+        assert_eq!(PhaseKind::Pr.to_string(), "pr");
+        assert!("deploy".parse::<PhaseKind>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_phase_rejects_wrong_state() {
+        let (_temp, ctx, item) = setup();
+        let item = item.with_state(WorkflowState::Planned);
+        let err = run_phase(&ResearchPhase, &ctx, item).await.unwrap_err();
+        assert!(matches!(err, WreckitError::StateTransition(_)));
+    }
+
+    #[tokio::test]
+    async fn test_run_phase_skips_agent_when_artifacts_exist() {
+        let (temp, ctx, item) = setup();
+        std::fs::write(fs::get_research_path(temp.path(), &item.id), "# Research").unwrap();
+
+        let item = run_phase(&ResearchPhase, &ctx, item).await.unwrap();
+        assert_eq!(item.state, WorkflowState::Researched);
+        assert_eq!(
+            fs::read_item(temp.path(), &item.id).unwrap().state,
+            WorkflowState::Researched
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_phase_fails_without_artifacts() {
+        let (temp, ctx, item) = setup();
+
+        let err = run_phase(&ResearchPhase, &ctx, item).await.unwrap_err();
+        assert!(err.to_string().contains("research"));
+        assert_eq!(
+            fs::read_item(temp.path(), "001-test").unwrap().state,
+            WorkflowState::Idea
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_phase_dry_run_does_not_transition() {
+        let (temp, ctx, item) = setup();
+        let ctx = ctx.with_dry_run(true);
+
+        let item = run_phase(&ResearchPhase, &ctx, item).await.unwrap();
+        assert_eq!(item.state, WorkflowState::Idea);
+        assert_eq!(
+            fs::read_item(temp.path(), "001-test").unwrap().state,
+            WorkflowState::Idea
+        );
+    }
+}
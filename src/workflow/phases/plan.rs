@@ -0,0 +1,191 @@
+//! Plan phase: write plan.md and a PRD of user stories
+//!
+//! With `plan.consensus` on (optionally only for items at or above
+//! `plan.min_priority`), two agents plan independently, each with its own
+//! model from `plan.models`. Their plans are kept as `plan.a.md` /
+//! `prd.a.json` and `plan.b.md` / `prd.b.json`, and an arbiter pass merges
+//! them into the plan.md and prd.json the rest of the workflow uses.
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::{Item, WorkflowState};
+use crate::workflow::context::{check_agent_result, AgentRole, WorkflowContext};
+use crate::workflow::context_pack::{prepare_context_pack, read_context_pack};
+use crate::workflow::digest::record_key_decisions;
+
+use super::{Phase, PhaseKind};
+
+/// Moves an item from researched to planned
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlanPhase;
+
+impl Phase for PlanPhase {
+    fn kind(&self) -> PhaseKind {
+        PhaseKind::Plan
+    }
+
+    fn entry_states(&self) -> &'static [WorkflowState] {
+        &[WorkflowState::Researched]
+    }
+
+    fn exit_state(&self) -> WorkflowState {
+        WorkflowState::Planned
+    }
+
+    fn is_complete(&self, ctx: &WorkflowContext, item: &Item) -> bool {
+        fs::get_plan_path(&ctx.root, &item.id).exists() && fs::read_prd(&ctx.root, &item.id).is_ok()
+    }
+
+    async fn preflight(&self, ctx: &WorkflowContext, _item: &Item) -> Result<()> {
+        prepare_context_pack(ctx).await;
+        Ok(())
+    }
+
+    fn build_prompt(&self, ctx: &WorkflowContext, item: &Item) -> Result<Option<String>> {
+        let mut variables = ctx.prompt_variables(item);
+        variables.repo_context = read_context_pack(ctx);
+        Ok(Some(ctx.render_prompt(
+            self.kind().name(),
+            &item.id,
+            variables,
+        )?))
+    }
+
+    async fn run_agent(&self, ctx: &WorkflowContext, item: Item, prompt: String) -> Result<Item> {
+        let output = if ctx.config.plan.uses_consensus(item.priority_hint) {
+            plan_by_consensus(ctx, &item, prompt).await?
+        } else {
+            let result = ctx.run_agent(&item.id, self.kind(), None, prompt).await?;
+            check_agent_result(&result)?;
+            result.output
+        };
+        let artifact = fs::get_plan_path(&ctx.root, &item.id);
+        record_key_decisions(ctx, &item.id, &artifact, &output);
+        Ok(item)
+    }
+}
+
+/// Label, plan file, and PRD file of each consensus candidate
+const CANDIDATES: [(&str, &str, &str); 2] = [
+    ("a", "plan.a.md", "prd.a.json"),
+    ("b", "plan.b.md", "prd.b.json"),
+];
+
+/// Plan with both candidate agents, then have the arbiter merge their plans.
+/// Returns the arbiter's output.
+///
+/// # Errors
+/// * `AgentError` - If an agent fails or a candidate writes no plan or PRD
+/// * `Io` - If the candidate files cannot be moved aside
+async fn plan_by_consensus(ctx: &WorkflowContext, item: &Item, prompt: String) -> Result<String> {
+    let dir = ctx.item_dir(&item.id);
+    let plan_path = fs::get_plan_path(&ctx.root, &item.id);
+    let prd_path = fs::get_prd_path(&ctx.root, &item.id);
+
+    let mut candidates = Vec::new();
+    for (i, (label, plan_file, prd_file)) in CANDIDATES.into_iter().enumerate() {
+        let model = ctx.config.plan.models.get(i).map(String::as_str);
+        tracing::info!(
+            "Consensus plan {} for {} ({})",
+            label,
+            item.id,
+            model.unwrap_or("plan agent")
+        );
+        if !ctx.dry_run {
+            for path in [&plan_path, &prd_path] {
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+        let role = AgentRole { label, model };
+        let result = ctx
+            .run_agent_as(&item.id, PhaseKind::Plan, role, prompt.clone())
+            .await?;
+        check_agent_result(&result)?;
+        if ctx.dry_run {
+            continue;
+        }
+
+        let (Ok(plan), Ok(prd)) = (
+            std::fs::read_to_string(&plan_path),
+            std::fs::read_to_string(&prd_path),
+        ) else {
+            return Err(WreckitError::AgentError(format!(
+                "consensus plan {} did not write plan.md and prd.json",
+                label
+            )));
+        };
+        fs::rename_file(&plan_path, &dir.join(plan_file))?;
+        fs::rename_file(&prd_path, &dir.join(prd_file))?;
+        candidates.push(format!(
+            "### Plan {}\n\n{}\n\n#### PRD\n\n```json\n{}\n```",
+            label.to_uppercase(),
+            plan.trim(),
+            prd.trim()
+        ));
+    }
+
+    let mut variables = ctx.prompt_variables(item);
+    variables.repo_context = read_context_pack(ctx);
+    variables.candidate_plans = Some(candidates.join("\n\n"));
+    let prompt = ctx.render_prompt("plan_consensus", &item.id, variables)?;
+    let role = AgentRole {
+        label: "arbiter",
+        model: ctx.config.plan.arbiter_model.as_deref(),
+    };
+    tracing::info!("Merging the consensus plans for {}", item.id);
+    let result = ctx
+        .run_agent_as(&item.id, PhaseKind::Plan, role, prompt)
+        .await?;
+    check_agent_result(&result)?;
+    Ok(result.output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{MockFixture, DEFAULT_FIXTURES_DIR};
+    use crate::schemas::{AgentMode, Config, PriorityHint};
+    use crate::workflow::phases::run_phase;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_consensus_keeps_candidates_and_merges() {
+        let temp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.agent.mode = AgentMode::Mock;
+        config.context_pack.enabled = false;
+        config.plan.consensus = true;
+        config.plan.min_priority = Some(PriorityHint::High);
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+
+        let prd = r#"{"schema_version":1,"id":"{{id}}","branch_name":"wreckit/{{id}}","user_stories":[{"id":"US-001","title":"Add feature","acceptance_criteria":[],"priority":1,"status":"pending","notes":""}]}"#;
+        let fixture = MockFixture {
+            files: [("plan.md", "# Plan"), ("prd.json", prd)]
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            complete: true,
+            ..Default::default()
+        };
+        fs::write_json(
+            &temp.path().join(DEFAULT_FIXTURES_DIR).join("plan.json"),
+            &fixture,
+        )
+        .unwrap();
+
+        let mut item = Item::new("001-test".to_string(), "Test".to_string(), String::new())
+            .with_state(WorkflowState::Researched);
+        item.priority_hint = Some(PriorityHint::Critical);
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+
+        let item = run_phase(&PlanPhase, &ctx, item).await.unwrap();
+        assert_eq!(item.state, WorkflowState::Planned);
+        let dir = ctx.item_dir(&item.id);
+        for file in ["plan.a.md", "prd.a.json", "plan.b.md", "prd.b.json"] {
+            assert!(dir.join(file).exists(), "{} is missing", file);
+        }
+        assert!(fs::read_prd(temp.path(), &item.id).is_ok());
+    }
+}
@@ -0,0 +1,411 @@
+//! PR phase: push the branch and open (or update) a pull request
+//!
+//! In direct merge mode the branch is merged into the base branch instead
+//! and the item goes straight to done.
+
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{all_stories_done, ValidationContext};
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git::{self, PrResult};
+use crate::schemas::{Item, MergeMode, WorkflowState};
+use crate::workflow::attachments::upload_attachments;
+use crate::workflow::changelog::write_changelog_fragment;
+use crate::workflow::context::{check_agent_result, WorkflowContext};
+use crate::workflow::conventions::{conventional_title, infer_change_type, labels_for};
+use crate::workflow::credentials::check_github;
+use crate::workflow::events::WorkflowEvent;
+use crate::workflow::gates::enforce_gates;
+use crate::workflow::issues::with_closing_keyword;
+use crate::workflow::outbox::{has_queued_pr, is_offline_error, queue_op, OutboxOp};
+use crate::workflow::policies::enforce_diff_policies;
+use crate::workflow::pr_size::enforce_pr_size;
+use crate::workflow::security::enforce_security_scans;
+
+use super::{transition_to, Phase, PhaseKind};
+
+const PR_JSON_START: &str = "PR_JSON_START";
+const PR_JSON_END: &str = "PR_JSON_END";
+
+/// Moves an item from implementing to in_pr (or done in direct mode)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrPhase;
+
+impl Phase for PrPhase {
+    fn kind(&self) -> PhaseKind {
+        PhaseKind::Pr
+    }
+
+    fn entry_states(&self) -> &'static [WorkflowState] {
+        &[WorkflowState::Implementing]
+    }
+
+    fn exit_state(&self) -> WorkflowState {
+        WorkflowState::InPr
+    }
+
+    fn preflight(
+        &self,
+        ctx: &WorkflowContext,
+        item: &Item,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let done = all_stories_done(fs::read_prd(&ctx.root, &item.id).ok().as_ref());
+        async move {
+            if !done {
+                return Err(WreckitError::StateTransition(
+                    "cannot open a PR before all stories are done".to_string(),
+                ));
+            }
+            // Enterprise hosts are checked up front so a bad host or token
+            // fails before the agent runs rather than at `gh pr create`
+            if let (MergeMode::Pr, Some(host)) = (
+                ctx.config.merge_mode,
+                ctx.config.forge.github.host.as_deref(),
+            ) {
+                let check = check_github(ctx).await;
+                if !check.passed {
+                    return Err(WreckitError::GitError(format!(
+                        "GitHub host {} is unreachable or not authenticated: {}",
+                        host, check.detail
+                    )));
+                }
+            }
+            enforce_gates(ctx, item).await?;
+            enforce_security_scans(ctx, item).await?;
+            enforce_diff_policies(ctx, item).await?;
+            enforce_pr_size(ctx, item).await
+        }
+    }
+
+    fn is_complete(&self, _ctx: &WorkflowContext, item: &Item) -> bool {
+        item.pr_url.is_some()
+    }
+
+    async fn run_agent(&self, ctx: &WorkflowContext, item: Item, prompt: String) -> Result<Item> {
+        let options = ctx.git_options();
+        let branch = ctx.branch_name(&item);
+
+        let result = ctx.run_agent(&item.id, PhaseKind::Pr, None, prompt).await?;
+        check_agent_result(&result)?;
+
+        write_changelog_fragment(ctx, &item)?;
+        if git::has_uncommitted_changes(&options).await {
+            git::commit_all(&format!("wreckit({}): finalize", item.id), &options).await?;
+        }
+
+        match ctx.config.merge_mode {
+            MergeMode::Pr => {
+                let (mut title, mut body) = parse_pr_description(&result.output)
+                    .unwrap_or_else(|| (item.title.clone(), item.overview.clone()));
+                match upload_attachments(ctx, &item).await {
+                    Ok(Some(section)) => body = format!("{}\n\n{}", body.trim_end(), section),
+                    Ok(None) => {}
+                    // The PR is still worth opening without its screenshots
+                    Err(e) => tracing::warn!("Failed to attach files for {}: {}", item.id, e),
+                }
+                if let Some(number) = item.issue_number {
+                    body = with_closing_keyword(&body, number);
+                }
+                let conventions = &ctx.config.pr_conventions;
+                let change_type = conventions.enabled.then(|| {
+                    let prd = fs::read_prd(&ctx.root, &item.id).ok();
+                    infer_change_type(conventions, &item, prd.as_ref())
+                });
+                if let Some(ref change_type) = change_type {
+                    title = conventional_title(change_type, &title);
+                }
+                let auto_merge = ctx.config.pr.auto_merge.method().map(|method| {
+                    let prd = fs::read_prd(&ctx.root, &item.id).ok();
+                    (
+                        method.to_string(),
+                        git::build_squash_message(&item, prd.as_ref()),
+                    )
+                });
+                let request = PrRequest {
+                    item_id: item.id.clone(),
+                    base: ctx.config.base_branch.clone(),
+                    branch,
+                    title,
+                    body,
+                    labels: change_type
+                        .as_ref()
+                        .map(|change_type| labels_for(conventions, change_type))
+                        .unwrap_or_default(),
+                    auto_merge,
+                };
+                if ctx.offline {
+                    queue_op(&ctx.root, OutboxOp::OpenPr(request))?;
+                    tracing::warn!("Offline; queued the PR for {} in the outbox", item.id);
+                    return Ok(item);
+                }
+                match open_pr(ctx, &request).await {
+                    Ok(pr) => Ok(item.with_pr(Some(pr.url), Some(pr.number))),
+                    Err(e) if is_offline_error(&e) => {
+                        queue_op(&ctx.root, OutboxOp::OpenPr(request))?;
+                        tracing::warn!(
+                            "Offline; queued the PR for {} in the outbox ({})",
+                            item.id,
+                            e
+                        );
+                        Ok(item)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            MergeMode::Direct => {
+                let base = ctx.config.base_branch.as_str();
+                let prd = fs::read_prd(&ctx.root, &item.id).ok();
+                let message = git::build_squash_message(&item, prd.as_ref());
+                let squash = ctx.config.pr.squash_direct_merge;
+                if !merge_direct(base, &branch, &message, squash, &options).await? {
+                    tracing::info!("{} has no changes left to merge into {}", item.id, base);
+                }
+                git::run_git_command(&["push", "origin", base], &options).await?;
+                Ok(item)
+            }
+        }
+    }
+
+    fn validation_context(
+        &self,
+        ctx: &WorkflowContext,
+        item: &Item,
+    ) -> impl Future<Output = ValidationContext> + Send {
+        let mut vctx = ctx.validation_context(item);
+        if ctx.config.merge_mode == MergeMode::Direct {
+            // A direct merge delivers the change without a PR
+            vctx.has_pr = true;
+            vctx.pr_merged = true;
+        } else if has_queued_pr(&ctx.root, &item.id) {
+            // The outbox opens it once the forge is reachable again
+            vctx.has_pr = true;
+        }
+        async move { vctx }
+    }
+
+    fn transition(&self, item: Item, vctx: &ValidationContext) -> Result<Item> {
+        let target = if vctx.pr_merged {
+            WorkflowState::Done
+        } else {
+            WorkflowState::InPr
+        };
+        transition_to(item, target, vctx)
+    }
+}
+
+/// A PR to open for an item, with what to do once it exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrRequest {
+    /// Item the PR is for
+    pub item_id: String,
+
+    /// Branch to merge into
+    pub base: String,
+
+    /// Item branch, pushed before the PR is opened
+    pub branch: String,
+
+    /// PR title
+    pub title: String,
+
+    /// PR body (markdown)
+    pub body: String,
+
+    /// Labels to add (failures are only logged)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+
+    /// Auto-merge method and squash message (failures are only logged)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_merge: Option<(String, String)>,
+}
+
+/// Push the branch and open (or find) the PR, then label it and turn on
+/// auto-merge. A draft opened during implementation (`pr.draft_early`) gets
+/// the final title and body and is marked ready for review.
+///
+/// # Errors
+/// * `GitError` - If the push or PR creation fails
+pub async fn open_pr(ctx: &WorkflowContext, request: &PrRequest) -> Result<PrResult> {
+    let options = ctx.git_options();
+    git::push_branch(&request.branch, &options).await?;
+    let pr = git::create_or_update_pr(
+        &request.base,
+        &request.branch,
+        &request.title,
+        &request.body,
+        &options,
+    )
+    .await?;
+    tracing::info!("PR for {}: {}", request.item_id, pr.url);
+    if !pr.created && ctx.config.pr.draft_early {
+        // The draft's body is a progress checklist; replace it and hand the
+        // PR over for review
+        git::edit_pr(pr.number, Some(&request.title), &request.body, &options).await?;
+        git::mark_pr_ready(pr.number, &options).await?;
+    }
+    if pr.created {
+        ctx.publish(WorkflowEvent::PrOpened {
+            item_id: request.item_id.clone(),
+            number: pr.number,
+            url: pr.url.clone(),
+        });
+    }
+    if let Err(e) = git::add_pr_labels(pr.number, &request.labels, &options).await {
+        // Missing labels should not fail an otherwise successful PR
+        tracing::warn!("Failed to label PR for {}: {}", request.item_id, e);
+    }
+    if let Some((ref method, ref message)) = request.auto_merge {
+        let enabled = git::enable_auto_merge(pr.number, method, Some(message), &options).await;
+        if let Err(e) = enabled {
+            // The PR still waits for a maintainer to merge it
+            tracing::warn!("Failed to enable auto-merge for {}: {}", request.item_id, e);
+        }
+    }
+    Ok(pr)
+}
+
+/// Extract the PR title and body from agent output.
+///
+/// Expects a JSON object `{"title": ..., "body": ...}` between
+/// `PR_JSON_START` and `PR_JSON_END` markers.
+pub fn parse_pr_description(output: &str) -> Option<(String, String)> {
+    let start = output.rfind(PR_JSON_START)? + PR_JSON_START.len();
+    let end = start + output[start..].find(PR_JSON_END)?;
+    let value: serde_json::Value = serde_json::from_str(output[start..end].trim()).ok()?;
+    let title = value["title"].as_str()?.trim().to_string();
+    if title.is_empty() {
+        return None;
+    }
+    let body = value["body"].as_str().unwrap_or_default().to_string();
+    Some((title, body))
+}
+
+/// Merge `branch` into `base` with `message`, as a merge commit or, with
+/// `squash`, as a single squashed commit. Returns false when a squash leaves
+/// nothing to commit (the branch has no net diff against `base`).
+async fn merge_direct(
+    base: &str,
+    branch: &str,
+    message: &str,
+    squash: bool,
+    options: &git::GitOptions,
+) -> Result<bool> {
+    git::run_git_command(&["checkout", base], options).await?;
+    if !squash {
+        git::run_git_command(&["merge", "--no-ff", "-m", message, branch], options).await?;
+        return Ok(true);
+    }
+    git::run_git_command(&["merge", "--squash", branch], options).await?;
+    // `diff --quiet` fails exactly when something is staged
+    if git::run_git_command(&["diff", "--cached", "--quiet"], options)
+        .await
+        .is_ok()
+    {
+        return Ok(false);
+    }
+    git::run_git_command(&["commit", "-m", message], options).await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?}", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// A repo whose `wreckit/001-a` branch writes `contents` to a file that
+    /// main already has as "base\n"
+    fn setup(contents: &str) -> (TempDir, git::GitOptions) {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        git(dir, &["init", "-q", "-b", "main"]);
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("a.txt"), "base\n").unwrap();
+        git(dir, &["add", "-A"]);
+        git(dir, &["commit", "-q", "-m", "init"]);
+        git(dir, &["checkout", "-q", "-b", "wreckit/001-a"]);
+        std::fs::write(dir.join("a.txt"), "wip\n").unwrap();
+        git(dir, &["commit", "-q", "-am", "wip"]);
+        std::fs::write(dir.join("a.txt"), contents).unwrap();
+        git(dir, &["commit", "-q", "--allow-empty", "-am", "more"]);
+        let options = git::GitOptions {
+            cwd: dir.to_path_buf(),
+            dry_run: false,
+            github: None,
+            gh_host: None,
+            cancel: None,
+        };
+        (temp, options)
+    }
+
+    #[tokio::test]
+    async fn test_merge_direct() {
+        let (temp, options) = setup("done\n");
+        let message = "A\n\nWreckit-Item: 001-a";
+        assert!(
+            merge_direct("main", "wreckit/001-a", message, false, &options)
+                .await
+                .unwrap()
+        );
+        let parents = git(temp.path(), &["log", "-1", "--format=%P", "main"]);
+        assert_eq!(parents.split_whitespace().count(), 2);
+        assert_eq!(git(temp.path(), &["log", "-1", "--format=%s", "main"]), "A");
+    }
+
+    #[tokio::test]
+    async fn test_merge_direct_squash() {
+        let (temp, options) = setup("done\n");
+        assert!(merge_direct("main", "wreckit/001-a", "A", true, &options)
+            .await
+            .unwrap());
+        let parents = git(temp.path(), &["log", "-1", "--format=%P", "main"]);
+        assert_eq!(parents.split_whitespace().count(), 1);
+        assert_eq!(git(temp.path(), &["log", "-1", "--format=%s", "main"]), "A");
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("a.txt")).unwrap(),
+            "done\n"
+        );
+
+        // A branch that ends where it started has nothing to squash
+        let (temp, options) = setup("base\n");
+        let before = git(temp.path(), &["rev-parse", "main"]);
+        assert!(!merge_direct("main", "wreckit/001-a", "A", true, &options)
+            .await
+            .unwrap());
+        assert_eq!(git(temp.path(), &["rev-parse", "main"]), before);
+    }
+
+    #[test]
+    fn test_parse_pr_description() {
+        let output = r#"Done.
+PR_JSON_START
+{"title": "Add login", "body": "Implements login"}
+PR_JSON_END
+<promise>COMPLETE</promise>"#;
+        let (title, body) = parse_pr_description(output).unwrap();
+        assert_eq!(title, "Add login");
+        assert_eq!(body, "Implements login");
+    }
+
+    #[test]
+    fn test_parse_pr_description_missing_or_invalid() {
+        assert!(parse_pr_description("no markers").is_none());
+        assert!(parse_pr_description("PR_JSON_START not json PR_JSON_END").is_none());
+        assert!(parse_pr_description(r#"PR_JSON_START {"title": ""} PR_JSON_END"#).is_none());
+    }
+}
@@ -0,0 +1,55 @@
+//! Research phase: explore the codebase and write research.md
+
+use crate::errors::Result;
+use crate::fs;
+use crate::schemas::{Item, WorkflowState};
+use crate::workflow::context::{check_agent_result, WorkflowContext};
+use crate::workflow::context_pack::{prepare_context_pack, read_context_pack};
+use crate::workflow::digest::record_key_decisions;
+
+use super::{Phase, PhaseKind};
+
+/// Moves an item from idea to researched
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResearchPhase;
+
+impl Phase for ResearchPhase {
+    fn kind(&self) -> PhaseKind {
+        PhaseKind::Research
+    }
+
+    fn entry_states(&self) -> &'static [WorkflowState] {
+        &[WorkflowState::Idea]
+    }
+
+    fn exit_state(&self) -> WorkflowState {
+        WorkflowState::Researched
+    }
+
+    fn is_complete(&self, ctx: &WorkflowContext, item: &Item) -> bool {
+        fs::get_research_path(&ctx.root, &item.id).exists()
+    }
+
+    async fn preflight(&self, ctx: &WorkflowContext, _item: &Item) -> Result<()> {
+        prepare_context_pack(ctx).await;
+        Ok(())
+    }
+
+    fn build_prompt(&self, ctx: &WorkflowContext, item: &Item) -> Result<Option<String>> {
+        let mut variables = ctx.prompt_variables(item);
+        variables.repo_context = read_context_pack(ctx);
+        Ok(Some(ctx.render_prompt(
+            self.kind().name(),
+            &item.id,
+            variables,
+        )?))
+    }
+
+    async fn run_agent(&self, ctx: &WorkflowContext, item: Item, prompt: String) -> Result<Item> {
+        let result = ctx.run_agent(&item.id, self.kind(), None, prompt).await?;
+        check_agent_result(&result)?;
+        let artifact = fs::get_research_path(&ctx.root, &item.id);
+        record_key_decisions(ctx, &item.id, &artifact, &result.output);
+        Ok(item)
+    }
+}
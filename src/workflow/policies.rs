@@ -0,0 +1,283 @@
+//! Diff content policies
+//!
+//! `guardrails.policies` are regex rules (no `dbg!(`, no `console.log`, no
+//! `TODO`) checked against every line the item branch adds relative to the
+//! base branch. Before a PR is opened, violations are reported per file and
+//! line; the agent gets one cleanup run to fix them, and the PR is blocked
+//! if any remain.
+
+use regex::Regex;
+
+use crate::errors::{Result, WreckitError};
+use crate::git::{self, AddedLine};
+use crate::schemas::{DiffPolicy, Item};
+
+use super::context::{check_agent_result, WorkflowContext};
+use super::guardrails::path_matches;
+use super::implement_loop::append_progress;
+use super::phases::PhaseKind;
+
+/// Story key used for the cleanup agent run (fixtures: `pr-cleanup.json`)
+const CLEANUP_STORY: &str = "cleanup";
+
+/// An added line that breaks a policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyViolation {
+    /// Name of the policy
+    pub policy: String,
+
+    /// File path relative to the repository root
+    pub path: String,
+
+    /// Line number in the branch version of the file
+    pub line: usize,
+
+    /// The offending line, trimmed
+    pub text: String,
+}
+
+/// Check added lines against the policies.
+///
+/// # Errors
+/// * `ConfigError` - If a policy's pattern is not a valid regex
+pub fn find_violations(
+    policies: &[DiffPolicy],
+    added: &[AddedLine],
+) -> Result<Vec<PolicyViolation>> {
+    let mut violations = Vec::new();
+    for policy in policies {
+        let regex = Regex::new(&policy.pattern).map_err(|e| {
+            WreckitError::ConfigError(format!("invalid policy pattern for {}: {}", policy.name, e))
+        })?;
+        let applies = |path: &str| {
+            policy.paths.is_empty() || policy.paths.iter().any(|glob| path_matches(glob, path))
+        };
+        violations.extend(
+            added
+                .iter()
+                .filter(|line| applies(&line.path) && regex.is_match(&line.text))
+                .map(|line| PolicyViolation {
+                    policy: policy.name.clone(),
+                    path: line.path.clone(),
+                    line: line.line,
+                    text: line.text.trim().to_string(),
+                }),
+        );
+    }
+    violations.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    Ok(violations)
+}
+
+/// Describe violations grouped by file, with each policy's guidance
+pub fn violation_report(policies: &[DiffPolicy], violations: &[PolicyViolation]) -> String {
+    let mut sections: Vec<String> = Vec::new();
+    let mut current: Option<&str> = None;
+    for violation in violations {
+        if current != Some(violation.path.as_str()) {
+            current = Some(&violation.path);
+            sections.push(format!("### {}", violation.path));
+        }
+        sections.push(format!(
+            "- line {} ({}): `{}`",
+            violation.line, violation.policy, violation.text
+        ));
+    }
+    let guidance: Vec<String> = policies
+        .iter()
+        .filter(|policy| violations.iter().any(|v| v.policy == policy.name))
+        .filter_map(|policy| {
+            policy
+                .message
+                .as_ref()
+                .map(|message| format!("- {}: {}", policy.name, message))
+        })
+        .collect();
+    if !guidance.is_empty() {
+        sections.push(format!("\n### Guidance\n{}", guidance.join("\n")));
+    }
+    sections.join("\n")
+}
+
+/// Ask the agent to fix the violations and commit the result
+async fn clean_up(ctx: &WorkflowContext, item: &Item, report: &str) -> Result<()> {
+    let options = ctx.git_options();
+    let mut variables = ctx.prompt_variables(item);
+    variables.policy_violations = Some(report.to_string());
+    let prompt = ctx.render_prompt("cleanup", &item.id, variables)?;
+
+    let result = ctx
+        .run_agent(&item.id, PhaseKind::Pr, Some(CLEANUP_STORY), prompt)
+        .await?;
+    check_agent_result(&result)?;
+
+    if git::has_uncommitted_changes(&options).await {
+        git::commit_all(
+            &format!("wreckit({}): clean up policy violations", item.id),
+            &options,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Block the PR while lines added on the branch break a diff policy.
+///
+/// No-op in dry-run mode or when no policy is configured. Otherwise the
+/// agent gets one cleanup run and the branch is checked again.
+///
+/// # Errors
+/// * `StateTransition` - If violations remain after cleanup
+/// * `ConfigError` - If a policy's pattern is not a valid regex
+/// * `GitError` - If the diff cannot be read or the cleanup committed
+/// * `AgentError` - If the cleanup agent run fails
+pub async fn enforce_diff_policies(ctx: &WorkflowContext, item: &Item) -> Result<()> {
+    let policies = &ctx.config.guardrails.policies;
+    if ctx.dry_run || policies.is_empty() {
+        return Ok(());
+    }
+
+    let options = ctx.git_options();
+    let base = &ctx.config.base_branch;
+    let violations = find_violations(policies, &git::added_lines(base, &options).await?)?;
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    let report = violation_report(policies, &violations);
+    tracing::warn!(
+        "{}: {} diff policy violation(s); asking the agent to clean up",
+        item.id,
+        violations.len()
+    );
+    append_progress(
+        &ctx.root,
+        &item.id,
+        &format!("diff policy violations before PR:\n{}", report),
+    )?;
+    clean_up(ctx, item, &report).await?;
+
+    let remaining = find_violations(policies, &git::added_lines(base, &options).await?)?;
+    if remaining.is_empty() {
+        return Ok(());
+    }
+    Err(WreckitError::StateTransition(format!(
+        "diff policy violations remain after cleanup:\n{}",
+        violation_report(policies, &remaining)
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{MockFixture, DEFAULT_FIXTURES_DIR};
+    use crate::fs;
+    use crate::schemas::{AgentMode, Config};
+    use std::collections::BTreeMap;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    fn added(path: &str, line: usize, text: &str) -> AddedLine {
+        AddedLine {
+            path: path.to_string(),
+            line,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_find_violations() {
+        let policies = vec![
+            DiffPolicy::new("no-dbg", r"dbg!\(").with_message("use tracing instead"),
+            DiffPolicy::new("no-console", r"console\.log").with_paths(vec!["**/*.js".to_string()]),
+        ];
+        let lines = vec![
+            added("src/b.rs", 9, "    dbg!(value);"),
+            added("src/a.rs", 3, "let x = 1;"),
+            added("web/app.js", 2, "console.log(x)"),
+            added("docs/README.md", 5, "call console.log to debug"),
+            added("src/a.rs", 1, "dbg!(x)"),
+        ];
+        let violations = find_violations(&policies, &lines).unwrap();
+        let found: Vec<(&str, usize, &str)> = violations
+            .iter()
+            .map(|v| (v.path.as_str(), v.line, v.policy.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("src/a.rs", 1, "no-dbg"),
+                ("src/b.rs", 9, "no-dbg"),
+                ("web/app.js", 2, "no-console"),
+            ]
+        );
+
+        let report = violation_report(&policies, &violations);
+        assert!(report.starts_with("### src/a.rs\n- line 1 (no-dbg): `dbg!(x)`\n### src/b.rs"));
+        assert!(report.contains("- line 9 (no-dbg): `dbg!(value);`"));
+        assert!(report.ends_with("### Guidance\n- no-dbg: use tracing instead"));
+
+        let bad = vec![DiffPolicy::new("bad", "(")];
+        assert!(matches!(
+            find_violations(&bad, &lines),
+            Err(WreckitError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_run_fixes_violations() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-q", "-b", "main"]);
+        git(root, &["config", "user.email", "test@example.com"]);
+        git(root, &["config", "user.name", "Test"]);
+        std::fs::write(root.join(".gitignore"), ".wreckit/\n").unwrap();
+        std::fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "init"]);
+        git(root, &["checkout", "-q", "-b", "wreckit/001-dbg"]);
+        std::fs::write(root.join("lib.rs"), "fn a() {\n    dbg!(1);\n}\n").unwrap();
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "work"]);
+
+        let mut config = Config::default();
+        config.agent.mode = AgentMode::Mock;
+        config.guardrails.policies = vec![DiffPolicy::new("no-dbg", r"dbg!\(")];
+        let ctx = WorkflowContext::new(root.to_path_buf(), config);
+        let item = Item::new("001-dbg".to_string(), "Dbg".to_string(), String::new());
+        fs::write_item(root, &item.id, &item).unwrap();
+
+        let fixtures = root.join(DEFAULT_FIXTURES_DIR);
+        std::fs::create_dir_all(&fixtures).unwrap();
+        let fixture = MockFixture {
+            repo_files: BTreeMap::from([("lib.rs".to_string(), "fn a() {}\n".to_string())]),
+            complete: true,
+            ..Default::default()
+        };
+        fs::write_json(&fixtures.join("pr-cleanup.json"), &fixture).unwrap();
+
+        enforce_diff_policies(&ctx, &item).await.unwrap();
+        let progress = std::fs::read_to_string(fs::get_progress_log_path(root, &item.id)).unwrap();
+        assert!(progress.contains("- line 2 (no-dbg): `dbg!(1);`"));
+
+        // Without a fix, the PR stays blocked
+        std::fs::write(root.join("lib.rs"), "fn a() {\n    dbg!(2);\n}\n").unwrap();
+        git(root, &["commit", "-q", "-am", "again"]);
+        let fixture = MockFixture {
+            complete: true,
+            ..Default::default()
+        };
+        fs::write_json(&fixtures.join("pr-cleanup.json"), &fixture).unwrap();
+        let err = enforce_diff_policies(&ctx, &item).await.unwrap_err();
+        assert!(matches!(err, WreckitError::StateTransition(_)));
+    }
+}
@@ -0,0 +1,365 @@
+//! PR comment commands for `wreckit watch`
+//!
+//! Maintainers steer an open PR by commenting on it:
+//!
+//! * `/wreckit revise <feedback>` adds a story for the feedback and re-runs
+//!   implement with the comment appended to the prompt
+//! * `/wreckit fix [notes]` adds a story for the PR's failing checks (those
+//!   matching `require_checks.only`, if set) and re-runs implement against
+//!   them
+//!
+//! Either way the branch is pushed back to the same PR and the outcome posted
+//! as a reply. Handled comment IDs are kept in `.wreckit/pr_bot.json` so a
+//! command runs once however often the PR is polled.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Result;
+use crate::fs;
+use crate::git::{self, PrComment};
+use crate::schemas::{Item, PrBotConfig, Prd, Story, WorkflowState};
+
+use super::context::WorkflowContext;
+use super::orchestrator::Orchestrator;
+use super::phases::PhaseKind;
+
+/// Prefix that marks a comment as a command
+pub const COMMAND_PREFIX: &str = "/wreckit";
+
+/// Repository associations that may issue commands when no maintainers are
+/// configured
+const MAINTAINER_ASSOCIATIONS: &[&str] = &["OWNER", "MEMBER", "COLLABORATOR"];
+
+/// A command parsed from a PR comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BotCommand {
+    /// Re-run implement with the feedback
+    Revise { feedback: String },
+
+    /// Re-run implement against the failing checks, with optional notes
+    Fix { feedback: String },
+
+    /// Anything else after the prefix
+    Unknown(String),
+}
+
+/// Comment IDs already handled, per item
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BotState {
+    #[serde(default)]
+    pub handled: BTreeMap<String, Vec<String>>,
+}
+
+impl BotState {
+    /// Read the state file, or an empty state if there is none
+    ///
+    /// # Errors
+    /// * `InvalidJson` - If the state file is malformed
+    pub fn load(root: &std::path::Path) -> Result<Self> {
+        let path = fs::get_pr_bot_state_path(root);
+        if !path.exists() {
+            return Ok(BotState::default());
+        }
+        fs::read_json(&path)
+    }
+
+    /// Write the state file
+    ///
+    /// # Errors
+    /// * `Io` - If the file cannot be written
+    pub fn save(&self, root: &std::path::Path) -> Result<()> {
+        fs::write_json(&fs::get_pr_bot_state_path(root), self)
+    }
+
+    fn is_handled(&self, item_id: &str, comment_id: &str) -> bool {
+        self.handled
+            .get(item_id)
+            .is_some_and(|ids| ids.iter().any(|id| id == comment_id))
+    }
+
+    fn mark_handled(&mut self, item_id: &str, comment_id: &str) {
+        self.handled
+            .entry(item_id.to_string())
+            .or_default()
+            .push(comment_id.to_string());
+    }
+}
+
+/// Parse the first `/wreckit <command>` line of a comment.
+///
+/// The feedback is the rest of that line plus every line after it.
+pub fn parse_bot_command(body: &str) -> Option<BotCommand> {
+    let lines: Vec<&str> = body.lines().collect();
+    let start = lines.iter().position(|line| {
+        let line = line.trim_start();
+        line == COMMAND_PREFIX || line.starts_with(&format!("{} ", COMMAND_PREFIX))
+    })?;
+    let first = lines[start].trim_start()[COMMAND_PREFIX.len()..].trim();
+    let (name, rest) = match first.split_once(char::is_whitespace) {
+        Some((name, rest)) => (name, rest.trim()),
+        None => (first, ""),
+    };
+    let mut feedback = rest.to_string();
+    for line in &lines[start + 1..] {
+        feedback.push('\n');
+        feedback.push_str(line);
+    }
+    let feedback = feedback.trim().to_string();
+    Some(match name {
+        "revise" => BotCommand::Revise { feedback },
+        "fix" => BotCommand::Fix { feedback },
+        other => BotCommand::Unknown(other.to_string()),
+    })
+}
+
+/// Whether the comment's author may issue commands
+pub fn is_maintainer(comment: &PrComment, config: &PrBotConfig) -> bool {
+    if config.maintainers.is_empty() {
+        MAINTAINER_ASSOCIATIONS.contains(&comment.association.as_str())
+    } else {
+        config
+            .maintainers
+            .iter()
+            .any(|login| login.eq_ignore_ascii_case(&comment.author))
+    }
+}
+
+/// A pending story placed after the PRD's existing ones, with the next
+/// `US-NNN` ID
+pub fn revision_story(prd: &Prd, title: String, acceptance_criteria: Vec<String>) -> Story {
+    let next = prd
+        .user_stories
+        .iter()
+        .filter_map(|story| story.id.strip_prefix("US-")?.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let priority = prd
+        .user_stories
+        .iter()
+        .map(|story| story.priority)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    Story::new(
+        format!("US-{:03}", next),
+        title,
+        acceptance_criteria,
+        priority,
+    )
+}
+
+/// Run a command against an item's PR and post the outcome.
+///
+/// # Errors
+/// * `GitError` - If the PR's checks cannot be read
+/// * `FileNotFound` - If the item has no PRD
+/// * Any error the implement or pr phase fails with; it is also posted on
+///   the PR
+pub async fn handle_command(
+    ctx: &WorkflowContext,
+    item: &Item,
+    pr_number: u32,
+    comment: &PrComment,
+    command: BotCommand,
+) -> Result<Item> {
+    let options = ctx.git_options();
+    let (title, criteria, feedback) = match command {
+        BotCommand::Revise { feedback } if feedback.is_empty() => {
+            let reply = "wreckit: `/wreckit revise` needs feedback after the command";
+            git::comment_on_pr(pr_number, reply, &options).await?;
+            return Ok(item.clone());
+        }
+        BotCommand::Revise { feedback } => (
+            format!("Address review feedback from @{}", comment.author),
+            vec![feedback.clone()],
+            feedback,
+        ),
+        BotCommand::Fix { feedback } => {
+            let checks =
+                git::failed_checks(pr_number, &ctx.config.require_checks.only, &options).await?;
+            if checks.is_empty() && feedback.is_empty() {
+                let reply = "wreckit: no checks are failing on this PR, so there is nothing to fix";
+                git::comment_on_pr(pr_number, reply, &options).await?;
+                return Ok(item.clone());
+            }
+            let mut criteria: Vec<String> = checks
+                .iter()
+                .map(|check| format!("The `{}` check passes", check))
+                .collect();
+            let mut prompt = Vec::new();
+            if !checks.is_empty() {
+                prompt.push(format!(
+                    "These CI checks are failing: {}",
+                    checks.join(", ")
+                ));
+            }
+            if !feedback.is_empty() {
+                criteria.push(feedback.clone());
+                prompt.push(feedback);
+            }
+            (
+                "Fix failing CI checks".to_string(),
+                criteria,
+                prompt.join("\n\n"),
+            )
+        }
+        BotCommand::Unknown(name) => {
+            let reply = format!(
+                "wreckit: unknown command `{}`; use `/wreckit revise <feedback>` or `/wreckit fix`",
+                name
+            );
+            git::comment_on_pr(pr_number, &reply, &options).await?;
+            return Ok(item.clone());
+        }
+    };
+
+    let prd = fs::read_prd(&ctx.root, &item.id)?;
+    let story = revision_story(&prd, title, criteria);
+    if !ctx.dry_run {
+        fs::write_prd(&ctx.root, &item.id, &prd.with_story(story.clone()))?;
+    }
+    let ack = format!(
+        "wreckit: working on {} ({}) for @{}",
+        story.id, story.title, comment.author
+    );
+    git::comment_on_pr(pr_number, &ack, &options).await?;
+
+    let orchestrator = Orchestrator::new(ctx.clone().with_feedback(Some(feedback)));
+    let outcome = match orchestrator
+        .retry_phase(&item.id, Some(PhaseKind::Implement), true)
+        .await
+    {
+        Ok(_) => orchestrator.run_phase(&item.id, PhaseKind::Pr).await,
+        Err(e) => Err(e),
+    };
+    let reply = match outcome {
+        Ok(_) => format!("wreckit: pushed {}; this PR is up to date", story.id),
+        Err(ref e) => format!("wreckit: {} failed: {}", story.id, e),
+    };
+    git::comment_on_pr(pr_number, &reply, &options).await?;
+    outcome
+}
+
+/// Check every in_pr item's PR for new commands and run them.
+///
+/// Comments are marked handled before their command runs, so a failing
+/// command is not retried on the next poll. Returns how many commands ran.
+///
+/// # Errors
+/// * `InvalidJson` - If an item file or the state file is malformed
+/// * `Io` - If the state file cannot be written
+pub async fn poll_pr_comments(ctx: &WorkflowContext) -> Result<usize> {
+    let mut state = BotState::load(&ctx.root)?;
+    let options = ctx.git_options();
+    let mut handled = 0;
+    for item in fs::list_items(&ctx.root)? {
+        let pr_number = match item.pr_number {
+            Some(number) if item.state == WorkflowState::InPr => number,
+            _ => continue,
+        };
+        let comments = match git::pr_comments(pr_number, &options).await {
+            Ok(comments) => comments,
+            Err(e) => {
+                tracing::warn!("Cannot read comments on PR #{}: {}", pr_number, e);
+                continue;
+            }
+        };
+        for comment in comments {
+            if state.is_handled(&item.id, &comment.id) {
+                continue;
+            }
+            state.mark_handled(&item.id, &comment.id);
+            if !ctx.dry_run {
+                state.save(&ctx.root)?;
+            }
+            let command = match parse_bot_command(&comment.body) {
+                Some(command) => command,
+                None => continue,
+            };
+            if !is_maintainer(&comment, &ctx.config.pr_bot) {
+                tracing::warn!(
+                    "Ignoring command from @{} on PR #{}: not a maintainer",
+                    comment.author,
+                    pr_number
+                );
+                continue;
+            }
+            tracing::info!(
+                "Running {:?} from @{} for {}",
+                command,
+                comment.author,
+                item.id
+            );
+            if let Err(e) = handle_command(ctx, &item, pr_number, &comment, command).await {
+                tracing::warn!("Command on PR #{} failed: {}", pr_number, e);
+            }
+            handled += 1;
+        }
+    }
+    Ok(handled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(author: &str, association: &str) -> PrComment {
+        PrComment {
+            id: "IC_1".to_string(),
+            author: author.to_string(),
+            association: association.to_string(),
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_bot_command() {
+        assert_eq!(
+            parse_bot_command("Thanks!\n/wreckit revise use the builder\nand add a test\n"),
+            Some(BotCommand::Revise {
+                feedback: "use the builder\nand add a test".to_string()
+            })
+        );
+        assert_eq!(
+            parse_bot_command("  /wreckit fix"),
+            Some(BotCommand::Fix {
+                feedback: String::new()
+            })
+        );
+        assert_eq!(
+            parse_bot_command("/wreckit merge"),
+            Some(BotCommand::Unknown("merge".to_string()))
+        );
+        assert_eq!(parse_bot_command("see /wreckit revise"), None);
+        assert_eq!(parse_bot_command("/wreckitfix"), None);
+    }
+
+    #[test]
+    fn test_is_maintainer() {
+        let mut config = PrBotConfig::default();
+        assert!(is_maintainer(&comment("alice", "MEMBER"), &config));
+        assert!(!is_maintainer(&comment("bob", "CONTRIBUTOR"), &config));
+
+        config.maintainers = vec!["Bob".to_string()];
+        assert!(is_maintainer(&comment("bob", "CONTRIBUTOR"), &config));
+        assert!(!is_maintainer(&comment("alice", "MEMBER"), &config));
+    }
+
+    #[test]
+    fn test_revision_story() {
+        let mut prd = Prd::new("001".to_string(), "wreckit/001".to_string());
+        let story = revision_story(&prd, "Fix".to_string(), vec![]);
+        assert_eq!((story.id.as_str(), story.priority), ("US-001", 1));
+
+        prd.user_stories
+            .push(Story::new("US-002".to_string(), "A".to_string(), vec![], 4));
+        prd.user_stories
+            .push(Story::new("US-009".to_string(), "B".to_string(), vec![], 2));
+        let story = revision_story(&prd, "Fix".to_string(), vec!["passes".to_string()]);
+        assert_eq!((story.id.as_str(), story.priority), ("US-010", 5));
+        assert!(story.is_pending());
+    }
+}
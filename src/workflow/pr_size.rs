@@ -0,0 +1,264 @@
+//! PR size guard
+//!
+//! Before an item moves to in_pr, its branch diff against the base branch is
+//! measured. If it changes more files or lines than `pr_size` allows, the PR
+//! is blocked. With `on_exceed = "warn"` the item simply stops with the
+//! numbers; with `on_exceed = "split"` the agent is asked to trim the branch
+//! and describe the deferred work, which becomes a follow-up idea, and the
+//! branch is measured again.
+
+use crate::errors::{Result, WreckitError};
+use crate::git::{self, DiffStat};
+use crate::schemas::{Item, PrSizeAction, PrSizeConfig};
+
+use super::context::{check_agent_result, WorkflowContext};
+use super::phases::PhaseKind;
+
+const SPLIT_JSON_START: &str = "SPLIT_JSON_START";
+const SPLIT_JSON_END: &str = "SPLIT_JSON_END";
+
+/// Story key used for the split agent run (fixtures: `pr-split.json`)
+const SPLIT_STORY: &str = "split";
+
+/// Describe how a diff compares to the limits, or `None` if it fits
+pub fn size_overage(stat: &DiffStat, limits: &PrSizeConfig) -> Option<String> {
+    let mut over = Vec::new();
+    if let Some(max) = limits.max_files.filter(|max| stat.files > *max) {
+        over.push(format!("{} files changed (limit {})", stat.files, max));
+    }
+    if let Some(max) = limits.max_lines.filter(|max| stat.lines > *max) {
+        over.push(format!("{} lines changed (limit {})", stat.lines, max));
+    }
+    if over.is_empty() {
+        None
+    } else {
+        Some(over.join(", "))
+    }
+}
+
+/// Extract the follow-up title and overview from split agent output.
+///
+/// Expects a JSON object `{"title": ..., "overview": ...}` between
+/// `SPLIT_JSON_START` and `SPLIT_JSON_END` markers.
+pub fn parse_split_proposal(output: &str) -> Option<(String, String)> {
+    let start = output.rfind(SPLIT_JSON_START)? + SPLIT_JSON_START.len();
+    let end = start + output[start..].find(SPLIT_JSON_END)?;
+    let value: serde_json::Value = serde_json::from_str(output[start..end].trim()).ok()?;
+    let title = value["title"].as_str()?.trim().to_string();
+    if title.is_empty() {
+        return None;
+    }
+    let overview = value["overview"].as_str().unwrap_or_default().to_string();
+    Some((title, overview))
+}
+
+/// Ask the agent to trim the branch and record the deferred work as a follow-up.
+async fn split_item(ctx: &WorkflowContext, item: &Item, overage: &str) -> Result<Option<Item>> {
+    let options = ctx.git_options();
+    let mut variables = ctx.prompt_variables(item);
+    variables.diff_stat = Some(overage.to_string());
+    let prompt = ctx.render_prompt("split", &item.id, variables)?;
+
+    let result = ctx
+        .run_agent(&item.id, PhaseKind::Pr, Some(SPLIT_STORY), prompt)
+        .await?;
+    check_agent_result(&result)?;
+
+    if git::has_uncommitted_changes(&options).await {
+        git::commit_all(
+            &format!("wreckit({}): split deferred work into a follow-up", item.id),
+            &options,
+        )
+        .await?;
+    }
+
+    let (title, overview) = match parse_split_proposal(&result.output) {
+        Some(proposal) => proposal,
+        None => {
+            tracing::warn!("{}: split agent did not describe a follow-up", item.id);
+            return Ok(None);
+        }
+    };
+    let mut follow_up = Item::new(ctx.new_item_id(&title)?, title, overview);
+    follow_up.section = item.section.clone();
+    follow_up.follow_up_of = Some(item.id.clone());
+    ctx.save_item(&follow_up)?;
+    tracing::info!("{}: deferred work moved to {}", item.id, follow_up.id);
+    Ok(Some(follow_up))
+}
+
+/// Block the PR while the branch diff exceeds the configured size limits.
+///
+/// No-op in dry-run mode or when no limit is set. In split mode the agent
+/// trims the branch first and the PR proceeds if the result fits.
+///
+/// # Errors
+/// * `StateTransition` - If the branch is (still) over a limit
+/// * `GitError` - If the diff cannot be measured or the split committed
+/// * `AgentError` - If the split agent run fails
+pub async fn enforce_pr_size(ctx: &WorkflowContext, item: &Item) -> Result<()> {
+    let limits = &ctx.config.pr_size;
+    if ctx.dry_run || (limits.max_files.is_none() && limits.max_lines.is_none()) {
+        return Ok(());
+    }
+
+    let options = ctx.git_options();
+    let stat = git::diff_stat(&ctx.config.base_branch, &options).await?;
+    let overage = match size_overage(&stat, limits) {
+        Some(overage) => overage,
+        None => return Ok(()),
+    };
+    if limits.on_exceed == PrSizeAction::Warn {
+        return Err(WreckitError::StateTransition(format!(
+            "PR too large: {}; split the work or raise pr_size limits",
+            overage
+        )));
+    }
+
+    tracing::warn!(
+        "{}: PR too large ({}); asking the agent to split it",
+        item.id,
+        overage
+    );
+    let follow_up = split_item(ctx, item, &overage).await?;
+    let stat = git::diff_stat(&ctx.config.base_branch, &options).await?;
+    match size_overage(&stat, limits) {
+        None => Ok(()),
+        Some(overage) => Err(WreckitError::StateTransition(format!(
+            "PR still too large after split: {}{}",
+            overage,
+            follow_up
+                .map(|f| format!(" (deferred work is in {})", f.id))
+                .unwrap_or_default()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{MockFixture, DEFAULT_FIXTURES_DIR};
+    use crate::fs;
+    use crate::schemas::{AgentMode, Config};
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    /// A repo whose item branch adds three files on top of main
+    fn setup(limits: PrSizeConfig) -> (TempDir, WorkflowContext, Item) {
+        let temp = TempDir::new().unwrap();
+        git(temp.path(), &["init", "-q", "-b", "main"]);
+        git(temp.path(), &["config", "user.email", "test@example.com"]);
+        git(temp.path(), &["config", "user.name", "Test"]);
+        std::fs::write(temp.path().join(".gitignore"), ".wreckit/\n").unwrap();
+        git(temp.path(), &["add", "-A"]);
+        git(temp.path(), &["commit", "-q", "-m", "init"]);
+        git(temp.path(), &["checkout", "-q", "-b", "wreckit/001-big"]);
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            std::fs::write(temp.path().join(name), "one\ntwo\n").unwrap();
+        }
+        git(temp.path(), &["add", "-A"]);
+        git(temp.path(), &["commit", "-q", "-m", "work"]);
+
+        let mut config = Config {
+            pr_size: limits,
+            ..Default::default()
+        };
+        config.agent.mode = AgentMode::Mock;
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        let item = Item::new("001-big".to_string(), "Big".to_string(), String::new());
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+        (temp, ctx, item)
+    }
+
+    #[test]
+    fn test_size_overage_and_proposal() {
+        let limits = PrSizeConfig {
+            max_files: Some(10),
+            max_lines: Some(100),
+            ..Default::default()
+        };
+        assert!(size_overage(
+            &DiffStat {
+                files: 10,
+                lines: 100
+            },
+            &limits
+        )
+        .is_none());
+        assert_eq!(
+            size_overage(
+                &DiffStat {
+                    files: 12,
+                    lines: 400
+                },
+                &limits
+            )
+            .unwrap(),
+            "12 files changed (limit 10), 400 lines changed (limit 100)"
+        );
+
+        let output = "trimmed\nSPLIT_JSON_START\n{\"title\": \"Add export\", \"overview\": \"CSV export\"}\nSPLIT_JSON_END";
+        assert_eq!(
+            parse_split_proposal(output).unwrap(),
+            ("Add export".to_string(), "CSV export".to_string())
+        );
+        assert!(parse_split_proposal("SPLIT_JSON_START {} SPLIT_JSON_END").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_warn_blocks_oversized_pr() {
+        let (_temp, ctx, item) = setup(PrSizeConfig {
+            max_files: Some(2),
+            ..Default::default()
+        });
+        let err = enforce_pr_size(&ctx, &item).await.unwrap_err();
+        assert!(matches!(err, WreckitError::StateTransition(_)));
+        assert!(err.to_string().contains("3 files changed (limit 2)"));
+
+        let mut ctx = ctx;
+        ctx.config.pr_size.max_files = Some(3);
+        enforce_pr_size(&ctx, &item).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_split_creates_follow_up() {
+        let (temp, ctx, item) = setup(PrSizeConfig {
+            max_files: Some(2),
+            on_exceed: PrSizeAction::Split,
+            ..Default::default()
+        });
+        let fixture = MockFixture {
+            output: "SPLIT_JSON_START\n{\"title\": \"Add c\", \"overview\": \"Deferred c.txt\"}\nSPLIT_JSON_END".to_string(),
+            complete: true,
+            ..Default::default()
+        };
+        fs::write_json(
+            &temp.path().join(DEFAULT_FIXTURES_DIR).join("pr-split.json"),
+            &fixture,
+        )
+        .unwrap();
+
+        // The mock agent cannot trim the branch, so the PR stays blocked
+        let err = enforce_pr_size(&ctx, &item).await.unwrap_err();
+        assert!(err.to_string().contains("still too large"));
+
+        let follow_ups: Vec<Item> = fs::list_items(temp.path())
+            .unwrap()
+            .into_iter()
+            .filter(|i| i.follow_up_of.as_deref() == Some("001-big"))
+            .collect();
+        assert_eq!(follow_ups.len(), 1);
+        assert_eq!(follow_ups[0].title, "Add c");
+        assert_eq!(follow_ups[0].overview, "Deferred c.txt");
+    }
+}
@@ -0,0 +1,606 @@
+//! Built-in item presets
+//!
+//! A preset creates an item with its research, plan, and stories already
+//! written, so it starts in the planned state and goes straight to the
+//! implement loop, verify, and a PR through the usual phases.
+//!
+//! `dependency_update` runs the configured outdated command when the item is
+//! created. Its first story applies compatible updates with the update
+//! command; each outdated dependency then gets a story of its own, so the
+//! agent moves one dependency and fixes its breakage before the next.
+//!
+//! `flaky_tests` reads the failed-step logs of recent CI runs on the base
+//! branch and picks out tests that failed in some runs but not all. Each gets
+//! a story whose verify step runs the test repeatedly, and the implement
+//! phase uses the bundled `flaky_tests` prompt.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::agent::resolve_env;
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git::{self, WorkflowRun};
+use crate::schemas::{FlakyTestsConfig, Item, Prd, Preset, Story, WorkflowState};
+
+use super::context::WorkflowContext;
+use super::implement_loop::run_verify_in;
+
+/// A dependency with a newer release than the one the project uses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutdatedDependency {
+    /// Package name
+    pub name: String,
+
+    /// Version the project resolves today
+    pub current: String,
+
+    /// Newest release
+    pub latest: String,
+}
+
+#[derive(Deserialize)]
+struct OutdatedReport {
+    #[serde(default)]
+    dependencies: Vec<OutdatedEntry>,
+}
+
+#[derive(Deserialize)]
+struct OutdatedEntry {
+    name: String,
+    project: String,
+    latest: String,
+}
+
+/// Parse `cargo outdated --format json` output (one report per line, one
+/// line per workspace member) into the dependencies with a newer release.
+/// Lines that are not reports are skipped.
+pub fn parse_outdated(output: &str) -> Vec<OutdatedDependency> {
+    let mut seen = BTreeSet::new();
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<OutdatedReport>(line).ok())
+        .flat_map(|report| report.dependencies)
+        .filter(|entry| {
+            // cargo-outdated prints "---" and "Removed" where there is no version
+            let is_version = |v: &str| v.chars().next().is_some_and(|c| c.is_ascii_digit());
+            is_version(&entry.latest) && entry.latest != entry.project
+        })
+        .filter(|entry| seen.insert(entry.name.clone()))
+        .map(|entry| OutdatedDependency {
+            name: entry.name,
+            current: entry.project,
+            latest: entry.latest,
+        })
+        .collect()
+}
+
+/// An item built from a preset, with the artifacts that let it skip the
+/// research and plan phases
+#[derive(Debug, Clone)]
+pub struct PresetItem {
+    /// The item, in the planned state
+    pub item: Item,
+
+    /// Contents of research.md
+    pub research: String,
+
+    /// Contents of plan.md
+    pub plan: String,
+
+    /// The stories
+    pub prd: Prd,
+}
+
+impl PresetItem {
+    /// Write the item and its artifacts.
+    ///
+    /// # Errors
+    /// * `Io` - If a file cannot be written
+    pub fn save(&self, ctx: &WorkflowContext) -> Result<()> {
+        ctx.save_item(&self.item)?;
+        fs::write_file(
+            &fs::get_research_path(&ctx.root, &self.item.id),
+            &self.research,
+        )?;
+        fs::write_file(&fs::get_plan_path(&ctx.root, &self.item.id), &self.plan)?;
+        fs::write_prd(&ctx.root, &self.item.id, &self.prd)
+    }
+}
+
+/// The dependency-update stories: compatible updates first, then one per
+/// outdated dependency
+pub fn dependency_update_stories(
+    update_command: &str,
+    outdated: &[OutdatedDependency],
+) -> Vec<Story> {
+    let mut stories = vec![Story::new(
+        "US-001".to_string(),
+        "Apply compatible dependency updates".to_string(),
+        vec![
+            format!(
+                "`{}` has been run and the updated lockfile is committed",
+                update_command
+            ),
+            "Any breakage from the updates is fixed".to_string(),
+        ],
+        1,
+    )];
+    for (i, dependency) in outdated.iter().enumerate() {
+        let n = i as u32 + 2;
+        stories.push(Story::new(
+            format!("US-{:03}", n),
+            format!(
+                "Update {} from {} to {}",
+                dependency.name, dependency.current, dependency.latest
+            ),
+            vec![
+                format!("The project uses {} {}", dependency.name, dependency.latest),
+                format!(
+                    "Breakage from the update is fixed in our code, without pinning {} back",
+                    dependency.name
+                ),
+            ],
+            n,
+        ));
+    }
+    stories
+}
+
+fn dependency_update_research(
+    update_command: &str,
+    outdated_command: &str,
+    report: &str,
+    outdated: &[OutdatedDependency],
+) -> String {
+    let listing = if outdated.is_empty() {
+        "No outdated dependencies were reported.".to_string()
+    } else {
+        outdated
+            .iter()
+            .map(|d| format!("- `{}`: {} → {}", d.name, d.current, d.latest))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    format!(
+        "# Research: Update dependencies\n\n\
+         ## Summary\n\
+         Dependencies have newer releases. Compatible updates are applied with \
+         `{update}`; the rest are moved one at a time so each one's breakage is fixed \
+         on its own.\n\n\
+         ## Current State Analysis\n\
+         `{outdated_cmd}` reported:\n\n{listing}\n\n\
+         ```\n{report}\n```\n\n\
+         ## Technical Considerations\n\
+         Updates past a major version may change APIs; read each dependency's changelog \
+         before fixing call sites.\n\n\
+         ## Risks and Mitigations\n\n\
+         | Risk | Impact | Mitigation |\n\
+         |------|--------|------------|\n\
+         | An update changes behavior without failing the build | Medium | Run the full verify suite after each story |\n\
+         | A fix is impractical | Low | Leave the dependency at its current version and note why in progress.log |\n\n\
+         ## Recommended Approach\n\
+         One story per dependency, compatible updates first.\n",
+        update = update_command,
+        outdated_cmd = outdated_command,
+        listing = listing,
+        report = report.trim_end(),
+    )
+}
+
+/// plan.md for a preset: its overview and one phase per story
+fn stories_plan(title: &str, overview: &str, stories: &[Story]) -> String {
+    let phases: Vec<String> = stories
+        .iter()
+        .map(|story| {
+            let criteria: Vec<String> = story
+                .acceptance_criteria
+                .iter()
+                .map(|criterion| format!("- {}", criterion))
+                .collect();
+            format!("### {}: {}\n{}", story.id, story.title, criteria.join("\n"))
+        })
+        .collect();
+    format!(
+        "# {} Implementation Plan\n\n\
+         ## Overview\n{}\n\n\
+         ## Phases\n\n{}\n\n\
+         ## Testing Strategy\n\
+         The configured verify checks, and any story's own verify step, run after every story.\n",
+        title,
+        overview,
+        phases.join("\n\n")
+    )
+}
+
+/// A test that failed in some recent runs on the base branch but not all
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlakyTest {
+    /// Test name as the failure pattern captured it
+    pub name: String,
+
+    /// Runs it failed in
+    pub failures: usize,
+}
+
+/// Count the failed runs each test appears in, given the failed-step logs
+/// of the failed runs among `total_runs` recent runs. A test that failed in
+/// every run is broken rather than flaky and is left out. Sorted by
+/// failures, most first.
+///
+/// # Errors
+/// * `ConfigError` - If the failure pattern is not a valid regex
+pub fn find_flaky_tests(
+    logs: &[String],
+    total_runs: usize,
+    failure_pattern: &str,
+) -> Result<Vec<FlakyTest>> {
+    let regex = Regex::new(failure_pattern).map_err(|e| {
+        WreckitError::ConfigError(format!("invalid flaky_tests.failure_pattern: {}", e))
+    })?;
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for log in logs {
+        let names: BTreeSet<&str> = regex
+            .captures_iter(log)
+            .filter_map(|caps| caps.get(1).or_else(|| caps.get(0)))
+            .map(|m| m.as_str())
+            .collect();
+        for name in names {
+            *counts.entry(name.to_string()).or_default() += 1;
+        }
+    }
+    let mut flaky: Vec<FlakyTest> = counts
+        .into_iter()
+        .filter(|(_, failures)| *failures < total_runs)
+        .map(|(name, failures)| FlakyTest { name, failures })
+        .collect();
+    flaky.sort_by_key(|test| std::cmp::Reverse(test.failures));
+    Ok(flaky)
+}
+
+/// The command that passes only if `test_command` passes `repeat` times in
+/// a row for the test
+pub fn repeat_test_command(test_command: &str, test: &str, repeat: u32) -> String {
+    format!(
+        "for i in $(seq {}); do {} || exit 1; done",
+        repeat,
+        test_command.replace("{test}", test)
+    )
+}
+
+/// One story per flaky test, each verified by running the test repeatedly
+pub fn flaky_test_stories(
+    flaky: &[FlakyTest],
+    total_runs: usize,
+    config: &FlakyTestsConfig,
+) -> Vec<Story> {
+    flaky
+        .iter()
+        .enumerate()
+        .map(|(i, test)| {
+            let n = i as u32 + 1;
+            Story::new(
+                format!("US-{:03}", n),
+                format!("Stabilize {}", test.name),
+                vec![
+                    format!("{} passes {} times in a row", test.name, config.repeat),
+                    "The fix removes the cause of the flakiness; the test is not retried, ignored, or weakened".to_string(),
+                ],
+                n,
+            )
+            .with_notes(format!(
+                "Failed in {} of the last {} CI runs",
+                test.failures, total_runs
+            ))
+            .with_verify(Some(repeat_test_command(
+                &config.test_command,
+                &test.name,
+                config.repeat,
+            )))
+        })
+        .collect()
+}
+
+fn flaky_tests_research(base_branch: &str, total_runs: usize, flaky: &[FlakyTest]) -> String {
+    let listing: Vec<String> = flaky
+        .iter()
+        .map(|test| {
+            format!(
+                "- `{}`: failed in {} of {} runs",
+                test.name, test.failures, total_runs
+            )
+        })
+        .collect();
+    format!(
+        "# Research: Stabilize flaky tests\n\n\
+         ## Summary\n\
+         These tests failed in some of the last {runs} CI runs on `{base}` and passed in \
+         others, so they fail intermittently rather than being broken.\n\n\
+         ## Current State Analysis\n{listing}\n\n\
+         ## Technical Considerations\n\
+         Intermittent failures come from nondeterminism: shared state, timing, ordering, \
+         the clock, randomness, or the network.\n\n\
+         ## Risks and Mitigations\n\n\
+         | Risk | Impact | Mitigation |\n\
+         |------|--------|------------|\n\
+         | A fix hides the failure instead of removing its cause | High | Retries, ignores, and weakened assertions are out of scope |\n\
+         | The test still fails rarely | Medium | Each story runs its test repeatedly before it is done |\n\n\
+         ## Recommended Approach\n\
+         One story per test, most frequent failures first.\n",
+        runs = total_runs,
+        base = base_branch,
+        listing = listing.join("\n"),
+    )
+}
+
+async fn dependency_update_item(ctx: &WorkflowContext) -> Result<Option<PresetItem>> {
+    let config = &ctx.config.presets.dependency_update;
+    let report = if ctx.dry_run {
+        tracing::info!("[DRY RUN] Would run `{}`", config.outdated_command);
+        String::new()
+    } else {
+        let outcome = run_verify_in(
+            ctx.config.agent.execution,
+            ctx.config.env_activation,
+            &config.outdated_command,
+            &ctx.root,
+            ctx.config.timeout_seconds,
+            &resolve_env(&ctx.config.agent.env)?,
+        )
+        .await?;
+        if !outcome.passed {
+            tracing::warn!(
+                "`{}` failed; creating a single update story",
+                config.outdated_command
+            );
+        }
+        outcome.output
+    };
+    let outdated = parse_outdated(&report);
+
+    let title = "Update dependencies".to_string();
+    let mut item = Item::new(
+        ctx.new_item_id(&title)?,
+        title,
+        format!(
+            "Bring dependencies up to date: apply compatible updates with `{}`, then move each outdated dependency to its latest release, fixing breakage one story at a time.",
+            config.update_command
+        ),
+    );
+    item.preset = Some(Preset::DependencyUpdate.name().to_string());
+    item.success_criteria = Some(vec![
+        "Every dependency is at its latest release or has a noted reason not to be".to_string(),
+        "Every verify check passes".to_string(),
+    ]);
+    let item = item.with_state(WorkflowState::Planned);
+
+    let mut prd = Prd::new(item.id.clone(), ctx.branch_name(&item));
+    prd.user_stories = dependency_update_stories(&config.update_command, &outdated);
+    Ok(Some(PresetItem {
+        research: dependency_update_research(
+            &config.update_command,
+            &config.outdated_command,
+            &report,
+            &outdated,
+        ),
+        plan: stories_plan(&item.title, &item.overview, &prd.user_stories),
+        prd,
+        item,
+    }))
+}
+
+async fn flaky_tests_item(ctx: &WorkflowContext) -> Result<Option<PresetItem>> {
+    let config = &ctx.config.presets.flaky_tests;
+    let base = &ctx.config.base_branch;
+    if ctx.dry_run {
+        tracing::info!(
+            "[DRY RUN] Would scan the last {} CI runs on {} for flaky tests",
+            config.runs,
+            base
+        );
+        return Ok(None);
+    }
+    let options = ctx.git_options();
+    let runs: Vec<WorkflowRun> = git::workflow_runs(base, config.runs, &options)
+        .await?
+        .into_iter()
+        .filter(|run| !run.conclusion.is_empty())
+        .collect();
+    let mut logs = Vec::new();
+    for run in runs.iter().filter(|run| run.conclusion == "failure") {
+        match git::failed_run_log(run.id, &options).await {
+            Ok(log) => logs.push(log),
+            Err(e) => tracing::warn!("Cannot read the log of run {}: {}", run.id, e),
+        }
+    }
+    let flaky = find_flaky_tests(&logs, runs.len(), &config.failure_pattern)?;
+    if flaky.is_empty() {
+        return Ok(None);
+    }
+
+    let title = "Stabilize flaky tests".to_string();
+    let mut item = Item::new(
+        ctx.new_item_id(&title)?,
+        title,
+        format!(
+            "Make the {} test(s) that fail intermittently on {} pass reliably, one story per test.",
+            flaky.len(),
+            base
+        ),
+    );
+    item.preset = Some(Preset::FlakyTests.name().to_string());
+    item.success_criteria = Some(vec![format!(
+        "Each test passes {} times in a row",
+        config.repeat
+    )]);
+    let item = item.with_state(WorkflowState::Planned);
+
+    let mut prd = Prd::new(item.id.clone(), ctx.branch_name(&item));
+    prd.user_stories = flaky_test_stories(&flaky, runs.len(), config);
+    Ok(Some(PresetItem {
+        research: flaky_tests_research(base, runs.len(), &flaky),
+        plan: stories_plan(&item.title, &item.overview, &prd.user_stories),
+        prd,
+        item,
+    }))
+}
+
+/// Build the item for a preset without saving it, or `None` if the preset
+/// found nothing to do (e.g. no flaky tests).
+///
+/// In dry-run mode no command is run.
+///
+/// # Errors
+/// * `GitError` - If gh cannot list recent CI runs
+/// * `ConfigError` - If the flaky test failure pattern is invalid
+/// * `Timeout` - If a preset command does not finish in time
+/// * `Io` - If a preset command cannot be spawned
+pub async fn build_preset_item(
+    ctx: &WorkflowContext,
+    preset: Preset,
+) -> Result<Option<PresetItem>> {
+    match preset {
+        Preset::DependencyUpdate => dependency_update_item(ctx).await,
+        Preset::FlakyTests => flaky_tests_item(ctx).await,
+    }
+}
+
+/// Create and save an item from a preset, or return `None` if the preset
+/// found nothing to do. In dry-run mode the item is returned but not saved.
+///
+/// # Errors
+/// See [`build_preset_item`]; also `Io` if the item cannot be written.
+pub async fn create_preset_item(ctx: &WorkflowContext, preset: Preset) -> Result<Option<Item>> {
+    let built = match build_preset_item(ctx, preset).await? {
+        Some(built) => built,
+        None => return Ok(None),
+    };
+    if ctx.dry_run {
+        tracing::info!("[DRY RUN] Would create {} from {}", built.item.id, preset);
+    } else {
+        built.save(ctx)?;
+    }
+    Ok(Some(built.item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{Config, EnvActivation, Execution};
+    use crate::workflow::lint_item;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_outdated() {
+        let output = concat!(
+            "warning: this is not JSON\n",
+            r#"{"crate_name":"app","dependencies":[{"name":"clap","project":"3.2.0","compat":"3.2.25","latest":"4.5.1","kind":"Normal","platform":null},{"name":"gone","project":"1.0.0","compat":"---","latest":"Removed","kind":"Normal","platform":null}]}"#,
+            "\n",
+            r#"{"crate_name":"cli","dependencies":[{"name":"clap","project":"3.2.0","compat":"3.2.25","latest":"4.5.1","kind":"Normal","platform":null}]}"#,
+        );
+        assert_eq!(
+            parse_outdated(output),
+            vec![OutdatedDependency {
+                name: "clap".to_string(),
+                current: "3.2.0".to_string(),
+                latest: "4.5.1".to_string(),
+            }]
+        );
+        assert!(parse_outdated("All dependencies are up to date").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_dependency_update_item() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(fs::get_wreckit_dir(root)).unwrap();
+        let mut config = Config::default();
+        config.presets.dependency_update.outdated_command = format!(
+            "echo '{}'",
+            r#"{"crate_name":"app","dependencies":[{"name":"serde","project":"0.9.0","latest":"1.0.200"}]}"#
+        );
+        let ctx = WorkflowContext::new(root.to_path_buf(), config);
+
+        let item = create_preset_item(&ctx, Preset::DependencyUpdate)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.state, WorkflowState::Planned);
+        assert_eq!(item.preset.as_deref(), Some("dependency_update"));
+
+        let prd = fs::read_prd(root, &item.id).unwrap();
+        let titles: Vec<&str> = prd.user_stories.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Apply compatible dependency updates",
+                "Update serde from 0.9.0 to 1.0.200"
+            ]
+        );
+        // The generated artifacts pass the deep lint
+        assert!(lint_item(&ctx, &item).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_flaky_tests() {
+        let config = FlakyTestsConfig::default();
+        let logs = vec![
+            "test\tstep\ttest net::retry ... FAILED\ntest db::pool ... FAILED\n".to_string(),
+            "test\tstep\ttest db::pool ... FAILED\ntest db::pool ... FAILED\n".to_string(),
+        ];
+        let flaky = find_flaky_tests(&logs, 3, &config.failure_pattern).unwrap();
+        let counts: Vec<(&str, usize)> = flaky
+            .iter()
+            .map(|test| (test.name.as_str(), test.failures))
+            .collect();
+        assert_eq!(counts, vec![("db::pool", 2), ("net::retry", 1)]);
+        assert!(find_flaky_tests(&logs, 3, "(").is_err());
+
+        // Failing in every run means broken, not flaky
+        let flaky = find_flaky_tests(&logs, 2, &config.failure_pattern).unwrap();
+        assert_eq!(flaky.len(), 1);
+        let stories = flaky_test_stories(&flaky, 2, &config);
+        assert_eq!(stories[0].title, "Stabilize net::retry");
+        assert_eq!(
+            stories[0].verify.as_deref(),
+            Some("for i in $(seq 20); do cargo test -- --exact net::retry || exit 1; done")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repeat_test_command() {
+        let temp = TempDir::new().unwrap();
+        let counter = temp.path().join("count");
+        let test = format!("echo x >> {}", counter.display());
+        let command = repeat_test_command("{test}", &test, 5);
+        let outcome = run_verify_in(
+            Execution::Host,
+            EnvActivation::None,
+            &command,
+            temp.path(),
+            10,
+            &[],
+        )
+        .await
+        .unwrap();
+        assert!(outcome.passed);
+        assert_eq!(
+            std::fs::read_to_string(&counter).unwrap().lines().count(),
+            5
+        );
+        let command = repeat_test_command("false", "t", 5);
+        let outcome = run_verify_in(
+            Execution::Host,
+            EnvActivation::None,
+            &command,
+            temp.path(),
+            10,
+            &[],
+        )
+        .await
+        .unwrap();
+        assert!(!outcome.passed);
+    }
+}
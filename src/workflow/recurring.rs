@@ -0,0 +1,195 @@
+//! Recurring items
+//!
+//! Each entry in `config.recurring` is a template with a cron-like
+//! `recurrence`. `wreckit watch` (or `wreckit recur` from a system cron job)
+//! creates a fresh idea item from a template once its next due time after
+//! the previous instance was created has passed. A template with no instances
+//! yet is due at once, and one whose latest instance is still open waits for
+//! it to finish rather than piling up duplicates. A template naming a preset
+//! creates its items from that preset.
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::Schedule;
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::{Item, RecurringItemConfig, WorkflowState};
+
+use super::context::WorkflowContext;
+use super::presets::build_preset_item;
+
+/// Whether a template with this schedule is due, given when its latest
+/// instance was created
+pub fn is_due(
+    schedule: &Schedule,
+    last_created: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    match last_created {
+        Some(last) => schedule.next_after(last).is_some_and(|due| due <= now),
+        None => true,
+    }
+}
+
+/// Create a template's next item, saving it unless in dry-run mode. `None`
+/// if the template's preset found nothing to do.
+async fn instantiate(
+    ctx: &WorkflowContext,
+    template: &RecurringItemConfig,
+    now: DateTime<Utc>,
+) -> Result<Option<Item>> {
+    let date = now.format("%Y-%m-%d");
+    if let Some(preset) = template.preset {
+        let mut built = match build_preset_item(ctx, preset).await? {
+            Some(built) => built,
+            None => {
+                tracing::info!(
+                    "Recurring item {}: {} found nothing to do",
+                    template.name,
+                    preset
+                );
+                return Ok(None);
+            }
+        };
+        if !template.title.is_empty() {
+            built.item.title = template.title.clone();
+        }
+        built.item.title = format!("{} ({})", built.item.title, date);
+        built.item.section = template.section.clone();
+        built.item.recurrence_of = Some(template.name.clone());
+        if !ctx.dry_run {
+            built.save(ctx)?;
+        }
+        return Ok(Some(built.item));
+    }
+
+    let title = format!("{} ({})", template.title, date);
+    let mut item = Item::new(ctx.new_item_id(&title)?, title, template.overview.clone());
+    item.section = template.section.clone();
+    item.recurrence_of = Some(template.name.clone());
+    if !template.success_criteria.is_empty() {
+        item.success_criteria = Some(template.success_criteria.clone());
+    }
+    if !ctx.dry_run {
+        ctx.save_item(&item)?;
+    }
+    Ok(Some(item))
+}
+
+/// Create an item for every recurring template that is due and return them.
+///
+/// In dry-run mode the items are returned but not saved.
+///
+/// # Errors
+/// * `ConfigError` - If a template's recurrence cannot be parsed
+/// * `InvalidJson` - If an item file is malformed
+/// * `GitError` / `Timeout` - If a preset cannot gather its input
+/// * `Io` - If an item cannot be written
+pub async fn create_due_items(ctx: &WorkflowContext, now: DateTime<Utc>) -> Result<Vec<Item>> {
+    let items = fs::list_items(&ctx.root)?;
+    let mut created = Vec::new();
+    for template in &ctx.config.recurring {
+        let schedule = Schedule::parse(&template.recurrence).map_err(|e| {
+            WreckitError::ConfigError(format!(
+                "recurring item {} has an invalid recurrence: {}",
+                template.name, e
+            ))
+        })?;
+        let latest = items
+            .iter()
+            .filter(|item| item.recurrence_of.as_deref() == Some(template.name.as_str()))
+            .filter_map(|item| {
+                let created = DateTime::parse_from_rfc3339(&item.created_at).ok()?;
+                Some((item, created.with_timezone(&Utc)))
+            })
+            .max_by_key(|(_, created)| *created);
+        if !is_due(&schedule, latest.map(|(_, created)| created), now) {
+            continue;
+        }
+        if let Some((item, _)) = latest {
+            if !matches!(item.state, WorkflowState::Done | WorkflowState::Abandoned) {
+                tracing::info!(
+                    "Recurring item {} is due but {} is still {}",
+                    template.name,
+                    item.id,
+                    item.state_name()
+                );
+                continue;
+            }
+        }
+
+        let item = match instantiate(ctx, template, now).await? {
+            Some(item) => item,
+            None => continue,
+        };
+        if ctx.dry_run {
+            tracing::info!("[DRY RUN] Would create {} from {}", item.id, template.name);
+        }
+        created.push(item);
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use tempfile::TempDir;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn test_create_due_items() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(fs::get_wreckit_dir(root)).unwrap();
+        let config = Config {
+            recurring: vec![RecurringItemConfig {
+                name: "deps".to_string(),
+                recurrence: "0 9 * * 1".to_string(),
+                title: "Update dependencies".to_string(),
+                success_criteria: vec!["cargo update has been run".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let ctx = WorkflowContext::new(root.to_path_buf(), config);
+
+        // The first instance is created at once
+        let created = create_due_items(&ctx, at("2024-07-03T12:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].title, "Update dependencies (2024-07-03)");
+        assert_eq!(created[0].recurrence_of.as_deref(), Some("deps"));
+        let mut first = created[0].clone();
+        first.created_at = "2024-07-03T12:00:00+00:00".to_string();
+        fs::write_item(root, &first.id, &first).unwrap();
+
+        // Not due again until Monday, and then only once the first is finished
+        assert!(create_due_items(&ctx, at("2024-07-05T12:00:00Z"))
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(create_due_items(&ctx, at("2024-07-08T09:00:00Z"))
+            .await
+            .unwrap()
+            .is_empty());
+        let first = first.with_state(WorkflowState::Done);
+        fs::write_item(root, &first.id, &first).unwrap();
+        let created = create_due_items(&ctx, at("2024-07-08T09:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(created.len(), 1);
+        assert_ne!(created[0].id, first.id);
+
+        let mut config = ctx.config.clone();
+        config.recurring[0].recurrence = "weekly".to_string();
+        let ctx = WorkflowContext::new(root.to_path_buf(), config);
+        assert!(create_due_items(&ctx, at("2024-07-08T09:00:00Z"))
+            .await
+            .is_err());
+    }
+}
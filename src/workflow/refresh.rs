@@ -0,0 +1,243 @@
+//! Keeping open PRs current with their base branch
+//!
+//! When the base branch moves while an item sits in in_pr, its PR goes stale
+//! and may start to conflict. The refresher rebases the item branch onto the
+//! fetched base, re-runs the verify checks, force-pushes, and says what
+//! happened on the PR. A rebase that conflicts, or a branch that no longer
+//! verifies, is rolled back and reported instead.
+
+use std::path::Path;
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::git::{self, GitOptions};
+use crate::schemas::{Item, WorkflowState};
+
+use super::context::WorkflowContext;
+use super::implement_loop::{failure_report, run_verify_checks};
+use super::outbox;
+
+/// What refreshing an item's branch did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefreshOutcome {
+    /// The branch already contains the base branch
+    UpToDate,
+
+    /// The branch was rebased, verified, and force-pushed
+    Refreshed,
+
+    /// The rebase conflicted and was aborted
+    Conflicted,
+
+    /// The rebased branch failed verify (the report) and was reset
+    VerifyFailed(String),
+}
+
+/// Post on the item's PR, if it has one, queueing the comment while
+/// offline; other failures are only logged
+async fn comment(root: &Path, item: &Item, body: &str, options: &GitOptions) {
+    if let Some(number) = item.pr_number {
+        if let Err(e) = outbox::comment_on_pr(root, &item.id, number, body, options).await {
+            tracing::warn!("Cannot comment on PR #{}: {}", number, e);
+        }
+    }
+}
+
+/// Rebase one in_pr item's branch onto the latest base branch.
+///
+/// The previously checked-out branch is restored afterwards.
+///
+/// # Errors
+/// * `StateTransition` - If the item has no branch or the working tree has
+///   uncommitted changes to tracked files
+/// * `GitError` - If fetching, checking out, or pushing fails
+/// * `Timeout` - If a verify check does not finish in time
+pub async fn refresh_item(ctx: &WorkflowContext, item: &Item) -> Result<RefreshOutcome> {
+    let branch = match item.branch {
+        Some(ref branch) => branch.clone(),
+        None => {
+            return Err(WreckitError::StateTransition(format!(
+                "{} has no branch to refresh",
+                item.id
+            )))
+        }
+    };
+    let options = ctx.git_options();
+    let base = format!("origin/{}", ctx.config.base_branch);
+    git::run_git_command(&["fetch", "origin", &ctx.config.base_branch], &options).await?;
+    if ctx.dry_run || git::commits_ahead(&branch, &base, &options).await? == 0 {
+        return Ok(RefreshOutcome::UpToDate);
+    }
+
+    let dirty =
+        git::run_git_command(&["status", "--porcelain", "--untracked-files=no"], &options).await?;
+    if !dirty.is_empty() {
+        return Err(WreckitError::StateTransition(format!(
+            "cannot refresh {}: the working tree has uncommitted changes",
+            item.id
+        )));
+    }
+
+    let previous = git::get_current_branch(&options).await?;
+    git::run_git_command(&["checkout", &branch], &options).await?;
+    let outcome = rebase_and_verify(ctx, item, &branch, &base, &options).await;
+    if let Err(e) = git::run_git_command(&["checkout", &previous], &options).await {
+        tracing::warn!("Cannot return to {}: {}", previous, e);
+    }
+    outcome
+}
+
+async fn rebase_and_verify(
+    ctx: &WorkflowContext,
+    item: &Item,
+    branch: &str,
+    base: &str,
+    options: &GitOptions,
+) -> Result<RefreshOutcome> {
+    let original = git::run_git_command(&["rev-parse", "HEAD"], options).await?;
+    if git::run_git_command(&["rebase", base], options)
+        .await
+        .is_err()
+    {
+        git::run_git_command(&["rebase", "--abort"], options).await?;
+        let body = format!(
+            "wreckit: {} moved and this branch no longer rebases cleanly onto it; it needs a manual rebase",
+            ctx.config.base_branch
+        );
+        comment(&ctx.root, item, &body, options).await;
+        return Ok(RefreshOutcome::Conflicted);
+    }
+
+    let checks = ctx.config.story_checks();
+    let outcomes = run_verify_checks(
+        ctx.config.agent.execution,
+        ctx.config.env_activation,
+        &checks,
+        &ctx.root,
+        ctx.config.timeout_seconds,
+        &ctx.verify_env(item)?,
+    )
+    .await?;
+    if let Some(report) = failure_report(&outcomes) {
+        git::run_git_command(&["reset", "--hard", &original], options).await?;
+        let body = format!(
+            "wreckit: rebased onto the latest {} but verify failed, so the branch was left as it was\n\n{}",
+            ctx.config.base_branch, report
+        );
+        comment(&ctx.root, item, &body, options).await;
+        return Ok(RefreshOutcome::VerifyFailed(report));
+    }
+
+    git::run_git_command(&["push", "--force-with-lease", "origin", branch], options).await?;
+    let head = git::run_git_command(&["rev-parse", "--short", base], options).await?;
+    let body = format!(
+        "wreckit: rebased onto {} ({}); verify passed and the branch was force-pushed",
+        ctx.config.base_branch, head
+    );
+    comment(&ctx.root, item, &body, options).await;
+    Ok(RefreshOutcome::Refreshed)
+}
+
+/// Refresh every in_pr item whose branch is behind the base branch and
+/// return each refreshed item's ID with its outcome. Items that cannot be
+/// refreshed are logged and skipped.
+///
+/// # Errors
+/// * `InvalidJson` - If an item file is malformed
+pub async fn refresh_stale_prs(ctx: &WorkflowContext) -> Result<Vec<(String, RefreshOutcome)>> {
+    let mut refreshed = Vec::new();
+    for item in fs::list_items(&ctx.root)? {
+        if item.state != WorkflowState::InPr || item.branch.is_none() {
+            continue;
+        }
+        match refresh_item(ctx, &item).await {
+            Ok(RefreshOutcome::UpToDate) => {}
+            Ok(outcome) => refreshed.push((item.id.clone(), outcome)),
+            Err(e) => tracing::warn!("Cannot refresh {}: {}", item.id, e),
+        }
+    }
+    Ok(refreshed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    fn commit(dir: &Path, file: &str, text: &str) {
+        std::fs::write(dir.join(file), text).unwrap();
+        git(dir, &["add", file]);
+        git(dir, &["commit", "-q", "-m", file]);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_item() {
+        let temp = TempDir::new().unwrap();
+        let origin = temp.path().join("origin.git");
+        let root = temp.path().join("repo");
+        std::fs::create_dir_all(&root).unwrap();
+        git(
+            temp.path(),
+            &["init", "-q", "--bare", "-b", "main", "origin.git"],
+        );
+        git(&root, &["init", "-q", "-b", "main"]);
+        git(&root, &["config", "user.email", "test@example.com"]);
+        git(&root, &["config", "user.name", "Test"]);
+        git(
+            &root,
+            &["remote", "add", "origin", origin.to_str().unwrap()],
+        );
+        std::fs::write(root.join(".git/info/exclude"), ".wreckit\n").unwrap();
+        commit(&root, "a.txt", "a");
+        git(&root, &["push", "-q", "origin", "main"]);
+        git(&root, &["checkout", "-q", "-b", "wreckit/001-test"]);
+        commit(&root, "b.txt", "b");
+        git(&root, &["push", "-q", "origin", "wreckit/001-test"]);
+        git(&root, &["checkout", "-q", "main"]);
+
+        let config = Config {
+            verify_command: Some("test -f b.txt".to_string()),
+            ..Default::default()
+        };
+        let ctx = WorkflowContext::new(root.clone(), config);
+        let item = Item::new("001-test".to_string(), "Test".to_string(), String::new())
+            .with_state(WorkflowState::InPr)
+            .with_branch(Some("wreckit/001-test".to_string()));
+        fs::write_item(&root, &item.id, &item).unwrap();
+        assert!(refresh_stale_prs(&ctx).await.unwrap().is_empty());
+
+        commit(&root, "c.txt", "c");
+        git(&root, &["push", "-q", "origin", "main"]);
+        assert_eq!(
+            refresh_stale_prs(&ctx).await.unwrap(),
+            vec![("001-test".to_string(), RefreshOutcome::Refreshed)]
+        );
+        let options = ctx.git_options();
+        assert_eq!(git::get_current_branch(&options).await.unwrap(), "main");
+        let behind = git::commits_ahead("origin/wreckit/001-test", "main", &options)
+            .await
+            .unwrap();
+        assert_eq!(behind, 0);
+
+        // A conflicting change on main is reported, not forced through
+        commit(&root, "b.txt", "conflict");
+        git(&root, &["push", "-q", "origin", "main"]);
+        assert_eq!(
+            refresh_item(&ctx, &item).await.unwrap(),
+            RefreshOutcome::Conflicted
+        );
+        assert_eq!(git::get_current_branch(&options).await.unwrap(), "main");
+    }
+}
@@ -0,0 +1,107 @@
+//! Re-opening finished items
+//!
+//! The state machine never moves backwards out of `done`, so follow-up work
+//! gets a fresh item instead: a new ID, a `follow_up_of` reference to the
+//! original, its context fields, and a copy of its research.md. The research
+//! phase reuses the carried-over research unless run with `--force`.
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::{Item, WorkflowState};
+
+use super::context::WorkflowContext;
+
+/// Create a follow-up item for a done item.
+///
+/// # Errors
+/// * `FileNotFound` - If the item does not exist
+/// * `StateTransition` - If the item is not done
+/// * `Io` - If the follow-up cannot be written
+pub fn reopen_item(ctx: &WorkflowContext, id: &str, title: Option<&str>) -> Result<Item> {
+    let original = fs::read_item(&ctx.root, id)?;
+    if original.state != WorkflowState::Done {
+        return Err(WreckitError::StateTransition(format!(
+            "only done items can be reopened; {} is {}",
+            id, original.state
+        )));
+    }
+
+    let title = title
+        .map(String::from)
+        .unwrap_or_else(|| format!("Follow-up: {}", original.title));
+    let new_id = ctx.new_item_id(&title)?;
+
+    let mut follow_up = Item::new(new_id, title, original.overview.clone());
+    follow_up.section = original.section.clone();
+    follow_up.follow_up_of = Some(original.id.clone());
+    follow_up.problem_statement = original.problem_statement.clone();
+    follow_up.motivation = original.motivation.clone();
+    follow_up.success_criteria = original.success_criteria.clone();
+    follow_up.technical_constraints = original.technical_constraints.clone();
+    follow_up.scope_in_scope = original.scope_in_scope.clone();
+    follow_up.scope_out_of_scope = original.scope_out_of_scope.clone();
+    follow_up.priority_hint = original.priority_hint;
+    follow_up.urgency_hint = original.urgency_hint.clone();
+
+    if ctx.dry_run {
+        tracing::info!("[DRY RUN] Would create {} from {}", follow_up.id, id);
+        return Ok(follow_up);
+    }
+
+    ctx.save_item(&follow_up)?;
+    let research = fs::get_research_path(&ctx.root, id);
+    if research.exists() {
+        fs::copy_file(&research, &fs::get_research_path(&ctx.root, &follow_up.id))?;
+    }
+    Ok(follow_up)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_reopen_creates_follow_up() {
+        let temp = TempDir::new().unwrap();
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), Config::default());
+        let mut original = Item::new(
+            "001-login".to_string(),
+            "Login".to_string(),
+            "Add login".to_string(),
+        )
+        .with_state(WorkflowState::Done)
+        .with_pr(Some("https://example.com/pr/1".to_string()), Some(1));
+        original.motivation = Some("security".to_string());
+        fs::write_item(temp.path(), &original.id, &original).unwrap();
+        std::fs::write(fs::get_research_path(temp.path(), &original.id), "# R").unwrap();
+
+        let follow_up = reopen_item(&ctx, "001-login", None).unwrap();
+        assert_eq!(follow_up.id, "002-follow-up-login");
+        assert_eq!(follow_up.state, WorkflowState::Idea);
+        assert_eq!(follow_up.follow_up_of.as_deref(), Some("001-login"));
+        assert_eq!(follow_up.motivation.as_deref(), Some("security"));
+        assert_eq!(follow_up.pr_number, None);
+        assert_eq!(
+            std::fs::read_to_string(fs::get_research_path(temp.path(), &follow_up.id)).unwrap(),
+            "# R"
+        );
+        assert_eq!(
+            fs::read_item(temp.path(), "001-login").unwrap().state,
+            WorkflowState::Done
+        );
+    }
+
+    #[test]
+    fn test_reopen_requires_done() {
+        let temp = TempDir::new().unwrap();
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), Config::default());
+        let item = Item::new("001-x".to_string(), "X".to_string(), String::new());
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+        assert!(matches!(
+            reopen_item(&ctx, "001-x", Some("Again")),
+            Err(WreckitError::StateTransition(_))
+        ));
+    }
+}
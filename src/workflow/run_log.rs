@@ -0,0 +1,261 @@
+//! Per-run event streams
+//!
+//! Every workflow run writes the [`WorkflowEvent`]s it publishes and the
+//! agent events it streams to `.wreckit/runs/<timestamp>.jsonl`, one JSON
+//! object per line with the time it was recorded, whether or not a TUI or
+//! server was watching. Workflow events are flattened under
+//! `"source": "workflow"` so they grep like the event bus's own JSON; agent
+//! events carry `"source": "agent"`, the item, and the event. `wreckit runs`
+//! lists and shows these files; `wreckit gc` removes those older than
+//! `gc.log_retention_days`.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::tui::events::AgentEvent;
+use crate::tui::runner::TuiUpdate;
+
+use super::events::WorkflowEvent;
+
+/// Format of run file names, sortable by start time
+const RUN_NAME_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// What a run record is about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum RunEntry {
+    /// An event published on the workflow's event bus
+    Workflow(WorkflowEvent),
+
+    /// An event streamed from the agent working on an item
+    Agent { item_id: String, event: AgentEvent },
+}
+
+/// One line of a run file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// RFC 3339 time the event was recorded
+    pub at: String,
+
+    #[serde(flatten)]
+    pub entry: RunEntry,
+}
+
+impl RunRecord {
+    fn now(entry: RunEntry) -> Self {
+        RunRecord {
+            at: Utc::now().to_rfc3339(),
+            entry,
+        }
+    }
+
+    /// The item the record is about
+    pub fn item_id(&self) -> &str {
+        match &self.entry {
+            RunEntry::Workflow(event) => event.item_id(),
+            RunEntry::Agent { item_id, .. } => item_id,
+        }
+    }
+}
+
+/// Path of a new run file started now
+pub fn new_run_path(root: &Path) -> PathBuf {
+    fs::get_runs_dir(root).join(format!("{}.jsonl", Utc::now().format(RUN_NAME_FORMAT)))
+}
+
+/// Run files, oldest first.
+///
+/// # Errors
+/// * `Io` - If the runs directory cannot be read
+pub fn list_runs(root: &Path) -> Result<Vec<PathBuf>> {
+    let dir = fs::get_runs_dir(root);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut runs: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect();
+    runs.sort();
+    Ok(runs)
+}
+
+/// Find a run by file name or name prefix (e.g. "20260305T14"), or the
+/// latest run if `name` is None.
+///
+/// # Errors
+/// * `FileNotFound` - If no run matches, or the prefix matches several
+pub fn find_run(root: &Path, name: Option<&str>) -> Result<PathBuf> {
+    let runs = list_runs(root)?;
+    let matching: Vec<&PathBuf> = match name {
+        Some(name) => runs
+            .iter()
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with(name))
+            })
+            .collect(),
+        None => runs.last().into_iter().collect(),
+    };
+    match matching.as_slice() {
+        [run] => Ok(run.to_path_buf()),
+        [] => Err(WreckitError::FileNotFound(format!(
+            "no run matching '{}' in {}",
+            name.unwrap_or("latest"),
+            fs::get_runs_dir(root).display()
+        ))),
+        _ => Err(WreckitError::FileNotFound(format!(
+            "{} runs match '{}'; give more of the name",
+            matching.len(),
+            name.unwrap_or_default()
+        ))),
+    }
+}
+
+/// Read the records of a run file, skipping lines that do not parse.
+///
+/// # Errors
+/// * `Io` - If the file cannot be read
+pub fn read_run(path: &Path) -> Result<Vec<RunRecord>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut records = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(e) if !line.trim().is_empty() => {
+                tracing::debug!("Skipping unreadable run record: {}", e)
+            }
+            Err(_) => {}
+        }
+    }
+    Ok(records)
+}
+
+fn write_record<W: Write>(out: &mut W, record: &RunRecord) -> Result<()> {
+    let line =
+        serde_json::to_string(record).map_err(|e| WreckitError::InvalidJson(e.to_string()))?;
+    writeln!(out, "{}", line)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Write a run's workflow and agent events to `path` until its update
+/// stream closes or the run finishes. Workflow events still buffered then
+/// are written before the file is closed. Nothing is recorded in read-only
+/// mode.
+pub fn record_run(
+    path: PathBuf,
+    mut events: broadcast::Receiver<WorkflowEvent>,
+    mut updates: broadcast::Receiver<TuiUpdate>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let file = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::create_file(&path));
+        let mut out = match file {
+            Ok(file) => BufWriter::new(file),
+            Err(e) => {
+                tracing::warn!("Cannot record the run to {}: {}", path.display(), e);
+                return;
+            }
+        };
+        let mut write = |entry: RunEntry| {
+            if let Err(e) = write_record(&mut out, &RunRecord::now(entry)) {
+                tracing::debug!("Cannot write run record: {}", e);
+            }
+        };
+        // The bus can outlive the run; the update stream ends it
+        let mut bus_open = true;
+        loop {
+            tokio::select! {
+                event = events.recv(), if bus_open => match event {
+                    Ok(event) => write(RunEntry::Workflow(event)),
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Run log skipped {} workflow events", skipped);
+                    }
+                    Err(RecvError::Closed) => bus_open = false,
+                },
+                update = updates.recv() => match update {
+                    Ok(TuiUpdate::AgentEvent(item_id, event)) => {
+                        write(RunEntry::Agent { item_id, event })
+                    }
+                    Ok(TuiUpdate::RunFinished) | Err(RecvError::Closed) => break,
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Run log skipped {} updates", skipped);
+                    }
+                },
+            }
+        }
+        while let Ok(event) = events.try_recv() {
+            write(RunEntry::Workflow(event));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::events::EventBus;
+
+    #[tokio::test]
+    async fn test_record_and_read_run() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let bus = EventBus::new();
+        let (updates, _) = broadcast::channel(16);
+        let path = new_run_path(root);
+        let recorder = record_run(path.clone(), bus.subscribe(), updates.subscribe());
+
+        bus.publish(WorkflowEvent::PhaseStarted {
+            item_id: "001-a".to_string(),
+            phase: "research".to_string(),
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        updates
+            .send(TuiUpdate::AgentEvent(
+                "001-a".to_string(),
+                AgentEvent::AssistantText {
+                    text: "Reading the code".to_string(),
+                },
+            ))
+            .unwrap();
+        updates.send(TuiUpdate::SetIteration(2)).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        drop(updates);
+        recorder.await.unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let first = text.lines().next().unwrap();
+        assert!(first.contains("\"source\":\"workflow\""));
+        assert!(first.contains("\"event\":\"phase_started\""));
+
+        let records = read_run(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].item_id(), "001-a");
+        assert!(matches!(
+            records[1].entry,
+            RunEntry::Agent {
+                event: AgentEvent::AssistantText { .. },
+                ..
+            }
+        ));
+
+        assert_eq!(list_runs(root).unwrap(), vec![path.clone()]);
+        assert_eq!(find_run(root, None).unwrap(), path);
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert_eq!(find_run(root, Some(&name[..8])).unwrap(), path);
+        assert!(find_run(root, Some("1999")).is_err());
+    }
+}
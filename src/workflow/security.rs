@@ -0,0 +1,247 @@
+//! Security scans before PR creation
+//!
+//! Scanners listed under `security` (cargo audit, semgrep, gitleaks, ...) run
+//! once when an item is about to move to in_pr. A scanner that exits non-zero
+//! blocks the PR: its findings are summarized into a pending `SEC-<name>`
+//! story whose notes carry the summary, so the next implement iteration is
+//! prompted to remediate them.
+
+use regex::Regex;
+
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::{Item, Story};
+
+use super::context::WorkflowContext;
+use super::gates::with_remediation_story;
+use super::implement_loop::run_verify_in;
+
+/// Prefix of the story IDs created for scan findings
+pub const SECURITY_STORY_PREFIX: &str = "SEC-";
+
+/// Most finding lines kept in a summary
+const MAX_FINDING_LINES: usize = 20;
+
+lazy_static::lazy_static! {
+    static ref FINDING_REGEX: Regex = Regex::new(
+        r"(?i)(RUSTSEC-\d|CVE-\d|GHSA-|vulnerab|secret|leak|token|api[ _-]?key|password|severity|critical|\bhigh\b|finding|warning:|error:)"
+    ).unwrap();
+}
+
+/// Result of one scanner run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanOutcome {
+    /// Scanner name
+    pub name: String,
+
+    /// Command that was run
+    pub cmd: String,
+
+    /// Whether the scanner exited cleanly
+    pub passed: bool,
+
+    /// Summary of what it found (empty if it passed)
+    pub summary: String,
+}
+
+/// Pick the lines of scanner output that describe findings.
+///
+/// Falls back to the last lines of output when nothing looks like a finding.
+pub fn summarize_findings(output: &str) -> String {
+    let lines: Vec<&str> = output
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let findings: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|line| FINDING_REGEX.is_match(line))
+        .collect();
+    let chosen = if findings.is_empty() {
+        &lines[lines.len().saturating_sub(MAX_FINDING_LINES)..]
+    } else {
+        &findings[..findings.len().min(MAX_FINDING_LINES)]
+    };
+    let mut summary = chosen.join("\n");
+    if findings.len() > MAX_FINDING_LINES {
+        summary.push_str(&format!(
+            "\n... and {} more",
+            findings.len() - MAX_FINDING_LINES
+        ));
+    }
+    summary
+}
+
+/// Run every configured scanner where the verify commands run, in the
+/// activated environment, with the item's variables set.
+///
+/// # Errors
+/// * `ConfigError` - If a variable references an unset variable, or the
+///   execution mode needs a devcontainer.json the repository lacks
+/// * `Timeout` / `Io` - If a scanner cannot be run
+pub async fn run_security_scans(ctx: &WorkflowContext, item: &Item) -> Result<Vec<ScanOutcome>> {
+    let env = ctx.verify_env(item)?;
+    let mut outcomes = Vec::new();
+    for scan in &ctx.config.security {
+        let run = run_verify_in(
+            ctx.config.agent.execution,
+            ctx.config.env_activation,
+            &scan.cmd,
+            &ctx.root,
+            ctx.config.timeout_seconds,
+            &env,
+        )
+        .await?;
+        outcomes.push(ScanOutcome {
+            name: scan.name.clone(),
+            cmd: scan.cmd.clone(),
+            passed: run.passed,
+            summary: if run.passed {
+                String::new()
+            } else {
+                summarize_findings(&run.output)
+            },
+        });
+    }
+    Ok(outcomes)
+}
+
+fn remediation_story(scan: &ScanOutcome) -> Story {
+    Story::new(
+        format!("{}{}", SECURITY_STORY_PREFIX, scan.name),
+        format!("Fix {} findings", scan.name),
+        vec![format!("`{}` exits cleanly with no findings", scan.cmd)],
+        0,
+    )
+    .with_notes(format!("Findings:\n{}", scan.summary))
+}
+
+/// Block the PR while any security scanner reports findings.
+///
+/// Each failing scanner becomes (or reopens) a `SEC-<name>` story in
+/// prd.json. No-op in dry-run mode or when no scanners are configured.
+///
+/// # Errors
+/// * `StateTransition` - If any scanner reported findings
+/// * `Timeout` / `Io` - If a scanner cannot be run
+pub async fn enforce_security_scans(ctx: &WorkflowContext, item: &Item) -> Result<()> {
+    if ctx.dry_run || ctx.config.security.is_empty() {
+        return Ok(());
+    }
+    let failed: Vec<ScanOutcome> = run_security_scans(ctx, item)
+        .await?
+        .into_iter()
+        .filter(|scan| !scan.passed)
+        .collect();
+    if failed.is_empty() {
+        return Ok(());
+    }
+
+    let mut prd = fs::read_prd(&ctx.root, &item.id)?;
+    for scan in &failed {
+        tracing::warn!("{}: {} reported findings", item.id, scan.name);
+        prd = with_remediation_story(&prd, remediation_story(scan));
+    }
+    fs::write_prd(&ctx.root, &item.id, &prd)?;
+
+    let names: Vec<&str> = failed.iter().map(|s| s.name.as_str()).collect();
+    Err(WreckitError::StateTransition(format!(
+        "security scan found issues: {}",
+        names.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{Config, Prd, SecurityScan};
+    use crate::workflow::implement_loop::story_brief;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_summarize_findings() {
+        let output = "Scanning Cargo.lock\n\
+                      Crate: time\n\
+                      ID: RUSTSEC-2020-0071\n\
+                      Title: Potential segfault\n\
+                      error: 1 vulnerability found!\n";
+        let summary = summarize_findings(output);
+        assert_eq!(
+            summary,
+            "ID: RUSTSEC-2020-0071\nerror: 1 vulnerability found!"
+        );
+
+        // Nothing recognizable: keep the tail
+        assert_eq!(summarize_findings("a\n\nb\n"), "a\nb");
+    }
+
+    #[tokio::test]
+    async fn test_findings_block_pr_and_add_story() {
+        let temp = TempDir::new().unwrap();
+        let config = Config {
+            security: vec![
+                SecurityScan::new("audit", "true"),
+                SecurityScan::new(
+                    "gitleaks",
+                    "echo 'Finding: AWS access key in src/config.rs:12'; exit 1",
+                ),
+            ],
+            ..Default::default()
+        };
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        let item = Item::new("001-test".to_string(), "Test".to_string(), String::new());
+        let prd = Prd::new(item.id.clone(), "wreckit/001-test".to_string())
+            .with_story(Story::new("US-001".to_string(), "Only".to_string(), vec![], 1).as_done());
+        fs::write_prd(temp.path(), &item.id, &prd).unwrap();
+
+        let err = enforce_security_scans(&ctx, &item).await.unwrap_err();
+        assert!(err.to_string().contains("gitleaks"));
+
+        let prd = fs::read_prd(temp.path(), &item.id).unwrap();
+        let story = prd.next_pending_story().unwrap();
+        assert_eq!(story.id, "SEC-gitleaks");
+        assert!(story_brief(story).contains("AWS access key in src/config.rs:12"));
+
+        let mut ctx = ctx;
+        ctx.config.security.truncate(1);
+        enforce_security_scans(&ctx, &item).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_scans_run_in_the_activated_environment() {
+        use crate::schemas::EnvActivation;
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        // A stand-in direnv, found through the PATH the item sets
+        let bin = temp.path().join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        let direnv = bin.join("direnv");
+        std::fs::write(
+            &direnv,
+            "#!/bin/sh\necho \"activated: $*\"\nshift 2\nexec \"$@\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&direnv, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let config = Config {
+            security: vec![SecurityScan::new("audit", "exit 1")],
+            env_activation: EnvActivation::Direnv,
+            ..Default::default()
+        };
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), config);
+        let mut item = Item::new("001-test".to_string(), "Test".to_string(), String::new());
+        let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+        item.env.insert("PATH".to_string(), path);
+
+        let scans = run_security_scans(&ctx, &item).await.unwrap();
+        assert!(!scans[0].passed);
+        assert!(
+            scans[0].summary.contains("activated: exec . sh -c exit 1"),
+            "{}",
+            scans[0].summary
+        );
+    }
+}
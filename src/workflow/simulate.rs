@@ -0,0 +1,572 @@
+//! Dry-run simulation
+//!
+//! Walks the workflow for an item without touching the repository, the
+//! agent, or GitHub, and produces a step-by-step plan of what a real run
+//! would do: branches, prompt sizes, files written, commands run, and state
+//! transitions. Used by `--dry-run` on the phase-running commands.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::errors::Result;
+use crate::fs;
+use crate::schemas::{Item, LowScoreAction, MergeMode, PrSizeAction, WorkflowState};
+
+use super::changelog::fragment_path;
+use super::context::WorkflowContext;
+use super::context_pack::read_context_pack;
+use super::conventions::{infer_change_type, labels_for};
+use super::implement_loop::story_prompt;
+use super::phases::PhaseKind;
+use super::test_first::tests_prompt;
+
+/// A single simulated action
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanStep {
+    /// A phase begins
+    Phase(PhaseKind),
+    /// A prompt would be rendered from a template
+    Prompt { template: String, chars: usize },
+    /// The agent would be invoked
+    Agent { command: String },
+    /// The agent would be skipped
+    Skip { reason: String },
+    /// A file would be written
+    Write(PathBuf),
+    /// A shell command would be run (git, gh, verify)
+    Command(String),
+    /// The item would change state
+    Transition {
+        from: WorkflowState,
+        to: WorkflowState,
+    },
+    /// Informational note
+    Note(String),
+}
+
+impl fmt::Display for PlanStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanStep::Phase(kind) => write!(f, "== {} phase ==", kind),
+            PlanStep::Prompt { template, chars } => {
+                write!(f, "render {} prompt ({} chars)", template, chars)
+            }
+            PlanStep::Agent { command } => write!(f, "run agent: {}", command),
+            PlanStep::Skip { reason } => write!(f, "skip agent: {}", reason),
+            PlanStep::Write(path) => write!(f, "write {}", path.display()),
+            PlanStep::Command(command) => write!(f, "$ {}", command),
+            PlanStep::Transition { from, to } => write!(f, "transition {} → {}", from, to),
+            PlanStep::Note(note) => write!(f, "note: {}", note),
+        }
+    }
+}
+
+/// The simulated execution plan for one item
+#[derive(Debug, Clone)]
+pub struct SimulationPlan {
+    /// Item being simulated
+    pub item_id: String,
+
+    /// State the item would end in
+    pub final_state: WorkflowState,
+
+    /// Steps in execution order
+    pub steps: Vec<PlanStep>,
+}
+
+impl fmt::Display for SimulationPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[DRY RUN] Execution plan for {}", self.item_id)?;
+        let mut n = 0;
+        for step in &self.steps {
+            if let PlanStep::Phase(_) = step {
+                writeln!(f, "{}", step)?;
+            } else {
+                n += 1;
+                writeln!(f, "{:>3}. {}", n, step)?;
+            }
+        }
+        writeln!(f, "Final state: {}", self.final_state)
+    }
+}
+
+/// Simulate everything `run` would do for an item (until in_pr or done)
+pub fn simulate_item(ctx: &WorkflowContext, item: &Item) -> Result<SimulationPlan> {
+    let mut sim = Simulator::new(ctx, item);
+    loop {
+        let kind = match sim.item.state {
+            WorkflowState::Idea => PhaseKind::Research,
+            WorkflowState::Researched => PhaseKind::Plan,
+            WorkflowState::Planned => PhaseKind::Implement,
+            WorkflowState::Implementing if !sim.implemented => PhaseKind::Implement,
+            WorkflowState::Implementing => PhaseKind::Pr,
+            WorkflowState::InPr | WorkflowState::Done | WorkflowState::Abandoned => break,
+        };
+        sim.phase(kind)?;
+    }
+    Ok(sim.finish())
+}
+
+/// Simulate a single phase for an item
+pub fn simulate_phase(
+    ctx: &WorkflowContext,
+    item: &Item,
+    kind: PhaseKind,
+) -> Result<SimulationPlan> {
+    let mut sim = Simulator::new(ctx, item);
+    sim.phase(kind)?;
+    Ok(sim.finish())
+}
+
+/// Tracks the virtual item as the simulated run advances it
+struct Simulator<'a> {
+    ctx: &'a WorkflowContext,
+    item: Item,
+    steps: Vec<PlanStep>,
+    implemented: bool,
+}
+
+impl<'a> Simulator<'a> {
+    fn new(ctx: &'a WorkflowContext, item: &Item) -> Self {
+        Self {
+            ctx,
+            item: item.clone(),
+            steps: Vec::new(),
+            implemented: false,
+        }
+    }
+
+    fn finish(self) -> SimulationPlan {
+        SimulationPlan {
+            item_id: self.item.id,
+            final_state: self.item.state,
+            steps: self.steps,
+        }
+    }
+
+    fn phase(&mut self, kind: PhaseKind) -> Result<()> {
+        self.steps.push(PlanStep::Phase(kind));
+        self.hook(&format!("pre_{}", kind.name()));
+        match kind {
+            PhaseKind::Research => {
+                let research = fs::get_research_path(&self.ctx.root, &self.item.id);
+                self.agent_step("research", &[research])?;
+                self.transition(WorkflowState::Researched);
+            }
+            PhaseKind::Plan => {
+                let plan = fs::get_plan_path(&self.ctx.root, &self.item.id);
+                let prd = fs::get_prd_path(&self.ctx.root, &self.item.id);
+                self.agent_step("plan", &[plan, prd])?;
+                self.transition(WorkflowState::Planned);
+            }
+            PhaseKind::Implement => self.implement()?,
+            PhaseKind::Pr => self.pr()?,
+            PhaseKind::Complete => {
+                match self.item.pr_number {
+                    Some(number) => self.command(format!("gh pr view {} --json state", number)),
+                    None => self.note("item has no PR number; it cannot be completed"),
+                }
+                self.note("transition only happens if the PR is merged");
+                self.transition(WorkflowState::Done);
+            }
+        }
+        self.hook(&format!("post_{}", kind.name()));
+        Ok(())
+    }
+
+    /// Run a configured hook
+    fn hook(&mut self, hook: &str) {
+        if let Some(command) = self.ctx.config.hooks.command(hook) {
+            self.command(command.to_string());
+        }
+    }
+
+    /// Render a phase prompt and run the agent unless its outputs already exist
+    fn agent_step(&mut self, template: &str, outputs: &[PathBuf]) -> Result<()> {
+        let existing = outputs.iter().all(|p| p.exists());
+        if existing && !self.ctx.force {
+            self.steps.push(PlanStep::Skip {
+                reason: format!(
+                    "{} already exists (use --force to re-run)",
+                    self.rel(&outputs[0]).display()
+                ),
+            });
+            return Ok(());
+        }
+        self.prompt(template)?;
+        if template == "plan" && self.ctx.config.plan.uses_consensus(self.item.priority_hint) {
+            self.consensus()?;
+        } else {
+            self.agent(template, None);
+        }
+        let evaluation = &self.ctx.config.evaluation;
+        if evaluation.evaluates(template) {
+            let action = match evaluation.on_low_score {
+                LowScoreAction::Revise => "revised, then held for approval",
+                LowScoreAction::Gate => "held for approval",
+            };
+            let note = format!(
+                "self-evaluation scores the {} artifact; below {}/5 it is {}",
+                template, evaluation.min_score, action
+            );
+            self.note(&note);
+        }
+        for path in outputs {
+            self.steps.push(PlanStep::Write(self.rel(path)));
+        }
+        Ok(())
+    }
+
+    fn implement(&mut self) -> Result<()> {
+        let branch = self.ctx.branch_name(&self.item);
+        let base = &self.ctx.config.base_branch;
+        self.command(format!(
+            "git checkout -b {} {}  (or checkout if it exists)",
+            branch, base
+        ));
+        self.item = self.item.clone().with_branch(Some(branch.clone()));
+        if self.item.state == WorkflowState::Planned {
+            self.transition(WorkflowState::Implementing);
+        }
+
+        match fs::read_prd(&self.ctx.root, &self.item.id) {
+            Ok(prd) => {
+                let pending = prd.pending_stories();
+                if pending.is_empty() {
+                    self.steps.push(PlanStep::Skip {
+                        reason: "all stories are already done".to_string(),
+                    });
+                }
+                let max = self.ctx.config.max_iterations as usize;
+                if pending.len() > max {
+                    self.note(&format!(
+                        "{} pending stories exceed max_iterations ({}); the run would stop early",
+                        pending.len(),
+                        max
+                    ));
+                }
+                for story in pending.into_iter().take(max) {
+                    let prompt = story_prompt(self.ctx, &self.item, story, None)?;
+                    self.steps.push(PlanStep::Note(format!(
+                        "story {} - {}",
+                        story.id, story.title
+                    )));
+                    if self.ctx.config.implement.test_first {
+                        let tests = tests_prompt(self.ctx, &self.item, story, None)?;
+                        self.steps.push(PlanStep::Prompt {
+                            template: "implement_tests".to_string(),
+                            chars: tests.len(),
+                        });
+                        self.agent("implement", None);
+                        self.command(format!(
+                            "git add -A && git commit -m \"wreckit({}): {} tests\"",
+                            self.item.id, story.id
+                        ));
+                    }
+                    self.steps.push(PlanStep::Prompt {
+                        template: "implement".to_string(),
+                        chars: prompt.len(),
+                    });
+                    self.agent("implement", None);
+                    for check in self.ctx.config.story_checks() {
+                        self.command(check.cmd);
+                    }
+                    if self.ctx.config.verification.enabled && !story.acceptance_criteria.is_empty()
+                    {
+                        self.note("acceptance criteria are verified; unverified ones keep the story pending");
+                        self.agent("implement", self.ctx.config.verification.model.as_deref());
+                        let report = fs::get_verification_path(&self.ctx.root, &self.item.id);
+                        self.steps.push(PlanStep::Write(self.rel(&report)));
+                    }
+                    let prd_path = self.rel(&fs::get_prd_path(&self.ctx.root, &self.item.id));
+                    self.steps.push(PlanStep::Write(prd_path));
+                    self.command(format!(
+                        "git add -A && git commit -m \"wreckit({}): {} {}\"",
+                        self.item.id, story.id, story.title
+                    ));
+                    if self.ctx.config.pr.commit_status {
+                        self.command(format!("git push -u origin {}", branch));
+                        self.note(
+                            "the \"wreckit\" commit status on the branch head shows story progress",
+                        );
+                    }
+                }
+            }
+            Err(_) => {
+                self.note("stories come from prd.json written by the plan phase; one agent run, verify, and commit per story");
+                self.agent("implement", None);
+            }
+        }
+        self.implemented = true;
+        Ok(())
+    }
+
+    fn pr(&mut self) -> Result<()> {
+        let branch = self.ctx.branch_name(&self.item);
+        let base = self.ctx.config.base_branch.clone();
+        for check in self.ctx.config.verify_checks() {
+            if let Some(ref gate) = check.gate {
+                self.command(check.cmd.clone());
+                self.note(&format!(
+                    "gate {}: metric matching `{}` must meet its threshold",
+                    check.name, gate.pattern
+                ));
+            }
+        }
+        for scan in &self.ctx.config.security {
+            self.command(scan.cmd.clone());
+            self.note(&format!(
+                "security scan {}: findings block the PR",
+                scan.name
+            ));
+        }
+        let policies = &self.ctx.config.guardrails.policies;
+        if !policies.is_empty() {
+            self.command(format!("git diff --unified=0 {}...HEAD", base));
+            let names: Vec<&str> = policies.iter().map(|p| p.name.as_str()).collect();
+            self.note(&format!(
+                "diff policies ({}): violations get one agent cleanup run, then block the PR",
+                names.join(", ")
+            ));
+        }
+        let limits = &self.ctx.config.pr_size;
+        if limits.max_files.is_some() || limits.max_lines.is_some() {
+            self.command(format!("git diff --numstat {}...HEAD", base));
+            self.note(&format!(
+                "PR size limit (files: {}, lines: {}): over the limit, {}",
+                limits
+                    .max_files
+                    .map_or("none".to_string(), |n| n.to_string()),
+                limits
+                    .max_lines
+                    .map_or("none".to_string(), |n| n.to_string()),
+                match limits.on_exceed {
+                    PrSizeAction::Warn => "the PR is blocked",
+                    PrSizeAction::Split => "the agent splits deferred work into a follow-up",
+                }
+            ));
+        }
+        if self.item.pr_url.is_some() && !self.ctx.force {
+            self.steps.push(PlanStep::Skip {
+                reason: "item already has a PR".to_string(),
+            });
+        } else {
+            self.prompt("pr")?;
+            self.agent("pr", None);
+            if self.ctx.config.changelog.enabled {
+                let path = fragment_path(self.ctx, &self.ctx.config.changelog, &self.item.id);
+                self.note(&format!("write changelog fragment {}", path.display()));
+            }
+        }
+
+        match self.ctx.config.merge_mode {
+            MergeMode::Pr => {
+                self.command(format!("git push -u origin {}", branch));
+                self.command(format!(
+                    "gh pr create --base {} --head {} --title <from agent> --body <from agent>",
+                    base, branch
+                ));
+                let conventions = &self.ctx.config.pr_conventions;
+                if conventions.enabled {
+                    let prd = fs::read_prd(&self.ctx.root, &self.item.id).ok();
+                    let change_type = infer_change_type(conventions, &self.item, prd.as_ref());
+                    self.note(&format!("prefix the PR title with \"{}: \"", change_type));
+                    let labels = labels_for(conventions, &change_type);
+                    if !labels.is_empty() {
+                        self.command(format!(
+                            "gh pr edit <number> --add-label {}",
+                            labels.join(",")
+                        ));
+                    }
+                }
+                self.transition(WorkflowState::InPr);
+            }
+            MergeMode::Direct => {
+                self.command(format!("git checkout {}", base));
+                if self.ctx.config.pr.squash_direct_merge {
+                    self.command(format!("git merge --squash {}", branch));
+                    self.command("git diff --cached --quiet".to_string());
+                    self.command("git commit -m <message from the PRD>".to_string());
+                } else {
+                    self.command(format!(
+                        "git merge --no-ff -m <message from the PRD> {}",
+                        branch
+                    ));
+                }
+                self.command(format!("git push origin {}", base));
+                self.transition(WorkflowState::InPr);
+                self.transition(WorkflowState::Done);
+            }
+        }
+        Ok(())
+    }
+
+    fn prompt(&mut self, template: &str) -> Result<()> {
+        let mut variables = self.ctx.prompt_variables(&self.item);
+        let uses_pack = matches!(template, "research" | "plan");
+        if uses_pack {
+            variables.repo_context = read_context_pack(self.ctx);
+        }
+        let pack_missing =
+            uses_pack && self.ctx.config.context_pack.enabled && variables.repo_context.is_none();
+        let (prompt, trims) = self.ctx.budgeted_prompt(template, variables)?;
+        self.steps.push(PlanStep::Prompt {
+            template: template.to_string(),
+            chars: prompt.len(),
+        });
+        if pack_missing {
+            self.note("repository context pack would be built in .wreckit/cache/context.md");
+        }
+        for trim in trims {
+            self.note(&format!(
+                "context budget: {} trimmed (~{} to ~{} tokens)",
+                trim.section, trim.tokens_before, trim.tokens_after
+            ));
+        }
+        Ok(())
+    }
+
+    /// Both consensus planners, then the arbiter merging their plans
+    fn consensus(&mut self) -> Result<()> {
+        let plan = self.ctx.config.plan.clone();
+        for (i, label) in ["a", "b"].into_iter().enumerate() {
+            self.note(&format!(
+                "consensus plan {} (kept as plan.{}.md and prd.{}.json)",
+                label, label, label
+            ));
+            self.agent("plan", plan.models.get(i).map(String::as_str));
+        }
+        self.prompt("plan_consensus")?;
+        self.agent("plan", plan.arbiter_model.as_deref());
+        Ok(())
+    }
+
+    fn agent(&mut self, phase: &str, model: Option<&str>) {
+        let mut agent = self.ctx.config.phase_agent(phase);
+        if let Some(model) = model {
+            agent = agent.with_model(model);
+        }
+        let command = std::iter::once(agent.command.as_str())
+            .chain(agent.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.steps.push(PlanStep::Agent { command });
+    }
+
+    fn command(&mut self, command: String) {
+        self.steps.push(PlanStep::Command(command));
+    }
+
+    fn note(&mut self, note: &str) {
+        self.steps.push(PlanStep::Note(note.to_string()));
+    }
+
+    fn transition(&mut self, to: WorkflowState) {
+        self.steps.push(PlanStep::Transition {
+            from: self.item.state,
+            to,
+        });
+        self.item = self.item.clone().with_state(to);
+        self.steps.push(PlanStep::Write(
+            self.rel(&fs::get_item_json_path(&self.ctx.root, &self.item.id)),
+        ));
+    }
+
+    fn rel(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.ctx.root)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{Config, Prd, Story};
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, WorkflowContext, Item) {
+        let temp = TempDir::new().unwrap();
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), Config::default());
+        let item = Item::new(
+            "001-test".to_string(),
+            "Test".to_string(),
+            "Overview".to_string(),
+        );
+        fs::write_item(temp.path(), &item.id, &item).unwrap();
+        (temp, ctx, item)
+    }
+
+    fn transitions(plan: &SimulationPlan) -> Vec<(WorkflowState, WorkflowState)> {
+        plan.steps
+            .iter()
+            .filter_map(|s| match s {
+                PlanStep::Transition { from, to } => Some((*from, *to)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_simulate_item_walks_to_in_pr() {
+        let (temp, ctx, item) = setup();
+        let plan = simulate_item(&ctx, &item).unwrap();
+
+        assert_eq!(plan.final_state, WorkflowState::InPr);
+        assert_eq!(
+            transitions(&plan),
+            vec![
+                (WorkflowState::Idea, WorkflowState::Researched),
+                (WorkflowState::Researched, WorkflowState::Planned),
+                (WorkflowState::Planned, WorkflowState::Implementing),
+                (WorkflowState::Implementing, WorkflowState::InPr),
+            ]
+        );
+        let text = plan.to_string();
+        assert!(text.contains("git checkout -b wreckit/001-test main"));
+        assert!(text.contains("gh pr create --base main --head wreckit/001-test"));
+        assert!(text.contains("write .wreckit/items/001-test/research.md"));
+
+        // Nothing was touched
+        assert!(!fs::get_research_path(temp.path(), &item.id).exists());
+        assert_eq!(
+            fs::read_item(temp.path(), &item.id).unwrap().state,
+            WorkflowState::Idea
+        );
+    }
+
+    #[test]
+    fn test_simulate_implement_lists_pending_stories() {
+        let (temp, mut ctx, item) = setup();
+        ctx.config.verify_command = Some("cargo test".to_string());
+        let item = item.with_state(WorkflowState::Planned);
+        let prd = Prd::new(item.id.clone(), "wreckit/001-test".to_string())
+            .with_story(Story::new(
+                "US-001".to_string(),
+                "First".to_string(),
+                vec![],
+                1,
+            ))
+            .with_story(Story::new("US-002".to_string(), "Done".to_string(), vec![], 2).as_done());
+        fs::write_prd(temp.path(), &item.id, &prd).unwrap();
+
+        let plan = simulate_phase(&ctx, &item, PhaseKind::Implement).unwrap();
+        let text = plan.to_string();
+        assert!(text.contains("story US-001 - First"));
+        assert!(!text.contains("US-002"));
+        assert!(text.contains("$ cargo test"));
+        assert_eq!(plan.final_state, WorkflowState::Implementing);
+    }
+
+    #[test]
+    fn test_simulate_skips_existing_artifacts() {
+        let (temp, ctx, item) = setup();
+        std::fs::write(fs::get_research_path(temp.path(), &item.id), "# Research").unwrap();
+
+        let plan = simulate_phase(&ctx, &item, PhaseKind::Research).unwrap();
+        assert!(matches!(plan.steps[1], PlanStep::Skip { .. }));
+
+        let plan = simulate_phase(&ctx.with_force(true), &item, PhaseKind::Research).unwrap();
+        assert!(matches!(plan.steps[1], PlanStep::Prompt { .. }));
+    }
+}
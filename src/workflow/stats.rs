@@ -0,0 +1,327 @@
+//! Local usage statistics
+//!
+//! `.wreckit/stats.json` accumulates how phases and agent runs went in this
+//! repository: run counts, outcomes, time spent, retries, and estimated
+//! token usage. Nothing leaves the machine; `wreckit stats` renders it so
+//! users can see their own agent throughput. Recording never fails a run —
+//! write errors are logged and dropped — and dry runs record nothing.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::format_duration;
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+
+use super::context::WorkflowContext;
+use super::phases::PhaseKind;
+
+/// Accumulated numbers for one phase
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseStats {
+    /// Phase runs started
+    #[serde(default)]
+    pub runs: u64,
+
+    /// Runs that finished successfully
+    #[serde(default)]
+    pub succeeded: u64,
+
+    /// Failed runs, keyed by failure class (e.g. "validation")
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub failures: BTreeMap<String, u64>,
+
+    /// Runs that re-ran the phase after a failure
+    #[serde(default)]
+    pub retries: u64,
+
+    /// Wall-clock seconds spent in the phase, across all runs
+    #[serde(default)]
+    pub total_seconds: u64,
+
+    /// Agent invocations made by the phase
+    #[serde(default)]
+    pub agent_runs: u64,
+
+    /// Estimated prompt tokens sent to the agent
+    #[serde(default)]
+    pub prompt_tokens: u64,
+
+    /// Estimated tokens of agent output
+    #[serde(default)]
+    pub output_tokens: u64,
+
+    /// Outcomes per prompt variant (see `PromptSelection`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variants: BTreeMap<String, VariantStats>,
+}
+
+/// Outcomes of the runs that used one prompt variant
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VariantStats {
+    /// Phase runs that used the variant
+    #[serde(default)]
+    pub runs: u64,
+
+    /// Those runs that finished successfully
+    #[serde(default)]
+    pub succeeded: u64,
+}
+
+impl PhaseStats {
+    /// Number of failed runs
+    pub fn failed(&self) -> u64 {
+        self.failures.values().sum()
+    }
+}
+
+/// Everything in `.wreckit/stats.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocalStats {
+    /// When recording started (ISO 8601)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+
+    /// Per-phase numbers, keyed by phase name
+    #[serde(default)]
+    pub phases: BTreeMap<String, PhaseStats>,
+}
+
+impl LocalStats {
+    /// Sum of every phase's numbers (prompt variants stay per phase)
+    pub fn totals(&self) -> PhaseStats {
+        let mut total = PhaseStats::default();
+        for phase in self.phases.values() {
+            total.runs += phase.runs;
+            total.succeeded += phase.succeeded;
+            for (class, count) in &phase.failures {
+                *total.failures.entry(class.clone()).or_default() += count;
+            }
+            total.retries += phase.retries;
+            total.total_seconds += phase.total_seconds;
+            total.agent_runs += phase.agent_runs;
+            total.prompt_tokens += phase.prompt_tokens;
+            total.output_tokens += phase.output_tokens;
+        }
+        total
+    }
+}
+
+/// Read the statistics file (empty stats if it does not exist)
+///
+/// # Errors
+/// * `InvalidJson` - If the file is malformed
+pub fn read_stats(root: &Path) -> Result<LocalStats> {
+    match fs::read_json(&fs::get_stats_path(root)) {
+        Ok(stats) => Ok(stats),
+        Err(WreckitError::FileNotFound(_)) => Ok(LocalStats::default()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Apply an update to one phase's numbers and save, logging any failure
+fn update_phase(ctx: &WorkflowContext, phase: PhaseKind, update: impl FnOnce(&mut PhaseStats)) {
+    if ctx.dry_run {
+        return;
+    }
+    let result = read_stats(&ctx.root).and_then(|mut stats| {
+        if stats.since.is_none() {
+            stats.since = Some(chrono::Utc::now().to_rfc3339());
+        }
+        update(stats.phases.entry(phase.name().to_string()).or_default());
+        fs::write_json(&fs::get_stats_path(&ctx.root), &stats)
+    });
+    if let Err(e) = result {
+        tracing::warn!(
+            "Failed to update {}: {}",
+            fs::get_stats_path(&ctx.root).display(),
+            e
+        );
+    }
+}
+
+/// Record a finished phase run: its duration, whether it was a retry, the
+/// prompt variant it used, and its failure class if it failed
+pub fn record_phase_run(
+    ctx: &WorkflowContext,
+    phase: PhaseKind,
+    seconds: u64,
+    retry: bool,
+    variant: Option<&str>,
+    failure: Option<&WreckitError>,
+) {
+    update_phase(ctx, phase, |stats| {
+        stats.runs += 1;
+        stats.total_seconds += seconds;
+        if retry {
+            stats.retries += 1;
+        }
+        if let Some(variant) = variant {
+            let entry = stats.variants.entry(variant.to_string()).or_default();
+            entry.runs += 1;
+            if failure.is_none() {
+                entry.succeeded += 1;
+            }
+        }
+        match failure {
+            Some(e) => {
+                *stats
+                    .failures
+                    .entry(e.classify().code().to_string())
+                    .or_default() += 1
+            }
+            None => stats.succeeded += 1,
+        }
+    });
+}
+
+/// Record an agent invocation with estimated token counts
+pub fn record_agent_run(ctx: &WorkflowContext, phase: PhaseKind, prompt: &str, output: &str) {
+    update_phase(ctx, phase, |stats| {
+        stats.agent_runs += 1;
+        let model = ctx.token_model(phase.name());
+        stats.prompt_tokens += model.count(prompt) as u64;
+        stats.output_tokens += model.count(output) as u64;
+    });
+}
+
+fn percent(part: u64, whole: u64) -> String {
+    match (part * 100).checked_div(whole) {
+        Some(pct) => format!("{}%", pct),
+        None => "-".to_string(),
+    }
+}
+
+fn format_row(name: &str, stats: &PhaseStats) -> String {
+    let average = match stats.total_seconds.checked_div(stats.runs) {
+        Some(seconds) => format_duration(seconds as i64),
+        None => "-".to_string(),
+    };
+    format!(
+        "{:<10} {:>6} {:>8} {:>8} {:>10} {:>8} {:>12} {:>12}",
+        name,
+        stats.runs,
+        percent(stats.succeeded, stats.runs),
+        stats.retries,
+        average,
+        stats.agent_runs,
+        stats.prompt_tokens,
+        stats.output_tokens
+    )
+}
+
+/// Render the statistics as a human-readable report
+pub fn format_report(stats: &LocalStats) -> String {
+    let mut lines = Vec::new();
+    if let Some(ref since) = stats.since {
+        lines.push(format!("Local statistics since {}", since));
+    }
+    lines.push(format!(
+        "{:<10} {:>6} {:>8} {:>8} {:>10} {:>8} {:>12} {:>12}",
+        "PHASE", "RUNS", "SUCCESS", "RETRIES", "AVG TIME", "AGENT", "PROMPT TOK", "OUTPUT TOK"
+    ));
+    // Workflow order, then anything unexpected by name
+    let mut names: Vec<&String> = stats.phases.keys().collect();
+    names.sort_by_key(|name| {
+        let order = PhaseKind::ALL
+            .iter()
+            .position(|kind| kind.name() == name.as_str());
+        (order.unwrap_or(usize::MAX), name.to_string())
+    });
+    for name in names {
+        lines.push(format_row(name, &stats.phases[name]));
+    }
+    let totals = stats.totals();
+    lines.push(format_row("total", &totals));
+
+    if !totals.failures.is_empty() {
+        let failures: Vec<String> = totals
+            .failures
+            .iter()
+            .map(|(class, count)| format!("{} {}", class, count))
+            .collect();
+        lines.push(format!("\nFailures: {}", failures.join(", ")));
+    }
+    let variants: Vec<String> = stats
+        .phases
+        .iter()
+        .flat_map(|(phase, stats)| {
+            stats.variants.iter().map(move |(variant, outcome)| {
+                format!(
+                    "  {}.{}: {} run(s), {} success",
+                    phase,
+                    variant,
+                    outcome.runs,
+                    percent(outcome.succeeded, outcome.runs)
+                )
+            })
+        })
+        .collect();
+    if !variants.is_empty() {
+        lines.push(format!("\nPrompt variants:\n{}", variants.join("\n")));
+    }
+    lines.push(format!(
+        "Time in phases: {}; token counts are estimates (about four characters per token)",
+        format_duration(totals.total_seconds as i64)
+    ));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Config;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_records_accumulate() {
+        let temp = TempDir::new().unwrap();
+        let ctx = WorkflowContext::new(temp.path().to_path_buf(), Config::default());
+
+        // The default agent is claude: about 3.5 characters per token
+        record_agent_run(&ctx, PhaseKind::Research, &"p".repeat(350), &"o".repeat(35));
+        record_phase_run(&ctx, PhaseKind::Research, 90, false, None, None);
+        let failure = WreckitError::AgentError("gave up".into());
+        record_phase_run(&ctx, PhaseKind::Plan, 30, false, Some("a"), Some(&failure));
+        record_phase_run(&ctx, PhaseKind::Plan, 60, true, Some("a"), None);
+        record_phase_run(
+            &ctx.clone().with_dry_run(true),
+            PhaseKind::Plan,
+            5,
+            false,
+            None,
+            None,
+        );
+
+        let stats = read_stats(temp.path()).unwrap();
+        assert!(stats.since.is_some());
+        let research = &stats.phases["research"];
+        assert_eq!((research.runs, research.succeeded), (1, 1));
+        assert_eq!((research.prompt_tokens, research.output_tokens), (100, 10));
+        let plan = &stats.phases["plan"];
+        assert_eq!(
+            (plan.runs, plan.succeeded, plan.failed(), plan.retries),
+            (2, 1, 1, 1)
+        );
+        assert_eq!(plan.failures["agent_gave_up"], 1);
+        assert_eq!(plan.total_seconds, 90);
+        assert_eq!(
+            plan.variants["a"],
+            VariantStats {
+                runs: 2,
+                succeeded: 1
+            }
+        );
+
+        let report = format_report(&stats);
+        let rows: Vec<&str> = report.lines().collect();
+        assert!(rows[2].starts_with("research"));
+        assert!(rows[3].starts_with("plan"));
+        assert!(rows[3].contains("50%"));
+        assert!(rows[4].starts_with("total"));
+        assert!(report.contains("Failures: agent_gave_up 1"));
+        assert!(report.contains("  plan.a: 2 run(s), 50% success"));
+    }
+}
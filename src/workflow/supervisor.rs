@@ -0,0 +1,229 @@
+//! Crash restarts for detached runs
+//!
+//! `wreckit run <id> --detach` hands the run to a supervisor process. The
+//! supervisor starts `wreckit run <id>` as a child, and when the child dies
+//! without finishing (a panic or a signal) it records the crash on the item
+//! and starts the child again after a backoff, up to
+//! `supervisor.max_restarts` times. A restarted run resumes from the item's
+//! current state, so the phase that was running starts over. A child that
+//! was interrupted (exit 130) or exited with an error (a held lock, a bad
+//! config, a failed phase) is not restarted: running it again would fail
+//! the same way.
+
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+
+use crate::agent::agent_cache_disabled;
+use crate::config;
+use crate::errors::{Result, WreckitError};
+use crate::fs;
+use crate::schemas::{Item, SupervisorConfig};
+use crate::tui::{append_event, TuiUpdate, EVENT_LOG_ENV, SUPERVISED_ENV};
+
+use super::context::WorkflowContext;
+
+/// Exit code of a run cancelled by the operator
+const INTERRUPTED_EXIT: i32 = 130;
+
+/// Exit code of a Rust process that panicked
+const PANIC_EXIT: i32 = 101;
+
+/// Whether a child that exited with `code` (`None` when killed by a signal)
+/// crashed and should be restarted
+pub fn crashed(code: Option<i32>) -> bool {
+    matches!(code, None | Some(PANIC_EXIT))
+}
+
+/// How a child process ended, for the crash record
+pub fn describe_exit(status: &ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        return format!("exit code {}", code);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("signal {}", signal);
+        }
+    }
+    "unknown exit".to_string()
+}
+
+/// Seconds to wait before the `restart`-th restart
+pub fn restart_delay(restart: u32, config: &SupervisorConfig) -> u64 {
+    config
+        .initial_backoff_seconds
+        .saturating_mul(1u64 << restart.saturating_sub(1).min(16))
+        .min(config.max_backoff_seconds)
+}
+
+/// PID of the supervisor still running `id` in the background, if any
+pub fn running_supervisor(root: &Path, id: &str) -> Option<u32> {
+    let pid = std::fs::read_to_string(fs::get_detached_pid_path(root, id)).ok()?;
+    let pid = pid.trim().parse().ok()?;
+    fs::process_alive(pid).then_some(pid)
+}
+
+/// Record a crash in the item's history.
+///
+/// # Errors
+/// * `FileNotFound` - If the item does not exist
+/// * `Io` - If the item cannot be written
+pub fn record_crash(ctx: &WorkflowContext, id: &str, exit: String) -> Result<Item> {
+    let item = fs::read_item(&ctx.root, id)?.with_crash(exit);
+    ctx.save_item(&item)?;
+    Ok(item)
+}
+
+/// Run an item in a child process, restarting it after crashes, and close
+/// the event file when done.
+///
+/// `force` applies to the first attempt only; restarts resume where the
+/// crashed attempt left off.
+///
+/// # Errors
+/// * `AgentError` - If the run exits with an error, or still crashes after
+///   the last restart
+/// * `Interrupted` - If the run was cancelled
+/// * `Io` - If the child cannot be started
+pub async fn supervise(ctx: &WorkflowContext, id: &str, force: bool, events: &Path) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let limits = &ctx.config.supervisor;
+    let mut restarts = 0;
+    let result = loop {
+        let mut command = tokio::process::Command::new(&exe);
+        command
+            .arg("--cwd")
+            .arg(&ctx.root)
+            .arg("--no-tui")
+            .args(["run", id]);
+        if force && restarts == 0 {
+            command.arg("--force");
+        }
+        if let Some(profile) = config::active_profile() {
+            command.arg("--profile").arg(profile);
+        }
+        if agent_cache_disabled() {
+            command.arg("--no-cache");
+        }
+        let status = command
+            .env(EVENT_LOG_ENV, events)
+            .env(SUPERVISED_ENV, "1")
+            .stdin(Stdio::null())
+            .status()
+            .await?;
+        if status.code() == Some(INTERRUPTED_EXIT) {
+            break Err(WreckitError::Interrupted);
+        }
+        if status.success() {
+            break Ok(());
+        }
+        let exit = describe_exit(&status);
+        if !crashed(status.code()) {
+            break Err(WreckitError::AgentError(format!(
+                "{} run ended with {}; not restarting (see its output above)",
+                id, exit
+            )));
+        }
+
+        let item = record_crash(ctx, id, exit.clone())?;
+        if restarts >= limits.max_restarts {
+            break Err(WreckitError::AgentError(format!(
+                "{} run ended with {} in {} after {} restart(s); giving up",
+                id,
+                exit,
+                item.state_name(),
+                restarts
+            )));
+        }
+        restarts += 1;
+        let wait = restart_delay(restarts, limits);
+        let note = format!(
+            "[WARN] {} run ended with {} in {}; restart {} of {} in {}s",
+            id,
+            exit,
+            item.state_name(),
+            restarts,
+            limits.max_restarts,
+            wait
+        );
+        tracing::warn!("{}", note);
+        if let Err(e) = append_event(events, &TuiUpdate::AppendLogs(vec![note])) {
+            tracing::debug!("Cannot write events to {}: {}", events.display(), e);
+        }
+        tokio::time::sleep(Duration::from_secs(wait)).await;
+    };
+
+    if let Err(e) = append_event(events, &TuiUpdate::RunFinished) {
+        tracing::debug!("Cannot write events to {}: {}", events.display(), e);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crashed() {
+        assert!(!crashed(Some(0)));
+        assert!(!crashed(Some(130)));
+        assert!(!crashed(Some(1)));
+        assert!(crashed(Some(101)));
+        assert!(crashed(None));
+    }
+
+    #[test]
+    fn test_lock_held_is_not_a_crash() {
+        let locked = WreckitError::Locked("pid 42 holds .wreckit/lock".to_string());
+        assert!(!crashed(Some(crate::errors::to_exit_code(&locked))));
+        let config = WreckitError::ConfigError("bad".to_string());
+        assert!(!crashed(Some(crate::errors::to_exit_code(&config))));
+    }
+
+    #[test]
+    fn test_restart_delay() {
+        let config = SupervisorConfig {
+            max_restarts: 5,
+            initial_backoff_seconds: 30,
+            max_backoff_seconds: 100,
+        };
+        assert_eq!(restart_delay(1, &config), 30);
+        assert_eq!(restart_delay(2, &config), 60);
+        assert_eq!(restart_delay(3, &config), 100);
+    }
+
+    #[test]
+    fn test_running_supervisor() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(running_supervisor(dir.path(), "001-a"), None);
+
+        let path = fs::get_detached_pid_path(dir.path(), "001-a");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, std::process::id().to_string()).unwrap();
+        assert_eq!(
+            running_supervisor(dir.path(), "001-a"),
+            Some(std::process::id())
+        );
+
+        std::fs::write(&path, (u32::MAX / 2).to_string()).unwrap();
+        assert_eq!(running_supervisor(dir.path(), "001-a"), None);
+    }
+
+    #[test]
+    fn test_record_crash() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".wreckit")).unwrap();
+        let ctx = WorkflowContext::new(dir.path().to_path_buf(), Default::default());
+        let item = Item::new("001-a".to_string(), "A".to_string(), String::new())
+            .with_state(crate::schemas::WorkflowState::Implementing);
+        fs::write_item(dir.path(), "001-a", &item).unwrap();
+
+        record_crash(&ctx, "001-a", "signal 9".to_string()).unwrap();
+        let stored = fs::read_item(dir.path(), "001-a").unwrap();
+        assert_eq!(stored.crashes.len(), 1);
+        assert_eq!(stored.crashes[0].state, "implementing");
+        assert_eq!(stored.crashes[0].exit, "signal 9");
+    }
+}
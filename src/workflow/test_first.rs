@@ -0,0 +1,134 @@
+//! Test-first story mode
+//!
+//! With `implement.test_first`, each story's iteration of the implement loop
+//! starts with a pass that only writes failing tests. They are committed on
+//! their own, and the verify checks' failures become the implementation
+//! pass's target. Before the story is marked done, the tests commit must
+//! still be in the branch history with its files in place, so the history
+//! shows the tests came first.
+
+use crate::errors::Result;
+use crate::git;
+use crate::schemas::{Item, Story, VerifyCheck};
+
+use super::context::{check_agent_result, WorkflowContext};
+use super::guardrails::{enforce_protected_paths, violation_report};
+use super::implement_loop::{failure_report, run_verify_checks, story_brief};
+use super::phases::PhaseKind;
+
+/// Result of a story's test pass
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestPass {
+    /// The tests were committed
+    Committed {
+        /// SHA of the tests commit
+        commit: String,
+
+        /// Failing-check report for the implementation pass, or None if the
+        /// checks already pass
+        failures: Option<String>,
+    },
+    /// Nothing was committed; feedback for the next test pass
+    Rejected(String),
+}
+
+/// Render the test pass prompt for a story
+pub fn tests_prompt(
+    ctx: &WorkflowContext,
+    item: &Item,
+    story: &Story,
+    feedback: Option<&str>,
+) -> Result<String> {
+    let mut variables = ctx.prompt_variables(item);
+    variables.story = Some(story_brief(story));
+    variables.verify_failures = feedback.map(String::from);
+    ctx.render_prompt("implement_tests", &item.id, variables)
+}
+
+/// Run the test pass for a story and commit the tests it writes.
+///
+/// Returns None in dry-run mode, after the agent call is described.
+///
+/// # Errors
+/// * `AgentError` / `Timeout` / `Interrupted` - If the agent run fails
+/// * `GitError` - If committing the tests fails
+/// * `StateTransition` - If the agent changed a protected path and
+///   `guardrails.on_violation` is `fail`
+pub async fn write_failing_tests(
+    ctx: &WorkflowContext,
+    item: &Item,
+    story: &Story,
+    checks: &[VerifyCheck],
+    feedback: Option<&str>,
+) -> Result<Option<TestPass>> {
+    let options = ctx.git_options();
+    let prompt = tests_prompt(ctx, item, story, feedback)?;
+    let result = ctx
+        .run_agent(&item.id, PhaseKind::Implement, Some(&story.id), prompt)
+        .await?;
+    check_agent_result(&result)?;
+    if ctx.dry_run {
+        tracing::info!("[DRY RUN] Would commit the tests for {}", story.id);
+        return Ok(None);
+    }
+
+    let violations = enforce_protected_paths(ctx, &item.id).await?;
+    if !violations.is_empty() {
+        return Ok(Some(TestPass::Rejected(violation_report(&violations))));
+    }
+    if !git::has_uncommitted_changes(&options).await {
+        return Ok(Some(TestPass::Rejected(
+            "### tests\nThe test pass changed no files. Write failing tests for the story before it is implemented.".to_string(),
+        )));
+    }
+    git::commit_all(
+        &format!("wreckit({}): {} tests", item.id, story.id),
+        &options,
+    )
+    .await?;
+    let commit = git::resolve_ref("HEAD", &options).await.unwrap_or_default();
+
+    let outcomes = run_verify_checks(
+        ctx.config.agent.execution,
+        ctx.config.env_activation,
+        checks,
+        &ctx.root,
+        ctx.config.timeout_seconds,
+        &ctx.verify_env(item)?,
+    )
+    .await?;
+    Ok(Some(TestPass::Committed {
+        commit,
+        failures: failure_report(&outcomes),
+    }))
+}
+
+/// Check that a story's tests commit still precedes its implementation:
+/// the commit is in HEAD's history and the files it added or changed still
+/// exist. Returns feedback for the next attempt if not.
+///
+/// # Errors
+/// * `GitError` - If the commit's files cannot be listed
+pub async fn check_tests_precede(ctx: &WorkflowContext, commit: &str) -> Result<Option<String>> {
+    let options = ctx.git_options();
+    if !git::is_ancestor_of_head(commit, &options).await {
+        return Ok(Some(format!(
+            "### tests\nThe tests commit {} is no longer in the branch history; the tests must be committed before the implementation.",
+            commit
+        )));
+    }
+    let removed: Vec<String> = git::commit_files(commit, &options)
+        .await?
+        .into_iter()
+        .filter(|path| !ctx.root.join(path).exists())
+        .map(|path| format!("- `{}`", path))
+        .collect();
+    if removed.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(format!(
+        "### tests\nThe implementation removed files from the tests commit {}; keep the tests and make them pass:\n{}",
+        commit,
+        removed.join("\n")
+    )))
+}
@@ -0,0 +1,246 @@
+//! Recorded agent transcripts and deterministic replay
+//!
+//! Every live agent invocation is recorded as
+//! `.wreckit/items/<id>/transcripts/NNN-<phase>.json`. A [`ReplaySource`]
+//! feeds those recordings back in order instead of calling the agent, so the
+//! orchestration code can be re-driven offline and bugs reproduced exactly.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::AgentResult;
+use crate::errors::Result;
+use crate::fs;
+
+/// One recorded agent invocation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    /// Position in the item's recording order (1-based)
+    pub sequence: u32,
+
+    /// Phase that invoked the agent (e.g., "implement")
+    pub phase: String,
+
+    /// Story being implemented, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub story: Option<String>,
+
+    /// Prompt sent to the agent
+    pub prompt: String,
+
+    /// Combined agent output
+    pub output: String,
+
+    /// Whether the agent completed successfully
+    pub success: bool,
+
+    /// Process exit code, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+
+    /// Whether the agent timed out
+    #[serde(default)]
+    pub timed_out: bool,
+
+    /// Whether the completion signal was detected
+    pub completion_detected: bool,
+
+    /// When the invocation finished (ISO 8601)
+    pub recorded_at: String,
+
+    /// Prompt variant the phase used (see `PromptSelection`), if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_variant: Option<String>,
+}
+
+impl Transcript {
+    /// Build a transcript from an agent result
+    pub fn from_result(
+        phase: &str,
+        story: Option<&str>,
+        prompt: &str,
+        result: &AgentResult,
+    ) -> Self {
+        Transcript {
+            sequence: 0,
+            phase: phase.to_string(),
+            story: story.map(String::from),
+            prompt: prompt.to_string(),
+            output: result.output.clone(),
+            success: result.success,
+            exit_code: result.exit_code,
+            timed_out: result.timed_out,
+            completion_detected: result.completion_detected,
+            recorded_at: Utc::now().to_rfc3339(),
+            prompt_variant: None,
+        }
+    }
+
+    /// Record the prompt variant the phase used
+    pub fn with_prompt_variant(mut self, variant: Option<String>) -> Self {
+        self.prompt_variant = variant;
+        self
+    }
+
+    /// Convert back into the agent result it recorded
+    pub fn to_result(&self) -> AgentResult {
+        AgentResult {
+            success: self.success,
+            output: self.output.clone(),
+            timed_out: self.timed_out,
+            exit_code: self.exit_code,
+            completion_detected: self.completion_detected,
+        }
+    }
+}
+
+/// Append a transcript to the item's recordings, assigning its sequence number.
+///
+/// # Errors
+/// * `Io` - If the transcripts directory cannot be read or written
+pub fn record_transcript(
+    root: &Path,
+    item_id: &str,
+    transcript: &Transcript,
+) -> Result<Transcript> {
+    let dir = fs::get_transcripts_dir(root, item_id);
+    let sequence = load_transcripts(root, item_id, None)?
+        .last()
+        .map(|t| t.sequence + 1)
+        .unwrap_or(1);
+    let recorded = Transcript {
+        sequence,
+        ..transcript.clone()
+    };
+    let path = dir.join(format!("{:03}-{}.json", sequence, recorded.phase));
+    fs::write_json(&path, &recorded)?;
+    Ok(recorded)
+}
+
+/// Load an item's transcripts in recording order, optionally for one phase.
+///
+/// Returns an empty list if nothing has been recorded.
+///
+/// # Errors
+/// * `InvalidJson` - If a transcript file cannot be parsed
+pub fn load_transcripts(
+    root: &Path,
+    item_id: &str,
+    phase: Option<&str>,
+) -> Result<Vec<Transcript>> {
+    let dir = fs::get_transcripts_dir(root, item_id);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut transcripts = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let transcript: Transcript = fs::read_json(&path)?;
+        if phase.is_none_or(|p| transcript.phase == p) {
+            transcripts.push(transcript);
+        }
+    }
+    transcripts.sort_by_key(|t| t.sequence);
+    Ok(transcripts)
+}
+
+/// Recorded outputs fed back to the workflow instead of live agent calls
+#[derive(Debug, Default)]
+pub struct ReplaySource {
+    queue: Mutex<VecDeque<Transcript>>,
+}
+
+impl ReplaySource {
+    /// Create a source that replays the given transcripts in order
+    pub fn new(transcripts: Vec<Transcript>) -> Self {
+        Self {
+            queue: Mutex::new(transcripts.into()),
+        }
+    }
+
+    /// Take the next recording, if any remain
+    pub fn next_transcript(&self) -> Option<Transcript> {
+        self.queue.lock().ok()?.pop_front()
+    }
+
+    /// Number of recordings not yet replayed
+    pub fn remaining(&self) -> usize {
+        self.queue.lock().map(|q| q.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn result(output: &str) -> AgentResult {
+        AgentResult {
+            success: true,
+            output: output.to_string(),
+            timed_out: false,
+            exit_code: Some(0),
+            completion_detected: true,
+        }
+    }
+
+    #[test]
+    fn test_record_and_load_in_order() {
+        let temp = TempDir::new().unwrap();
+        let first = Transcript::from_result("research", None, "p1", &result("o1"));
+        let second = Transcript::from_result("implement", Some("US-001"), "p2", &result("o2"));
+
+        assert_eq!(
+            record_transcript(temp.path(), "001", &first)
+                .unwrap()
+                .sequence,
+            1
+        );
+        assert_eq!(
+            record_transcript(temp.path(), "001", &second)
+                .unwrap()
+                .sequence,
+            2
+        );
+        assert!(fs::get_transcripts_dir(temp.path(), "001")
+            .join("002-implement.json")
+            .exists());
+
+        let all = load_transcripts(temp.path(), "001", None).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].output, "o1");
+
+        let implement = load_transcripts(temp.path(), "001", Some("implement")).unwrap();
+        assert_eq!(implement.len(), 1);
+        assert_eq!(implement[0].story.as_deref(), Some("US-001"));
+        assert_eq!(implement[0].to_result().output, "o2");
+    }
+
+    #[test]
+    fn test_load_transcripts_missing_dir() {
+        let temp = TempDir::new().unwrap();
+        assert!(load_transcripts(temp.path(), "001", None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_replay_source_pops_in_order() {
+        let source = ReplaySource::new(vec![
+            Transcript::from_result("plan", None, "a", &result("1")),
+            Transcript::from_result("plan", None, "b", &result("2")),
+        ]);
+        assert_eq!(source.remaining(), 2);
+        assert_eq!(source.next_transcript().unwrap().output, "1");
+        assert_eq!(source.next_transcript().unwrap().output, "2");
+        assert!(source.next_transcript().is_none());
+    }
+}
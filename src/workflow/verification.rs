@@ -0,0 +1,196 @@
+//! Acceptance-criteria verification
+//!
+//! With `verification.enabled`, once the implement loop's agent reports a
+//! story done and its verify checks pass, a second agent pass maps each of
+//! the story's acceptance criteria to evidence (files, tests, diff hunks).
+//! The report is appended to the item's verification.md. A story with a
+//! criterion left unverified is set back to pending, and the next attempt
+//! at it is told which criteria lack evidence.
+
+use std::io::Write;
+
+use serde::Deserialize;
+
+use crate::errors::Result;
+use crate::fs;
+use crate::git;
+use crate::schemas::{Item, Story, StoryStatus};
+
+use super::context::{check_agent_result, AgentRole, WorkflowContext};
+use super::implement_loop::story_brief;
+use super::phases::PhaseKind;
+
+const VERIFICATION_JSON_START: &str = "VERIFICATION_JSON_START";
+const VERIFICATION_JSON_END: &str = "VERIFICATION_JSON_END";
+
+/// The verifier's finding for one acceptance criterion
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CriterionCheck {
+    /// The criterion as written in the PRD
+    pub criterion: String,
+
+    /// Whether the evidence shows the criterion is met
+    pub verified: bool,
+
+    /// Where it is met (file, test, or hunk), or what is missing
+    #[serde(default)]
+    pub evidence: String,
+}
+
+#[derive(Deserialize)]
+struct VerificationReport {
+    criteria: Vec<CriterionCheck>,
+}
+
+/// Parse the per-criterion findings from the verifier's output.
+///
+/// Expects `{"criteria": [{"criterion", "verified", "evidence"}, ...]}`
+/// between `VERIFICATION_JSON_START` and `VERIFICATION_JSON_END` markers.
+pub fn parse_verification(output: &str) -> Option<Vec<CriterionCheck>> {
+    let start = output.rfind(VERIFICATION_JSON_START)? + VERIFICATION_JSON_START.len();
+    let end = start + output[start..].find(VERIFICATION_JSON_END)?;
+    let report: VerificationReport = serde_json::from_str(output[start..end].trim()).ok()?;
+    Some(report.criteria)
+}
+
+/// Line up the findings with the story's criteria, in order; criteria the
+/// verifier skipped count as unverified
+fn complete_checks(story: &Story, mut checks: Vec<CriterionCheck>) -> Vec<CriterionCheck> {
+    for criterion in story.acceptance_criteria.iter().skip(checks.len()) {
+        checks.push(CriterionCheck {
+            criterion: criterion.clone(),
+            verified: false,
+            evidence: "not covered by the verification report".to_string(),
+        });
+    }
+    checks
+}
+
+/// Markdown section for a story's findings, as written to verification.md
+pub fn verification_section(story: &Story, checks: &[CriterionCheck]) -> String {
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC");
+    let mut section = format!("## {} - {} ({})\n", story.id, story.title, now);
+    for check in checks {
+        let mark = if check.verified { "x" } else { " " };
+        section.push_str(&format!("\n- [{}] {}", mark, check.criterion));
+        if !check.evidence.is_empty() {
+            section.push_str(&format!(" — {}", check.evidence));
+        }
+    }
+    section.push('\n');
+    section
+}
+
+/// Feedback for the next attempt at a story, or None if every criterion is
+/// verified
+pub fn unverified_report(checks: &[CriterionCheck]) -> Option<String> {
+    let lines: Vec<String> = checks
+        .iter()
+        .filter(|check| !check.verified)
+        .map(|check| format!("- {}: {}", check.criterion, check.evidence))
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "### unverified acceptance criteria\nNo evidence was found that these criteria are met:\n{}",
+        lines.join("\n")
+    ))
+}
+
+/// Verify a story's acceptance criteria against the uncommitted changes.
+///
+/// Appends the findings to verification.md and, if a criterion is
+/// unverified, sets the story back to pending in prd.json and returns the
+/// feedback for the next attempt. Returns None when the story is verified,
+/// has no criteria, verification is disabled, or the verifier's output has
+/// no readable report.
+///
+/// # Errors
+/// * `AgentError` - If the verification agent fails
+/// * `FileNotFound` - If the verify_criteria prompt cannot be loaded
+/// * `Io` - If verification.md or prd.json cannot be written
+pub async fn verify_story(
+    ctx: &WorkflowContext,
+    item: &Item,
+    story: &Story,
+) -> Result<Option<String>> {
+    if !ctx.config.verification.enabled || ctx.dry_run || story.acceptance_criteria.is_empty() {
+        return Ok(None);
+    }
+    let mut variables = ctx.prompt_variables(item);
+    variables.research = None;
+    variables.plan = None;
+    variables.prd = None;
+    variables.progress = None;
+    variables.story = Some(story_brief(story));
+    variables.changes = git::uncommitted_diff(&ctx.git_options()).await.ok();
+    let prompt = ctx.render_prompt("verify_criteria", &item.id, variables)?;
+    let role = AgentRole {
+        label: "verify",
+        model: ctx.config.verification.model.as_deref(),
+    };
+    let result = ctx
+        .run_agent_as(&item.id, PhaseKind::Implement, role, prompt)
+        .await?;
+    check_agent_result(&result)?;
+    let Some(checks) = parse_verification(&result.output) else {
+        tracing::warn!(
+            "The verification of {} for {} had no readable report; skipping it",
+            story.id,
+            item.id
+        );
+        return Ok(None);
+    };
+    let checks = complete_checks(story, checks);
+
+    let mut file = fs::append_file(&fs::get_verification_path(&ctx.root, &item.id))?;
+    writeln!(file, "{}", verification_section(story, &checks))?;
+
+    let report = unverified_report(&checks);
+    if report.is_some() {
+        // The agent may have marked the story done itself
+        let prd =
+            fs::read_prd(&ctx.root, &item.id)?.with_story_status(&story.id, StoryStatus::Pending);
+        fs::write_prd(&ctx.root, &item.id, &prd)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story() -> Story {
+        Story::new(
+            "US-001".to_string(),
+            "Login".to_string(),
+            vec!["accepts email".to_string(), "rejects blanks".to_string()],
+            1,
+        )
+    }
+
+    #[test]
+    fn test_parse_verification() {
+        let output = "Checked.\nVERIFICATION_JSON_START\n{\"criteria\": [{\"criterion\": \"accepts email\", \"verified\": true, \"evidence\": \"src/login.rs:42\"}]}\nVERIFICATION_JSON_END\nDONE";
+        let checks = parse_verification(output).unwrap();
+        assert_eq!(checks.len(), 1);
+        assert!(checks[0].verified);
+        assert_eq!(checks[0].evidence, "src/login.rs:42");
+        assert!(parse_verification("no report").is_none());
+
+        let checks = complete_checks(&story(), checks);
+        assert_eq!(checks.len(), 2);
+        assert!(!checks[1].verified);
+        assert_eq!(checks[1].criterion, "rejects blanks");
+
+        let report = unverified_report(&checks).unwrap();
+        assert!(report.contains("- rejects blanks: not covered"));
+        assert!(!report.contains("accepts email"));
+        let section = verification_section(&story(), &checks);
+        assert!(section.starts_with("## US-001 - Login"));
+        assert!(section.contains("- [x] accepts email — src/login.rs:42"));
+        assert!(section.contains("- [ ] rejects blanks"));
+        assert!(unverified_report(&checks[..1]).is_none());
+    }
+}